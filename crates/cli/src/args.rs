@@ -1,9 +1,22 @@
+use std::ops::Range;
 use clap::ValueEnum;
-use eda::EdaTool;
+use thiserror::Error;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+use eda::placement::DecimalSeparator;
 use pnp::pcb::{PcbKind, PcbSide};
 use util::sorting::SortOrder;
-use planning::placement::{PlacementOperation, PlacementSortingMode};
-use planning::process::{ProcessOperationKind, ProcessOperationSetItem};
+use planning::placement::{PlacementOperation, PlacementOverride, PlacementSortingItem, PlacementSortingMode, RotationRange};
+use planning::process::{ArtifactType, OperationTransitions, ProcessOperationKind, ProcessOperationSetItem};
+use stores::preferences::PreferenceKey;
+use planning::phase::WorkInstructionsStyle;
+use planning::moisture::MslLevel;
+use planning::bom::BomFormat;
+use planning::quantity_check::QuantityCheckMode;
+use planning::export::ExportFormat;
+use planning::analytics::AnalyticsFormat;
+use planning::report::render::ReportFormat;
+use machine::driver::MachineKind;
 
 /// Args decouple of CLI arg handling requirements from the internal data structures
 
@@ -30,8 +43,18 @@ impl SortOrderArg {
 pub enum PlacementSortingModeArg {
     FeederReference,
     PcbUnit,
+    Part,
+    Nozzle,
+    RefDes,
+    Area,
+    Height,
+    Angle,
+    DesignX,
+    DesignY,
+    PanelX,
+    PanelY,
 
-    // FUTURE add other modes, such as COST, PART, AREA, HEIGHT, REFDES, ANGLE, DESIGN_X, DESIGN_Y, PANEL_X, PANEL_Y, DESCRIPTION
+    // FUTURE add other modes, such as COST, DESCRIPTION
 }
 
 impl PlacementSortingModeArg {
@@ -39,6 +62,16 @@ impl PlacementSortingModeArg {
         match self {
             PlacementSortingModeArg::FeederReference => PlacementSortingMode::FeederReference,
             PlacementSortingModeArg::PcbUnit => PlacementSortingMode::PcbUnit,
+            PlacementSortingModeArg::Part => PlacementSortingMode::Part,
+            PlacementSortingModeArg::Nozzle => PlacementSortingMode::Nozzle,
+            PlacementSortingModeArg::RefDes => PlacementSortingMode::RefDes,
+            PlacementSortingModeArg::Area => PlacementSortingMode::Area,
+            PlacementSortingModeArg::Height => PlacementSortingMode::Height,
+            PlacementSortingModeArg::Angle => PlacementSortingMode::Angle,
+            PlacementSortingModeArg::DesignX => PlacementSortingMode::DesignX,
+            PlacementSortingModeArg::DesignY => PlacementSortingMode::DesignY,
+            PlacementSortingModeArg::PanelX => PlacementSortingMode::PanelX,
+            PlacementSortingModeArg::PanelY => PlacementSortingMode::PanelY,
         }
     }
 }
@@ -82,13 +115,41 @@ pub enum EdaToolArg {
     DipTrace,
     #[value(name("kicad"))]
     KiCad,
+    #[value(name("eurocircuits"))]
+    Eurocircuits,
+    #[value(name("aisler"))]
+    Aisler,
 }
 
 impl EdaToolArg {
-    pub fn build(&self) -> EdaTool {
+    /// The name of the EDA tool plugin, see `EdaToolRegistry::find`.
+    pub fn name(&self) -> &'static str {
         match self {
-            EdaToolArg::DipTrace => EdaTool::DipTrace,
-            EdaToolArg::KiCad => EdaTool::KiCad,
+            EdaToolArg::DipTrace => "DipTrace",
+            EdaToolArg::KiCad => "KiCad",
+            EdaToolArg::Eurocircuits => "Eurocircuits",
+            EdaToolArg::Aisler => "Aisler",
+        }
+    }
+}
+
+#[derive(Clone)]
+#[derive(ValueEnum)]
+pub enum DecimalSeparatorArg {
+    #[value(name("auto"))]
+    Auto,
+    #[value(name("point"))]
+    Point,
+    #[value(name("comma"))]
+    Comma,
+}
+
+impl From<DecimalSeparatorArg> for DecimalSeparator {
+    fn from(value: DecimalSeparatorArg) -> Self {
+        match value {
+            DecimalSeparatorArg::Auto => DecimalSeparator::Auto,
+            DecimalSeparatorArg::Point => DecimalSeparator::Point,
+            DecimalSeparatorArg::Comma => DecimalSeparator::Comma,
         }
     }
 }
@@ -98,12 +159,42 @@ impl EdaToolArg {
 pub enum PlacementOperationArg {
     #[value(name("placed"))]
     Placed,
+    #[value(name("unplaced"))]
+    Unplaced,
+    #[value(name("inspectionfailed"))]
+    InspectionFailed,
 }
 
 impl From<PlacementOperationArg> for PlacementOperation {
     fn from(value: PlacementOperationArg) -> Self {
         match value {
             PlacementOperationArg::Placed => Self::Placed,
+            PlacementOperationArg::Unplaced => Self::Unplaced,
+            PlacementOperationArg::InspectionFailed => Self::InspectionFailed,
+        }
+    }
+}
+
+#[derive(Clone)]
+#[derive(ValueEnum)]
+pub enum PlacementOverrideArg {
+    /// Do not place, even if the design places it
+    #[value(name("skip"))]
+    Skip,
+    /// Place, even if the design does not place it
+    #[value(name("place"))]
+    Place,
+    /// Use the design, i.e. remove the override
+    #[value(name("design"))]
+    Design,
+}
+
+impl From<PlacementOverrideArg> for Option<PlacementOverride> {
+    fn from(value: PlacementOverrideArg) -> Self {
+        match value {
+            PlacementOverrideArg::Skip => Some(PlacementOverride::Skip),
+            PlacementOverrideArg::Place => Some(PlacementOverride::Place),
+            PlacementOverrideArg::Design => None,
         }
     }
 }
@@ -162,4 +253,498 @@ impl From<ProcessOperationSetArg> for ProcessOperationSetItem {
             ProcessOperationSetArg::Completed => ProcessOperationSetItem::Completed
         }
     }
-}
\ No newline at end of file
+}
+#[derive(Clone)]
+#[derive(ValueEnum)]
+pub enum OperationTransitionsArg {
+    #[value(name("automatic"))]
+    Automatic,
+    #[value(name("manual"))]
+    Manual,
+}
+
+impl From<OperationTransitionsArg> for OperationTransitions {
+    fn from(value: OperationTransitionsArg) -> Self {
+        match value {
+            OperationTransitionsArg::Automatic => OperationTransitions::Automatic,
+            OperationTransitionsArg::Manual => OperationTransitions::Manual,
+        }
+    }
+}
+
+#[derive(Clone)]
+#[derive(ValueEnum)]
+pub enum WorkInstructionsStyleArg {
+    #[value(name("parts"))]
+    Parts,
+    #[value(name("pick-list"))]
+    PickList,
+}
+
+impl From<WorkInstructionsStyleArg> for WorkInstructionsStyle {
+    fn from(value: WorkInstructionsStyleArg) -> Self {
+        match value {
+            WorkInstructionsStyleArg::Parts => WorkInstructionsStyle::Parts,
+            WorkInstructionsStyleArg::PickList => WorkInstructionsStyle::PickList,
+        }
+    }
+}
+
+#[derive(Clone)]
+#[derive(ValueEnum)]
+pub enum QuantityCheckModeArg {
+    #[value(name("off"))]
+    Off,
+    #[value(name("warn"))]
+    Warn,
+    #[value(name("block"))]
+    Block,
+}
+
+impl QuantityCheckModeArg {
+    pub fn to_quantity_check_mode(&self) -> Option<QuantityCheckMode> {
+        match self {
+            QuantityCheckModeArg::Off => None,
+            QuantityCheckModeArg::Warn => Some(QuantityCheckMode::Warn),
+            QuantityCheckModeArg::Block => Some(QuantityCheckMode::Block),
+        }
+    }
+}
+
+#[derive(Clone)]
+#[derive(ValueEnum)]
+pub enum ExportFormatArg {
+    #[value(name("centroid"))]
+    Centroid,
+}
+
+impl From<ExportFormatArg> for ExportFormat {
+    fn from(value: ExportFormatArg) -> Self {
+        match value {
+            ExportFormatArg::Centroid => ExportFormat::Centroid,
+        }
+    }
+}
+
+#[derive(Clone)]
+#[derive(ValueEnum)]
+pub enum RotationRangeArg {
+    /// -180 < rotation <= 180
+    #[value(name("signed"))]
+    Signed,
+    /// 0 <= rotation < 360
+    #[value(name("unsigned"))]
+    Unsigned,
+}
+
+impl From<RotationRangeArg> for RotationRange {
+    fn from(value: RotationRangeArg) -> Self {
+        match value {
+            RotationRangeArg::Signed => RotationRange::Signed,
+            RotationRangeArg::Unsigned => RotationRange::Unsigned,
+        }
+    }
+}
+
+#[derive(Clone)]
+#[derive(ValueEnum)]
+pub enum MachineKindArg {
+    #[value(name("simulated"))]
+    Simulated,
+}
+
+impl From<MachineKindArg> for MachineKind {
+    fn from(value: MachineKindArg) -> Self {
+        match value {
+            MachineKindArg::Simulated => MachineKind::Simulated,
+        }
+    }
+}
+
+#[derive(Clone)]
+#[derive(ValueEnum)]
+pub enum ArtifactTypeArg {
+    #[value(name("phase-placements"))]
+    PhasePlacements,
+    #[value(name("work-instructions"))]
+    WorkInstructions,
+    #[value(name("rework-instructions"))]
+    ReworkInstructions,
+    #[value(name("phase-export"))]
+    PhaseExport,
+    #[value(name("first-article-checklist"))]
+    FirstArticleChecklist,
+    #[value(name("machine-export"))]
+    MachineExport,
+    #[value(name("feeder-setup-sheet"))]
+    FeederSetupSheet,
+}
+
+impl From<ArtifactTypeArg> for ArtifactType {
+    fn from(value: ArtifactTypeArg) -> Self {
+        match value {
+            ArtifactTypeArg::PhasePlacements => ArtifactType::PhasePlacements,
+            ArtifactTypeArg::WorkInstructions => ArtifactType::WorkInstructions,
+            ArtifactTypeArg::ReworkInstructions => ArtifactType::ReworkInstructions,
+            ArtifactTypeArg::PhaseExport => ArtifactType::PhaseExport,
+            ArtifactTypeArg::FirstArticleChecklist => ArtifactType::FirstArticleChecklist,
+            ArtifactTypeArg::MachineExport => ArtifactType::MachineExport,
+            ArtifactTypeArg::FeederSetupSheet => ArtifactType::FeederSetupSheet,
+        }
+    }
+}
+
+#[derive(Clone)]
+#[derive(ValueEnum)]
+pub enum MslLevelArg {
+    /// Not moisture sensitive
+    #[value(name("none"))]
+    None,
+    #[value(name("1"))]
+    Level1,
+    #[value(name("2"))]
+    Level2,
+    #[value(name("2a"))]
+    Level2a,
+    #[value(name("3"))]
+    Level3,
+    #[value(name("4"))]
+    Level4,
+    #[value(name("5"))]
+    Level5,
+    #[value(name("5a"))]
+    Level5a,
+    #[value(name("6"))]
+    Level6,
+}
+
+impl MslLevelArg {
+    pub fn build(&self) -> Option<MslLevel> {
+        match self {
+            MslLevelArg::None => None,
+            MslLevelArg::Level1 => Some(MslLevel::Level1),
+            MslLevelArg::Level2 => Some(MslLevel::Level2),
+            MslLevelArg::Level2a => Some(MslLevel::Level2a),
+            MslLevelArg::Level3 => Some(MslLevel::Level3),
+            MslLevelArg::Level4 => Some(MslLevel::Level4),
+            MslLevelArg::Level5 => Some(MslLevel::Level5),
+            MslLevelArg::Level5a => Some(MslLevel::Level5a),
+            MslLevelArg::Level6 => Some(MslLevel::Level6),
+        }
+    }
+}
+
+#[derive(Clone)]
+#[derive(ValueEnum)]
+pub enum PreferenceKeyArg {
+    #[value(name("language"))]
+    Language,
+    #[value(name("output-format"))]
+    OutputFormat,
+    #[value(name("artifact-directory"))]
+    ArtifactDirectory,
+    #[value(name("operator"))]
+    Operator,
+    #[value(name("backup-max-count"))]
+    BackupMaxCount,
+    #[value(name("backup-max-age-days"))]
+    BackupMaxAgeDays,
+    #[value(name("signing-key"))]
+    SigningKey,
+    #[value(name("verifying-key"))]
+    VerifyingKey,
+}
+
+impl From<PreferenceKeyArg> for PreferenceKey {
+    fn from(value: PreferenceKeyArg) -> Self {
+        match value {
+            PreferenceKeyArg::Language => PreferenceKey::Language,
+            PreferenceKeyArg::OutputFormat => PreferenceKey::OutputFormat,
+            PreferenceKeyArg::ArtifactDirectory => PreferenceKey::ArtifactDirectory,
+            PreferenceKeyArg::Operator => PreferenceKey::Operator,
+            PreferenceKeyArg::BackupMaxCount => PreferenceKey::BackupMaxCount,
+            PreferenceKeyArg::BackupMaxAgeDays => PreferenceKey::BackupMaxAgeDays,
+            PreferenceKeyArg::SigningKey => PreferenceKey::SigningKey,
+            PreferenceKeyArg::VerifyingKey => PreferenceKey::VerifyingKey,
+        }
+    }
+}
+
+/// Parses a placement ordering in the format '<MODE>:<SORT_ORDER>', e.g. 'FEEDER_REFERENCE:ASC'.
+///
+/// Names are case-insensitive and '-' can be used instead of '_', e.g. 'feeder-reference:asc'.  The modes and sort
+/// orders are those of `PlacementSortingModeArg` and `SortOrderArg`.
+pub fn parse_placement_sorting_item(value: &str) -> Result<PlacementSortingItem, PlacementSortingItemParseError> {
+    let error = |kind: PlacementSortingItemParseErrorKind, span: Range<usize>| {
+        PlacementSortingItemParseError { input: value.to_string(), span, kind }
+    };
+
+    let Some((mode_str, sort_order_str)) = value.split_once(':') else {
+        return Err(error(PlacementSortingItemParseErrorKind::MissingSortOrder, value.len()..value.len()))
+    };
+
+    let sort_order_start = mode_str.len() + 1;
+
+    if let Some(index) = sort_order_str.find(':') {
+        return Err(error(PlacementSortingItemParseErrorKind::UnexpectedSeparator, sort_order_start + index..value.len()))
+    }
+
+    let mode = find_value::<PlacementSortingModeArg>(mode_str)
+        .map_err(|(expected, suggestion)| {
+            error(PlacementSortingItemParseErrorKind::UnknownMode { expected, suggestion }, 0..mode_str.len())
+        })?;
+
+    let sort_order = find_value::<SortOrderArg>(sort_order_str)
+        .map_err(|(expected, suggestion)| {
+            error(PlacementSortingItemParseErrorKind::UnknownSortOrder { expected, suggestion }, sort_order_start..value.len())
+        })?;
+
+    Ok(PlacementSortingItem {
+        mode: mode.to_placement_sorting_mode(),
+        sort_order: sort_order.to_sort_order(),
+    })
+}
+
+/// Finds the value with the name, returns the possible names and the closest name if there is no such value.
+fn find_value<T: ValueEnum + Clone>(name: &str) -> Result<T, (Vec<String>, Option<String>)> {
+    let normalize = |name: &str| name.trim().to_uppercase().replace('-', "_");
+    let normalized_name = normalize(name);
+
+    let mut possible_names = vec![];
+    for variant in T::value_variants() {
+        let possible_name = variant.to_possible_value().unwrap().get_name().to_string();
+        if normalize(&possible_name).eq(&normalized_name) {
+            return Ok(variant.clone())
+        }
+        possible_names.push(possible_name);
+    }
+
+    // a name that starts with the given name, or else the closest name, allowing one edit per three characters
+    let max_distance = (normalized_name.chars().count() / 3).max(1);
+    let suggestion = possible_names.iter()
+        .find(|possible_name| !normalized_name.is_empty() && normalize(possible_name).starts_with(&normalized_name))
+        .or_else(|| possible_names.iter()
+            .map(|possible_name| (edit_distance(&normalize(possible_name), &normalized_name), possible_name))
+            .filter(|(distance, _)| *distance <= max_distance)
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, possible_name)| possible_name)
+        )
+        .cloned();
+
+    Err((possible_names, suggestion))
+}
+
+/// Levenshtein distance, in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous_row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, char_a) in a.chars().enumerate() {
+        let mut row = vec![i + 1];
+        for (j, char_b) in b.iter().enumerate() {
+            let substitution = previous_row[j] + if char_a.eq(char_b) { 0 } else { 1 };
+            row.push(substitution.min(previous_row[j + 1] + 1).min(row[j] + 1));
+        }
+        previous_row = row;
+    }
+
+    previous_row[b.len()]
+}
+
+/// An error with the span of the input that caused it, displayed with the span underlined, e.g.
+/// ```text
+/// Invalid placement ordering. Unknown mode, expected one of: FEEDER_REFERENCE, PCB_UNIT, did you mean 'PCB_UNIT'?
+///   PCB:ASC
+///   ^^^
+/// ```
+#[derive(Error, Debug, PartialEq)]
+#[error("Invalid placement ordering. {kind}\n  {input}\n  {}", build_span_marker(.input, .span))]
+pub struct PlacementSortingItemParseError {
+    pub input: String,
+    /// Byte range of the input
+    pub span: Range<usize>,
+    pub kind: PlacementSortingItemParseErrorKind,
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum PlacementSortingItemParseErrorKind {
+    #[error("Missing sort order, required format: '<MODE>:<SORT_ORDER>'")]
+    MissingSortOrder,
+
+    #[error("Unexpected separator, required format: '<MODE>:<SORT_ORDER>'")]
+    UnexpectedSeparator,
+
+    #[error("Unknown mode, expected one of: {}{}", .expected.join(", "), format_suggestion(.suggestion))]
+    UnknownMode { expected: Vec<String>, suggestion: Option<String> },
+
+    #[error("Unknown sort order, expected one of: {}{}", .expected.join(", "), format_suggestion(.suggestion))]
+    UnknownSortOrder { expected: Vec<String>, suggestion: Option<String> },
+}
+
+fn format_suggestion(suggestion: &Option<String>) -> String {
+    match suggestion {
+        Some(suggestion) => format!(", did you mean '{}'?", suggestion),
+        None => "".to_string(),
+    }
+}
+
+fn build_span_marker(input: &str, span: &Range<usize>) -> String {
+    let offset = input[..span.start].chars().count();
+    let length = input[span.clone()].chars().count().max(1);
+    format!("{}{}", " ".repeat(offset), "^".repeat(length))
+}
+
+#[cfg(test)]
+mod parse_placement_sorting_item_tests {
+    use indoc::indoc;
+    use rstest::rstest;
+    use planning::placement::{PlacementSortingItem, PlacementSortingMode};
+    use util::sorting::SortOrder;
+    use super::{parse_placement_sorting_item, PlacementSortingItemParseErrorKind};
+
+    #[rstest]
+    #[case("FEEDER_REFERENCE:ASC", PlacementSortingMode::FeederReference, SortOrder::Asc)]
+    #[case("pcb_unit:desc", PlacementSortingMode::PcbUnit, SortOrder::Desc)]
+    #[case("feeder-reference:Asc", PlacementSortingMode::FeederReference, SortOrder::Asc)]
+    #[case("PART:ASC", PlacementSortingMode::Part, SortOrder::Asc)]
+    #[case("REF_DES:ASC", PlacementSortingMode::RefDes, SortOrder::Asc)]
+    #[case("design_x:desc", PlacementSortingMode::DesignX, SortOrder::Desc)]
+    #[case("panel-y:asc", PlacementSortingMode::PanelY, SortOrder::Asc)]
+    pub fn parse(#[case] value: &str, #[case] expected_mode: PlacementSortingMode, #[case] expected_sort_order: SortOrder) {
+        // expect
+        assert_eq!(parse_placement_sorting_item(value), Ok(PlacementSortingItem { mode: expected_mode, sort_order: expected_sort_order }));
+    }
+
+    #[rstest]
+    #[case("PCB_UNIT", 8..8)]
+    #[case("PCB_UNIT:ASC:DESC", 12..17)]
+    #[case("FEEDR:ASC", 0..5)]
+    #[case("PCB_UNIT:UP", 9..11)]
+    pub fn span(#[case] value: &str, #[case] expected_span: std::ops::Range<usize>) {
+        // expect
+        assert_eq!(parse_placement_sorting_item(value).unwrap_err().span, expected_span);
+    }
+
+    #[test]
+    pub fn unknown_mode() {
+        // when
+        let error = parse_placement_sorting_item("FEEDR_REFERENCE:ASC").unwrap_err();
+
+        // then
+        assert_eq!(error.kind, PlacementSortingItemParseErrorKind::UnknownMode {
+            expected: vec!["FEEDER_REFERENCE".to_string(), "PCB_UNIT".to_string(), "PART".to_string(), "NOZZLE".to_string(),
+                "REF_DES".to_string(), "AREA".to_string(), "HEIGHT".to_string(), "ANGLE".to_string(),
+                "DESIGN_X".to_string(), "DESIGN_Y".to_string(), "PANEL_X".to_string(), "PANEL_Y".to_string(),
+            ],
+            suggestion: Some("FEEDER_REFERENCE".to_string()),
+        });
+
+        // and
+        assert_eq!(error.to_string(), indoc! {"
+            Invalid placement ordering. Unknown mode, expected one of: FEEDER_REFERENCE, PCB_UNIT, PART, NOZZLE, REF_DES, AREA, HEIGHT, ANGLE, DESIGN_X, DESIGN_Y, PANEL_X, PANEL_Y, did you mean 'FEEDER_REFERENCE'?
+              FEEDR_REFERENCE:ASC
+              ^^^^^^^^^^^^^^^"
+        });
+    }
+
+    #[rstest]
+    #[case("PCB:ASC", Some("PCB_UNIT"))]
+    #[case("pcb-unti:ASC", Some("PCB_UNIT"))]
+    #[case("COST:ASC", None)]
+    pub fn unknown_mode_suggestion(#[case] value: &str, #[case] expected_suggestion: Option<&str>) {
+        // when
+        let error = parse_placement_sorting_item(value).unwrap_err();
+
+        // then
+        let PlacementSortingItemParseErrorKind::UnknownMode { suggestion, .. } = error.kind else {
+            panic!("unexpected error kind. kind: {:?}", error.kind)
+        };
+        assert_eq!(suggestion.as_deref(), expected_suggestion);
+    }
+
+    #[test]
+    pub fn missing_sort_order() {
+        // when
+        let error = parse_placement_sorting_item("PCB_UNIT").unwrap_err();
+
+        // then
+        assert_eq!(error.to_string(), indoc! {"
+            Invalid placement ordering. Missing sort order, required format: '<MODE>:<SORT_ORDER>'
+              PCB_UNIT
+                      ^"
+        });
+    }
+}
+
+#[derive(Clone)]
+#[derive(ValueEnum)]
+pub enum BomFormatArg {
+    #[value(name("csv"))]
+    Csv,
+    #[value(name("json"))]
+    Json,
+    #[value(name("xlsx"))]
+    Xlsx,
+}
+
+impl From<BomFormatArg> for BomFormat {
+    fn from(value: BomFormatArg) -> Self {
+        match value {
+            BomFormatArg::Csv => BomFormat::Csv,
+            BomFormatArg::Json => BomFormat::Json,
+            BomFormatArg::Xlsx => BomFormat::Xlsx,
+        }
+    }
+}
+
+#[derive(Clone)]
+#[derive(ValueEnum)]
+pub enum AnalyticsFormatArg {
+    #[value(name("csv"))]
+    Csv,
+    #[value(name("json"))]
+    Json,
+}
+
+impl From<AnalyticsFormatArg> for AnalyticsFormat {
+    fn from(value: AnalyticsFormatArg) -> Self {
+        match value {
+            AnalyticsFormatArg::Csv => AnalyticsFormat::Csv,
+            AnalyticsFormatArg::Json => AnalyticsFormat::Json,
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+#[derive(ValueEnum)]
+pub enum ReportFormatArg {
+    #[default]
+    #[value(name("json"))]
+    Json,
+    #[value(name("html"))]
+    Html,
+}
+
+impl From<ReportFormatArg> for ReportFormat {
+    fn from(value: ReportFormatArg) -> Self {
+        match value {
+            ReportFormatArg::Json => ReportFormat::Json,
+            ReportFormatArg::Html => ReportFormat::Html,
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+#[derive(ValueEnum)]
+pub enum DiffFormatArg {
+    #[default]
+    #[value(name("text"))]
+    Text,
+    #[value(name("json"))]
+    Json,
+}
+
+/// Parses an RFC 3339 date and time, e.g. '2024-10-16T08:00:00Z'.
+pub fn parse_date_time(value: &str) -> Result<OffsetDateTime, time::error::Parse> {
+    OffsetDateTime::parse(value, &Rfc3339)
+}
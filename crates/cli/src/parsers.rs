@@ -1,9 +1,11 @@
-use std::ffi::{OsStr, OsString};
+use std::ffi::OsStr;
+use std::str::FromStr;
 use clap::builder::TypedValueParser;
-use clap::{Arg, Command, Error, value_parser};
+use clap::{Arg, Command, Error, ValueEnum};
 use clap::error::ErrorKind;
 use planning::placement::PlacementSortingItem;
-use crate::args::{PlacementSortingModeArg, SortOrderArg};
+use planning::process::ProcessOperationKind;
+use crate::args::{parse_placement_sorting_item, ProcessOperationArg};
 
 #[derive(Clone, Default)]
 pub struct PlacementSortingItemParser {}
@@ -11,34 +13,40 @@ pub struct PlacementSortingItemParser {}
 impl TypedValueParser for PlacementSortingItemParser {
     type Value = PlacementSortingItem;
 
-    /// Parses a value in the format '<MODE>:<SORT_ORDER>' with values in SCREAMING_SNAKE_CASE, e.g. 'FEEDER_REFERENCE:ASC'
-    fn parse_ref(&self, cmd: &Command, _arg: Option<&Arg>, value: &OsStr) -> Result<Self::Value, Error> {
+    /// Parses a value in the format '<MODE>:<SORT_ORDER>', e.g. 'FEEDER_REFERENCE:ASC', see `parse_placement_sorting_item`.
+    fn parse_ref(&self, _cmd: &Command, _arg: Option<&Arg>, value: &OsStr) -> Result<Self::Value, Error> {
 
-        let chunks_str = match value.to_str() {
+        let value_str = match value.to_str() {
             Some(str) => Ok(str),
             // TODO create a test for this edge case, how to invoke this code path, is the message helpful to the user, how is it displayed by clap?
             None => Err(Error::raw(ErrorKind::InvalidValue, "Invalid argument encoding")),
         }?;
 
-        let mut chunks: Vec<_> = chunks_str.split(':').collect();
-        if chunks.len() != 2 {
-            return Err(Error::raw(ErrorKind::InvalidValue, format!("Invalid argument. Required format: '<MODE>:<SORT_ORDER>', found: '{}'", chunks_str)))
-        }
+        parse_placement_sorting_item(value_str)
+            .map_err(|error| Error::raw(ErrorKind::InvalidValue, format!("{}\n", error)))
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct ProcessOperationParser {}
 
-        let sort_order_str = chunks.pop().unwrap();
-        let mode_str = chunks.pop().unwrap();
+impl TypedValueParser for ProcessOperationParser {
+    type Value = ProcessOperationKind;
 
-        let mode_parser = value_parser!(PlacementSortingModeArg);
-        let mode_os_str = OsString::from(mode_str);
-        let mode_arg = mode_parser.parse_ref(cmd, None, &mode_os_str)?;
+    /// Parses a built-in operation, e.g. 'automatedpnp', see `ProcessOperationArg`, any other value is the name of a
+    /// custom operation of a process definition, e.g. 'SelectiveSolder'.
+    fn parse_ref(&self, _cmd: &Command, _arg: Option<&Arg>, value: &OsStr) -> Result<Self::Value, Error> {
 
-        let sort_order_parser = value_parser!(SortOrderArg);
-        let sort_order_os_str = OsString::from(sort_order_str);
-        let sort_order_arg = sort_order_parser.parse_ref(cmd, None, &sort_order_os_str)?;
+        let value_str = match value.to_str() {
+            Some(str) => Ok(str),
+            None => Err(Error::raw(ErrorKind::InvalidValue, "Invalid argument encoding")),
+        }?;
+
+        if let Ok(arg) = ProcessOperationArg::from_str(value_str, true) {
+            return Ok(arg.into())
+        }
 
-        Ok(PlacementSortingItem {
-            mode: mode_arg.to_placement_sorting_mode(),
-            sort_order: sort_order_arg.to_sort_order(),
-        })
+        ProcessOperationKind::from_str(value_str)
+            .map_err(|error| Error::raw(ErrorKind::InvalidValue, format!("{}\n", error)))
     }
 }
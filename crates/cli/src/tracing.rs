@@ -1,40 +1,335 @@
-use std::fs::File;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 use clap_verbosity_flag::{LogLevel, Verbosity};
-use tracing_subscriber::fmt::Subscriber as FmtSubscriber;
-use tracing_subscriber::fmt::format::FmtSpan;
+use serde_json::{json, Value};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::filter::{filter_fn, Targets};
+use tracing_subscriber::field::RecordFields;
+use tracing_subscriber::fmt::format::{FmtSpan, Writer};
+use tracing_subscriber::fmt::FormatFields;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::Layer;
 use tracing_log::AsTrace;
+use planning::mutation::MUTATION_TARGET;
+
+/// A structured log, a JSON lines file with one JSON object for each event, appended to so that the events of a
+/// sequence of commands are in one file.
+///
+/// Only the mutation events, see `planning::mutation`, are logged, regardless of the verbosity, one for each change that
+/// a command makes, with the rendered message and the changed values as fields.
+pub struct JsonLog {
+    pub path: PathBuf,
+    /// The command, e.g. 'create-phase', included in each event.
+    pub command: String,
+}
+
+/// The rendered messages of the mutation events, see `planning::mutation`, logged since the tracing was configured,
+/// e.g. for the audit log of the planner, regardless of the verbosity.
+#[derive(Debug, Clone, Default)]
+pub struct Mutations(Arc<Mutex<Vec<String>>>);
+
+impl Mutations {
+    /// Oldest first.
+    pub fn messages(&self) -> Vec<String> {
+        self.0.lock()
+            .map(|messages| messages.clone())
+            .unwrap_or_default()
+    }
+}
 
 pub fn configure_tracing<IL: LogLevel>(path: Option<PathBuf>, verbosity: Verbosity<IL>) -> anyhow::Result<()> {
+    configure_tracing_with_json_log(path, None, None, verbosity)
+}
+
+pub fn configure_tracing_with_json_log<IL: LogLevel>(path: Option<PathBuf>, json_log: Option<JsonLog>, mutations: Option<Mutations>, verbosity: Verbosity<IL>) -> anyhow::Result<()> {
 
-    const SUBSCRIBER_FAILED_MESSAGE: &'static str = "setting default subscriber failed";
-    match path {
+    const SUBSCRIBER_FAILED_MESSAGE: &str = "setting default subscriber failed";
+    let fmt_layer = match path {
         Some(path) => {
             //println!("using file_subscriber");
             let trace_file: File = File::create(path)?;
 
-            let file_subscriber = FmtSubscriber::builder()
+            tracing_subscriber::fmt::layer()
+                .fmt_fields(MessageFields)
                 .with_writer(trace_file)
-                .with_max_level(verbosity.log_level_filter().as_trace())
-                .finish();
-
-            tracing::subscriber::set_global_default(file_subscriber)
-                .expect(SUBSCRIBER_FAILED_MESSAGE);
+                .boxed()
         },
         _ => {
             //println!("using stdout_subscriber");
-            let stdout_subscriber = FmtSubscriber::builder()
+            tracing_subscriber::fmt::layer()
+                .fmt_fields(MessageFields)
                 .with_level(false)
                 .with_line_number(false)
                 .with_span_events(FmtSpan::NONE)
                 .without_time()
-                .with_max_level(verbosity.log_level_filter().as_trace())
-                .finish();
-
-            tracing::subscriber::set_global_default(stdout_subscriber)
-                .expect(SUBSCRIBER_FAILED_MESSAGE);
+                .boxed()
         }
     };
 
+    let json_log_layer = match json_log {
+        Some(json_log) => {
+            let json_log_file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&json_log.path)?;
+
+            Some(JsonLogLayer { file: Mutex::new(json_log_file), command: json_log.command }.with_filter(Targets::new().with_target(MUTATION_TARGET, Level::INFO)))
+        },
+        None => None,
+    };
+
+    let mutations_layer = mutations
+        .map(|mutations| MutationsLayer { mutations }.with_filter(Targets::new().with_target(MUTATION_TARGET, Level::INFO)));
+
+    let level_filter = verbosity.log_level_filter().as_trace();
+    let fmt_filter = filter_fn(move |metadata| level_filter >= *metadata.level());
+
+    let subscriber = tracing_subscriber::registry()
+        .with(fmt_layer.with_filter(fmt_filter))
+        .with(json_log_layer)
+        .with(mutations_layer);
+
+    tracing::subscriber::set_global_default(subscriber)
+        .expect(SUBSCRIBER_FAILED_MESSAGE);
+
     Ok(())
-}
\ No newline at end of file
+}
+
+struct JsonLogLayer {
+    file: Mutex<File>,
+    command: String,
+}
+
+impl<S: Subscriber> Layer<S> for JsonLogLayer {
+    fn on_event(&self, event: &Event<'_>, _context: Context<'_, S>) {
+        let mut visitor = JsonLogVisitor::default();
+        event.record(&mut visitor);
+
+        let metadata = event.metadata();
+        let item = json!({
+            "date_time": OffsetDateTime::now_utc().format(&Rfc3339).unwrap_or_default(),
+            "level": metadata.level().as_str(),
+            "target": metadata.target(),
+            "command": self.command,
+            "message": render_message(&visitor.message, |name| visitor.fields.get(name).map(|value| match value {
+                Value::String(value) => value.clone(),
+                value => value.to_string(),
+            })),
+            "fields": visitor.fields,
+        });
+
+        // a failure to log must not fail the command
+        if let Ok(mut file) = self.file.lock() {
+            let _ = writeln!(file, "{}", item);
+        }
+    }
+}
+
+struct MutationsLayer {
+    mutations: Mutations,
+}
+
+impl<S: Subscriber> Layer<S> for MutationsLayer {
+    fn on_event(&self, event: &Event<'_>, _context: Context<'_, S>) {
+        let mut visitor = MessageFieldsVisitor::default();
+        event.record(&mut visitor);
+
+        if let Ok(mut messages) = self.mutations.0.lock() {
+            messages.push(visitor.render_message());
+        }
+    }
+}
+
+#[derive(Default)]
+struct JsonLogVisitor {
+    message: String,
+    fields: BTreeMap<String, Value>,
+}
+
+impl Visit for JsonLogVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "message" => self.message = value.to_string(),
+            name => { self.fields.insert(name.to_string(), Value::from(value)); },
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        match field.name() {
+            "message" => self.message = format!("{:?}", value),
+            name => { self.fields.insert(name.to_string(), Value::from(format!("{:?}", value))); },
+        }
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.fields.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.fields.insert(field.name().to_string(), Value::from(value));
+    }
+
+    /// Non-finite values, which JSON cannot represent, are logged as `null`.
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.fields.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.fields.insert(field.name().to_string(), Value::from(value));
+    }
+}
+
+/// Replaces each `{field}` in the message with the value of the field, as used by the mutation events, see
+/// `planning::mutation`, braces that do not enclose the name of a field are kept.
+fn render_message(message: &str, field_value: impl Fn(&str) -> Option<String>) -> String {
+    let mut rendered = String::with_capacity(message.len());
+    let mut remaining = message;
+
+    while let Some(start) = remaining.find('{') {
+        rendered.push_str(&remaining[..start]);
+        let placeholder = &remaining[start..];
+
+        match placeholder.find('}').and_then(|end| field_value(&placeholder[1..end]).map(|value| (end, value))) {
+            Some((end, value)) => {
+                rendered.push_str(&value);
+                remaining = &placeholder[end + 1..];
+            },
+            None => {
+                rendered.push('{');
+                remaining = &placeholder[1..];
+            },
+        }
+    }
+    rendered.push_str(remaining);
+
+    rendered
+}
+
+/// Renders the message of an event for the trace log, the fields of the message are replaced with their values, see
+/// `render_message`, any other fields follow the message, e.g. `count=3`.
+struct MessageFields;
+
+impl<'writer> FormatFields<'writer> for MessageFields {
+    fn format_fields<R: RecordFields>(&self, mut writer: Writer<'writer>, fields: R) -> fmt::Result {
+        let mut visitor = MessageFieldsVisitor::default();
+        fields.record(&mut visitor);
+
+        let message = visitor.render_message();
+        write!(writer, "{}", message)?;
+
+        let other_fields = visitor.fields.iter()
+            .filter(|(name, _value)| !visitor.message.contains(&format!("{{{}}}", name)));
+        for (index, (name, value)) in other_fields.enumerate() {
+            match index == 0 && message.is_empty() {
+                true => write!(writer, "{}={}", name, value)?,
+                false => write!(writer, " {}={}", name, value)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct MessageFieldsVisitor {
+    message: String,
+    /// In the order of the fields of the event.
+    fields: Vec<(String, String)>,
+}
+
+impl MessageFieldsVisitor {
+    fn render_message(&self) -> String {
+        render_message(&self.message, |name| self.fields.iter()
+            .find(|(field_name, _value)| field_name.eq(name))
+            .map(|(_name, value)| value.clone())
+        )
+    }
+}
+
+impl Visit for MessageFieldsVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "message" => self.message = value.to_string(),
+            name => self.fields.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        match field.name() {
+            "message" => self.message = format!("{:?}", value),
+            name => self.fields.push((name.to_string(), format!("{:?}", value))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod render_message_tests {
+    use rstest::rstest;
+    use super::render_message;
+
+    #[rstest]
+    #[case("Removed phase. phase: '{phase}'", "Removed phase. phase: 'top_1'")]
+    #[case("Renamed phase. from: '{phase}', placements: {placements}", "Renamed phase. from: 'top_1', placements: 4")]
+    #[case("Updated. old: Part { mpn: \"RES1\" }", "Updated. old: Part { mpn: \"RES1\" }")]
+    #[case("Unknown field. {other}", "Unknown field. {other}")]
+    #[case("Unclosed. {phase", "Unclosed. {phase")]
+    pub fn render(#[case] message: &str, #[case] expected_message: &str) {
+        // given
+        let field_value = |name: &str| match name {
+            "phase" => Some("top_1".to_string()),
+            "placements" => Some("4".to_string()),
+            _ => None,
+        };
+
+        // expect
+        assert_eq!(render_message(message, field_value), expected_message);
+    }
+}
+
+#[cfg(test)]
+mod json_log_tests {
+    use std::sync::Mutex;
+    use serde_json::{json, Value};
+    use tracing::info;
+    use tracing_subscriber::layer::SubscriberExt;
+    use planning::mutation::MUTATION_TARGET;
+    use super::JsonLogLayer;
+
+    #[test]
+    pub fn fields_have_json_types() -> anyhow::Result<()> {
+        // given
+        let file = tempfile::NamedTempFile::new()?;
+        let layer = JsonLogLayer { file: Mutex::new(file.reopen()?), command: "test".to_string() };
+        let subscriber = tracing_subscriber::registry().with(layer);
+
+        // when
+        tracing::subscriber::with_default(subscriber, || {
+            info!(target: MUTATION_TARGET, count = 3_u64, offset = -2_i64, ratio = 0.5_f64, placed = true, name = "top_1", part = ?("RES", 1), message = "Tested. name: '{name}', count: {count}");
+        });
+
+        // then
+        let item: Value = serde_json::from_str(&std::fs::read_to_string(file.path())?)?;
+        assert_eq!(item["message"], json!("Tested. name: 'top_1', count: 3"));
+        assert_eq!(item["fields"], json!({
+            "count": 3,
+            "offset": -2,
+            "ratio": 0.5,
+            "placed": true,
+            "name": "top_1",
+            "part": "(\"RES\", 1)",
+        }));
+        assert!(item["fields"]["count"].is_u64());
+        assert!(item["fields"]["offset"].is_i64());
+        assert!(item["fields"]["ratio"].is_f64());
+        assert!(item["fields"]["placed"].is_boolean());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,117 @@
+use rust_decimal::Decimal;
+use thiserror::Error;
+use pnp::pcb::PcbSide;
+use pnp::placement::PlacementKind;
+use crate::placement::{deserialize_lenient_decimal, normalize_rotation, EdaPlacement, EdaPlacementField};
+
+/// The columns that identify an Aisler centroid file.
+pub const AISLER_HEADERS: [&str; 4] = ["Designator", "Mid X", "Mid Y", "Layer"];
+
+/// The numeric columns of an Aisler centroid file.
+pub const AISLER_NUMERIC_HEADERS: [&str; 3] = ["Mid X", "Mid Y", "Rotation"];
+
+/// A record of an Aisler centroid file, the coordinates usually have a 'mm' suffix.
+#[derive(Debug, serde::Deserialize)]
+pub struct AislerPlacementRecord {
+    #[serde(rename(deserialize = "Designator"))]
+    ref_des: String,
+    #[serde(rename(deserialize = "Comment"))]
+    comment: String,
+    #[serde(rename(deserialize = "Footprint"))]
+    footprint: String,
+    #[serde(rename(deserialize = "Layer"))]
+    layer: AislerPcbSide,
+    #[serde(rename(deserialize = "Mid X"), deserialize_with = "deserialize_lenient_decimal")]
+    x: Decimal,
+    #[serde(rename(deserialize = "Mid Y"), deserialize_with = "deserialize_lenient_decimal")]
+    y: Decimal,
+    /// Positive values indicate anti-clockwise rotation
+    /// Range is 0 - < 360
+    #[serde(rename(deserialize = "Rotation"), deserialize_with = "deserialize_lenient_decimal")]
+    rotation: Decimal,
+}
+
+#[derive(Debug, serde::Deserialize, Clone)]
+enum AislerPcbSide {
+    #[serde(alias = "top", alias = "TopLayer", alias = "T")]
+    Top,
+    #[serde(alias = "bottom", alias = "BottomLayer", alias = "B")]
+    Bottom,
+}
+
+impl From<&AislerPcbSide> for PcbSide {
+    fn from(value: &AislerPcbSide) -> Self {
+        match value {
+            AislerPcbSide::Top => PcbSide::Top,
+            AislerPcbSide::Bottom => PcbSide::Bottom,
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum AislerPlacementRecordError {
+    #[error("Unknown")]
+    Unknown
+}
+
+impl AislerPlacementRecord {
+    /// The footprint and comment columns are mapped to the same fields as Eurocircuits files, 'package' and 'value',
+    /// so that part mappings can be shared between the fab-house dialects.
+    pub fn build_eda_placement(&self) -> Result<EdaPlacement, AislerPlacementRecordError> {
+        Ok(EdaPlacement {
+            ref_des: self.ref_des.to_string(),
+            place: true,
+            fields: vec![
+                EdaPlacementField { name: "package".to_string(), value: self.footprint.to_string() },
+                EdaPlacementField { name: "value".to_string(), value: self.comment.to_string() },
+            ],
+            pcb_side: PcbSide::from(&self.layer),
+            x: self.x,
+            y: self.y,
+            rotation: normalize_rotation(self.rotation),
+            kind: PlacementKind::Component,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use rust_decimal_macros::dec;
+    use pnp::pcb::PcbSide;
+    use pnp::placement::PlacementKind;
+    use crate::aisler::csv::AislerPlacementRecord;
+    use crate::placement::{EdaPlacement, EdaPlacementField};
+
+    #[test]
+    fn coordinates_with_units() {
+        // given
+        let content = indoc! {r#"
+            "Designator","Comment","Footprint","Mid X","Mid Y","Rotation","Layer"
+            "C1","100nF","C_0402","10.16mm","-5.08mm","90","TopLayer"
+        "#};
+
+        // and
+        let mut reader = csv::ReaderBuilder::new()
+            .from_reader(content.as_bytes());
+
+        // when
+        let record: AislerPlacementRecord = reader.deserialize().next().unwrap().unwrap();
+        let placement = record.build_eda_placement().unwrap();
+
+        // then
+        assert_eq!(placement, EdaPlacement {
+            ref_des: "C1".to_string(),
+            place: true,
+            fields: vec![
+                EdaPlacementField::new("package".to_string(), "C_0402".to_string()),
+                EdaPlacementField::new("value".to_string(), "100nF".to_string()),
+            ],
+            pcb_side: PcbSide::Top,
+            x: dec!(10.16),
+            y: dec!(-5.08),
+            rotation: dec!(90),
+            kind: PlacementKind::Component,
+        });
+    }
+}
@@ -0,0 +1,30 @@
+use ::csv::StringRecord;
+use crate::placement::EdaPlacement;
+use crate::plugin::{build_eda_placement_from_record, EdaToolPlugin, EdaToolPluginError};
+
+pub mod csv;
+
+/// Aisler centroid files, not an EDA tool, but a fab-house dialect, the fields are the same as Eurocircuits files.
+pub struct AislerPlugin;
+
+impl EdaToolPlugin for AislerPlugin {
+    fn name(&self) -> &'static str {
+        "Aisler"
+    }
+
+    fn required_headers(&self) -> &'static [&'static str] {
+        &csv::AISLER_HEADERS
+    }
+
+    fn numeric_headers(&self) -> &'static [&'static str] {
+        &csv::AISLER_NUMERIC_HEADERS
+    }
+
+    fn field_names(&self) -> &'static [&'static str] {
+        &["package", "value"]
+    }
+
+    fn build_eda_placement(&self, headers: &StringRecord, record: &StringRecord) -> Result<EdaPlacement, EdaToolPluginError> {
+        build_eda_placement_from_record(headers, record, csv::AislerPlacementRecord::build_eda_placement)
+    }
+}
@@ -0,0 +1,81 @@
+use regex::Regex;
+use pnp::placement::PlacementKind;
+use crate::placement::EdaPlacement;
+
+/// The names of the fields that contain the package, or footprint, of a placement, the first field found is used.
+const PACKAGE_FIELD_NAMES: [&str; 2] = ["package", "name"];
+
+/// Classifies placements by ref des and/or package, e.g. ref des `^FID\d+$` for fiducials.
+#[derive(Clone, Debug)]
+pub struct PlacementClassificationRule {
+    pub kind: PlacementKind,
+    pub ref_des_pattern: Option<Regex>,
+    pub package_pattern: Option<Regex>,
+}
+
+impl PlacementClassificationRule {
+    /// A rule without any patterns does not match.
+    pub fn matches(&self, eda_placement: &EdaPlacement) -> bool {
+        if self.ref_des_pattern.is_none() && self.package_pattern.is_none() {
+            return false
+        }
+
+        let ref_des_matched = self.ref_des_pattern.as_ref()
+            .is_none_or(|pattern| pattern.is_match(&eda_placement.ref_des));
+
+        let package_matched = self.package_pattern.as_ref()
+            .is_none_or(|pattern| find_package(eda_placement).is_some_and(|package| pattern.is_match(package)));
+
+        ref_des_matched && package_matched
+    }
+}
+
+fn find_package(eda_placement: &EdaPlacement) -> Option<&str> {
+    PACKAGE_FIELD_NAMES.iter()
+        .find_map(|name| eda_placement.fields.iter().find(|field| field.name.eq(name)))
+        .map(|field| field.value.as_str())
+}
+
+/// The kind of the first rule that matches, placements that do not match any rule are components.
+pub fn classify(eda_placement: &EdaPlacement, rules: &[PlacementClassificationRule]) -> PlacementKind {
+    rules.iter()
+        .find(|rule| rule.matches(eda_placement))
+        .map_or(PlacementKind::Component, |rule| rule.kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use regex::Regex;
+    use pnp::placement::PlacementKind;
+    use crate::classification::{classify, PlacementClassificationRule};
+    use crate::placement::{EdaPlacement, EdaPlacementField};
+
+    fn build_eda_placement(ref_des: &str, package: &str) -> EdaPlacement {
+        EdaPlacement {
+            ref_des: ref_des.to_string(),
+            place: true,
+            fields: vec![EdaPlacementField::new("package".to_string(), package.to_string())],
+            ..EdaPlacement::default()
+        }
+    }
+
+    #[test]
+    pub fn classify_by_ref_des_and_package() {
+        // given
+        let rules = vec![
+            PlacementClassificationRule { kind: PlacementKind::Fiducial, ref_des_pattern: Some(Regex::new("^FID\\d+$").unwrap()), package_pattern: None },
+            PlacementClassificationRule { kind: PlacementKind::TestPoint, ref_des_pattern: Some(Regex::new("^TP").unwrap()), package_pattern: Some(Regex::new("^TestPoint_").unwrap()) },
+            PlacementClassificationRule { kind: PlacementKind::Fiducial, ref_des_pattern: None, package_pattern: None },
+        ];
+
+        // expect
+        assert_eq!(classify(&build_eda_placement("FID1", "Fiducial_1mm"), &rules), PlacementKind::Fiducial);
+        assert_eq!(classify(&build_eda_placement("TP1", "TestPoint_Pad_1.0x1.0mm"), &rules), PlacementKind::TestPoint);
+
+        // and both patterns must match
+        assert_eq!(classify(&build_eda_placement("TP2", "R_0402"), &rules), PlacementKind::Component);
+
+        // and a rule without patterns does not match
+        assert_eq!(classify(&build_eda_placement("R1", "R_0402"), &rules), PlacementKind::Component);
+    }
+}
@@ -0,0 +1,89 @@
+use pnp::part::{Part, PartDetails};
+
+/// The columns that identify a DipTrace BOM file, the manufacturer part number is usually an additional field of the
+/// components, see `DiptraceBomRecord`.
+pub const DIPTRACE_BOM_HEADERS: [&str; 3] = ["RefDes", "Quantity", "Manufacturer"];
+
+/// A record of a DipTrace BOM file, one record for each part, e.g.
+/// ```text
+/// "Quantity","RefDes","Value","Name","Pattern","Manufacturer","MPN","Datasheet"
+/// "2","R1, R2","330R","RES","RES_0402","RES_MFR1","RES1","https://example.com/res1.pdf"
+/// ```
+#[derive(Debug, serde::Deserialize)]
+pub struct DiptraceBomRecord {
+    #[serde(rename(deserialize = "RefDes"))]
+    ref_des: String,
+    #[serde(rename(deserialize = "Manufacturer"))]
+    manufacturer: String,
+    #[serde(rename(deserialize = "MPN"), alias = "Mpn", alias = "Manufacturer Part Number", alias = "Part Number")]
+    mpn: String,
+    #[serde(rename(deserialize = "Pattern"), default)]
+    pattern: Option<String>,
+    #[serde(rename(deserialize = "Datasheet"), default)]
+    datasheet: Option<String>,
+}
+
+impl DiptraceBomRecord {
+    /// `None` if the manufacturer or manufacturer part number is empty, e.g. for fiducials and test-points.
+    pub fn build_part(&self) -> Option<Part> {
+        let manufacturer = self.manufacturer.trim();
+        let mpn = self.mpn.trim();
+
+        match manufacturer.is_empty() || mpn.is_empty() {
+            true => None,
+            false => Some(Part::new(manufacturer.to_string(), mpn.to_string())),
+        }
+    }
+
+    /// The pattern is used as the package.
+    pub fn build_part_details(&self) -> PartDetails {
+        PartDetails {
+            image: None,
+            datasheet: self.datasheet.clone(),
+            package: self.pattern.clone(),
+            ..PartDetails::default()
+        }
+    }
+
+    /// e.g. 'R1, R2' -> ['R1', 'R2']
+    pub fn ref_des_list(&self) -> Vec<String> {
+        self.ref_des.split([',', ' '])
+            .filter(|ref_des| !ref_des.is_empty())
+            .map(|ref_des| ref_des.to_string())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pnp::part::{Part, PartDetails};
+    use crate::diptrace::bom::DiptraceBomRecord;
+
+    #[test]
+    fn build_parts() {
+        // given
+        let content = indoc! {r#"
+            "Quantity","RefDes","Value","Name","Pattern","Manufacturer","Manufacturer Part Number","Datasheet"
+            "2","R1, R2","330R","RES","RES_0402","RES_MFR1","RES1","https://example.com/res1.pdf"
+            "1","FID1","","FIDUCIAL","FID_1MM","","",""
+        "#};
+        let mut reader = csv::ReaderBuilder::new().from_reader(content.as_bytes());
+
+        // when
+        let records: Vec<DiptraceBomRecord> = reader.deserialize().collect::<Result<_, _>>().unwrap();
+
+        // then
+        assert_eq!(records[0].build_part(), Some(Part::new("RES_MFR1".to_string(), "RES1".to_string())));
+        assert_eq!(records[0].build_part_details(), PartDetails {
+            image: None,
+            datasheet: Some("https://example.com/res1.pdf".to_string()),
+            package: Some("RES_0402".to_string()),
+            ..PartDetails::default()
+        });
+        assert_eq!(records[0].ref_des_list(), vec!["R1", "R2"]);
+
+        // and the fiducial has no part
+        assert_eq!(records[1].build_part(), None);
+    }
+}
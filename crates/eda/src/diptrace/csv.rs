@@ -1,12 +1,17 @@
-use std::ops::{Add, Sub};
 use rust_decimal::Decimal;
-use rust_decimal_macros::dec;
 use thiserror::Error;
-use crate::placement::{EdaPlacement, EdaPlacementField};
+use crate::placement::{normalize_rotation, EdaPlacement, EdaPlacementField};
 use pnp::pcb::PcbSide;
+use pnp::placement::PlacementKind;
 
 // TODO add tests for aliases
 
+/// The columns that identify a DipTrace placements file.
+pub const DIPTRACE_HEADERS: [&str; 4] = ["RefDes", "Name", "Value", "Side"];
+
+/// The numeric columns of a DipTrace placements file, including aliases.
+pub const DIPTRACE_NUMERIC_HEADERS: [&str; 5] = ["X", "Y", "Center X (mm)", "Center Y (mm)", "Rotation"];
+
 #[derive(Debug, serde::Deserialize)]
 #[serde(rename_all(deserialize = "PascalCase"))]
 pub struct DiptracePlacementRecord {
@@ -59,6 +64,7 @@ impl DiptracePlacementRecord {
             x: self.x,
             y: self.y,
             rotation: DipTraceRotationConverter::convert(self.rotation),
+            kind: PlacementKind::Component,
         })
 
         // _ => Err(DiptracePlacementRecordError::Unknown)
@@ -67,17 +73,8 @@ impl DiptracePlacementRecord {
 
 struct DipTraceRotationConverter {}
 impl DipTraceRotationConverter {
-    pub fn convert(mut input: Decimal) -> Decimal {
-        while input >= dec!(360) {
-            input = input.sub(dec!(360));
-        }
-        while input < dec!(0) {
-            input = input.add( dec!(360));
-        }
-        if input > dec!(180) {
-            input = input.sub(dec!(360));
-        }
-        input
+    pub fn convert(input: Decimal) -> Decimal {
+        normalize_rotation(input)
     }
 }
 
@@ -1 +1,31 @@
+use ::csv::StringRecord;
+use crate::placement::EdaPlacement;
+use crate::plugin::{build_eda_placement_from_record, EdaToolPlugin, EdaToolPluginError};
+
 pub mod csv;
+pub mod bom;
+
+/// DipTrace placements files.
+pub struct DipTracePlugin;
+
+impl EdaToolPlugin for DipTracePlugin {
+    fn name(&self) -> &'static str {
+        "DipTrace"
+    }
+
+    fn required_headers(&self) -> &'static [&'static str] {
+        &csv::DIPTRACE_HEADERS
+    }
+
+    fn numeric_headers(&self) -> &'static [&'static str] {
+        &csv::DIPTRACE_NUMERIC_HEADERS
+    }
+
+    fn field_names(&self) -> &'static [&'static str] {
+        &["name", "value"]
+    }
+
+    fn build_eda_placement(&self, headers: &StringRecord, record: &StringRecord) -> Result<EdaPlacement, EdaToolPluginError> {
+        build_eda_placement_from_record(headers, record, csv::DiptracePlacementRecord::build_eda_placement)
+    }
+}
@@ -0,0 +1,116 @@
+use rust_decimal::Decimal;
+use thiserror::Error;
+use pnp::pcb::PcbSide;
+use pnp::placement::PlacementKind;
+use crate::placement::{deserialize_lenient_decimal, normalize_rotation, EdaPlacement, EdaPlacementField};
+
+/// The columns that identify a Eurocircuits centroid file.
+pub const EUROCIRCUITS_HEADERS: [&str; 4] = ["Designator", "PosX", "PosY", "Side"];
+
+/// The numeric columns of a Eurocircuits centroid file.
+pub const EUROCIRCUITS_NUMERIC_HEADERS: [&str; 3] = ["PosX", "PosY", "Rot"];
+
+/// A record of a Eurocircuits centroid (CPL) file, these are usually semicolon separated and may use decimal commas.
+#[derive(Debug, serde::Deserialize)]
+pub struct EurocircuitsPlacementRecord {
+    #[serde(rename(deserialize = "Designator"))]
+    ref_des: String,
+    #[serde(rename(deserialize = "Value"))]
+    value: String,
+    #[serde(rename(deserialize = "Package"))]
+    package: String,
+    #[serde(rename(deserialize = "Side"))]
+    side: EurocircuitsPcbSide,
+    #[serde(rename(deserialize = "PosX"), deserialize_with = "deserialize_lenient_decimal")]
+    x: Decimal,
+    #[serde(rename(deserialize = "PosY"), deserialize_with = "deserialize_lenient_decimal")]
+    y: Decimal,
+    /// Positive values indicate anti-clockwise rotation
+    /// Range is 0 - < 360
+    #[serde(rename(deserialize = "Rot"), deserialize_with = "deserialize_lenient_decimal")]
+    rotation: Decimal,
+}
+
+#[derive(Debug, serde::Deserialize, Clone)]
+enum EurocircuitsPcbSide {
+    #[serde(alias = "top", alias = "TOP", alias = "T")]
+    Top,
+    #[serde(alias = "bottom", alias = "BOTTOM", alias = "B")]
+    Bottom,
+}
+
+impl From<&EurocircuitsPcbSide> for PcbSide {
+    fn from(value: &EurocircuitsPcbSide) -> Self {
+        match value {
+            EurocircuitsPcbSide::Top => PcbSide::Top,
+            EurocircuitsPcbSide::Bottom => PcbSide::Bottom,
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum EurocircuitsPlacementRecordError {
+    #[error("Unknown")]
+    Unknown
+}
+
+impl EurocircuitsPlacementRecord {
+    pub fn build_eda_placement(&self) -> Result<EdaPlacement, EurocircuitsPlacementRecordError> {
+        Ok(EdaPlacement {
+            ref_des: self.ref_des.to_string(),
+            place: true,
+            fields: vec![
+                EdaPlacementField { name: "package".to_string(), value: self.package.to_string() },
+                EdaPlacementField { name: "value".to_string(), value: self.value.to_string() },
+            ],
+            pcb_side: PcbSide::from(&self.side),
+            x: self.x,
+            y: self.y,
+            rotation: normalize_rotation(self.rotation),
+            kind: PlacementKind::Component,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use rust_decimal_macros::dec;
+    use pnp::pcb::PcbSide;
+    use pnp::placement::PlacementKind;
+    use crate::eurocircuits::csv::EurocircuitsPlacementRecord;
+    use crate::placement::{EdaPlacement, EdaPlacementField};
+
+    #[test]
+    fn semicolon_separated_with_decimal_commas() {
+        // given
+        let content = indoc! {"
+            Designator;Value;Package;PosX;PosY;Rot;Side
+            R1;330R;0402;12,5;7,25;270;B
+        "};
+
+        // and
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(b';')
+            .from_reader(content.as_bytes());
+
+        // when
+        let record: EurocircuitsPlacementRecord = reader.deserialize().next().unwrap().unwrap();
+        let placement = record.build_eda_placement().unwrap();
+
+        // then
+        assert_eq!(placement, EdaPlacement {
+            ref_des: "R1".to_string(),
+            place: true,
+            fields: vec![
+                EdaPlacementField::new("package".to_string(), "0402".to_string()),
+                EdaPlacementField::new("value".to_string(), "330R".to_string()),
+            ],
+            pcb_side: PcbSide::Bottom,
+            x: dec!(12.5),
+            y: dec!(7.25),
+            rotation: dec!(-90),
+            kind: PlacementKind::Component,
+        });
+    }
+}
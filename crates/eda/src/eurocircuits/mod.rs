@@ -0,0 +1,30 @@
+use ::csv::StringRecord;
+use crate::placement::EdaPlacement;
+use crate::plugin::{build_eda_placement_from_record, EdaToolPlugin, EdaToolPluginError};
+
+pub mod csv;
+
+/// Eurocircuits centroid files, not an EDA tool, but a fab-house dialect.
+pub struct EurocircuitsPlugin;
+
+impl EdaToolPlugin for EurocircuitsPlugin {
+    fn name(&self) -> &'static str {
+        "Eurocircuits"
+    }
+
+    fn required_headers(&self) -> &'static [&'static str] {
+        &csv::EUROCIRCUITS_HEADERS
+    }
+
+    fn numeric_headers(&self) -> &'static [&'static str] {
+        &csv::EUROCIRCUITS_NUMERIC_HEADERS
+    }
+
+    fn field_names(&self) -> &'static [&'static str] {
+        &["package", "value"]
+    }
+
+    fn build_eda_placement(&self, headers: &StringRecord, record: &StringRecord) -> Result<EdaPlacement, EdaToolPluginError> {
+        build_eda_placement_from_record(headers, record, csv::EurocircuitsPlacementRecord::build_eda_placement)
+    }
+}
@@ -1,7 +1,8 @@
 use rust_decimal::Decimal;
 use thiserror::Error;
 use pnp::pcb::PcbSide;
-use crate::placement::{EdaPlacement, EdaPlacementField};
+use pnp::placement::PlacementKind;
+use crate::placement::{normalize_rotation, EdaPlacement, EdaPlacementField};
 
 #[derive(Error, Debug)]
 pub enum KiCadPlacementRecordError {
@@ -9,20 +10,33 @@ pub enum KiCadPlacementRecordError {
     Unknown
 }
 
+/// The columns that identify a KiCad placements file.
+pub const KICAD_HEADERS: [&str; 4] = ["Ref", "Package", "Val", "Side"];
+
+/// The numeric columns of a KiCad placements file, including aliases.
+pub const KICAD_NUMERIC_HEADERS: [&str; 6] = ["X", "Y", "PosX", "PosY", "Rotation", "Rot"];
+
+/// A record of a KiCad placements file, either the native CSV position file, e.g. 'board-top-pos.csv', which has the
+/// 'PosX', 'PosY' and 'Rot' columns, or a file with 'X', 'Y' and 'Rotation' columns.
+///
+/// The native CSV position file must be written using millimeters, see `kicad::pos` for the ASCII position file.
 #[derive(Debug, serde::Deserialize)]
 #[serde(rename_all(deserialize = "PascalCase"))]
 pub struct KiCadPlacementRecord {
-    #[serde(rename(deserialize = "ref"))]
+    #[serde(rename(deserialize = "ref"), alias = "Ref")]
     ref_des: String,
     package: String,
     val: String,
     side: KiCadPcbSide,
+    #[serde(alias = "PosX")]
     x: Decimal,
+    #[serde(alias = "PosY")]
     y: Decimal,
     /// Positive values indicate anti-clockwise rotation
-    /// Range is >-180 to +180.
+    /// Range is >-180 to +180, older versions of KiCad write 0 - < 360.
     /// No rounding.
     /// Values are truncated to 3 decimal places in the UI.
+    #[serde(alias = "Rot")]
     rotation: Decimal,
 }
 
@@ -54,10 +68,52 @@ impl KiCadPlacementRecord {
             pcb_side: PcbSide::from(&self.side),
             x: self.x,
             y: self.y,
-            // TODO normalize rotation in case kicad uses values outside it's expected range.
-            rotation: self.rotation,
+            rotation: normalize_rotation(self.rotation),
+            kind: PlacementKind::Component,
         })
 
         // _ => Err(KiCadPlacementRecordError::Unknown)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use rust_decimal_macros::dec;
+    use pnp::pcb::PcbSide;
+    use pnp::placement::PlacementKind;
+    use crate::kicad::csv::KiCadPlacementRecord;
+    use crate::placement::{EdaPlacement, EdaPlacementField};
+
+    #[test]
+    fn native_position_file() {
+        // given
+        let content = indoc! {r#"
+            Ref,Val,Package,PosX,PosY,Rot,Side
+            "C1","100nF","C_0402_1005Metric",10.0000,-20.0000,270.0000,top
+        "#};
+
+        // and
+        let mut reader = csv::ReaderBuilder::new()
+            .from_reader(content.as_bytes());
+
+        // when
+        let record: KiCadPlacementRecord = reader.deserialize().next().unwrap().unwrap();
+        let placement = record.build_eda_placement().unwrap();
+
+        // then
+        assert_eq!(placement, EdaPlacement {
+            ref_des: "C1".to_string(),
+            place: true,
+            fields: vec![
+                EdaPlacementField::new("package".to_string(), "C_0402_1005Metric".to_string()),
+                EdaPlacementField::new("val".to_string(), "100nF".to_string()),
+            ],
+            pcb_side: PcbSide::Top,
+            x: dec!(10.0000),
+            y: dec!(-20.0000),
+            rotation: dec!(-90.0000),
+            kind: PlacementKind::Component,
+        });
+    }
+}
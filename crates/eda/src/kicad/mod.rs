@@ -1 +1,45 @@
-pub mod csv;
\ No newline at end of file
+use ::csv::StringRecord;
+use crate::placement::EdaPlacement;
+use crate::plugin::{build_eda_placement_from_record, EdaToolPlugin, EdaToolPluginError};
+
+pub mod csv;
+pub mod pos;
+pub mod pcb;
+
+/// KiCad CSV position files, and the native ASCII position and board files.
+pub struct KiCadPlugin;
+
+impl EdaToolPlugin for KiCadPlugin {
+    fn name(&self) -> &'static str {
+        "KiCad"
+    }
+
+    fn required_headers(&self) -> &'static [&'static str] {
+        &csv::KICAD_HEADERS
+    }
+
+    fn numeric_headers(&self) -> &'static [&'static str] {
+        &csv::KICAD_NUMERIC_HEADERS
+    }
+
+    fn field_names(&self) -> &'static [&'static str] {
+        &["package", "val"]
+    }
+
+    fn build_eda_placement(&self, headers: &StringRecord, record: &StringRecord) -> Result<EdaPlacement, EdaToolPluginError> {
+        build_eda_placement_from_record(headers, record, csv::KiCadPlacementRecord::build_eda_placement)
+    }
+
+    fn is_native_content(&self, content: &str) -> bool {
+        pcb::is_pcb_content(content) || pos::is_pos_content(content)
+    }
+
+    fn parse_native_content(&self, content: &str) -> Result<Vec<EdaPlacement>, EdaToolPluginError> {
+        let result = match pcb::is_pcb_content(content) {
+            true => pcb::parse_pcb(content).map_err(|reason| Box::new(reason) as Box<dyn std::error::Error + Send + Sync>),
+            false => pos::parse_pos(content).map_err(|reason| Box::new(reason) as Box<dyn std::error::Error + Send + Sync>),
+        };
+
+        result.map_err(|reason| EdaToolPluginError::InvalidContent { eda_tool: self.name(), reason })
+    }
+}
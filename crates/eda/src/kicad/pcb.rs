@@ -0,0 +1,342 @@
+//! The KiCad board file, e.g. 'board.kicad_pcb', an s-expression file, the placements are read from the footprints
+//! directly, so that a position file does not have to be exported.
+//!
+//! ```text
+//! (kicad_pcb (version 20240108) (generator "pcbnew")
+//!   (footprint "Capacitor_SMD:C_0402_1005Metric" (layer "F.Cu")
+//!     (at 10 20 90)
+//!     (property "Reference" "C1" (at 0 -1.16 90) (layer "F.SilkS"))
+//!     (property "Value" "100nF" (at 0 1.16 90) (layer "F.Fab"))
+//!     (attr smd)
+//!   )
+//! )
+//! ```
+//!
+//! KiCad 5 'module' and KiCad 6/7 'fp_text' footprints are also supported.  The board's Y axis points down, so the Y
+//! coordinates are negated, the same as the position files that KiCad writes.  Footprints that are excluded from the
+//! position files, or that are only on the board (e.g. logos), are omitted and footprints marked 'do not populate' are
+//! not placed.
+
+use std::str::FromStr;
+use rust_decimal::Decimal;
+use thiserror::Error;
+use pnp::pcb::PcbSide;
+use pnp::placement::PlacementKind;
+use crate::placement::{normalize_rotation, EdaPlacement, EdaPlacementField};
+
+#[derive(Error, Debug, PartialEq)]
+pub enum KiCadPcbError {
+    #[error("Invalid board file syntax. line: {line}, reason: {reason}")]
+    InvalidSyntax { line: usize, reason: String },
+
+    #[error("Not a KiCad board file.")]
+    NotABoard,
+
+    #[error("Invalid footprint. footprint: '{footprint}', reason: {reason}")]
+    InvalidFootprint { footprint: String, reason: String },
+}
+
+/// Returns true if the content is a KiCad board file.
+pub fn is_pcb_content(content: &str) -> bool {
+    content.trim_start().starts_with("(kicad_pcb")
+}
+
+pub fn parse_pcb(content: &str) -> Result<Vec<EdaPlacement>, KiCadPcbError> {
+    let board = parse_expression(content)?;
+
+    if board.name() != Some("kicad_pcb") {
+        return Err(KiCadPcbError::NotABoard)
+    }
+
+    let mut placements = vec![];
+
+    for footprint in board.children().iter().filter(|child| matches!(child.name(), Some("footprint" | "module"))) {
+        let footprint_name = footprint.atom(1).unwrap_or_default();
+
+        if let Some(placement) = parse_footprint(footprint)
+            .map_err(|reason| KiCadPcbError::InvalidFootprint { footprint: footprint_name.to_string(), reason })? {
+            placements.push(placement);
+        }
+    }
+
+    Ok(placements)
+}
+
+/// `None` if the footprint is excluded from the position files.
+fn parse_footprint(footprint: &Expression) -> Result<Option<EdaPlacement>, String> {
+    let attributes: Vec<&str> = footprint.find("attr")
+        .map(|attr| attr.children().iter().skip(1).filter_map(Expression::as_atom).collect())
+        .unwrap_or_default();
+
+    if attributes.iter().any(|attribute| matches!(*attribute, "exclude_from_pos_files" | "board_only" | "virtual")) {
+        return Ok(None)
+    }
+
+    // the library name is not part of the package, e.g. 'C_0402_1005Metric' for 'Capacitor_SMD:C_0402_1005Metric'
+    let lib_id = footprint.atom(1).ok_or("missing footprint name")?;
+    let package = lib_id.rsplit_once(':').map_or(lib_id, |(_library, name)| name);
+
+    let ref_des = find_text(footprint, "Reference", "reference").ok_or("missing reference")?;
+    let val = find_text(footprint, "Value", "value").unwrap_or_default();
+
+    let pcb_side = match footprint.find("layer").and_then(|layer| layer.atom(1)) {
+        Some("F.Cu") => PcbSide::Top,
+        Some("B.Cu") => PcbSide::Bottom,
+        layer => return Err(format!("invalid layer: {:?}", layer)),
+    };
+
+    let at = footprint.find("at").ok_or("missing position")?;
+    let parse_decimal = |index: usize| -> Result<Decimal, String> {
+        match at.atom(index) {
+            Some(value) => Decimal::from_str(value)
+                .or_else(|_error| Decimal::from_scientific(value))
+                .map_err(|_error| format!("invalid decimal: '{}'", value)),
+            None => Ok(Decimal::ZERO),
+        }
+    };
+
+    Ok(Some(EdaPlacement {
+        ref_des: ref_des.to_string(),
+        place: !attributes.contains(&"dnp"),
+        fields: vec![
+            EdaPlacementField { name: "package".to_string(), value: package.to_string() },
+            EdaPlacementField { name: "val".to_string(), value: val.to_string() },
+        ],
+        pcb_side,
+        x: parse_decimal(1)?.normalize(),
+        y: (-parse_decimal(2)?).normalize(),
+        rotation: normalize_rotation(parse_decimal(3)?).normalize(),
+        kind: PlacementKind::Component,
+    }))
+}
+
+/// KiCad 8 uses properties, e.g. `(property "Reference" "R1")`, earlier versions use text, e.g. `(fp_text reference "R1")`.
+fn find_text<'a>(footprint: &'a Expression, property_name: &str, text_kind: &str) -> Option<&'a str> {
+    footprint.children().iter()
+        .find_map(|child| match child.name() {
+            Some("property") if child.atom(1) == Some(property_name) => child.atom(2),
+            Some("fp_text") if child.atom(1) == Some(text_kind) => child.atom(2),
+            _ => None,
+        })
+}
+
+#[derive(Debug, PartialEq)]
+enum Expression {
+    Atom(String),
+    List(Vec<Expression>),
+}
+
+impl Expression {
+    fn as_atom(&self) -> Option<&str> {
+        match self {
+            Expression::Atom(value) => Some(value),
+            Expression::List(_) => None,
+        }
+    }
+
+    fn children(&self) -> &[Expression] {
+        match self {
+            Expression::List(children) => children,
+            Expression::Atom(_) => &[],
+        }
+    }
+
+    /// The first atom of a list, e.g. 'footprint' for `(footprint "R_0402" ...)`.
+    fn name(&self) -> Option<&str> {
+        self.atom(0)
+    }
+
+    fn atom(&self, index: usize) -> Option<&str> {
+        self.children().get(index).and_then(Expression::as_atom)
+    }
+
+    /// The first child list with the name.
+    fn find(&self, name: &str) -> Option<&Expression> {
+        self.children().iter().find(|child| child.name() == Some(name))
+    }
+}
+
+fn parse_expression(content: &str) -> Result<Expression, KiCadPcbError> {
+    let mut stack: Vec<Vec<Expression>> = vec![];
+    let mut result: Option<Expression> = None;
+    let mut line = 1;
+
+    let mut chars = content.chars().peekable();
+    while let Some(char) = chars.next() {
+        match char {
+            '\n' => line += 1,
+            _ if char.is_whitespace() => {},
+            '(' => stack.push(vec![]),
+            ')' => {
+                let list = stack.pop()
+                    .ok_or(KiCadPcbError::InvalidSyntax { line, reason: "unexpected ')'".to_string() })?;
+
+                match stack.last_mut() {
+                    Some(parent) => parent.push(Expression::List(list)),
+                    None => {
+                        result = Some(Expression::List(list));
+                        break
+                    },
+                }
+            },
+            '"' => {
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => value.push('\n'),
+                            Some(escaped) => value.push(escaped),
+                            None => return Err(KiCadPcbError::InvalidSyntax { line, reason: "unterminated string".to_string() }),
+                        },
+                        Some(char) => {
+                            if char == '\n' {
+                                line += 1;
+                            }
+                            value.push(char)
+                        },
+                        None => return Err(KiCadPcbError::InvalidSyntax { line, reason: "unterminated string".to_string() }),
+                    }
+                }
+                push_atom(&mut stack, value, line)?;
+            },
+            _ => {
+                let mut value = char.to_string();
+                while let Some(next) = chars.peek() {
+                    if next.is_whitespace() || matches!(next, '(' | ')' | '"') {
+                        break
+                    }
+                    value.push(*next);
+                    chars.next();
+                }
+                push_atom(&mut stack, value, line)?;
+            },
+        }
+    }
+
+    result.ok_or(KiCadPcbError::InvalidSyntax { line, reason: "unexpected end of file".to_string() })
+}
+
+fn push_atom(stack: &mut [Vec<Expression>], value: String, line: usize) -> Result<(), KiCadPcbError> {
+    match stack.last_mut() {
+        Some(list) => {
+            list.push(Expression::Atom(value));
+            Ok(())
+        },
+        None => Err(KiCadPcbError::InvalidSyntax { line, reason: format!("unexpected atom outside of a list: '{}'", value) }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use rust_decimal_macros::dec;
+    use pnp::pcb::PcbSide;
+    use pnp::placement::PlacementKind;
+    use crate::kicad::pcb::{is_pcb_content, parse_pcb, KiCadPcbError};
+    use crate::placement::{EdaPlacement, EdaPlacementField};
+
+    #[test]
+    fn parse_footprints() {
+        // given
+        let content = indoc! {r#"
+            (kicad_pcb (version 20240108) (generator "pcbnew")
+              (general (thickness 1.6))
+              (footprint "Capacitor_SMD:C_0402_1005Metric" (layer "F.Cu")
+                (uuid "c1")
+                (at 10 20 90)
+                (property "Reference" "C1" (at 0 -1.16 90) (layer "F.SilkS"))
+                (property "Value" "100nF" (at 0 1.16 90) (layer "F.Fab"))
+                (attr smd)
+              )
+              (footprint "Connector:PinHeader_1x02" (layer "B.Cu")
+                (at 30.5 25 270)
+                (fp_text reference "J1" (at 0 0) (layer "B.SilkS"))
+                (fp_text value "CONN \"2 pin\"" (at 0 0) (layer "B.Fab"))
+                (attr through_hole dnp)
+              )
+              (footprint "Logo:Logo" (layer "F.Cu")
+                (at 0 0)
+                (property "Reference" "G1")
+                (attr board_only exclude_from_pos_files exclude_from_bom)
+              )
+            )
+        "#};
+
+        // when
+        let placements = parse_pcb(content).unwrap();
+
+        // then
+        assert_eq!(placements, vec![
+            EdaPlacement {
+                ref_des: "C1".to_string(),
+                place: true,
+                fields: vec![
+                    EdaPlacementField::new("package".to_string(), "C_0402_1005Metric".to_string()),
+                    EdaPlacementField::new("val".to_string(), "100nF".to_string()),
+                ],
+                pcb_side: PcbSide::Top,
+                x: dec!(10),
+                y: dec!(-20),
+                rotation: dec!(90),
+                kind: PlacementKind::Component,
+            },
+            EdaPlacement {
+                ref_des: "J1".to_string(),
+                place: false,
+                fields: vec![
+                    EdaPlacementField::new("package".to_string(), "PinHeader_1x02".to_string()),
+                    EdaPlacementField::new("val".to_string(), "CONN \"2 pin\"".to_string()),
+                ],
+                pcb_side: PcbSide::Bottom,
+                x: dec!(30.5),
+                y: dec!(-25),
+                // normalized from 270
+                rotation: dec!(-90),
+                kind: PlacementKind::Component,
+            },
+        ]);
+    }
+
+    #[test]
+    fn parse_kicad_5_modules() {
+        // given
+        let content = indoc! {r#"
+            (kicad_pcb (version 20171130) (host pcbnew 5.1.9)
+              (module Resistor_SMD:R_0402_1005Metric (layer F.Cu) (tedit 5F68FEEE) (tstamp 5F000001)
+                (at 12.5 -7.25)
+                (attr smd)
+                (fp_text reference R1 (at 0 -1.17) (layer F.SilkS))
+                (fp_text value 10k (at 0 1.17) (layer F.Fab))
+              )
+            )
+        "#};
+
+        // when
+        let placements = parse_pcb(content).unwrap();
+
+        // then
+        assert_eq!(placements.len(), 1);
+        assert_eq!(placements[0].ref_des, "R1");
+        assert_eq!((placements[0].x, placements[0].y, placements[0].rotation), (dec!(12.5), dec!(7.25), dec!(0)));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(parse_pcb("(kicad_sch (version 20231120))"), Err(KiCadPcbError::NotABoard));
+        assert_eq!(
+            parse_pcb("(kicad_pcb\n  (footprint \"R_0402\" (layer \"F.Cu\")"),
+            Err(KiCadPcbError::InvalidSyntax { line: 2, reason: "unexpected end of file".to_string() })
+        );
+        assert_eq!(
+            parse_pcb("(kicad_pcb (footprint \"R_0402\" (layer \"In1.Cu\") (at 0 0) (property \"Reference\" \"R1\")))"),
+            Err(KiCadPcbError::InvalidFootprint { footprint: "R_0402".to_string(), reason: "invalid layer: Some(\"In1.Cu\")".to_string() })
+        );
+    }
+
+    #[test]
+    fn detect_pcb_content() {
+        assert!(is_pcb_content("\n(kicad_pcb (version 20240108) (generator \"pcbnew\")\n"));
+        assert!(!is_pcb_content("### Footprint positions - created on 2024-01-01T12:00:00 ###\n"));
+    }
+}
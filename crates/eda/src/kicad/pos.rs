@@ -0,0 +1,220 @@
+//! The native KiCad footprint position file, as written by 'File -> Fabrication Outputs -> Component Placement' using
+//! the ASCII format, e.g. 'board-top.pos', 'board-bottom.pos' or 'board-all.pos'.
+//!
+//! ```text
+//! ### Footprint positions - created on 2024-01-01T12:00:00 ###
+//! ### Printed by KiCad version 8.0.0
+//! ## Unit = mm, Angle = deg.
+//! ## Side : top
+//! # Ref     Val       Package                PosX       PosY       Rot  Side
+//! C1        100nF     C_0402_1005Metric    10.0000   -20.0000   90.0000  top
+//! ## End
+//! ```
+//!
+//! KiCad replaces spaces in values and packages with underscores, so the columns are separated by whitespace.
+//! Coordinates in inches are converted to millimeters.
+
+use std::str::FromStr;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use thiserror::Error;
+use pnp::pcb::PcbSide;
+use pnp::placement::PlacementKind;
+use crate::placement::{normalize_rotation, EdaPlacement, EdaPlacementField};
+
+#[derive(Error, Debug, PartialEq)]
+pub enum KiCadPosError {
+    #[error("Unknown unit. line: {line}, unit: '{unit}'")]
+    UnknownUnit { line: usize, unit: String },
+
+    #[error("Invalid placement. line: {line}, reason: {reason}")]
+    InvalidPlacement { line: usize, reason: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KiCadUnit {
+    Millimeters,
+    Inches,
+}
+
+impl KiCadUnit {
+    fn to_millimeters(self, value: Decimal) -> Decimal {
+        match self {
+            KiCadUnit::Millimeters => value,
+            KiCadUnit::Inches => value * dec!(25.4),
+        }
+    }
+}
+
+/// Returns true if the content is a KiCad position file, rather than a CSV file, position files start with a comment.
+pub fn is_pos_content(content: &str) -> bool {
+    content.lines()
+        .find(|line| !line.trim().is_empty())
+        .is_some_and(|line| line.trim_start().starts_with("###"))
+}
+
+pub fn parse_pos(content: &str) -> Result<Vec<EdaPlacement>, KiCadPosError> {
+    let mut unit = KiCadUnit::Millimeters;
+    let mut placements = vec![];
+
+    for (index, line) in content.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+
+        if line.is_empty() {
+            continue
+        }
+
+        if let Some(comment) = line.strip_prefix('#') {
+            if let Some(unit_name) = parse_unit_name(comment) {
+                unit = match unit_name.to_lowercase().as_str() {
+                    "mm" => KiCadUnit::Millimeters,
+                    "inch" | "inches" => KiCadUnit::Inches,
+                    _ => return Err(KiCadPosError::UnknownUnit { line: line_number, unit: unit_name.to_string() }),
+                };
+            }
+            continue
+        }
+
+        let placement = parse_placement(line, unit)
+            .map_err(|reason| KiCadPosError::InvalidPlacement { line: line_number, reason })?;
+
+        placements.push(placement);
+    }
+
+    Ok(placements)
+}
+
+/// e.g. '## Unit = mm, Angle = deg.'
+fn parse_unit_name(comment: &str) -> Option<&str> {
+    let (_, unit) = comment.split_once("Unit =")?;
+
+    unit.split(',').next().map(str::trim)
+}
+
+fn parse_placement(line: &str, unit: KiCadUnit) -> Result<EdaPlacement, String> {
+    let columns: Vec<&str> = line.split_whitespace().collect();
+
+    let [ref_des, val, package, x, y, rotation, side] = columns.as_slice() else {
+        return Err(format!("expected 7 columns, found {}", columns.len()))
+    };
+
+    let parse_decimal = |value: &str| Decimal::from_str(value)
+        .map_err(|_error| format!("invalid decimal: '{}'", value));
+
+    let pcb_side = match *side {
+        "top" => PcbSide::Top,
+        "bottom" => PcbSide::Bottom,
+        _ => return Err(format!("invalid side: '{}'", side)),
+    };
+
+    Ok(EdaPlacement {
+        ref_des: ref_des.to_string(),
+        place: true,
+        fields: vec![
+            EdaPlacementField { name: "package".to_string(), value: package.to_string() },
+            EdaPlacementField { name: "val".to_string(), value: val.to_string() },
+        ],
+        pcb_side,
+        x: unit.to_millimeters(parse_decimal(x)?),
+        y: unit.to_millimeters(parse_decimal(y)?),
+        rotation: normalize_rotation(parse_decimal(rotation)?),
+        kind: PlacementKind::Component,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use rust_decimal_macros::dec;
+    use pnp::pcb::PcbSide;
+    use pnp::placement::PlacementKind;
+    use crate::kicad::pos::{is_pos_content, parse_pos, KiCadPosError};
+    use crate::placement::{EdaPlacement, EdaPlacementField};
+
+    #[test]
+    fn parse_millimeters() {
+        // given
+        let content = indoc! {"
+            ### Footprint positions - created on 2024-01-01T12:00:00 ###
+            ### Printed by KiCad version 8.0.0
+            ## Unit = mm, Angle = deg.
+            ## Side : All
+            # Ref     Val       Package                PosX       PosY       Rot  Side
+            C1        100nF     C_0402_1005Metric    10.0000   -20.0000   90.0000  top
+            J1        CONN_01x02 PinHeader_1x02     30.5000   -25.0000  270.0000  bottom
+            ## End
+        "};
+
+        // when
+        let placements = parse_pos(content).unwrap();
+
+        // then
+        assert_eq!(placements, vec![
+            EdaPlacement {
+                ref_des: "C1".to_string(),
+                place: true,
+                fields: vec![
+                    EdaPlacementField::new("package".to_string(), "C_0402_1005Metric".to_string()),
+                    EdaPlacementField::new("val".to_string(), "100nF".to_string()),
+                ],
+                pcb_side: PcbSide::Top,
+                x: dec!(10.0000),
+                y: dec!(-20.0000),
+                rotation: dec!(90.0000),
+                kind: PlacementKind::Component,
+            },
+            EdaPlacement {
+                ref_des: "J1".to_string(),
+                place: true,
+                fields: vec![
+                    EdaPlacementField::new("package".to_string(), "PinHeader_1x02".to_string()),
+                    EdaPlacementField::new("val".to_string(), "CONN_01x02".to_string()),
+                ],
+                pcb_side: PcbSide::Bottom,
+                x: dec!(30.5000),
+                y: dec!(-25.0000),
+                // normalized from 270
+                rotation: dec!(-90.0000),
+                kind: PlacementKind::Component,
+            },
+        ]);
+    }
+
+    #[test]
+    fn parse_inches() {
+        // given
+        let content = indoc! {"
+            ### Footprint positions - created on 2024-01-01T12:00:00 ###
+            ## Unit = inches, Angle = deg.
+            ## Side : top
+            # Ref     Val       Package                PosX       PosY       Rot  Side
+            R1        10k       R_0402_1005Metric    1.0000    -0.5000     0.0000  top
+            ## End
+        "};
+
+        // when
+        let placements = parse_pos(content).unwrap();
+
+        // then
+        assert_eq!((placements[0].x, placements[0].y), (dec!(25.4), dec!(-12.7)));
+    }
+
+    #[test]
+    fn invalid_placement() {
+        // given
+        let content = indoc! {"
+            ### Footprint positions - created on 2024-01-01T12:00:00 ###
+            R1        10k       R_0402_1005Metric    1.0000    -0.5000     0.0000  middle
+        "};
+
+        // expect
+        assert_eq!(parse_pos(content), Err(KiCadPosError::InvalidPlacement { line: 2, reason: "invalid side: 'middle'".to_string() }));
+    }
+
+    #[test]
+    fn detect_pos_content() {
+        assert!(is_pos_content("\n### Footprint positions - created on 2024-01-01T12:00:00 ###\n"));
+        assert!(!is_pos_content("Ref,Val,Package,PosX,PosY,Rot,Side\n"));
+    }
+}
@@ -1,12 +1,10 @@
 pub mod diptrace;
 pub mod kicad;
+pub mod eurocircuits;
+pub mod aisler;
 
 pub mod placement;
 pub mod substitution;
+pub mod classification;
 pub mod criteria;
-
-#[derive(Debug)]
-pub enum EdaTool {
-    DipTrace,
-    KiCad,
-}
+pub mod plugin;
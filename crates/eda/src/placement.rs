@@ -1,5 +1,11 @@
+use std::ops::{Add, Sub};
+use std::str::FromStr;
 use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{de, Deserialize, Deserializer};
+use thiserror::Error;
 use pnp::pcb::PcbSide;
+use pnp::placement::PlacementKind;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct EdaPlacementField {
@@ -33,6 +39,8 @@ pub struct EdaPlacement {
     /// Positive values indicate anti-clockwise rotation
     /// Range is >-180 to +180.
     pub rotation: Decimal,
+    /// See `classification::classify`.
+    pub kind: PlacementKind,
 }
 
 impl Default for EdaPlacement {
@@ -45,6 +53,109 @@ impl Default for EdaPlacement {
             x: Default::default(),
             y: Default::default(),
             rotation: Default::default(),
+            kind: PlacementKind::Component,
         }
     }
-}
\ No newline at end of file
+}
+/// Normalizes an anti-clockwise rotation to the range used by `EdaPlacement`, i.e. >-180 to +180.
+pub fn normalize_rotation(mut input: Decimal) -> Decimal {
+    while input >= dec!(360) {
+        input = input.sub(dec!(360));
+    }
+    while input < dec!(0) {
+        input = input.add( dec!(360));
+    }
+    if input > dec!(180) {
+        input = input.sub(dec!(360));
+    }
+    input
+}
+
+/// The decimal separator used by the numeric fields of a placements file, e.g. the X, Y and rotation columns.
+///
+/// Placements files written by tools using a European locale may use a decimal comma, e.g. '12,5'.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum DecimalSeparator {
+    /// Either a decimal point or a decimal comma, values that contain both are rejected as ambiguous.
+    ///
+    /// Thousands separators are not supported, e.g. '1,250' is read as 1.25, use `Point` if values may contain them.
+    #[default]
+    Auto,
+    /// e.g. '12.5'
+    Point,
+    /// e.g. '12,5'
+    Comma,
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum DecimalParseError {
+    #[error("Unexpected decimal separator. expected: {expected:?}, value: '{value}'")]
+    UnexpectedSeparator { expected: DecimalSeparator, value: String },
+
+    #[error("Ambiguous decimal separator, the value contains both a decimal point and a decimal comma. value: '{value}'")]
+    AmbiguousSeparator { value: String },
+
+    #[error("Invalid decimal. value: '{value}'")]
+    InvalidDecimal { value: String },
+}
+
+/// Parses a decimal that may have a 'mm' suffix, e.g. '12,5mm', as used by fab-house centroid files.
+pub fn parse_decimal(value: &str, decimal_separator: DecimalSeparator) -> Result<Decimal, DecimalParseError> {
+    let trimmed_value = value.trim()
+        .trim_end_matches("mm")
+        .trim_end();
+
+    let has_point = trimmed_value.contains('.');
+    let has_comma = trimmed_value.contains(',');
+
+    let normalized_value = match (decimal_separator, has_point, has_comma) {
+        (DecimalSeparator::Auto, true, true) => return Err(DecimalParseError::AmbiguousSeparator { value: value.to_string() }),
+        (DecimalSeparator::Point, _, true) | (DecimalSeparator::Comma, true, _) => {
+            return Err(DecimalParseError::UnexpectedSeparator { expected: decimal_separator, value: value.to_string() })
+        },
+        _ => trimmed_value.replace(',', "."),
+    };
+
+    Decimal::from_str(&normalized_value)
+        .map_err(|_error| DecimalParseError::InvalidDecimal { value: value.to_string() })
+}
+
+/// Deserializes a decimal that may have a 'mm' suffix and may use a decimal comma, e.g. '12,5mm', as used by
+/// fab-house centroid files.
+pub(crate) fn deserialize_lenient_decimal<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Decimal, D::Error> {
+    let value = String::deserialize(deserializer)?;
+
+    parse_decimal(&value, DecimalSeparator::Auto)
+        .map_err(de::Error::custom)
+}
+
+#[cfg(test)]
+mod parse_decimal_tests {
+    use rstest::rstest;
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+    use crate::placement::{parse_decimal, DecimalParseError, DecimalSeparator};
+
+    #[rstest]
+    #[case("12.5", DecimalSeparator::Auto, dec!(12.5))]
+    #[case("12,5", DecimalSeparator::Auto, dec!(12.5))]
+    #[case(" -7,25mm ", DecimalSeparator::Auto, dec!(-7.25))]
+    #[case("12.5", DecimalSeparator::Point, dec!(12.5))]
+    #[case("12,5", DecimalSeparator::Comma, dec!(12.5))]
+    #[case("90", DecimalSeparator::Comma, dec!(90))]
+    pub fn parse(#[case] value: &str, #[case] decimal_separator: DecimalSeparator, #[case] expected_value: Decimal) {
+        // expect
+        assert_eq!(parse_decimal(value, decimal_separator), Ok(expected_value));
+    }
+
+    #[rstest]
+    #[case("1.250,5", DecimalSeparator::Auto, DecimalParseError::AmbiguousSeparator { value: "1.250,5".to_string() })]
+    #[case("12,5", DecimalSeparator::Point, DecimalParseError::UnexpectedSeparator { expected: DecimalSeparator::Point, value: "12,5".to_string() })]
+    #[case("12.5", DecimalSeparator::Comma, DecimalParseError::UnexpectedSeparator { expected: DecimalSeparator::Comma, value: "12.5".to_string() })]
+    #[case("12,5,0", DecimalSeparator::Comma, DecimalParseError::InvalidDecimal { value: "12,5,0".to_string() })]
+    #[case("", DecimalSeparator::Auto, DecimalParseError::InvalidDecimal { value: "".to_string() })]
+    pub fn error(#[case] value: &str, #[case] decimal_separator: DecimalSeparator, #[case] expected_error: DecimalParseError) {
+        // expect
+        assert_eq!(parse_decimal(value, decimal_separator), Err(expected_error));
+    }
+}
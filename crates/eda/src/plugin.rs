@@ -0,0 +1,187 @@
+//! EDA tools, and fab-house dialects, are plugins, see [`EdaToolPlugin`], so that support for other tools can be added
+//! by registering a plugin, see [`EdaToolRegistry::register`], without changing the placements loading, part mapping or
+//! substitution code.
+
+use std::fmt::{Debug, Formatter};
+use csv::StringRecord;
+use serde::de::DeserializeOwned;
+use thiserror::Error;
+use crate::placement::EdaPlacement;
+use crate::{aisler, diptrace, eurocircuits, kicad};
+
+#[derive(Error, Debug)]
+pub enum EdaToolPluginError {
+    #[error("Invalid placement record. reason: {0}")]
+    InvalidRecord(csv::Error),
+
+    #[error("Unable to build placement from record. record: {record}, reason: {reason}")]
+    InvalidPlacement { record: String, reason: Box<dyn std::error::Error + Send + Sync> },
+
+    #[error("Invalid placements content. eda_tool: {eda_tool}, reason: {reason}")]
+    InvalidContent { eda_tool: &'static str, reason: Box<dyn std::error::Error + Send + Sync> },
+
+    #[error("Native placements files are not supported. eda_tool: {eda_tool}")]
+    NativeContentUnsupported { eda_tool: &'static str },
+}
+
+/// An EDA tool, or fab-house dialect, of placements files.
+pub trait EdaToolPlugin: Send + Sync {
+    /// The name, as used in the 'Eda' column of part mapping and substitution files, e.g. 'KiCad'.
+    fn name(&self) -> &'static str;
+
+    /// The columns that identify a placements file of the tool, see [`EdaToolRegistry::detect`].
+    fn required_headers(&self) -> &'static [&'static str];
+
+    /// The columns that contain numbers, i.e. coordinates and rotations, including aliases.
+    fn numeric_headers(&self) -> &'static [&'static str];
+
+    /// The placement fields that part mappings and substitution rules match on and substitute, e.g. 'package' and 'val'.
+    fn field_names(&self) -> &'static [&'static str];
+
+    /// Builds a placement from a record of a CSV placements file, the numeric fields use a decimal point.
+    fn build_eda_placement(&self, headers: &StringRecord, record: &StringRecord) -> Result<EdaPlacement, EdaToolPluginError>;
+
+    /// True if the content is a native, non-CSV, placements file of the tool, e.g. a KiCad board file.
+    fn is_native_content(&self, _content: &str) -> bool {
+        false
+    }
+
+    /// Parses the placements of a native placements file, see `is_native_content`.
+    fn parse_native_content(&self, _content: &str) -> Result<Vec<EdaPlacement>, EdaToolPluginError> {
+        Err(EdaToolPluginError::NativeContentUnsupported { eda_tool: self.name() })
+    }
+}
+
+impl Debug for dyn EdaToolPlugin + '_ {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Deserializes the record into the record type of a tool and builds the placement, for CSV based plugins.
+pub fn build_eda_placement_from_record<R, E>(headers: &StringRecord, record: &StringRecord, build_eda_placement: impl FnOnce(&R) -> Result<EdaPlacement, E>) -> Result<EdaPlacement, EdaToolPluginError>
+where
+    R: DeserializeOwned + Debug,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let record: R = record.deserialize(Some(headers))
+        .map_err(EdaToolPluginError::InvalidRecord)?;
+
+    build_eda_placement(&record)
+        .map_err(|reason| EdaToolPluginError::InvalidPlacement { record: format!("{:?}", record), reason: Box::new(reason) })
+}
+
+/// The EDA tools, detection uses the order of registration.
+#[derive(Debug)]
+pub struct EdaToolRegistry {
+    plugins: Vec<Box<dyn EdaToolPlugin>>,
+}
+
+impl EdaToolRegistry {
+    pub fn empty() -> Self {
+        Self { plugins: vec![] }
+    }
+
+    /// Registers the plugin, replacing a plugin with the same name.
+    pub fn register(&mut self, plugin: Box<dyn EdaToolPlugin>) {
+        self.plugins.retain(|existing| !existing.name().eq_ignore_ascii_case(plugin.name()));
+        self.plugins.push(plugin);
+    }
+
+    pub fn plugins(&self) -> impl Iterator<Item = &dyn EdaToolPlugin> {
+        self.plugins.iter().map(|plugin| plugin.as_ref())
+    }
+
+    /// Finds the plugin by name, case-insensitively.
+    pub fn find(&self, name: &str) -> Option<&dyn EdaToolPlugin> {
+        self.plugins().find(|plugin| plugin.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Detects the EDA tool, or fab-house dialect, of a placements file from the column names, case-insensitively.
+    pub fn detect(&self, headers: &[&str]) -> Option<&dyn EdaToolPlugin> {
+        self.plugins().find(|plugin| {
+            plugin.required_headers().iter().all(|required_header| {
+                headers.iter().any(|header| header.trim().eq_ignore_ascii_case(required_header))
+            })
+        })
+    }
+
+    /// Detects the EDA tool of a native, non-CSV, placements file.
+    pub fn detect_native_content(&self, content: &str) -> Option<&dyn EdaToolPlugin> {
+        self.plugins().find(|plugin| plugin.is_native_content(content))
+    }
+}
+
+impl Default for EdaToolRegistry {
+    /// The built-in EDA tools and fab-house dialects.
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry.register(Box::new(diptrace::DipTracePlugin));
+        registry.register(Box::new(kicad::KiCadPlugin));
+        registry.register(Box::new(eurocircuits::EurocircuitsPlugin));
+        registry.register(Box::new(aisler::AislerPlugin));
+
+        registry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use csv::StringRecord;
+    use rstest::rstest;
+    use crate::placement::EdaPlacement;
+    use crate::plugin::{EdaToolPlugin, EdaToolPluginError, EdaToolRegistry};
+
+    #[rstest]
+    #[case(&["RefDes", "Name", "Value", "Side", "X", "Y", "Rotation"], Some("DipTrace"))]
+    #[case(&["Ref", "Package", "Val", "Side", "X", "Y", "Rotation"], Some("KiCad"))]
+    #[case(&["Designator", "Value", "Package", "PosX", "PosY", "Rot", "Side"], Some("Eurocircuits"))]
+    #[case(&["Designator", "Comment", "Footprint", "Mid X", "Mid Y", "Rotation", "Layer"], Some("Aisler"))]
+    #[case(&["designator", " mid x", "mid y ", "layer"], Some("Aisler"))]
+    #[case(&["Designator", "X", "Y"], None)]
+    fn detect(#[case] headers: &[&str], #[case] expected_eda_tool: Option<&str>) {
+        // given
+        let registry = EdaToolRegistry::default();
+
+        // expect
+        assert_eq!(registry.detect(headers).map(|plugin| plugin.name()), expected_eda_tool);
+    }
+
+    struct TestPlugin;
+
+    impl EdaToolPlugin for TestPlugin {
+        fn name(&self) -> &'static str { "Test" }
+        fn required_headers(&self) -> &'static [&'static str] { &["Designator", "X", "Y"] }
+        fn numeric_headers(&self) -> &'static [&'static str] { &["X", "Y"] }
+        fn field_names(&self) -> &'static [&'static str] { &["footprint"] }
+        fn build_eda_placement(&self, _headers: &StringRecord, _record: &StringRecord) -> Result<EdaPlacement, EdaToolPluginError> {
+            Ok(EdaPlacement::default())
+        }
+    }
+
+    #[test]
+    fn register() {
+        // given
+        let mut registry = EdaToolRegistry::default();
+
+        // when
+        registry.register(Box::new(TestPlugin));
+
+        // then
+        assert_eq!(registry.detect(&["Designator", "X", "Y"]).map(|plugin| plugin.name()), Some("Test"));
+        assert_eq!(registry.find("test").map(|plugin| plugin.field_names()), Some(["footprint"].as_slice()));
+
+        // and the built-in plugins are still registered
+        assert!(registry.find("KiCad").is_some());
+    }
+
+    #[test]
+    fn native_content() {
+        // given
+        let registry = EdaToolRegistry::default();
+
+        // expect
+        assert_eq!(registry.detect_native_content("(kicad_pcb (version 20221018))").map(|plugin| plugin.name()), Some("KiCad"));
+        assert!(registry.detect_native_content("RefDes,Name,Value").is_none());
+    }
+}
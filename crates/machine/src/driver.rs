@@ -0,0 +1,90 @@
+//! Drivers for pick-and-place machines.
+//!
+//! A driver connects to a machine, homes it, sets up the feeders from a load-out and then places the placements of a
+//! phase, one at a time, so that each placement can be recorded as soon as the machine has placed it.
+
+use std::fmt::{Display, Formatter};
+use std::sync::mpsc::Receiver;
+use thiserror::Error;
+use pnp::load_out::LoadOutItem;
+use pnp::object_path::ObjectPath;
+use pnp::part::Part;
+use pnp::placement::Placement;
+use crate::simulated::SimulatedDriver;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MachineKind {
+    /// A machine that only exists in memory, for testing processes and planning without hardware.
+    Simulated,
+}
+
+impl Display for MachineKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MachineKind::Simulated => write!(f, "simulated"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MachineStatus {
+    Connected,
+    Homed,
+    FeedersSetUp { feeders: usize },
+    Placing { object_path: ObjectPath },
+    Placed { object_path: ObjectPath },
+    Disconnected,
+}
+
+impl Display for MachineStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MachineStatus::Connected => write!(f, "connected"),
+            MachineStatus::Homed => write!(f, "homed"),
+            MachineStatus::FeedersSetUp { feeders } => write!(f, "feeders set up, feeders: {}", feeders),
+            MachineStatus::Placing { object_path } => write!(f, "placing, object_path: {}", object_path),
+            MachineStatus::Placed { object_path } => write!(f, "placed, object_path: {}", object_path),
+            MachineStatus::Disconnected => write!(f, "disconnected"),
+        }
+    }
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum MachineError {
+    #[error("Machine not connected. machine: {0}")]
+    NotConnected(MachineKind),
+
+    #[error("Machine not homed. machine: {0}")]
+    NotHomed(MachineKind),
+
+    #[error("No feeder has been set up for the part. part: {part}, object_path: {object_path}")]
+    MissingFeeder { part: Part, object_path: ObjectPath },
+
+    #[error("Placement failed. object_path: {object_path}, reason: {reason}")]
+    PlacementFailed { object_path: ObjectPath, reason: String },
+}
+
+pub trait MachineDriver {
+    fn kind(&self) -> MachineKind;
+
+    fn connect(&mut self) -> Result<(), MachineError>;
+
+    fn home(&mut self) -> Result<(), MachineError>;
+
+    /// Sets up the feeders of the machine from the load-out, items that have not been assigned to a feeder are ignored.
+    fn setup_feeders(&mut self, load_out_items: &[LoadOutItem]) -> Result<(), MachineError>;
+
+    /// Returns when the placement has been placed.
+    fn place(&mut self, object_path: &ObjectPath, placement: &Placement) -> Result<(), MachineError>;
+
+    fn disconnect(&mut self) -> Result<(), MachineError>;
+
+    /// The status changes of the machine, a driver only has one subscriber, subscribing again replaces it.
+    fn subscribe(&mut self) -> Receiver<MachineStatus>;
+}
+
+pub fn build_driver(kind: MachineKind) -> Box<dyn MachineDriver> {
+    match kind {
+        MachineKind::Simulated => Box::new(SimulatedDriver::default()),
+    }
+}
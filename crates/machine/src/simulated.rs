@@ -0,0 +1,183 @@
+use std::collections::BTreeMap;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use tracing::{debug, info};
+use pnp::load_out::LoadOutItem;
+use pnp::object_path::ObjectPath;
+use pnp::part::Part;
+use pnp::placement::Placement;
+use crate::driver::{MachineDriver, MachineError, MachineKind, MachineStatus};
+
+/// A machine that places the placements immediately, it checks the same preconditions as a real machine, i.e. that it
+/// is connected and homed, and that the part of each placement is in a feeder.
+#[derive(Default)]
+pub struct SimulatedDriver {
+    connected: bool,
+    homed: bool,
+    /// Part to feeder reference.
+    feeders: BTreeMap<Part, String>,
+    /// In the order they were placed.
+    pub placed: Vec<ObjectPath>,
+    status_sender: Option<Sender<MachineStatus>>,
+}
+
+impl SimulatedDriver {
+    fn send_status(&mut self, status: MachineStatus) {
+        debug!("Machine status. machine: {}, status: {}", self.kind(), status);
+
+        if let Some(sender) = &self.status_sender {
+            // the subscriber may have gone away, the machine carries on regardless
+            if sender.send(status).is_err() {
+                self.status_sender = None;
+            }
+        }
+    }
+
+    fn ensure_ready(&self) -> Result<(), MachineError> {
+        if !self.connected {
+            return Err(MachineError::NotConnected(self.kind()))
+        }
+        if !self.homed {
+            return Err(MachineError::NotHomed(self.kind()))
+        }
+        Ok(())
+    }
+}
+
+impl MachineDriver for SimulatedDriver {
+    fn kind(&self) -> MachineKind {
+        MachineKind::Simulated
+    }
+
+    fn connect(&mut self) -> Result<(), MachineError> {
+        self.connected = true;
+        info!("Connected to machine. machine: {}", self.kind());
+        self.send_status(MachineStatus::Connected);
+        Ok(())
+    }
+
+    fn home(&mut self) -> Result<(), MachineError> {
+        if !self.connected {
+            return Err(MachineError::NotConnected(self.kind()))
+        }
+        self.homed = true;
+        self.send_status(MachineStatus::Homed);
+        Ok(())
+    }
+
+    fn setup_feeders(&mut self, load_out_items: &[LoadOutItem]) -> Result<(), MachineError> {
+        self.ensure_ready()?;
+
+        self.feeders = load_out_items.iter()
+            .filter(|load_out_item| !load_out_item.reference.is_empty())
+            .map(|load_out_item| (Part::new(load_out_item.manufacturer.clone(), load_out_item.mpn.clone()), load_out_item.reference.clone()))
+            .collect();
+
+        self.send_status(MachineStatus::FeedersSetUp { feeders: self.feeders.len() });
+        Ok(())
+    }
+
+    fn place(&mut self, object_path: &ObjectPath, placement: &Placement) -> Result<(), MachineError> {
+        self.ensure_ready()?;
+
+        let feeder_reference = self.feeders.get(&placement.part)
+            .ok_or_else(|| MachineError::MissingFeeder { part: placement.part.clone(), object_path: object_path.clone() })?
+            .clone();
+
+        self.send_status(MachineStatus::Placing { object_path: object_path.clone() });
+        debug!("Simulated placement. object_path: {}, feeder: '{}', x: {}, y: {}, rotation: {}", object_path, feeder_reference, placement.x, placement.y, placement.rotation);
+
+        self.placed.push(object_path.clone());
+        self.send_status(MachineStatus::Placed { object_path: object_path.clone() });
+        Ok(())
+    }
+
+    fn disconnect(&mut self) -> Result<(), MachineError> {
+        self.connected = false;
+        self.homed = false;
+        info!("Disconnected from machine. machine: {}", self.kind());
+        self.send_status(MachineStatus::Disconnected);
+        Ok(())
+    }
+
+    fn subscribe(&mut self) -> Receiver<MachineStatus> {
+        let (sender, receiver) = channel();
+        self.status_sender = Some(sender);
+        receiver
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use rust_decimal_macros::dec;
+    use pnp::load_out::LoadOutItem;
+    use pnp::object_path::ObjectPath;
+    use pnp::part::Part;
+    use pnp::pcb::PcbSide;
+    use pnp::placement::{Placement, PlacementKind};
+    use crate::driver::{MachineDriver, MachineError, MachineKind, MachineStatus};
+    use crate::simulated::SimulatedDriver;
+
+    fn build_placement(mpn: &str) -> Placement {
+        Placement {
+            ref_des: "R1".to_string(),
+            part: Part::new("RES_MFR1".to_string(), mpn.to_string()),
+            place: true,
+            pcb_side: PcbSide::Top,
+            x: dec!(10),
+            y: dec!(20),
+            rotation: dec!(90),
+            kind: PlacementKind::Component,
+        }
+    }
+
+    #[test]
+    pub fn place() -> anyhow::Result<()> {
+        // given
+        let mut driver = SimulatedDriver::default();
+        let statuses = driver.subscribe();
+        let object_path = ObjectPath::from_str("panel=1::unit=1::ref_des=R1")?;
+
+        // when
+        driver.connect()?;
+        driver.home()?;
+        driver.setup_feeders(&[
+            LoadOutItem::new("FEEDER_1".to_string(), "RES_MFR1".to_string(), "RES1".to_string()),
+            LoadOutItem::new("".to_string(), "RES_MFR1".to_string(), "RES2".to_string()),
+        ])?;
+        driver.place(&object_path, &build_placement("RES1"))?;
+
+        // then
+        assert_eq!(driver.placed, vec![object_path.clone()]);
+        assert_eq!(statuses.try_iter().collect::<Vec<_>>(), vec![
+            MachineStatus::Connected,
+            MachineStatus::Homed,
+            MachineStatus::FeedersSetUp { feeders: 1 },
+            MachineStatus::Placing { object_path: object_path.clone() },
+            MachineStatus::Placed { object_path: object_path.clone() },
+        ]);
+
+        // and the part without a feeder cannot be placed
+        assert_eq!(driver.place(&object_path, &build_placement("RES2")), Err(MachineError::MissingFeeder {
+            part: Part::new("RES_MFR1".to_string(), "RES2".to_string()),
+            object_path,
+        }));
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn place_requires_homing() -> anyhow::Result<()> {
+        // given
+        let mut driver = SimulatedDriver::default();
+        driver.connect()?;
+
+        // when
+        let result = driver.place(&ObjectPath::from_str("panel=1::unit=1::ref_des=R1")?, &build_placement("RES1"));
+
+        // then
+        assert_eq!(result, Err(MachineError::NotHomed(MachineKind::Simulated)));
+
+        Ok(())
+    }
+}
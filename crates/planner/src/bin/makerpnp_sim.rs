@@ -0,0 +1,251 @@
+//! A simulated pick-and-place machine, for demonstrating the workflow and for end-to-end testing without hardware.
+//!
+//! Consumes the phase placements generated by the planner and writes a machine-style log, one record per pick attempt.
+//! Picks fail at random, using the attrition rate, a failed pick is retried and the placement is skipped after the
+//! retries are exhausted, placements without a feeder are skipped.
+//!
+//! The same seed always produces the same log.
+
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+use anyhow::{bail, Context};
+use clap::Parser;
+use clap_verbosity_flag::{InfoLevel, Verbosity};
+use csv::QuoteStyle;
+use rust_decimal::Decimal;
+use tracing::{info, trace};
+
+#[derive(Parser)]
+#[command(name = "makerpnp_sim")]
+#[command(bin_name = "makerpnp_sim")]
+#[command(version, about, long_about = None)]
+struct Opts {
+    /// Trace log file
+    #[arg(long, num_args = 0..=1, default_missing_value = "trace.log")]
+    trace: Option<PathBuf>,
+
+    /// Phase placements file, as generated by the planner (e.g. 'top_1_placements.csv')
+    #[arg(long, value_name = "FILE")]
+    placements: PathBuf,
+
+    /// Machine log file to write
+    #[arg(long, value_name = "FILE")]
+    log: PathBuf,
+
+    /// Probability of a pick failing, from 0 to 1
+    #[arg(long, default_value_t = 0.02)]
+    attrition: f64,
+
+    /// Amount of times a failed pick is retried before the placement is skipped
+    #[arg(long, default_value_t = 2)]
+    retries: u32,
+
+    /// Seed for the random attrition, a random seed is used if not specified
+    #[arg(long)]
+    seed: Option<u64>,
+
+    #[command(flatten)]
+    verbose: Verbosity<InfoLevel>,
+}
+
+/// The columns of the phase placements file that the machine uses.
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all(deserialize = "PascalCase"))]
+struct MachinePlacementRecord {
+    object_path: String,
+    feeder_reference: String,
+    manufacturer: String,
+    mpn: String,
+    x: Decimal,
+    y: Decimal,
+    rotation: Decimal,
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all(serialize = "PascalCase"))]
+struct MachineLogRecord {
+    sequence: usize,
+    object_path: String,
+    feeder_reference: String,
+    manufacturer: String,
+    mpn: String,
+    x: Decimal,
+    y: Decimal,
+    rotation: Decimal,
+    attempt: u32,
+    result: MachineLogResult,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+enum MachineLogResult {
+    Placed,
+    PickFailed,
+    Skipped,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct SimulationSummary {
+    placed: usize,
+    skipped: usize,
+    pick_failures: usize,
+}
+
+impl Display for SimulationSummary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "placed: {}, skipped: {}, pick_failures: {}", self.placed, self.skipped, self.pick_failures)
+    }
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = argfile::expand_args(
+        argfile::parse_fromfile,
+        argfile::PREFIX,
+    ).unwrap();
+
+    let opts = Opts::parse_from(args);
+
+    cli::tracing::configure_tracing(opts.trace, opts.verbose)?;
+
+    if !(0.0..=1.0).contains(&opts.attrition) {
+        bail!("Attrition must be from 0 to 1. attrition: {}", opts.attrition)
+    }
+
+    let seed = opts.seed.unwrap_or_else(|| fastrand::u64(..));
+    info!("Simulating machine. placements: {:?}, attrition: {}, retries: {}, seed: {}", opts.placements, opts.attrition, opts.retries, seed);
+
+    let mut csv_reader = csv::ReaderBuilder::new().from_path(&opts.placements)
+        .with_context(|| format!("Error reading placements. file: {:?}", opts.placements))?;
+
+    let placements = csv_reader.deserialize()
+        .collect::<Result<Vec<MachinePlacementRecord>, _>>()
+        .with_context(|| format!("Error reading placements. file: {:?}", opts.placements))?;
+
+    let mut rng = fastrand::Rng::with_seed(seed);
+    let (log_records, summary) = simulate(&placements, opts.attrition, opts.retries, &mut rng);
+
+    let mut writer = csv::WriterBuilder::new()
+        .quote_style(QuoteStyle::Always)
+        .from_path(&opts.log)
+        .with_context(|| format!("Error writing machine log. file: {:?}", opts.log))?;
+
+    for log_record in log_records.iter() {
+        writer.serialize(log_record)?;
+    }
+    writer.flush()?;
+
+    info!("Simulation complete. {}, log: {:?}", summary, opts.log);
+
+    Ok(())
+}
+
+fn simulate(placements: &[MachinePlacementRecord], attrition: f64, retries: u32, rng: &mut fastrand::Rng) -> (Vec<MachineLogRecord>, SimulationSummary) {
+    let mut log_records = vec![];
+    let mut summary = SimulationSummary::default();
+
+    for (index, placement) in placements.iter().enumerate() {
+        let mut log = |attempt: u32, result: MachineLogResult| {
+            trace!("Machine event. object_path: '{}', attempt: {}, result: {:?}", placement.object_path, attempt, result);
+
+            log_records.push(MachineLogRecord {
+                sequence: index + 1,
+                object_path: placement.object_path.clone(),
+                feeder_reference: placement.feeder_reference.clone(),
+                manufacturer: placement.manufacturer.clone(),
+                mpn: placement.mpn.clone(),
+                x: placement.x,
+                y: placement.y,
+                rotation: placement.rotation,
+                attempt,
+                result,
+            });
+        };
+
+        if placement.feeder_reference.is_empty() {
+            log(0, MachineLogResult::Skipped);
+            summary.skipped += 1;
+            continue
+        }
+
+        let placed = (1..=retries + 1).any(|attempt| {
+            if rng.f64() < attrition {
+                log(attempt, MachineLogResult::PickFailed);
+                summary.pick_failures += 1;
+                false
+            } else {
+                log(attempt, MachineLogResult::Placed);
+                true
+            }
+        });
+
+        if placed {
+            summary.placed += 1;
+        } else {
+            log(retries + 1, MachineLogResult::Skipped);
+            summary.skipped += 1;
+        }
+    }
+
+    (log_records, summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+    use crate::{simulate, MachineLogResult, MachinePlacementRecord, SimulationSummary};
+
+    fn build_placement(object_path: &str, feeder_reference: &str) -> MachinePlacementRecord {
+        MachinePlacementRecord {
+            object_path: object_path.to_string(),
+            feeder_reference: feeder_reference.to_string(),
+            manufacturer: "RES_MFR1".to_string(),
+            mpn: "RES1".to_string(),
+            x: dec!(10),
+            y: dec!(20),
+            rotation: dec!(90),
+        }
+    }
+
+    #[test]
+    pub fn skips_after_retries() {
+        // given
+        let placements = vec![
+            build_placement("panel=1::unit=1::ref_des=R1", "FEEDER_1"),
+            build_placement("panel=1::unit=1::ref_des=R2", ""),
+        ];
+        let mut rng = fastrand::Rng::with_seed(1);
+
+        // when
+        let (log_records, summary) = simulate(&placements, 1.0, 2, &mut rng);
+
+        // then
+        let results: Vec<(usize, u32, MachineLogResult)> = log_records.iter()
+            .map(|record| (record.sequence, record.attempt, record.result))
+            .collect();
+        assert_eq!(results, vec![
+            (1, 1, MachineLogResult::PickFailed),
+            (1, 2, MachineLogResult::PickFailed),
+            (1, 3, MachineLogResult::PickFailed),
+            (1, 3, MachineLogResult::Skipped),
+            (2, 0, MachineLogResult::Skipped),
+        ]);
+
+        // and
+        assert_eq!(summary, SimulationSummary { placed: 0, skipped: 2, pick_failures: 3 });
+    }
+
+    #[test]
+    pub fn same_seed_same_log() {
+        // given
+        let placements: Vec<MachinePlacementRecord> = (1..=20)
+            .map(|index| build_placement(&format!("panel=1::unit=1::ref_des=R{}", index), "FEEDER_1"))
+            .collect();
+
+        // when
+        let (_log_records, summary_1) = simulate(&placements, 0.5, 0, &mut fastrand::Rng::with_seed(42));
+        let (_log_records, summary_2) = simulate(&placements, 0.5, 0, &mut fastrand::Rng::with_seed(42));
+
+        // then
+        assert_eq!(summary_1, summary_2);
+        assert_eq!(summary_1.placed + summary_1.skipped, 20);
+    }
+}
@@ -0,0 +1,265 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::{Arc, Condvar, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+use anyhow::anyhow;
+use tracing::{debug, info, trace, warn};
+use planning::project;
+use planning::project::ArtifactKind;
+use planning::report::render::ReportFormat;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Each connection is handled by its own thread, the event streams are long-lived, so the connections are limited.
+const MAX_CONNECTIONS: usize = 32;
+
+const DASHBOARD_HTML: &str = include_str!("dashboard.html");
+
+/// The status, built once each time the project file is modified, and shared by all the connections.
+struct SharedStatus {
+    status: Mutex<Arc<StatusUpdate>>,
+    changed: Condvar,
+}
+
+struct StatusUpdate {
+    /// Incremented each time the status is built.
+    generation: u64,
+    /// The report, or the reason it could not be built.
+    status: Result<Vec<u8>, String>,
+}
+
+impl SharedStatus {
+    fn new(status: Result<Vec<u8>, String>) -> Self {
+        Self {
+            status: Mutex::new(Arc::new(StatusUpdate { generation: 0, status })),
+            changed: Condvar::new(),
+        }
+    }
+
+    fn latest(&self) -> Arc<StatusUpdate> {
+        self.status.lock().unwrap().clone()
+    }
+
+    fn publish(&self, status: Result<Vec<u8>, String>) {
+        let mut latest = self.status.lock().unwrap();
+        *latest = Arc::new(StatusUpdate { generation: latest.generation + 1, status });
+        self.changed.notify_all();
+    }
+
+    /// Waits for a status newer than the given generation, `None` if there is none before the timeout.
+    fn wait_for_update(&self, generation: Option<u64>, timeout: Duration) -> Option<Arc<StatusUpdate>> {
+        let latest = self.status.lock().unwrap();
+        let (latest, _result) = self.changed.wait_timeout_while(latest, timeout, |latest| generation.eq(&Some(latest.generation))).unwrap();
+
+        match generation.eq(&Some(latest.generation)) {
+            true => None,
+            false => Some(latest.clone()),
+        }
+    }
+}
+
+/// Decrements the number of connections when the connection is closed.
+struct ConnectionGuard(Arc<AtomicUsize>);
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Serves the dashboard until the process is terminated.
+pub fn serve(listen: SocketAddr, project_name: &str, project_file_path: &Path, path: &Path) -> anyhow::Result<()> {
+    let listener = TcpListener::bind(listen)?;
+
+    let shared_status = Arc::new(SharedStatus::new(build_status(project_name, project_file_path, path).map_err(|reason| reason.to_string())));
+    {
+        let shared_status = shared_status.clone();
+        let project_name = project_name.to_string();
+        let project_file_path = project_file_path.to_path_buf();
+        let path = path.to_path_buf();
+
+        thread::spawn(move || watch_project(&shared_status, &project_name, &project_file_path, &path));
+    }
+
+    info!("Serving dashboard. address: 'http://{}'", listener.local_addr()?);
+
+    let connections = Arc::new(AtomicUsize::new(0));
+
+    for stream in listener.incoming() {
+        let mut stream = match stream {
+            Ok(stream) => stream,
+            Err(reason) => {
+                warn!("Unable to accept dashboard connection. cause: {}", reason);
+                continue
+            }
+        };
+
+        if connections.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+            connections.fetch_sub(1, Ordering::SeqCst);
+            warn!("Too many dashboard connections. max_connections: {}", MAX_CONNECTIONS);
+            let _ = write_response(&mut stream, "503 Service Unavailable", "text/plain", b"Too many connections\n");
+            continue
+        }
+        let connection = ConnectionGuard(connections.clone());
+
+        let shared_status = shared_status.clone();
+
+        thread::spawn(move || {
+            let _connection = connection;
+            if let Err(reason) = handle_connection(stream, &shared_status) {
+                debug!("Dashboard connection closed. cause: {}", reason);
+            }
+        });
+    }
+
+    Ok(())
+}
+
+/// Rebuilds the status each time the project file is modified.
+fn watch_project(shared_status: &SharedStatus, project_name: &str, project_file_path: &Path, path: &Path) {
+    let mut last_modified = project_file_path.metadata().and_then(|metadata| metadata.modified()).ok();
+
+    loop {
+        thread::sleep(POLL_INTERVAL);
+
+        let modified = project_file_path.metadata().and_then(|metadata| metadata.modified()).ok();
+        if last_modified.eq(&modified) {
+            continue
+        }
+        last_modified = modified;
+
+        trace!("Project modified, building dashboard status.");
+        shared_status.publish(build_status(project_name, project_file_path, path).map_err(|reason| reason.to_string()));
+    }
+}
+
+fn handle_connection(mut stream: TcpStream, shared_status: &SharedStatus) -> anyhow::Result<()> {
+    // so that a client that stops sending or receiving does not hold a connection
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    stream.set_write_timeout(Some(REQUEST_TIMEOUT))?;
+
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+
+    // the headers are not used, but they must be read before responding
+    let mut header_line = String::new();
+    while reader.read_line(&mut header_line)? > 0 && !header_line.trim().is_empty() {
+        header_line.clear();
+    }
+
+    trace!("Dashboard request. request: '{}'", request_line.trim());
+
+    let mut parts = request_line.split_whitespace();
+    let (method, target) = (parts.next().unwrap_or_default(), parts.next().unwrap_or_default());
+
+    if method != "GET" {
+        return write_response(&mut stream, "405 Method Not Allowed", "text/plain", b"Method not allowed\n");
+    }
+
+    match build_request_path(target) {
+        "/" => write_response(&mut stream, "200 OK", "text/html; charset=utf-8", DASHBOARD_HTML.as_bytes()),
+        "/status" => match &shared_status.latest().status {
+            Ok(status) => write_response(&mut stream, "200 OK", "application/json", status),
+            Err(reason) => write_response(&mut stream, "500 Internal Server Error", "text/plain", format!("{}\n", reason).as_bytes()),
+        },
+        "/events" => stream_events(&mut stream, shared_status),
+        _ => write_response(&mut stream, "404 Not Found", "text/plain", b"Not found\n"),
+    }
+}
+
+/// The path of the request target, without the query, e.g. '/events?token=1' -> '/events'.
+fn build_request_path(target: &str) -> &str {
+    target.split_once('?')
+        .map_or(target, |(path, _query)| path)
+}
+
+fn write_response(stream: &mut TcpStream, status: &str, content_type: &str, body: &[u8]) -> anyhow::Result<()> {
+    write!(stream, "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", status, content_type, body.len())?;
+    stream.write_all(body)?;
+    stream.flush()?;
+    Ok(())
+}
+
+/// Sends a server-sent event with the status each time the project file is modified.
+fn stream_events(stream: &mut TcpStream, shared_status: &SharedStatus) -> anyhow::Result<()> {
+    write!(stream, "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n")?;
+    stream.flush()?;
+
+    let mut generation = None;
+
+    loop {
+        match shared_status.wait_for_update(generation, KEEP_ALIVE_INTERVAL) {
+            Some(update) => {
+                generation = Some(update.generation);
+
+                let event = match &update.status {
+                    Ok(status) => format_event("status", &String::from_utf8_lossy(status)),
+                    Err(reason) => format_event("failure", reason),
+                };
+                stream.write_all(event.as_bytes())?;
+            },
+            None => {
+                // a comment, so that disconnected clients are detected
+                stream.write_all(b": keep-alive\n\n")?;
+            },
+        }
+        stream.flush()?;
+    }
+}
+
+fn format_event(event: &str, data: &str) -> String {
+    let mut message = format!("event: {}\n", event);
+    for line in data.lines() {
+        message.push_str(&format!("data: {}\n", line));
+    }
+    message.push('\n');
+    message
+}
+
+/// Builds the same report that `generate-artifacts` writes, using the current project file.
+fn build_status(project_name: &str, project_file_path: &Path, path: &Path) -> anyhow::Result<Vec<u8>> {
+    let mut project = project::load(&project_file_path.to_path_buf())?;
+
+    let _modified = project::update_phase_operation_states(&mut project);
+
+    let phase_load_out_item_map = crate::load_phase_load_out_items(&project, path)?;
+
+    let price_list = crate::load_price_list(&project, path)?;
+    let inventory = crate::load_inventory(&project, path)?;
+
+    let artifacts = project::build_artifacts(&project, project_name, &phase_load_out_item_map, price_list.as_ref(), inventory.as_ref(), ReportFormat::Json)?;
+
+    artifacts.into_iter()
+        .find(|artifact| matches!(artifact.kind, ArtifactKind::Report))
+        .map(|artifact| artifact.content)
+        .ok_or(anyhow!("No report artifact"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dashboard::{build_request_path, format_event};
+
+    #[test]
+    pub fn format_multi_line_event() {
+        // when
+        let event = format_event("status", "{\n    \"name\": \"job1\"\n}");
+
+        // then
+        assert_eq!(event, "event: status\ndata: {\ndata:     \"name\": \"job1\"\ndata: }\n\n");
+    }
+
+    #[test]
+    pub fn request_path_without_query() {
+        // expect
+        assert_eq!(build_request_path("/events?token=1"), "/events");
+        assert_eq!(build_request_path("/status"), "/status");
+        assert_eq!(build_request_path("/?"), "/");
+    }
+}
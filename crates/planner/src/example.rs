@@ -0,0 +1,251 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use csv::QuoteStyle;
+use regex::Regex;
+use rust_decimal::Decimal;
+use tracing::info;
+use planning::design::{DesignName, DesignVariant};
+use planning::placement::{PlacementSortingItem, PlacementSortingMode};
+use planning::project;
+use planning::project::{ProcessFactory, Project};
+use planning::reference::Reference;
+use planning::progress::NoProgress;
+use planning::variant::VariantName;
+use pnp::load_out::LoadOutItem;
+use pnp::object_path::ObjectPath;
+use pnp::pcb::{PcbKind, PcbSide};
+use pnp::placement::PlacementKind;
+use stores::load_out::LoadOutSource;
+use stores::placements::{PlacementRecord, PlacementRecordPcbSide};
+use util::sorting::SortOrder;
+
+const DESIGN: &str = "design_a";
+const VARIANT: &str = "variant_a";
+const PANEL: &str = "panel_a";
+const UNITS: [&str; 2] = ["panel=1::unit=1", "panel=1::unit=2"];
+
+const EDA_PLACEMENTS_FILE: &str = "design_a_kicad_placements.csv";
+const PARTS_FILE: &str = "parts.csv";
+const PART_MAPPINGS_FILE: &str = "part_mappings.csv";
+const SCRIPT_FILE: &str = "example.sh";
+
+struct ExampleComponent {
+    ref_des: &'static str,
+    package: &'static str,
+    val: &'static str,
+    manufacturer: &'static str,
+    mpn: &'static str,
+    pcb_side: PcbSide,
+    x: i64,
+    y: i64,
+    rotation: i64,
+}
+
+const COMPONENTS: [ExampleComponent; 4] = [
+    ExampleComponent { ref_des: "R1", package: "R_0402_1005Metric", val: "330R", manufacturer: "RES_MFR1", mpn: "RES1", pcb_side: PcbSide::Top, x: 10, y: 10, rotation: 0 },
+    ExampleComponent { ref_des: "R2", package: "R_0402_1005Metric", val: "10K", manufacturer: "RES_MFR1", mpn: "RES2", pcb_side: PcbSide::Top, x: 20, y: 10, rotation: 90 },
+    ExampleComponent { ref_des: "C1", package: "C_0402_1005Metric", val: "100nF", manufacturer: "CAP_MFR1", mpn: "CAP1", pcb_side: PcbSide::Top, x: 10, y: 20, rotation: -90 },
+    ExampleComponent { ref_des: "J1", package: "PinHeader_1x02_P2.54mm", val: "CONN", manufacturer: "CONN_MFR1", mpn: "CONN1", pcb_side: PcbSide::Bottom, x: 30, y: 20, rotation: 180 },
+];
+
+struct ExamplePhase {
+    reference: &'static str,
+    process: &'static str,
+    load_out: &'static str,
+    pcb_side: PcbSide,
+    pcb_side_arg: &'static str,
+    manufacturer_pattern: &'static str,
+}
+
+const PHASES: [ExamplePhase; 2] = [
+    ExamplePhase { reference: "top_1", process: "pnp", load_out: "load_out_top_1.csv", pcb_side: PcbSide::Top, pcb_side_arg: "top", manufacturer_pattern: "RES_MFR.*|CAP_MFR.*" },
+    ExamplePhase { reference: "bottom_1", process: "manual", load_out: "load_out_bottom_1.csv", pcb_side: PcbSide::Bottom, pcb_side_arg: "bottom", manufacturer_pattern: "CONN_MFR.*" },
+];
+
+pub fn generate(project_name: &str, into: &PathBuf) -> anyhow::Result<()> {
+    fs::create_dir_all(into)?;
+
+    write_eda_placements(&into.join(EDA_PLACEMENTS_FILE))?;
+    write_parts(&into.join(PARTS_FILE))?;
+    write_part_mappings(&into.join(PART_MAPPINGS_FILE))?;
+    write_variant_placements(&into.join(format!("{}_{}_placements.csv", DESIGN, VARIANT)))?;
+
+    let project = build_project(project_name, into)?;
+    project::save(&project, &project::build_project_file_path(project_name, into))?;
+
+    fs::write(into.join(SCRIPT_FILE), build_script(project_name))?;
+
+    info!("Generated example. project: '{}', path: {:?}", project_name, into);
+
+    Ok(())
+}
+
+fn build_writer(path: &Path) -> anyhow::Result<csv::Writer<fs::File>> {
+    Ok(csv::WriterBuilder::new()
+        .quote_style(QuoteStyle::Always)
+        .from_path(path)?)
+}
+
+fn write_eda_placements(path: &Path) -> anyhow::Result<()> {
+    let mut writer = build_writer(path)?;
+    writer.write_record(["ref", "Package", "Val", "Side", "X", "Y", "Rotation"])?;
+    for component in COMPONENTS.iter() {
+        let side = match component.pcb_side {
+            PcbSide::Top => "top",
+            PcbSide::Bottom => "bottom",
+        };
+        writer.write_record([
+            component.ref_des, component.package, component.val, side,
+            &component.x.to_string(), &component.y.to_string(), &component.rotation.to_string(),
+        ])?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn write_parts(path: &Path) -> anyhow::Result<()> {
+    let mut writer = build_writer(path)?;
+    writer.write_record(["Manufacturer", "Mpn"])?;
+    for component in COMPONENTS.iter() {
+        writer.write_record([component.manufacturer, component.mpn])?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn write_part_mappings(path: &Path) -> anyhow::Result<()> {
+    let mut writer = build_writer(path)?;
+    writer.write_record(["Eda", "Package", "Val", "Manufacturer", "Mpn"])?;
+    for component in COMPONENTS.iter() {
+        writer.write_record(["KiCad", component.package, component.val, component.manufacturer, component.mpn])?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// The same content that `variantbuilder build` produces from the EDA placements, parts and part mappings.
+fn write_variant_placements(path: &Path) -> anyhow::Result<()> {
+    let mut writer = build_writer(path)?;
+    for component in COMPONENTS.iter() {
+        writer.serialize(PlacementRecord {
+            ref_des: component.ref_des.to_string(),
+            manufacturer: component.manufacturer.to_string(),
+            mpn: component.mpn.to_string(),
+            place: true,
+            pcb_side: PlacementRecordPcbSide::from(&component.pcb_side),
+            x: Decimal::from(component.x),
+            y: Decimal::from(component.y),
+            rotation: Decimal::from(component.rotation),
+            kind: PlacementKind::Component,
+        })?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn build_project(project_name: &str, into: &Path) -> anyhow::Result<Project> {
+    let mut project = Project::new(project_name.to_string());
+
+    project::add_pcb(&mut project, PcbKind::Panel, PANEL.to_string())?;
+
+    let design_variant = DesignVariant {
+        design_name: DesignName::from_str(DESIGN)?,
+        variant_name: VariantName::from_str(VARIANT)?,
+    };
+    for unit in UNITS.iter() {
+        project.update_assignment(ObjectPath::from_str(unit)?, design_variant.clone())?;
+    }
+
+    let all_parts = crate::refresh_from_design_variants(&mut project, into, false, &NoProgress)?;
+
+    let any_pattern = Regex::new(".*")?;
+    let manufacturer_patterns = PHASES.iter()
+        .map(|example_phase| Regex::new(example_phase.manufacturer_pattern))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for (example_phase, manufacturer_pattern) in PHASES.iter().zip(manufacturer_patterns) {
+        let process = ProcessFactory::by_name(example_phase.process)?;
+        project::update_applicable_processes(&mut project, all_parts.as_slice(), process.clone(), manufacturer_pattern, any_pattern.clone());
+
+        let reference = Reference::from_str(example_phase.reference)?;
+        project.update_phase(reference.clone(), process.name.clone(), example_phase.load_out.to_string(), example_phase.pcb_side.clone())?;
+
+        let phase = project.phases.get(&reference).unwrap().clone();
+        let parts = project::assign_placements_to_phase(&mut project, &phase, any_pattern.clone(), false, false)?;
+
+        // feeders are assigned in part order, e.g. 'FEEDER_1', 'FEEDER_2'
+        let load_out_items: Vec<LoadOutItem> = parts.iter().enumerate().map(|(index, part)| {
+            LoadOutItem::new(format!("FEEDER_{}", index + 1), part.manufacturer.clone(), part.mpn.clone())
+        }).collect();
+
+        let load_out_source = LoadOutSource::from_str(into.join(example_phase.load_out).to_str().unwrap())?;
+        stores::load_out::store_items(&load_out_source, &load_out_items)?;
+    }
+
+    let placement_orderings = vec![
+        PlacementSortingItem { mode: PlacementSortingMode::PcbUnit, sort_order: SortOrder::Asc },
+        PlacementSortingItem { mode: PlacementSortingMode::FeederReference, sort_order: SortOrder::Asc },
+    ];
+    project::update_placement_orderings(&mut project, &Reference::from_str(PHASES[0].reference)?, &placement_orderings)?;
+
+    let _modified = project::update_phase_operation_states(&mut project);
+
+    Ok(project)
+}
+
+/// Builds a script of the commands that re-create the example project from the EDA files.
+fn build_script(project_name: &str) -> String {
+    let mut commands: Vec<String> = vec![];
+
+    commands.push(format!(
+        "variantbuilder build --eda kicad --placements {} --parts {} --part-mappings {} --output {}_{}_placements.csv",
+        EDA_PLACEMENTS_FILE, PARTS_FILE, PART_MAPPINGS_FILE, DESIGN, VARIANT,
+    ));
+
+    let planner = format!("planner --project {}", project_name);
+
+    commands.push(format!("{} create", planner));
+    commands.push(format!("{} add-pcb --kind panel --name {}", planner, PANEL));
+    for unit in UNITS.iter() {
+        commands.push(format!("{} assign-variant-to-unit --design {} --variant {} --unit {}", planner, DESIGN, VARIANT, unit));
+    }
+    for example_phase in PHASES.iter() {
+        commands.push(format!("{} assign-process-to-parts --process {} --manufacturer '{}' --mpn '.*'", planner, example_phase.process, example_phase.manufacturer_pattern));
+    }
+    for example_phase in PHASES.iter() {
+        commands.push(format!("{} create-phase --process {} --reference {} --load-out {} --pcb-side {}", planner, example_phase.process, example_phase.reference, example_phase.load_out, example_phase.pcb_side_arg));
+        commands.push(format!("{} assign-placements-to-phase --phase {} --placements '.*'", planner, example_phase.reference));
+    }
+
+    let mut feeder_assignments: BTreeMap<&str, Vec<(&str, &str)>> = BTreeMap::new();
+    for example_phase in PHASES.iter() {
+        let mut parts: Vec<(&str, &str)> = COMPONENTS.iter()
+            .filter(|component| component.pcb_side == example_phase.pcb_side)
+            .map(|component| (component.manufacturer, component.mpn))
+            .collect();
+        parts.sort();
+        parts.dedup();
+        feeder_assignments.insert(example_phase.reference, parts);
+    }
+    for example_phase in PHASES.iter() {
+        for (index, (manufacturer, mpn)) in feeder_assignments.get(example_phase.reference).unwrap().iter().enumerate() {
+            commands.push(format!("{} assign-feeder-to-load-out-item --phase {} --feeder-reference FEEDER_{} --manufacturer '^{}$' --mpn '^{}$'", planner, example_phase.reference, index + 1, manufacturer, mpn));
+        }
+    }
+
+    commands.push(format!("{} set-placement-ordering --phase {} --placement-orderings PCB_UNIT:ASC,FEEDER_REFERENCE:ASC", planner, PHASES[0].reference));
+    commands.push(format!("{} generate-artifacts", planner));
+
+    let mut script = String::new();
+    script.push_str("#!/bin/sh\n");
+    script.push_str("# Re-creates the example project, run from the directory containing this script.\n");
+    script.push_str("set -e\n\n");
+    for command in commands {
+        script.push_str(&command);
+        script.push('\n');
+    }
+
+    script
+}
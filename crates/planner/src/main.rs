@@ -1,23 +1,98 @@
-use std::collections::BTreeMap;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, BTreeSet};
+use std::collections::btree_map::Entry;
+use std::io::{IsTerminal, Write};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
-use clap::{Parser, Subcommand, ArgGroup};
+use std::time::{Duration, SystemTime};
+use clap::{ArgGroup, CommandFactory, FromArgMatches, Parser, Subcommand};
 use clap_verbosity_flag::{InfoLevel, Verbosity};
+use anyhow::bail;
 use regex::Regex;
-use tracing::{info, trace};
-use {cli, planning};
-use cli::args::{PcbKindArg, PcbSideArg, PlacementOperationArg, ProcessOperationArg, ProcessOperationSetArg};
+use time::OffsetDateTime;
+use tracing::{debug, error, info, trace, warn};
+use cli::args::{AnalyticsFormatArg, ArtifactTypeArg, BomFormatArg, DiffFormatArg, ExportFormatArg, MachineKindArg, MslLevelArg, OperationTransitionsArg, PcbKindArg, PcbSideArg, PlacementOperationArg, PlacementOverrideArg, PreferenceKeyArg, ProcessOperationSetArg, QuantityCheckModeArg, ReportFormatArg, RotationRangeArg, WorkInstructionsStyleArg};
+use cli::tracing::{JsonLog, Mutations};
 use planning::design::{DesignName, DesignVariant};
 use planning::reference::Reference;
-use planning::placement::PlacementSortingItem;
-use planning::process::ProcessName;
-use planning::project::{PartStateError, ProcessFactory, Project};
+use planning::placement::{ObjectPathMatcher, PlacementOperation, PlacementSortingItem, RotationNormalization};
+use planning::process::{ProcessName, ProcessOperationKind};
+use planning::project::{PartPlacementCounts, PartStateError, ProcessFactory, Project};
+use planning::locking;
+use planning::locking::{LockMode, ProjectLock};
 use planning::project;
-use planning::phase::PhaseError;
+use planning::phase::{Phase, PhaseError, PhaseTag};
+use planning::signing;
+use planning::health;
+use planning::progress::{NoProgress, ProgressReporter};
+use planning::search;
+use planning::status;
+use planning::checklist;
+use planning::first_article;
+use planning::nozzle::{Nozzle, NozzleConfiguration};
+use planning::quantity_check;
+use planning::export;
+use planning::quantity_check::QuantityCheckMode;
+use planning::load_out_reuse;
+use planning::analytics;
+use planning::analytics::AnalyticsFormat;
+use planning::audit;
+use planning::audit::AuditLogItem;
+use planning::mutation::MUTATION_TARGET;
+use planning::pricing::PriceList;
+use planning::inventory::Inventory;
+use planning::estimation::{EstimationParameters, FormattedDuration, PlacementRate};
+use planning::bom;
+use planning::bom::BomFormat;
+use planning::certificate;
+use planning::issue;
+use planning::issue::IssueResolutionStatus;
+use planning::operation_history;
+use planning::operation_history::{OperationHistoryFilter, OperationHistoryItem, OperationHistoryMetrics};
+use planning::release;
+use planning::production_run;
+use planning::moisture::{MoistureSensitivity, MslLevel};
 use planning::variant::VariantName;
+use planning::report::render::ReportFormat;
 use pnp::load_out::LoadOutItem;
-use pnp::object_path::ObjectPath;
-use stores::load_out::LoadOutSource;
+use pnp::object_path::{ObjectPath, ObjectPathPattern};
+use pnp::part::{Part, PartDetails};
+use pnp::pcb::{Fiducial, PanelGeometry, PanelUnit, PcbDimensions};
+use pnp::placement::Placement;
+use rust_decimal::Decimal;
+use stores::load_out::{FeederAssignmentError, LoadOutSource};
+use stores::part_rename;
+use stores::part_rename::{FileChange, RecordChange};
+use stores::backup::{CleanupSummary, RetentionPolicy};
+use stores::preferences;
+use stores::preferences::PreferenceKey;
+
+/// A miniature example, a panel with two units of a single design variant, built with two phases.
+///
+/// Everything is produced using the same code the commands use, so the generated files always match the current
+/// schema.
+mod example;
+
+/// A read-only web page of the project progress, for shop-floor displays.
+mod dashboard;
+
+/// A static site of the project report, for customer deliverables.
+mod report_site;
+
+/// Refreshes the project when the design variant placements are re-exported from the EDA tool.
+mod watch;
+
+/// Records production activities from barcode scans.
+mod scan;
+
+/// Progress bars for long-running operations.
+mod progress;
+
+/// Runs the placements of a phase on a pick-and-place machine.
+mod run_phase;
+
+/// The directory, in the project directory, that release snapshots are written to.
+const RELEASES_DIRECTORY: &str = "releases";
 
 #[derive(Parser)]
 #[command(name = "planner")]
@@ -36,6 +111,10 @@ struct Opts {
     #[arg(long, num_args = 0..=1, default_missing_value = "trace.log")]
     trace: Option<PathBuf>,
 
+    /// Structured log file, JSON lines, appended to with an object for each change to the project
+    #[arg(long, value_name = "FILE")]
+    log_json: Option<PathBuf>,
+
     /// Path
     #[arg(long, default_value = ".")]
     path: PathBuf,
@@ -55,6 +134,22 @@ enum Command {
     /// Create a new job
     Create {
     },
+    /// Clone the project for a repeat job, without any recorded operations
+    CloneProject {
+        /// Name of the new project
+        #[arg(long)]
+        name: String,
+
+        /// Directory for the new project, the design variant placements and load-outs are copied into it [default: the project directory]
+        #[arg(long)]
+        into: Option<PathBuf>,
+    },
+    /// Compress the project file, for very large projects, or decompress it
+    CompressProject {
+        /// Decompress the project file instead
+        #[arg(long)]
+        decompress: bool,
+    },
     /// Add a PCB
     AddPcb {
         /// PCB kind
@@ -65,6 +160,44 @@ enum Command {
         #[arg(long)]
         name: String,
     },
+    /// Set the positions of the units and fiducials of a panel, placements are transformed into panel coordinates
+    SetPanelGeometry {
+        /// Name of the panel PCB
+        #[arg(long, value_name = "PCB_NAME")]
+        pcb: String,
+
+        /// Position of the origin of a unit, and its anti-clockwise rotation, e.g. '2,55.5,0,90'
+        #[arg(long = "unit", required = true, value_parser = clap::value_parser!(PanelUnit), value_name = "UNIT,X,Y,ROTATION")]
+        units: Vec<PanelUnit>,
+
+        /// Position of a fiducial, e.g. 'FID1,5,5'
+        #[arg(long = "fiducial", value_parser = clap::value_parser!(Fiducial), value_name = "NAME,X,Y")]
+        fiducials: Vec<Fiducial>,
+    },
+    /// Set the dimensions of a board or panel, placement coordinates are validated against them
+    SetPcbDimensions {
+        /// Name of the PCB
+        #[arg(long, value_name = "PCB_NAME")]
+        pcb: String,
+
+        /// Width, in millimeters
+        #[arg(long)]
+        width: Decimal,
+
+        /// Height, in millimeters
+        #[arg(long)]
+        height: Decimal,
+    },
+    /// Discover design variants from the placements files in the project directory
+    DiscoverVariants {
+        /// Name of the design, required to register variants when design or variant names contain underscores
+        #[arg(long, value_parser = clap::value_parser!(DesignName), value_name = "DESIGN_NAME")]
+        design: Option<DesignName>,
+
+        /// Register the discovered variants, units can then only be assigned registered variants of the design
+        #[arg(long)]
+        register: bool,
+    },
     /// Assign a design variant to a PCB unit
     AssignVariantToUnit {
         /// Name of the design
@@ -78,7 +211,18 @@ enum Command {
         /// PCB unit path
         #[arg(long, value_parser = clap::value_parser!(ObjectPath), value_name = "OBJECT_PATH")]
         unit: ObjectPath,
+
+        /// Accept conflicting placements of the design variants, placements with the same ref_des, the last of the placements is used
+        #[arg(long)]
+        force: bool,
     },
+    /// Export or import the design variant assignments of the PCB units, as CSV
+    UnitAssignments {
+        #[command(subcommand)]
+        command: UnitAssignmentsCommand,
+    },
+    /// Acknowledge changes to the design variant placements files, so that the placements can be refreshed from them
+    AcknowledgeDesignChanges {},
     /// Assign a process to parts
     AssignProcessToParts {
         /// Process name
@@ -93,9 +237,33 @@ enum Command {
         #[arg(long)]
         mpn: Regex,
     },
+    /// Set the moisture sensitivity level (MSL) of parts
+    SetMoistureSensitivity {
+        /// Manufacturer pattern (regexp)
+        #[arg(long)]
+        manufacturer: Regex,
+
+        /// Manufacturer part number (regexp)
+        #[arg(long)]
+        mpn: Regex,
+
+        /// Moisture sensitivity level, 'none' to remove
+        #[arg(long)]
+        level: MslLevelArg,
+
+        /// Floor life in hours, overrides the floor life of the level, required for level 6
+        #[arg(long)]
+        floor_life_hours: Option<u32>,
+    },
+    /// Import part details (e.g. image, datasheet, value, description, MSL) from a parts master, or part library
+    ImportPartDetails {
+        /// Parts master ('.toml'), parts file, or DipTrace BOM, relative to the project directory (e.g. 'parts.csv')
+        #[arg(long)]
+        parts: PathBuf,
+    },
     /// Create a phase
     CreatePhase {
-        /// Process name
+        /// Process name, 'pnp', 'manual' or a process defined in 'processes.json' or 'processes.toml' of the project directory
         #[arg(long)]
         process: ProcessName,
         
@@ -103,7 +271,25 @@ enum Command {
         #[arg(long)]
         reference: Reference,
         
-        /// Load-out source (e.g. 'load_out_1')
+        /// Load-out source, relative to the project directory, or a URL (e.g. 'load_out_1.csv' or 'https://example.com/load_out_1.csv')
+        #[arg(long)]
+        load_out: LoadOutSource,
+
+        /// PCB side
+        #[arg(long)]
+        pcb_side: PcbSideArg,
+
+        /// Machine-native format to export the placements in, alongside the other artifacts
+        #[arg(long)]
+        export_format: Option<ExportFormatArg>,
+    },
+    /// Create a rework phase from placements with open inspection defects
+    CreateReworkPhase {
+        /// Phase reference (e.g. 'rework_1')
+        #[arg(long)]
+        reference: Reference,
+
+        /// Load-out source, relative to the project directory, or a URL (e.g. 'load_out_1.csv' or 'https://example.com/load_out_1.csv')
         #[arg(long)]
         load_out: LoadOutSource,
 
@@ -111,6 +297,42 @@ enum Command {
         #[arg(long)]
         pcb_side: PcbSideArg,
     },
+    /// Clone a phase, the placements are not assigned to the new phase
+    ClonePhase {
+        /// Phase reference (e.g. 'top_1')
+        #[arg(long)]
+        phase: Reference,
+
+        /// New phase reference (e.g. 'top_2')
+        #[arg(long)]
+        reference: Reference,
+
+        /// Load-out source for the new phase, the load-out of the phase is copied [default: the load-out of the phase]
+        #[arg(long)]
+        load_out: Option<LoadOutSource>,
+    },
+    /// Remove a phase, its placements are unassigned
+    RemovePhase {
+        /// Phase reference (e.g. 'top_1')
+        #[arg(long)]
+        phase: Reference,
+    },
+    /// Rename a phase, the placements, dependencies, phase ordering and operation history are updated
+    RenamePhase {
+        /// Phase reference (e.g. 'top_1')
+        #[arg(long)]
+        phase: Reference,
+
+        /// New phase reference (e.g. 'top_a')
+        #[arg(long)]
+        reference: Reference,
+    },
+    /// Set the order of the phases, each phase must be given exactly once
+    ReorderPhases {
+        /// Phase references, in order (e.g. 'bottom_1,top_1')
+        #[arg(long, required = true, num_args = 1.., value_delimiter = ',')]
+        phases: Vec<Reference>,
+    },
     /// Assign placements to a phase
     AssignPlacementsToPhase {
         /// Phase reference (e.g. 'top_1')
@@ -120,6 +342,34 @@ enum Command {
         /// Placements object path pattern (regexp)
         #[arg(long)]
         placements: Regex,
+
+        /// Allow placements that are assigned to another phase to be reassigned
+        #[arg(long)]
+        allow_reassign: bool,
+
+        /// Allow placements on the other side of the PCB to the phase to be assigned, e.g. for glue-dot workflows
+        #[arg(long)]
+        allow_side_mismatch: bool,
+    },
+    /// Unassign placements from a phase
+    UnassignPlacementsFromPhase {
+        /// Phase reference (e.g. 'top_1')
+        #[arg(long)]
+        phase: Reference,
+
+        /// Placements object path pattern (regexp)
+        #[arg(long)]
+        placements: Regex,
+    },
+    /// Override whether placements are placed, for this project only, without changing the design (e.g. skip 'C7')
+    SetPlacementOverride {
+        /// List of object path patterns (regexp) of the placements to override
+        #[arg(long, required = true, num_args = 1.., value_delimiter = ',')]
+        object_path_patterns: Vec<Regex>,
+
+        /// The override
+        #[arg(long = "override", value_name = "OVERRIDE")]
+        place_override: PlacementOverrideArg,
     },
     /// Assign feeder to load-out item
     AssignFeederToLoadOutItem {
@@ -138,214 +388,2553 @@ enum Command {
         /// Manufacturer part number (regexp)
         #[arg(long)]
         mpn: Regex,
+
+        /// Quantity of parts loaded
+        #[arg(long)]
+        quantity: Option<u32>,
+
+        /// Reel reference (e.g. 'REEL_1')
+        #[arg(long)]
+        reel: Option<String>,
+
+        /// Feeder library file, to check the feeder constraints
+        #[arg(long, value_name = "FEEDERS_FILE")]
+        feeders: Option<PathBuf>,
     },
-    /// Set placement ordering for a phase
-    SetPlacementOrdering {
+    /// Set the alternate parts of a load-out item, in order of preference, that can be loaded instead of the part
+    SetLoadOutAlternates {
         /// Phase reference (e.g. 'top_1')
         #[arg(long)]
         phase: Reference,
 
-        /// Orderings (e.g. 'PCB_UNIT:ASC,FEEDER_REFERENCE:ASC')
-        #[arg(long, num_args = 0.., value_delimiter = ',', value_parser = cli::parsers::PlacementSortingItemParser::default())]
-        placement_orderings: Vec<PlacementSortingItem>
-    },
-    
-    // FUTURE consider adding a command to allow the phase ordering to be changed, currently phase ordering is determined by the order of phase creation.
-    
-    /// Generate artifacts
-    GenerateArtifacts {
+        /// Feeder reference (e.g. 'FEEDER_1')
+        #[arg(long)]
+        feeder: String,
+
+        /// Alternate part '<manufacturer>:<mpn>', repeat for each alternate, omit to remove the alternates
+        #[arg(long)]
+        alternate: Vec<Part>,
     },
-    /// Record phase operation
-    RecordPhaseOperation {
+    /// Remove the load-out item of a feeder, e.g. when the part is no-longer loaded
+    RemoveLoadOutItem {
         /// Phase reference (e.g. 'top_1')
         #[arg(long)]
         phase: Reference,
 
-        /// The operation to update
+        /// Feeder reference (e.g. 'FEEDER_1')
         #[arg(long)]
-        operation: ProcessOperationArg,
+        feeder: String,
+    },
+    /// Set the quantity of parts loaded in a feeder
+    SetLoadOutItemQuantity {
+        /// Phase reference (e.g. 'top_1')
+        #[arg(long)]
+        phase: Reference,
 
-        /// The process operation to set
+        /// Feeder reference (e.g. 'FEEDER_1')
         #[arg(long)]
-        set: ProcessOperationSetArg,
-    },   
-    /// Record placements operation
-    RecordPlacementsOperation {
-        /// List of reference designators to apply the operation to
-        #[arg(long, required = true, num_args = 1.., value_delimiter = ',')]
-        object_path_patterns: Vec<Regex>,
-        
-        /// The completed operation to apply
+        feeder: String,
+
+        /// Quantity of parts loaded, omit to stop tracking the quantity
         #[arg(long)]
-        operation: PlacementOperationArg,
+        quantity: Option<u32>,
     },
-    /// Reset operations
-    ResetOperations {
-    }
-}
+    /// Move the load-out item of a feeder to another feeder
+    RenameFeeder {
+        /// Phase reference (e.g. 'top_1')
+        #[arg(long)]
+        phase: Reference,
 
-// FUTURE consider merging the AssignProcessToParts and AssignLoadOutToParts commands
-//        consider making a group for the criteria args (manufacturer/mpn/etc).
+        /// Feeder reference (e.g. 'FEEDER_1')
+        #[arg(long)]
+        feeder: String,
 
-fn main() -> anyhow::Result<()>{
-    let args = argfile::expand_args(
-        argfile::parse_fromfile,
-        argfile::PREFIX,
-    ).unwrap();
+        /// New feeder reference (e.g. 'FEEDER_2')
+        #[arg(long)]
+        new_feeder: String,
+    },
+    /// Suggest feeders for load-out items
+    SuggestFeeders {
+        /// Phase reference (e.g. 'top_1')
+        #[arg(long)]
+        phase: Reference,
 
-    let opts = Opts::parse_from(args);
+        /// Manufacturer pattern (regexp)
+        #[arg(long)]
+        manufacturer: Regex,
 
-    cli::tracing::configure_tracing(opts.trace, opts.verbose)?;
+        /// Manufacturer part number (regexp)
+        #[arg(long)]
+        mpn: Regex,
 
-    let project_name = &opts.project.unwrap();
-    let project_file_path = project::build_project_file_path(&project_name, &opts.path);
+        /// Maximum amount of suggestions for each load-out item
+        #[arg(long, default_value_t = 3)]
+        limit: usize,
 
-    match opts.command {
-        Command::Create {} => {
-            let project = Project::new(project_name.to_string());
-            project::save(&project, &project_file_path)?;
+        /// Feeder library file, to only suggest feeders that are compatible with the part
+        #[arg(long, value_name = "FEEDERS_FILE")]
+        feeders: Option<PathBuf>,
+    },
+    /// Set placement ordering for a phase
+    SetPlacementOrdering {
+        /// Phase reference (e.g. 'top_1')
+        #[arg(long)]
+        phase: Reference,
 
-            info!("Created job: {}", project.name);
-        },
-        Command::AddPcb { kind, name } => {
-            let mut project = project::load(&project_file_path)?;
+        /// Orderings (e.g. 'PCB_UNIT:ASC,FEEDER_REFERENCE:ASC')
+        #[arg(long, num_args = 0.., value_delimiter = ',', value_parser = cli::parsers::PlacementSortingItemParser::default())]
+        placement_orderings: Vec<PlacementSortingItem>
+    },
+    /// Set the artifacts that must be generated for each phase that uses a process
+    SetRequiredArtifacts {
+        /// Process name (e.g. 'pnp')
+        #[arg(long)]
+        process: ProcessName,
 
-            project::add_pcb(&mut project, kind.clone().into(), name)?;
+        /// Artifacts (e.g. 'phase-placements,work-instructions'), none to remove the requirements
+        #[arg(long, num_args = 0.., value_delimiter = ',')]
+        artifacts: Vec<ArtifactTypeArg>,
+    },
+    /// Set the tools and consumables to confirm when starting an operation of a process
+    SetOperationChecklist {
+        /// Process name (e.g. 'pnp')
+        #[arg(long)]
+        process: ProcessName,
 
-            project::save(&project, &project_file_path)?;
-        },
-        Command::AssignVariantToUnit { design, variant, unit } => {
-            let mut project = project::load(&project_file_path)?;
+        /// The operation, 'loadpcbs', 'automatedpnp', 'reflowcomponents', 'manuallysoldercomponents' or a custom operation of the process
+        #[arg(long, value_parser = cli::parsers::ProcessOperationParser::default())]
+        operation: ProcessOperationKind,
 
-            project.update_assignment(unit.clone(), DesignVariant { design_name: design.clone(), variant_name: variant.clone() })?;
+        /// Checklist item (e.g. 'stencil=STN-001'), may be repeated, none to remove
+        #[arg(long = "item")]
+        items: Vec<String>,
+    },
+    /// Set the style of the work instructions for a phase
+    SetWorkInstructionsStyle {
+        /// Phase reference (e.g. 'top_1')
+        #[arg(long)]
+        phase: Reference,
 
-            let unique_design_variants = project.unique_design_variants();
-            let design_variant_placement_map = stores::placements::load_all_placements(&unique_design_variants, &opts.path)?;
-            let _all_parts = project::refresh_from_design_variants(&mut project, design_variant_placement_map);
+        /// Style, 'pick-list' groups the placements by feeder and part, for manual assembly
+        #[arg(long)]
+        style: WorkInstructionsStyleArg,
+    },
+    /// Set or remove tags of a phase, e.g. 'line=A'
+    SetPhaseTags {
+        /// Phase reference (e.g. 'top_1')
+        #[arg(long)]
+        phase: Reference,
 
-            project::save(&project, &project_file_path)?;
-        },
-        Command::AssignProcessToParts { process: process_name, manufacturer: manufacturer_pattern, mpn: mpn_pattern } => {
-            let mut project = project::load(&project_file_path)?;
+        /// Tag to set (e.g. 'line=A'), may be repeated
+        #[arg(long, value_name = "KEY=VALUE")]
+        tag: Vec<PhaseTag>,
 
-            let process = project.find_process(&process_name)?.clone();
+        /// Key of a tag to remove, may be repeated
+        #[arg(long, value_name = "KEY")]
+        remove: Vec<String>,
+    },
+    /// Set the phases that must be complete before a phase is started, replacing any previous dependencies
+    SetPhaseDependencies {
+        /// Phase reference (e.g. 'manual_bottom')
+        #[arg(long)]
+        phase: Reference,
 
-            let unique_design_variants = project.unique_design_variants();
-            let design_variant_placement_map = stores::placements::load_all_placements(&unique_design_variants, &opts.path)?;
-            let all_parts = project::refresh_from_design_variants(&mut project, design_variant_placement_map);
+        /// Phase references (e.g. 'reflow_top'), omit to remove the dependencies
+        #[arg(long, num_args = 0.., value_delimiter = ',')]
+        depends_on: Vec<Reference>,
+    },
+    /// Normalize the rotations of the placements in the artifacts of a phase, with optional per-part rotation offsets
+    SetRotationNormalization {
+        /// Phase reference (e.g. 'top_1')
+        #[arg(long)]
+        phase: Reference,
 
-            project::update_applicable_processes(&mut project, all_parts.as_slice(), process, manufacturer_pattern, mpn_pattern);
+        /// Rotation range, omit to remove the normalization
+        #[arg(long)]
+        range: Option<RotationRangeArg>,
 
-            project::save(&project, &project_file_path)?;
-        },
-        Command::CreatePhase { process: process_name, reference, load_out, pcb_side: pcb_side_arg } => {
-            let mut project = project::load(&project_file_path)?;
+        /// Rotation offsets file, relative to the project directory, anti-clockwise degrees for each part (e.g. 'rotation_offsets.csv')
+        #[arg(long, requires = "range")]
+        offsets: Option<String>,
+    },
+    /// Require a first-article inspection for a phase, the first PCB unit must be signed off before the other units are placed
+    SetFirstArticleInspection {
+        /// Phase reference (e.g. 'top_1')
+        #[arg(long)]
+        phase: Reference,
 
-            let pcb_side = pcb_side_arg.into();
-            
-            let process_name_str = process_name.to_string();
-            let process = ProcessFactory::by_name(process_name_str.as_str())?;
-            
-            project.ensure_process(&process)?;
+        /// Require the inspection, omit to no longer require it
+        #[arg(long)]
+        required: bool,
+    },
+    /// Set the nozzles of the machine used by a phase, placements are assigned a nozzle by the package of their part
+    SetPhaseNozzles {
+        /// Phase reference (e.g. 'top_1')
+        #[arg(long)]
+        phase: Reference,
 
-            stores::load_out::ensure_load_out(&load_out)?;
+        /// Count of heads, each head holds one nozzle
+        #[arg(long, default_value_t = 1)]
+        heads: u32,
 
-            project.update_phase(reference, process.name.clone(), load_out.to_string(), pcb_side)?;
+        /// Nozzle and the packages it can pick (e.g. 'N1=0402,0603'), in order of preference, may be repeated, none to remove
+        #[arg(long = "nozzle", value_name = "NOZZLE=PACKAGES")]
+        nozzles: Vec<Nozzle>,
+    },
+    /// Set the check of the remaining load-out item quantities before a placement operation of a phase is started
+    SetQuantityCheck {
+        /// Phase reference (e.g. 'top_1')
+        #[arg(long)]
+        phase: Reference,
 
-            project::save(&project, &project_file_path)?;
-        },
-        Command::AssignPlacementsToPhase { phase: reference, placements: placements_pattern } => {
-            let mut project = project::load(&project_file_path)?;
+        /// Mode, 'block' prevents the operation from being started unless overridden
+        #[arg(long)]
+        mode: QuantityCheckModeArg,
+    },
+    /// List the phases, with their tags
+    ListPhases {
+        /// Only list phases with the tag (e.g. 'line=A'), may be repeated
+        #[arg(long, value_name = "KEY=VALUE")]
+        tag: Vec<PhaseTag>,
 
-            let unique_design_variants = project.unique_design_variants();
-            let design_variant_placement_map = stores::placements::load_all_placements(&unique_design_variants, &opts.path)?;
-            let _all_parts = project::refresh_from_design_variants(&mut project, design_variant_placement_map);
+        /// Only list phases with placements on the PCB, by name (e.g. 'display_board')
+        #[arg(long, value_name = "PCB_NAME")]
+        pcb: Option<String>,
+    },
+    /// Show the status of the project, i.e. phases, operation states and placements, without modifying it
+    Status {
+    },
+    /// Show the status of a phase, including its placements, without modifying it
+    InspectPhase {
+        /// Phase reference (e.g. 'top_1')
+        #[arg(long)]
+        phase: Reference,
+    },
+    /// Suggest a shared machine setup for batching the project with other projects, from the parts they have in common
+    AnalyzeLoadOutReuse {
+        /// Project file of another project, relative to the path, may be repeated
+        #[arg(long = "other-project", required = true, value_name = "PROJECT_FILE")]
+        other_projects: Vec<PathBuf>,
+    },
+    /// Export cycle-time and yield statistics, for each process, operator and part, from the operation history
+    Analytics {
+        /// Format
+        #[arg(long, default_value = "csv")]
+        format: AnalyticsFormatArg,
 
-            let phase = project.phases.get(&reference)
-                .ok_or(PhaseError::UnknownPhase(reference))?.clone();
+        /// Analytics file, relative to the project directory [default: '<PROJECT_NAME>_analytics.<FORMAT>' in the artifact directory]
+        #[arg(long)]
+        file: Option<PathBuf>,
 
-            let parts = project::assign_placements_to_phase(&mut project, &phase, placements_pattern);
-            trace!("Required load_out parts: {:?}", parts);
+        /// Project file of another project to include, relative to the path, may be repeated
+        #[arg(long = "other-project", value_name = "PROJECT_FILE")]
+        other_projects: Vec<PathBuf>,
+    },
+    /// Show throughput statistics of a phase, placements per hour and time per operation, from the operation history
+    Metrics {
+        /// Phase reference (e.g. 'top_1')
+        #[arg(long)]
+        phase: Reference,
 
-            let _modified = project::update_phase_operation_states(&mut project);
+        /// Only include the operation history from this date and time, RFC 3339 (e.g. '2024-10-16T08:00:00Z')
+        #[arg(long, value_parser = cli::args::parse_date_time)]
+        since: Option<OffsetDateTime>,
 
-            for part in parts.iter() {
-                let part_state = project.part_states.get_mut(&part)
-                    .ok_or_else(|| PartStateError::NoPartStateFound { part: part.clone() })?;
+        /// Only include the operation history before this date and time, RFC 3339 (e.g. '2024-10-17T08:00:00Z')
+        #[arg(long, value_parser = cli::args::parse_date_time)]
+        until: Option<OffsetDateTime>,
+    },
+    /// Set the price list used for the cost estimates of the report
+    SetPriceList {
+        /// Price list file, relative to the project directory, omit to remove the price list
+        #[arg(long)]
+        source: Option<String>,
+    },
+    /// Set the inventory, used for the inventory shortages of the report, the inventory is consumed as placements are placed
+    SetInventory {
+        /// Inventory file, relative to the project directory, omit to remove the inventory
+        #[arg(long)]
+        source: Option<String>,
+    },
+    /// Set the parameters of the phase time estimates of the report and status, omit all the parameters to remove them
+    SetEstimation {
+        /// Placement rate of a process, in placements per hour, may be repeated, e.g. 'pnp=1200'
+        #[arg(long = "placement-rate", value_name = "PROCESS=RATE")]
+        placement_rates: Vec<PlacementRate>,
 
-                project::add_process_to_part(part_state, part, phase.process.clone());
-            }
+        /// Feeder setup time, in seconds per feeder [default: 0]
+        #[arg(long)]
+        feeder_setup_time: Option<u32>,
 
-            stores::load_out::add_parts_to_load_out(&LoadOutSource::from_str(&phase.load_out_source).unwrap(), parts)?;
+        /// Reflow time, in seconds per phase whose process reflows the components [default: 0]
+        #[arg(long)]
+        reflow_time: Option<u32>,
+    },
+    /// Generate artifacts
+    GenerateArtifacts {
+        /// Sign the artifacts using the signing key file (hex encoded ed25519 secret key) [default: the 'signing-key' preference]
+        #[arg(long, env = "MAKERPNP_SIGNING_KEY")]
+        signing_key: Option<PathBuf>,
+
+        /// Generate the machine exports even if parts have not been assigned to a feeder, the parts are logged as warnings
+        #[arg(long)]
+        allow_missing_feeders: bool,
+
+        /// Report format, the JSON report is always generated, 'html' also generates an HTML report
+        #[arg(long, default_value = "json")]
+        report_format: ReportFormatArg,
+    },
+    /// Export a bill of materials, the quantity of each part, for each phase and for each unit
+    ExportBom {
+        /// Format
+        #[arg(long, default_value = "csv")]
+        format: BomFormatArg,
+
+        /// BOM file, relative to the project directory [default: '<PROJECT_NAME>[_<PCB_NAME>]_bom.<FORMAT>' in the artifact directory]
+        #[arg(long)]
+        file: Option<PathBuf>,
+
+        /// Only include the placements of the PCB, by name (e.g. 'display_board')
+        #[arg(long, value_name = "PCB_NAME")]
+        pcb: Option<String>,
+    },
+    /// Generate a completion certificate for a completed phase, certificates are also generated when a phase is completed
+    GenerateCertificate {
+        /// Phase reference (e.g. 'top_1')
+        #[arg(long)]
+        phase: Reference,
+
+        /// Sign the certificate using the signing key file (hex encoded ed25519 secret key) [default: the 'signing-key' preference]
+        #[arg(long, env = "MAKERPNP_SIGNING_KEY")]
+        signing_key: Option<PathBuf>,
+    },
+    /// Preview artifacts, without writing them
+    PreviewArtifacts {
+        /// Maximum amount of lines to show for each artifact
+        #[arg(long, default_value_t = 20)]
+        max_lines: usize,
+    },
+    /// Search the placements, phases, parts and load-out items of the project
+    Search {
+        /// Text to find, case-insensitive
+        #[arg(long)]
+        query: String,
+    },
+    /// Validate the required artifacts of each phase exist and are up to date with the project
+    Validate {},
+    /// Acknowledge a report issue, acknowledged issues are still counted as errors or warnings
+    AcknowledgeIssue {
+        /// Id of the issue, as listed in the report
+        #[arg(long, value_name = "ISSUE_ID")]
+        id: String,
+
+        /// Reason for acknowledging the issue
+        #[arg(long)]
+        reason: String,
+    },
+    /// Waive a report issue, waived issues are listed in the report but are not counted as errors or warnings
+    WaiveIssue {
+        /// Id of the issue, as listed in the report
+        #[arg(long, value_name = "ISSUE_ID")]
+        id: String,
+
+        /// Reason for waiving the issue
+        #[arg(long)]
+        reason: String,
+    },
+    /// Release the project to production, validates the project, generates the artifacts, snapshots the project files and freezes the planning data
+    Release {},
+    /// Reopen a released project, so that planning changes can be made for the next release
+    Reopen {},
+    /// Verify signed artifacts
+    Verify {
+        /// Verifying key file (hex encoded ed25519 public key) [default: the 'verifying-key' preference]
+        #[arg(long, env = "MAKERPNP_VERIFYING_KEY")]
+        verifying_key: Option<PathBuf>,
+    },
+    /// Verify the operation history has not been modified, or had records removed
+    VerifyOperationHistory {
+        /// Phase reference (e.g. 'top_1'), all phases if not specified
+        #[arg(long)]
+        phase: Option<Reference>,
+
+        /// A previously recorded head hash of the phase operation history, to detect records removed from the end
+        #[arg(long, requires = "phase")]
+        head: Option<String>,
+    },
+    /// Show the changes to the placements, part states and phases since a previous save of the project
+    #[command(alias = "diff")]
+    DiffProject {
+        /// Previous save of the project file, relative to the project directory (e.g. 'backup/project-job1.mpnp.json')
+        #[arg(long)]
+        against: PathBuf,
+
+        /// Output format
+        #[arg(long, default_value = "text")]
+        format: DiffFormatArg,
+    },
+    /// Show the audit log, the changes made to the project by each command, oldest first
+    History {
+        /// Only show the most recent items
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+    /// Record the start of a phase operation, confirming the checklist of the operation
+    StartPhaseOperation {
+        /// Phase reference (e.g. 'top_1')
+        #[arg(long)]
+        phase: Reference,
+
+        /// The operation to start, 'loadpcbs', 'automatedpnp', 'reflowcomponents', 'manuallysoldercomponents' or a custom operation of the process
+        #[arg(long, value_parser = cli::parsers::ProcessOperationParser::default())]
+        operation: ProcessOperationKind,
+
+        /// Checklist item that has been checked, may be repeated, unconfirmed items are prompted for
+        #[arg(long = "confirm", value_name = "ITEM")]
+        confirmed: Vec<String>,
+
+        /// Start the operation even if the load-out item quantities are insufficient, otherwise prompted for
+        #[arg(long)]
+        allow_insufficient_quantities: bool,
+    },
+    /// Record phase operation
+    RecordPhaseOperation {
+        /// Phase reference (e.g. 'top_1')
+        #[arg(long)]
+        phase: Reference,
+
+        /// The operation to update, 'loadpcbs', 'automatedpnp', 'reflowcomponents', 'manuallysoldercomponents' or a custom operation of the process
+        #[arg(long, value_parser = cli::parsers::ProcessOperationParser::default())]
+        operation: ProcessOperationKind,
+
+        /// The process operation to set
+        #[arg(long)]
+        set: ProcessOperationSetArg,
+    },   
+    /// Record a feeder being loaded, which starts the floor life of a moisture sensitive part
+    RecordFeederLoaded {
+        /// Phase reference (e.g. 'top_1')
+        #[arg(long)]
+        phase: Reference,
+
+        /// Feeder reference (e.g. 'FEEDER_1')
+        #[arg(long)]
+        feeder: String,
+
+        /// The alternate part '<manufacturer>:<mpn>' that was loaded, omit if the part itself was loaded
+        #[arg(long)]
+        alternate: Option<Part>,
+    },
+    /// Record placements operation
+    RecordPlacementsOperation {
+        /// List of reference designators to apply the operation to
+        #[arg(long, required_unless_present = "object_paths", num_args = 1.., value_delimiter = ',')]
+        object_path_patterns: Vec<Regex>,
+
+        /// List of object paths to apply the operation to, e.g. 'panel=1::unit=*::ref_des=R*', '*' and '?' are wildcards
+        #[arg(long, num_args = 1.., value_delimiter = ',')]
+        object_paths: Vec<ObjectPathPattern>,
+        
+        /// The completed operation to apply
+        #[arg(long)]
+        operation: PlacementOperationArg,
+    },
+    /// Record placements and operations by scanning barcodes, reads one barcode per line from stdin
+    ///
+    /// Scan a unit (e.g. 'panel=1::unit=1') then a feeder (e.g. 'FEEDER_1') to record the unplaced placements of the
+    /// feeder's part on the unit as placed, scan an operation to record it as complete.
+    Scan {
+        /// Phase reference (e.g. 'top_1')
+        #[arg(long)]
+        phase: Reference,
+
+        /// Barcode mappings file, relative to the project directory, defaults to 'barcodes.csv' if it exists
+        #[arg(long, value_name = "FILE")]
+        mappings: Option<PathBuf>,
+    },
+    /// Run the placements of a phase on a machine, recording each placement as it is placed
+    RunPhase {
+        /// Phase reference (e.g. 'top_1')
+        #[arg(long)]
+        phase: Reference,
+
+        /// The machine to run the phase on
+        #[arg(long)]
+        machine: MachineKindArg,
+    },
+    /// Set the quantity of panels, or boards, to build, each is built by a production run
+    SetProductionQuantity {
+        /// Quantity, omit for an open-ended quantity
+        #[arg(long)]
+        quantity: Option<u32>,
+    },
+    /// Start a production run, the placement and phase operations are reset if a previous run was completed
+    StartProductionRun {
+        /// Serial number of the panel, or board (e.g. 'SN0001')
+        #[arg(long)]
+        serial: String,
+    },
+    /// Complete the production run in progress, recording the placed placements
+    CompleteProductionRun {
+        /// Complete the run even if some placements have not been placed
+        #[arg(long)]
+        force: bool,
+    },
+    /// Show the completed, in progress and pending production runs
+    ProductionRuns {},
+    /// Record the sign-off of the first-article inspection of a phase
+    RecordFirstArticleInspection {
+        /// Phase reference (e.g. 'top_1')
+        #[arg(long)]
+        phase: Reference,
+    },
+    /// Reset operations
+    ResetOperations {
+    },
+    /// Set how the status of placement operations is updated
+    SetOperationTransitions {
+        /// Operation transitions mode
+        #[arg(long)]
+        mode: OperationTransitionsArg,
+    },
+    /// Migrate absolute load-out sources to project-relative load-out sources
+    MigrateLoadOutSources {
+    },
+    /// Restore a load-out from a backup, backups are made automatically before load-outs are modified
+    RestoreLoadOut {
+        /// Phase reference (e.g. 'top_1')
+        #[arg(long)]
+        phase: Reference,
+
+        /// File name of the backup (e.g. 'load_out_1.20241016T120000.000Z.csv'), defaults to the latest backup
+        #[arg(long, value_name = "FILE_NAME")]
+        backup: Option<String>,
+
+        /// List the backups, without restoring
+        #[arg(long)]
+        list: bool,
+    },
+    /// Rename a part in the project, the design variant placements, the load-outs, the inventory and other files
+    RenamePart {
+        /// Manufacturer
+        #[arg(long)]
+        manufacturer: String,
+
+        /// Manufacturer part number
+        #[arg(long)]
+        mpn: String,
+
+        /// New manufacturer
+        #[arg(long)]
+        new_manufacturer: String,
+
+        /// New manufacturer part number
+        #[arg(long)]
+        new_mpn: String,
+
+        /// Other files to rename the part in, relative to the project directory (e.g. 'part_mappings.csv')
+        #[arg(long = "file", value_name = "FILE")]
+        files: Vec<PathBuf>,
+
+        /// Show the changes, without writing them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Watch the design variant placements files, refreshing the project when they change
+    Watch {
+        /// Interval, in seconds, between checks for changes
+        #[arg(long, default_value = "2")]
+        interval: u64,
+
+        /// Stop after refreshing the project this many times, watches until terminated if not specified
+        #[arg(long)]
+        max_refreshes: Option<usize>,
+    },
+    /// Serve a read-only dashboard of the project progress
+    Dashboard {
+        /// Address to listen on (e.g. '0.0.0.0:8080')
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        listen: SocketAddr,
+    },
+    /// Project report exports
+    Report {
+        #[command(subcommand)]
+        command: ReportCommand,
+    },
+    /// Project maintenance
+    Maintenance {
+        #[command(subcommand)]
+        command: MaintenanceCommand,
+    },
+    /// User preferences, shared by all projects
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+    /// Example projects
+    Example {
+        #[command(subcommand)]
+        command: ExampleCommand,
+    },
+}
+
+#[derive(Subcommand)]
+#[command(arg_required_else_help(true))]
+enum ReportCommand {
+    /// Render the report into a static site (HTML/JS, no server required), generate the artifacts first
+    Site {
+        /// Directory to write the site into [default: '<PROJECT_NAME>_report_site' in the artifact directory]
+        #[arg(long, value_name = "DIR")]
+        into: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+#[command(arg_required_else_help(true))]
+enum UnitAssignmentsCommand {
+    /// Export the unit assignments, so that they can be edited in a spreadsheet
+    Export {
+        /// Unit assignments file, relative to the project directory (e.g. 'units.csv')
+        #[arg(long)]
+        file: PathBuf,
+    },
+    /// Import the unit assignments, all the assignments are validated before any are made, units not in the file are unchanged
+    Import {
+        /// Unit assignments file, relative to the project directory (e.g. 'units.csv')
+        #[arg(long)]
+        file: PathBuf,
+
+        /// Accept conflicting placements of the design variants, placements with the same ref_des, the last of the placements is used
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand)]
+#[command(arg_required_else_help(true))]
+enum MaintenanceCommand {
+    /// Remove old backups of the project files and load-outs, the latest backup of each file is always kept
+    Cleanup {
+        /// Maximum amount of backups of each file to keep [default: the 'backup-max-count' preference, or 10]
+        #[arg(long)]
+        max_count: Option<usize>,
+
+        /// Maximum age of backups to keep, in days [default: the 'backup-max-age-days' preference]
+        #[arg(long)]
+        max_age_days: Option<u32>,
+
+        /// Show the backups that would be removed, without removing them
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+#[command(arg_required_else_help(true))]
+enum ExampleCommand {
+    /// Generate an example project, design files, load-outs and a script of the commands used to create them
+    Generate {
+        /// Directory to generate the example into
+        #[arg(long, value_name = "DIR")]
+        into: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+#[command(arg_required_else_help(true))]
+enum ConfigCommand {
+    /// Show a preference, nothing is shown if the preference is not set
+    Get {
+        /// Preference
+        #[arg(long)]
+        key: PreferenceKeyArg,
+    },
+    /// Set a preference
+    Set {
+        /// Preference
+        #[arg(long)]
+        key: PreferenceKeyArg,
+
+        /// Value (e.g. 'artifacts' for the artifact directory)
+        #[arg(long)]
+        value: String,
+    },
+    /// Remove a preference, so that the default is used
+    Unset {
+        /// Preference
+        #[arg(long)]
+        key: PreferenceKeyArg,
+    },
+    /// Show all the preferences that are set
+    List {
+    },
+}
+
+// FUTURE consider merging the AssignProcessToParts and AssignLoadOutToParts commands
+//        consider making a group for the criteria args (manufacturer/mpn/etc).
+
+fn main() -> anyhow::Result<()>{
+    let args = argfile::expand_args(
+        argfile::parse_fromfile,
+        argfile::PREFIX,
+    ).unwrap();
+
+    let matches = Opts::command().get_matches_from(args);
+    let opts = Opts::from_arg_matches(&matches).unwrap_or_else(|error| error.exit());
+    let command_name = build_command_name(&matches);
+
+    let json_log = opts.log_json.map(|path| JsonLog { path, command: command_name.clone() });
+    // every change made by the command is recorded in the audit log
+    let mutations = Mutations::default();
+    cli::tracing::configure_tracing_with_json_log(opts.trace, json_log, Some(mutations.clone()), opts.verbose)?;
+
+    let project_name = &opts.project.unwrap();
+    let project_file_path = project::build_project_file_path(project_name, &opts.path);
+
+    let show_health_summary = opts.command.shows_health_summary();
+
+    let mut session = ProjectSession::new(project_file_path.clone());
+
+    if opts.command.modifies_planning() && session.exists() {
+        release::ensure_planning_modifiable(&session.load()?)?;
+    }
+
+    match opts.command {
+        Command::Create {} => {
+            let project = Project::new(project_name.to_string());
+            session.save(&project)?;
+
+            info!(target: MUTATION_TARGET, name = %project.name, message = "Created job: {name}");
+        },
+        Command::CloneProject { name, into } => {
+            let project = session.load()?;
+
+            let into = into.unwrap_or(opts.path.clone());
+
+            let cloned_project_file_path = project::build_project_file_path(&name, &into);
+            if cloned_project_file_path.exists() {
+                bail!("Project already exists. path: {:?}", cloned_project_file_path);
+            }
+
+            let files = find_project_files_to_copy(&project, &opts.path, &into);
+            if let Some((_from, to)) = files.iter().find(|(_from, to)| to.exists()) {
+                bail!("File already exists. path: {:?}", to);
+            }
+
+            let cloned_project = project::clone_project(&project, name);
+
+            std::fs::create_dir_all(&into)?;
+            for (from, to) in files.iter() {
+                std::fs::copy(from, to)?;
+                info!("Copied file. from: {:?}, to: {:?}", from, to);
+            }
+
+            project::save(&cloned_project, &cloned_project_file_path)?;
+        },
+        Command::CompressProject { decompress } => {
+            let project = session.load()?;
+
+            let plain_project_file_path = project::build_plain_project_file_path(project_name, &opts.path);
+            let compressed_project_file_path = project::build_compressed_project_file_path(&plain_project_file_path);
+
+            let (from, to) = match decompress {
+                true => (compressed_project_file_path, plain_project_file_path),
+                false => (plain_project_file_path, compressed_project_file_path),
+            };
+
+            if project_file_path.eq(&from) {
+                // the project files share a lock file, the lock held since loading `from` also locks `to`
+                project::save_locked(&project, &to, session.lock()?)?;
+                std::fs::remove_file(&from)?;
+
+                info!(target: MUTATION_TARGET, from = ?from, to = ?to, message = "Converted project file. from: {from}, to: {to}");
+            } else {
+                info!("Project file already converted. path: {:?}", project_file_path);
+            }
+        },
+        Command::AddPcb { kind, name } => {
+            let mut project = session.load()?;
+
+            project::add_pcb(&mut project, kind.clone().into(), name)?;
+
+            session.save(&project)?;
+        },
+        Command::SetPanelGeometry { pcb, units, fiducials } => {
+            let mut project = session.load()?;
+
+            let geometry = PanelGeometry::new(units, fiducials)?;
+
+            let modified = project::set_panel_geometry(&mut project, &pcb, geometry)?;
+
+            if modified {
+                session.save(&project)?;
+            }
+        },
+        Command::SetPcbDimensions { pcb, width, height } => {
+            let mut project = session.load()?;
+
+            let modified = project::set_pcb_dimensions(&mut project, &pcb, PcbDimensions { width, height })?;
+
+            if modified {
+                session.save(&project)?;
+            }
+        },
+        Command::DiscoverVariants { design, register } => {
+            let discovered = stores::placements::discover_design_variants(&opts.path, design.as_ref())?;
+
+            let mut design_variants: Vec<DesignVariant> = vec![];
+            for discovered_placements in discovered.iter() {
+                let candidates: Vec<String> = discovered_placements.candidates.iter().map(DesignVariant::to_string).collect();
+                info!("Discovered placements. file: '{}', candidates: {:?}", discovered_placements.file_name, candidates);
+
+                match discovered_placements.candidates.as_slice() {
+                    [design_variant] => design_variants.push(design_variant.clone()),
+                    _ => if register {
+                        error!("Ambiguous placements file name, specify the design. file: '{}'", discovered_placements.file_name);
+                    },
+                }
+            }
+
+            if register {
+                let mut project = session.load()?;
+
+                if project::register_design_variants(&mut project, &design_variants) {
+                    session.save(&project)?;
+                }
+            }
+        },
+        Command::AssignVariantToUnit { design, variant, unit, force } => {
+            let mut project = session.load()?;
+
+            project.update_assignment(unit.clone(), DesignVariant { design_name: design.clone(), variant_name: variant.clone() })?;
+
+            let _all_parts = refresh_from_design_variants(&mut project, &opts.path, force, &progress::ProgressBarReporter::new())?;
+
+            session.save(&project)?;
+        },
+        Command::UnitAssignments { command: UnitAssignmentsCommand::Export { file } } => {
+            let project = session.load()?;
+
+            stores::unit_assignments::export(&opts.path.join(file), &project.unit_assignments)?;
+        },
+        Command::UnitAssignments { command: UnitAssignmentsCommand::Import { file, force } } => {
+            let mut project = session.load()?;
+
+            let unit_assignments = stores::unit_assignments::import(&opts.path.join(file))?;
+            for (unit_path, design_variant) in unit_assignments {
+                project.update_assignment(unit_path, design_variant)?;
+            }
+
+            let _all_parts = refresh_from_design_variants(&mut project, &opts.path, force, &progress::ProgressBarReporter::new())?;
+
+            session.save(&project)?;
+        },
+        Command::AcknowledgeDesignChanges {} => {
+            let mut project = session.load()?;
+
+            let unique_design_variants = project.unique_design_variants();
+            let design_revisions = stores::placements::build_design_revisions(&unique_design_variants, &opts.path)?;
+
+            if project::acknowledge_design_revisions(&mut project, &design_revisions) {
+                session.save(&project)?;
+            } else {
+                info!("No design changes to acknowledge.");
+            }
+        },
+        Command::AssignProcessToParts { process: process_name, manufacturer: manufacturer_pattern, mpn: mpn_pattern } => {
+            let mut project = session.load()?;
+
+            let process = project.find_process(&process_name)?.clone();
+
+            let all_parts = refresh_from_design_variants(&mut project, &opts.path, false, &NoProgress)?;
+
+            project::update_applicable_processes(&mut project, all_parts.as_slice(), process, manufacturer_pattern, mpn_pattern);
+
+            session.save(&project)?;
+        },
+        Command::SetMoistureSensitivity { manufacturer: manufacturer_pattern, mpn: mpn_pattern, level, floor_life_hours } => {
+            let moisture_sensitivity = level.build().map(|level| MoistureSensitivity { level, floor_life_hours });
+
+            if matches!(&moisture_sensitivity, Some(MoistureSensitivity { level: MslLevel::Level6, floor_life_hours: None })) {
+                bail!("Level 6 parts require a floor life, use '--floor-life-hours'")
+            }
+
+            let mut project = session.load()?;
+
+            let _all_parts = refresh_from_design_variants(&mut project, &opts.path, false, &NoProgress)?;
+
+            let _modified = project::update_moisture_sensitivity(&mut project, moisture_sensitivity, manufacturer_pattern, mpn_pattern);
+
+            session.save(&project)?;
+        },
+        Command::ImportPartDetails { parts } => {
+            let mut project = session.load()?;
+
+            let parts_source = opts.path.join(parts).to_string_lossy().to_string();
+            let parts_master = stores::parts::load_parts_master(&parts_source)?;
+
+            let part_details: BTreeMap<Part, PartDetails> = parts_master.iter()
+                .map(|item| (item.part.clone(), item.details.clone()))
+                .collect();
+            let msl_levels: BTreeMap<Part, MslLevel> = parts_master.iter()
+                .filter_map(|item| item.msl_level.map(|msl_level| (item.part.clone(), msl_level)))
+                .collect();
+
+            let mut modified = project::update_part_details(&mut project, &part_details);
+            modified |= project::update_msl_levels(&mut project, &msl_levels);
+
+            if modified {
+                session.save(&project)?;
+            }
+        },
+        Command::CreatePhase { process: process_name, reference, load_out, pcb_side: pcb_side_arg, export_format } => {
+            let mut project = session.load()?;
+
+            let pcb_side = pcb_side_arg.into();
+            
+            let process_name_str = process_name.to_string();
+            let process_definitions = stores::process_definitions::load_process_definitions(&opts.path)?;
+            let process = ProcessFactory::by_name_with_definitions(process_name_str.as_str(), &process_definitions)?;
+            
+            project.ensure_process(&process)?;
+
+            stores::load_out::ensure_load_out(&load_out.resolve(&opts.path))?;
+
+            project.update_phase(reference.clone(), process.name.clone(), load_out.to_string(), pcb_side)?;
+
+            if let Some(export_format) = export_format {
+                export::update_export_format(&mut project, &reference, Some(export_format.into()))?;
+            }
+
+            log_shared_load_out(&project, &reference, &load_out, &opts.path);
+
+            session.save(&project)?;
+        },
+        Command::CreateReworkPhase { reference, load_out, pcb_side: pcb_side_arg } => {
+            let mut project = session.load()?;
+
+            let pcb_side = pcb_side_arg.into();
+
+            let process = ProcessFactory::by_name("manual")?;
+
+            project.ensure_process(&process)?;
+
+            stores::load_out::ensure_load_out(&load_out.resolve(&opts.path))?;
+
+            let parts = project::create_rework_phase(&mut project, &reference, &process, load_out.to_string(), pcb_side)?;
+            trace!("Required load_out parts: {:?}", parts);
+
+            for part in parts.iter() {
+                let part_state = project.part_states.get_mut(part)
+                    .ok_or_else(|| PartStateError::NoPartStateFound { part: part.clone() })?;
+
+                project::add_process_to_part(part_state, part, process.name.clone());
+            }
+
+            stores::load_out::add_parts_to_load_out(&load_out.resolve(&opts.path), parts)?;
+
+            log_shared_load_out(&project, &reference, &load_out, &opts.path);
+
+            session.save(&project)?;
+        },
+        Command::ClonePhase { phase: source_reference, reference, load_out } => {
+            let mut project = session.load()?;
+
+            let source_phase = project.phases.get(&source_reference)
+                .ok_or(PhaseError::UnknownPhase(source_reference.clone()))?;
+
+            let load_out_copy = match &load_out {
+                Some(load_out) => {
+                    let from = build_load_out_source(source_phase, &opts.path);
+                    let to = load_out.resolve(&opts.path);
+                    if to.path().is_some_and(|path| path.exists()) {
+                        bail!("Load-out already exists. source: '{}'", to);
+                    }
+                    Some((from, to))
+                },
+                None => None,
+            };
+
+            let load_out_source = match load_out {
+                Some(load_out) => load_out.to_string(),
+                None => source_phase.load_out_source.clone(),
+            };
+
+            project::clone_phase(&mut project, &source_reference, reference, load_out_source)?;
+
+            if let Some((from, to)) = load_out_copy {
+                match (from.path(), to.path()) {
+                    (Some(from_path), Some(to_path)) => { std::fs::copy(from_path, to_path)?; },
+                    _ => stores::load_out::store_items(&to, &stores::load_out::load_items(&from)?)?,
+                }
+                info!("Copied load-out. from: '{}', to: '{}'", from, to);
+            }
+
+            session.save(&project)?;
+        },
+        Command::RemovePhase { phase: reference } => {
+            let mut project = session.load()?;
+
+            let phase = project.phases.get(&reference)
+                .ok_or(PhaseError::UnknownPhase(reference.clone()))?.clone();
+
+            let parts = project::remove_phase(&mut project, &reference)?;
+
+            remove_unrequired_parts_from_load_out(&project, &phase, &parts, &opts.path)?;
+
+            session.save(&project)?;
+        },
+        Command::RenamePhase { phase: from, reference: to } => {
+            let mut project = session.load()?;
+
+            project::rename_phase(&mut project, &from, to.clone())?;
+            project::record_phase_renamed(&opts.path, &from, &to)?;
+
+            session.save(&project)?;
+        },
+        Command::ReorderPhases { phases } => {
+            let mut project = session.load()?;
+
+            let modified = project::update_phase_orderings(&mut project, &phases)?;
+
+            if modified {
+                session.save(&project)?;
+            }
+        },
+        Command::AssignPlacementsToPhase { phase: reference, placements: placements_pattern, allow_reassign, allow_side_mismatch } => {
+            let mut project = session.load()?;
+
+            let _all_parts = refresh_from_design_variants(&mut project, &opts.path, false, &NoProgress)?;
+
+            let phase = project.phases.get(&reference)
+                .ok_or(PhaseError::UnknownPhase(reference))?.clone();
+
+            let parts = project::assign_placements_to_phase(&mut project, &phase, placements_pattern, allow_reassign, allow_side_mismatch)?;
+            trace!("Required load_out parts: {:?}", parts);
+
+            let _modified = project::update_phase_operation_states(&mut project);
+
+            for part in parts.iter() {
+                let part_state = project.part_states.get_mut(part)
+                    .ok_or_else(|| PartStateError::NoPartStateFound { part: part.clone() })?;
+
+                project::add_process_to_part(part_state, part, phase.process.clone());
+            }
+
+            stores::load_out::add_parts_to_load_out(&build_load_out_source(&phase, &opts.path), parts)?;
+
+            session.save(&project)?;
+        },
+        Command::UnassignPlacementsFromPhase { phase: reference, placements: placements_pattern } => {
+            let mut project = session.load()?;
+
+            let phase = project.phases.get(&reference)
+                .ok_or(PhaseError::UnknownPhase(reference.clone()))?.clone();
+
+            let parts = project::unassign_placements_from_phase(&mut project, &reference, &placements_pattern)?;
+
+            let _modified = project::update_phase_operation_states(&mut project);
+
+            remove_unrequired_parts_from_load_out(&project, &phase, &parts, &opts.path)?;
+
+            session.save(&project)?;
+        },
+        Command::SetPlacementOverride { object_path_patterns, place_override } => {
+            let mut project = session.load()?;
+
+            let modified = project::set_placement_override(&mut project, &object_path_patterns, place_override.into());
+
+            if modified {
+                session.save(&project)?;
+            }
+        },
+        Command::SetPlacementOrdering { phase: reference, placement_orderings } => {
+            let mut project = session.load()?;
+
+            let _all_parts = refresh_from_design_variants(&mut project, &opts.path, false, &NoProgress)?;
+
+            let modified = project::update_placement_orderings(&mut project, &reference, &placement_orderings)?;
+
+            if modified {
+                session.save(&project)?;
+            }
+        },
+        Command::SetRequiredArtifacts { process: process_name, artifacts } => {
+            let mut project = session.load()?;
+
+            let required_artifacts = artifacts.into_iter().map(Into::into).collect();
+
+            let modified = project::update_required_artifacts(&mut project, &process_name, required_artifacts)?;
+
+            if modified {
+                session.save(&project)?;
+            }
+        },
+        Command::SetOperationChecklist { process: process_name, operation, items } => {
+            let mut project = session.load()?;
+
+            let modified = checklist::set_operation_checklist(&mut project, &process_name, operation, items)?;
+
+            if modified {
+                session.save(&project)?;
+            }
+        },
+        Command::SetWorkInstructionsStyle { phase: reference, style } => {
+            let mut project = session.load()?;
+
+            let modified = project::update_work_instructions_style(&mut project, &reference, style.into())?;
+
+            if modified {
+                session.save(&project)?;
+            }
+        },
+        Command::SetPhaseTags { phase: reference, tag: tags, remove } => {
+            let mut project = session.load()?;
+
+            let modified = project::update_phase_tags(&mut project, &reference, &tags, &remove)?;
+
+            if modified {
+                session.save(&project)?;
+            }
+        },
+        Command::SetPhaseDependencies { phase: reference, depends_on } => {
+            let mut project = session.load()?;
+
+            let modified = project::update_phase_dependencies(&mut project, &reference, depends_on.into_iter().collect())?;
+
+            if modified {
+                session.save(&project)?;
+            }
+        },
+        Command::SetRotationNormalization { phase: reference, range, offsets } => {
+            let mut project = session.load()?;
+
+            let rotation_normalization = range.map(|range| {
+                let offsets = offsets
+                    .map(|offsets| stores::rotation_offsets::load_rotation_offsets(&opts.path.join(offsets)))
+                    .transpose()?
+                    .unwrap_or_default();
+
+                Ok::<_, anyhow::Error>(RotationNormalization { range: range.into(), offsets })
+            }).transpose()?;
+
+            let modified = project::update_rotation_normalization(&mut project, &reference, rotation_normalization)?;
+
+            if modified {
+                session.save(&project)?;
+            }
+        },
+        Command::SetFirstArticleInspection { phase: reference, required } => {
+            let mut project = session.load()?;
+
+            let modified = first_article::set_first_article_inspection_required(&mut project, &reference, required)?;
+
+            if modified {
+                session.save(&project)?;
+            }
+        },
+        Command::SetQuantityCheck { phase: reference, mode } => {
+            let mut project = session.load()?;
+
+            let modified = quantity_check::set_quantity_check(&mut project, &reference, mode.to_quantity_check_mode())?;
+
+            if modified {
+                session.save(&project)?;
+            }
+        },
+        Command::SetPhaseNozzles { phase: reference, heads, nozzles } => {
+            let mut project = session.load()?;
+
+            let nozzle_configuration = match nozzles.is_empty() {
+                true => None,
+                false => Some(NozzleConfiguration { heads, nozzles }),
+            };
+
+            let modified = project::update_nozzle_configuration(&mut project, &reference, nozzle_configuration)?;
+
+            if modified {
+                session.save(&project)?;
+            }
+        },
+        Command::ListPhases { tag: tags, pcb } => {
+            let project = session.load()?;
+
+            let pcb = pcb.map(|name| project.find_pcb_by_name(&name)).transpose()?;
+
+            let phases: Vec<&Phase> = project.phase_orderings.iter()
+                .map(|reference| project.phases.get(reference).unwrap())
+                .filter(|phase| phase.has_tags(&tags))
+                .filter(|phase| pcb.is_none_or(|pcb| project.find_phase_pcbs(&phase.reference).contains(&pcb)))
+                .collect();
+
+            for phase in phases.iter() {
+                let phase_tags: Vec<String> = phase.tags.iter().map(|(key, value)| format!("{}={}", key, value)).collect();
+                println!("{} process: {}, pcb_side: {:?}, tags: [{}]", phase.reference, phase.process, phase.pcb_side, phase_tags.join(", "));
+            }
+
+            info!("Listed phases. count: {}", phases.len());
+        },
+        Command::Status {} => {
+            let mut project = session.load()?;
+
+            // the project is not saved, the operation states are only updated for display
+            let _modified = project::update_phase_operation_states(&mut project);
+
+            print!("{}", status::build_project_status(&project));
+        },
+        Command::InspectPhase { phase } => {
+            let mut project = session.load()?;
+
+            let _modified = project::update_phase_operation_states(&mut project);
+
+            print!("{}", status::build_phase_status(&project, &phase)?);
+        },
+        Command::AnalyzeLoadOutReuse { other_projects } => {
+            let project = session.load()?;
+            let phase_load_out_item_map = load_phase_load_out_items(&project, &opts.path)?;
+
+            // the load-out sources of each project are relative to the directory of its project file
+            let other_projects = other_projects.iter().map(|other_project_file_path| {
+                let other_project_file_path = opts.path.join(other_project_file_path);
+                let other_project = project::load(&other_project_file_path)?;
+                let other_path = other_project_file_path.parent().unwrap().to_path_buf();
+                let other_phase_load_out_item_map = load_phase_load_out_items(&other_project, &other_path)?;
+
+                anyhow::Ok((other_project, other_phase_load_out_item_map))
+            }).collect::<anyhow::Result<Vec<_>>>()?;
+
+            let projects: Vec<(&Project, &BTreeMap<Reference, Vec<LoadOutItem>>)> = std::iter::once((&project, &phase_load_out_item_map))
+                .chain(other_projects.iter().map(|(other_project, other_phase_load_out_item_map)| (other_project, other_phase_load_out_item_map)))
+                .collect();
+
+            print!("{}", load_out_reuse::analyze_load_out_reuse(&projects));
+        },
+        Command::Analytics { format, file, other_projects } => {
+            let project = session.load()?;
+            let phase_operation_histories = analytics::load_phase_operation_histories(&project, &opts.path)?;
+
+            // the operation histories of each project are in the directory of its project file
+            let other_projects = other_projects.iter().map(|other_project_file_path| {
+                let other_project_file_path = opts.path.join(other_project_file_path);
+                let other_project = project::load(&other_project_file_path)?;
+                let other_path = other_project_file_path.parent().unwrap().to_path_buf();
+                let other_phase_operation_histories = analytics::load_phase_operation_histories(&other_project, &other_path)?;
+
+                anyhow::Ok((other_project, other_phase_operation_histories))
+            }).collect::<anyhow::Result<Vec<_>>>()?;
+
+            let projects: Vec<(&Project, &BTreeMap<Reference, Vec<OperationHistoryItem>>)> = std::iter::once((&project, &phase_operation_histories))
+                .chain(other_projects.iter().map(|(other_project, other_phase_operation_histories)| (other_project, other_phase_operation_histories)))
+                .collect();
+
+            let format: AnalyticsFormat = format.into();
+            let path = match file {
+                Some(file) => opts.path.join(file),
+                None => {
+                    let artifact_path = build_artifact_path(&opts.path)?;
+                    std::fs::create_dir_all(&artifact_path)?;
+                    artifact_path.join(analytics::build_analytics_file_name(project_name, format))
+                },
+            };
+
+            let records = analytics::build_analytics(&projects);
+            std::fs::write(&path, analytics::build_analytics_content(&records, format)?)?;
+
+            info!("Exported analytics. format: {}, path: {:?}, records: {}", format, path, records.len());
+        },
+        Command::Metrics { phase: reference, since, until } => {
+            let project = session.load()?;
+            if !project.phases.contains_key(&reference) {
+                return Err(PhaseError::UnknownPhase(reference).into())
+            }
+
+            let operation_history = operation_history::read_or_default(&operation_history::build_phase_log_path(&opts.path, &reference))?;
+            let filter = OperationHistoryFilter { since, until };
+            let metrics = operation_history::build_metrics(&filter.apply(&operation_history));
+
+            print_metrics(&reference, &metrics);
+        },
+        Command::SetPriceList { source } => {
+            let mut project = session.load()?;
+
+            let modified = project::update_price_list_source(&mut project, source);
+
+            if modified {
+                session.save(&project)?;
+            }
+        },
+        Command::SetInventory { source } => {
+            let mut project = session.load()?;
+
+            let modified = project::update_inventory_source(&mut project, source);
+
+            if modified {
+                session.save(&project)?;
+            }
+        },
+        Command::SetEstimation { placement_rates, feeder_setup_time, reflow_time } => {
+            let mut project = session.load()?;
+
+            let estimation_parameters = match (placement_rates.is_empty(), feeder_setup_time, reflow_time) {
+                (true, None, None) => None,
+                _ => Some(EstimationParameters {
+                    placement_rates: placement_rates.into_iter()
+                        .map(|placement_rate| (placement_rate.process, placement_rate.placements_per_hour))
+                        .collect(),
+                    feeder_setup_time: feeder_setup_time.unwrap_or_default(),
+                    reflow_time: reflow_time.unwrap_or_default(),
+                }),
+            };
+
+            let modified = project::update_estimation_parameters(&mut project, estimation_parameters);
+
+            if modified {
+                session.save(&project)?;
+            }
+        },
+        Command::GenerateArtifacts { signing_key, allow_missing_feeders, report_format } => {
+            let mut project = session.load()?;
+
+            let modified = project::update_phase_operation_states(&mut project);
+
+            let phase_load_out_item_map = load_phase_load_out_items(&project, &opts.path)?;
+            let price_list = load_price_list(&project, &opts.path)?;
+            let inventory = load_inventory(&project, &opts.path)?;
+
+            export::check_feeders(&project, &phase_load_out_item_map, allow_missing_feeders)?;
+
+            // saved before the artifacts are written, so that the artifacts are not older than the project
+            if modified {
+                session.save(&project)?;
+            }
+
+            let artifact_path = build_artifact_path(&opts.path)?;
+            std::fs::create_dir_all(&artifact_path)?;
+
+            let artifact_paths = project::generate_artifacts_with_progress(&project, &artifact_path, project_name, phase_load_out_item_map, price_list.as_ref(), inventory.as_ref(), report_format.into(), &progress::ProgressBarReporter::new())?;
+
+            if let Some(signing_key_path) = resolve_signing_key_path(signing_key)? {
+                let signing_key = signing::load_signing_key(&signing_key_path)?;
+                signing::sign_artifacts(&signing_key, &artifact_path, project_name, &artifact_paths)?;
+            }
+        },
+        Command::ExportBom { format, file, pcb } => {
+            let project = session.load()?;
+
+            let pcb = pcb.map(|name| project.find_pcb_by_name(&name)).transpose()?;
+
+            let format: BomFormat = format.into();
+            let path = match file {
+                Some(file) => opts.path.join(file),
+                None => {
+                    let artifact_path = build_artifact_path(&opts.path)?;
+                    std::fs::create_dir_all(&artifact_path)?;
+                    artifact_path.join(bom::build_bom_file_name(project_name, pcb, format))
+                },
+            };
+
+            let bom = bom::build_bom(&project, pcb);
+            std::fs::write(&path, bom::build_bom_content(&bom, format)?)?;
+
+            info!("Exported BOM. format: {}, path: {:?}, parts: {}", format, path, bom.items.len());
+        },
+        Command::GenerateCertificate { phase: reference, signing_key } => {
+            let project = session.load()?;
+
+            let signing_key = resolve_signing_key_path(signing_key)?;
+
+            generate_certificate(&project, &opts.path, &reference, signing_key.as_deref())?;
+        },
+        Command::PreviewArtifacts { max_lines } => {
+            let mut project = session.load()?;
+
+            let _modified = project::update_phase_operation_states(&mut project);
+
+            let phase_load_out_item_map = load_phase_load_out_items(&project, &opts.path)?;
+
+            let price_list = load_price_list(&project, &opts.path)?;
+            let inventory = load_inventory(&project, &opts.path)?;
+
+            let previews = project::preview_artifacts(&project, project_name, &phase_load_out_item_map, price_list.as_ref(), inventory.as_ref(), max_lines)?;
+
+            for preview in previews.iter() {
+                println!("==> {} ({} bytes{}) <==", preview.file_name, preview.size, if preview.truncated { ", truncated" } else { "" });
+                print!("{}", preview.content);
+                println!();
+            }
+        },
+        Command::Search { query } => {
+            let project = session.load()?;
+            let phase_load_out_item_map = load_phase_load_out_items(&project, &opts.path)?;
+
+            let results = search::search_project(&project, &phase_load_out_item_map, &query);
+
+            for (group, search_matches) in results.iter() {
+                println!("{} ({})", group, search_matches.len());
+                for search_match in search_matches.iter() {
+                    println!("  {} {}: {}", search_match.key, search_match.field, search_match.highlighted("[", "]"));
+                }
+            }
+
+            info!("Searched project. query: '{}', matches: {}", query, results.values().map(Vec::len).sum::<usize>());
+        },
+        Command::Validate {} => {
+            let project = session.load()?;
+
+            let artifact_path = build_artifact_path(&opts.path)?;
+
+            let validation_issues = project::validate_artifacts(&project, &project_file_path, &artifact_path)?;
+
+            if !validation_issues.is_empty() {
+                bail!("Required artifacts are missing or out of date. count: {}", validation_issues.len())
+            }
+
+            info!("Required artifacts are up to date.");
+        },
+        Command::AcknowledgeIssue { id, reason } => {
+            resolve_issue(&mut session, &opts.path, &id, IssueResolutionStatus::Acknowledged, reason)?;
+        },
+        Command::WaiveIssue { id, reason } => {
+            resolve_issue(&mut session, &opts.path, &id, IssueResolutionStatus::Waived, reason)?;
+        },
+        Command::Release {} => {
+            let mut project = session.load()?;
+
+            release::ensure_planning_modifiable(&project)?;
+
+            let _modified = project::update_phase_operation_states(&mut project);
+
+            let phase_load_out_item_map = load_phase_load_out_items(&project, &opts.path)?;
+            let price_list = load_price_list(&project, &opts.path)?;
+            let inventory = load_inventory(&project, &opts.path)?;
+
+            let health_summary = health::build_health_summary(&project, &phase_load_out_item_map);
+            if health_summary.errors > 0 {
+                bail!("The project has errors, resolve them before releasing. {}", health_summary)
+            }
+
+            let release = release::release(&mut project, OffsetDateTime::now_utc())?;
+
+            let snapshot_path = opts.path.join(RELEASES_DIRECTORY).join(&release.snapshot);
+            if snapshot_path.exists() {
+                bail!("Release snapshot already exists. path: {:?}", snapshot_path)
+            }
+
+            // saved before the artifacts are written, so that the artifacts are not older than the project
+            session.save(&project)?;
+
+            let file_count = match create_release_snapshot(&project, &project_file_path, &opts.path, project_name, &snapshot_path, phase_load_out_item_map, price_list.as_ref(), inventory.as_ref()) {
+                Ok(file_count) => file_count,
+                Err(error) => {
+                    // the project must not remain released without a release snapshot
+                    project.releases.pop();
+                    session.save(&project)?;
+                    if snapshot_path.exists() {
+                        std::fs::remove_dir_all(&snapshot_path)?;
+                    }
+                    warn!("Release rolled back. version: {}", release.version);
+                    return Err(error)
+                },
+            };
+
+            info!("Created release snapshot. version: {}, path: {:?}, files: {}", release.version, snapshot_path, file_count);
+        },
+        Command::Reopen {} => {
+            let mut project = session.load()?;
+
+            release::reopen(&mut project, OffsetDateTime::now_utc())?;
+
+            session.save(&project)?;
+        },
+        Command::Verify { verifying_key } => {
+            let verifying_key_path = match verifying_key {
+                Some(verifying_key_path) => verifying_key_path,
+                None => match preferences::load(&preferences::build_preferences_path()?)?.verifying_key {
+                    Some(verifying_key_path) => verifying_key_path,
+                    None => bail!("No verifying key, use '--verifying-key' or set the 'verifying-key' preference"),
+                },
+            };
+            let verifying_key = signing::load_verifying_key(&verifying_key_path)?;
+
+            let artifact_path = build_artifact_path(&opts.path)?;
+
+            signing::verify_artifacts(&verifying_key, &artifact_path, project_name)?;
+        },
+        Command::DiffProject { against, format } => {
+            let project = session.load()?;
+            let against_path = opts.path.join(against);
+            let previous_project = project::load(&against_path)?;
+
+            let diff = project::build_project_diff(&previous_project, &project)?;
+
+            match format {
+                DiffFormatArg::Text => print!("{}", diff),
+                DiffFormatArg::Json => println!("{}", serde_json::to_string_pretty(&diff)?),
+            }
+
+            info!("Diffed project. against: {:?}, placements: {}, parts: {}, phases: {}", against_path, diff.placements.len(), diff.part_states.len(), diff.phases.len());
+        },
+        Command::VerifyOperationHistory { phase, head } => {
+            let project = session.load()?;
+
+            let results = project::verify_operation_history(&project, &opts.path, phase.as_ref(), head.as_deref())?;
+
+            let mut failures = 0;
+            for (reference, result) in results {
+                match result {
+                    Ok(verification) => info!("Verified operation history. phase: '{}', items: {}, unchained_items: {}, head: {}",
+                        reference, verification.items, verification.unchained_items, verification.head.unwrap_or_default(),
+                    ),
+                    Err(reason) => {
+                        error!("Operation history verification failed. phase: '{}', cause: {}", reference, reason);
+                        failures += 1;
+                    }
+                }
+            }
+
+            if failures > 0 {
+                bail!("Operation history verification failed. phases: {}", failures)
+            }
+        },
+        Command::History { limit } => {
+            let items = audit::read(&audit::build_audit_log_path(project_name, &opts.path))?;
+
+            let skipped = limit.map_or(0, |limit| items.len().saturating_sub(limit));
+            for item in items.iter().skip(skipped) {
+                println!("{}", item);
+            }
+        },
+        Command::StartPhaseOperation { phase: reference, operation, confirmed, allow_insufficient_quantities } => {
+            let project = session.load()?;
+
+            let operation_checklist = checklist::find_operation_checklist(&project, &reference, &operation)?;
+            let confirmed = prompt_for_unconfirmed_items(&operation_checklist, confirmed)?;
+
+            let phase = project.phases.get(&reference)
+                .ok_or(PhaseError::UnknownPhase(reference.clone()))?;
+
+            if phase.quantity_check.is_some() && project.find_process(&phase.process)?.tracks_placements(&operation) {
+                let load_out_items = stores::load_out::load_items(&build_load_out_source(phase, &opts.path))?;
+
+                let blocked = matches!(phase.quantity_check, Some(QuantityCheckMode::Block))
+                    && !quantity_check::find_quantity_shortfalls(&project, &reference, &load_out_items).is_empty();
+                let overridden = allow_insufficient_quantities
+                    || (blocked && prompt_for_confirmation("Insufficient load-out item quantities, start the operation anyway")?);
+
+                quantity_check::check_quantities(&project, &reference, &load_out_items, overridden)?;
+            }
+
+            let preferences = preferences::load(&preferences::build_preferences_path()?)?;
+
+            checklist::record_operation_started(&project, &opts.path, &reference, operation, confirmed, preferences.get(PreferenceKey::Operator), OffsetDateTime::now_utc())?;
+        },
+        Command::RecordPhaseOperation { phase: reference, operation, set } => {
+            let mut project = session.load()?;
+
+            let completed_phases = find_completed_phases(&project);
+
+            let modified = project::update_phase_operation(&mut project, &opts.path, &reference, operation, set.into())?;
+
+            if modified {
+                session.save(&project)?;
+
+                generate_certificates_for_completed_phases(&project, &opts.path, &completed_phases)?;
+            }
+        },
+        Command::RecordFeederLoaded { phase: reference, feeder, alternate } => {
+            let mut project = session.load()?;
+
+            let phase = project.phases.get(&reference)
+                .ok_or(PhaseError::UnknownPhase(reference.clone()))?.clone();
+
+            let load_out_source = build_load_out_source(&phase, &opts.path);
+
+            let loaded_part = stores::load_out::set_loaded_alternate(&load_out_source, &feeder, alternate)?;
+            info!("Loaded part. phase: '{}', feeder: '{}', part: {:?}", reference, feeder, loaded_part);
+
+            let load_out_items = stores::load_out::load_items(&load_out_source)?;
+
+            project::record_feeder_loaded(&mut project, &opts.path, &reference, &feeder, &load_out_items)?;
+
+            session.save(&project)?;
+        },
+        Command::RecordPlacementsOperation { object_path_patterns, object_paths, operation } => {
+            let mut project = session.load()?;
+
+            let object_path_patterns: Vec<ObjectPathMatcher> = object_path_patterns.into_iter().map(ObjectPathMatcher::from)
+                .chain(object_paths.into_iter().map(ObjectPathMatcher::from))
+                .collect();
+
+            let original_counts = project::count_phase_part_placements(&project);
+            let completed_phases = find_completed_phases(&project);
+
+            let modified = project::update_placements_operation(&mut project, &opts.path, object_path_patterns, operation.into())?;
+
+            if modified {
+                save_project_and_consume_parts(&project, &mut session, &opts.path, &original_counts)?;
+
+                generate_certificates_for_completed_phases(&project, &opts.path, &completed_phases)?;
+            }
+        },
+        Command::Scan { phase: reference, mappings } => {
+            let mappings_path = mappings
+                .or_else(|| Some(PathBuf::from(stores::barcodes::BARCODE_MAPPINGS_FILE)).filter(|file| opts.path.join(file).exists()))
+                .map(|file| opts.path.join(file));
+
+            let mappings = match mappings_path {
+                Some(mappings_path) => stores::barcodes::load_barcode_mappings(&mappings_path)?,
+                None => BTreeMap::new(),
+            };
+
+            session.release();
+            scan::scan(&project_file_path, &opts.path, &reference, &mappings)?;
+        },
+        Command::RunPhase { phase: reference, machine } => {
+            let mut project = session.load()?;
+
+            let phase = project.phases.get(&reference)
+                .ok_or(PhaseError::UnknownPhase(reference.clone()))?.clone();
+
+            let placements: Vec<(ObjectPath, Placement)> = project.placements.iter()
+                .filter(|(_object_path, placement_state)| placement_state.phase.as_ref() == Some(&reference) && placement_state.place() && !placement_state.placed)
+                .map(|(object_path, placement_state)| (object_path.clone(), placement_state.placement.clone()))
+                .collect();
+            let object_path_patterns: Vec<ObjectPathMatcher> = placements.iter()
+                .map(|(object_path, _placement)| ObjectPathMatcher::from(ObjectPathPattern::from(object_path)))
+                .collect();
+
+            // checked before the machine places anything, since the placements are recorded as they are placed
+            project::ensure_first_articles_signed_off(&project, &object_path_patterns)?;
+
+            let load_out_items = stores::load_out::load_items(&build_load_out_source(&phase, &opts.path))?;
+
+            let original_counts = project::count_phase_part_placements(&project);
+            let completed_phases = find_completed_phases(&project);
+
+            let mut driver = machine::driver::build_driver(machine.into());
+            let machine_kind = driver.kind();
+
+            run_phase::run(driver.as_mut(), &load_out_items, &placements, &mut project,
+                |project, object_path| {
+                    let object_path_pattern = ObjectPathMatcher::from(ObjectPathPattern::from(object_path));
+                    project::update_placements_operation(project, &opts.path, vec![object_path_pattern], PlacementOperation::Placed)
+                },
+                |project, placed| {
+                    // the placements that were placed before a failure remain recorded
+                    if placed > 0 {
+                        save_project_and_consume_parts(project, &mut session, &opts.path, &original_counts)?;
+
+                        generate_certificates_for_completed_phases(project, &opts.path, &completed_phases)?;
+                    }
+
+                    info!("Ran phase. phase: '{}', machine: {}, placed: {}, remaining: {}", reference, machine_kind, placed, placements.len() - placed);
+
+                    Ok(())
+                },
+            )?;
+        },
+        Command::SetProductionQuantity { quantity } => {
+            let mut project = session.load()?;
+
+            if production_run::update_quantity(&mut project, quantity) {
+                session.save(&project)?;
+            }
+        },
+        Command::StartProductionRun { serial } => {
+            let mut project = session.load()?;
+
+            production_run::start(&mut project, serial, OffsetDateTime::now_utc())?;
+
+            session.save(&project)?;
+        },
+        Command::CompleteProductionRun { force } => {
+            let mut project = session.load()?;
+
+            production_run::complete(&mut project, OffsetDateTime::now_utc(), force)?;
+
+            session.save(&project)?;
+        },
+        Command::ProductionRuns {} => {
+            let project = session.load()?;
+
+            print!("{}", production_run::build_report(&project));
+        },
+        Command::RecordFirstArticleInspection { phase: reference } => {
+            let mut project = session.load()?;
+
+            let preferences = preferences::load(&preferences::build_preferences_path()?)?;
+
+            first_article::record_first_article_inspection(&mut project, &opts.path, &reference, preferences.get(PreferenceKey::Operator), OffsetDateTime::now_utc())?;
+
+            session.save(&project)?;
+        },
+        Command::AssignFeederToLoadOutItem { phase: reference, feeder_reference, manufacturer, mpn, quantity, reel, feeders } => {
+            let project = session.load()?;
+
+            let phase = project.phases.get(&reference)
+                .ok_or(PhaseError::UnknownPhase(reference))?.clone();
+
+            let process = project.find_process(&phase.process)?.clone();
+
+            let feeder_library = feeders
+                .map(|feeders| stores::feeders::load_feeders(&opts.path.join(feeders).to_string_lossy().to_string()))
+                .transpose()?;
+            let feeder = feeder_library.as_ref()
+                .map(|feeder_library| stores::feeders::find_feeder(feeder_library, &feeder_reference.to_string()))
+                .transpose()?;
+
+            let part_packages: BTreeMap<Part, String> = project.part_states.iter()
+                .filter_map(|(part, part_state)| part_state.details.package.clone().map(|package| (part.clone(), package)))
+                .collect();
+
+            stores::load_out::assign_feeder_to_load_out_item(&build_load_out_source(&phase, &opts.path), &process, &feeder_reference, manufacturer, mpn, quantity, reel, feeder, &part_packages)?;
+        },
+        Command::SetLoadOutAlternates { phase: reference, feeder, alternate } => {
+            let project = session.load()?;
+
+            let phase = project.phases.get(&reference)
+                .ok_or(PhaseError::UnknownPhase(reference))?.clone();
+
+            stores::load_out::set_load_out_item_alternates(&build_load_out_source(&phase, &opts.path), &feeder, alternate)?;
+        },
+        Command::RemoveLoadOutItem { phase: reference, feeder } => {
+            let project = session.load()?;
+
+            let phase = project.phases.get(&reference)
+                .ok_or(PhaseError::UnknownPhase(reference))?.clone();
 
-            project::save(&project, &project_file_path)?;
+            stores::load_out::remove_load_out_item(&build_load_out_source(&phase, &opts.path), &feeder)?;
         },
-        Command::SetPlacementOrdering { phase: reference, placement_orderings } => {
-            let mut project = project::load(&project_file_path)?;
+        Command::SetLoadOutItemQuantity { phase: reference, feeder, quantity } => {
+            let project = session.load()?;
 
-            let unique_design_variants = project.unique_design_variants();
-            let design_variant_placement_map = stores::placements::load_all_placements(&unique_design_variants, &opts.path)?;
-            let _all_parts = project::refresh_from_design_variants(&mut project, design_variant_placement_map);
+            let phase = project.phases.get(&reference)
+                .ok_or(PhaseError::UnknownPhase(reference))?.clone();
 
-            let modified = project::update_placement_orderings(&mut project, &reference, &placement_orderings)?;
+            stores::load_out::set_load_out_item_quantity(&build_load_out_source(&phase, &opts.path), &feeder, quantity)?;
+        },
+        Command::RenameFeeder { phase: reference, feeder, new_feeder } => {
+            let project = session.load()?;
 
-            if modified {
-                project::save(&project, &project_file_path)?;
+            let phase = project.phases.get(&reference)
+                .ok_or(PhaseError::UnknownPhase(reference))?.clone();
+
+            stores::load_out::rename_feeder(&build_load_out_source(&phase, &opts.path), &feeder, &new_feeder)?;
+        },
+        Command::SuggestFeeders { phase: reference, manufacturer, mpn, limit, feeders } => {
+            let project = session.load()?;
+
+            let phase = project.phases.get(&reference)
+                .ok_or(PhaseError::UnknownPhase(reference.clone()))?.clone();
+
+            let mut phase_load_out_item_map = load_phase_load_out_items(&project, &opts.path)?;
+            let load_out_items = phase_load_out_item_map.remove(&reference).unwrap();
+            let other_load_out_items: Vec<LoadOutItem> = phase_load_out_item_map.into_values().flatten().collect();
+
+            let parts: Vec<Part> = load_out_items.iter()
+                .filter(|item| manufacturer.is_match(&item.manufacturer) && mpn.is_match(&item.mpn))
+                .map(|item| Part::new(item.manufacturer.clone(), item.mpn.clone()))
+                .collect();
+
+            if parts.is_empty() {
+                return Err(FeederAssignmentError::NoMatchingPart { manufacturer, mpn }.into())
+            }
+
+            let feeder_library = feeders
+                .map(|feeders| stores::feeders::load_feeders(&opts.path.join(feeders).to_string_lossy().to_string()))
+                .transpose()?;
+
+            for part in parts.iter() {
+                let part_details = project.part_states.get(part).map(|part_state| &part_state.details);
+                let suggestions = pnp::load_out::suggest_feeders(part, part_details, &load_out_items, &other_load_out_items, feeder_library.as_deref(), limit);
+                for suggestion in suggestions.iter() {
+                    info!("Feeder suggestion. phase: '{}', part: {:?}, feeder: {}, reason: {:?}", phase.reference, part, suggestion.reference, suggestion.reason);
+                }
             }
         },
-        Command::GenerateArtifacts { } => {
-            let mut project = project::load(&project_file_path)?;
+        Command::ResetOperations { } => {
+            let mut project = session.load()?;
 
-            let modified = project::update_phase_operation_states(&mut project);
+            project::reset_operations(&mut project)?;
+            
+            session.save(&project)?;
+        },
+        Command::SetOperationTransitions { mode } => {
+            let mut project = session.load()?;
+
+            project.operation_transitions = mode.into();
+            info!(target: MUTATION_TARGET, mode = ?project.operation_transitions, message = "Set operation transitions. mode: {mode}");
+
+            let _modified = project::update_phase_operation_states(&mut project);
 
-            let phase_load_out_item_map = project.phases.iter().try_fold(BTreeMap::<Reference, Vec<LoadOutItem>>::new(), |mut map, (reference, phase) | {
-                let load_out_items = stores::load_out::load_items(&LoadOutSource::from_str(&phase.load_out_source).unwrap())?;
-                map.insert(reference.clone(), load_out_items);
-                Ok::<BTreeMap<Reference, Vec<LoadOutItem>>, anyhow::Error>(map)
-            })?;
+            session.save(&project)?;
+        },
+        Command::MigrateLoadOutSources { } => {
+            let mut project = session.load()?;
 
-            project::generate_artifacts(&project, &opts.path, &project_name, phase_load_out_item_map)?;
+            let modified = stores::load_out::migrate_load_out_sources(&mut project, &opts.path);
 
             if modified {
-                project::save(&project, &project_file_path)?;
+                session.save(&project)?;
             }
         },
-        Command::RecordPhaseOperation { phase: reference, operation, set } => {
-            let mut project = project::load(&project_file_path)?;
+        Command::RestoreLoadOut { phase: reference, backup, list } => {
+            let project = session.load()?;
 
-            let modified = project::update_phase_operation(&mut project, &opts.path, &reference, operation.into(), set.into())?;
+            let phase = project.phases.get(&reference)
+                .ok_or(PhaseError::UnknownPhase(reference))?;
 
-            if modified {
-                project::save(&project, &project_file_path)?;
+            let load_out_source = build_load_out_source(phase, &opts.path);
+            let Some(load_out_path) = load_out_source.path() else {
+                bail!("Load-out backups are only available for file load-outs. source: '{}'", load_out_source);
+            };
+
+            let backups = stores::backup::find_backups(&load_out_path)?;
+
+            if list {
+                for backup_path in backups.iter() {
+                    println!("{}", backup_path.file_name().unwrap().to_string_lossy());
+                }
+                return Ok(())
             }
+
+            let backup_path = match backup {
+                Some(backup) => stores::backup::build_backup_dir(&load_out_path).join(backup),
+                None => match backups.last() {
+                    Some(backup_path) => backup_path.clone(),
+                    None => bail!("No load-out backups. load_out: {:?}", load_out_path),
+                },
+            };
+
+            stores::backup::restore(&load_out_path, &backup_path)?;
         },
-        Command::RecordPlacementsOperation { object_path_patterns, operation } => {
-            let mut project = project::load(&project_file_path)?;
+        Command::RenamePart { manufacturer, mpn, new_manufacturer, new_mpn, files, dry_run } => {
+            let mut project = session.load()?;
 
-            let modified = project::update_placements_operation(&mut project, &opts.path, object_path_patterns, operation.into())?;
+            let from = Part::new(manufacturer, mpn);
+            let to = Part::new(new_manufacturer, new_mpn);
 
-            if modified {
-                project::save(&project, &project_file_path)?;
+            let object_paths = project::rename_part(&mut project, &from, &to)?;
+
+            let mut phases: BTreeSet<Reference> = object_paths.iter()
+                .filter_map(|object_path| project.placements.get(object_path).unwrap().phase.clone())
+                .collect();
+
+            let mut file_changes: Vec<FileChange> = vec![];
+
+            let mut paths: Vec<PathBuf> = project.unique_design_variants().iter()
+                .map(|design_variant| stores::placements::build_placements_path(design_variant, &opts.path))
+                .collect();
+            paths.extend(files.iter().map(|file| opts.path.join(file)));
+
+            for path in paths.iter() {
+                if let Some(file_change) = part_rename::rename_part_in_csv(path, &from, &to)? {
+                    file_changes.push(file_change);
+                }
+            }
+
+            // phases can share a load-out
+            let mut load_out_phases: BTreeMap<LoadOutSource, Vec<Reference>> = BTreeMap::new();
+            for (reference, phase) in project.phases.iter() {
+                load_out_phases.entry(build_load_out_source(phase, &opts.path)).or_default().push(reference.clone());
             }
+
+            let mut load_out_paths: Vec<PathBuf> = vec![];
+            // load-outs that are not files, e.g. on a web server, are renamed using their repository
+            let mut load_out_changes: Vec<(LoadOutSource, Vec<RecordChange>, Vec<LoadOutItem>)> = vec![];
+            for (load_out_source, references) in load_out_phases {
+                match load_out_source.path() {
+                    Some(load_out_path) => {
+                        if let Some(file_change) = part_rename::rename_part_in_csv(&load_out_path, &from, &to)? {
+                            phases.extend(references);
+                            file_changes.push(file_change);
+                            load_out_paths.push(load_out_path);
+                        }
+                    },
+                    None => {
+                        let load_out_items = stores::load_out::load_items(&load_out_source)?;
+                        if let Some((changes, load_out_items)) = part_rename::rename_part_in_load_out_items(load_out_items, &from, &to)? {
+                            phases.extend(references);
+                            load_out_changes.push((load_out_source, changes, load_out_items));
+                        }
+                    },
+                }
+            }
+
+            let mut inventory_path = None;
+            if let Some(inventory_source) = &project.inventory_source {
+                let path = opts.path.join(inventory_source);
+                if let Some(file_change) = part_rename::rename_part_in_csv(&path, &from, &to)? {
+                    file_changes.push(file_change);
+                    inventory_path = Some(path);
+                }
+            }
+
+            if dry_run {
+                println!("==> {} <==", project_file_path.display());
+                println!("-part: {}, {}", from.manufacturer, from.mpn);
+                println!("+part: {}, {}", to.manufacturer, to.mpn);
+                for object_path in object_paths.iter() {
+                    println!("-{}: {}, {}", object_path, from.manufacturer, from.mpn);
+                    println!("+{}: {}, {}", object_path, to.manufacturer, to.mpn);
+                }
+
+                for file_change in file_changes.iter() {
+                    println!("==> {} <==", file_change.path.display());
+                    for change in file_change.changes.iter() {
+                        println!("-{}", change.before);
+                        println!("+{}", change.after);
+                    }
+                }
+                for (load_out_source, changes, _load_out_items) in load_out_changes.iter() {
+                    println!("==> {} <==", load_out_source);
+                    for change in changes.iter() {
+                        println!("-{}", change.before);
+                        println!("+{}", change.after);
+                    }
+                }
+                return Ok(())
+            }
+
+            for load_out_path in load_out_paths.iter().chain(inventory_path.iter()) {
+                stores::backup::backup_before_modification(load_out_path)?;
+            }
+
+            // stored first, the files cannot be written if a load-out cannot be stored
+            for (load_out_source, _changes, load_out_items) in load_out_changes.iter() {
+                stores::load_out::store_items(load_out_source, load_out_items)?;
+            }
+
+            let mut contents: Vec<(PathBuf, Vec<u8>)> = file_changes.into_iter()
+                .map(|file_change| (file_change.path, file_change.content))
+                .collect();
+            contents.push((project_file_path.clone(), project::serialize(&project)?));
+
+            part_rename::write_files(&contents)?;
+
+            project::record_part_renamed(&opts.path, &phases, &from, &to)?;
+            session.invalidate();
+        },
+        Command::Watch { interval, max_refreshes } => {
+            session.release();
+            watch::watch(&project_file_path, &opts.path, Duration::from_secs(interval), max_refreshes)?;
+        },
+        Command::Dashboard { listen } => {
+            session.release();
+            dashboard::serve(listen, project_name, &project_file_path, &opts.path)?;
         },
-        Command::AssignFeederToLoadOutItem { phase: reference, feeder_reference, manufacturer, mpn } => {
-            let project = project::load(&project_file_path)?;
+        Command::Report { command: ReportCommand::Site { into } } => {
+            let artifact_path = build_artifact_path(&opts.path)?;
+            let into = into.unwrap_or_else(|| artifact_path.join(format!("{}_report_site", project_name)));
 
-            let phase = project.phases.get(&reference)
-                .ok_or(PhaseError::UnknownPhase(reference))?.clone();
+            report_site::generate(project_name, &session.load()?, &artifact_path, &into)?;
+        },
+        Command::Maintenance { command: MaintenanceCommand::Cleanup { max_count, max_age_days, dry_run } } => {
+            let preferences = preferences::load(&preferences::build_preferences_path()?)?;
 
-            let process = project.find_process(&phase.process)?.clone();
-            
-            stores::load_out::assign_feeder_to_load_out_item(&phase, &process, &feeder_reference, manufacturer, mpn)?;
+            let default_policy = RetentionPolicy::default();
+            let policy = RetentionPolicy {
+                max_count: max_count.or(preferences.backup_max_count).unwrap_or(default_policy.max_count),
+                max_age: max_age_days.or(preferences.backup_max_age_days)
+                    .map(|days| Duration::from_secs(u64::from(days) * 60 * 60 * 24))
+                    .or(default_policy.max_age),
+            };
+            info!("Cleaning up backups. policy: {:?}, dry_run: {}", policy, dry_run);
+
+            let project = session.load()?;
+
+            // the backups of the load-outs are alongside the load-outs, which may be outside the project directory
+            let backup_dirs: BTreeSet<PathBuf> = std::iter::once(stores::backup::build_backup_dir(&project_file_path))
+                .chain(project.phases.values().filter_map(|phase| {
+                    let load_out_path = build_load_out_source(phase, &opts.path).path()?;
+                    Some(stores::backup::build_backup_dir(&load_out_path))
+                }))
+                .collect();
+
+            let now = SystemTime::now();
+            let mut summary = CleanupSummary::default();
+            for backup_dir in backup_dirs.iter() {
+                let dir_summary = stores::backup::cleanup(backup_dir, &policy, now, dry_run)?;
+                summary.removed.extend(dir_summary.removed);
+                summary.reclaimed_bytes += dir_summary.reclaimed_bytes;
+            }
+
+            if dry_run {
+                for backup_path in summary.removed.iter() {
+                    println!("{}", backup_path.to_string_lossy());
+                }
+                println!("Would remove {} backups, reclaiming {} bytes", summary.removed.len(), summary.reclaimed_bytes);
+            } else {
+                println!("Removed {} backups, reclaimed {} bytes", summary.removed.len(), summary.reclaimed_bytes);
+            }
         },
-        Command::ResetOperations { } => {
-            let mut project = project::load(&project_file_path)?;
+        Command::Config { command } => {
+            let preferences_path = preferences::build_preferences_path()?;
+            let mut preferences = preferences::load(&preferences_path)?;
 
-            project::reset_operations(&mut project)?;
-            
-            project::save(&project, &project_file_path)?;
+            match command {
+                ConfigCommand::Get { key } => {
+                    if let Some(value) = preferences.get(key.into()) {
+                        println!("{}", value);
+                    }
+                },
+                ConfigCommand::Set { key, value } => {
+                    preferences.set(key.into(), Some(value))?;
+                    preferences::save(&preferences_path, &preferences)?;
+                },
+                ConfigCommand::Unset { key } => {
+                    preferences.set(key.into(), None)?;
+                    preferences::save(&preferences_path, &preferences)?;
+                },
+                ConfigCommand::List {} => {
+                    for key in PreferenceKey::ALL {
+                        if let Some(value) = preferences.get(key) {
+                            println!("{} = {}", key, value);
+                        }
+                    }
+                },
+            }
+        },
+        Command::Example { command: ExampleCommand::Generate { into } } => {
+            example::generate(project_name, &into)?;
+        },
+    }
+
+    // not recorded for commands without a project, e.g. `example generate`
+    if project::build_project_file_path(project_name, &opts.path).exists() {
+        record_audit_log_item(project_name, &opts.path, &command_name, mutations.messages())?;
+    }
+
+    if show_health_summary && session.exists() {
+        match session.load() {
+            Ok(project) => print_health_summary(&project, &opts.path),
+            Err(reason) => debug!("Unable to build health summary. cause: {}", reason),
+        }
+    }
+
+    Ok(())
+}
+
+impl Command {
+    /// Commands that change the planning data, which is frozen when the project is released.
+    ///
+    /// Recording operations, generating artifacts and rework are production activities, they are allowed.
+    fn modifies_planning(&self) -> bool {
+        matches!(self,
+            Command::AddPcb { .. } | Command::SetPanelGeometry { .. } | Command::SetPcbDimensions { .. } | Command::DiscoverVariants { register: true, .. } | Command::AssignVariantToUnit { .. }
+            | Command::UnitAssignments { command: UnitAssignmentsCommand::Import { .. } }
+            | Command::AcknowledgeDesignChanges { .. } | Command::AssignProcessToParts { .. } | Command::SetMoistureSensitivity { .. }
+            | Command::ImportPartDetails { .. } | Command::CreatePhase { .. } | Command::ClonePhase { .. } | Command::RemovePhase { .. }
+            | Command::RenamePhase { .. } | Command::ReorderPhases { .. }
+            | Command::AssignPlacementsToPhase { .. } | Command::UnassignPlacementsFromPhase { .. } | Command::SetPlacementOverride { .. } | Command::AssignFeederToLoadOutItem { .. } | Command::SetLoadOutAlternates { .. }
+            | Command::RemoveLoadOutItem { .. } | Command::SetLoadOutItemQuantity { .. } | Command::RenameFeeder { .. }
+            | Command::SetPlacementOrdering { .. }
+            | Command::SetRequiredArtifacts { .. } | Command::SetOperationChecklist { .. } | Command::SetWorkInstructionsStyle { .. } | Command::SetPhaseTags { .. } | Command::SetPhaseDependencies { .. } | Command::SetRotationNormalization { .. }
+            | Command::SetPriceList { .. } | Command::SetInventory { .. } | Command::SetEstimation { .. } | Command::SetProductionQuantity { .. } | Command::SetFirstArticleInspection { .. } | Command::SetPhaseNozzles { .. } | Command::SetQuantityCheck { .. }
+            | Command::SetOperationTransitions { .. } | Command::MigrateLoadOutSources { .. } | Command::RestoreLoadOut { list: false, .. }
+            | Command::RenamePart { dry_run: false, .. } | Command::Watch { .. }
+        )
+    }
+
+    /// Commands that are not about the project, or whose output is used by other tools, do not show the health summary.
+    fn shows_health_summary(&self) -> bool {
+        !matches!(self,
+            Command::PreviewArtifacts { .. } | Command::ListPhases { .. } | Command::DiffProject { .. } | Command::History { .. } | Command::Dashboard { .. } | Command::Report { .. } | Command::Maintenance { .. } | Command::Config { .. } | Command::Example { .. }
+        )
+    }
+}
+
+/// The name of the command, including the names of any nested commands, e.g. 'unit-assignments import'.
+fn build_command_name(matches: &clap::ArgMatches) -> String {
+    let mut names = vec![];
+    let mut current = matches;
+    while let Some((name, sub_matches)) = current.subcommand() {
+        names.push(name);
+        current = sub_matches;
+    }
+
+    names.join(" ")
+}
+
+/// Refreshes the project from the placements of the design variants, after checking the design revisions, returns
+/// all the parts.
+fn refresh_from_design_variants(project: &mut Project, path: &Path, force: bool, progress: &dyn ProgressReporter) -> anyhow::Result<Vec<Part>> {
+    let unique_design_variants = project.unique_design_variants();
+    let design_revisions = stores::placements::build_design_revisions(&unique_design_variants, path)?;
+    project::check_design_revisions(project, &design_revisions)?;
+    let design_variant_placement_map = stores::placements::load_all_placements(&unique_design_variants, path)?;
+
+    Ok(project::refresh_from_design_variants_with_progress(project, design_variant_placement_map, force, progress)?)
+}
+
+/// The project of the command, loaded at most once, so that the release check, the audit log and the health summary
+/// do not each load the project file again.
+///
+/// The exclusive lock of the project file is held from the first load, or save, until the session is dropped or
+/// released, so that other processes cannot change the project in between, see `locking`.
+struct ProjectSession {
+    project_file_path: PathBuf,
+    /// The project as last loaded or saved.
+    project: Option<Project>,
+    lock: Option<ProjectLock>,
+}
+
+impl ProjectSession {
+    fn new(project_file_path: PathBuf) -> Self {
+        Self {
+            project_file_path,
+            project: None,
+            lock: None,
+        }
+    }
+
+    fn lock(&mut self) -> anyhow::Result<&ProjectLock> {
+        match &mut self.lock {
+            Some(lock) => Ok(lock),
+            lock @ None => Ok(lock.insert(locking::lock(&self.project_file_path, LockMode::Exclusive)?)),
+        }
+    }
+
+    fn exists(&self) -> bool {
+        self.project.is_some() || self.project_file_path.exists()
+    }
+
+    fn load(&mut self) -> anyhow::Result<Project> {
+        if let Some(project) = &self.project {
+            return Ok(project.clone())
+        }
+
+        // a lock file is not created when there is no project file
+        if !self.project_file_path.exists() {
+            return project::load(&self.project_file_path)
+        }
+
+        let project_file_path = self.project_file_path.clone();
+        let project = project::load_locked(&project_file_path, self.lock()?)?;
+        self.project = Some(project.clone());
+        Ok(project)
+    }
+
+    fn save(&mut self, project: &Project) -> anyhow::Result<()> {
+        let project_file_path = self.project_file_path.clone();
+        project::save_locked(project, &project_file_path, self.lock()?)?;
+        self.project = Some(project.clone());
+        Ok(())
+    }
+
+    /// Used after the project file was written other than by the session, e.g. by `rename-part`, while holding the lock,
+    /// so that it is loaded again.
+    fn invalidate(&mut self) {
+        self.project = None;
+    }
+
+    /// Used before commands that load and save the project themselves, e.g. `watch`, so that they can lock the project
+    /// and so that the project is loaded again afterwards.
+    fn release(&mut self) {
+        self.project = None;
+        self.lock = None;
+    }
+}
+
+/// Records the changes made by the command, the messages of its mutation events, in the audit log, commands that did not
+/// change anything are not recorded.
+fn record_audit_log_item(project_name: &str, path: &Path, command_name: &str, changes: Vec<String>) -> anyhow::Result<()> {
+    if changes.is_empty() {
+        return Ok(())
+    }
+
+    let item = AuditLogItem {
+        date_time: OffsetDateTime::now_utc(),
+        user: audit::find_user(),
+        command: command_name.to_string(),
+        changes,
+    };
+
+    audit::append(&audit::build_audit_log_path(project_name, path), &item)
+}
+
+/// Prints a one-line summary of the remaining setup work to stderr, so that the output of commands is unchanged.
+///
+/// The summary is informational, it is not shown if it cannot be built, e.g. if a load-out is missing.
+fn print_health_summary(project: &Project, path: &Path) {
+    // the logging of loading the load-outs would repeat the logging of the command
+    let result = tracing::subscriber::with_default(tracing::subscriber::NoSubscriber::default(), || {
+        let phase_load_out_item_map = load_phase_load_out_items(project, path)?;
+
+        anyhow::Ok(health::build_health_summary(project, &phase_load_out_item_map))
+    });
+
+    match result {
+        Ok(health_summary) => eprintln!("{}", health_summary),
+        Err(reason) => debug!("Unable to build health summary. cause: {}", reason),
+    }
+}
+
+fn print_metrics(reference: &Reference, metrics: &OperationHistoryMetrics) {
+    println!("Phase: {}", reference);
+    println!("History items: {}", metrics.items);
+    println!("Placed: {}, unplaced: {}, inspection failed: {}", metrics.placed, metrics.unplaced, metrics.inspection_failed);
+    if let (Some(first_placed_at), Some(last_placed_at)) = (metrics.first_placed_at, metrics.last_placed_at) {
+        println!("Placing: {} - {}", first_placed_at, last_placed_at);
+    }
+    match metrics.placements_per_hour {
+        Some(placements_per_hour) => println!("Placements per hour: {}", placements_per_hour),
+        None => println!("Placements per hour: -"),
+    }
+    for operation_metrics in metrics.operations.iter() {
+        println!("Operation: {}, cycles: {}, mean time: {}, total time: {}",
+            operation_metrics.operation,
+            operation_metrics.cycles,
+            FormattedDuration(u32::try_from(operation_metrics.mean_time).unwrap_or_default()),
+            FormattedDuration(u32::try_from(operation_metrics.total_time).unwrap_or_default()),
+        );
+    }
+}
+
+fn find_completed_phases(project: &Project) -> BTreeSet<Reference> {
+    project.phase_orderings.iter()
+        .filter(|reference| certificate::is_phase_complete(project, reference))
+        .cloned()
+        .collect()
+}
+
+/// Generates a certificate for each phase that is complete now, but was not complete before, signed using the
+/// signing key from the user preferences, if set.
+fn generate_certificates_for_completed_phases(project: &Project, path: &Path, previously_completed_phases: &BTreeSet<Reference>) -> anyhow::Result<()> {
+    let completed_phases = find_completed_phases(project);
+    let mut newly_completed_phases = completed_phases.difference(previously_completed_phases).peekable();
+    if newly_completed_phases.peek().is_none() {
+        return Ok(())
+    }
+
+    let signing_key = resolve_signing_key_path(None)?;
+
+    for reference in newly_completed_phases {
+        info!("Phase completed. phase: '{}'", reference);
+        generate_certificate(project, path, reference, signing_key.as_deref())?;
+    }
+
+    Ok(())
+}
+
+/// Prompts for each checklist item that has not been confirmed, when the planner is used interactively.
+///
+/// When stdin is not a terminal, e.g. in scripts, the items must be confirmed using the arguments.
+fn prompt_for_unconfirmed_items(checklist: &[String], confirmed: Vec<String>) -> anyhow::Result<Vec<String>> {
+    let mut confirmed = confirmed;
+
+    let stdin = std::io::stdin();
+    if !stdin.is_terminal() {
+        return Ok(confirmed)
+    }
+
+    for item in checklist.iter().filter(|item| !confirmed.contains(item)).cloned().collect::<Vec<String>>() {
+        eprint!("Confirm '{}' [y/N]: ", item);
+        std::io::stderr().flush()?;
+
+        let mut answer = String::new();
+        stdin.read_line(&mut answer)?;
+
+        if answer.trim().eq_ignore_ascii_case("y") {
+            confirmed.push(item);
+        }
+    }
+
+    Ok(confirmed)
+}
+
+/// Prompts for a yes/no answer, when the planner is used interactively, the answer is 'no' when stdin is not a terminal.
+fn prompt_for_confirmation(question: &str) -> anyhow::Result<bool> {
+    let stdin = std::io::stdin();
+    if !stdin.is_terminal() {
+        return Ok(false)
+    }
+
+    eprint!("{} [y/N]: ", question);
+    std::io::stderr().flush()?;
+
+    let mut answer = String::new();
+    stdin.read_line(&mut answer)?;
+
+    Ok(answer.trim().eq_ignore_ascii_case("y"))
+}
+
+/// The operator preference is recorded with the resolution.
+fn resolve_issue(session: &mut ProjectSession, path: &Path, id: &str, status: IssueResolutionStatus, reason: String) -> anyhow::Result<()> {
+    let mut project = session.load()?;
+
+    let phase_load_out_item_map = load_phase_load_out_items(&project, path)?;
+    let price_list = load_price_list(&project, path)?;
+    let inventory = load_inventory(&project, path)?;
+    let issues = project::build_project_issues(&project, &phase_load_out_item_map, price_list.as_ref(), inventory.as_ref())?;
+
+    let preferences = preferences::load(&preferences::build_preferences_path()?)?;
+
+    issue::resolve_issue(&mut project, &issues, id, status, reason, preferences.get(PreferenceKey::Operator), OffsetDateTime::now_utc())?;
+
+    session.save(&project)?;
+
+    Ok(())
+}
+
+/// Writes the JSON and Markdown certificates to the artifact directory, the operator preference is used as the issuer.
+fn generate_certificate(project: &Project, path: &Path, reference: &Reference, signing_key_path: Option<&Path>) -> anyhow::Result<()> {
+    let operation_history = operation_history::read_or_default(&operation_history::build_phase_log_path(path, reference))?;
+
+    let preferences = preferences::load(&preferences::build_preferences_path()?)?;
+
+    let certificate = certificate::build_phase_certificate(project, reference, &operation_history, preferences.get(PreferenceKey::Operator), OffsetDateTime::now_utc())?;
+
+    let artifact_path = build_artifact_path(path)?;
+    std::fs::create_dir_all(&artifact_path)?;
+
+    let json_path = artifact_path.join(certificate::build_certificate_json_file_name(reference));
+    std::fs::write(&json_path, certificate::serialize_certificate(&certificate)?)?;
+
+    let markdown_path = artifact_path.join(certificate::build_certificate_markdown_file_name(reference));
+    std::fs::write(&markdown_path, certificate::build_certificate_markdown(&certificate))?;
+
+    if let Some(signing_key_path) = signing_key_path {
+        let signing_key = signing::load_signing_key(signing_key_path)?;
+        for certificate_path in [&json_path, &markdown_path] {
+            signing::sign_file(&signing_key, certificate_path)?;
+        }
+    }
+
+    info!("Generated phase certificate. phase: '{}', path: {:?}, signed: {}, outstanding_issues: {}", reference, json_path, signing_key_path.is_some(), certificate.outstanding_issues.len());
+
+    Ok(())
+}
+
+/// The directory the artifacts are written to, from the user preferences, defaults to the project directory.
+fn build_artifact_path(path: &Path) -> anyhow::Result<PathBuf> {
+    let preferences = preferences::load(&preferences::build_preferences_path()?)?;
+
+    Ok(preferences.resolve_artifact_directory(path))
+}
+
+/// The signing key file, from the user preferences if not specified, `None` if artifacts are not to be signed.
+fn resolve_signing_key_path(signing_key_path: Option<PathBuf>) -> anyhow::Result<Option<PathBuf>> {
+    match signing_key_path {
+        Some(signing_key_path) => Ok(Some(signing_key_path)),
+        None => Ok(preferences::load(&preferences::build_preferences_path()?)?.signing_key),
+    }
+}
+
+/// Generates and validates the artifacts of the released project, then copies the project files and the artifacts
+/// into the release snapshot, returns the count of copied files.
+#[allow(clippy::too_many_arguments)]
+fn create_release_snapshot(project: &Project, project_file_path: &Path, path: &Path, project_name: &str, snapshot_path: &Path, phase_load_out_item_map: BTreeMap<Reference, Vec<LoadOutItem>>, price_list: Option<&PriceList>, inventory: Option<&Inventory>) -> anyhow::Result<usize> {
+    let artifact_path = build_artifact_path(path)?;
+    std::fs::create_dir_all(&artifact_path)?;
+
+    let artifact_paths = project::generate_artifacts_with_progress(project, &artifact_path, project_name, phase_load_out_item_map, price_list, inventory, ReportFormat::Json, &progress::ProgressBarReporter::new())?;
+
+    let validation_issues = project::validate_artifacts(project, project_file_path, &artifact_path)?;
+    if !validation_issues.is_empty() {
+        bail!("Required artifacts are missing or out of date, the project was not released. count: {}", validation_issues.len())
+    }
+
+    let mut files = find_project_files_to_copy(project, path, snapshot_path);
+    files.push((project_file_path.to_path_buf(), snapshot_path.join(project_file_path.file_name().unwrap())));
+    for artifact_path in artifact_paths.iter() {
+        files.push((artifact_path.clone(), snapshot_path.join(artifact_path.file_name().unwrap())));
+    }
+
+    std::fs::create_dir_all(snapshot_path)?;
+    for (from, to) in files.iter() {
+        std::fs::copy(from, to)?;
+        debug!("Copied file. from: {:?}, to: {:?}", from, to);
+    }
+
+    Ok(files.len())
+}
+
+/// Finds the design variant placements and project-relative load-outs that need copying when the project is cloned
+/// into another directory, returns pairs of paths, from and to.
+fn find_project_files_to_copy(project: &Project, path: &Path, into: &Path) -> Vec<(PathBuf, PathBuf)> {
+    let mut files: Vec<(PathBuf, PathBuf)> = vec![];
+
+    for design_variant in project.unique_design_variants().iter() {
+        files.push((
+            stores::placements::build_placements_path(design_variant, path),
+            stores::placements::build_placements_path(design_variant, into),
+        ));
+    }
+
+    // load-outs that are not files, e.g. URLs, are shared
+    for phase in project.phases.values() {
+        let load_out_source = LoadOutSource::from_str(&phase.load_out_source).unwrap();
+        if let (Some(from), Some(to)) = (load_out_source.resolve(path).path(), load_out_source.resolve(into).path()) {
+            files.push((from, to));
+        }
+    }
+
+    // absolute load-outs are shared, phases can share load-outs
+    files.retain(|(from, to)| std::path::absolute(from).ok().ne(&std::path::absolute(to).ok()));
+    files.sort();
+    files.dedup();
+
+    files
+}
+
+/// Builds the load-out source of the phase, resolved using the project directory.
+/// Phases that share a load-out share the feeders, see `planning::load_out_sharing` for the conflicts this can cause.
+fn log_shared_load_out(project: &Project, reference: &Reference, load_out_source: &LoadOutSource, path: &Path) {
+    let phases = stores::load_out::find_load_out_phases(project, load_out_source, path);
+    if phases.len() > 1 {
+        info!("Phase shares a load-out. phase: '{}', source: '{}', phases: [{}]", reference, load_out_source, phases.iter().map(Reference::to_string).collect::<Vec<_>>().join(", "));
+    }
+}
+
+fn build_load_out_source(phase: &Phase, path: &Path) -> LoadOutSource {
+    LoadOutSource::from_str(&phase.load_out_source).unwrap().resolve(path)
+}
+
+fn load_phase_load_out_items(project: &Project, path: &Path) -> anyhow::Result<BTreeMap<Reference, Vec<LoadOutItem>>> {
+    project.phases.iter().try_fold(BTreeMap::<Reference, Vec<LoadOutItem>>::new(), |mut map, (reference, phase) | {
+        let load_out_items = stores::load_out::load_items(&build_load_out_source(phase, path))?;
+        map.insert(reference.clone(), load_out_items);
+        Ok(map)
+    })
+}
+
+/// Removes the parts that are no longer required by the phase from its load-out, unless they are required by another
+/// phase that shares the load-out.
+fn remove_unrequired_parts_from_load_out(project: &Project, phase: &Phase, parts: &BTreeSet<Part>, path: &Path) -> anyhow::Result<()> {
+    let load_out_source = build_load_out_source(phase, path);
+
+    let phase_part_counts = project::count_phase_part_placements(project);
+    let shared_parts: BTreeSet<&Part> = stores::load_out::find_load_out_phases(project, &load_out_source, path).iter()
+        .filter_map(|reference| phase_part_counts.get(reference))
+        .flat_map(|part_counts| part_counts.keys())
+        .collect();
+
+    let parts: BTreeSet<Part> = parts.iter()
+        .filter(|part| !shared_parts.contains(part))
+        .cloned()
+        .collect();
+
+    if parts.is_empty() {
+        return Ok(())
+    }
+
+    let removed = stores::load_out::remove_parts_from_load_out(&load_out_source, &parts)?;
+    info!("Removed parts from load-out. phase: '{}', source: '{}', parts: {}", phase.reference, load_out_source, removed.len());
+
+    Ok(())
+}
+
+/// `None` if the project has no price list.
+fn load_price_list(project: &Project, path: &Path) -> anyhow::Result<Option<PriceList>> {
+    project.price_list_source.as_ref()
+        .map(|price_list_source| stores::pricing::load_price_list(&path.join(price_list_source)))
+        .transpose()
+}
+
+/// `None` if the project has no inventory.
+fn load_inventory(project: &Project, path: &Path) -> anyhow::Result<Option<Inventory>> {
+    project.inventory_source.as_ref()
+        .map(|inventory_source| stores::inventory::load_inventory(&path.join(inventory_source)))
+        .transpose()
+}
+
+/// The parts of each phase that have been placed since the `original_counts` were made.
+fn count_consumed_parts(counts: &BTreeMap<Reference, BTreeMap<Part, PartPlacementCounts>>, original_counts: &BTreeMap<Reference, BTreeMap<Part, PartPlacementCounts>>) -> BTreeMap<Reference, BTreeMap<Part, u32>> {
+    count_changed_parts(counts, original_counts, |placed, original_placed| placed.saturating_sub(original_placed))
+}
+
+/// The parts of each phase that have been unplaced since the `original_counts` were made.
+fn count_returned_parts(counts: &BTreeMap<Reference, BTreeMap<Part, PartPlacementCounts>>, original_counts: &BTreeMap<Reference, BTreeMap<Part, PartPlacementCounts>>) -> BTreeMap<Reference, BTreeMap<Part, u32>> {
+    count_changed_parts(counts, original_counts, |placed, original_placed| original_placed.saturating_sub(placed))
+}
+
+/// `change` is given the placed count and the original placed count of each part.
+fn count_changed_parts(counts: &BTreeMap<Reference, BTreeMap<Part, PartPlacementCounts>>, original_counts: &BTreeMap<Reference, BTreeMap<Part, PartPlacementCounts>>, change: impl Fn(u32, u32) -> u32) -> BTreeMap<Reference, BTreeMap<Part, u32>> {
+    counts.iter().map(|(reference, part_counts)| {
+        let changed: BTreeMap<Part, u32> = part_counts.iter().filter_map(|(part, part_count)| {
+            let original_placed = original_counts.get(reference)
+                .and_then(|original_part_counts| original_part_counts.get(part))
+                .map_or(0, |original_part_count| original_part_count.placed);
+
+            match change(part_count.placed, original_placed) {
+                0 => None,
+                quantity => Some((part.clone(), quantity)),
+            }
+        }).collect();
+
+        (reference.clone(), changed)
+    }).collect()
+}
+
+/// Saves the project, and consumes the parts that have been placed since the `original_counts` were made from the
+/// load-outs of each phase, and from the inventory, if the project has one.
+///
+/// The parts of placements that have been unplaced are returned, so that placing them again does not consume them twice.
+///
+/// The load-outs and the inventory are loaded, and the parts consumed, before the project is saved, so that a load-out
+/// or an inventory that cannot be loaded leaves the project, the load-outs and the inventory unchanged.
+fn save_project_and_consume_parts(project: &Project, session: &mut ProjectSession, path: &Path, original_counts: &BTreeMap<Reference, BTreeMap<Part, PartPlacementCounts>>) -> anyhow::Result<()> {
+    let counts = project::count_phase_part_placements(project);
+    let phase_consumed = count_consumed_parts(&counts, original_counts);
+    let phase_returned = count_returned_parts(&counts, original_counts);
+
+    // phases that share a load-out consume from the same items
+    let mut load_outs: BTreeMap<LoadOutSource, Vec<LoadOutItem>> = BTreeMap::new();
+    for (reference, consumed) in phase_consumed.iter() {
+        let returned = &phase_returned[reference];
+        if consumed.is_empty() && returned.is_empty() {
+            continue
+        }
+
+        let phase = project.phases.get(reference).unwrap();
+        let load_out_source = build_load_out_source(phase, path);
+
+        let load_out_items = match load_outs.entry(load_out_source) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let load_out_items = stores::load_out::load_items(entry.key())?;
+                entry.insert(load_out_items)
+            },
+        };
+
+        let required: BTreeMap<Part, u32> = counts[reference].iter()
+            .map(|(part, part_count)| (part.clone(), part_count.unplaced))
+            .collect();
+
+        stores::load_out::return_items(load_out_items, returned);
+        stores::load_out::consume_items(load_out_items, consumed, &required);
+    }
+
+    let mut inventory = None;
+    if let Some(inventory_source) = &project.inventory_source {
+        let sum_phases = |phase_quantities: BTreeMap<Reference, BTreeMap<Part, u32>>| phase_quantities.into_values()
+            .flatten()
+            .fold(BTreeMap::<Part, u32>::new(), |mut quantities, (part, quantity)| {
+                *quantities.entry(part).or_default() += quantity;
+                quantities
+            });
+        let consumed = sum_phases(phase_consumed);
+        let returned = sum_phases(phase_returned);
+
+        if !consumed.is_empty() || !returned.is_empty() {
+            let inventory_path = path.join(inventory_source);
+            let mut consumed_inventory = stores::inventory::load_inventory(&inventory_path)?;
+            stores::inventory::return_parts(&mut consumed_inventory, &returned);
+            stores::inventory::consume(&mut consumed_inventory, &consumed);
+            inventory = Some((inventory_path, consumed_inventory));
         }
     }
 
+    session.save(project)?;
+
+    for (load_out_source, load_out_items) in load_outs.iter() {
+        stores::load_out::store_items(load_out_source, load_out_items)?;
+    }
+
+    if let Some((inventory_path, inventory)) = inventory {
+        stores::inventory::store_inventory(&inventory_path, &inventory)?;
+    }
+
     Ok(())
 }
@@ -0,0 +1,40 @@
+use std::time::Duration;
+use indicatif::{ProgressBar, ProgressStyle};
+use planning::progress::{Progress, ProgressReporter, ProgressStage};
+
+/// Shows the progress on stderr, the bar is hidden when stderr is not a terminal, e.g. when the output is redirected.
+pub struct ProgressBarReporter {
+    bar: ProgressBar,
+}
+
+impl ProgressBarReporter {
+    pub fn new() -> Self {
+        let bar = ProgressBar::new(100);
+        bar.set_style(ProgressStyle::with_template("{msg:30} [{bar:40}] {pos:>3}%")
+            .unwrap()
+            .progress_chars("=> "));
+        bar.enable_steady_tick(Duration::from_millis(100));
+
+        Self { bar }
+    }
+}
+
+impl ProgressReporter for ProgressBarReporter {
+    fn report(&self, progress: Progress) {
+        self.bar.set_message(progress.stage.to_string());
+        self.bar.set_position(progress.percent as u64);
+
+        if progress.stage == ProgressStage::Complete {
+            self.bar.finish_and_clear();
+        }
+    }
+}
+
+impl Drop for ProgressBarReporter {
+    fn drop(&mut self) {
+        // also cleared when the operation fails, so the error is not appended to the bar
+        if !self.bar.is_finished() {
+            self.bar.finish_and_clear();
+        }
+    }
+}
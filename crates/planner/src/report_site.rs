@@ -0,0 +1,97 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use anyhow::{bail, Context};
+use serde_json::{json, Map, Value};
+use tracing::{debug, info};
+use planning::process::ArtifactType;
+use planning::project::{self, Project};
+use planning::report;
+
+const SITE_HTML: &str = include_str!("report_site.html");
+
+const INDEX_FILE: &str = "index.html";
+const DATA_FILE: &str = "report.js";
+
+/// Renders the report, written by `generate-artifacts`, into a static site that can be opened without a server.
+///
+/// The data is written as a script, rather than JSON, since browsers do not allow pages opened from the filesystem to
+/// fetch other files. The phase placements artifacts, when present, are included so that they can be searched.
+pub fn generate(project_name: &str, project: &Project, artifact_path: &Path, into: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let report_path = artifact_path.join(report::build_report_file_name(project_name));
+    if !report_path.exists() {
+        bail!("Report not found, generate the artifacts first. path: {:?}", report_path)
+    }
+
+    let report_content = fs::read_to_string(&report_path)
+        .with_context(|| format!("Error reading report. path: {:?}", report_path))?;
+    let report: Value = serde_json::from_str(&report_content)
+        .with_context(|| format!("Error parsing report. path: {:?}", report_path))?;
+
+    let mut placements = Map::new();
+    let phase_names = report["phase_overviews"].as_array().into_iter().flatten()
+        .filter_map(|phase_overview| phase_overview["phase_name"].as_str());
+
+    for phase_name in phase_names {
+        let Some(phase) = project.phases.values().find(|phase| phase.reference.to_string().eq(phase_name)) else {
+            debug!("Phase not in project. phase: '{}'", phase_name);
+            continue
+        };
+
+        let placements_path = artifact_path.join(project::build_phase_artifact_file_name(&ArtifactType::PhasePlacements, phase));
+        if !placements_path.exists() {
+            debug!("No phase placements. phase: '{}', path: {:?}", phase_name, placements_path);
+            continue
+        }
+        placements.insert(phase_name.to_string(), read_table(&placements_path)?);
+    }
+
+    let data = json!({ "report": report, "placements": placements });
+
+    fs::create_dir_all(into)?;
+
+    let index_path = into.join(INDEX_FILE);
+    fs::write(&index_path, SITE_HTML)?;
+
+    let data_path = into.join(DATA_FILE);
+    fs::write(&data_path, build_data_script(&data)?)?;
+
+    info!("Generated report site. path: {:?}", index_path);
+
+    Ok(vec![index_path, data_path])
+}
+
+/// Reads a CSV file into an object with the headers and the rows, each row being an array of strings.
+fn read_table(path: &Path) -> anyhow::Result<Value> {
+    let mut csv_reader = csv::ReaderBuilder::new().from_path(path)
+        .with_context(|| format!("Error reading placements. path: {:?}", path))?;
+
+    let headers: Vec<String> = csv_reader.headers()?.iter().map(str::to_string).collect();
+    let rows = csv_reader.records()
+        .map(|record| record.map(|record| record.iter().map(str::to_string).collect::<Vec<_>>()))
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("Error reading placements. path: {:?}", path))?;
+
+    Ok(json!({ "headers": headers, "rows": rows }))
+}
+
+/// `</` is escaped so that content of the report cannot end the script when the data is inlined.
+fn build_data_script(data: &Value) -> anyhow::Result<String> {
+    let json = serde_json::to_string(data)?.replace("</", "<\\/");
+
+    Ok(format!("const SITE_DATA = {};\n", json))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use crate::report_site::build_data_script;
+
+    #[test]
+    pub fn data_script() {
+        // when
+        let script = build_data_script(&json!({ "report": { "name": "</script>" } })).unwrap();
+
+        // then
+        assert_eq!(script, "const SITE_DATA = {\"report\":{\"name\":\"<\\/script>\"}};\n");
+    }
+}
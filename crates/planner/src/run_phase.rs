@@ -0,0 +1,144 @@
+use tracing::info;
+use machine::driver::MachineDriver;
+use pnp::load_out::LoadOutItem;
+use pnp::object_path::ObjectPath;
+use pnp::placement::Placement;
+
+/// Places the placements on the machine, one at a time, `record_placed` is called as soon as each one has been placed,
+/// returns the count of placements that were recorded.
+///
+/// `save` is called with that count before the machine is disconnected, also when placing fails, so that the placements
+/// that were placed remain recorded even if the machine cannot be disconnected. Placement errors are returned before
+/// disconnection errors.
+pub fn run<S>(
+    driver: &mut dyn MachineDriver,
+    load_out_items: &[LoadOutItem],
+    placements: &[(ObjectPath, Placement)],
+    state: &mut S,
+    mut record_placed: impl FnMut(&mut S, &ObjectPath) -> anyhow::Result<bool>,
+    save: impl FnOnce(&S, usize) -> anyhow::Result<()>,
+) -> anyhow::Result<usize> {
+    let statuses = driver.subscribe();
+
+    driver.connect()?;
+    driver.home()?;
+    driver.setup_feeders(load_out_items)?;
+
+    let mut placed = 0;
+    let mut result = Ok(());
+    for (object_path, placement) in placements.iter() {
+        result = driver.place(object_path, placement);
+        for status in statuses.try_iter() {
+            info!("Machine status. machine: {}, status: {}", driver.kind(), status);
+        }
+        if result.is_err() {
+            break
+        }
+
+        if record_placed(state, object_path)? {
+            placed += 1;
+        }
+    }
+
+    save(state, placed)?;
+
+    let disconnect_result = driver.disconnect();
+
+    result?;
+    disconnect_result?;
+
+    Ok(placed)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use std::sync::mpsc::{channel, Receiver};
+    use rust_decimal_macros::dec;
+    use machine::driver::{MachineDriver, MachineError, MachineKind, MachineStatus};
+    use pnp::load_out::LoadOutItem;
+    use pnp::object_path::ObjectPath;
+    use pnp::part::Part;
+    use pnp::pcb::PcbSide;
+    use pnp::placement::{Placement, PlacementKind};
+    use crate::run_phase::run;
+
+    /// Places everything, but cannot be disconnected.
+    struct DisconnectFailingDriver;
+
+    impl MachineDriver for DisconnectFailingDriver {
+        fn kind(&self) -> MachineKind {
+            MachineKind::Simulated
+        }
+
+        fn connect(&mut self) -> Result<(), MachineError> {
+            Ok(())
+        }
+
+        fn home(&mut self) -> Result<(), MachineError> {
+            Ok(())
+        }
+
+        fn setup_feeders(&mut self, _load_out_items: &[LoadOutItem]) -> Result<(), MachineError> {
+            Ok(())
+        }
+
+        fn place(&mut self, _object_path: &ObjectPath, _placement: &Placement) -> Result<(), MachineError> {
+            Ok(())
+        }
+
+        fn disconnect(&mut self) -> Result<(), MachineError> {
+            Err(MachineError::NotConnected(self.kind()))
+        }
+
+        fn subscribe(&mut self) -> Receiver<MachineStatus> {
+            let (_sender, receiver) = channel();
+            receiver
+        }
+    }
+
+    #[test]
+    pub fn placements_are_saved_when_disconnecting_fails() -> anyhow::Result<()> {
+        // given
+        let mut driver = DisconnectFailingDriver;
+        let placement = Placement {
+            ref_des: "R1".to_string(),
+            part: Part::new("RES_MFR1".to_string(), "RES1".to_string()),
+            place: true,
+            pcb_side: PcbSide::Top,
+            x: dec!(10),
+            y: dec!(20),
+            rotation: dec!(90),
+            kind: PlacementKind::Component,
+        };
+        let placements = vec![
+            (ObjectPath::from_str("panel=1::unit=1::ref_des=R1")?, placement.clone()),
+            (ObjectPath::from_str("panel=1::unit=2::ref_des=R1")?, placement),
+        ];
+
+        // and
+        let mut recorded: Vec<ObjectPath> = vec![];
+        let mut saved: Option<(Vec<ObjectPath>, usize)> = None;
+
+        // when
+        let result = run(&mut driver, &[], &placements, &mut recorded,
+            |recorded, object_path| {
+                recorded.push(object_path.clone());
+                Ok(true)
+            },
+            |recorded, placed| {
+                saved = Some((recorded.clone(), placed));
+                Ok(())
+            },
+        );
+
+        // then
+        assert_eq!(saved, Some((placements.iter().map(|(object_path, _placement)| object_path.clone()).collect(), 2)));
+
+        // and the disconnection error is still reported
+        let error = result.unwrap_err();
+        assert_eq!(error.downcast_ref::<MachineError>(), Some(&MachineError::NotConnected(MachineKind::Simulated)));
+
+        Ok(())
+    }
+}
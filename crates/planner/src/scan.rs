@@ -0,0 +1,103 @@
+use std::collections::BTreeMap;
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::Path;
+use pnp::object_path::ObjectPathPattern;
+use tracing::{info, warn};
+use planning::phase::PhaseError;
+use planning::placement::{ObjectPathMatcher, PlacementOperation};
+use planning::process::ProcessOperationSetItem;
+use planning::project;
+use planning::reference::Reference;
+use planning::scan::{ScanAction, ScanSession, ScanTarget};
+
+/// Reads barcodes from stdin, one per line, recording the production activities of the phase, until the end of the
+/// input.
+///
+/// The project and the load-out are reloaded for each scan, so other commands can be used during the session.  Scans
+/// that fail are logged and ignored, so that a mis-scan does not end the session.
+pub fn scan(project_file_path: &Path, path: &Path, reference: &Reference, mappings: &BTreeMap<String, ScanTarget>) -> anyhow::Result<()> {
+    let project_file_path = project_file_path.to_path_buf();
+    let path = path.to_path_buf();
+
+    let project = project::load(&project_file_path)?;
+    if !project.phases.contains_key(reference) {
+        return Err(PhaseError::UnknownPhase(reference.clone()).into())
+    }
+
+    let mut session = ScanSession::new(reference.clone());
+
+    info!("Scanning. phase: '{}', mappings: {}", reference, mappings.len());
+
+    let stdin = std::io::stdin();
+    let interactive = stdin.is_terminal();
+
+    let mut lines = stdin.lock().lines();
+    loop {
+        if interactive {
+            eprint!("Scan: ");
+            std::io::stderr().flush()?;
+        }
+
+        let Some(line) = lines.next() else {
+            break
+        };
+        let barcode = line?;
+        if barcode.trim().is_empty() {
+            continue
+        }
+
+        if let Err(reason) = record_scan(&project_file_path, &path, &mut session, mappings, &barcode) {
+            warn!("Scan ignored. barcode: '{}', cause: {}", barcode.trim(), reason);
+        }
+    }
+
+    info!("Scanning finished. phase: '{}'", reference);
+
+    Ok(())
+}
+
+fn record_scan(project_file_path: &Path, path: &Path, session: &mut ScanSession, mappings: &BTreeMap<String, ScanTarget>, barcode: &str) -> anyhow::Result<()> {
+    let mut project_session = crate::ProjectSession::new(project_file_path.to_path_buf());
+    let mut project = project_session.load()?;
+
+    let phase = project.phases.get(&session.phase)
+        .ok_or(PhaseError::UnknownPhase(session.phase.clone()))?;
+    let load_out_items = stores::load_out::load_items(&crate::build_load_out_source(phase, path))?;
+
+    let target = planning::scan::resolve_barcode(barcode, mappings, &load_out_items)?;
+    let action = session.scan(&project, &load_out_items, target)?;
+
+    match action {
+        ScanAction::UnitSelected { unit_path } => {
+            info!("Selected unit. phase: '{}', unit: '{}'", session.phase, unit_path);
+        },
+        ScanAction::PlaceFeeder { feeder_reference, part, placements } => {
+            let original_counts = project::count_phase_part_placements(&project);
+            let completed_phases = crate::find_completed_phases(&project);
+
+            let object_path_patterns: Vec<ObjectPathMatcher> = placements.iter()
+                .map(|object_path| ObjectPathMatcher::from(ObjectPathPattern::from(object_path)))
+                .collect();
+
+            project::update_placements_operation(&mut project, path, object_path_patterns, PlacementOperation::Placed)?;
+            crate::save_project_and_consume_parts(&project, &mut project_session, path, &original_counts)?;
+
+            info!("Placed feeder. phase: '{}', feeder: '{}', part: {:?}, placements: {}", session.phase, feeder_reference, part, placements.len());
+            crate::generate_certificates_for_completed_phases(&project, path, &completed_phases)?;
+        },
+        ScanAction::CompleteOperation { operation } => {
+            let completed_phases = crate::find_completed_phases(&project);
+
+            let modified = project::update_phase_operation(&mut project, path, &session.phase, operation.clone(), ProcessOperationSetItem::Completed)?;
+            if modified {
+                project_session.save(&project)?;
+
+                info!("Completed operation. phase: '{}', operation: '{}'", session.phase, operation);
+
+                crate::generate_certificates_for_completed_phases(&project, path, &completed_phases)?;
+            }
+        },
+    }
+
+    Ok(())
+}
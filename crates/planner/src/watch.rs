@@ -0,0 +1,71 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+use planning::design::DesignVariant;
+use planning::project;
+
+/// Polls the design variant placements files of the project, refreshing the project when any of them change, until
+/// the process is terminated or `max_refreshes` is reached.
+///
+/// Changes are detected using the design revisions, i.e. the content of the files, not their modification times, so
+/// re-exports that do not change the placements are ignored.  Changed design variants are acknowledged, see
+/// `project::acknowledge_design_revisions`, as running the watcher is an explicit request to pick up the changes.
+pub fn watch(project_file_path: &Path, path: &Path, interval: Duration, max_refreshes: Option<usize>) -> anyhow::Result<()> {
+    let project_file_path = project_file_path.to_path_buf();
+    let project = project::load(&project_file_path)?;
+
+    let mut design_revisions = stores::placements::build_design_revisions(&project.unique_design_variants(), path)?;
+
+    info!("Watching design variant placements. design_variants: {}, interval: {}s", design_revisions.len(), interval.as_secs_f32());
+
+    let mut refreshes = 0;
+    while max_refreshes.is_none_or(|max_refreshes| refreshes < max_refreshes) {
+        thread::sleep(interval);
+
+        // the project is locked until it is saved, or until the next poll
+        let mut session = crate::ProjectSession::new(project_file_path.clone());
+        let mut project = session.load()?;
+        let unique_design_variants = project.unique_design_variants();
+
+        // the files may be read while the EDA tool is still writing them, the next poll will pick up the changes
+        let current_design_revisions = match stores::placements::build_design_revisions(&unique_design_variants, path) {
+            Ok(current_design_revisions) => current_design_revisions,
+            Err(reason) => {
+                warn!("Unable to read design variant placements. cause: {}", reason);
+                continue
+            }
+        };
+
+        let changed_design_variants = find_changed_design_variants(&design_revisions, &current_design_revisions);
+        if changed_design_variants.is_empty() {
+            debug!("No design variant placements changed.");
+            continue
+        }
+
+        info!("Design variant placements changed. design_variants: {:?}", changed_design_variants.iter().map(ToString::to_string).collect::<Vec<_>>());
+
+        project::acknowledge_design_revisions(&mut project, &current_design_revisions);
+        project::check_design_revisions(&mut project, &current_design_revisions)?;
+        let design_variant_placement_map = stores::placements::load_all_placements(&unique_design_variants, path)?;
+        let _all_parts = project::refresh_from_design_variants(&mut project, design_variant_placement_map, false)?;
+
+        session.save(&project)?;
+
+        design_revisions = current_design_revisions;
+        refreshes += 1;
+
+        info!("Refreshed from design variants. refreshes: {}", refreshes);
+    }
+
+    Ok(())
+}
+
+/// Design variants that were added, or whose revision changed.
+fn find_changed_design_variants<'a>(design_revisions: &BTreeMap<DesignVariant, String>, current_design_revisions: &'a BTreeMap<DesignVariant, String>) -> Vec<&'a DesignVariant> {
+    current_design_revisions.iter()
+        .filter(|(design_variant, revision)| design_revisions.get(*design_variant).is_none_or(|previous_revision| previous_revision.ne(*revision)))
+        .map(|(design_variant, _revision)| design_variant)
+        .collect()
+}
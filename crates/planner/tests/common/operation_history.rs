@@ -12,6 +12,7 @@ pub enum TestOperationHistoryPlacementOperation {
 #[derive(Debug, Clone, serde::Deserialize, PartialEq)]
 pub enum TestOperationHistoryKind {
     LoadPcbs { status: TestProcessOperationStatus },
+    AutomatedPnp { status: TestProcessOperationStatus },
     // FUTURE add support for other kinds that can be used, see `OperationHistoryKind` 
     PlacementOperation { object_path: String, operation: TestOperationHistoryPlacementOperation },
 }
@@ -1,3 +1,5 @@
+#![allow(clippy::type_complexity)]
+
 use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter};
 use rust_decimal::Decimal;
@@ -11,6 +13,7 @@ pub struct TestProjectBuilder<'a> {
     processes: Option<&'a [(&'a str, &'a [&'a str])]>,
     pcbs: Option<&'a [(&'a str, &'a str)]>,
     unit_assignments: Option<&'a[(&'a str, BTreeMap<&'a str, &'a str>)]>,
+    design_revisions: Option<&'a [((&'a str, &'a str), &'a str)]>,
     part_states: Option<&'a [((&'a str, &'a str), &'a [&'a str])]>,
     placements: Option<&'a [
         (&'a str, &'a str, (
@@ -73,6 +76,14 @@ impl<'a> TestProjectBuilder<'a> {
             root["unit_assignments"] = Value::Array(values);
         }
 
+        if let Some(design_revisions) = self.design_revisions {
+            let values: Vec<Value> = design_revisions.iter().map(|((design_name, variant_name), revision)|{
+                json!([{ "design_name": design_name, "variant_name": variant_name }, revision])
+            }).collect();
+
+            root["design_revisions"] = Value::Array(values);
+        }
+
         if let Some(part_states) = self.part_states {
             let values: Vec<Value> = part_states.iter().map(|((manufacturer, mpn), applicable_processes)|{
 
@@ -139,22 +150,19 @@ impl<'a> TestProjectBuilder<'a> {
                     let mut operation_state_map = Map::new();
 
                     operation_state_map.insert("status".to_string(), Value::String(status.to_string()));
-                    match extra_state {
-                        Some(TestProcessOperationExtraState::PlacementOperation { placements_state }) => {
-                            
-                            let mut placements_state_map = Map::new();
-                            placements_state_map.insert("placed".to_string(), Value::Number(Number::from(placements_state.placed)));
-                            placements_state_map.insert("total".to_string(), Value::Number(Number::from(placements_state.total)));
-                            
-                            let mut placement_operation_map= Map::new();
-                            placement_operation_map.insert("placements_state".to_string(), Value::Object(placements_state_map));
-                            
-                            let mut extra_map = Map::new();
-                            extra_map.insert("PlacementOperation".to_string(), Value::Object(placement_operation_map));
-                            
-                            operation_state_map.insert("extra".to_string(), Value::Object(extra_map));        
-                        },
-                        _ => {}
+                    if let Some(TestProcessOperationExtraState::PlacementOperation { placements_state }) = extra_state {
+                        
+                        let mut placements_state_map = Map::new();
+                        placements_state_map.insert("placed".to_string(), Value::Number(Number::from(placements_state.placed)));
+                        placements_state_map.insert("total".to_string(), Value::Number(Number::from(placements_state.total)));
+                        
+                        let mut placement_operation_map= Map::new();
+                        placement_operation_map.insert("placements_state".to_string(), Value::Object(placements_state_map));
+                        
+                        let mut extra_map = Map::new();
+                        extra_map.insert("PlacementOperation".to_string(), Value::Object(placement_operation_map));
+                        
+                        operation_state_map.insert("extra".to_string(), Value::Object(extra_map));        
                     }
                     
                     
@@ -264,6 +272,11 @@ impl<'a> TestProjectBuilder<'a> {
         self
     }
 
+    pub fn with_design_revisions(mut self, design_revisions: &'a [((&'a str, &'a str), &'a str)]) -> Self {
+        self.design_revisions = Some(design_revisions);
+        self
+    }
+
     pub fn with_unit_assignments(mut self, unit_assignments: &'a [(&'a str, BTreeMap<&'a str, &'a str>)]) -> Self {
         self.unit_assignments = Some(unit_assignments);
         self
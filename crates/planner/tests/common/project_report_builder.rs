@@ -64,6 +64,8 @@ pub struct TestPhaseOverview {
     pub status: String,
     pub process: String,
     pub operations_overview: Vec<TestPhaseOperationOverview>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub pcbs: Vec<String>,
 }
 
 #[derive(Clone, serde::Serialize)]
@@ -136,6 +138,7 @@ pub struct TestPart {
 
 #[derive(Clone, serde::Serialize)]
 pub struct TestIssue {
+    pub id: String,
     pub message: String, 
     pub severity: TestIssueSeverity,
     pub kind: TestIssueKind,
@@ -234,7 +234,7 @@ mod operation_sequence_1 {
         placements_path.push("design_a_variant_a_placements.csv");
 
         let mut placments_file = File::create(placements_path)?;
-        placments_file.write(design_a_variant_a_placements_csv_content.as_bytes())?;
+        placments_file.write_all(design_a_variant_a_placements_csv_content.as_bytes())?;
         placments_file.flush()?;
 
         // and
@@ -253,6 +253,9 @@ mod operation_sequence_1 {
                     ])
                 )
             ])
+            .with_design_revisions(&[
+                (("design_a", "variant_a"), "9df102475d99c76bd0673e3027c19574a17b1cc948d2e8e6e2eb1b2e7caffdc9"),
+            ])
             .with_part_states(&[
                 (("CAP_MFR1", "CAP1"), &[]),
                 (("CONN_MFR1", "CONN1"), &[]),
@@ -322,10 +325,10 @@ mod operation_sequence_1 {
             "New part. part: Part { manufacturer: \"RES_MFR1\", mpn: \"RES1\" }\n",
             "New part. part: Part { manufacturer: \"CAP_MFR1\", mpn: \"CAP1\" }\n",
             "New part. part: Part { manufacturer: \"CONN_MFR1\", mpn: \"CONN1\" }\n",
-            "New placement. placement: Placement { ref_des: \"R1\", part: Part { manufacturer: \"RES_MFR1\", mpn: \"RES1\" }, place: true, pcb_side: Top, x: 10, y: 110, rotation: 0 }\n",
-            "New placement. placement: Placement { ref_des: \"C1\", part: Part { manufacturer: \"CAP_MFR1\", mpn: \"CAP1\" }, place: true, pcb_side: Bottom, x: 30, y: 130, rotation: 180 }\n",
-            "New placement. placement: Placement { ref_des: \"J1\", part: Part { manufacturer: \"CONN_MFR1\", mpn: \"CONN1\" }, place: true, pcb_side: Bottom, x: 40, y: 140, rotation: -90 }\n",
-            "New placement. placement: Placement { ref_des: \"R3\", part: Part { manufacturer: \"RES_MFR1\", mpn: \"RES1\" }, place: true, pcb_side: Top, x: 5, y: 105, rotation: 90 }\n",
+            "New placement. placement: Placement { ref_des: \"R1\", part: Part { manufacturer: \"RES_MFR1\", mpn: \"RES1\" }, place: true, pcb_side: Top, x: 10, y: 110, rotation: 0, kind: Component }\n",
+            "New placement. placement: Placement { ref_des: \"C1\", part: Part { manufacturer: \"CAP_MFR1\", mpn: \"CAP1\" }, place: true, pcb_side: Bottom, x: 30, y: 130, rotation: 180, kind: Component }\n",
+            "New placement. placement: Placement { ref_des: \"J1\", part: Part { manufacturer: \"CONN_MFR1\", mpn: \"CONN1\" }, place: true, pcb_side: Bottom, x: 40, y: 140, rotation: -90, kind: Component }\n",
+            "New placement. placement: Placement { ref_des: \"R3\", part: Part { manufacturer: \"RES_MFR1\", mpn: \"RES1\" }, place: true, pcb_side: Top, x: 5, y: 105, rotation: 90, kind: Component }\n",
         ]);
 
         // and
@@ -358,9 +361,15 @@ mod operation_sequence_1 {
         placements_path.push("design_a_variant_a_placements.csv");
 
         let mut placments_file = File::create(placements_path)?;
-        placments_file.write(design_a_variant_a_placements_csv_content.as_bytes())?;
+        placments_file.write_all(design_a_variant_a_placements_csv_content.as_bytes())?;
         placments_file.flush()?;
 
+        // and the design change is acknowledged
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec![ctx.path_arg.as_str(), ctx.project_arg.as_str(), "acknowledge-design-changes"]))
+            .assert()
+            .success();
+
         // and
         let expected_project_content = TestProjectBuilder::new()
             .with_name("job1")
@@ -377,6 +386,9 @@ mod operation_sequence_1 {
                     ])
                 )
             ])
+            .with_design_revisions(&[
+                (("design_a", "variant_a"), "7fe20c16620aa1559b2d4f753145e15504a578029978be104832ab00806c23fb"),
+            ])
             .with_part_states(&[
                 (("CONN_MFR1", "CONN1"), &["manual"]),
                 (("RES_MFR1", "RES1"), &[]),
@@ -452,11 +464,11 @@ mod operation_sequence_1 {
         assert_contains_inorder!(trace_content, [
             "New part. part: Part { manufacturer: \"RES_MFR2\", mpn: \"RES2\" }\n",
             "Removing previously part. part: Part { manufacturer: \"CAP_MFR1\", mpn: \"CAP1\" }\n",
-            "Updating placement. old: Placement { ref_des: \"R1\", part: Part { manufacturer: \"RES_MFR1\", mpn: \"RES1\" }, place: true, pcb_side: Top, x: 10, y: 110, rotation: 0 }, new: Placement { ref_des: \"R1\", part: Part { manufacturer: \"RES_MFR1\", mpn: \"RES1\" }, place: true, pcb_side: Top, x: 110, y: 1110, rotation: 1 }\n",
-            "New placement. placement: Placement { ref_des: \"R2\", part: Part { manufacturer: \"RES_MFR2\", mpn: \"RES2\" }, place: true, pcb_side: Top, x: 120, y: 1120, rotation: 91 }\n",
-            "Updating placement. old: Placement { ref_des: \"J1\", part: Part { manufacturer: \"CONN_MFR1\", mpn: \"CONN1\" }, place: true, pcb_side: Bottom, x: 40, y: 140, rotation: -90 }, new: Placement { ref_des: \"J1\", part: Part { manufacturer: \"CONN_MFR1\", mpn: \"CONN1\" }, place: true, pcb_side: Bottom, x: 130, y: 1130, rotation: -179 }\n",
-            "Updating placement. old: Placement { ref_des: \"R3\", part: Part { manufacturer: \"RES_MFR1\", mpn: \"RES1\" }, place: true, pcb_side: Top, x: 5, y: 105, rotation: 90 }, new: Placement { ref_des: \"R3\", part: Part { manufacturer: \"RES_MFR1\", mpn: \"RES1\" }, place: true, pcb_side: Top, x: 105, y: 1105, rotation: 91 }\n",
-            "Marking placement as unused. placement: Placement { ref_des: \"C1\", part: Part { manufacturer: \"CAP_MFR1\", mpn: \"CAP1\" }, place: true, pcb_side: Bottom, x: 30, y: 130, rotation: 180 }\n",
+            "Updating placement. old: Placement { ref_des: \"R1\", part: Part { manufacturer: \"RES_MFR1\", mpn: \"RES1\" }, place: true, pcb_side: Top, x: 10, y: 110, rotation: 0, kind: Component }, new: Placement { ref_des: \"R1\", part: Part { manufacturer: \"RES_MFR1\", mpn: \"RES1\" }, place: true, pcb_side: Top, x: 110, y: 1110, rotation: 1, kind: Component }\n",
+            "New placement. placement: Placement { ref_des: \"R2\", part: Part { manufacturer: \"RES_MFR2\", mpn: \"RES2\" }, place: true, pcb_side: Top, x: 120, y: 1120, rotation: 91, kind: Component }\n",
+            "Updating placement. old: Placement { ref_des: \"J1\", part: Part { manufacturer: \"CONN_MFR1\", mpn: \"CONN1\" }, place: true, pcb_side: Bottom, x: 40, y: 140, rotation: -90, kind: Component }, new: Placement { ref_des: \"J1\", part: Part { manufacturer: \"CONN_MFR1\", mpn: \"CONN1\" }, place: true, pcb_side: Bottom, x: 130, y: 1130, rotation: -179, kind: Component }\n",
+            "Updating placement. old: Placement { ref_des: \"R3\", part: Part { manufacturer: \"RES_MFR1\", mpn: \"RES1\" }, place: true, pcb_side: Top, x: 5, y: 105, rotation: 90, kind: Component }, new: Placement { ref_des: \"R3\", part: Part { manufacturer: \"RES_MFR1\", mpn: \"RES1\" }, place: true, pcb_side: Top, x: 105, y: 1105, rotation: 91, kind: Component }\n",
+            "Marking placement as unused. placement: Placement { ref_des: \"C1\", part: Part { manufacturer: \"CAP_MFR1\", mpn: \"CAP1\" }, place: true, pcb_side: Bottom, x: 30, y: 130, rotation: 180, kind: Component }\n",
             "Added process. part: Part { manufacturer: \"CONN_MFR1\", mpn: \"CONN1\" }, applicable_processes: [\"manual\"]",
         ]);
 
@@ -494,6 +506,9 @@ mod operation_sequence_1 {
                     ])
                 )
             ])
+            .with_design_revisions(&[
+                (("design_a", "variant_a"), "7fe20c16620aa1559b2d4f753145e15504a578029978be104832ab00806c23fb"),
+            ])
             .with_part_states(&[
                 (("CONN_MFR1", "CONN1"), &["manual"]),
                 (("RES_MFR1", "RES1"), &[]),
@@ -626,6 +641,9 @@ mod operation_sequence_1 {
                     ])
                 )
             ])
+            .with_design_revisions(&[
+                (("design_a", "variant_a"), "7fe20c16620aa1559b2d4f753145e15504a578029978be104832ab00806c23fb"),
+            ])
             .with_part_states(&[
                 (("CONN_MFR1", "CONN1"), &["manual"]),
                 (("RES_MFR1", "RES1"), &[]),
@@ -759,6 +777,9 @@ mod operation_sequence_1 {
                     ])
                 )
             ])
+            .with_design_revisions(&[
+                (("design_a", "variant_a"), "7fe20c16620aa1559b2d4f753145e15504a578029978be104832ab00806c23fb"),
+            ])
             .with_part_states(&[
                 (("CONN_MFR1", "CONN1"), &["manual"]),
                 (("RES_MFR1", "RES1"), &["pnp"]),
@@ -856,8 +877,8 @@ mod operation_sequence_1 {
         // and
         let expected_phase_1_load_out_content = LoadOutCSVBuilder::new()
             .with_items(&[
-                TestLoadOutRecord { reference: "".to_string(), manufacturer: "RES_MFR1".to_string(), mpn: "RES1".to_string() },
-                TestLoadOutRecord { reference: "".to_string(), manufacturer: "RES_MFR2".to_string(), mpn: "RES2".to_string() },
+                TestLoadOutRecord { reference: "".to_string(), manufacturer: "RES_MFR1".to_string(), mpn: "RES1".to_string(), ..Default::default() },
+                TestLoadOutRecord { reference: "".to_string(), manufacturer: "RES_MFR2".to_string(), mpn: "RES2".to_string(), ..Default::default() },
             ])
             .as_string();
 
@@ -935,8 +956,8 @@ mod operation_sequence_1 {
 
         let expected_phase_1_load_out_content = LoadOutCSVBuilder::new()
             .with_items(&[
-                TestLoadOutRecord { reference: "FEEDER_1".to_string(), manufacturer: "RES_MFR1".to_string(), mpn: "RES1".to_string() },
-                TestLoadOutRecord { reference: "".to_string(), manufacturer: "RES_MFR2".to_string(), mpn: "RES2".to_string() },
+                TestLoadOutRecord { reference: "FEEDER_1".to_string(), manufacturer: "RES_MFR1".to_string(), mpn: "RES1".to_string(), ..Default::default() },
+                TestLoadOutRecord { reference: "".to_string(), manufacturer: "RES_MFR2".to_string(), mpn: "RES2".to_string(), ..Default::default() },
             ])
             .as_string();
         
@@ -991,6 +1012,9 @@ mod operation_sequence_1 {
                     ])
                 )
             ])
+            .with_design_revisions(&[
+                (("design_a", "variant_a"), "7fe20c16620aa1559b2d4f753145e15504a578029978be104832ab00806c23fb"),
+            ])
             .with_part_states(&[
                 (("CONN_MFR1", "CONN1"), &["manual"]),
                 (("RES_MFR1", "RES1"), &["pnp"]),
@@ -1113,15 +1137,6 @@ mod operation_sequence_1 {
         // and
         let expected_phase_1_placements_content = PhasePlacementsCSVBuilder::new()
             .with_items(&[
-                TestPhasePlacementRecord {
-                    object_path: "panel=1::unit=1::ref_des=R2".to_string(),
-                    feeder_reference: "".to_string(),
-                    manufacturer: "RES_MFR2".to_string(),
-                    mpn: "RES2".to_string(),
-                    x: dec!(120),
-                    y: dec!(1120),
-                    rotation: dec!(91),
-                },
                 TestPhasePlacementRecord {
                     object_path: "panel=1::unit=1::ref_des=R1".to_string(),
                     feeder_reference: "FEEDER_1".to_string(),
@@ -1140,6 +1155,15 @@ mod operation_sequence_1 {
                     y: dec!(1105),
                     rotation: dec!(91),
                 },
+                TestPhasePlacementRecord {
+                    object_path: "panel=1::unit=1::ref_des=R2".to_string(),
+                    feeder_reference: "".to_string(),
+                    manufacturer: "RES_MFR2".to_string(),
+                    mpn: "RES2".to_string(),
+                    x: dec!(120),
+                    y: dec!(1120),
+                    rotation: dec!(91),
+                },
             ])
             .as_string();
 
@@ -1154,14 +1178,14 @@ mod operation_sequence_1 {
                         message: "0/3 placements placed".to_string(),
                         status: TestProcessOperationStatus::Pending,
                     }
-                ]},
+                ], pcbs: vec!["panel_a".to_string()] },
                 TestPhaseOverview { phase_name: "bottom_1".to_string(), status: "Incomplete".to_string(), process: "manual".to_string(), operations_overview: vec![
                     TestPhaseOperationOverview { 
                         operation: TestPhaseOperationKind::ManuallySolderComponents,
                         message: "0/0 placements placed".to_string(),
                         status: TestProcessOperationStatus::Pending,
                     }
-                ]},
+                ], pcbs: vec![] },
             ])
             .with_phase_specification(&[
                 TestPhaseSpecification {
@@ -1198,16 +1222,8 @@ mod operation_sequence_1 {
                 TestPhaseSpecification {
                     phase_name: "bottom_1".to_string(),
                     operations: vec![
-                        TestPhaseOperation::PreparePcbs { pcbs: vec![
-                            TestPcb::Panel {
-                                name: "panel_a".to_string(),
-                                unit_assignments: vec![TestPcbUnitAssignment {
-                                    unit_path: "panel=1::unit=1".to_string(),
-                                    design_name: "design_a".to_string(),
-                                    variant_name: "variant_a".to_string(),
-                                }]
-                            }
-                        ]},
+                        // no placements are assigned to the phase, so there are no PCBs to prepare
+                        TestPhaseOperation::PreparePcbs { pcbs: vec![] },
                         TestPhaseOperation::ManuallySolderComponents {},
                     ],
                     load_out_assignments: vec![
@@ -1216,6 +1232,7 @@ mod operation_sequence_1 {
             ])
             .with_issues(&[
                 TestIssue {
+                    id: "a7c521e1".to_string(),
                     message: "A placement has not been assigned to a phase".to_string(),
                     severity: TestIssueSeverity::Warning,
                     kind: TestIssueKind::UnassignedPlacement {
@@ -1223,6 +1240,7 @@ mod operation_sequence_1 {
                     },
                 },
                 TestIssue {
+                    id: "0c33be68".to_string(),
                     message: "A part has not been assigned to a feeder".to_string(),
                     severity: TestIssueSeverity::Warning,
                     kind: TestIssueKind::UnassignedPartFeeder {
@@ -1325,6 +1343,9 @@ mod operation_sequence_1 {
                     ])
                 )
             ])
+            .with_design_revisions(&[
+                (("design_a", "variant_a"), "7fe20c16620aa1559b2d4f753145e15504a578029978be104832ab00806c23fb"),
+            ])
             .with_part_states(&[
                 (("CONN_MFR1", "CONN1"), &["manual"]),
                 (("RES_MFR1", "RES1"), &["pnp"]),
@@ -1469,6 +1490,9 @@ mod operation_sequence_1 {
                     ])
                 )
             ])
+            .with_design_revisions(&[
+                (("design_a", "variant_a"), "7fe20c16620aa1559b2d4f753145e15504a578029978be104832ab00806c23fb"),
+            ])
             .with_part_states(&[
                 (("CONN_MFR1", "CONN1"), &["manual"]),
                 (("RES_MFR1", "RES1"), &["pnp"]),
@@ -1544,6 +1568,7 @@ mod operation_sequence_1 {
             ("require", Some(("top_1".to_string(), TestOperationHistoryKind::PlacementOperation { object_path: "panel=1::unit=1::ref_des=R1".to_string(), operation: TestOperationHistoryPlacementOperation::Placed}))),
             ("require", Some(("top_1".to_string(), TestOperationHistoryKind::PlacementOperation { object_path: "panel=1::unit=1::ref_des=R2".to_string(), operation: TestOperationHistoryPlacementOperation::Placed}))),
             ("require", Some(("top_1".to_string(), TestOperationHistoryKind::PlacementOperation { object_path: "panel=1::unit=1::ref_des=R3".to_string(), operation: TestOperationHistoryPlacementOperation::Placed}))),
+            ("require", Some(("top_1".to_string(), TestOperationHistoryKind::AutomatedPnp { status: TestProcessOperationStatus::Complete }))),
             ("eof", None),
         ];
         
@@ -1577,6 +1602,7 @@ mod operation_sequence_1 {
             "Unmatched object path pattern. object_path_pattern: panel=1::unit=2::ref_des=.*\n",
             "Updating phase status. phase: top_1\n",
             "Phase operation complete. phase: top_1, operation: AutomatedPnp\n",
+            "Phase operation completed automatically. phase: top_1, operation: AutomatedPnp\n",
             &log_file_message,
         ]);
 
@@ -1621,6 +1647,9 @@ mod operation_sequence_1 {
                     ])
                 )
             ])
+            .with_design_revisions(&[
+                (("design_a", "variant_a"), "7fe20c16620aa1559b2d4f753145e15504a578029978be104832ab00806c23fb"),
+            ])
             .with_part_states(&[
                 (("CONN_MFR1", "CONN1"), &["manual"]),
                 (("RES_MFR1", "RES1"), &["pnp"]),
@@ -1724,20 +1753,17 @@ mod operation_sequence_1 {
         Ok(())
     }
     fn assert_operation_history(mut operation_history: Vec<TestOperationHistoryItem>, operation_expectations: Vec<(&str, Option<(String, TestOperationHistoryKind)>)>) {
-        for (index, (&ref expectation_operation, expectation)) in operation_expectations.iter().enumerate() {
+        for (index, (expectation_operation, expectation)) in operation_expectations.iter().enumerate() {
 
-            match expectation_operation {
-                "eof" => {
-                    assert!(operation_history.is_empty());
-                    break
-                },
-                _ => {}
+            if *expectation_operation == "eof" {
+                assert!(operation_history.is_empty());
+                break
             }
             
             let (item, remaining_operation_history) = operation_history.split_first().unwrap();
             println!("index: {}, expectation: {}, item: {:?}", index, expectation_operation, item);
             
-            match expectation_operation {
+            match *expectation_operation {
                 "ignore" => {},
                 "require" => {
                     assert_eq!(&(item.phase.clone(), item.operation.clone()), expectation.as_ref().unwrap());
@@ -1757,248 +1783,7073 @@ mod operation_sequence_1 {
     }
 }
 
-mod help {
+mod example {
     use assert_cmd::Command;
-    use indoc::indoc;
-    use predicates::prelude::{predicate, PredicateBooleanExt};
-    use util::test::print;
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
 
     #[test]
-    fn no_args() {
+    fn generate_example_and_artifacts() -> Result<(), anyhow::Error> {
         // given
-        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
 
-        // and
-        let expected_output = indoc! {"
-            Usage: planner [OPTIONS] <--project <PROJECT_NAME>> <COMMAND>
-
-            Commands:
-              create                          Create a new job
-              add-pcb                         Add a PCB
-              assign-variant-to-unit          Assign a design variant to a PCB unit
-              assign-process-to-parts         Assign a process to parts
-              create-phase                    Create a phase
-              assign-placements-to-phase      Assign placements to a phase
-              assign-feeder-to-load-out-item  Assign feeder to load-out item
-              set-placement-ordering          Set placement ordering for a phase
-              generate-artifacts              Generate artifacts
-              record-phase-operation          Record phase operation
-              record-placements-operation     Record placements operation
-              reset-operations                Reset operations
-              help                            Print this message or the help of the given subcommand(s)
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout"));
 
-            Options:
-                  --trace [<TRACE>]         Trace log file
-                  --path <PATH>             Path [default: .]
-                  --project <PROJECT_NAME>  Project name
-              -v, --verbose...              Increase logging verbosity
-              -q, --quiet...                Decrease logging verbosity
-              -h, --help                    Print help
-              -V, --version                 Print version
-        "};
+        // and
+        for file_name in ["project-example1.mpnp.json", "design_a_variant_a_placements.csv", "load_out_top_1.csv", "load_out_bottom_1.csv", "example.sh"] {
+            assert!(temp_dir.path().join(file_name).exists(), "missing file. file_name: {}", file_name);
+        }
 
         // when
-        cmd
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .current_dir(temp_dir.path())
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "generate-artifacts"]))
             // then
             .assert()
-            .failure()
-            .stderr(print("stderr").and(predicate::str::diff(expected_output)))
+            .success()
+            .stderr(print("stderr"))
             .stdout(print("stdout"));
+
+        // and
+        for file_name in ["top_1_placements.csv", "bottom_1_placements.csv", "top_1_export.json", "bottom_1_export.json", "example1_report.json"] {
+            assert!(temp_dir.path().join(file_name).exists(), "missing file. file_name: {}", file_name);
+        }
+
+        Ok(())
     }
+}
+
+mod phase_assignment {
+    use std::fs::read_to_string;
+    use assert_cmd::Command;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
 
     #[test]
-    fn help_for_create() {
+    fn reassign_placements_requires_allow_reassign() -> Result<(), anyhow::Error> {
         // given
-        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+        let trace_log_path = temp_dir.path().join("trace.log");
+        let trace_log_arg = format!("--trace {}", trace_log_path.to_str().unwrap());
 
         // and
-        let expected_output = indoc! {"
-            Create a new job
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
 
-            Usage: planner <--project <PROJECT_NAME>> create [OPTIONS]
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "create-phase", "--process manual", "--reference bottom_2", "--load-out load_out_bottom_2.csv", "--pcb-side bottom"]))
+            .assert()
+            .success();
 
-            Options:
-              -v, --verbose...  Increase logging verbosity
-              -q, --quiet...    Decrease logging verbosity
-              -h, --help        Print help
-        "};
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec![trace_log_arg.as_str(), "--project example1", path_arg.as_str(), "assign-placements-to-phase", "--phase bottom_2", "--placements .*"]))
+            // then
+            .assert()
+            .failure()
+            .stderr(print("stderr"))
+            .stdout(print("stdout"));
+
+        // and
+        let trace_content = read_to_string(&trace_log_path)?;
+        assert_contains_inorder!(trace_content, [
+            "Placement already assigned to another phase. phase: bottom_1, placement_path: panel=1::unit=1::ref_des=J1",
+            "Placement already assigned to another phase. phase: bottom_1, placement_path: panel=1::unit=2::ref_des=J1",
+        ]);
 
         // when
-        cmd.args(["create", "--help"])
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec![trace_log_arg.as_str(), "--project example1", path_arg.as_str(), "assign-placements-to-phase", "--phase bottom_2", "--placements .*", "--allow-reassign"]))
             // then
             .assert()
             .success()
             .stderr(print("stderr"))
-            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+            .stdout(print("stdout"));
+
+        // and
+        let trace_content = read_to_string(&trace_log_path)?;
+        assert_contains_inorder!(trace_content, [
+            "Reassigning placement to phase. old_phase: bottom_1, new_phase: bottom_2, placement_path: panel=1::unit=1::ref_des=J1",
+            "Reassigning placement to phase. old_phase: bottom_1, new_phase: bottom_2, placement_path: panel=1::unit=2::ref_des=J1",
+            "Reassigned placements. old_phase: bottom_1, new_phase: bottom_2, count: 2",
+        ]);
+
+        Ok(())
     }
 
     #[test]
-    fn help_for_add_pcb() {
+    fn unassign_placements_and_remove_phase() -> Result<(), anyhow::Error> {
         // given
-        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
 
         // and
-        let expected_output = indoc! {"
-            Add a PCB
-
-            Usage: planner <--project <PROJECT_NAME>> add-pcb [OPTIONS] --kind <KIND> --name <NAME>
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and a phase with the 'R2' placements of both units
+        for args in [
+            vec!["create-phase", "--process pnp", "--reference top_2", "--load-out load_out_top_2.csv", "--pcb-side top"],
+            vec!["assign-placements-to-phase", "--phase top_2", "--placements .*::ref_des=R2", "--allow-reassign"],
+        ] {
+            Command::new(env!("CARGO_BIN_EXE_planner"))
+                .args(prepare_args([vec!["--project example1", path_arg.as_str()], args].concat()))
+                .assert()
+                .success();
+        }
 
-            Options:
-                  --kind <KIND>  PCB kind [possible values: single, panel]
-                  --name <NAME>  Name of the PCB, e.g. 'panel_1'
-              -v, --verbose...   Increase logging verbosity
-              -q, --quiet...     Decrease logging verbosity
-              -h, --help         Print help
-        "};
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "unassign-placements-from-phase", "--phase top_2", "--placements panel=1::unit=1::ref_des=R2"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout")
+                .and(predicate::str::contains("Unassigning placement from phase. phase: top_2, placement_path: panel=1::unit=1::ref_des=R2"))
+                // the part is still required for the other unit
+                .and(predicate::str::contains("Removed parts from load-out").not())
+            );
 
         // when
-        cmd.args(["add-pcb", "--help"])
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "remove-phase", "--phase top_2"]))
             // then
             .assert()
             .success()
             .stderr(print("stderr"))
-            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+            .stdout(print("stdout")
+                .and(predicate::str::contains("Removed phase. phase: 'top_2'"))
+                .and(predicate::str::contains("Phase ordering: ['top_1', 'bottom_1']"))
+                .and(predicate::str::contains("Removed parts from load-out. phase: 'top_2'"))
+            );
+
+        // and
+        let load_out_content = read_to_string(temp_dir.path().join("load_out_top_2.csv"))?;
+        assert!(!load_out_content.contains("RES2"), "load_out_content: {}", load_out_content);
+
+        // and the placements are no longer assigned to a phase
+        let project_content = read_to_string(temp_dir.path().join("project-example1.mpnp.json"))?;
+        assert!(!project_content.contains("top_2"), "project_content: {}", project_content);
+
+        Ok(())
     }
 
     #[test]
-    fn help_for_assign_variant_to_unit() {
+    fn side_mismatch() -> Result<(), anyhow::Error> {
         // given
-        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
 
         // and
-        let expected_output = indoc! {"
-            Assign a design variant to a PCB unit
-
-            Usage: planner <--project <PROJECT_NAME>> assign-variant-to-unit [OPTIONS] --design <DESIGN_NAME> --variant <VARIANT_NAME> --unit <OBJECT_PATH>
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
 
-            Options:
-                  --design <DESIGN_NAME>    Name of the design
-                  --variant <VARIANT_NAME>  Variant of the design
-                  --unit <OBJECT_PATH>      PCB unit path
-              -v, --verbose...              Increase logging verbosity
-              -q, --quiet...                Decrease logging verbosity
-              -h, --help                    Print help
-        "};
+        // when only a placement on the bottom of the PCB is assigned to a top phase
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "assign-placements-to-phase", "--phase top_1", "--placements .*J1", "--allow-reassign"]))
+            // then
+            .assert()
+            .failure()
+            .stderr(print("stderr").and(predicate::str::contains("Placements are on the other side of the PCB to the phase, use '--allow-side-mismatch' to assign them")));
 
         // when
-        cmd.args(["assign-variant-to-unit", "--help"])
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "assign-placements-to-phase", "--phase top_1", "--placements .*J1", "--allow-reassign", "--allow-side-mismatch"]))
             // then
             .assert()
             .success()
             .stderr(print("stderr"))
-            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+            .stdout(print("stdout").and(predicate::str::contains("Assigning placements on the other side of the PCB to the phase.")));
+
+        Ok(())
     }
+}
+
+mod part_rename {
+    use std::fs::read_to_string;
+    use assert_cmd::Command;
+    use indoc::indoc;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
 
     #[test]
-    fn help_for_assign_process_to_parts() {
+    fn rename_part() -> Result<(), anyhow::Error> {
         // given
-        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+        let rename_args = vec!["rename-part", "--manufacturer RES_MFR1", "--mpn RES1", "--new-manufacturer RES_MFR2", "--new-mpn RES1A", "--file part_mappings.csv"];
 
         // and
-        let expected_output = indoc! {"
-            Assign a process to parts
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
 
-            Usage: planner <--project <PROJECT_NAME>> assign-process-to-parts [OPTIONS] --process <PROCESS> --manufacturer <MANUFACTURER> --mpn <MPN>
+        let load_out_path = temp_dir.path().join("load_out_top_1.csv");
+        let original_load_out_content = read_to_string(&load_out_path)?;
 
-            Options:
-                  --process <PROCESS>            Process name
-                  --manufacturer <MANUFACTURER>  Manufacturer pattern (regexp)
-                  --mpn <MPN>                    Manufacturer part number (regexp)
-              -v, --verbose...                   Increase logging verbosity
-              -q, --quiet...                     Decrease logging verbosity
-              -h, --help                         Print help
-        "};
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args([vec!["--project example1", path_arg.as_str()], rename_args.clone(), vec!["--dry-run"]].concat()))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains(indoc! {"
+                -FEEDER_2,RES_MFR1,RES1,,,,
+                +FEEDER_2,RES_MFR2,RES1A,,,,
+            "})));
+
+        // and nothing is written
+        assert_eq!(read_to_string(&load_out_path)?, original_load_out_content);
+        assert!(!temp_dir.path().join("top_1_log.json").exists());
 
         // when
-        cmd.args(["assign-process-to-parts", "--help"])
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args([vec!["--project example1", path_arg.as_str()], rename_args].concat()))
             // then
             .assert()
             .success()
             .stderr(print("stderr"))
-            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+            .stdout(print("stdout"));
+
+        // and
+        for file in ["project-example1.mpnp.json", "design_a_variant_a_placements.csv", "load_out_top_1.csv", "part_mappings.csv"] {
+            let content = read_to_string(temp_dir.path().join(file))?;
+            assert!(content.contains("RES1A"), "file: {}", file);
+            assert!(!content.contains("\"RES1\""), "file: {}", file);
+        }
+
+        // and
+        let log_content = read_to_string(temp_dir.path().join("top_1_log.json"))?;
+        assert!(log_content.contains("PartRenamed"));
+
+        Ok(())
     }
 
     #[test]
-    fn help_for_create_phase() {
+    fn rename_part_in_inventory() -> Result<(), anyhow::Error> {
         // given
-        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+        let rename_args = vec!["rename-part", "--manufacturer RES_MFR1", "--mpn RES1", "--new-manufacturer RES_MFR2", "--new-mpn RES1A"];
 
         // and
-        let expected_output = indoc! {"
-            Create a phase
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
 
-            Usage: planner <--project <PROJECT_NAME>> create-phase [OPTIONS] --process <PROCESS> --reference <REFERENCE> --load-out <LOAD_OUT> --pcb-side <PCB_SIDE>
+        // and
+        let inventory_path = temp_dir.path().join("inventory.csv");
+        let original_inventory_content = indoc! {r#"
+            "Manufacturer","Mpn","Quantity"
+            "RES_MFR1","RES1","100"
+            "CAP_MFR1","CAP1","50"
+        "#};
+        std::fs::write(&inventory_path, original_inventory_content)?;
 
-            Options:
-                  --process <PROCESS>      Process name
-                  --reference <REFERENCE>  Phase reference (e.g. 'top_1')
-                  --load-out <LOAD_OUT>    Load-out source (e.g. 'load_out_1')
-                  --pcb-side <PCB_SIDE>    PCB side [possible values: top, bottom]
-              -v, --verbose...             Increase logging verbosity
-              -q, --quiet...               Decrease logging verbosity
-              -h, --help                   Print help
-        "};
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "set-inventory", "--source inventory.csv"]))
+            .assert()
+            .success();
 
         // when
-        cmd.args(["create-phase", "--help"])
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args([vec!["--project example1", path_arg.as_str()], rename_args.clone(), vec!["--dry-run"]].concat()))
             // then
             .assert()
             .success()
             .stderr(print("stderr"))
-            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
-    }
-
-    #[test]
-    fn help_for_assign_placements_to_phase() {
-        // given
-        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
-
-        // and
-        let expected_output = indoc! {"
-            Assign placements to a phase
-
-            Usage: planner <--project <PROJECT_NAME>> assign-placements-to-phase [OPTIONS] --phase <PHASE> --placements <PLACEMENTS>
+            .stdout(print("stdout").and(predicate::str::contains(indoc! {"
+                -RES_MFR1,RES1,100
+                +RES_MFR2,RES1A,100
+            "})));
 
-            Options:
-                  --phase <PHASE>            Phase reference (e.g. 'top_1')
-                  --placements <PLACEMENTS>  Placements object path pattern (regexp)
-              -v, --verbose...               Increase logging verbosity
-              -q, --quiet...                 Decrease logging verbosity
-              -h, --help                     Print help
-        "};
+        // and nothing is written
+        assert_eq!(read_to_string(&inventory_path)?, original_inventory_content);
 
         // when
-        cmd.args(["assign-placements-to-phase", "--help"])
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args([vec!["--project example1", path_arg.as_str()], rename_args].concat()))
             // then
             .assert()
             .success()
             .stderr(print("stderr"))
-            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+            .stdout(print("stdout"));
+
+        // and
+        assert_eq!(read_to_string(&inventory_path)?, indoc! {r#"
+            "Manufacturer","Mpn","Quantity"
+            "RES_MFR2","RES1A","100"
+            "CAP_MFR1","CAP1","50"
+        "#});
+
+        Ok(())
     }
+}
+
+mod work_instructions {
+    use std::fs::read_to_string;
+    use assert_cmd::Command;
+    use indoc::indoc;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
 
     #[test]
-    fn help_for_assign_feeder_to_load_out_item() {
+    fn work_instructions_include_part_details() -> Result<(), anyhow::Error> {
         // given
-        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
 
         // and
-        let expected_output = indoc! {"
-            Assign feeder to load-out item
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and
+        std::fs::write(temp_dir.path().join("part_library.csv"), indoc! {r#"
+            "Manufacturer","Mpn","Image","Datasheet"
+            "RES_MFR1","RES1","images/res1.png","https://example.com/res1.pdf"
+        "#})?;
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "import-part-details", "--parts part_library.csv"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout"));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "generate-artifacts"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout"));
+
+        // and
+        let work_instructions_content = read_to_string(temp_dir.path().join("top_1_work_instructions.md"))?;
+        assert!(work_instructions_content.contains(
+            r#"| FEEDER_2 | RES_MFR1 | RES1 | 2 | <img src="images/res1.png" alt="RES1" height="48"> | [Datasheet](https://example.com/res1.pdf) |"#
+        ), "content: {}", work_instructions_content);
+
+        Ok(())
+    }
+
+    #[test]
+    fn work_instructions_pick_list() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "set-work-instructions-style", "--phase top_1", "--style pick-list"]))
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Phase work instructions style set. phase: 'top_1', old: Parts, new: PickList")));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "generate-artifacts"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout"));
+
+        // and
+        let work_instructions_content = read_to_string(temp_dir.path().join("top_1_work_instructions.md"))?;
+        assert!(work_instructions_content.contains("## Pick list"), "content: {}", work_instructions_content);
+        assert!(work_instructions_content.contains("### FEEDER_2 - RES_MFR1 RES1"), "content: {}", work_instructions_content);
+
+        // and
+        let project_content = read_to_string(temp_dir.path().join("project-example1.mpnp.json"))?;
+        assert!(project_content.contains(r#""work_instructions_style": "PickList""#));
+
+        Ok(())
+    }
+}
+
+mod validation {
+    use std::fs::read_to_string;
+    use assert_cmd::Command;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
+
+    #[test]
+    fn validate_required_artifacts() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "set-required-artifacts", "--process pnp", "--artifacts phase-placements,rework-instructions"]))
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Required artifacts updated. process: 'pnp', old: [], new: [PhasePlacements, ReworkInstructions]")));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "generate-artifacts"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Required artifact not generated. phase: 'top_1', artifact: ReworkInstructions")));
+
+        // and the report contains the issue
+        let report_content = read_to_string(temp_dir.path().join("example1_report.json"))?;
+        assert!(report_content.contains(r#""MissingRequiredArtifact""#), "content: {}", report_content);
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "validate"]))
+            .assert()
+            .failure()
+            .stderr(print("stderr").and(predicate::str::contains("Required artifacts are missing or out of date. count: 1")))
+            .stdout(print("stdout").and(predicate::str::contains("Required artifact missing. phase: 'top_1', artifact: ReworkInstructions")));
+
+        // when the requirements are changed, after the artifacts were generated
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "set-required-artifacts", "--process pnp", "--artifacts phase-placements"]))
+            .assert()
+            .success();
+
+        // then
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "validate"]))
+            .assert()
+            .failure()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Required artifact out of date. phase: 'top_1', artifact: PhasePlacements")));
+
+        // when the artifacts are regenerated
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "generate-artifacts"]))
+            .assert()
+            .success();
+
+        // then
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "validate"]))
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Required artifacts are up to date.")));
+
+        Ok(())
+    }
+}
+
+mod moisture_sensitivity {
+    use std::fs::read_to_string;
+    use assert_cmd::Command;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
+
+    #[test]
+    fn moisture_sensitive_floor_life() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and a part without any floor life remaining
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "set-moisture-sensitivity", "--manufacturer ^CAP_MFR1$", "--mpn ^CAP1$", "--level 6", "--floor-life-hours 0"]))
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Updated moisture sensitivity. part: Part { manufacturer: \"CAP_MFR1\", mpn: \"CAP1\" }")));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "record-feeder-loaded", "--phase top_1", "--feeder FEEDER_1"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Recorded feeder loaded. phase: 'top_1', feeder: 'FEEDER_1'")));
+
+        // and
+        let log_content = read_to_string(temp_dir.path().join("top_1_log.json"))?;
+        assert!(log_content.contains(r#""FeederLoaded""#), "content: {}", log_content);
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "generate-artifacts"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr").and(predicate::str::contains("Health: 1 error")))
+            .stdout(print("stdout"));
+
+        // and
+        let report_content = read_to_string(temp_dir.path().join("example1_report.json"))?;
+        assert!(report_content.contains(r#""FloorLifeExceeded""#), "content: {}", report_content);
+
+        // and a level 6 part requires a floor life
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "set-moisture-sensitivity", "--manufacturer .*", "--mpn .*", "--level 6"]))
+            .assert()
+            .failure()
+            .stderr(print("stderr").and(predicate::str::contains("Level 6 parts require a floor life")));
+
+        Ok(())
+    }
+}
+
+mod load_out {
+    use std::fs::read_to_string;
+    use assert_cmd::Command;
+    use indoc::indoc;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use stores::test::load_out_builder::{LoadOutCSVBuilder, TestLoadOutRecord};
+    use util::test::{prepare_args, print};
+
+    #[test]
+    fn edit_load_out_items() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "set-load-out-item-quantity", "--phase top_1", "--feeder FEEDER_2", "--quantity 500"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Set load-out item quantity. feeder: 'FEEDER_2', part: Part { manufacturer: \"RES_MFR1\", mpn: \"RES1\" }, old: None, new: Some(500)")));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "remove-load-out-item", "--phase top_1", "--feeder FEEDER_3"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Removed load-out item. feeder: 'FEEDER_3'")));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "rename-feeder", "--phase top_1", "--feeder FEEDER_2", "--new-feeder FEEDER_3"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Renamed feeder. feeder: 'FEEDER_2', new_feeder: 'FEEDER_3'")));
+
+        // and
+        let expected_load_out_content = LoadOutCSVBuilder::new()
+            .with_items(&[
+                TestLoadOutRecord { reference: "FEEDER_1".to_string(), manufacturer: "CAP_MFR1".to_string(), mpn: "CAP1".to_string(), ..Default::default() },
+                TestLoadOutRecord { reference: "FEEDER_3".to_string(), manufacturer: "RES_MFR1".to_string(), mpn: "RES1".to_string(), quantity: Some(500), ..Default::default() },
+            ])
+            .as_string();
+        assert_eq!(read_to_string(temp_dir.path().join("load_out_top_1.csv"))?, expected_load_out_content);
+
+        // and a feeder that is in use cannot be renamed to
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "rename-feeder", "--phase top_1", "--feeder FEEDER_1", "--new-feeder FEEDER_3"]))
+            .assert()
+            .failure()
+            .stderr(print("stderr").and(predicate::str::contains("Feeder already in use. feeder: 'FEEDER_3'")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn load_out_alternates() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "set-load-out-alternates", "--phase top_1", "--feeder FEEDER_2", "--alternate RES_MFR2:RES1A", "--alternate RES_MFR3:RES1B"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Set load-out item alternates. feeder: 'FEEDER_2'")));
+
+        // when the second alternate is loaded
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "record-feeder-loaded", "--phase top_1", "--feeder FEEDER_2", "--alternate RES_MFR3:RES1B"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Loaded part. phase: 'top_1', feeder: 'FEEDER_2', part: Part { manufacturer: \"RES_MFR3\", mpn: \"RES1B\" }")));
+
+        // and
+        let load_out_content = read_to_string(temp_dir.path().join("load_out_top_1.csv"))?;
+        assert!(load_out_content.contains(r#""RES_MFR2:RES1A;RES_MFR3:RES1B","RES_MFR3:RES1B""#), "content: {}", load_out_content);
+
+        // and the alternate is recorded in the operation history
+        let log_content = read_to_string(temp_dir.path().join("top_1_log.json"))?;
+        assert!(log_content.contains(r#""mpn": "RES1B""#), "content: {}", log_content);
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "generate-artifacts"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout"));
+
+        // and
+        let report_content = read_to_string(temp_dir.path().join("example1_report.json"))?;
+        assert!(report_content.contains(r#""loaded_alternate""#), "content: {}", report_content);
+
+        // and an unknown alternate cannot be loaded
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "record-feeder-loaded", "--phase top_1", "--feeder FEEDER_2", "--alternate RES_MFR4:RES1C"]))
+            .assert()
+            .failure()
+            .stderr(print("stderr").and(predicate::str::contains("Alternate not in the alternates of the load-out item")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn consume_part_loaded_into_two_feeders() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and 'RES1' is loaded into two feeders
+        let load_out_content = LoadOutCSVBuilder::new()
+            .with_items(&[
+                TestLoadOutRecord { reference: "FEEDER_1".to_string(), manufacturer: "CAP_MFR1".to_string(), mpn: "CAP1".to_string(), ..Default::default() },
+                TestLoadOutRecord { reference: "FEEDER_2".to_string(), manufacturer: "RES_MFR1".to_string(), mpn: "RES1".to_string(), quantity: Some(1), ..Default::default() },
+                TestLoadOutRecord { reference: "FEEDER_3".to_string(), manufacturer: "RES_MFR1".to_string(), mpn: "RES2".to_string(), ..Default::default() },
+                TestLoadOutRecord { reference: "FEEDER_4".to_string(), manufacturer: "RES_MFR1".to_string(), mpn: "RES1".to_string(), quantity: Some(5), ..Default::default() },
+            ])
+            .as_string();
+        std::fs::write(temp_dir.path().join("load_out_top_1.csv"), load_out_content)?;
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "record-placements-operation", "--object-path-patterns .*R1", "--operation placed"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout")
+                .and(predicate::str::contains("Consumed load-out item quantity. feeder: 'FEEDER_2', part: Part { manufacturer: \"RES_MFR1\", mpn: \"RES1\" }, consumed: 1, remaining: 0"))
+                .and(predicate::str::contains("Consumed load-out item quantity. feeder: 'FEEDER_4', part: Part { manufacturer: \"RES_MFR1\", mpn: \"RES1\" }, consumed: 1, remaining: 4"))
+            );
+
+        // and the two placements are consumed once, emptying the first feeder before the next feeder is used
+        let expected_load_out_content = LoadOutCSVBuilder::new()
+            .with_items(&[
+                TestLoadOutRecord { reference: "FEEDER_1".to_string(), manufacturer: "CAP_MFR1".to_string(), mpn: "CAP1".to_string(), ..Default::default() },
+                TestLoadOutRecord { reference: "FEEDER_2".to_string(), manufacturer: "RES_MFR1".to_string(), mpn: "RES1".to_string(), quantity: Some(0), ..Default::default() },
+                TestLoadOutRecord { reference: "FEEDER_3".to_string(), manufacturer: "RES_MFR1".to_string(), mpn: "RES2".to_string(), ..Default::default() },
+                TestLoadOutRecord { reference: "FEEDER_4".to_string(), manufacturer: "RES_MFR1".to_string(), mpn: "RES1".to_string(), quantity: Some(4), ..Default::default() },
+            ])
+            .as_string();
+        assert_eq!(read_to_string(temp_dir.path().join("load_out_top_1.csv"))?, expected_load_out_content);
+
+        Ok(())
+    }
+
+    #[test]
+    fn unplaced_parts_are_returned() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and
+        let build_load_out_content = |quantity: u32| LoadOutCSVBuilder::new()
+            .with_items(&[
+                TestLoadOutRecord { reference: "FEEDER_1".to_string(), manufacturer: "CAP_MFR1".to_string(), mpn: "CAP1".to_string(), ..Default::default() },
+                TestLoadOutRecord { reference: "FEEDER_2".to_string(), manufacturer: "RES_MFR1".to_string(), mpn: "RES1".to_string(), quantity: Some(quantity), ..Default::default() },
+                TestLoadOutRecord { reference: "FEEDER_3".to_string(), manufacturer: "RES_MFR1".to_string(), mpn: "RES2".to_string(), ..Default::default() },
+            ])
+            .as_string();
+        std::fs::write(temp_dir.path().join("load_out_top_1.csv"), build_load_out_content(5))?;
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "record-placements-operation", "--object-path-patterns .*R1", "--operation placed"]))
+            .assert()
+            .success();
+        assert_eq!(read_to_string(temp_dir.path().join("load_out_top_1.csv"))?, build_load_out_content(3));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "record-placements-operation", "--object-path-patterns .*R1", "--operation unplaced"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout")
+                .and(predicate::str::contains("Returned load-out item quantity. feeder: 'FEEDER_2', part: Part { manufacturer: \"RES_MFR1\", mpn: \"RES1\" }, returned: 2, remaining: 5"))
+            );
+        assert_eq!(read_to_string(temp_dir.path().join("load_out_top_1.csv"))?, build_load_out_content(5));
+
+        // when the placements are placed again
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "record-placements-operation", "--object-path-patterns .*R1", "--operation placed"]))
+            .assert()
+            .success();
+
+        // then the parts are only consumed once
+        assert_eq!(read_to_string(temp_dir.path().join("load_out_top_1.csv"))?, build_load_out_content(3));
+
+        Ok(())
+    }
+
+    #[test]
+    fn restore_load_out() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+        let load_out_path = temp_dir.path().join("load_out_top_1.csv");
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and a bad update
+        let original_content = read_to_string(&load_out_path)?;
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "assign-feeder-to-load-out-item", "--phase top_1", "--feeder-reference FEEDER_99", "--manufacturer ^RES_MFR1$", "--mpn ^RES1$"]))
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Backed up file.")));
+        let modified_content = read_to_string(&load_out_path)?;
+        assert_ne!(modified_content, original_content);
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "restore-load-out", "--phase top_1", "--list"]))
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::starts_with("load_out_top_1.")));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "restore-load-out", "--phase top_1"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Restored file.")));
+
+        // and
+        assert_eq!(read_to_string(&load_out_path)?, original_content);
+
+        // and the modified load-out was backed up before the restore
+        let backups: Vec<String> = std::fs::read_dir(temp_dir.path().join(".backups"))?
+            .map(|entry| read_to_string(entry.unwrap().path()).unwrap())
+            .collect();
+        assert_eq!(backups.len(), 2);
+        assert!(backups.contains(&modified_content));
+
+        Ok(())
+    }
+
+    #[test]
+    fn generate_artifacts_with_shared_load_out() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // when a phase is created that uses the load-out of another phase
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec![
+                "--project example1", path_arg.as_str(), "create-phase",
+                "--process pnp", "--reference top_2", "--load-out load_out_top_1.csv", "--pcb-side top",
+            ]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Phase shares a load-out. phase: 'top_2', source: 'load_out_top_1.csv', phases: [top_1, top_2]")));
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "assign-placements-to-phase", "--phase top_2", "--placements panel=1::unit=2::ref_des=R1", "--allow-reassign"]))
+            .assert()
+            .success();
+
+        // and 'RES1' is assigned to the feeder of 'CAP1'
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec![
+                "--project example1", path_arg.as_str(), "assign-feeder-to-load-out-item",
+                "--phase top_1", "--feeder-reference FEEDER_1", "--manufacturer RES_MFR1", "--mpn RES1",
+            ]))
+            .assert()
+            .success();
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "generate-artifacts"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Phases sharing a load-out require different parts in the same feeder")));
+
+        // and the quantities of the phases are aggregated
+        let report_content = read_to_string(temp_dir.path().join("example1_report.json"))?;
+        let report: serde_json::Value = serde_json::from_str(&report_content)?;
+        let shared_load_out = &report["shared_load_outs"][0];
+        assert_eq!(shared_load_out["load_out_source"], "load_out_top_1.csv");
+        assert_eq!(shared_load_out["phases"], serde_json::json!(["top_1", "top_2"]));
+
+        let res1_item = shared_load_out["items"].as_array().unwrap().iter()
+            .find(|item| item["mpn"] == "RES1")
+            .unwrap();
+        assert_eq!(res1_item["feeder_reference"], "FEEDER_1");
+        assert_eq!(res1_item["phase_quantities"]["top_2"], 1);
+        assert_eq!(res1_item["quantity"].as_u64().unwrap(), res1_item["phase_quantities"]["top_1"].as_u64().unwrap() + 1);
+
+        // and
+        assert!(report_content.contains(r#""SharedFeederConflict""#), "content: {}", report_content);
+
+        Ok(())
+    }
+
+    #[test]
+    fn analyze_load_out_reuse() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let project_path = temp_dir.path().join("example1");
+        let into_arg = format!("--into {}", project_path.to_str().unwrap());
+        let path_arg = format!("--path {}", project_path.to_str().unwrap());
+        let clone_into_arg = format!("--into {}", temp_dir.path().join("job2").to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and a repeat job, in another directory
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "clone-project", "--name job2", clone_into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "analyze-load-out-reuse", "--other-project ../job2/project-job2.mpnp.json"]))
+            // then
+            .assert()
+            .success()
+            .stdout(print("stdout").and(predicate::str::contains(indoc! {"
+                Projects: example1, job2
+                Shared setup: 3 feeders
+                  FEEDER_1: CAP_MFR1:CAP1, projects: [example1, job2]
+                  FEEDER_2: RES_MFR1:RES1, projects: [example1, job2]
+                  FEEDER_3: RES_MFR1:RES2, projects: [example1, job2]
+                Additional feeders:
+                  example1: 0 []
+                  job2: 0 []
+            "})));
+
+        Ok(())
+    }
+}
+
+mod unit_assignments {
+    use std::fs::read_to_string;
+    use assert_cmd::Command;
+    use indoc::indoc;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
+
+    #[test]
+    fn unit_assignments_export_and_import() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+        let units_path = temp_dir.path().join("units.csv");
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "unit-assignments", "export", "--file units.csv"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Exported unit assignments.")));
+
+        // and
+        assert_eq!(read_to_string(&units_path)?, indoc! {r#"
+            "Unit","Design","Variant"
+            "panel=1::unit=1","design_a","variant_a"
+            "panel=1::unit=2","design_a","variant_a"
+        "#});
+
+        // when an invalid file is imported
+        std::fs::write(&units_path, indoc! {r#"
+            "Unit","Design","Variant"
+            "panel=1::unit=3","design_a","variant_a"
+            "panel=1::unit=3","design_a","variant_a"
+        "#})?;
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "unit-assignments", "import", "--file units.csv"]))
+            // then
+            .assert()
+            .failure()
+            .stderr(print("stderr").and(predicate::str::contains("Invalid unit assignments, see the log for details.")))
+            .stdout(print("stdout").and(predicate::str::contains("Duplicate unit assignment. line: 3, unit: 'panel=1::unit=3'")));
+
+        // when
+        std::fs::write(&units_path, indoc! {r#"
+            "Unit","Design","Variant"
+            "panel=1::unit=1","design_a","variant_a"
+            "panel=1::unit=3","design_a","variant_a"
+        "#})?;
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "unit-assignments", "import", "--file units.csv"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Unit assignment added. unit: 'panel=1::unit=3', design_variant: design_a-variant_a")));
+
+        // and
+        let project_content = read_to_string(temp_dir.path().join("project-example1.mpnp.json"))?;
+        assert!(project_content.contains("panel=1::unit=3::ref_des=R1"), "content: {}", project_content);
+
+        Ok(())
+    }
+}
+
+mod bom {
+    use std::fs::read_to_string;
+    use assert_cmd::Command;
+    use indoc::indoc;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
+
+    #[test]
+    fn export_bom() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "export-bom"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Exported BOM. format: CSV")));
+
+        // and
+        assert_eq!(read_to_string(temp_dir.path().join("example1_bom.csv"))?, indoc! {r#"
+            "Manufacturer","Mpn","Quantity","RefDes","Phase top_1","Phase bottom_1","Unit panel=1::unit=1","Unit panel=1::unit=2"
+            "CAP_MFR1","CAP1","2","C1","2","0","1","1"
+            "CONN_MFR1","CONN1","2","J1","0","2","1","1"
+            "RES_MFR1","RES1","2","R1","2","0","1","1"
+            "RES_MFR1","RES2","2","R2","2","0","1","1"
+        "#});
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "export-bom", "--format xlsx", "--file bom.xlsx"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Exported BOM. format: XLSX")));
+
+        // and
+        assert!(temp_dir.path().join("bom.xlsx").exists());
+
+        Ok(())
+    }
+}
+
+mod certificate {
+    use std::fs::read_to_string;
+    use assert_cmd::Command;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
+
+    #[test]
+    fn generate_certificate_on_phase_completion() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "record-placements-operation", "--object-path-patterns .*J1", "--operation placed"]))
+            .assert()
+            .success();
+
+        // and the phase is not complete
+        assert!(!temp_dir.path().join("bottom_1_certificate.json").exists());
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "record-phase-operation", "--phase bottom_1", "--operation loadpcbs", "--set completed"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Generated phase certificate. phase: 'bottom_1'")));
+
+        // and
+        let certificate: serde_json::Value = serde_json::from_str(&read_to_string(temp_dir.path().join("bottom_1_certificate.json"))?)?;
+        assert_eq!(certificate["placed"], 2);
+        assert_eq!(certificate["total"], 2);
+        assert_eq!(certificate["outstanding_issues"], serde_json::json!([]));
+
+        // and
+        let markdown = read_to_string(temp_dir.path().join("bottom_1_certificate.md"))?;
+        assert!(markdown.contains("| CONN_MFR1 | CONN1 | 2 |"));
+
+        // and
+        let signing_key_path = temp_dir.path().join("signing.key");
+        std::fs::write(&signing_key_path, "01".repeat(32))?;
+        let signing_key_arg = format!("--signing-key {}", signing_key_path.to_str().unwrap());
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "generate-certificate", "--phase bottom_1", signing_key_arg.as_str()]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("signed: true")));
+
+        // and
+        assert!(temp_dir.path().join("bottom_1_certificate.json.sig").exists());
+        assert!(temp_dir.path().join("bottom_1_certificate.md.sig").exists());
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "generate-certificate", "--phase top_1"]))
+            // then
+            .assert()
+            .failure()
+            .stderr(print("stderr").and(predicate::str::contains("Phase is not complete. phase: 'top_1'")))
+            .stdout(print("stdout"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn sign_certificate_on_phase_completion_using_key_preferences() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let config_dir = temp_dir.path().join("config");
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and
+        let signing_key_path = temp_dir.path().join("signing.key");
+        std::fs::write(&signing_key_path, "01".repeat(32))?;
+        let value_arg = format!("--value {}", signing_key_path.to_str().unwrap());
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .env("MAKERPNP_CONFIG_DIR", &config_dir)
+            .args(prepare_args(vec!["--project example1", "config", "set", "--key signing-key", value_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .env("MAKERPNP_CONFIG_DIR", &config_dir)
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "record-placements-operation", "--object-path-patterns .*J1", "--operation placed"]))
+            .assert()
+            .success();
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .env("MAKERPNP_CONFIG_DIR", &config_dir)
+            .env_remove("MAKERPNP_SIGNING_KEY")
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "record-phase-operation", "--phase bottom_1", "--operation loadpcbs", "--set completed"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Generated phase certificate. phase: 'bottom_1'").and(predicate::str::contains("signed: true"))));
+
+        // and
+        assert!(temp_dir.path().join("bottom_1_certificate.json.sig").exists());
+        assert!(temp_dir.path().join("bottom_1_certificate.md.sig").exists());
+
+        Ok(())
+    }
+}
+
+mod signing {
+    use assert_cmd::Command;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
+
+    #[test]
+    fn sign_and_verify_using_key_preferences() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let config_dir = temp_dir.path().join("config");
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and the key pair for the secret key of 32 '0x01' bytes
+        let signing_key_path = temp_dir.path().join("signing.key");
+        std::fs::write(&signing_key_path, "01".repeat(32))?;
+        let verifying_key_path = temp_dir.path().join("verifying.key");
+        std::fs::write(&verifying_key_path, "8a88e3dd7409f195fd52db2d3cba5d72ca6709bf1d94121bf3748801b40f6f5c")?;
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .env("MAKERPNP_CONFIG_DIR", &config_dir)
+            .env_remove("MAKERPNP_VERIFYING_KEY")
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "verify"]))
+            // then
+            .assert()
+            .failure()
+            .stderr(print("stderr").and(predicate::str::contains("No verifying key, use '--verifying-key' or set the 'verifying-key' preference")))
+            .stdout(print("stdout"));
+
+        // and
+        for (key, path) in [("signing-key", &signing_key_path), ("verifying-key", &verifying_key_path)] {
+            let key_arg = format!("--key {}", key);
+            let value_arg = format!("--value {}", path.to_str().unwrap());
+            Command::new(env!("CARGO_BIN_EXE_planner"))
+                .env("MAKERPNP_CONFIG_DIR", &config_dir)
+                .args(prepare_args(vec!["--project example1", "config", "set", key_arg.as_str(), value_arg.as_str()]))
+                .assert()
+                .success();
+        }
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .env("MAKERPNP_CONFIG_DIR", &config_dir)
+            .env_remove("MAKERPNP_SIGNING_KEY")
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "generate-artifacts"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout"));
+
+        // and
+        assert!(temp_dir.path().join("example1_report.json.sig").exists());
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .env("MAKERPNP_CONFIG_DIR", &config_dir)
+            .env_remove("MAKERPNP_VERIFYING_KEY")
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "verify"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout"));
+
+        // when an artifact is modified
+        std::fs::write(temp_dir.path().join("example1_report.json"), "{}")?;
+
+        // then
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .env("MAKERPNP_CONFIG_DIR", &config_dir)
+            .env_remove("MAKERPNP_VERIFYING_KEY")
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "verify"]))
+            .assert()
+            .failure();
+
+        Ok(())
+    }
+}
+
+mod pcbs {
+    use std::fs::read_to_string;
+    use assert_cmd::Command;
+    use indoc::indoc;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use rust_decimal_macros::dec;
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
+    use crate::common::phase_placement_builder::{PhasePlacementsCSVBuilder, TestPhasePlacementRecord};
+
+    #[test]
+    fn multiple_pcbs() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "add-pcb", "--kind panel", "--name panel_a"]))
+            // then
+            .assert()
+            .failure()
+            .stderr(print("stderr").and(predicate::str::contains("A PCB with the name already exists. name: 'panel_a'")))
+            .stdout(print("stdout"));
+
+        // and
+        for args in [
+            vec!["add-pcb", "--kind panel", "--name display_board"],
+            vec!["assign-variant-to-unit", "--design design_a", "--variant variant_a", "--unit panel=2::unit=1"],
+            vec!["create-phase", "--process pnp", "--reference display_top_1", "--load-out load_out_display_top_1.csv", "--pcb-side top"],
+            vec!["assign-placements-to-phase", "--phase display_top_1", "--placements panel=2::.*"],
+        ] {
+            Command::new(env!("CARGO_BIN_EXE_planner"))
+                .args(prepare_args([vec!["--project example1", path_arg.as_str()], args].concat()))
+                .assert()
+                .success();
+        }
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "list-phases", "--pcb display_board"]))
+            // then 'top_1' is not listed, only 'display_top_1'
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("display_top_1 process: pnp").and(predicate::str::contains("top_1 process: pnp").count(1))));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "export-bom", "--pcb display_board"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout"));
+
+        // and
+        assert_eq!(read_to_string(temp_dir.path().join("example1_display_board_bom.csv"))?, indoc! {r#"
+            "Manufacturer","Mpn","Quantity","RefDes","Phase display_top_1","Unit panel=2::unit=1"
+            "CAP_MFR1","CAP1","1","C1","1","1"
+            "CONN_MFR1","CONN1","1","J1","0","1"
+            "RES_MFR1","RES1","1","R1","1","1"
+            "RES_MFR1","RES2","1","R2","1","1"
+        "#});
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "generate-artifacts"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout"));
+
+        // and each phase only prepares the PCBs of its placements
+        let report: serde_json::Value = serde_json::from_str(&read_to_string(temp_dir.path().join("example1_report.json"))?)?;
+        assert_eq!(report["phase_overviews"][0]["pcbs"], serde_json::json!(["panel_a"]));
+        assert_eq!(report["phase_overviews"][2]["pcbs"], serde_json::json!(["display_board"]));
+        assert_eq!(report["phase_specifications"][2]["operations"][0]["PreparePcbs"]["pcbs"][0]["Panel"]["name"], "display_board");
+
+        Ok(())
+    }
+
+    #[test]
+    fn panel_geometry() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and only the first unit is in the geometry
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "set-panel-geometry", "--pcb panel_a", "--unit 1,5,5,0"]))
+            .assert()
+            .success();
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "generate-artifacts"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout"));
+
+        // and
+        let report: serde_json::Value = serde_json::from_str(&read_to_string(temp_dir.path().join("example1_report.json"))?)?;
+        let issues = report["issues"].as_array().unwrap();
+        assert!(issues.iter().any(|issue| issue["kind"]["MissingPanelUnitGeometry"]["object_path"].eq("panel=1::unit=2")));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec![
+                "--project example1", path_arg.as_str(), "set-panel-geometry", "--pcb panel_a",
+                "--unit 1,5,5,0", "--unit 2,105,5,180", "--fiducial FID1,2,2", "--fiducial FID2,108,2",
+            ]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Panel geometry updated. name: 'panel_a', units: 2, fiducials: 2")));
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "generate-artifacts"]))
+            .assert()
+            .success();
+
+        // and the second unit is rotated by 180 degrees
+        let expected_placements_content = PhasePlacementsCSVBuilder::new()
+            .with_items(&[
+                TestPhasePlacementRecord {
+                    object_path: "panel=1::unit=1::ref_des=C1".to_string(),
+                    feeder_reference: "FEEDER_1".to_string(),
+                    manufacturer: "CAP_MFR1".to_string(),
+                    mpn: "CAP1".to_string(),
+                    x: dec!(15),
+                    y: dec!(25),
+                    rotation: dec!(-90),
+                },
+                TestPhasePlacementRecord {
+                    object_path: "panel=1::unit=1::ref_des=R1".to_string(),
+                    feeder_reference: "FEEDER_2".to_string(),
+                    manufacturer: "RES_MFR1".to_string(),
+                    mpn: "RES1".to_string(),
+                    x: dec!(15),
+                    y: dec!(15),
+                    rotation: dec!(0),
+                },
+                TestPhasePlacementRecord {
+                    object_path: "panel=1::unit=1::ref_des=R2".to_string(),
+                    feeder_reference: "FEEDER_3".to_string(),
+                    manufacturer: "RES_MFR1".to_string(),
+                    mpn: "RES2".to_string(),
+                    x: dec!(25),
+                    y: dec!(15),
+                    rotation: dec!(90),
+                },
+                TestPhasePlacementRecord {
+                    object_path: "panel=1::unit=2::ref_des=C1".to_string(),
+                    feeder_reference: "FEEDER_1".to_string(),
+                    manufacturer: "CAP_MFR1".to_string(),
+                    mpn: "CAP1".to_string(),
+                    x: dec!(95),
+                    y: dec!(-15),
+                    rotation: dec!(90),
+                },
+                TestPhasePlacementRecord {
+                    object_path: "panel=1::unit=2::ref_des=R1".to_string(),
+                    feeder_reference: "FEEDER_2".to_string(),
+                    manufacturer: "RES_MFR1".to_string(),
+                    mpn: "RES1".to_string(),
+                    x: dec!(95),
+                    y: dec!(-5),
+                    rotation: dec!(180),
+                },
+                TestPhasePlacementRecord {
+                    object_path: "panel=1::unit=2::ref_des=R2".to_string(),
+                    feeder_reference: "FEEDER_3".to_string(),
+                    manufacturer: "RES_MFR1".to_string(),
+                    mpn: "RES2".to_string(),
+                    x: dec!(85),
+                    y: dec!(-5),
+                    rotation: dec!(-90),
+                },
+            ])
+            .as_string();
+        assert_eq!(read_to_string(temp_dir.path().join("top_1_placements.csv"))?, expected_placements_content);
+
+        // and the geometry is saved in the project
+        let project: serde_json::Value = serde_json::from_str(&read_to_string(temp_dir.path().join("project-example1.mpnp.json"))?)?;
+        assert_eq!(project["pcbs"][0]["geometry"]["fiducials"][1], serde_json::json!({ "name": "FID2", "x": "108", "y": "2" }));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "set-panel-geometry", "--pcb panel_a", "--unit 1,5,5,0", "--unit 1,55,5,0"]))
+            // then
+            .assert()
+            .failure()
+            .stderr(print("stderr").and(predicate::str::contains("Duplicate panel unit. unit: 1")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn placements_outside_pcb_dimensions() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and the second unit is rotated about its origin, which is on the bottom edge of the panel, moving it off the panel
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "set-panel-geometry", "--pcb panel_a", "--unit 1,5,5,0", "--unit 2,105,5,180"]))
+            .assert()
+            .success();
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "set-pcb-dimensions", "--pcb panel_a", "--width 110", "--height 40"]))
+            // then
+            .assert()
+            .success()
+            .stdout(print("stdout").and(predicate::str::contains("PCB dimensions updated. name: 'panel_a', width: 110, height: 40")));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "generate-artifacts"]))
+            // then
+            .assert()
+            .success()
+            .stdout(print("stdout").and(predicate::str::contains(
+                "Placement outside PCB. object_path: panel=1::unit=2::ref_des=C1, x: 95, y: -15, pcb: 'panel_a', width: 110, height: 40"
+            )));
+
+        // and only the placements of the second unit are outside the panel
+        let report: serde_json::Value = serde_json::from_str(&read_to_string(temp_dir.path().join("example1_report.json"))?)?;
+        let object_paths: Vec<&str> = report["issues"].as_array().unwrap().iter()
+            .filter_map(|issue| issue["kind"]["PlacementOutsidePcb"]["object_path"].as_str())
+            .collect();
+        assert_eq!(object_paths, vec!["panel=1::unit=2::ref_des=C1", "panel=1::unit=2::ref_des=J1", "panel=1::unit=2::ref_des=R1", "panel=1::unit=2::ref_des=R2"]);
+
+        // when the origin of the second unit is corrected
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "set-panel-geometry", "--pcb panel_a", "--unit 1,5,5,0", "--unit 2,105,45,180"]))
+            .assert()
+            .success();
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "generate-artifacts"]))
+            .assert()
+            .success();
+
+        // then
+        let report: serde_json::Value = serde_json::from_str(&read_to_string(temp_dir.path().join("example1_report.json"))?)?;
+        assert!(!report["issues"].as_array().unwrap().iter().any(|issue| issue["kind"].get("PlacementOutsidePcb").is_some()));
+
+        Ok(())
+    }
+}
+
+mod operation_checklist {
+    use std::fs::read_to_string;
+    use assert_cmd::Command;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
+
+    #[test]
+    fn operation_checklist() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let config_dir = temp_dir.path().join("config");
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .env("MAKERPNP_CONFIG_DIR", &config_dir)
+            .args(prepare_args(vec!["--project example1", "config", "set", "--key operator", "--value Operator1"]))
+            .assert()
+            .success();
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec![
+                "--project example1", path_arg.as_str(), "set-operation-checklist",
+                "--process pnp", "--operation reflowcomponents", "--item stencil=STN-001", "--item paste=SAC305",
+            ]))
+            // then
+            .assert()
+            .success()
+            .stdout(print("stdout").and(predicate::str::contains(
+                "Operation checklist updated. process: 'pnp', operation: ReflowComponents, old: [], new: [\"stencil=STN-001\", \"paste=SAC305\"]"
+            )));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .env("MAKERPNP_CONFIG_DIR", &config_dir)
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "start-phase-operation", "--phase top_1", "--operation reflowcomponents", "--confirm paste=SAC305"]))
+            // then
+            .assert()
+            .failure()
+            .stderr(print("stderr").and(predicate::str::contains(
+                "Checklist not confirmed. phase: 'top_1', operation: ReflowComponents, unconfirmed: [\"stencil=STN-001\"]"
+            )));
+
+        // and
+        assert!(!temp_dir.path().join("top_1_log.json").exists());
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .env("MAKERPNP_CONFIG_DIR", &config_dir)
+            .args(prepare_args(vec![
+                "--project example1", path_arg.as_str(), "start-phase-operation", "--phase top_1", "--operation reflowcomponents",
+                "--confirm paste=SAC305", "--confirm stencil=STN-001",
+            ]))
+            // then
+            .assert()
+            .success();
+
+        // and the confirmations are recorded in the operation history
+        let operation_history: serde_json::Value = serde_json::from_str(&read_to_string(temp_dir.path().join("top_1_log.json"))?)?;
+        assert_eq!(operation_history[0]["operation"], serde_json::json!({
+            "OperationStarted": {
+                "operation": "ReflowComponents",
+                "confirmed": ["stencil=STN-001", "paste=SAC305"],
+                "operator": "Operator1",
+            }
+        }));
+
+        // and operations without a checklist can be started without confirmations
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .env("MAKERPNP_CONFIG_DIR", &config_dir)
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "start-phase-operation", "--phase bottom_1", "--operation loadpcbs"]))
+            .assert()
+            .success();
+
+        Ok(())
+    }
+}
+
+mod quantity_check {
+    use assert_cmd::Command;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
+
+    #[test]
+    fn quantity_check() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let config_dir = temp_dir.path().join("config");
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and only one 'RES1' remains, but two are required
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec![
+                "--project example1", path_arg.as_str(), "assign-feeder-to-load-out-item",
+                "--phase top_1", "--feeder-reference FEEDER_1", "--manufacturer RES_MFR1", "--mpn RES1", "--quantity 1",
+            ]))
+            .assert()
+            .success();
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "set-quantity-check", "--phase top_1", "--mode block"]))
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Phase quantity check set. phase: 'top_1', old: None, new: Some(Block)")));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .env("MAKERPNP_CONFIG_DIR", &config_dir)
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "start-phase-operation", "--phase top_1", "--operation automatedpnp"]))
+            // then
+            .assert()
+            .failure()
+            .stderr(print("stderr").and(predicate::str::contains(
+                "Insufficient load-out item quantities, the operation was not started. phase: 'top_1', shortfalls: [RES_MFR1:RES1 (feeder: 'FEEDER_1', remaining: 1, required: 2)]"
+            )));
+
+        // and
+        assert!(!temp_dir.path().join("top_1_log.json").exists());
+
+        // and operations that do not place parts are not checked
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .env("MAKERPNP_CONFIG_DIR", &config_dir)
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "start-phase-operation", "--phase top_1", "--operation loadpcbs"]))
+            .assert()
+            .success();
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .env("MAKERPNP_CONFIG_DIR", &config_dir)
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "start-phase-operation", "--phase top_1", "--operation automatedpnp", "--allow-insufficient-quantities"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Insufficient load-out item quantities overridden. phase: 'top_1', shortfalls: 1")));
+
+        Ok(())
+    }
+}
+
+mod analytics {
+    use std::fs::read_to_string;
+    use assert_cmd::Command;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
+
+    #[test]
+    fn analytics() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let config_dir = temp_dir.path().join("config");
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .env("MAKERPNP_CONFIG_DIR", &config_dir)
+            .args(prepare_args(vec!["--project example1", "config", "set", "--key operator", "--value Operator1"]))
+            .assert()
+            .success();
+
+        // and an operation is started and completed, and placements are placed and inspected
+        for args in [
+            vec!["start-phase-operation", "--phase top_1", "--operation loadpcbs"],
+            vec!["record-phase-operation", "--phase top_1", "--operation loadpcbs", "--set completed"],
+            vec!["record-placements-operation", "--object-path-patterns panel=1::unit=1::ref_des=R1,panel=1::unit=2::ref_des=R1", "--operation placed"],
+            vec!["record-placements-operation", "--object-path-patterns panel=1::unit=2::ref_des=R1", "--operation inspectionfailed"],
+        ] {
+            Command::new(env!("CARGO_BIN_EXE_planner"))
+                .env("MAKERPNP_CONFIG_DIR", &config_dir)
+                .args(prepare_args([vec!["--project example1", path_arg.as_str()], args].concat()))
+                .assert()
+                .success();
+        }
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "analytics", "--file analytics.csv"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Exported analytics. format: CSV")));
+
+        // and
+        let content = read_to_string(temp_dir.path().join("analytics.csv"))?;
+        assert!(content.starts_with(r#""Group","Key","Cycles","MinCycleTime","MeanCycleTime","MaxCycleTime","Placed","InspectionFailed","Yield""#), "content: {}", content);
+        assert!(content.contains(r#""Process","pnp","1","#), "content: {}", content);
+        assert!(content.contains(r#""Operator","Operator1","1","#), "content: {}", content);
+        assert!(content.contains(r#""Part","RES_MFR1:RES1","0","","","","2","1","50""#), "content: {}", content);
+
+        Ok(())
+    }
+}
+
+mod metrics {
+    use assert_cmd::Command;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
+
+    #[test]
+    fn metrics() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let config_dir = temp_dir.path().join("config");
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and an operation is started and completed, and placements are placed and inspected
+        for args in [
+            vec!["start-phase-operation", "--phase top_1", "--operation loadpcbs"],
+            vec!["record-phase-operation", "--phase top_1", "--operation loadpcbs", "--set completed"],
+            vec!["record-placements-operation", "--object-path-patterns panel=1::unit=1::ref_des=R1,panel=1::unit=2::ref_des=R1", "--operation placed"],
+            vec!["record-placements-operation", "--object-path-patterns panel=1::unit=2::ref_des=R1", "--operation inspectionfailed"],
+        ] {
+            Command::new(env!("CARGO_BIN_EXE_planner"))
+                .env("MAKERPNP_CONFIG_DIR", &config_dir)
+                .args(prepare_args([vec!["--project example1", path_arg.as_str()], args].concat()))
+                .assert()
+                .success();
+        }
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "metrics", "--phase top_1"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout")
+                .and(predicate::str::contains("Phase: top_1"))
+                .and(predicate::str::contains("Placed: 2, unplaced: 0, inspection failed: 1"))
+                .and(predicate::str::contains("Operation: LoadPcbs, cycles: 1, mean time: "))
+            );
+
+        // when the history is filtered
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "metrics", "--phase top_1", "--since 2100-01-01T00:00:00Z"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout")
+                .and(predicate::str::contains("History items: 0"))
+                .and(predicate::str::contains("Placements per hour: -"))
+            );
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "metrics", "--phase top_1", "--since yesterday"]))
+            // then
+            .assert()
+            .failure()
+            .stderr(print("stderr").and(predicate::str::contains("invalid value 'yesterday' for '--since <SINCE>'")));
+
+        Ok(())
+    }
+}
+
+mod placements_operation {
+    use assert_cmd::Command;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
+
+    #[test]
+    fn record_placements_operation_with_object_paths() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // when a malformed object path is given
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "record-placements-operation", "--object-paths panel=1::unit=x::ref_des=R1", "--operation placed"]))
+            // then
+            .assert()
+            .failure()
+            .stderr(print("stderr").and(predicate::str::contains("Invalid index in path. index: 'x'")));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "record-placements-operation", "--object-paths panel=1::unit=*::ref_des=R?", "--operation placed"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout")
+                .and(predicate::str::contains("Setting placed flag. object_path: panel=1::unit=1::ref_des=R1"))
+                .and(predicate::str::contains("Setting placed flag. object_path: panel=1::unit=2::ref_des=R2"))
+                .and(predicate::str::contains("ref_des=C1").not())
+            );
+
+        Ok(())
+    }
+}
+
+mod production_runs {
+    use assert_cmd::Command;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
+
+    #[test]
+    fn production_runs() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and
+        for args in [
+            vec!["set-production-quantity", "--quantity 2"],
+            vec!["start-production-run", "--serial SN0001"],
+            vec!["record-placements-operation", "--object-path-patterns .*unit=1::", "--operation placed"],
+        ] {
+            Command::new(env!("CARGO_BIN_EXE_planner"))
+                .args(prepare_args([vec!["--project example1", path_arg.as_str()], args].concat()))
+                .assert()
+                .success();
+        }
+
+        // when the run is incomplete
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "complete-production-run"]))
+            // then
+            .assert()
+            .failure()
+            .stderr(print("stderr").and(predicate::str::contains("Production run is incomplete, use '--force' to complete it anyway. serial: 'SN0001', placed: 4/8")));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "record-placements-operation", "--object-path-patterns .*", "--operation placed"]))
+            .assert()
+            .success();
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "complete-production-run"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Completed production run. serial: 'SN0001', placed: 8/8")));
+
+        // when the next run is started
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "start-production-run", "--serial SN0002"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout")
+                .and(predicate::str::contains("Placement operations reset."))
+                .and(predicate::str::contains("Started production run. serial: 'SN0002', run: 2"))
+            );
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "production-runs"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout")
+                .and(predicate::str::contains("Production runs: quantity: 2, completed: 1, in progress: SN0002, pending: 0"))
+                .and(predicate::str::contains("Run: SN0001, status: completed, placed: 8/8, "))
+                .and(predicate::str::contains("Run: SN0002, status: in progress, "))
+            );
+
+        Ok(())
+    }
+}
+
+mod log_json {
+    use std::fs::read_to_string;
+    use assert_cmd::Command;
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
+
+    #[test]
+    fn log_json() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+        let log_path = temp_dir.path().join("log.jsonl");
+        let log_json_arg = format!("--log-json {}", log_path.to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // when
+        for args in [
+            vec!["create-phase", "--process pnp", "--reference top_2", "--load-out load_out_2.csv", "--pcb-side top"],
+            vec!["record-placements-operation", "--object-paths panel=1::unit=1::ref_des=R1", "--operation placed"],
+        ] {
+            Command::new(env!("CARGO_BIN_EXE_planner"))
+                .args(prepare_args([vec!["--project example1", path_arg.as_str(), log_json_arg.as_str()], args].concat()))
+                .assert()
+                .success()
+                .stderr(print("stderr"))
+                .stdout(print("stdout"));
+        }
+
+        // then there is one event per line, for each change, with the rendered message and the changed values as fields
+        let events: Vec<serde_json::Value> = read_to_string(&log_path)?.lines()
+            .map(serde_json::from_str)
+            .collect::<Result<_, _>>()?;
+
+        let created_phase = events.iter()
+            .find(|event| event["message"] == "Created phase. reference: 'top_2', process: pnp, load_out: load_out_2.csv")
+            .expect("created phase event");
+        assert_eq!(created_phase["level"], "INFO");
+        assert_eq!(created_phase["target"], "mutation");
+        assert_eq!(created_phase["command"], "create-phase");
+        assert_eq!(created_phase["fields"], serde_json::json!({ "reference": "top_2", "process": "pnp", "load_out": "load_out_2.csv" }));
+
+        // and the events of the commands are appended
+        let placed = events.iter()
+            .find(|event| event["message"] == "Setting placed flag. object_path: panel=1::unit=1::ref_des=R1")
+            .expect("placed event");
+        assert_eq!(placed["command"], "record-placements-operation");
+        assert_eq!(placed["fields"], serde_json::json!({ "object_path": "panel=1::unit=1::ref_des=R1" }));
+
+        // and only the changes are logged
+        assert!(events.iter().all(|event| event["target"] == "mutation"));
+
+        Ok(())
+    }
+}
+
+mod history {
+    use assert_cmd::Command;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
+
+    #[test]
+    fn history() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .env("USER", "user1")
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "set-phase-tags", "--phase top_1", "--tag line=A"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Recorded audit log item. command: 'set-phase-tags', changes: 1")));
+
+        // and commands that do not change the project are not recorded
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "status"]))
+            .assert()
+            .success()
+            .stdout(print("stdout").and(predicate::str::contains("Recorded audit log item").not()));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "history", "--limit 1"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::is_match(r"(?m)^\S+ user1 set-phase-tags: Phase tag set. phase: 'top_1', tag: 'line=A'$")?));
+
+        Ok(())
+    }
+
+    #[test]
+    fn history_production_commands() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .env("USER", "user1")
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "record-placements-operation", "--object-path-patterns .*R1", "--operation placed"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Recorded audit log item. command: 'record-placements-operation'")));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .env("USER", "user1")
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "reset-operations"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Recorded audit log item. command: 'reset-operations'")));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "history"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout")
+                .and(predicate::str::is_match(r"(?m)^\S+ user1 record-placements-operation: Setting placed flag. object_path: panel=1::unit=1::ref_des=R1; Setting placed flag. object_path: panel=1::unit=2::ref_des=R1$")?)
+                .and(predicate::str::is_match(r"(?m)^\S+ user1 reset-operations: Operations reset. placements: 8, phases: 2$")?)
+            );
+
+        Ok(())
+    }
+
+    #[test]
+    fn history_load_out_and_operation_history_commands() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // when the load-out is changed, but the project is not
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .env("USER", "user1")
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "set-load-out-item-quantity", "--phase top_1", "--feeder FEEDER_2", "--quantity 500"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Recorded audit log item. command: 'set-load-out-item-quantity', changes: 1")));
+
+        // when only the operation history is changed
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .env("USER", "user1")
+            .env("MAKERPNP_CONFIG_DIR", temp_dir.path())
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "start-phase-operation", "--phase top_1", "--operation loadpcbs"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Recorded audit log item. command: 'start-phase-operation', changes: 1")));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "history"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout")
+                .and(predicate::str::is_match(r#"(?m)^\S+ user1 set-load-out-item-quantity: Set load-out item quantity. feeder: 'FEEDER_2', part: Part \{ manufacturer: "RES_MFR1", mpn: "RES1" \}, old: None, new: Some\(500\)$"#)?)
+                .and(predicate::str::is_match(r"(?m)^\S+ user1 start-phase-operation: Recorded operation started. phase: 'top_1', operation: LoadPcbs, confirmed: \[\]$")?)
+            );
+
+        Ok(())
+    }
+}
+
+mod machine {
+    use std::fs::read_to_string;
+    use assert_cmd::Command;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
+
+    #[test]
+    fn run_phase() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "run-phase", "--phase top_1", "--machine simulated"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout")
+                .and(predicate::str::contains("Machine status. machine: simulated, status: placed, object_path: panel=1::unit=1::ref_des=R1"))
+                .and(predicate::str::contains("Ran phase. phase: 'top_1', machine: simulated, placed: 6, remaining: 0"))
+            );
+
+        // and the placements are recorded in the phase log
+        let phase_log = read_to_string(temp_dir.path().join("top_1_log.json"))?;
+        assert!(phase_log.contains(r#""object_path": "panel=1::unit=1::ref_des=R1""#), "phase_log: {}", phase_log);
+
+        // when the phase is run again
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "run-phase", "--phase top_1", "--machine simulated"]))
+            // then there is nothing left to place
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Ran phase. phase: 'top_1', machine: simulated, placed: 0, remaining: 0")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn simulated_machine_progress() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "generate-artifacts"]))
+            .assert()
+            .success();
+
+        // and
+        let placements_arg = format!("--placements {}", temp_dir.path().join("top_1_placements.csv").to_str().unwrap());
+        let log_path = temp_dir.path().join("top_1_machine_log.csv");
+        let log_arg = format!("--log {}", log_path.to_str().unwrap());
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_makerpnp_sim"))
+            .args(prepare_args(vec![placements_arg.as_str(), log_arg.as_str(), "--attrition 0.5", "--retries 0", "--seed 3"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Simulation complete. placed: 4, skipped: 2, pick_failures: 2")));
+
+        // and the placed placements are recorded, as an operator would, using the machine log
+        let mut csv_reader = csv::ReaderBuilder::new().from_path(&log_path)?;
+        let placed_object_path_patterns: Vec<String> = csv_reader.records()
+            .map(|record| record.unwrap())
+            .filter(|record| record.get(9).eq(&Some("Placed")))
+            .map(|record| format!("^{}$", record.get(1).unwrap()))
+            .collect();
+        assert_eq!(placed_object_path_patterns.len(), 4);
+
+        let object_path_patterns_arg = format!("--object-path-patterns {}", placed_object_path_patterns.join(","));
+
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "record-placements-operation", object_path_patterns_arg.as_str(), "--operation placed"]))
+            .assert()
+            .success();
+
+        // then
+        let project_content = read_to_string(temp_dir.path().join("project-example1.mpnp.json"))?;
+        assert_eq!(project_content.matches(r#""placed": true"#).count(), 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn generate_artifacts_with_machine_export() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec![
+                "--project example1", path_arg.as_str(), "create-phase",
+                "--process pnp", "--reference top_1", "--load-out load_out_top_1.csv", "--pcb-side top", "--export-format centroid",
+            ]))
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Phase export format set. phase: 'top_1', old: None, new: Some(Centroid)")));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "generate-artifacts"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Generated machine export. phase: 'top_1'")));
+
+        // and the designators of the two units are unique
+        let content = read_to_string(temp_dir.path().join("top_1_centroid.csv"))?;
+        assert!(content.starts_with(r#""Designator","Layer","MidX","MidY","Rotation","Manufacturer","Mpn","Package""#), "content: {}", content);
+        assert!(content.contains(r#""R1_1","Top","#), "content: {}", content);
+        assert!(content.contains(r#""R1_2","Top","#), "content: {}", content);
+
+        // and phases without an export format are not exported
+        assert!(!temp_dir.path().join("bottom_1_centroid.csv").exists());
+
+        Ok(())
+    }
+}
+
+mod issues {
+    use std::fs::read_to_string;
+    use assert_cmd::Command;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
+
+    #[test]
+    fn waive_issue() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and a unit that is not on any PCB, which is an error
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "assign-variant-to-unit", "--design design_a", "--variant variant_a", "--unit panel=2::unit=1"]))
+            .assert()
+            .success();
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "release"]))
+            .assert()
+            .failure()
+            .stderr(print("stderr").and(predicate::str::contains("The project has errors, resolve them before releasing. Health: 1 error, 4 warnings")));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "acknowledge-issue", "--id 00000000", "--reason Seen"]))
+            // then
+            .assert()
+            .failure()
+            .stderr(print("stderr").and(predicate::str::contains("Unknown issue, the ids of the issues are listed in the report. id: '00000000'")));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "waive-issue", "--id 782828f9", "--reason Deferred"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr").and(predicate::str::contains("Health: 0 errors, 4 warnings, 1 waived,")))
+            .stdout(print("stdout").and(predicate::str::contains(
+                "Issue resolved. id: '782828f9', status: Waived, message: 'Invalid unit assignment, index out of range.', reason: 'Deferred'"
+            )));
+
+        // and the waived issue is still listed in the report
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "generate-artifacts"]))
+            .assert()
+            .success();
+
+        let report: serde_json::Value = serde_json::from_str(&read_to_string(temp_dir.path().join("example1_report.json"))?)?;
+        assert_eq!(report["issues"][0]["id"], "782828f9");
+        assert_eq!(report["issues"][0]["resolution"]["status"], "waived");
+        assert_eq!(report["issues"][0]["resolution"]["reason"], "Deferred");
+
+        // and waived issues do not prevent a release
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "release"]))
+            .assert()
+            .success();
+
+        Ok(())
+    }
+}
+
+mod feeders {
+    use std::fs::read_to_string;
+    use assert_cmd::Command;
+    use indoc::indoc;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
+
+    #[test]
+    fn assign_feeder_using_feeder_library() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and
+        std::fs::write(temp_dir.path().join("parts.csv"), indoc! {r#"
+            "Manufacturer","Mpn","Package"
+            "RES_MFR1","RES1","0402"
+            "CAP_MFR1","CAP1","0603"
+        "#})?;
+        std::fs::write(temp_dir.path().join("feeders.csv"), indoc! {r#"
+            "Reference","Type","TapeWidth","Packages","Capacity"
+            "FEEDER_1","tape","8","0402","5000"
+            "FEEDER_2","tape","8","0603","5000"
+        "#})?;
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "import-part-details", "--parts parts.csv"]))
+            .assert()
+            .success();
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec![
+                "--project example1", path_arg.as_str(), "assign-feeder-to-load-out-item",
+                "--phase top_1", "--feeder-reference FEEDER_1", "--manufacturer CAP_MFR1", "--mpn CAP1", "--feeders feeders.csv",
+            ]))
+            // then
+            .assert()
+            .failure()
+            .stderr(print("stderr").and(predicate::str::contains(
+                r#"Incompatible feeder. part: CAP_MFR1:CAP1, reason: Package not compatible with the feeder. feeder: 'FEEDER_1', package: '0603', packages: ["0402"]"#
+            )));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec![
+                "--project example1", path_arg.as_str(), "assign-feeder-to-load-out-item",
+                "--phase top_1", "--feeder-reference FEEDER_1", "--manufacturer RES_MFR1", "--mpn RES1", "--quantity 6000", "--feeders feeders.csv",
+            ]))
+            // then
+            .assert()
+            .failure()
+            .stderr(print("stderr").and(predicate::str::contains("Quantity exceeds the capacity of the feeder. feeder: 'FEEDER_1', quantity: 6000, capacity: 5000")));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec![
+                "--project example1", path_arg.as_str(), "assign-feeder-to-load-out-item",
+                "--phase top_1", "--feeder-reference FEEDER_9", "--manufacturer RES_MFR1", "--mpn RES1", "--feeders feeders.csv",
+            ]))
+            // then
+            .assert()
+            .failure()
+            .stderr(print("stderr").and(predicate::str::contains("Unknown feeder, the feeder is not in the feeder library. feeder: 'FEEDER_9'")));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec![
+                "--project example1", path_arg.as_str(), "assign-feeder-to-load-out-item",
+                "--phase top_1", "--feeder-reference FEEDER_1", "--manufacturer RES_MFR1", "--mpn RES1", "--quantity 5000", "--feeders feeders.csv",
+            ]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Assigned feeder to load-out item. feeder: FEEDER_1")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn feeder_setup_sheet() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and
+        std::fs::write(temp_dir.path().join("part_details.csv"), indoc! {r#"
+            "Manufacturer","Mpn","Description","TapeWidth"
+            "RES_MFR1","RES1","10K 1% resistor","8"
+        "#})?;
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "import-part-details", "--parts part_details.csv"]))
+            .assert()
+            .success();
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "generate-artifacts"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Generated feeder setup sheet. phase: 'top_1'")));
+
+        // and
+        let expected_content = indoc! {r#"
+            "FeederReference","Manufacturer","Mpn","Value","Description","Package","Quantity","TapeWidth"
+            "FEEDER_1","CAP_MFR1","CAP1","","","","2",""
+            "FEEDER_2","RES_MFR1","RES1","","10K 1% resistor","","2","8"
+            "FEEDER_3","RES_MFR1","RES2","","","","2",""
+        "#};
+        assert_eq!(read_to_string(temp_dir.path().join("top_1_feeder_setup.csv"))?, expected_content);
+
+        // and
+        assert!(temp_dir.path().join("top_1_feeder_setup.html").exists());
+
+        // and the manual phase does not use feeders
+        assert!(!temp_dir.path().join("bottom_1_feeder_setup.csv").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn generate_artifacts_with_missing_feeders() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec![
+                "--project example1", path_arg.as_str(), "create-phase",
+                "--process pnp", "--reference top_1", "--load-out load_out_top_1.csv", "--pcb-side top", "--export-format centroid",
+            ]))
+            .assert()
+            .success();
+
+        // and a part is removed from the load-out
+        let load_out_path = temp_dir.path().join("load_out_top_1.csv");
+        let load_out_content: String = read_to_string(&load_out_path)?.lines()
+            .filter(|line| !line.contains("CAP1"))
+            .map(|line| format!("{}\n", line))
+            .collect();
+        std::fs::write(&load_out_path, load_out_content)?;
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "generate-artifacts"]))
+            // then
+            .assert()
+            .failure()
+            .stderr(print("stderr").and(predicate::str::contains("Parts of the machine exports have not been assigned to a feeder, the artifacts were not generated. missing: [CAP_MFR1:CAP1 (phase: 'top_1', placements: ")))
+            .stdout(print("stdout"));
+
+        // and
+        assert!(!temp_dir.path().join("top_1_centroid.csv").exists());
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "generate-artifacts", "--allow-missing-feeders"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Part of a machine export has not been assigned to a feeder. phase: 'top_1', part: Part { manufacturer: \"CAP_MFR1\", mpn: \"CAP1\" }")));
+
+        // and
+        assert!(temp_dir.path().join("top_1_centroid.csv").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn generate_artifacts_with_nozzle_assignments() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and 'RES2' has no package
+        std::fs::write(temp_dir.path().join("parts.csv"), indoc! {r#"
+            "Manufacturer","Mpn","Package"
+            "RES_MFR1","RES1","0402"
+            "CAP_MFR1","CAP1","0603"
+        "#})?;
+
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "import-part-details", "--parts parts.csv"]))
+            .assert()
+            .success();
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "set-phase-nozzles", "--phase top_1", "--heads 1", "--nozzle N1=0402", "--nozzle N2=0603,0805"]))
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout"));
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "set-placement-ordering", "--phase top_1", "--placement-orderings NOZZLE:ASC,PCB_UNIT:ASC"]))
+            .assert()
+            .success();
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "generate-artifacts"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Assigned nozzles. phase: 'top_1', placements: 4, nozzle_changes: 1")));
+
+        // and
+        let export_content = read_to_string(temp_dir.path().join("top_1_export.json"))?;
+        assert!(export_content.contains(r#""nozzle": "N1""#), "content: {}", export_content);
+        assert!(export_content.contains(r#""nozzle": "N2""#), "content: {}", export_content);
+
+        // and
+        let report_content = read_to_string(temp_dir.path().join("example1_report.json"))?;
+        assert!(report_content.contains(r#""NoNozzleForPart""#), "content: {}", report_content);
+        assert!(report_content.contains(r#""mpn": "RES2""#), "content: {}", report_content);
+
+        Ok(())
+    }
+}
+
+mod compression {
+    use std::fs::read_to_string;
+    use assert_cmd::Command;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
+
+    #[test]
+    fn compress_project() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+        let project_file_path = temp_dir.path().join("project-example1.mpnp.json");
+        let compressed_project_file_path = temp_dir.path().join("project-example1.mpnp.json.zst");
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        let original_content = read_to_string(&project_file_path)?;
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "compress-project"]))
+            // then
+            .assert()
+            .success()
+            .stdout(print("stdout").and(predicate::str::contains("Converted project file.")));
+
+        // and only the compressed project file exists
+        assert!(!project_file_path.exists());
+        assert!(compressed_project_file_path.exists());
+
+        // and the compressed project file is used by other commands
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "set-phase-tags", "--phase top_1", "--tag line=A"]))
+            .assert()
+            .success();
+
+        assert!(!project_file_path.exists());
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "compress-project", "--decompress"]))
+            // then
+            .assert()
+            .success();
+
+        // and
+        assert!(!compressed_project_file_path.exists());
+        let content = read_to_string(&project_file_path)?;
+        assert_ne!(content, original_content);
+        assert!(content.contains("\"line\": \"A\""));
+
+        Ok(())
+    }
+}
+
+mod locking {
+    use std::fs::read_to_string;
+    use std::process::{Command, Stdio};
+    use assert_cmd::Command as AssertCommand;
+    use tempfile::tempdir;
+    use util::test::prepare_args;
+
+    /// Each command holds the lock of the project from loading it until saving it, so no changes are lost.
+    #[test]
+    fn concurrent_commands() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        AssertCommand::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // when commands that each set a different tag are run at the same time
+        let tag_args: Vec<String> = (1..=8).map(|index| format!("--tag key{}=value", index)).collect();
+        let children = tag_args.iter()
+            .map(|tag_arg| Command::new(env!("CARGO_BIN_EXE_planner"))
+                .args(prepare_args(vec!["--project example1", path_arg.as_str(), "set-phase-tags", "--phase top_1", tag_arg.as_str()]))
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // then
+        for child in children {
+            let output = child.wait_with_output()?;
+            assert!(output.status.success(), "output: {:?}", output);
+        }
+
+        // and the changes of all the commands are saved
+        let content = read_to_string(temp_dir.path().join("project-example1.mpnp.json"))?;
+        for index in 1..=8 {
+            assert!(content.contains(&format!("\"key{}\": \"value\"", index)), "content: {}", content);
+        }
+
+        Ok(())
+    }
+}
+
+mod status {
+    use std::fs::read_to_string;
+    use assert_cmd::Command;
+    use indoc::indoc;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
+
+    #[test]
+    fn status_and_inspect_phase() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+        let project_file_path = temp_dir.path().join("project-example1.mpnp.json");
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "record-placements-operation", "--object-path-patterns .*unit=1::ref_des=R.*", "--operation placed"]))
+            .assert()
+            .success();
+
+        let original_content = read_to_string(&project_file_path)?;
+        let original_modified = project_file_path.metadata()?.modified()?;
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "status"]))
+            // then
+            .assert()
+            .success()
+            .stdout(print("stdout").and(predicate::str::diff(indoc! {"
+                Project: example1
+                Placements: placed: 2/8, unassigned: 0
+                Phases:
+                  top_1 process: pnp, pcb_side: Top, placed: 2/6, operations: [LoadPcbs: Pending, AutomatedPnp: Incomplete, ReflowComponents: Pending]
+                  bottom_1 process: manual, pcb_side: Bottom, placed: 0/2, operations: [LoadPcbs: Pending, ManuallySolderComponents: Pending]
+            "})));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "inspect-phase", "--phase top_1"]))
+            // then
+            .assert()
+            .success()
+            .stdout(print("stdout")
+                .and(predicate::str::contains("Placements: placed: 2/6\n"))
+                .and(predicate::str::contains("  panel=1::unit=1::ref_des=R1 part: RES_MFR1:RES1, placed: true\n"))
+                .and(predicate::str::contains("  panel=1::unit=2::ref_des=R1 part: RES_MFR1:RES1, placed: false\n"))
+            );
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "inspect-phase", "--phase top_9"]))
+            .assert()
+            .failure()
+            .stderr(print("stderr").and(predicate::str::contains("Unknown phase. phase: 'top_9'")));
+
+        // and the project file is unchanged
+        assert_eq!(read_to_string(&project_file_path)?, original_content);
+        assert_eq!(project_file_path.metadata()?.modified()?, original_modified);
+
+        Ok(())
+    }
+
+    #[test]
+    fn health_summary() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and a part without a feeder
+        let load_out_path = temp_dir.path().join("load_out_top_1.csv");
+        let load_out_content = read_to_string(&load_out_path)?;
+        std::fs::write(&load_out_path, load_out_content.replace(r#""FEEDER_2","RES_MFR1""#, r#""","RES_MFR1""#))?;
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "generate-artifacts"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr").and(predicate::eq(
+                "Health: 0 errors, 1 warning, placements assigned: 8/8 (100%), feeders assigned: 3/4 (75%)\n"
+            )))
+            .stdout(print("stdout"));
+
+        Ok(())
+    }
+}
+
+mod pricing {
+    use std::fs::read_to_string;
+    use assert_cmd::Command;
+    use indoc::indoc;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
+
+    #[test]
+    fn generate_artifacts_with_cost_estimate() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and a price list without prices for 'RES2' and 'CONN1'
+        std::fs::write(temp_dir.path().join("prices.csv"), indoc! {r#"
+            "Manufacturer","Mpn","Currency","Quantity","UnitPrice"
+            "CAP_MFR1","CAP1","EUR","1","0.10"
+            "RES_MFR1","RES1","EUR","1","0.02"
+            "RES_MFR1","RES1","EUR","100","0.01"
+        "#})?;
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "set-price-list", "--source prices.csv"]))
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains(r#"Price list set. old: None, new: Some("prices.csv")"#)));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "generate-artifacts"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Estimated cost. total: EUR 0.24, unpriced parts: 2")));
+
+        // and
+        let report: serde_json::Value = serde_json::from_str(&read_to_string(temp_dir.path().join("example1_report.json"))?)?;
+        assert_eq!(report["cost_estimate"]["phases"][0]["phase_name"], "top_1");
+        assert_eq!(report["cost_estimate"]["phases"][0]["cost"]["EUR"], "0.24");
+        assert_eq!(report["cost_estimate"]["total"]["EUR"], "0.24");
+
+        // and
+        let missing_prices: Vec<&serde_json::Value> = report["issues"].as_array().unwrap().iter()
+            .filter_map(|issue| issue["kind"].get("MissingPartPrice"))
+            .collect();
+        assert_eq!(missing_prices.len(), 2);
+        assert_eq!(missing_prices[1]["part"]["mpn"], "RES2");
+
+        Ok(())
+    }
+}
+
+mod inventory {
+    use std::fs::read_to_string;
+    use assert_cmd::Command;
+    use indoc::indoc;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
+
+    #[test]
+    fn inventory() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and an inventory with too few 'RES1' parts and without 'CONN1'
+        std::fs::write(temp_dir.path().join("inventory.csv"), indoc! {r#"
+            "Manufacturer","Mpn","Quantity"
+            "CAP_MFR1","CAP1","10"
+            "RES_MFR1","RES1","1"
+            "RES_MFR1","RES2","2"
+        "#})?;
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "set-inventory", "--source inventory.csv"]))
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains(r#"Inventory set. old: None, new: Some("inventory.csv")"#)));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "generate-artifacts"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("The phase requires more parts than are in the inventory.")));
+
+        // and
+        let report: serde_json::Value = serde_json::from_str(&read_to_string(temp_dir.path().join("example1_report.json"))?)?;
+        let shortages: Vec<&serde_json::Value> = report["issues"].as_array().unwrap().iter()
+            .filter_map(|issue| issue["kind"].get("InventoryShortage"))
+            .collect();
+        assert_eq!(shortages.len(), 2);
+        assert_eq!(shortages[0]["phase"], "bottom_1");
+        assert_eq!(shortages[0]["part"]["mpn"], "CONN1");
+        assert_eq!(shortages[1]["phase"], "top_1");
+        assert_eq!(shortages[1]["part"]["mpn"], "RES1");
+        assert_eq!(shortages[1]["required"], 2);
+        assert_eq!(shortages[1]["on_hand"], 1);
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "record-placements-operation", "--object-path-patterns panel=1::unit=1::ref_des=R1", "--operation placed"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Consumed inventory quantity. part: Part { manufacturer: \"RES_MFR1\", mpn: \"RES1\" }, consumed: 1, remaining: 0")));
+
+        // and
+        let content = read_to_string(temp_dir.path().join("inventory.csv"))?;
+        assert!(content.contains(r#""RES_MFR1","RES1","0""#), "content: {}", content);
+
+        Ok(())
+    }
+}
+
+mod estimation {
+    use std::fs::read_to_string;
+    use assert_cmd::Command;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
+
+    #[test]
+    fn estimation() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "set-estimation", "--placement-rate pnp=1200", "--feeder-setup-time 60", "--reflow-time 600"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Estimation parameters set. old: None, new: Some(EstimationParameters")));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "status"]))
+            // then 6 placements at 3 seconds each, 3 feeders and a reflow, the manual process has no placement rate
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout")
+                .and(predicate::str::contains("Estimate: total: 14m 18s, remaining: 14m 18s\n"))
+                .and(predicate::str::contains("ReflowComponents: Pending], estimate: 13m 18s, remaining: 13m 18s\n"))
+                .and(predicate::str::contains("ManuallySolderComponents: Pending], estimate: 1m 0s, remaining: 1m 0s\n"))
+            );
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "generate-artifacts"]))
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Estimated time. total: 14m 18s, remaining: 14m 18s")));
+
+        // then
+        let report: serde_json::Value = serde_json::from_str(&read_to_string(temp_dir.path().join("example1_report.json"))?)?;
+        assert_eq!(report["time_estimate"]["total_time"], 858);
+        assert_eq!(report["time_estimate"]["phases"][0]["phase"], "top_1");
+        assert_eq!(report["time_estimate"]["phases"][0]["placement_time"], 18);
+        assert!(report["time_estimate"]["phases"][1].get("placement_time").is_none());
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "set-estimation"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("new: None")));
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "status"]))
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Estimate:").not()));
+
+        Ok(())
+    }
+}
+
+mod placement_conflicts {
+    use std::fs::read_to_string;
+    use std::io::Write;
+    use assert_cmd::Command;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
+
+    #[test]
+    fn placement_conflicts() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and a placement with the same ref_des as another, but a different part
+        let mut placements_file = std::fs::OpenOptions::new().append(true).open(temp_dir.path().join("design_a_variant_a_placements.csv"))?;
+        writeln!(placements_file, r#""R1","RES_MFR1","RES2","true","Top","10","10","0","Component""#)?;
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "acknowledge-design-changes"]))
+            .assert()
+            .success();
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "assign-variant-to-unit", "--design design_a", "--variant variant_a", "--unit panel=1::unit=1"]))
+            // then
+            .assert()
+            .failure()
+            .stderr(print("stderr").and(predicate::str::contains("Design variants have conflicting placements, fix the designs or use '--force' to use the last of the placements.")));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "assign-variant-to-unit", "--design design_a", "--variant variant_a", "--unit panel=1::unit=1", "--force"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Accepted placement conflict, the last placement is used. conflict: design_variant: design_a-variant_a, ref_des: R1, kind: PartConflict, parts: [RES_MFR1:RES1, RES_MFR1:RES2]")));
+
+        // and the accepted conflict does not prevent other commands from refreshing the placements
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "assign-process-to-parts", "--process pnp", "--manufacturer RES_MFR1", "--mpn .*"]))
+            .assert()
+            .success();
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "generate-artifacts"]))
+            .assert()
+            .success();
+
+        // then
+        let report: serde_json::Value = serde_json::from_str(&read_to_string(temp_dir.path().join("example1_report.json"))?)?;
+        let conflicts: Vec<&serde_json::Value> = report["issues"].as_array().unwrap().iter()
+            .filter_map(|issue| issue["kind"].get("PlacementPartConflict"))
+            .collect();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0]["design_variant"], "design_a-variant_a");
+        assert_eq!(conflicts[0]["ref_des"], "R1");
+        assert_eq!(conflicts[0]["parts"].as_array().unwrap().len(), 2);
+
+        Ok(())
+    }
+}
+
+mod parts_master {
+    use std::fs::read_to_string;
+    use assert_cmd::Command;
+    use indoc::indoc;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
+
+    #[test]
+    fn parts_master() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and
+        std::fs::write(temp_dir.path().join("parts_master.toml"), indoc! {r#"
+            [[parts]]
+            manufacturer = "RES_MFR1"
+            mpn = "RES1"
+            value = "10K"
+            description = "10K 1% resistor"
+            package = "0402"
+
+            [[parts]]
+            manufacturer = "CAP_MFR1"
+            mpn = "CAP1"
+            msl = "3"
+        "#})?;
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "import-part-details", "--parts parts_master.toml"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout")
+                .and(predicate::str::contains("Updated part details. part: Part { manufacturer: \"RES_MFR1\", mpn: \"RES1\" }"))
+                .and(predicate::str::contains("Updated moisture sensitivity. part: Part { manufacturer: \"CAP_MFR1\", mpn: \"CAP1\" }, old: None, new: Some(MoistureSensitivity { level: Level3, floor_life_hours: None })"))
+            );
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "generate-artifacts"]))
+            .assert()
+            .success();
+
+        // then the attributes are in the report
+        let report: serde_json::Value = serde_json::from_str(&read_to_string(temp_dir.path().join("example1_report.json"))?)?;
+        let load_out_assignments = report["phase_specifications"][0]["load_out_assignments"].as_array().unwrap();
+        let res1 = load_out_assignments.iter().find(|item| item["mpn"] == "RES1").unwrap();
+        assert_eq!((&res1["value"], &res1["description"], &res1["package"]), (&serde_json::json!("10K"), &serde_json::json!("10K 1% resistor"), &serde_json::json!("0402")));
+
+        // and in the placements of the phase export
+        let phase_export: serde_json::Value = serde_json::from_str(&read_to_string(temp_dir.path().join("top_1_export.json"))?)?;
+        let r1 = phase_export["placements"].as_array().unwrap().iter().find(|placement| placement["mpn"] == "RES1").unwrap();
+        assert_eq!(r1["value"], "10K");
+
+        // and in the feeder setup sheet
+        assert!(read_to_string(temp_dir.path().join("top_1_feeder_setup.csv"))?.contains(r#""FEEDER_2","RES_MFR1","RES1","10K","10K 1% resistor","0402","2","""#));
+
+        Ok(())
+    }
+}
+
+mod phases {
+    use std::fs::read_to_string;
+    use assert_cmd::Command;
+    use indoc::indoc;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
+
+    #[test]
+    fn rename_and_reorder_phases() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and an operation history for the phase
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "record-phase-operation", "--phase top_1", "--operation loadpcbs", "--set completed"]))
+            .assert()
+            .success();
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "rename-phase", "--phase top_1", "--reference top_a"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout")
+                .and(predicate::str::contains("Renamed phase. from: 'top_1', to: 'top_a', placements: 6"))
+                .and(predicate::str::contains("Phase ordering: ['top_a', 'bottom_1']"))
+            );
+
+        // and
+        let project_content = read_to_string(temp_dir.path().join("project-example1.mpnp.json"))?;
+        assert!(!project_content.contains("\"top_1\""), "content: {}", project_content);
+
+        // and
+        assert!(!temp_dir.path().join("top_1_log.json").exists());
+        let log_content = read_to_string(temp_dir.path().join("top_a_log.json"))?;
+        assert!(!log_content.contains("\"phase\": \"top_1\""), "content: {}", log_content);
+        assert!(log_content.contains("PhaseRenamed"), "content: {}", log_content);
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "verify-operation-history"]))
+            .assert()
+            .success();
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "reorder-phases", "--phases bottom_1,top_a"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Phase ordering: ['bottom_1', 'top_a']")));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "reorder-phases", "--phases bottom_1"]))
+            // then
+            .assert()
+            .failure()
+            .stderr(print("stderr").and(predicate::str::contains("Invalid phase ordering, each phase must be given exactly once. phases: ['bottom_1']")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn phase_dependencies() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "set-phase-dependencies", "--phase bottom_1", "--depends-on top_1"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout")
+                .and(predicate::str::contains("Phase dependencies set. phase: 'bottom_1', dependencies: ['top_1']"))
+                .and(predicate::str::contains("Execution plan: ['top_1', 'bottom_1']"))
+            );
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "set-phase-dependencies", "--phase top_1", "--depends-on bottom_1"]))
+            // then
+            .assert()
+            .failure()
+            .stderr(print("stderr").and(predicate::str::contains(r#"Phase dependencies contain a cycle. phases: ["top_1", "bottom_1"]"#)));
+
+        // when an operation of 'bottom_1' is recorded before 'top_1' is complete
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "record-phase-operation", "--phase bottom_1", "--operation loadpcbs", "--set completed"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Phase operation recorded before the phases it depends on were complete. phase: 'bottom_1', incomplete dependencies: ['top_1']")));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "generate-artifacts"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Phase started before a phase it depends on was complete.")));
+
+        // and
+        let report: serde_json::Value = serde_json::from_str(&read_to_string(temp_dir.path().join("example1_report.json"))?)?;
+        assert_eq!(report["execution_plan"], serde_json::json!(["top_1", "bottom_1"]));
+
+        // and
+        let violations: Vec<&serde_json::Value> = report["issues"].as_array().unwrap().iter()
+            .filter_map(|issue| issue["kind"].get("PhaseDependencyViolation"))
+            .collect();
+        assert_eq!(violations, vec![&serde_json::json!({ "phase": "bottom_1", "dependency": "top_1" })]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn phase_tags() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "set-phase-tags", "--phase top_1", "--tag line=A", "--tag priority=high"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Phase tag set. phase: 'top_1', tag: 'line=A'")));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "list-phases", "--tag line=A"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout")
+                .and(predicate::str::contains("top_1 process: pnp, pcb_side: Top, tags: [line=A, priority=high]"))
+                .and(predicate::str::contains("bottom_1").not())
+            );
+
+        // and the tags are included in the report
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "generate-artifacts"]))
+            .assert()
+            .success();
+
+        let report: serde_json::Value = serde_json::from_str(&read_to_string(temp_dir.path().join("example1_report.json"))?)?;
+        assert_eq!(report["phase_overviews"][0]["tags"], serde_json::json!({ "line": "A", "priority": "high" }));
+
+        // and tags can be removed
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "set-phase-tags", "--phase top_1", "--remove priority"]))
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Phase tag removed. phase: 'top_1', key: 'priority'")));
+
+        // and invalid tags are rejected
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "set-phase-tags", "--phase top_1", "--tag line"]))
+            .assert()
+            .failure()
+            .stderr(print("stderr").and(predicate::str::contains("Invalid phase tag, expected 'key=value'. value: 'line'")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn clone_phase_with_load_out() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "clone-phase", "--phase top_1", "--reference top_2", "--load-out load_out_top_2.csv"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout"));
+
+        // and
+        assert_eq!(
+            read_to_string(temp_dir.path().join("load_out_top_2.csv"))?,
+            read_to_string(temp_dir.path().join("load_out_top_1.csv"))?,
+        );
+
+        // and
+        let project_content = read_to_string(temp_dir.path().join("project-example1.mpnp.json"))?;
+        assert!(project_content.contains(r#""load_out_source": "load_out_top_2.csv""#));
+
+        // when the load-out already exists
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "clone-phase", "--phase top_1", "--reference top_3", "--load-out load_out_top_2.csv"]))
+            // then
+            .assert()
+            .failure()
+            .stderr(print("stderr").and(predicate::str::contains("Load-out already exists.")))
+            .stdout(print("stdout"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn set_placement_ordering_with_unknown_mode() {
+        // given
+        let expected_error = indoc! {"
+            error: Invalid placement ordering. Unknown mode, expected one of: FEEDER_REFERENCE, PCB_UNIT, PART, NOZZLE, REF_DES, AREA, HEIGHT, ANGLE, DESIGN_X, DESIGN_Y, PANEL_X, PANEL_Y, did you mean 'FEEDER_REFERENCE'?
+              FEEDR_REFERENCE:ASC
+              ^^^^^^^^^^^^^^^
+        "};
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "set-placement-ordering", "--phase top_1", "--placement-orderings pcb_unit:asc,FEEDR_REFERENCE:ASC"]))
+            // then
+            .assert()
+            .failure()
+            .stderr(print("stderr").and(predicate::str::diff(expected_error)))
+            .stdout(print("stdout"));
+    }
+}
+
+mod project_diff {
+    use assert_cmd::Command;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
+
+    #[test]
+    fn diff_project() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and a previous save of the project
+        std::fs::copy(temp_dir.path().join("project-example1.mpnp.json"), temp_dir.path().join("previous.mpnp.json"))?;
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "record-placements-operation", "--object-path-patterns panel=1::unit=1::ref_des=R1", "--operation placed"]))
+            .assert()
+            .success();
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "remove-phase", "--phase bottom_1"]))
+            .assert()
+            .success();
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "diff-project", "--against previous.mpnp.json"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout")
+                .and(predicate::str::contains("placement 'panel=1::unit=1::ref_des=R1' changed, fields: placed\n"))
+                .and(predicate::str::contains("placement 'panel=1::unit=1::ref_des=J1' changed, fields: phase\n"))
+                .and(predicate::str::contains("phase 'bottom_1' removed\n"))
+                .and(predicate::str::contains("Diffed project."))
+            );
+
+        // when
+        let output = Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "diff-project", "--against previous.mpnp.json", "--format json"]))
+            .output()?;
+
+        // then
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout)?;
+        let json_start = stdout.find("{\n").unwrap();
+        let json_end = stdout.find("\n}\n").unwrap() + 2;
+        let diff: serde_json::Value = serde_json::from_str(&stdout[json_start..json_end])?;
+        assert_eq!(diff["phases"], serde_json::json!([{ "key": "bottom_1", "kind": "Removed" }]));
+        let placement_diff = diff["placements"].as_array().unwrap().iter().find(|item| item["key"] == "panel=1::unit=1::ref_des=R1");
+        assert_eq!(placement_diff, Some(&serde_json::json!({ "key": "panel=1::unit=1::ref_des=R1", "kind": "Changed", "fields": ["placed"] })));
+
+        Ok(())
+    }
+}
+
+mod process_definitions {
+    use std::fs::read_to_string;
+    use assert_cmd::Command;
+    use indoc::indoc;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
+
+    #[test]
+    fn process_definitions() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and
+        std::fs::write(temp_dir.path().join("processes.toml"), indoc! {r#"
+            [[processes]]
+            name = "selective"
+            operations = [
+                { kind = "LoadPcbs" },
+                { kind = "SelectiveSolder", tracks_placements = true },
+                { kind = "Wash" },
+            ]
+        "#})?;
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec![
+                "--project example1", path_arg.as_str(), "create-phase",
+                "--process selective", "--reference bottom_2", "--load-out load_out_bottom_2.csv", "--pcb-side bottom",
+            ]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Created phase. reference: 'bottom_2', process: selective")));
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "assign-placements-to-phase", "--phase bottom_2", "--placements .*J1", "--allow-reassign"]))
+            .assert()
+            .success();
+
+        // when the placements of the custom operation are placed
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "record-placements-operation", "--object-path-patterns panel=1::unit=1::ref_des=J1", "--operation placed"]))
+            .assert()
+            .success();
+
+        // and a custom operation that does not track placements is completed
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "record-phase-operation", "--phase bottom_2", "--operation Wash", "--set completed"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout"));
+
+        // and
+        let log_content = read_to_string(temp_dir.path().join("bottom_2_log.json"))?;
+        assert!(log_content.contains(r#""operation": "Wash""#), "content: {}", log_content);
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "generate-artifacts"]))
+            .assert()
+            .success();
+
+        // then the custom operation follows the progress of the placements
+        let report_content = read_to_string(temp_dir.path().join("example1_report.json"))?;
+        assert!(report_content.contains(r#""Custom": "SelectiveSolder""#), "content: {}", report_content);
+        assert!(report_content.contains(r#""message": "1/2 placements placed""#), "content: {}", report_content);
+
+        // and invalid definitions are rejected
+        std::fs::write(temp_dir.path().join("processes.toml"), indoc! {r#"
+            [[processes]]
+            name = "selective"
+            operations = [{ kind = "ReflowComponents", tracks_placements = true }]
+        "#})?;
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec![
+                "--project example1", path_arg.as_str(), "create-phase",
+                "--process selective", "--reference bottom_3", "--load-out load_out_bottom_3.csv", "--pcb-side bottom",
+            ]))
+            .assert()
+            .failure()
+            .stderr(print("stderr").and(predicate::str::contains("Placement tracking of built-in operations can not be changed. process: 'selective', operation: 'ReflowComponents'")));
+
+        Ok(())
+    }
+}
+
+mod scan {
+    use std::fs::read_to_string;
+    use assert_cmd::Command;
+    use indoc::indoc;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
+
+    #[test]
+    fn scan() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and a mapping for the barcode label of the second unit
+        std::fs::write(temp_dir.path().join("barcodes.csv"), indoc! {r#"
+            "Barcode","Kind","Value"
+            "PCB-0002","Unit","panel=1::unit=2"
+        "#})?;
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "scan", "--phase top_1"]))
+            .write_stdin("FEEDER_2\npanel=1::unit=1\nFEEDER_2\nUNKNOWN\nPCB-0002\nFEEDER_2\n")
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout")
+                .and(predicate::str::contains("Loaded barcode mappings."))
+                .and(predicate::str::contains("Scan ignored. barcode: 'FEEDER_2', cause: No unit selected, scan a unit first. feeder: 'FEEDER_2'"))
+                .and(predicate::str::contains("Selected unit. phase: 'top_1', unit: 'panel=1::unit=1'"))
+                .and(predicate::str::contains("Scan ignored. barcode: 'UNKNOWN', cause: Unknown barcode. barcode: 'UNKNOWN'"))
+                .and(predicate::str::contains("Selected unit. phase: 'top_1', unit: 'panel=1::unit=2'"))
+                .and(predicate::str::contains("Placed feeder. phase: 'top_1', feeder: 'FEEDER_2', part: Part { manufacturer: \"RES_MFR1\", mpn: \"RES1\" }, placements: 1").count(2)));
+
+        // and
+        let project_content = read_to_string(temp_dir.path().join("project-example1.mpnp.json"))?;
+        assert_eq!(project_content.matches(r#""placed": true"#).count(), 2);
+
+        // when the same unit is scanned again, there is nothing left to place
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "scan", "--phase top_1"]))
+            .write_stdin("panel=1::unit=1\nFEEDER_2\nManuallySolderComponents\nLoadPcbs\n")
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout")
+                .and(predicate::str::contains("cause: No unplaced placements. phase: 'top_1', unit: 'panel=1::unit=1', feeder: 'FEEDER_2'"))
+                .and(predicate::str::contains("cause: Unknown operation for phase. phase: 'top_1', operation: 'ManuallySolderComponents'"))
+                .and(predicate::str::contains("Completed operation. phase: 'top_1', operation: 'LoadPcbs'")));
+
+        Ok(())
+    }
+}
+
+mod placement_overrides {
+    use std::fs::read_to_string;
+    use assert_cmd::Command;
+    use indoc::indoc;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
+
+    #[test]
+    fn set_placement_override() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "set-placement-override", "--object-path-patterns ^panel=1::unit=1::ref_des=R1$", "--override skip"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr").and(predicate::str::contains("placements assigned: 7/7 (100%)")))
+            .stdout(print("stdout").and(predicate::str::contains("Setting placement override. object_path: panel=1::unit=1::ref_des=R1, old: None, new: Some(Skip)")));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "generate-artifacts"]))
+            .assert()
+            .success();
+
+        // then the placement is not in the generated placements
+        let placements_content = read_to_string(temp_dir.path().join("top_1_placements.csv"))?;
+        assert!(!placements_content.contains("panel=1::unit=1::ref_des=R1"));
+        assert!(placements_content.contains("panel=1::unit=2::ref_des=R1"));
+
+        // and the override is in the report
+        let report_content = read_to_string(temp_dir.path().join("example1_report.json"))?;
+        assert!(report_content.contains(indoc! {r#"
+            "placement_overrides": [
+                    {
+                        "object_path": "panel=1::unit=1::ref_des=R1",
+                        "place_override": "Skip"
+                    }
+                ],
+        "#}.trim()));
+
+        // and the override is recorded in the project
+        let project_content = read_to_string(temp_dir.path().join("project-example1.mpnp.json"))?;
+        assert!(project_content.contains(r#""place_override": "Skip""#));
+
+        // when the override is removed
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "set-placement-override", "--object-path-patterns ^panel=1::unit=1::ref_des=R1$", "--override design"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr").and(predicate::str::contains("placements assigned: 8/8 (100%)")))
+            .stdout(print("stdout"));
+
+        // and
+        let project_content = read_to_string(temp_dir.path().join("project-example1.mpnp.json"))?;
+        assert!(!project_content.contains("place_override"));
+
+        Ok(())
+    }
+}
+
+mod rotation {
+    use std::fs::read_to_string;
+    use assert_cmd::Command;
+    use indoc::indoc;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use rust_decimal_macros::dec;
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
+    use crate::common::phase_placement_builder::{PhasePlacementsCSVBuilder, TestPhasePlacementRecord};
+
+    #[test]
+    fn rotation_normalization() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and
+        std::fs::write(temp_dir.path().join("rotation_offsets.csv"), indoc! {r#"
+            "Manufacturer","Mpn","Offset"
+            "RES_MFR1","RES2","180"
+        "#})?;
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "set-rotation-normalization", "--phase top_1", "--range unsigned", "--offsets rotation_offsets.csv"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Phase rotation normalization set. phase: 'top_1', range: Unsigned, offsets: 1")));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "generate-artifacts"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"));
+
+        // and the negative rotation is normalized and the offset is applied
+        let expected_placements_content = PhasePlacementsCSVBuilder::new()
+            .with_items(&[
+                TestPhasePlacementRecord {
+                    object_path: "panel=1::unit=1::ref_des=C1".to_string(),
+                    feeder_reference: "FEEDER_1".to_string(),
+                    manufacturer: "CAP_MFR1".to_string(),
+                    mpn: "CAP1".to_string(),
+                    x: dec!(10),
+                    y: dec!(20),
+                    rotation: dec!(270),
+                },
+                TestPhasePlacementRecord {
+                    object_path: "panel=1::unit=1::ref_des=R1".to_string(),
+                    feeder_reference: "FEEDER_2".to_string(),
+                    manufacturer: "RES_MFR1".to_string(),
+                    mpn: "RES1".to_string(),
+                    x: dec!(10),
+                    y: dec!(10),
+                    rotation: dec!(0),
+                },
+                TestPhasePlacementRecord {
+                    object_path: "panel=1::unit=1::ref_des=R2".to_string(),
+                    feeder_reference: "FEEDER_3".to_string(),
+                    manufacturer: "RES_MFR1".to_string(),
+                    mpn: "RES2".to_string(),
+                    x: dec!(20),
+                    y: dec!(10),
+                    rotation: dec!(270),
+                },
+                TestPhasePlacementRecord {
+                    object_path: "panel=1::unit=2::ref_des=C1".to_string(),
+                    feeder_reference: "FEEDER_1".to_string(),
+                    manufacturer: "CAP_MFR1".to_string(),
+                    mpn: "CAP1".to_string(),
+                    x: dec!(10),
+                    y: dec!(20),
+                    rotation: dec!(270),
+                },
+                TestPhasePlacementRecord {
+                    object_path: "panel=1::unit=2::ref_des=R1".to_string(),
+                    feeder_reference: "FEEDER_2".to_string(),
+                    manufacturer: "RES_MFR1".to_string(),
+                    mpn: "RES1".to_string(),
+                    x: dec!(10),
+                    y: dec!(10),
+                    rotation: dec!(0),
+                },
+                TestPhasePlacementRecord {
+                    object_path: "panel=1::unit=2::ref_des=R2".to_string(),
+                    feeder_reference: "FEEDER_3".to_string(),
+                    manufacturer: "RES_MFR1".to_string(),
+                    mpn: "RES2".to_string(),
+                    x: dec!(20),
+                    y: dec!(10),
+                    rotation: dec!(270),
+                },
+            ])
+            .as_string();
+        assert_eq!(read_to_string(temp_dir.path().join("top_1_placements.csv"))?, expected_placements_content);
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "set-rotation-normalization", "--phase top_1"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Phase rotation normalization removed. phase: 'top_1'")));
+
+        Ok(())
+    }
+}
+
+mod first_article {
+    use std::fs::read_to_string;
+    use assert_cmd::Command;
+    use indoc::indoc;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
+
+    #[test]
+    fn first_article_inspection() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "set-first-article-inspection", "--phase top_1", "--required"]))
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Phase first-article inspection set. phase: 'top_1', required: true")));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "generate-artifacts"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout"));
+
+        // and only the first article is in the checklist
+        assert_eq!(read_to_string(temp_dir.path().join("top_1_first_article.csv"))?, indoc! {r#"
+            "Manufacturer","Mpn","Package","Quantity","SampleRefDes","SampleObjectPath","Verified","Inspector","Date"
+            "CAP_MFR1","CAP1","","1","C1","panel=1::unit=1::ref_des=C1","","",""
+            "RES_MFR1","RES1","","1","R1","panel=1::unit=1::ref_des=R1","","",""
+            "RES_MFR1","RES2","","1","R2","panel=1::unit=1::ref_des=R2","","",""
+        "#});
+
+        // when the other unit is placed before the first article is signed off
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "record-placements-operation", "--object-path-patterns .*unit=2::ref_des=R1", "--operation placed"]))
+            // then
+            .assert()
+            .failure()
+            .stderr(print("stderr").and(predicate::str::contains("The first article of the phase has not been signed off")));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "record-first-article-inspection", "--phase top_1"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Recorded first-article inspection. phase: 'top_1'")));
+
+        // and
+        let log_content = read_to_string(temp_dir.path().join("top_1_log.json"))?;
+        assert!(log_content.contains(r#""FirstArticleInspected""#), "content: {}", log_content);
+
+        // and the other unit can be placed
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "record-placements-operation", "--object-path-patterns .*unit=2::ref_des=R1", "--operation placed"]))
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Setting placed flag. object_path: panel=1::unit=2::ref_des=R1")));
+
+        Ok(())
+    }
+}
+
+mod report {
+    use std::fs::read_to_string;
+    use assert_cmd::Command;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
+
+    #[test]
+    fn generate_artifacts_with_html_report() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "generate-artifacts", "--report-format html"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Generated HTML report.")));
+
+        // and the JSON report is still generated
+        assert!(temp_dir.path().join("example1_report.json").exists());
+
+        // and
+        let content = read_to_string(temp_dir.path().join("example1_report.html"))?;
+        assert!(content.contains("<title>Report - example1</title>"), "content: {}", content);
+        assert!(content.contains("<h2>Phase - top_1</h2>"), "content: {}", content);
+        assert!(content.contains("<h2>Issues</h2>"), "content: {}", content);
+
+        Ok(())
+    }
+
+    #[test]
+    fn report_site() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and the report is required
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "report", "site"]))
+            .assert()
+            .failure()
+            .stderr(print("stderr").and(predicate::str::contains("Report not found, generate the artifacts first")));
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "generate-artifacts"]))
+            .assert()
+            .success();
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "report", "site"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Generated report site.")));
+
+        // and
+        let site_path = temp_dir.path().join("example1_report_site");
+        assert!(site_path.join("index.html").exists());
+
+        // and the report and phase placements are included
+        let data_content = read_to_string(site_path.join("report.js"))?;
+        assert!(data_content.starts_with("const SITE_DATA = {"), "content: {}", data_content);
+        assert!(data_content.contains(r#""name":"example1""#), "content: {}", data_content);
+        assert!(data_content.contains(r#""top_1":{"headers":["ObjectPath""#), "content: {}", data_content);
+
+        Ok(())
+    }
+}
+
+mod variants {
+    use std::fs::read_to_string;
+    use assert_cmd::Command;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
+
+    #[test]
+    fn discover_and_register_variants() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "discover-variants"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains(
+                r#"Discovered placements. file: 'design_a_variant_a_placements.csv', candidates: ["design-a_variant_a", "design_a-variant_a", "design_a_variant-a"]"#
+            )));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "discover-variants", "--design design_a", "--register"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Registered design variant. design_variant: design_a-variant_a")));
+
+        // and units can only be assigned registered variants
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "assign-variant-to-unit", "--design design_a", "--variant variant_b", "--unit panel=1::unit=1"]))
+            .assert()
+            .failure()
+            .stderr(print("stderr").and(predicate::str::contains("Unregistered variant. design_variant: design_a-variant_b")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn design_revision_changes() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and the design is changed
+        let placements_path = temp_dir.path().join("design_a_variant_a_placements.csv");
+        let placements_content = read_to_string(&placements_path)?;
+        std::fs::write(&placements_path, placements_content.replace(r#""R1","RES_MFR1","RES1","true","Top","10""#, r#""R1","RES_MFR1","RES1","true","Top","11""#))?;
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "set-placement-ordering", "--phase top_1", "--placement-orderings PCB_UNIT:ASC"]))
+            // then
+            .assert()
+            .failure()
+            .stderr(print("stderr").and(predicate::str::contains(
+                r#"Design variants have changed, review the changes and acknowledge them before continuing. design_variants: ["design_a-variant_a"], affected phases: ["bottom_1", "top_1"]"#
+            )));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "acknowledge-design-changes"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Acknowledged design revision. design_variant: design_a-variant_a")));
+
+        // and the placements are refreshed
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "set-placement-ordering", "--phase top_1", "--placement-orderings PCB_UNIT:ASC"]))
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Updating placement.")));
+
+        Ok(())
+    }
+}
+
+mod search {
+    use assert_cmd::Command;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
+
+    #[test]
+    fn search_project() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "search", "--query res1"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout")
+                .and(predicate::str::contains("Placements ("))
+                .and(predicate::str::contains("mpn: [RES1]"))
+                .and(predicate::str::contains("top_1 FEEDER_2 mpn: [RES1]"))
+                .and(predicate::str::contains("Searched project. query: 'res1'"))
+            );
+
+        Ok(())
+    }
+}
+
+mod release {
+    use assert_cmd::Command;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
+
+    #[test]
+    fn release_and_reopen() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "release"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout")
+                .and(predicate::str::contains("Released project. version: 1, snapshot: 'example1_v1'"))
+                .and(predicate::str::contains("Created release snapshot. version: 1"))
+            );
+
+        // and the snapshot contains the project, the design variant placements, the load-outs and the artifacts
+        let snapshot_path = temp_dir.path().join("releases").join("example1_v1");
+        for file_name in ["project-example1.mpnp.json", "design_a_variant_a_placements.csv", "load_out_top_1.csv", "top_1_placements.csv", "example1_report.json"] {
+            assert!(snapshot_path.join(file_name).exists(), "file: {}", file_name);
+        }
+
+        // and planning changes are not allowed
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "set-phase-tags", "--phase top_1", "--tag line=A"]))
+            .assert()
+            .failure()
+            .stderr(print("stderr").and(predicate::str::contains("Project is released, planning changes are not allowed, reopen the project first. version: 1")));
+
+        // and production operations are allowed
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "record-phase-operation", "--phase top_1", "--operation loadpcbs", "--set completed"]))
+            .assert()
+            .success();
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "reopen"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Reopened project. version: 1")));
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "set-phase-tags", "--phase top_1", "--tag line=A"]))
+            .assert()
+            .success();
+
+        // and the next release has the next version
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "release"]))
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Released project. version: 2, snapshot: 'example1_v2'")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn release_is_rolled_back_when_artifact_generation_fails() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and a directory where the report is to be written
+        std::fs::create_dir_all(temp_dir.path().join("example1_report.json"))?;
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "release"]))
+            // then
+            .assert()
+            .failure()
+            .stderr(print("stderr").and(predicate::str::contains("Unable to write artifact")))
+            .stdout(print("stdout").and(predicate::str::contains("Release rolled back. version: 1")));
+
+        // and there is no release snapshot
+        assert!(!temp_dir.path().join("releases").join("example1_v1").exists());
+
+        // and planning changes are allowed
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "set-phase-tags", "--phase top_1", "--tag line=A"]))
+            .assert()
+            .success();
+
+        // and the next release has the same version
+        std::fs::remove_dir(temp_dir.path().join("example1_report.json"))?;
+
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "release"]))
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Released project. version: 1, snapshot: 'example1_v1'")));
+
+        Ok(())
+    }
+}
+
+mod operation_history {
+    use std::fs::read_to_string;
+    use assert_cmd::Command;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
+
+    #[test]
+    fn verify_operation_history() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "record-placements-operation", "--object-path-patterns .*", "--operation placed"]))
+            .assert()
+            .success();
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "verify-operation-history"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Verified operation history. phase: 'top_1'")));
+
+        // and when the log is modified
+        let log_path = temp_dir.path().join("top_1_log.json");
+        let log_content = read_to_string(&log_path)?;
+        std::fs::write(&log_path, log_content.replacen("ref_des=R1", "ref_des=R9", 1))?;
+
+        // then
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "verify-operation-history", "--phase top_1"]))
+            .assert()
+            .failure()
+            .stderr(print("stderr").and(predicate::str::contains("Operation history verification failed. phases: 1")))
+            .stdout(print("stdout").and(predicate::str::contains("Item modified")));
+
+        // and when the hash of an item is removed
+        let log_path = temp_dir.path().join("bottom_1_log.json");
+        let mut log: serde_json::Value = serde_json::from_str(&read_to_string(&log_path)?)?;
+        log[0].as_object_mut().unwrap().remove("hash");
+        std::fs::write(&log_path, serde_json::to_string_pretty(&log)?)?;
+
+        // then
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "verify-operation-history", "--phase bottom_1"]))
+            .assert()
+            .failure()
+            .stderr(print("stderr").and(predicate::str::contains("Operation history verification failed. phases: 1")))
+            .stdout(print("stdout").and(predicate::str::contains("Unchained item")));
+
+        Ok(())
+    }
+}
+
+mod maintenance {
+    use assert_cmd::Command;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
+
+    #[test]
+    fn maintenance_cleanup() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let config_dir = temp_dir.path().join("config");
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+        let backup_dir = temp_dir.path().join(".backups");
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and backups of the load-outs
+        std::fs::create_dir_all(&backup_dir)?;
+        for index in 1..=4 {
+            std::fs::write(backup_dir.join(format!("load_out_top_1.20240101T00000{}.000Z.csv", index)), "backup")?;
+        }
+        std::fs::write(backup_dir.join("load_out_bottom_1.20240101T000001.000Z.csv"), "backup")?;
+
+        // and a retention policy
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .env("MAKERPNP_CONFIG_DIR", &config_dir)
+            .args(prepare_args(vec!["--project example1", "config", "set", "--key backup-max-count", "--value 3"]))
+            .assert()
+            .success();
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .env("MAKERPNP_CONFIG_DIR", &config_dir)
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "maintenance", "cleanup", "--max-count 2", "--dry-run"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Would remove 2 backups, reclaiming 12 bytes")));
+
+        // and
+        assert_eq!(std::fs::read_dir(&backup_dir)?.count(), 5);
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .env("MAKERPNP_CONFIG_DIR", &config_dir)
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "maintenance", "cleanup"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Removed 1 backups, reclaimed 6 bytes")));
+
+        // and the oldest backup was removed
+        assert!(!backup_dir.join("load_out_top_1.20240101T000001.000Z.csv").exists());
+        assert!(backup_dir.join("load_out_bottom_1.20240101T000001.000Z.csv").exists());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .env("MAKERPNP_CONFIG_DIR", &config_dir)
+            .args(prepare_args(vec!["--project example1", "config", "set", "--key backup-max-age-days", "--value week"]))
+            .assert()
+            .failure()
+            .stderr(print("stderr").and(predicate::str::contains("Invalid preference value, expected a whole number. key: 'backup-max-age-days', value: 'week'")));
+
+        Ok(())
+    }
+}
+
+mod preferences {
+    use assert_cmd::Command;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
+
+    #[test]
+    fn generate_artifacts_into_preferred_artifact_directory() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let config_dir = temp_dir.path().join("config");
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .env("MAKERPNP_CONFIG_DIR", &config_dir)
+            .args(prepare_args(vec!["--project example1", "config", "set", "--key artifact-directory", "--value artifacts"]))
+            .assert()
+            .success();
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .env("MAKERPNP_CONFIG_DIR", &config_dir)
+            .args(prepare_args(vec!["--project example1", "config", "get", "--key artifact-directory"]))
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::eq("artifacts\n")));
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .env("MAKERPNP_CONFIG_DIR", &config_dir)
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "generate-artifacts"]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout"));
+
+        // and
+        assert!(temp_dir.path().join("artifacts").join("example1_report.json").exists());
+        assert!(!temp_dir.path().join("example1_report.json").exists());
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .env("MAKERPNP_CONFIG_DIR", &config_dir)
+            .args(prepare_args(vec!["--project example1", "config", "unset", "--key artifact-directory"]))
+            .assert()
+            .success();
+
+        // then
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .env("MAKERPNP_CONFIG_DIR", &config_dir)
+            .args(prepare_args(vec!["--project example1", "config", "list"]))
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::is_empty()));
+
+        Ok(())
+    }
+}
+
+mod clone_project {
+    use std::fs::read_to_string;
+    use assert_cmd::Command;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use tempfile::tempdir;
+    use util::test::{prepare_args, print};
+
+    #[test]
+    fn clone_project_into_other_directory() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let clone_dir = temp_dir.path().join("repeat");
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+        let path_arg = format!("--path {}", temp_dir.path().to_str().unwrap());
+        let clone_into_arg = format!("--into {}", clone_dir.to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "record-placements-operation", "--object-path-patterns .*", "--operation placed"]))
+            .assert()
+            .success();
+
+        // when
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "clone-project", "--name example2", clone_into_arg.as_str()]))
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout"));
+
+        // and
+        for file_name in ["project-example2.mpnp.json", "design_a_variant_a_placements.csv", "load_out_top_1.csv", "load_out_bottom_1.csv"] {
+            assert!(clone_dir.join(file_name).exists(), "missing file. file_name: {}", file_name);
+        }
+
+        // and
+        let project_content = read_to_string(clone_dir.join("project-example2.mpnp.json"))?;
+        assert!(project_content.contains(r#""name": "example2""#));
+        assert!(!project_content.contains(r#""placed": true"#));
+
+        // when cloning again
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", path_arg.as_str(), "clone-project", "--name example2", clone_into_arg.as_str()]))
+            // then
+            .assert()
+            .failure()
+            .stderr(print("stderr").and(predicate::str::contains("Project already exists.")))
+            .stdout(print("stdout"));
+
+        Ok(())
+    }
+}
+
+mod watch {
+    use std::fs::read_to_string;
+    use std::io::{BufRead, BufReader};
+    use std::process::Stdio;
+    use std::sync::mpsc;
+    use std::thread;
+    use std::time::Duration;
+    use assert_cmd::Command;
+    use tempfile::tempdir;
+    use util::test::prepare_args;
+
+    #[test]
+    fn watch() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and
+        let mut child = std::process::Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(["--project", "example1", "--path", temp_dir.path().to_str().unwrap(), "watch", "--interval", "1", "--max-refreshes", "1"])
+            .stdout(Stdio::piped())
+            .spawn()?;
+
+        let (sender, receiver) = mpsc::channel();
+        let stdout = child.stdout.take().unwrap();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                println!("{}", line);
+                if sender.send(line).is_err() {
+                    break
+                }
+            }
+        });
+
+        let wait_for = |message: &str| loop {
+            match receiver.recv_timeout(Duration::from_secs(30)) {
+                Ok(line) if line.contains(message) => break true,
+                Ok(_line) => continue,
+                Err(_err) => break false,
+            }
+        };
+
+        assert!(wait_for("Watching design variant placements. design_variants: 1"));
+
+        // when the design variant placements are re-exported
+        let placements_path = temp_dir.path().join("design_a_variant_a_placements.csv");
+        let placements_content = read_to_string(&placements_path)?;
+        std::fs::write(&placements_path, placements_content.replace(r#""R2","RES_MFR1","RES2","true","Top","20","10","90""#, r#""R2","RES_MFR1","RES2","true","Top","20","10","45""#))?;
+
+        // then
+        let refreshed = wait_for("Refreshed from design variants. refreshes: 1");
+        if !refreshed {
+            child.kill()?;
+        }
+        let status = child.wait()?;
+
+        assert!(refreshed);
+        assert!(status.success());
+
+        // and
+        let project_content = read_to_string(temp_dir.path().join("project-example1.mpnp.json"))?;
+        assert!(project_content.contains(r#""rotation": "45""#));
+
+        Ok(())
+    }
+}
+
+mod dashboard {
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::thread::sleep;
+    use std::time::Duration;
+    use assert_cmd::Command;
+    use tempfile::tempdir;
+    use util::test::prepare_args;
+
+    #[test]
+    fn dashboard_status() -> Result<(), anyhow::Error> {
+        // given
+        let temp_dir = tempdir()?;
+        let into_arg = format!("--into {}", temp_dir.path().to_str().unwrap());
+
+        // and
+        Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(prepare_args(vec!["--project example1", "example", "generate", into_arg.as_str()]))
+            .assert()
+            .success();
+
+        // and a free port
+        let address = TcpListener::bind("127.0.0.1:0")?.local_addr()?;
+
+        // when
+        let mut child = std::process::Command::new(env!("CARGO_BIN_EXE_planner"))
+            .args(["--project", "example1", "--path", temp_dir.path().to_str().unwrap(), "dashboard", "--listen", &address.to_string()])
+            .spawn()?;
+
+        let response = (0..50).find_map(|_attempt| {
+            let mut stream = TcpStream::connect(address).inspect_err(|_err| sleep(Duration::from_millis(100))).ok()?;
+            stream.write_all(b"GET /status HTTP/1.1\r\nHost: localhost\r\n\r\n").ok()?;
+            let mut response = String::new();
+            stream.read_to_string(&mut response).ok()?;
+            Some(response)
+        });
+
+        child.kill()?;
+        child.wait()?;
+
+        // then
+        let response = response.expect("dashboard response");
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "response: {}", response);
+        assert!(response.contains(r#""name": "example1""#), "response: {}", response);
+        assert!(response.contains(r#""phase_name": "top_1""#), "response: {}", response);
+
+        Ok(())
+    }
+}
+
+mod help {
+    use assert_cmd::Command;
+    use indoc::indoc;
+    use predicates::prelude::{predicate, PredicateBooleanExt};
+    use util::test::print;
+
+    #[test]
+    fn no_args() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Usage: planner [OPTIONS] <--project <PROJECT_NAME>> <COMMAND>
+
+            Commands:
+              create                           Create a new job
+              clone-project                    Clone the project for a repeat job, without any recorded operations
+              compress-project                 Compress the project file, for very large projects, or decompress it
+              add-pcb                          Add a PCB
+              set-panel-geometry               Set the positions of the units and fiducials of a panel, placements are transformed into panel coordinates
+              set-pcb-dimensions               Set the dimensions of a board or panel, placement coordinates are validated against them
+              discover-variants                Discover design variants from the placements files in the project directory
+              assign-variant-to-unit           Assign a design variant to a PCB unit
+              unit-assignments                 Export or import the design variant assignments of the PCB units, as CSV
+              acknowledge-design-changes       Acknowledge changes to the design variant placements files, so that the placements can be refreshed from them
+              assign-process-to-parts          Assign a process to parts
+              set-moisture-sensitivity         Set the moisture sensitivity level (MSL) of parts
+              import-part-details              Import part details (e.g. image, datasheet, value, description, MSL) from a parts master, or part library
+              create-phase                     Create a phase
+              create-rework-phase              Create a rework phase from placements with open inspection defects
+              clone-phase                      Clone a phase, the placements are not assigned to the new phase
+              remove-phase                     Remove a phase, its placements are unassigned
+              rename-phase                     Rename a phase, the placements, dependencies, phase ordering and operation history are updated
+              reorder-phases                   Set the order of the phases, each phase must be given exactly once
+              assign-placements-to-phase       Assign placements to a phase
+              unassign-placements-from-phase   Unassign placements from a phase
+              set-placement-override           Override whether placements are placed, for this project only, without changing the design (e.g. skip 'C7')
+              assign-feeder-to-load-out-item   Assign feeder to load-out item
+              set-load-out-alternates          Set the alternate parts of a load-out item, in order of preference, that can be loaded instead of the part
+              remove-load-out-item             Remove the load-out item of a feeder, e.g. when the part is no-longer loaded
+              set-load-out-item-quantity       Set the quantity of parts loaded in a feeder
+              rename-feeder                    Move the load-out item of a feeder to another feeder
+              suggest-feeders                  Suggest feeders for load-out items
+              set-placement-ordering           Set placement ordering for a phase
+              set-required-artifacts           Set the artifacts that must be generated for each phase that uses a process
+              set-operation-checklist          Set the tools and consumables to confirm when starting an operation of a process
+              set-work-instructions-style      Set the style of the work instructions for a phase
+              set-phase-tags                   Set or remove tags of a phase, e.g. 'line=A'
+              set-phase-dependencies           Set the phases that must be complete before a phase is started, replacing any previous dependencies
+              set-rotation-normalization       Normalize the rotations of the placements in the artifacts of a phase, with optional per-part rotation offsets
+              set-first-article-inspection     Require a first-article inspection for a phase, the first PCB unit must be signed off before the other units are placed
+              set-phase-nozzles                Set the nozzles of the machine used by a phase, placements are assigned a nozzle by the package of their part
+              set-quantity-check               Set the check of the remaining load-out item quantities before a placement operation of a phase is started
+              list-phases                      List the phases, with their tags
+              status                           Show the status of the project, i.e. phases, operation states and placements, without modifying it
+              inspect-phase                    Show the status of a phase, including its placements, without modifying it
+              analyze-load-out-reuse           Suggest a shared machine setup for batching the project with other projects, from the parts they have in common
+              analytics                        Export cycle-time and yield statistics, for each process, operator and part, from the operation history
+              metrics                          Show throughput statistics of a phase, placements per hour and time per operation, from the operation history
+              set-price-list                   Set the price list used for the cost estimates of the report
+              set-inventory                    Set the inventory, used for the inventory shortages of the report, the inventory is consumed as placements are placed
+              set-estimation                   Set the parameters of the phase time estimates of the report and status, omit all the parameters to remove them
+              generate-artifacts               Generate artifacts
+              export-bom                       Export a bill of materials, the quantity of each part, for each phase and for each unit
+              generate-certificate             Generate a completion certificate for a completed phase, certificates are also generated when a phase is completed
+              preview-artifacts                Preview artifacts, without writing them
+              search                           Search the placements, phases, parts and load-out items of the project
+              validate                         Validate the required artifacts of each phase exist and are up to date with the project
+              acknowledge-issue                Acknowledge a report issue, acknowledged issues are still counted as errors or warnings
+              waive-issue                      Waive a report issue, waived issues are listed in the report but are not counted as errors or warnings
+              release                          Release the project to production, validates the project, generates the artifacts, snapshots the project files and freezes the planning data
+              reopen                           Reopen a released project, so that planning changes can be made for the next release
+              verify                           Verify signed artifacts
+              verify-operation-history         Verify the operation history has not been modified, or had records removed
+              diff-project                     Show the changes to the placements, part states and phases since a previous save of the project
+              history                          Show the audit log, the changes made to the project by each command, oldest first
+              start-phase-operation            Record the start of a phase operation, confirming the checklist of the operation
+              record-phase-operation           Record phase operation
+              record-feeder-loaded             Record a feeder being loaded, which starts the floor life of a moisture sensitive part
+              record-placements-operation      Record placements operation
+              scan                             Record placements and operations by scanning barcodes, reads one barcode per line from stdin
+              run-phase                        Run the placements of a phase on a machine, recording each placement as it is placed
+              set-production-quantity          Set the quantity of panels, or boards, to build, each is built by a production run
+              start-production-run             Start a production run, the placement and phase operations are reset if a previous run was completed
+              complete-production-run          Complete the production run in progress, recording the placed placements
+              production-runs                  Show the completed, in progress and pending production runs
+              record-first-article-inspection  Record the sign-off of the first-article inspection of a phase
+              reset-operations                 Reset operations
+              set-operation-transitions        Set how the status of placement operations is updated
+              migrate-load-out-sources         Migrate absolute load-out sources to project-relative load-out sources
+              restore-load-out                 Restore a load-out from a backup, backups are made automatically before load-outs are modified
+              rename-part                      Rename a part in the project, the design variant placements, the load-outs, the inventory and other files
+              watch                            Watch the design variant placements files, refreshing the project when they change
+              dashboard                        Serve a read-only dashboard of the project progress
+              report                           Project report exports
+              maintenance                      Project maintenance
+              config                           User preferences, shared by all projects
+              example                          Example projects
+              help                             Print this message or the help of the given subcommand(s)
+
+            Options:
+                  --trace [<TRACE>]         Trace log file
+                  --log-json <FILE>         Structured log file, JSON lines, appended to with an object for each change to the project
+                  --path <PATH>             Path [default: .]
+                  --project <PROJECT_NAME>  Project name
+              -v, --verbose...              Increase logging verbosity
+              -q, --quiet...                Decrease logging verbosity
+              -h, --help                    Print help
+              -V, --version                 Print version
+        "};
+
+        // when
+        cmd
+            // then
+            .assert()
+            .failure()
+            .stderr(print("stderr").and(predicate::str::diff(expected_output)))
+            .stdout(print("stdout"));
+    }
+
+    #[test]
+    fn help_for_create() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Create a new job
+
+            Usage: planner <--project <PROJECT_NAME>> create [OPTIONS]
+
+            Options:
+              -v, --verbose...  Increase logging verbosity
+              -q, --quiet...    Decrease logging verbosity
+              -h, --help        Print help
+        "};
+
+        // when
+        cmd.args(["create", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_clone_project() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Clone the project for a repeat job, without any recorded operations
+
+            Usage: planner <--project <PROJECT_NAME>> clone-project [OPTIONS] --name <NAME>
+
+            Options:
+                  --name <NAME>  Name of the new project
+                  --into <INTO>  Directory for the new project, the design variant placements and load-outs are copied into it [default: the project directory]
+              -v, --verbose...   Increase logging verbosity
+              -q, --quiet...     Decrease logging verbosity
+              -h, --help         Print help
+        "};
+
+        // when
+        cmd.args(["clone-project", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_compress_project() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Compress the project file, for very large projects, or decompress it
+
+            Usage: planner <--project <PROJECT_NAME>> compress-project [OPTIONS]
+
+            Options:
+                  --decompress  Decompress the project file instead
+              -v, --verbose...  Increase logging verbosity
+              -q, --quiet...    Decrease logging verbosity
+              -h, --help        Print help
+        "};
+
+        // when
+        cmd.args(["compress-project", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_add_pcb() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Add a PCB
+
+            Usage: planner <--project <PROJECT_NAME>> add-pcb [OPTIONS] --kind <KIND> --name <NAME>
+
+            Options:
+                  --kind <KIND>  PCB kind [possible values: single, panel]
+                  --name <NAME>  Name of the PCB, e.g. 'panel_1'
+              -v, --verbose...   Increase logging verbosity
+              -q, --quiet...     Decrease logging verbosity
+              -h, --help         Print help
+        "};
+
+        // when
+        cmd.args(["add-pcb", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_set_panel_geometry() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Set the positions of the units and fiducials of a panel, placements are transformed into panel coordinates
+
+            Usage: planner <--project <PROJECT_NAME>> set-panel-geometry [OPTIONS] --pcb <PCB_NAME> --unit <UNIT,X,Y,ROTATION>
+
+            Options:
+                  --pcb <PCB_NAME>            Name of the panel PCB
+                  --unit <UNIT,X,Y,ROTATION>  Position of the origin of a unit, and its anti-clockwise rotation, e.g. '2,55.5,0,90'
+                  --fiducial <NAME,X,Y>       Position of a fiducial, e.g. 'FID1,5,5'
+              -v, --verbose...                Increase logging verbosity
+              -q, --quiet...                  Decrease logging verbosity
+              -h, --help                      Print help
+        "};
+
+        // when
+        cmd.args(["set-panel-geometry", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_set_pcb_dimensions() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Set the dimensions of a board or panel, placement coordinates are validated against them
+
+            Usage: planner <--project <PROJECT_NAME>> set-pcb-dimensions [OPTIONS] --pcb <PCB_NAME> --width <WIDTH> --height <HEIGHT>
+
+            Options:
+                  --pcb <PCB_NAME>   Name of the PCB
+                  --width <WIDTH>    Width, in millimeters
+                  --height <HEIGHT>  Height, in millimeters
+              -v, --verbose...       Increase logging verbosity
+              -q, --quiet...         Decrease logging verbosity
+              -h, --help             Print help
+        "};
+
+        // when
+        cmd.args(["set-pcb-dimensions", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_discover_variants() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Discover design variants from the placements files in the project directory
+
+            Usage: planner <--project <PROJECT_NAME>> discover-variants [OPTIONS]
+
+            Options:
+                  --design <DESIGN_NAME>  Name of the design, required to register variants when design or variant names contain underscores
+                  --register              Register the discovered variants, units can then only be assigned registered variants of the design
+              -v, --verbose...            Increase logging verbosity
+              -q, --quiet...              Decrease logging verbosity
+              -h, --help                  Print help
+        "};
+
+        // when
+        cmd.args(["discover-variants", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_assign_variant_to_unit() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Assign a design variant to a PCB unit
+
+            Usage: planner <--project <PROJECT_NAME>> assign-variant-to-unit [OPTIONS] --design <DESIGN_NAME> --variant <VARIANT_NAME> --unit <OBJECT_PATH>
+
+            Options:
+                  --design <DESIGN_NAME>    Name of the design
+                  --variant <VARIANT_NAME>  Variant of the design
+                  --unit <OBJECT_PATH>      PCB unit path
+                  --force                   Accept conflicting placements of the design variants, placements with the same ref_des, the last of the placements is used
+              -v, --verbose...              Increase logging verbosity
+              -q, --quiet...                Decrease logging verbosity
+              -h, --help                    Print help
+        "};
+
+        // when
+        cmd.args(["assign-variant-to-unit", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_unit_assignments() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Export or import the design variant assignments of the PCB units, as CSV
+
+            Usage: planner <--project <PROJECT_NAME>> unit-assignments [OPTIONS] <COMMAND>
+
+            Commands:
+              export  Export the unit assignments, so that they can be edited in a spreadsheet
+              import  Import the unit assignments, all the assignments are validated before any are made, units not in the file are unchanged
+              help    Print this message or the help of the given subcommand(s)
+
+            Options:
+              -v, --verbose...  Increase logging verbosity
+              -q, --quiet...    Decrease logging verbosity
+              -h, --help        Print help
+        "};
+
+        // when
+        cmd.args(["unit-assignments", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_unit_assignments_import() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Import the unit assignments, all the assignments are validated before any are made, units not in the file are unchanged
+
+            Usage: planner unit-assignments import [OPTIONS] --file <FILE>
+
+            Options:
+                  --file <FILE>  Unit assignments file, relative to the project directory (e.g. 'units.csv')
+                  --force        Accept conflicting placements of the design variants, placements with the same ref_des, the last of the placements is used
+              -v, --verbose...   Increase logging verbosity
+              -q, --quiet...     Decrease logging verbosity
+              -h, --help         Print help
+        "};
+
+        // when
+        cmd.args(["unit-assignments", "import", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_acknowledge_design_changes() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Acknowledge changes to the design variant placements files, so that the placements can be refreshed from them
+
+            Usage: planner <--project <PROJECT_NAME>> acknowledge-design-changes [OPTIONS]
+
+            Options:
+              -v, --verbose...  Increase logging verbosity
+              -q, --quiet...    Decrease logging verbosity
+              -h, --help        Print help
+        "};
+
+        // when
+        cmd.args(["acknowledge-design-changes", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_assign_process_to_parts() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Assign a process to parts
+
+            Usage: planner <--project <PROJECT_NAME>> assign-process-to-parts [OPTIONS] --process <PROCESS> --manufacturer <MANUFACTURER> --mpn <MPN>
+
+            Options:
+                  --process <PROCESS>            Process name
+                  --manufacturer <MANUFACTURER>  Manufacturer pattern (regexp)
+                  --mpn <MPN>                    Manufacturer part number (regexp)
+              -v, --verbose...                   Increase logging verbosity
+              -q, --quiet...                     Decrease logging verbosity
+              -h, --help                         Print help
+        "};
+
+        // when
+        cmd.args(["assign-process-to-parts", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_set_moisture_sensitivity() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Set the moisture sensitivity level (MSL) of parts
+
+            Usage: planner <--project <PROJECT_NAME>> set-moisture-sensitivity [OPTIONS] --manufacturer <MANUFACTURER> --mpn <MPN> --level <LEVEL>
+
+            Options:
+                  --manufacturer <MANUFACTURER>
+                      Manufacturer pattern (regexp)
+
+                  --mpn <MPN>
+                      Manufacturer part number (regexp)
+
+                  --level <LEVEL>
+                      Moisture sensitivity level, 'none' to remove
+
+                      Possible values:
+                      - none: Not moisture sensitive
+                      - 1
+                      - 2
+                      - 2a
+                      - 3
+                      - 4
+                      - 5
+                      - 5a
+                      - 6
+
+                  --floor-life-hours <FLOOR_LIFE_HOURS>
+                      Floor life in hours, overrides the floor life of the level, required for level 6
+
+              -v, --verbose...
+                      Increase logging verbosity
+
+              -q, --quiet...
+                      Decrease logging verbosity
+
+              -h, --help
+                      Print help (see a summary with '-h')
+        "};
+
+        // when
+        cmd.args(["set-moisture-sensitivity", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_import_part_details() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Import part details (e.g. image, datasheet, value, description, MSL) from a parts master, or part library
+
+            Usage: planner <--project <PROJECT_NAME>> import-part-details [OPTIONS] --parts <PARTS>
+
+            Options:
+                  --parts <PARTS>  Parts master ('.toml'), parts file, or DipTrace BOM, relative to the project directory (e.g. 'parts.csv')
+              -v, --verbose...     Increase logging verbosity
+              -q, --quiet...       Decrease logging verbosity
+              -h, --help           Print help
+        "};
+
+        // when
+        cmd.args(["import-part-details", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_create_phase() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Create a phase
+
+            Usage: planner <--project <PROJECT_NAME>> create-phase [OPTIONS] --process <PROCESS> --reference <REFERENCE> --load-out <LOAD_OUT> --pcb-side <PCB_SIDE>
+
+            Options:
+                  --process <PROCESS>              Process name, 'pnp', 'manual' or a process defined in 'processes.json' or 'processes.toml' of the project directory
+                  --reference <REFERENCE>          Phase reference (e.g. 'top_1')
+                  --load-out <LOAD_OUT>            Load-out source, relative to the project directory, or a URL (e.g. 'load_out_1.csv' or 'https://example.com/load_out_1.csv')
+                  --pcb-side <PCB_SIDE>            PCB side [possible values: top, bottom]
+                  --export-format <EXPORT_FORMAT>  Machine-native format to export the placements in, alongside the other artifacts [possible values: centroid]
+              -v, --verbose...                     Increase logging verbosity
+              -q, --quiet...                       Decrease logging verbosity
+              -h, --help                           Print help
+        "};
+
+        // when
+        cmd.args(["create-phase", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_create_rework_phase() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Create a rework phase from placements with open inspection defects
+
+            Usage: planner <--project <PROJECT_NAME>> create-rework-phase [OPTIONS] --reference <REFERENCE> --load-out <LOAD_OUT> --pcb-side <PCB_SIDE>
+
+            Options:
+                  --reference <REFERENCE>  Phase reference (e.g. 'rework_1')
+                  --load-out <LOAD_OUT>    Load-out source, relative to the project directory, or a URL (e.g. 'load_out_1.csv' or 'https://example.com/load_out_1.csv')
+                  --pcb-side <PCB_SIDE>    PCB side [possible values: top, bottom]
+              -v, --verbose...             Increase logging verbosity
+              -q, --quiet...               Decrease logging verbosity
+              -h, --help                   Print help
+        "};
+
+        // when
+        cmd.args(["create-rework-phase", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_clone_phase() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Clone a phase, the placements are not assigned to the new phase
+
+            Usage: planner <--project <PROJECT_NAME>> clone-phase [OPTIONS] --phase <PHASE> --reference <REFERENCE>
+
+            Options:
+                  --phase <PHASE>          Phase reference (e.g. 'top_1')
+                  --reference <REFERENCE>  New phase reference (e.g. 'top_2')
+                  --load-out <LOAD_OUT>    Load-out source for the new phase, the load-out of the phase is copied [default: the load-out of the phase]
+              -v, --verbose...             Increase logging verbosity
+              -q, --quiet...               Decrease logging verbosity
+              -h, --help                   Print help
+        "};
+
+        // when
+        cmd.args(["clone-phase", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_remove_phase() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Remove a phase, its placements are unassigned
+
+            Usage: planner <--project <PROJECT_NAME>> remove-phase [OPTIONS] --phase <PHASE>
+
+            Options:
+                  --phase <PHASE>  Phase reference (e.g. 'top_1')
+              -v, --verbose...     Increase logging verbosity
+              -q, --quiet...       Decrease logging verbosity
+              -h, --help           Print help
+        "};
+
+        // when
+        cmd.args(["remove-phase", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_rename_phase() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Rename a phase, the placements, dependencies, phase ordering and operation history are updated
+
+            Usage: planner <--project <PROJECT_NAME>> rename-phase [OPTIONS] --phase <PHASE> --reference <REFERENCE>
+
+            Options:
+                  --phase <PHASE>          Phase reference (e.g. 'top_1')
+                  --reference <REFERENCE>  New phase reference (e.g. 'top_a')
+              -v, --verbose...             Increase logging verbosity
+              -q, --quiet...               Decrease logging verbosity
+              -h, --help                   Print help
+        "};
+
+        // when
+        cmd.args(["rename-phase", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_reorder_phases() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Set the order of the phases, each phase must be given exactly once
+
+            Usage: planner <--project <PROJECT_NAME>> reorder-phases [OPTIONS] --phases <PHASES>...
+
+            Options:
+                  --phases <PHASES>...  Phase references, in order (e.g. 'bottom_1,top_1')
+              -v, --verbose...          Increase logging verbosity
+              -q, --quiet...            Decrease logging verbosity
+              -h, --help                Print help
+        "};
+
+        // when
+        cmd.args(["reorder-phases", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_assign_placements_to_phase() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Assign placements to a phase
+
+            Usage: planner <--project <PROJECT_NAME>> assign-placements-to-phase [OPTIONS] --phase <PHASE> --placements <PLACEMENTS>
+
+            Options:
+                  --phase <PHASE>            Phase reference (e.g. 'top_1')
+                  --placements <PLACEMENTS>  Placements object path pattern (regexp)
+                  --allow-reassign           Allow placements that are assigned to another phase to be reassigned
+                  --allow-side-mismatch      Allow placements on the other side of the PCB to the phase to be assigned, e.g. for glue-dot workflows
+              -v, --verbose...               Increase logging verbosity
+              -q, --quiet...                 Decrease logging verbosity
+              -h, --help                     Print help
+        "};
+
+        // when
+        cmd.args(["assign-placements-to-phase", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_unassign_placements_from_phase() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Unassign placements from a phase
+
+            Usage: planner <--project <PROJECT_NAME>> unassign-placements-from-phase [OPTIONS] --phase <PHASE> --placements <PLACEMENTS>
+
+            Options:
+                  --phase <PHASE>            Phase reference (e.g. 'top_1')
+                  --placements <PLACEMENTS>  Placements object path pattern (regexp)
+              -v, --verbose...               Increase logging verbosity
+              -q, --quiet...                 Decrease logging verbosity
+              -h, --help                     Print help
+        "};
+
+        // when
+        cmd.args(["unassign-placements-from-phase", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_set_placement_override() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Override whether placements are placed, for this project only, without changing the design (e.g. skip 'C7')
+
+            Usage: planner <--project <PROJECT_NAME>> set-placement-override [OPTIONS] --object-path-patterns <OBJECT_PATH_PATTERNS>... --override <OVERRIDE>
+
+            Options:
+                  --object-path-patterns <OBJECT_PATH_PATTERNS>...
+                      List of object path patterns (regexp) of the placements to override
+
+                  --override <OVERRIDE>
+                      The override
+
+                      Possible values:
+                      - skip:   Do not place, even if the design places it
+                      - place:  Place, even if the design does not place it
+                      - design: Use the design, i.e. remove the override
+
+              -v, --verbose...
+                      Increase logging verbosity
+
+              -q, --quiet...
+                      Decrease logging verbosity
+
+              -h, --help
+                      Print help (see a summary with '-h')
+        "};
+
+        // when
+        cmd.args(["set-placement-override", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_assign_feeder_to_load_out_item() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Assign feeder to load-out item
 
             Usage: planner <--project <PROJECT_NAME>> assign-feeder-to-load-out-item [OPTIONS] --phase <PHASE> --feeder-reference <FEEDER_REFERENCE> --manufacturer <MANUFACTURER> --mpn <MPN>
 
             Options:
-                  --phase <PHASE>                        Phase reference (e.g. 'top_1')
-                  --feeder-reference <FEEDER_REFERENCE>  Feeder reference (e.g. 'FEEDER_1')
-                  --manufacturer <MANUFACTURER>          Manufacturer pattern (regexp)
-                  --mpn <MPN>                            Manufacturer part number (regexp)
-              -v, --verbose...                           Increase logging verbosity
-              -q, --quiet...                             Decrease logging verbosity
-              -h, --help                                 Print help
+                  --phase <PHASE>                        Phase reference (e.g. 'top_1')
+                  --feeder-reference <FEEDER_REFERENCE>  Feeder reference (e.g. 'FEEDER_1')
+                  --manufacturer <MANUFACTURER>          Manufacturer pattern (regexp)
+                  --mpn <MPN>                            Manufacturer part number (regexp)
+                  --quantity <QUANTITY>                  Quantity of parts loaded
+                  --reel <REEL>                          Reel reference (e.g. 'REEL_1')
+                  --feeders <FEEDERS_FILE>               Feeder library file, to check the feeder constraints
+              -v, --verbose...                           Increase logging verbosity
+              -q, --quiet...                             Decrease logging verbosity
+              -h, --help                                 Print help
+        "};
+
+        // when
+        cmd.args(["assign-feeder-to-load-out-item", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_set_load_out_alternates() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Set the alternate parts of a load-out item, in order of preference, that can be loaded instead of the part
+
+            Usage: planner <--project <PROJECT_NAME>> set-load-out-alternates [OPTIONS] --phase <PHASE> --feeder <FEEDER>
+
+            Options:
+                  --phase <PHASE>          Phase reference (e.g. 'top_1')
+                  --feeder <FEEDER>        Feeder reference (e.g. 'FEEDER_1')
+                  --alternate <ALTERNATE>  Alternate part '<manufacturer>:<mpn>', repeat for each alternate, omit to remove the alternates
+              -v, --verbose...             Increase logging verbosity
+              -q, --quiet...               Decrease logging verbosity
+              -h, --help                   Print help
+        "};
+
+        // when
+        cmd.args(["set-load-out-alternates", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_remove_load_out_item() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Remove the load-out item of a feeder, e.g. when the part is no-longer loaded
+
+            Usage: planner <--project <PROJECT_NAME>> remove-load-out-item [OPTIONS] --phase <PHASE> --feeder <FEEDER>
+
+            Options:
+                  --phase <PHASE>    Phase reference (e.g. 'top_1')
+                  --feeder <FEEDER>  Feeder reference (e.g. 'FEEDER_1')
+              -v, --verbose...       Increase logging verbosity
+              -q, --quiet...         Decrease logging verbosity
+              -h, --help             Print help
+        "};
+
+        // when
+        cmd.args(["remove-load-out-item", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_set_load_out_item_quantity() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Set the quantity of parts loaded in a feeder
+
+            Usage: planner <--project <PROJECT_NAME>> set-load-out-item-quantity [OPTIONS] --phase <PHASE> --feeder <FEEDER>
+
+            Options:
+                  --phase <PHASE>        Phase reference (e.g. 'top_1')
+                  --feeder <FEEDER>      Feeder reference (e.g. 'FEEDER_1')
+                  --quantity <QUANTITY>  Quantity of parts loaded, omit to stop tracking the quantity
+              -v, --verbose...           Increase logging verbosity
+              -q, --quiet...             Decrease logging verbosity
+              -h, --help                 Print help
+        "};
+
+        // when
+        cmd.args(["set-load-out-item-quantity", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_rename_feeder() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Move the load-out item of a feeder to another feeder
+
+            Usage: planner <--project <PROJECT_NAME>> rename-feeder [OPTIONS] --phase <PHASE> --feeder <FEEDER> --new-feeder <NEW_FEEDER>
+
+            Options:
+                  --phase <PHASE>            Phase reference (e.g. 'top_1')
+                  --feeder <FEEDER>          Feeder reference (e.g. 'FEEDER_1')
+                  --new-feeder <NEW_FEEDER>  New feeder reference (e.g. 'FEEDER_2')
+              -v, --verbose...               Increase logging verbosity
+              -q, --quiet...                 Decrease logging verbosity
+              -h, --help                     Print help
+        "};
+
+        // when
+        cmd.args(["rename-feeder", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_suggest_feeders() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Suggest feeders for load-out items
+
+            Usage: planner <--project <PROJECT_NAME>> suggest-feeders [OPTIONS] --phase <PHASE> --manufacturer <MANUFACTURER> --mpn <MPN>
+
+            Options:
+                  --phase <PHASE>                Phase reference (e.g. 'top_1')
+                  --manufacturer <MANUFACTURER>  Manufacturer pattern (regexp)
+                  --mpn <MPN>                    Manufacturer part number (regexp)
+                  --limit <LIMIT>                Maximum amount of suggestions for each load-out item [default: 3]
+                  --feeders <FEEDERS_FILE>       Feeder library file, to only suggest feeders that are compatible with the part
+              -v, --verbose...                   Increase logging verbosity
+              -q, --quiet...                     Decrease logging verbosity
+              -h, --help                         Print help
+        "};
+
+        // when
+        cmd.args(["suggest-feeders", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_set_placement_ordering() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Set placement ordering for a phase
+
+            Usage: planner <--project <PROJECT_NAME>> set-placement-ordering [OPTIONS] --phase <PHASE>
+
+            Options:
+                  --phase <PHASE>
+                      Phase reference (e.g. 'top_1')
+                  --placement-orderings [<PLACEMENT_ORDERINGS>...]
+                      Orderings (e.g. 'PCB_UNIT:ASC,FEEDER_REFERENCE:ASC')
+              -v, --verbose...
+                      Increase logging verbosity
+              -q, --quiet...
+                      Decrease logging verbosity
+              -h, --help
+                      Print help
+        "};
+
+        // when
+        cmd.args(["set-placement-ordering", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+
+    #[test]
+    fn help_for_set_required_artifacts() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Set the artifacts that must be generated for each phase that uses a process
+
+            Usage: planner <--project <PROJECT_NAME>> set-required-artifacts [OPTIONS] --process <PROCESS>
+
+            Options:
+                  --process <PROCESS>           Process name (e.g. 'pnp')
+                  --artifacts [<ARTIFACTS>...]  Artifacts (e.g. 'phase-placements,work-instructions'), none to remove the requirements [possible values: phase-placements, work-instructions, rework-instructions, phase-export, first-article-checklist, machine-export, feeder-setup-sheet]
+              -v, --verbose...                  Increase logging verbosity
+              -q, --quiet...                    Decrease logging verbosity
+              -h, --help                        Print help
+        "};
+
+        // when
+        cmd.args(["set-required-artifacts", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_set_operation_checklist() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Set the tools and consumables to confirm when starting an operation of a process
+
+            Usage: planner <--project <PROJECT_NAME>> set-operation-checklist [OPTIONS] --process <PROCESS> --operation <OPERATION>
+
+            Options:
+                  --process <PROCESS>      Process name (e.g. 'pnp')
+                  --operation <OPERATION>  The operation, 'loadpcbs', 'automatedpnp', 'reflowcomponents', 'manuallysoldercomponents' or a custom operation of the process
+                  --item <ITEMS>           Checklist item (e.g. 'stencil=STN-001'), may be repeated, none to remove
+              -v, --verbose...             Increase logging verbosity
+              -q, --quiet...               Decrease logging verbosity
+              -h, --help                   Print help
+        "};
+
+        // when
+        cmd.args(["set-operation-checklist", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_set_work_instructions_style() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Set the style of the work instructions for a phase
+
+            Usage: planner <--project <PROJECT_NAME>> set-work-instructions-style [OPTIONS] --phase <PHASE> --style <STYLE>
+
+            Options:
+                  --phase <PHASE>  Phase reference (e.g. 'top_1')
+                  --style <STYLE>  Style, 'pick-list' groups the placements by feeder and part, for manual assembly [possible values: parts, pick-list]
+              -v, --verbose...     Increase logging verbosity
+              -q, --quiet...       Decrease logging verbosity
+              -h, --help           Print help
+        "};
+
+        // when
+        cmd.args(["set-work-instructions-style", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_set_phase_tags() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Set or remove tags of a phase, e.g. 'line=A'
+
+            Usage: planner <--project <PROJECT_NAME>> set-phase-tags [OPTIONS] --phase <PHASE>
+
+            Options:
+                  --phase <PHASE>    Phase reference (e.g. 'top_1')
+                  --tag <KEY=VALUE>  Tag to set (e.g. 'line=A'), may be repeated
+                  --remove <KEY>     Key of a tag to remove, may be repeated
+              -v, --verbose...       Increase logging verbosity
+              -q, --quiet...         Decrease logging verbosity
+              -h, --help             Print help
+        "};
+
+        // when
+        cmd.args(["set-phase-tags", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_set_phase_dependencies() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Set the phases that must be complete before a phase is started, replacing any previous dependencies
+
+            Usage: planner <--project <PROJECT_NAME>> set-phase-dependencies [OPTIONS] --phase <PHASE>
+
+            Options:
+                  --phase <PHASE>                 Phase reference (e.g. 'manual_bottom')
+                  --depends-on [<DEPENDS_ON>...]  Phase references (e.g. 'reflow_top'), omit to remove the dependencies
+              -v, --verbose...                    Increase logging verbosity
+              -q, --quiet...                      Decrease logging verbosity
+              -h, --help                          Print help
+        "};
+
+        // when
+        cmd.args(["set-phase-dependencies", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_set_rotation_normalization() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Normalize the rotations of the placements in the artifacts of a phase, with optional per-part rotation offsets
+
+            Usage: planner <--project <PROJECT_NAME>> set-rotation-normalization [OPTIONS] --phase <PHASE>
+
+            Options:
+                  --phase <PHASE>
+                      Phase reference (e.g. 'top_1')
+
+                  --range <RANGE>
+                      Rotation range, omit to remove the normalization
+
+                      Possible values:
+                      - signed:   -180 < rotation <= 180
+                      - unsigned: 0 <= rotation < 360
+
+                  --offsets <OFFSETS>
+                      Rotation offsets file, relative to the project directory, anti-clockwise degrees for each part (e.g. 'rotation_offsets.csv')
+
+              -v, --verbose...
+                      Increase logging verbosity
+
+              -q, --quiet...
+                      Decrease logging verbosity
+
+              -h, --help
+                      Print help (see a summary with '-h')
+        "};
+
+        // when
+        cmd.args(["set-rotation-normalization", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_set_first_article_inspection() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Require a first-article inspection for a phase, the first PCB unit must be signed off before the other units are placed
+
+            Usage: planner <--project <PROJECT_NAME>> set-first-article-inspection [OPTIONS] --phase <PHASE>
+
+            Options:
+                  --phase <PHASE>  Phase reference (e.g. 'top_1')
+                  --required       Require the inspection, omit to no longer require it
+              -v, --verbose...     Increase logging verbosity
+              -q, --quiet...       Decrease logging verbosity
+              -h, --help           Print help
+        "};
+
+        // when
+        cmd.args(["set-first-article-inspection", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_set_phase_nozzles() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Set the nozzles of the machine used by a phase, placements are assigned a nozzle by the package of their part
+
+            Usage: planner <--project <PROJECT_NAME>> set-phase-nozzles [OPTIONS] --phase <PHASE>
+
+            Options:
+                  --phase <PHASE>             Phase reference (e.g. 'top_1')
+                  --heads <HEADS>             Count of heads, each head holds one nozzle [default: 1]
+                  --nozzle <NOZZLE=PACKAGES>  Nozzle and the packages it can pick (e.g. 'N1=0402,0603'), in order of preference, may be repeated, none to remove
+              -v, --verbose...                Increase logging verbosity
+              -q, --quiet...                  Decrease logging verbosity
+              -h, --help                      Print help
+        "};
+
+        // when
+        cmd.args(["set-phase-nozzles", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_set_quantity_check() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Set the check of the remaining load-out item quantities before a placement operation of a phase is started
+
+            Usage: planner <--project <PROJECT_NAME>> set-quantity-check [OPTIONS] --phase <PHASE> --mode <MODE>
+
+            Options:
+                  --phase <PHASE>  Phase reference (e.g. 'top_1')
+                  --mode <MODE>    Mode, 'block' prevents the operation from being started unless overridden [possible values: off, warn, block]
+              -v, --verbose...     Increase logging verbosity
+              -q, --quiet...       Decrease logging verbosity
+              -h, --help           Print help
+        "};
+
+        // when
+        cmd.args(["set-quantity-check", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_list_phases() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            List the phases, with their tags
+
+            Usage: planner <--project <PROJECT_NAME>> list-phases [OPTIONS]
+
+            Options:
+                  --tag <KEY=VALUE>  Only list phases with the tag (e.g. 'line=A'), may be repeated
+                  --pcb <PCB_NAME>   Only list phases with placements on the PCB, by name (e.g. 'display_board')
+              -v, --verbose...       Increase logging verbosity
+              -q, --quiet...         Decrease logging verbosity
+              -h, --help             Print help
+        "};
+
+        // when
+        cmd.args(["list-phases", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_status() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Show the status of the project, i.e. phases, operation states and placements, without modifying it
+
+            Usage: planner <--project <PROJECT_NAME>> status [OPTIONS]
+
+            Options:
+              -v, --verbose...  Increase logging verbosity
+              -q, --quiet...    Decrease logging verbosity
+              -h, --help        Print help
+        "};
+
+        // when
+        cmd.args(["status", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_inspect_phase() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Show the status of a phase, including its placements, without modifying it
+
+            Usage: planner <--project <PROJECT_NAME>> inspect-phase [OPTIONS] --phase <PHASE>
+
+            Options:
+                  --phase <PHASE>  Phase reference (e.g. 'top_1')
+              -v, --verbose...     Increase logging verbosity
+              -q, --quiet...       Decrease logging verbosity
+              -h, --help           Print help
+        "};
+
+        // when
+        cmd.args(["inspect-phase", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_analyze_load_out_reuse() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Suggest a shared machine setup for batching the project with other projects, from the parts they have in common
+
+            Usage: planner <--project <PROJECT_NAME>> analyze-load-out-reuse [OPTIONS] --other-project <PROJECT_FILE>
+
+            Options:
+                  --other-project <PROJECT_FILE>  Project file of another project, relative to the path, may be repeated
+              -v, --verbose...                    Increase logging verbosity
+              -q, --quiet...                      Decrease logging verbosity
+              -h, --help                          Print help
+        "};
+
+        // when
+        cmd.args(["analyze-load-out-reuse", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_analytics() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Export cycle-time and yield statistics, for each process, operator and part, from the operation history
+
+            Usage: planner <--project <PROJECT_NAME>> analytics [OPTIONS]
+
+            Options:
+                  --format <FORMAT>               Format [default: csv] [possible values: csv, json]
+                  --file <FILE>                   Analytics file, relative to the project directory [default: '<PROJECT_NAME>_analytics.<FORMAT>' in the artifact directory]
+                  --other-project <PROJECT_FILE>  Project file of another project to include, relative to the path, may be repeated
+              -v, --verbose...                    Increase logging verbosity
+              -q, --quiet...                      Decrease logging verbosity
+              -h, --help                          Print help
+        "};
+
+        // when
+        cmd.args(["analytics", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_metrics() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Show throughput statistics of a phase, placements per hour and time per operation, from the operation history
+
+            Usage: planner <--project <PROJECT_NAME>> metrics [OPTIONS] --phase <PHASE>
+
+            Options:
+                  --phase <PHASE>  Phase reference (e.g. 'top_1')
+                  --since <SINCE>  Only include the operation history from this date and time, RFC 3339 (e.g. '2024-10-16T08:00:00Z')
+                  --until <UNTIL>  Only include the operation history before this date and time, RFC 3339 (e.g. '2024-10-17T08:00:00Z')
+              -v, --verbose...     Increase logging verbosity
+              -q, --quiet...       Decrease logging verbosity
+              -h, --help           Print help
+        "};
+
+        // when
+        cmd.args(["metrics", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_set_price_list() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Set the price list used for the cost estimates of the report
+
+            Usage: planner <--project <PROJECT_NAME>> set-price-list [OPTIONS]
+
+            Options:
+                  --source <SOURCE>  Price list file, relative to the project directory, omit to remove the price list
+              -v, --verbose...       Increase logging verbosity
+              -q, --quiet...         Decrease logging verbosity
+              -h, --help             Print help
+        "};
+
+        // when
+        cmd.args(["set-price-list", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_set_inventory() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Set the inventory, used for the inventory shortages of the report, the inventory is consumed as placements are placed
+
+            Usage: planner <--project <PROJECT_NAME>> set-inventory [OPTIONS]
+
+            Options:
+                  --source <SOURCE>  Inventory file, relative to the project directory, omit to remove the inventory
+              -v, --verbose...       Increase logging verbosity
+              -q, --quiet...         Decrease logging verbosity
+              -h, --help             Print help
+        "};
+
+        // when
+        cmd.args(["set-inventory", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_set_estimation() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Set the parameters of the phase time estimates of the report and status, omit all the parameters to remove them
+
+            Usage: planner <--project <PROJECT_NAME>> set-estimation [OPTIONS]
+
+            Options:
+                  --placement-rate <PROCESS=RATE>
+                      Placement rate of a process, in placements per hour, may be repeated, e.g. 'pnp=1200'
+                  --feeder-setup-time <FEEDER_SETUP_TIME>
+                      Feeder setup time, in seconds per feeder [default: 0]
+                  --reflow-time <REFLOW_TIME>
+                      Reflow time, in seconds per phase whose process reflows the components [default: 0]
+              -v, --verbose...
+                      Increase logging verbosity
+              -q, --quiet...
+                      Decrease logging verbosity
+              -h, --help
+                      Print help
+        "};
+
+        // when
+        cmd.args(["set-estimation", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_generate_artifacts() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Generate artifacts
+
+            Usage: planner <--project <PROJECT_NAME>> generate-artifacts [OPTIONS]
+
+            Options:
+                  --signing-key <SIGNING_KEY>      Sign the artifacts using the signing key file (hex encoded ed25519 secret key) [default: the 'signing-key' preference] [env: MAKERPNP_SIGNING_KEY=]
+                  --allow-missing-feeders          Generate the machine exports even if parts have not been assigned to a feeder, the parts are logged as warnings
+                  --report-format <REPORT_FORMAT>  Report format, the JSON report is always generated, 'html' also generates an HTML report [default: json] [possible values: json, html]
+              -v, --verbose...                     Increase logging verbosity
+              -q, --quiet...                       Decrease logging verbosity
+              -h, --help                           Print help
+        "};
+
+        // when
+        cmd.args(["generate-artifacts", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_export_bom() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Export a bill of materials, the quantity of each part, for each phase and for each unit
+
+            Usage: planner <--project <PROJECT_NAME>> export-bom [OPTIONS]
+
+            Options:
+                  --format <FORMAT>  Format [default: csv] [possible values: csv, json, xlsx]
+                  --file <FILE>      BOM file, relative to the project directory [default: '<PROJECT_NAME>[_<PCB_NAME>]_bom.<FORMAT>' in the artifact directory]
+                  --pcb <PCB_NAME>   Only include the placements of the PCB, by name (e.g. 'display_board')
+              -v, --verbose...       Increase logging verbosity
+              -q, --quiet...         Decrease logging verbosity
+              -h, --help             Print help
+        "};
+
+        // when
+        cmd.args(["export-bom", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_generate_certificate() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Generate a completion certificate for a completed phase, certificates are also generated when a phase is completed
+
+            Usage: planner <--project <PROJECT_NAME>> generate-certificate [OPTIONS] --phase <PHASE>
+
+            Options:
+                  --phase <PHASE>              Phase reference (e.g. 'top_1')
+                  --signing-key <SIGNING_KEY>  Sign the certificate using the signing key file (hex encoded ed25519 secret key) [default: the 'signing-key' preference] [env: MAKERPNP_SIGNING_KEY=]
+              -v, --verbose...                 Increase logging verbosity
+              -q, --quiet...                   Decrease logging verbosity
+              -h, --help                       Print help
+        "};
+
+        // when
+        cmd.args(["generate-certificate", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_preview_artifacts() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Preview artifacts, without writing them
+
+            Usage: planner <--project <PROJECT_NAME>> preview-artifacts [OPTIONS]
+
+            Options:
+                  --max-lines <MAX_LINES>  Maximum amount of lines to show for each artifact [default: 20]
+              -v, --verbose...             Increase logging verbosity
+              -q, --quiet...               Decrease logging verbosity
+              -h, --help                   Print help
+        "};
+
+        // when
+        cmd.args(["preview-artifacts", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_search() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Search the placements, phases, parts and load-out items of the project
+
+            Usage: planner <--project <PROJECT_NAME>> search [OPTIONS] --query <QUERY>
+
+            Options:
+                  --query <QUERY>  Text to find, case-insensitive
+              -v, --verbose...     Increase logging verbosity
+              -q, --quiet...       Decrease logging verbosity
+              -h, --help           Print help
+        "};
+
+        // when
+        cmd.args(["search", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_validate() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Validate the required artifacts of each phase exist and are up to date with the project
+
+            Usage: planner <--project <PROJECT_NAME>> validate [OPTIONS]
+
+            Options:
+              -v, --verbose...  Increase logging verbosity
+              -q, --quiet...    Decrease logging verbosity
+              -h, --help        Print help
+        "};
+
+        // when
+        cmd.args(["validate", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_acknowledge_issue() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Acknowledge a report issue, acknowledged issues are still counted as errors or warnings
+
+            Usage: planner <--project <PROJECT_NAME>> acknowledge-issue [OPTIONS] --id <ISSUE_ID> --reason <REASON>
+
+            Options:
+                  --id <ISSUE_ID>    Id of the issue, as listed in the report
+                  --reason <REASON>  Reason for acknowledging the issue
+              -v, --verbose...       Increase logging verbosity
+              -q, --quiet...         Decrease logging verbosity
+              -h, --help             Print help
+        "};
+
+        // when
+        cmd.args(["acknowledge-issue", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_waive_issue() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Waive a report issue, waived issues are listed in the report but are not counted as errors or warnings
+
+            Usage: planner <--project <PROJECT_NAME>> waive-issue [OPTIONS] --id <ISSUE_ID> --reason <REASON>
+
+            Options:
+                  --id <ISSUE_ID>    Id of the issue, as listed in the report
+                  --reason <REASON>  Reason for waiving the issue
+              -v, --verbose...       Increase logging verbosity
+              -q, --quiet...         Decrease logging verbosity
+              -h, --help             Print help
+        "};
+
+        // when
+        cmd.args(["waive-issue", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_release() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Release the project to production, validates the project, generates the artifacts, snapshots the project files and freezes the planning data
+
+            Usage: planner <--project <PROJECT_NAME>> release [OPTIONS]
+
+            Options:
+              -v, --verbose...  Increase logging verbosity
+              -q, --quiet...    Decrease logging verbosity
+              -h, --help        Print help
+        "};
+
+        // when
+        cmd.args(["release", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_reopen() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Reopen a released project, so that planning changes can be made for the next release
+
+            Usage: planner <--project <PROJECT_NAME>> reopen [OPTIONS]
+
+            Options:
+              -v, --verbose...  Increase logging verbosity
+              -q, --quiet...    Decrease logging verbosity
+              -h, --help        Print help
+        "};
+
+        // when
+        cmd.args(["reopen", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_verify() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Verify signed artifacts
+
+            Usage: planner <--project <PROJECT_NAME>> verify [OPTIONS]
+
+            Options:
+                  --verifying-key <VERIFYING_KEY>  Verifying key file (hex encoded ed25519 public key) [default: the 'verifying-key' preference] [env: MAKERPNP_VERIFYING_KEY=]
+              -v, --verbose...                     Increase logging verbosity
+              -q, --quiet...                       Decrease logging verbosity
+              -h, --help                           Print help
+        "};
+
+        // when
+        cmd.args(["verify", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_verify_operation_history() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Verify the operation history has not been modified, or had records removed
+
+            Usage: planner <--project <PROJECT_NAME>> verify-operation-history [OPTIONS]
+
+            Options:
+                  --phase <PHASE>  Phase reference (e.g. 'top_1'), all phases if not specified
+                  --head <HEAD>    A previously recorded head hash of the phase operation history, to detect records removed from the end
+              -v, --verbose...     Increase logging verbosity
+              -q, --quiet...       Decrease logging verbosity
+              -h, --help           Print help
+        "};
+
+        // when
+        cmd.args(["verify-operation-history", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_diff_project() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Show the changes to the placements, part states and phases since a previous save of the project
+
+            Usage: planner <--project <PROJECT_NAME>> diff-project [OPTIONS] --against <AGAINST>
+
+            Options:
+                  --against <AGAINST>  Previous save of the project file, relative to the project directory (e.g. 'backup/project-job1.mpnp.json')
+                  --format <FORMAT>    Output format [default: text] [possible values: text, json]
+              -v, --verbose...         Increase logging verbosity
+              -q, --quiet...           Decrease logging verbosity
+              -h, --help               Print help
+        "};
+
+        // when
+        cmd.args(["diff-project", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_history() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Show the audit log, the changes made to the project by each command, oldest first
+
+            Usage: planner <--project <PROJECT_NAME>> history [OPTIONS]
+
+            Options:
+                  --limit <LIMIT>  Only show the most recent items
+              -v, --verbose...     Increase logging verbosity
+              -q, --quiet...       Decrease logging verbosity
+              -h, --help           Print help
+        "};
+
+        // when
+        cmd.args(["history", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_start_phase_operation() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Record the start of a phase operation, confirming the checklist of the operation
+
+            Usage: planner <--project <PROJECT_NAME>> start-phase-operation [OPTIONS] --phase <PHASE> --operation <OPERATION>
+
+            Options:
+                  --phase <PHASE>                  Phase reference (e.g. 'top_1')
+                  --operation <OPERATION>          The operation to start, 'loadpcbs', 'automatedpnp', 'reflowcomponents', 'manuallysoldercomponents' or a custom operation of the process
+                  --confirm <ITEM>                 Checklist item that has been checked, may be repeated, unconfirmed items are prompted for
+                  --allow-insufficient-quantities  Start the operation even if the load-out item quantities are insufficient, otherwise prompted for
+              -v, --verbose...                     Increase logging verbosity
+              -q, --quiet...                       Decrease logging verbosity
+              -h, --help                           Print help
+        "};
+
+        // when
+        cmd.args(["start-phase-operation", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_record_phase_operation() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Record phase operation
+
+            Usage: planner <--project <PROJECT_NAME>> record-phase-operation [OPTIONS] --phase <PHASE> --operation <OPERATION> --set <SET>
+
+            Options:
+                  --phase <PHASE>          Phase reference (e.g. 'top_1')
+                  --operation <OPERATION>  The operation to update, 'loadpcbs', 'automatedpnp', 'reflowcomponents', 'manuallysoldercomponents' or a custom operation of the process
+                  --set <SET>              The process operation to set [possible values: completed]
+              -v, --verbose...             Increase logging verbosity
+              -q, --quiet...               Decrease logging verbosity
+              -h, --help                   Print help
+        "};
+
+        // when
+        cmd.args(["record-phase-operation", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_record_feeder_loaded() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Record a feeder being loaded, which starts the floor life of a moisture sensitive part
+
+            Usage: planner <--project <PROJECT_NAME>> record-feeder-loaded [OPTIONS] --phase <PHASE> --feeder <FEEDER>
+
+            Options:
+                  --phase <PHASE>          Phase reference (e.g. 'top_1')
+                  --feeder <FEEDER>        Feeder reference (e.g. 'FEEDER_1')
+                  --alternate <ALTERNATE>  The alternate part '<manufacturer>:<mpn>' that was loaded, omit if the part itself was loaded
+              -v, --verbose...             Increase logging verbosity
+              -q, --quiet...               Decrease logging verbosity
+              -h, --help                   Print help
+        "};
+
+        // when
+        cmd.args(["record-feeder-loaded", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_record_placements_operation() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Record placements operation
+
+            Usage: planner <--project <PROJECT_NAME>> record-placements-operation [OPTIONS] --operation <OPERATION>
+
+            Options:
+                  --object-path-patterns <OBJECT_PATH_PATTERNS>...
+                      List of reference designators to apply the operation to
+                  --object-paths <OBJECT_PATHS>...
+                      List of object paths to apply the operation to, e.g. 'panel=1::unit=*::ref_des=R*', '*' and '?' are wildcards
+                  --operation <OPERATION>
+                      The completed operation to apply [possible values: placed, unplaced, inspectionfailed]
+              -v, --verbose...
+                      Increase logging verbosity
+              -q, --quiet...
+                      Decrease logging verbosity
+              -h, --help
+                      Print help
+        "};
+
+        // when
+        cmd.args(["record-placements-operation", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_scan() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Record placements and operations by scanning barcodes, reads one barcode per line from stdin
+
+            Scan a unit (e.g. 'panel=1::unit=1') then a feeder (e.g. 'FEEDER_1') to record the unplaced placements of the feeder's part on the unit as placed, scan an operation to record it as complete.
+
+            Usage: planner <--project <PROJECT_NAME>> scan [OPTIONS] --phase <PHASE>
+
+            Options:
+                  --phase <PHASE>
+                      Phase reference (e.g. 'top_1')
+
+                  --mappings <FILE>
+                      Barcode mappings file, relative to the project directory, defaults to 'barcodes.csv' if it exists
+
+              -v, --verbose...
+                      Increase logging verbosity
+
+              -q, --quiet...
+                      Decrease logging verbosity
+
+              -h, --help
+                      Print help (see a summary with '-h')
+        "};
+
+        // when
+        cmd.args(["scan", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_run_phase() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Run the placements of a phase on a machine, recording each placement as it is placed
+
+            Usage: planner <--project <PROJECT_NAME>> run-phase [OPTIONS] --phase <PHASE> --machine <MACHINE>
+
+            Options:
+                  --phase <PHASE>      Phase reference (e.g. 'top_1')
+                  --machine <MACHINE>  The machine to run the phase on [possible values: simulated]
+              -v, --verbose...         Increase logging verbosity
+              -q, --quiet...           Decrease logging verbosity
+              -h, --help               Print help
+        "};
+
+        // when
+        cmd.args(["run-phase", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_set_production_quantity() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Set the quantity of panels, or boards, to build, each is built by a production run
+
+            Usage: planner <--project <PROJECT_NAME>> set-production-quantity [OPTIONS]
+
+            Options:
+                  --quantity <QUANTITY>  Quantity, omit for an open-ended quantity
+              -v, --verbose...           Increase logging verbosity
+              -q, --quiet...             Decrease logging verbosity
+              -h, --help                 Print help
+        "};
+
+        // when
+        cmd.args(["set-production-quantity", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_start_production_run() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Start a production run, the placement and phase operations are reset if a previous run was completed
+
+            Usage: planner <--project <PROJECT_NAME>> start-production-run [OPTIONS] --serial <SERIAL>
+
+            Options:
+                  --serial <SERIAL>  Serial number of the panel, or board (e.g. 'SN0001')
+              -v, --verbose...       Increase logging verbosity
+              -q, --quiet...         Decrease logging verbosity
+              -h, --help             Print help
+        "};
+
+        // when
+        cmd.args(["start-production-run", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_complete_production_run() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Complete the production run in progress, recording the placed placements
+
+            Usage: planner <--project <PROJECT_NAME>> complete-production-run [OPTIONS]
+
+            Options:
+                  --force       Complete the run even if some placements have not been placed
+              -v, --verbose...  Increase logging verbosity
+              -q, --quiet...    Decrease logging verbosity
+              -h, --help        Print help
+        "};
+
+        // when
+        cmd.args(["complete-production-run", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_production_runs() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Show the completed, in progress and pending production runs
+
+            Usage: planner <--project <PROJECT_NAME>> production-runs [OPTIONS]
+
+            Options:
+              -v, --verbose...  Increase logging verbosity
+              -q, --quiet...    Decrease logging verbosity
+              -h, --help        Print help
+        "};
+
+        // when
+        cmd.args(["production-runs", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_record_first_article_inspection() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Record the sign-off of the first-article inspection of a phase
+
+            Usage: planner <--project <PROJECT_NAME>> record-first-article-inspection [OPTIONS] --phase <PHASE>
+
+            Options:
+                  --phase <PHASE>  Phase reference (e.g. 'top_1')
+              -v, --verbose...     Increase logging verbosity
+              -q, --quiet...       Decrease logging verbosity
+              -h, --help           Print help
+        "};
+
+        // when
+        cmd.args(["record-first-article-inspection", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_reset_operations() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Reset operations
+
+            Usage: planner <--project <PROJECT_NAME>> reset-operations [OPTIONS]
+
+            Options:
+              -v, --verbose...  Increase logging verbosity
+              -q, --quiet...    Decrease logging verbosity
+              -h, --help        Print help
         "};
 
         // when
-        cmd.args(["assign-feeder-to-load-out-item", "--help"])
+        cmd.args(["reset-operations", "--help"])
             // then
             .assert()
             .success()
@@ -2007,21 +8858,111 @@ mod help {
     }
 
     #[test]
-    fn help_for_set_placement_ordering() {
+    fn help_for_set_operation_transitions() {
         // given
         let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
 
         // and
         let expected_output = indoc! {"
-            Set placement ordering for a phase
+            Set how the status of placement operations is updated
 
-            Usage: planner <--project <PROJECT_NAME>> set-placement-ordering [OPTIONS] --phase <PHASE>
+            Usage: planner <--project <PROJECT_NAME>> set-operation-transitions [OPTIONS] --mode <MODE>
 
             Options:
-                  --phase <PHASE>
-                      Phase reference (e.g. 'top_1')
-                  --placement-orderings [<PLACEMENT_ORDERINGS>...]
-                      Orderings (e.g. 'PCB_UNIT:ASC,FEEDER_REFERENCE:ASC')
+                  --mode <MODE>  Operation transitions mode [possible values: automatic, manual]
+              -v, --verbose...   Increase logging verbosity
+              -q, --quiet...     Decrease logging verbosity
+              -h, --help         Print help
+        "};
+
+        // when
+        cmd.args(["set-operation-transitions", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_migrate_load_out_sources() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Migrate absolute load-out sources to project-relative load-out sources
+
+            Usage: planner <--project <PROJECT_NAME>> migrate-load-out-sources [OPTIONS]
+
+            Options:
+              -v, --verbose...  Increase logging verbosity
+              -q, --quiet...    Decrease logging verbosity
+              -h, --help        Print help
+        "};
+
+        // when
+        cmd.args(["migrate-load-out-sources", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_restore_load_out() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Restore a load-out from a backup, backups are made automatically before load-outs are modified
+
+            Usage: planner <--project <PROJECT_NAME>> restore-load-out [OPTIONS] --phase <PHASE>
+
+            Options:
+                  --phase <PHASE>       Phase reference (e.g. 'top_1')
+                  --backup <FILE_NAME>  File name of the backup (e.g. 'load_out_1.20241016T120000.000Z.csv'), defaults to the latest backup
+                  --list                List the backups, without restoring
+              -v, --verbose...          Increase logging verbosity
+              -q, --quiet...            Decrease logging verbosity
+              -h, --help                Print help
+        "};
+
+        // when
+        cmd.args(["restore-load-out", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_rename_part() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Rename a part in the project, the design variant placements, the load-outs, the inventory and other files
+
+            Usage: planner <--project <PROJECT_NAME>> rename-part [OPTIONS] --manufacturer <MANUFACTURER> --mpn <MPN> --new-manufacturer <NEW_MANUFACTURER> --new-mpn <NEW_MPN>
+
+            Options:
+                  --manufacturer <MANUFACTURER>
+                      Manufacturer
+                  --mpn <MPN>
+                      Manufacturer part number
+                  --new-manufacturer <NEW_MANUFACTURER>
+                      New manufacturer
+                  --new-mpn <NEW_MPN>
+                      New manufacturer part number
+                  --file <FILE>
+                      Other files to rename the part in, relative to the project directory (e.g. 'part_mappings.csv')
+                  --dry-run
+                      Show the changes, without writing them
               -v, --verbose...
                       Increase logging verbosity
               -q, --quiet...
@@ -2031,7 +8972,7 @@ mod help {
         "};
 
         // when
-        cmd.args(["set-placement-ordering", "--help"])
+        cmd.args(["rename-part", "--help"])
             // then
             .assert()
             .success()
@@ -2039,17 +8980,75 @@ mod help {
             .stdout(print("stdout").and(predicate::str::diff(expected_output)));
     }
 
+    #[test]
+    fn help_for_watch() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Watch the design variant placements files, refreshing the project when they change
+
+            Usage: planner <--project <PROJECT_NAME>> watch [OPTIONS]
+
+            Options:
+                  --interval <INTERVAL>            Interval, in seconds, between checks for changes [default: 2]
+                  --max-refreshes <MAX_REFRESHES>  Stop after refreshing the project this many times, watches until terminated if not specified
+              -v, --verbose...                     Increase logging verbosity
+              -q, --quiet...                       Decrease logging verbosity
+              -h, --help                           Print help
+        "};
+
+        // when
+        cmd.args(["watch", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
 
     #[test]
-    fn help_for_generate_artifacts() {
+    fn help_for_dashboard() {
         // given
         let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
 
         // and
         let expected_output = indoc! {"
-            Generate artifacts
+            Serve a read-only dashboard of the project progress
 
-            Usage: planner <--project <PROJECT_NAME>> generate-artifacts [OPTIONS]
+            Usage: planner <--project <PROJECT_NAME>> dashboard [OPTIONS]
+
+            Options:
+                  --listen <LISTEN>  Address to listen on (e.g. '0.0.0.0:8080') [default: 127.0.0.1:8080]
+              -v, --verbose...       Increase logging verbosity
+              -q, --quiet...         Decrease logging verbosity
+              -h, --help             Print help
+        "};
+
+        // when
+        cmd.args(["dashboard", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_report() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Project report exports
+
+            Usage: planner <--project <PROJECT_NAME>> report [OPTIONS] <COMMAND>
+
+            Commands:
+              site  Render the report into a static site (HTML/JS, no server required), generate the artifacts first
+              help  Print this message or the help of the given subcommand(s)
 
             Options:
               -v, --verbose...  Increase logging verbosity
@@ -2058,7 +9057,7 @@ mod help {
         "};
 
         // when
-        cmd.args(["generate-artifacts", "--help"])
+        cmd.args(["report", "--help"])
             // then
             .assert()
             .success()
@@ -2067,27 +9066,25 @@ mod help {
     }
 
     #[test]
-    fn help_for_record_phase_operation() {
+    fn help_for_report_site() {
         // given
         let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
 
         // and
         let expected_output = indoc! {"
-            Record phase operation
+            Render the report into a static site (HTML/JS, no server required), generate the artifacts first
 
-            Usage: planner <--project <PROJECT_NAME>> record-phase-operation [OPTIONS] --phase <PHASE> --operation <OPERATION> --set <SET>
+            Usage: planner report site [OPTIONS]
 
             Options:
-                  --phase <PHASE>          Phase reference (e.g. 'top_1')
-                  --operation <OPERATION>  The operation to update [possible values: loadpcbs, automatedpnp, reflowcomponents, manuallysoldercomponents]
-                  --set <SET>              The process operation to set [possible values: completed]
-              -v, --verbose...             Increase logging verbosity
-              -q, --quiet...               Decrease logging verbosity
-              -h, --help                   Print help
+                  --into <DIR>  Directory to write the site into [default: '<PROJECT_NAME>_report_site' in the artifact directory]
+              -v, --verbose...  Increase logging verbosity
+              -q, --quiet...    Decrease logging verbosity
+              -h, --help        Print help
         "};
 
         // when
-        cmd.args(["record-phase-operation", "--help"])
+        cmd.args(["report", "site", "--help"])
             // then
             .assert()
             .success()
@@ -2096,31 +9093,28 @@ mod help {
     }
 
     #[test]
-    fn help_for_record_placements_operation() {
+    fn help_for_maintenance() {
         // given
         let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
 
         // and
         let expected_output = indoc! {"
-            Record placements operation
+            Project maintenance
 
-            Usage: planner <--project <PROJECT_NAME>> record-placements-operation [OPTIONS] --object-path-patterns <OBJECT_PATH_PATTERNS>... --operation <OPERATION>
+            Usage: planner <--project <PROJECT_NAME>> maintenance [OPTIONS] <COMMAND>
+
+            Commands:
+              cleanup  Remove old backups of the project files and load-outs, the latest backup of each file is always kept
+              help     Print this message or the help of the given subcommand(s)
 
             Options:
-                  --object-path-patterns <OBJECT_PATH_PATTERNS>...
-                      List of reference designators to apply the operation to
-                  --operation <OPERATION>
-                      The completed operation to apply [possible values: placed]
-              -v, --verbose...
-                      Increase logging verbosity
-              -q, --quiet...
-                      Decrease logging verbosity
-              -h, --help
-                      Print help
+              -v, --verbose...  Increase logging verbosity
+              -q, --quiet...    Decrease logging verbosity
+              -h, --help        Print help
         "};
 
         // when
-        cmd.args(["record-placements-operation", "--help"])
+        cmd.args(["maintenance", "--help"])
             // then
             .assert()
             .success()
@@ -2129,15 +9123,51 @@ mod help {
     }
 
     #[test]
-    fn help_for_reset_operations() {
+    fn help_for_maintenance_cleanup() {
         // given
         let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
 
         // and
         let expected_output = indoc! {"
-            Reset operations
+            Remove old backups of the project files and load-outs, the latest backup of each file is always kept
 
-            Usage: planner <--project <PROJECT_NAME>> reset-operations [OPTIONS]
+            Usage: planner maintenance cleanup [OPTIONS]
+
+            Options:
+                  --max-count <MAX_COUNT>        Maximum amount of backups of each file to keep [default: the 'backup-max-count' preference, or 10]
+                  --max-age-days <MAX_AGE_DAYS>  Maximum age of backups to keep, in days [default: the 'backup-max-age-days' preference]
+                  --dry-run                      Show the backups that would be removed, without removing them
+              -v, --verbose...                   Increase logging verbosity
+              -q, --quiet...                     Decrease logging verbosity
+              -h, --help                         Print help
+        "};
+
+        // when
+        cmd.args(["maintenance", "cleanup", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_config() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            User preferences, shared by all projects
+
+            Usage: planner <--project <PROJECT_NAME>> config [OPTIONS] <COMMAND>
+
+            Commands:
+              get    Show a preference, nothing is shown if the preference is not set
+              set    Set a preference
+              unset  Remove a preference, so that the default is used
+              list   Show all the preferences that are set
+              help   Print this message or the help of the given subcommand(s)
 
             Options:
               -v, --verbose...  Increase logging verbosity
@@ -2146,7 +9176,92 @@ mod help {
         "};
 
         // when
-        cmd.args(["reset-operations", "--help"])
+        cmd.args(["config", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_config_set() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Set a preference
+
+            Usage: planner config set [OPTIONS] --key <KEY> --value <VALUE>
+
+            Options:
+                  --key <KEY>      Preference [possible values: language, output-format, artifact-directory, operator, backup-max-count, backup-max-age-days, signing-key, verifying-key]
+                  --value <VALUE>  Value (e.g. 'artifacts' for the artifact directory)
+              -v, --verbose...     Increase logging verbosity
+              -q, --quiet...       Decrease logging verbosity
+              -h, --help           Print help
+        "};
+
+        // when
+        cmd.args(["config", "set", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_example() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Example projects
+
+            Usage: planner <--project <PROJECT_NAME>> example [OPTIONS] <COMMAND>
+
+            Commands:
+              generate  Generate an example project, design files, load-outs and a script of the commands used to create them
+              help      Print this message or the help of the given subcommand(s)
+
+            Options:
+              -v, --verbose...  Increase logging verbosity
+              -q, --quiet...    Decrease logging verbosity
+              -h, --help        Print help
+        "};
+
+        // when
+        cmd.args(["example", "--help"])
+            // then
+            .assert()
+            .success()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::diff(expected_output)));
+    }
+
+    #[test]
+    fn help_for_example_generate() {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_planner"));
+
+        // and
+        let expected_output = indoc! {"
+            Generate an example project, design files, load-outs and a script of the commands used to create them
+
+            Usage: planner example generate [OPTIONS] --into <DIR>
+
+            Options:
+                  --into <DIR>  Directory to generate the example into
+              -v, --verbose...  Increase logging verbosity
+              -q, --quiet...    Decrease logging verbosity
+              -h, --help        Print help
+        "};
+
+        // when
+        cmd.args(["example", "generate", "--help"])
             // then
             .assert()
             .success()
@@ -0,0 +1,77 @@
+//! Compares loading and saving of a large project, as plain JSON and compressed.
+//!
+//! Run with `cargo bench -p planning --features zstd`.
+
+use std::path::PathBuf;
+use std::str::FromStr;
+use criterion::{criterion_group, criterion_main, Criterion};
+use rust_decimal::Decimal;
+use tempfile::TempDir;
+use planning::placement::{PlacementState, PlacementStatus};
+use planning::project;
+use planning::project::Project;
+use pnp::object_path::{ObjectPath, PanelIndex, RefDes, UnitIndex};
+use pnp::part::Part;
+use pnp::pcb::{PcbKind, PcbSide};
+use pnp::placement::{Placement, PlacementKind};
+
+const UNITS: usize = 100;
+const PLACEMENTS_PER_UNIT: usize = 500;
+
+fn build_large_project() -> Project {
+    let mut project = Project::new("bench".to_string());
+
+    for unit in 1..=UNITS {
+        let unit_path = ObjectPath::new_unit(PcbKind::Panel, PanelIndex::new(1).unwrap(), UnitIndex::new(unit).unwrap());
+
+        for index in 1..=PLACEMENTS_PER_UNIT {
+            let ref_des = format!("R{}", index);
+            let mut object_path = unit_path.clone();
+            object_path.set_ref_des(RefDes::from_str(&ref_des).unwrap());
+
+            project.placements.insert(object_path, PlacementState {
+                unit_path: unit_path.clone(),
+                placement: Placement {
+                    ref_des,
+                    part: Part::new("RES_MFR1".to_string(), format!("RES{}", index % 50)),
+                    place: true,
+                    pcb_side: PcbSide::Top,
+                    x: Decimal::new(index as i64 * 125, 2),
+                    y: Decimal::new(unit as i64 * 250, 2),
+                    rotation: Decimal::new(90, 0),
+                    kind: PlacementKind::Component,
+                },
+                placed: false,
+                status: PlacementStatus::Known,
+                phase: None,
+                place_override: None,
+                defects: vec![],
+            });
+        }
+    }
+
+    project
+}
+
+fn project_file(c: &mut Criterion) {
+    let temp_dir = TempDir::new().unwrap();
+    let project = build_large_project();
+
+    let plain_path = project::build_plain_project_file_path("bench", temp_dir.path());
+    let compressed_path: PathBuf = project::build_compressed_project_file_path(&plain_path);
+
+    let mut group = c.benchmark_group("project_file");
+    group.sample_size(10);
+
+    for (name, path) in [("plain", &plain_path), ("compressed", &compressed_path)] {
+        group.bench_function(format!("save_{}", name), |b| b.iter(|| project::save(&project, path).unwrap()));
+        group.bench_function(format!("load_{}", name), |b| b.iter(|| project::load(path).unwrap()));
+
+        println!("{} size: {} bytes", name, path.metadata().unwrap().len());
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, project_file);
+criterion_main!(benches);
@@ -0,0 +1,301 @@
+//! Historical analytics, the operation histories of the phases of one or more projects are aggregated into cycle-time
+//! and yield statistics, for each process, each operator and each part.
+//!
+//! The cycle time of an operation is the time from when the operation was started, see `OperationStarted`, until it
+//! was completed.  The yield is the percentage of placed placements that did not fail inspection, placements that are
+//! re-placed after failing inspection are counted each time they are placed.
+//!
+//! Placement operations are attributed to the operator that started the most recent operation of the phase.
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use csv::QuoteStyle;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use thiserror::Error;
+use crate::operation_history;
+use crate::operation_history::{OperationHistoryItem, OperationHistoryKind};
+use crate::placement::PlacementOperation;
+use crate::project::Project;
+use crate::reference::Reference;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AnalyticsFormat {
+    Csv,
+    Json,
+}
+
+impl AnalyticsFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            AnalyticsFormat::Csv => "csv",
+            AnalyticsFormat::Json => "json",
+        }
+    }
+}
+
+impl Display for AnalyticsFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AnalyticsFormat::Csv => write!(f, "CSV"),
+            AnalyticsFormat::Json => write!(f, "JSON"),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum AnalyticsError {
+    #[error("Unable to build CSV. cause: {0:}")]
+    Csv(#[from] csv::Error),
+
+    #[error("Unable to build JSON. cause: {0:}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Unable to write CSV. cause: {0:}")]
+    Io(#[from] std::io::Error),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum AnalyticsGroup {
+    Process,
+    Operator,
+    Part,
+}
+
+/// The statistics of a process, an operator or a part, the cycle times are in seconds and are `None` if no operations
+/// were completed, the yield is `None` if no placements were placed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all(serialize = "PascalCase"))]
+pub struct AnalyticsRecord {
+    pub group: AnalyticsGroup,
+    /// The process name, the operator or the part, e.g. 'pnp', 'Operator1' or 'RES_MFR1:RES1'.
+    pub key: String,
+    pub cycles: usize,
+    pub min_cycle_time: Option<i64>,
+    pub mean_cycle_time: Option<i64>,
+    pub max_cycle_time: Option<i64>,
+    pub placed: usize,
+    pub inspection_failed: usize,
+    #[serde(rename(serialize = "Yield"))]
+    pub yield_percent: Option<Decimal>,
+}
+
+#[derive(Default)]
+struct Statistics {
+    cycle_times: Vec<i64>,
+    placed: usize,
+    inspection_failed: usize,
+}
+
+pub fn build_analytics_file_name(name: &str, format: AnalyticsFormat) -> String {
+    format!("{}_analytics.{}", name, format.extension())
+}
+
+/// Loads the operation history of each phase of the project, phases without an operation history are omitted.
+pub fn load_phase_operation_histories(project: &Project, path: &Path) -> anyhow::Result<BTreeMap<Reference, Vec<OperationHistoryItem>>> {
+    let mut phase_operation_histories = BTreeMap::new();
+
+    for reference in project.phases.keys() {
+        let phase_log_path = operation_history::build_phase_log_path(path, reference);
+        if !phase_log_path.exists() {
+            continue
+        }
+
+        phase_operation_histories.insert(reference.clone(), operation_history::read_or_default(&phase_log_path)?);
+    }
+
+    Ok(phase_operation_histories)
+}
+
+/// Each project is given with the operation history of each of its phases, the records are in group order, then in key
+/// order.
+pub fn build_analytics(projects: &[(&Project, &BTreeMap<Reference, Vec<OperationHistoryItem>>)]) -> Vec<AnalyticsRecord> {
+    let mut statistics: BTreeMap<(AnalyticsGroup, String), Statistics> = BTreeMap::new();
+
+    for (project, phase_operation_histories) in projects.iter() {
+        for (reference, operation_history) in phase_operation_histories.iter() {
+            // phases that have since been removed from the project cannot be attributed to a process
+            let Some(phase) = project.phases.get(reference) else {
+                continue
+            };
+            let process = phase.process.to_string();
+
+            let mut started = BTreeMap::new();
+            let mut operator: Option<String> = None;
+
+            for item in operation_history.iter() {
+                match &item.operation {
+                    OperationHistoryKind::OperationStarted { operation, operator: started_by, .. } => {
+                        started.insert(operation.clone(), item.date_time);
+                        operator.clone_from(started_by);
+                    },
+                    OperationHistoryKind::PlacementOperation { object_path, operation } => {
+                        let part = project.placements.get(object_path)
+                            .map(|placement_state| placement_state.placement.part.to_string());
+
+                        let keys = [Some((AnalyticsGroup::Process, process.clone())), operator.clone().map(|operator| (AnalyticsGroup::Operator, operator)), part.map(|part| (AnalyticsGroup::Part, part))];
+                        for key in keys.into_iter().flatten() {
+                            let entry = statistics.entry(key).or_default();
+                            match operation {
+                                PlacementOperation::Placed => entry.placed += 1,
+                                PlacementOperation::InspectionFailed => entry.inspection_failed += 1,
+                                PlacementOperation::Unplaced => (),
+                            }
+                        }
+                    },
+                    kind => {
+                        let Some(operation) = kind.completed_operation() else {
+                            continue
+                        };
+                        let Some(started_at) = started.remove(&operation) else {
+                            continue
+                        };
+                        let cycle_time = (item.date_time - started_at).whole_seconds();
+
+                        let keys = [Some((AnalyticsGroup::Process, process.clone())), operator.clone().map(|operator| (AnalyticsGroup::Operator, operator))];
+                        for key in keys.into_iter().flatten() {
+                            statistics.entry(key).or_default().cycle_times.push(cycle_time);
+                        }
+                    },
+                }
+            }
+        }
+    }
+
+    statistics.into_iter()
+        .map(|((group, key), statistics)| {
+            let cycles = statistics.cycle_times.len();
+            let yield_percent = (statistics.placed > 0).then(|| {
+                let passed = statistics.placed.saturating_sub(statistics.inspection_failed);
+                (Decimal::from(passed) * Decimal::ONE_HUNDRED / Decimal::from(statistics.placed)).round_dp(1)
+            });
+
+            AnalyticsRecord {
+                group,
+                key,
+                cycles,
+                min_cycle_time: statistics.cycle_times.iter().min().copied(),
+                mean_cycle_time: (cycles > 0).then(|| statistics.cycle_times.iter().sum::<i64>() / cycles as i64),
+                max_cycle_time: statistics.cycle_times.iter().max().copied(),
+                placed: statistics.placed,
+                inspection_failed: statistics.inspection_failed,
+                yield_percent,
+            }
+        })
+        .collect()
+}
+
+pub fn build_analytics_content(records: &[AnalyticsRecord], format: AnalyticsFormat) -> Result<Vec<u8>, AnalyticsError> {
+    match format {
+        AnalyticsFormat::Csv => {
+            let mut writer = csv::WriterBuilder::new()
+                .quote_style(QuoteStyle::Always)
+                .from_writer(vec![]);
+
+            for record in records {
+                writer.serialize(record)?;
+            }
+
+            writer.into_inner().map_err(|error| AnalyticsError::Io(error.into_error()))
+        },
+        AnalyticsFormat::Json => {
+            let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
+            let mut ser = serde_json::Serializer::with_formatter(vec![], formatter);
+            records.serialize(&mut ser)?;
+
+            let mut content = ser.into_inner();
+            content.push(b'\n');
+
+            Ok(content)
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::str::FromStr;
+    use indoc::indoc;
+    use rust_decimal_macros::dec;
+    use time::{Duration, OffsetDateTime};
+    use pnp::object_path::ObjectPath;
+    use pnp::part::Part;
+    use pnp::pcb::PcbSide;
+    use crate::analytics::{build_analytics, build_analytics_content, AnalyticsFormat, AnalyticsGroup, AnalyticsRecord};
+    use crate::operation_history::{OperationHistoryItem, OperationHistoryKind};
+    use crate::placement::PlacementOperation;
+    use crate::process::{ProcessOperationKind, ProcessOperationStatus};
+    use crate::project::Project;
+    use crate::reference::Reference;
+    use crate::test::project_builder::{PlacementStateBuilder, ProjectBuilder};
+
+    fn build_project() -> Project {
+        let res1 = Part::new("RES_MFR1".to_string(), "RES1".to_string());
+
+        ProjectBuilder::new()
+            .with_phase("top_1", "pnp", "load_out_1.csv", PcbSide::Top)
+            .with_placements(["R1", "R2"].map(|ref_des| PlacementStateBuilder::new(1, ref_des, &res1).with_phase("top_1")))
+            .build()
+    }
+
+    fn build_operation_history() -> Vec<OperationHistoryItem> {
+        let reference = Reference::from_str("top_1").unwrap();
+        let start = OffsetDateTime::UNIX_EPOCH;
+        let placement_operation = |ref_des: &str, operation: PlacementOperation| OperationHistoryKind::PlacementOperation {
+            object_path: ObjectPath::from_str(&format!("panel=1::unit=1::ref_des={}", ref_des)).unwrap(),
+            operation,
+        };
+
+        [
+            (0, OperationHistoryKind::OperationStarted { operation: ProcessOperationKind::AutomatedPnp, confirmed: vec![], operator: Some("Operator1".to_string()) }),
+            (10, placement_operation("R1", PlacementOperation::Placed)),
+            (20, placement_operation("R2", PlacementOperation::Placed)),
+            (30, placement_operation("R2", PlacementOperation::InspectionFailed)),
+            (60, OperationHistoryKind::AutomatedPnp { status: ProcessOperationStatus::Complete }),
+            (100, OperationHistoryKind::OperationStarted { operation: ProcessOperationKind::AutomatedPnp, confirmed: vec![], operator: None }),
+            (110, placement_operation("R2", PlacementOperation::Placed)),
+            (200, OperationHistoryKind::AutomatedPnp { status: ProcessOperationStatus::Complete }),
+            // completed without being started, not a cycle
+            (300, OperationHistoryKind::LoadPcbs { status: ProcessOperationStatus::Complete }),
+        ].into_iter()
+            .map(|(seconds, kind)| OperationHistoryItem::new(start + Duration::seconds(seconds), reference.clone(), kind))
+            .collect()
+    }
+
+    #[test]
+    pub fn analytics() {
+        // given
+        let project = build_project();
+        let phase_operation_histories = BTreeMap::from([(Reference::from_str("top_1").unwrap(), build_operation_history())]);
+
+        // when
+        let records = build_analytics(&[(&project, &phase_operation_histories)]);
+
+        // then
+        assert_eq!(records, vec![
+            AnalyticsRecord { group: AnalyticsGroup::Process, key: "pnp".to_string(), cycles: 2, min_cycle_time: Some(60), mean_cycle_time: Some(80), max_cycle_time: Some(100), placed: 3, inspection_failed: 1, yield_percent: Some(dec!(66.7)) },
+            AnalyticsRecord { group: AnalyticsGroup::Operator, key: "Operator1".to_string(), cycles: 1, min_cycle_time: Some(60), mean_cycle_time: Some(60), max_cycle_time: Some(60), placed: 2, inspection_failed: 1, yield_percent: Some(dec!(50.0)) },
+            AnalyticsRecord { group: AnalyticsGroup::Part, key: "RES_MFR1:RES1".to_string(), cycles: 0, min_cycle_time: None, mean_cycle_time: None, max_cycle_time: None, placed: 3, inspection_failed: 1, yield_percent: Some(dec!(66.7)) },
+        ]);
+    }
+
+    #[test]
+    pub fn csv_content() -> anyhow::Result<()> {
+        // given
+        let records = vec![
+            AnalyticsRecord { group: AnalyticsGroup::Part, key: "RES_MFR1:RES1".to_string(), cycles: 0, min_cycle_time: None, mean_cycle_time: None, max_cycle_time: None, placed: 2, inspection_failed: 0, yield_percent: Some(dec!(100.0)) },
+        ];
+
+        // when
+        let content = build_analytics_content(&records, AnalyticsFormat::Csv)?;
+
+        // then
+        assert_eq!(String::from_utf8(content)?, indoc! {r#"
+            "Group","Key","Cycles","MinCycleTime","MeanCycleTime","MaxCycleTime","Placed","InspectionFailed","Yield"
+            "Part","RES_MFR1:RES1","0","","","","2","0","100.0"
+        "#});
+
+        Ok(())
+    }
+}
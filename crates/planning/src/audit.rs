@@ -0,0 +1,117 @@
+//! Project-level audit log, an append-only JSON lines file with one item for each command that modified the project.
+//!
+//! Unlike the operation history of a phase, which records production activities, the audit log records all the
+//! changes to the project, including changes to the planning and to the load-outs, with the messages of the mutation
+//! events, see `crate::mutation`, that were logged by the command.
+
+use std::fmt::{Display, Formatter};
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use anyhow::Context;
+use time::format_description::well_known::Rfc3339;
+use time::serde::rfc3339;
+use time::OffsetDateTime;
+use tracing::info;
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct AuditLogItem {
+    #[serde(with = "rfc3339")]
+    pub date_time: OffsetDateTime,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub user: Option<String>,
+    /// The command that modified the project, e.g. 'create-phase'.
+    pub command: String,
+    /// The rendered messages of the mutation events logged by the command, one for each change, oldest first.
+    pub changes: Vec<String>,
+}
+
+impl Display for AuditLogItem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let date_time = self.date_time.format(&Rfc3339).map_err(|_| std::fmt::Error)?;
+
+        write!(f, "{} {} {}: {}", date_time, self.user.as_deref().unwrap_or("-"), self.command, self.changes.join("; "))
+    }
+}
+
+/// e.g. 'project-job1.audit.jsonl'
+pub fn build_audit_log_path(name: &str, path: &Path) -> PathBuf {
+    path.join(format!("project-{}.audit.jsonl", name))
+}
+
+/// The user, from the `USER` environment variable, or `USERNAME` on Windows.
+pub fn find_user() -> Option<String> {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .ok()
+        .filter(|user| !user.is_empty())
+}
+
+pub fn append(audit_log_path: &Path, item: &AuditLogItem) -> anyhow::Result<()> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(audit_log_path)
+        .with_context(|| format!("Error opening audit log. file: {:?}", audit_log_path))?;
+
+    let mut line = serde_json::to_vec(item)?;
+    line.push(b'\n');
+    file.write_all(&line)?;
+
+    info!("Recorded audit log item. command: '{}', changes: {}", item.command, item.changes.len());
+
+    Ok(())
+}
+
+/// The items of the audit log, oldest first, empty if there is no audit log.
+pub fn read(audit_log_path: &Path) -> anyhow::Result<Vec<AuditLogItem>> {
+    if !audit_log_path.exists() {
+        return Ok(vec![])
+    }
+
+    let file = std::fs::File::open(audit_log_path)
+        .with_context(|| format!("Error reading audit log. file: {:?}", audit_log_path))?;
+
+    BufReader::new(file).lines()
+        .enumerate()
+        .filter(|(_index, line)| line.as_ref().map_or(true, |line| !line.trim().is_empty()))
+        .map(|(index, line)| {
+            let line = line?;
+            serde_json::from_str(&line)
+                .with_context(|| format!("Error reading audit log item. file: {:?}, line: {}", audit_log_path, index + 1))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+    use time::OffsetDateTime;
+    use crate::audit::{append, read, AuditLogItem};
+
+    #[test]
+    pub fn append_and_read() -> anyhow::Result<()> {
+        // given
+        let temp_dir = tempdir()?;
+        let audit_log_path = temp_dir.path().join("project-job1.audit.jsonl");
+        let item = AuditLogItem {
+            date_time: OffsetDateTime::UNIX_EPOCH,
+            user: Some("user1".to_string()),
+            command: "create-phase".to_string(),
+            changes: vec!["Created phase. reference: 'top_1', process: pnp, load_out: load_out_1.csv".to_string()],
+        };
+
+        // when
+        append(&audit_log_path, &item)?;
+        append(&audit_log_path, &item)?;
+
+        // then
+        assert_eq!(read(&audit_log_path)?, vec![item.clone(), item.clone()]);
+
+        // and
+        assert_eq!(item.to_string(), "1970-01-01T00:00:00Z user1 create-phase: Created phase. reference: 'top_1', process: pnp, load_out: load_out_1.csv");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,318 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter};
+use csv::QuoteStyle;
+use rust_xlsxwriter::{Format, Workbook};
+use serde::Serialize;
+use thiserror::Error;
+use pnp::object_path::ObjectPath;
+use pnp::part::Part;
+use pnp::pcb::Pcb;
+use util::sorting::natural_cmp;
+use crate::placement::PlacementStatus;
+use crate::project::Project;
+use crate::reference::Reference;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BomFormat {
+    Csv,
+    Json,
+    Xlsx,
+}
+
+impl BomFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            BomFormat::Csv => "csv",
+            BomFormat::Json => "json",
+            BomFormat::Xlsx => "xlsx",
+        }
+    }
+}
+
+impl Display for BomFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BomFormat::Csv => write!(f, "CSV"),
+            BomFormat::Json => write!(f, "JSON"),
+            BomFormat::Xlsx => write!(f, "XLSX"),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum BomError {
+    #[error("Unable to build CSV. cause: {0:}")]
+    Csv(#[from] csv::Error),
+
+    #[error("Unable to build JSON. cause: {0:}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Unable to build XLSX. cause: {0:}")]
+    Xlsx(#[from] rust_xlsxwriter::XlsxError),
+
+    #[error("Unable to write CSV. cause: {0:}")]
+    Io(#[from] std::io::Error),
+}
+
+/// A bill of materials, the placements that are to be placed, aggregated by part.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Bom {
+    /// The phases and units that have placements, in order, used for the columns of the tabular formats.
+    pub phases: Vec<Reference>,
+    pub units: Vec<String>,
+    pub items: Vec<BomItem>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BomItem {
+    pub manufacturer: String,
+    pub mpn: String,
+    pub quantity: u32,
+    /// Reference designators, in natural order, e.g. 'R2' before 'R10'.
+    pub ref_des: Vec<String>,
+    /// Quantities of the placements that are assigned to a phase.
+    pub phase_quantities: BTreeMap<Reference, u32>,
+    pub unit_quantities: BTreeMap<String, u32>,
+}
+
+/// The PCB name is included when the BOM is for a single PCB, e.g. 'job1_display_board_bom.csv'.
+pub fn build_bom_file_name(name: &str, pcb: Option<&Pcb>, format: BomFormat) -> String {
+    match pcb {
+        Some(pcb) => format!("{}_{}_bom.{}", name, pcb.name, format.extension()),
+        None => format!("{}_bom.{}", name, format.extension()),
+    }
+}
+
+/// Builds the BOM, only placements that are to be placed, and that are still in the design, are included.
+///
+/// When a PCB is specified, only the placements of the units of the PCB are included.
+pub fn build_bom(project: &Project, pcb: Option<&Pcb>) -> Bom {
+    let mut part_items: BTreeMap<&Part, BomItem> = BTreeMap::new();
+    let mut phases: BTreeSet<&Reference> = BTreeSet::new();
+    let mut units: BTreeSet<&ObjectPath> = BTreeSet::new();
+
+    let placement_states = project.placements.values()
+        .filter(|placement_state| placement_state.place() && matches!(placement_state.status, PlacementStatus::Known))
+        .filter(|placement_state| pcb.is_none() || project.find_pcb(&placement_state.unit_path).eq(&pcb));
+
+    for placement_state in placement_states {
+        let part = &placement_state.placement.part;
+        let item = part_items.entry(part).or_insert_with(|| BomItem {
+            manufacturer: part.manufacturer.clone(),
+            mpn: part.mpn.clone(),
+            quantity: 0,
+            ref_des: vec![],
+            phase_quantities: Default::default(),
+            unit_quantities: Default::default(),
+        });
+
+        item.quantity += 1;
+        if !item.ref_des.contains(&placement_state.placement.ref_des) {
+            item.ref_des.push(placement_state.placement.ref_des.clone());
+        }
+        if let Some(phase) = &placement_state.phase {
+            *item.phase_quantities.entry(phase.clone()).or_default() += 1;
+            phases.insert(phase);
+        }
+        *item.unit_quantities.entry(placement_state.unit_path.to_string()).or_default() += 1;
+        units.insert(&placement_state.unit_path);
+    }
+
+    let items = part_items.into_values()
+        .map(|mut item| {
+            item.ref_des.sort_by(|a, b| natural_cmp(a, b));
+            item
+        })
+        .collect();
+
+    // phases are in the order they are performed
+    let phases = project.phase_orderings.iter()
+        .filter(|reference| phases.contains(reference))
+        .cloned()
+        .collect();
+
+    Bom {
+        phases,
+        units: units.into_iter().map(ObjectPath::to_string).collect(),
+        items,
+    }
+}
+
+pub fn build_bom_content(bom: &Bom, format: BomFormat) -> Result<Vec<u8>, BomError> {
+    match format {
+        BomFormat::Csv => build_bom_csv(bom),
+        BomFormat::Json => build_bom_json(bom),
+        BomFormat::Xlsx => build_bom_xlsx(bom),
+    }
+}
+
+/// The headers and rows of the tabular formats, with a quantity column for each phase and for each unit.
+fn build_bom_table(bom: &Bom) -> (Vec<String>, Vec<Vec<BomCell>>) {
+    let mut headers: Vec<String> = ["Manufacturer", "Mpn", "Quantity", "RefDes"].iter().map(|header| header.to_string()).collect();
+    headers.extend(bom.phases.iter().map(|phase| format!("Phase {}", phase)));
+    headers.extend(bom.units.iter().map(|unit| format!("Unit {}", unit)));
+
+    let rows = bom.items.iter().map(|item| {
+        let mut row = vec![
+            BomCell::Text(item.manufacturer.clone()),
+            BomCell::Text(item.mpn.clone()),
+            BomCell::Quantity(item.quantity),
+            BomCell::Text(item.ref_des.join(" ")),
+        ];
+        row.extend(bom.phases.iter().map(|phase| BomCell::Quantity(item.phase_quantities.get(phase).copied().unwrap_or(0))));
+        row.extend(bom.units.iter().map(|unit| BomCell::Quantity(item.unit_quantities.get(unit).copied().unwrap_or(0))));
+        row
+    }).collect();
+
+    (headers, rows)
+}
+
+enum BomCell {
+    Text(String),
+    Quantity(u32),
+}
+
+impl Display for BomCell {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BomCell::Text(text) => f.write_str(text),
+            BomCell::Quantity(quantity) => write!(f, "{}", quantity),
+        }
+    }
+}
+
+fn build_bom_csv(bom: &Bom) -> Result<Vec<u8>, BomError> {
+    let (headers, rows) = build_bom_table(bom);
+
+    let mut writer = csv::WriterBuilder::new()
+        .quote_style(QuoteStyle::Always)
+        .from_writer(vec![]);
+
+    writer.write_record(&headers)?;
+    for row in rows {
+        writer.write_record(row.iter().map(BomCell::to_string))?;
+    }
+
+    writer.into_inner().map_err(|error| BomError::Io(error.into_error()))
+}
+
+fn build_bom_json(bom: &Bom) -> Result<Vec<u8>, BomError> {
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
+    let mut ser = serde_json::Serializer::with_formatter(vec![], formatter);
+    bom.serialize(&mut ser)?;
+
+    let mut content = ser.into_inner();
+    content.push(b'\n');
+
+    Ok(content)
+}
+
+fn build_bom_xlsx(bom: &Bom) -> Result<Vec<u8>, BomError> {
+    let (headers, rows) = build_bom_table(bom);
+
+    let mut workbook = Workbook::new();
+    let worksheet = workbook.add_worksheet();
+    worksheet.set_name("BOM")?;
+
+    let header_format = Format::new().set_bold();
+    for (column, header) in headers.iter().enumerate() {
+        worksheet.write_string_with_format(0, column as u16, header, &header_format)?;
+    }
+
+    for (index, row) in rows.iter().enumerate() {
+        let row_index = index as u32 + 1;
+        for (column, cell) in row.iter().enumerate() {
+            match cell {
+                BomCell::Text(text) => worksheet.write_string(row_index, column as u16, text)?,
+                BomCell::Quantity(quantity) => worksheet.write_number(row_index, column as u16, *quantity)?,
+            };
+        }
+    }
+
+    Ok(workbook.save_to_buffer()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use indoc::indoc;
+    use pnp::part::Part;
+    use pnp::pcb::PcbKind;
+    use crate::bom::{build_bom, build_bom_content, build_bom_file_name, BomFormat};
+    use crate::placement::PlacementStatus;
+    use crate::reference::Reference;
+    use crate::test::project_builder::{PlacementStateBuilder, ProjectBuilder};
+
+    #[test]
+    pub fn aggregate_placements_by_part() {
+        // given
+        let resistor = Part::new("RES_MFR1".to_string(), "RES1".to_string());
+        let capacitor = Part::new("CAP_MFR1".to_string(), "CAP1".to_string());
+
+        // and
+        let mut project = ProjectBuilder::new()
+            .with_pcb(PcbKind::Panel, "panel_a")
+            .with_placements([
+                PlacementStateBuilder::new(1, "R10", &resistor).with_phase("top_1"),
+                PlacementStateBuilder::new(1, "R2", &resistor).with_phase("top_1"),
+                PlacementStateBuilder::new(2, "R2", &resistor),
+                PlacementStateBuilder::new(2, "C1", &capacitor).with_phase("top_1"),
+                // not placed, or no-longer in the design
+                PlacementStateBuilder::new(1, "C1", &capacitor).with_place(false).with_phase("top_1"),
+                PlacementStateBuilder::new(1, "C2", &capacitor).with_status(PlacementStatus::Unknown).with_phase("top_1"),
+            ])
+            .build();
+        project.phase_orderings.insert(Reference::from_str("top_1").unwrap());
+
+        // and
+        let expected_content = indoc! {r#"
+            "Manufacturer","Mpn","Quantity","RefDes","Phase top_1","Unit panel=1::unit=1","Unit panel=1::unit=2"
+            "CAP_MFR1","CAP1","1","C1","1","0","1"
+            "RES_MFR1","RES1","3","R2 R10","2","2","1"
+        "#};
+
+        // when
+        let bom = build_bom(&project, None);
+        let content = build_bom_content(&bom, BomFormat::Csv).unwrap();
+
+        // then
+        assert_eq!(String::from_utf8(content).unwrap(), expected_content);
+
+        // and
+        let json: serde_json::Value = serde_json::from_slice(&build_bom_content(&bom, BomFormat::Json).unwrap()).unwrap();
+        assert_eq!(json["items"][1]["phase_quantities"]["top_1"], 2);
+
+        // and
+        let xlsx = build_bom_content(&bom, BomFormat::Xlsx).unwrap();
+        assert!(xlsx.starts_with(b"PK"));
+    }
+
+    #[test]
+    pub fn filter_by_pcb() {
+        // given
+        let resistor = Part::new("RES_MFR1".to_string(), "RES1".to_string());
+        let display = Part::new("LCD_MFR1".to_string(), "LCD1".to_string());
+
+        // and
+        let project = ProjectBuilder::new()
+            .with_pcb(PcbKind::Panel, "main_board")
+            .with_pcb(PcbKind::Panel, "display_board")
+            .with_placements([
+                PlacementStateBuilder::new(1, "R1", &resistor),
+                PlacementStateBuilder::new(1, "R1", &resistor).with_unit_path("panel=2::unit=1"),
+                PlacementStateBuilder::new(1, "LCD1", &display).with_unit_path("panel=2::unit=1"),
+            ])
+            .build();
+
+        // when
+        let display_board = project.find_pcb_by_name("display_board").unwrap().clone();
+        let bom = build_bom(&project, Some(&display_board));
+
+        // then
+        assert_eq!(bom.units, vec!["panel=2::unit=1".to_string()]);
+        assert_eq!(bom.items.iter().map(|item| (item.mpn.as_str(), item.quantity)).collect::<Vec<_>>(), vec![("LCD1", 1), ("RES1", 1)]);
+
+        // and
+        assert_eq!(build_bom_file_name("job1", Some(&display_board), BomFormat::Csv), "job1_display_board_bom.csv");
+    }
+}
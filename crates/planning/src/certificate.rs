@@ -0,0 +1,352 @@
+//! A completion certificate for a phase, to hand to quality or the customer.
+//!
+//! The certificate is generated when a phase is completed, as JSON and as printable Markdown, and summarizes the
+//! operations, operators, timestamps, quantities and any outstanding issues of the phase.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use serde::Serialize;
+use thiserror::Error;
+use time::format_description::well_known::Rfc3339;
+use time::serde::rfc3339;
+use time::OffsetDateTime;
+use pnp::part::Part;
+use pnp::pcb::PcbSide;
+use util::sorting::natural_cmp;
+use crate::operation_history::OperationHistoryItem;
+use crate::placement::PlacementStatus;
+use crate::process::{ProcessOperationKind, ProcessOperationStatus};
+use crate::project::Project;
+use crate::reference::Reference;
+
+/// Key of the operator in the `extra` fields of an operation history item.
+pub const OPERATOR_HISTORY_KEY: &str = "operator";
+
+#[derive(Error, Debug)]
+pub enum CertificateError {
+    #[error("Unknown phase. phase: '{0:}'")]
+    UnknownPhase(Reference),
+
+    #[error("Phase is not complete. phase: '{0:}'")]
+    PhaseIncomplete(Reference),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PhaseCertificate {
+    pub project: String,
+    pub phase: String,
+    pub process: String,
+    pub pcb_side: PcbSide,
+
+    #[serde(with = "rfc3339")]
+    pub issued_at: OffsetDateTime,
+    pub issued_by: Option<String>,
+    /// The operators recorded in the operation history of the phase.
+    pub operators: Vec<String>,
+
+    /// When the first operation history item of the phase was recorded.
+    #[serde(with = "rfc3339::option")]
+    pub started_at: Option<OffsetDateTime>,
+    /// When the last operation of the phase was completed.
+    #[serde(with = "rfc3339::option")]
+    pub completed_at: Option<OffsetDateTime>,
+
+    pub operations: Vec<CertificateOperation>,
+    pub units: Vec<String>,
+    pub placed: usize,
+    pub total: usize,
+    pub parts: Vec<CertificatePart>,
+    pub outstanding_issues: Vec<CertificateIssue>,
+
+    /// The hash of the last operation history item, so that the history the certificate was issued for can be verified.
+    pub operation_history_head: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CertificateOperation {
+    pub operation: ProcessOperationKind,
+    #[serde(with = "rfc3339::option")]
+    pub completed_at: Option<OffsetDateTime>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CertificatePart {
+    pub manufacturer: String,
+    pub mpn: String,
+    pub quantity: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CertificateIssue {
+    pub object_path: String,
+    pub message: String,
+}
+
+pub fn build_certificate_json_file_name(reference: &Reference) -> String {
+    format!("{}_certificate.json", reference)
+}
+
+pub fn build_certificate_markdown_file_name(reference: &Reference) -> String {
+    format!("{}_certificate.md", reference)
+}
+
+pub fn is_phase_complete(project: &Project, reference: &Reference) -> bool {
+    project.phase_states.get(reference)
+        .is_some_and(|phase_state| phase_state.operation_state.values()
+            .all(|operation_state| operation_state.status.eq(&ProcessOperationStatus::Complete)))
+}
+
+/// Builds the certificate for a completed phase, from the project and the operation history of the phase.
+pub fn build_phase_certificate(project: &Project, reference: &Reference, operation_history: &[OperationHistoryItem], issued_by: Option<String>, now: OffsetDateTime) -> Result<PhaseCertificate, CertificateError> {
+    let phase = project.phases.get(reference)
+        .ok_or(CertificateError::UnknownPhase(reference.clone()))?;
+    let phase_state = project.phase_states.get(reference)
+        .ok_or(CertificateError::UnknownPhase(reference.clone()))?;
+
+    if !is_phase_complete(project, reference) {
+        return Err(CertificateError::PhaseIncomplete(reference.clone()))
+    }
+
+    let phase_history: Vec<&OperationHistoryItem> = operation_history.iter()
+        .filter(|item| item.phase.eq(reference))
+        .collect();
+
+    // the last completion is used, operations can be reopened and completed again
+    let operations: Vec<CertificateOperation> = phase_state.operation_state.keys().map(|operation| {
+        let completed_at = phase_history.iter().rev()
+            .find(|item| item.operation.completed_operation().is_some_and(|completed| completed.eq(operation)))
+            .map(|item| item.date_time);
+
+        CertificateOperation { operation: operation.clone(), completed_at }
+    }).collect();
+
+    let operators: BTreeSet<String> = phase_history.iter()
+        .filter_map(|item| item.extra.get(OPERATOR_HISTORY_KEY))
+        .filter_map(|operator| operator.as_str())
+        .map(str::to_string)
+        .collect();
+
+    let mut units: BTreeSet<String> = BTreeSet::new();
+    let mut parts: BTreeMap<&Part, u32> = BTreeMap::new();
+    let mut outstanding_issues = vec![];
+    let mut placed = 0;
+    let mut total = 0;
+
+    let placement_states = project.placements.iter()
+        .filter(|(_object_path, placement_state)| {
+            placement_state.phase.as_ref().is_some_and(|phase| phase.eq(reference))
+                && placement_state.place()
+                && placement_state.status == PlacementStatus::Known
+        });
+
+    for (object_path, placement_state) in placement_states {
+        units.insert(placement_state.unit_path.to_string());
+        total += 1;
+
+        if placement_state.placed {
+            placed += 1;
+            *parts.entry(&placement_state.placement.part).or_default() += 1;
+        } else {
+            outstanding_issues.push(CertificateIssue { object_path: object_path.to_string(), message: "Not placed".to_string() });
+        }
+
+        if placement_state.has_open_defect() {
+            outstanding_issues.push(CertificateIssue { object_path: object_path.to_string(), message: "Open inspection defect".to_string() });
+        }
+    }
+
+    let mut units: Vec<String> = units.into_iter().collect();
+    units.sort_by(|a, b| natural_cmp(a, b));
+
+    Ok(PhaseCertificate {
+        project: project.name.clone(),
+        phase: reference.to_string(),
+        process: phase.process.to_string(),
+        pcb_side: phase.pcb_side.clone(),
+        issued_at: now,
+        issued_by,
+        operators: operators.into_iter().collect(),
+        started_at: phase_history.first().map(|item| item.date_time),
+        completed_at: operations.iter().filter_map(|operation| operation.completed_at).max(),
+        operations,
+        units,
+        placed,
+        total,
+        parts: parts.into_iter().map(|(part, quantity)| CertificatePart {
+            manufacturer: part.manufacturer.clone(),
+            mpn: part.mpn.clone(),
+            quantity,
+        }).collect(),
+        outstanding_issues,
+        operation_history_head: phase_history.last().and_then(|item| item.hash.clone()),
+    })
+}
+
+pub fn serialize_certificate(certificate: &PhaseCertificate) -> Result<Vec<u8>, serde_json::Error> {
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
+    let mut ser = serde_json::Serializer::with_formatter(vec![], formatter);
+    certificate.serialize(&mut ser)?;
+
+    let mut content = ser.into_inner();
+    content.push(b'\n');
+
+    Ok(content)
+}
+
+/// A printable version of the certificate, Markdown can be converted to PDF with any Markdown tool.
+pub fn build_certificate_markdown(certificate: &PhaseCertificate) -> String {
+    let format_date_time = |date_time: Option<OffsetDateTime>| date_time
+        .and_then(|date_time| date_time.format(&Rfc3339).ok())
+        .unwrap_or_else(|| "-".to_string());
+
+    // writing to a String cannot fail
+    let mut markdown = String::new();
+    let _ = writeln!(markdown, "# Phase completion certificate");
+    let _ = writeln!(markdown);
+    let _ = writeln!(markdown, "| | |");
+    let _ = writeln!(markdown, "|---|---|");
+    let _ = writeln!(markdown, "| Project | {} |", certificate.project);
+    let _ = writeln!(markdown, "| Phase | {} |", certificate.phase);
+    let _ = writeln!(markdown, "| Process | {} |", certificate.process);
+    let _ = writeln!(markdown, "| PCB side | {:?} |", certificate.pcb_side);
+    let _ = writeln!(markdown, "| Units | {} |", certificate.units.join(", "));
+    let _ = writeln!(markdown, "| Placements | {}/{} |", certificate.placed, certificate.total);
+    let _ = writeln!(markdown, "| Started | {} |", format_date_time(certificate.started_at));
+    let _ = writeln!(markdown, "| Completed | {} |", format_date_time(certificate.completed_at));
+    let _ = writeln!(markdown, "| Operators | {} |", if certificate.operators.is_empty() { "-".to_string() } else { certificate.operators.join(", ") });
+    let _ = writeln!(markdown, "| Issued | {} |", format_date_time(Some(certificate.issued_at)));
+    let _ = writeln!(markdown, "| Issued by | {} |", certificate.issued_by.as_deref().unwrap_or("-"));
+    let _ = writeln!(markdown, "| Operation history head | {} |", certificate.operation_history_head.as_deref().unwrap_or("-"));
+
+    let _ = writeln!(markdown);
+    let _ = writeln!(markdown, "## Operations");
+    let _ = writeln!(markdown);
+    let _ = writeln!(markdown, "| Operation | Completed |");
+    let _ = writeln!(markdown, "|---|---|");
+    for operation in certificate.operations.iter() {
+        let _ = writeln!(markdown, "| {:?} | {} |", operation.operation, format_date_time(operation.completed_at));
+    }
+
+    let _ = writeln!(markdown);
+    let _ = writeln!(markdown, "## Parts");
+    let _ = writeln!(markdown);
+    let _ = writeln!(markdown, "| Manufacturer | Mpn | Quantity |");
+    let _ = writeln!(markdown, "|---|---|---|");
+    for part in certificate.parts.iter() {
+        let _ = writeln!(markdown, "| {} | {} | {} |", part.manufacturer, part.mpn, part.quantity);
+    }
+
+    let _ = writeln!(markdown);
+    let _ = writeln!(markdown, "## Outstanding issues");
+    let _ = writeln!(markdown);
+    if certificate.outstanding_issues.is_empty() {
+        let _ = writeln!(markdown, "None");
+    } else {
+        let _ = writeln!(markdown, "| Placement | Issue |");
+        let _ = writeln!(markdown, "|---|---|");
+        for issue in certificate.outstanding_issues.iter() {
+            let _ = writeln!(markdown, "| {} | {} |", issue.object_path, issue.message);
+        }
+    }
+
+    markdown
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use rust_decimal_macros::dec;
+    use serde_json::Value;
+    use time::{Duration, OffsetDateTime};
+    use pnp::part::Part;
+    use pnp::pcb::{PcbKind, PcbSide};
+    use crate::certificate::{build_certificate_markdown, build_phase_certificate, CertificateError, CertificateIssue, CertificatePart, OPERATOR_HISTORY_KEY};
+    use crate::operation_history::{OperationHistoryItem, OperationHistoryKind};
+    use crate::process::ProcessOperationStatus;
+    use crate::project::Project;
+    use crate::reference::Reference;
+    use crate::test::project_builder::{PlacementStateBuilder, ProjectBuilder};
+
+    fn build_project(placed: bool) -> Project {
+        let conn1 = Part::new("CONN_MFR1".to_string(), "CONN1".to_string());
+
+        let mut project = ProjectBuilder::new()
+            .with_pcb(PcbKind::Panel, "panel_a")
+            .with_phase("bottom_1", "manual", "load_out_1.csv", PcbSide::Bottom)
+            .with_placements([2, 10].map(|unit| PlacementStateBuilder::new(unit, "J1", &conn1)
+                .with_pcb_side(PcbSide::Bottom)
+                .with_position(dec!(10), dec!(20), dec!(0))
+                .with_placed(placed)
+                .with_phase("bottom_1")
+            ))
+            .build();
+
+        let reference = Reference::from_str("bottom_1").unwrap();
+        for operation_state in project.phase_states.get_mut(&reference).unwrap().operation_state.values_mut() {
+            operation_state.status = ProcessOperationStatus::Complete;
+        }
+
+        project
+    }
+
+    #[test]
+    pub fn build_certificate() {
+        // given
+        let project = build_project(true);
+        let reference = Reference::from_str("bottom_1").unwrap();
+        let now = OffsetDateTime::UNIX_EPOCH;
+
+        // and
+        let mut load_pcbs = OperationHistoryItem::new(now, reference.clone(), OperationHistoryKind::LoadPcbs { status: ProcessOperationStatus::Complete });
+        load_pcbs.extra.insert(OPERATOR_HISTORY_KEY.to_string(), Value::String("Operator 1".to_string()));
+        let solder = OperationHistoryItem::new(now + Duration::hours(1), reference.clone(), OperationHistoryKind::ManuallySolderComponents { status: ProcessOperationStatus::Complete });
+
+        // when
+        let certificate = build_phase_certificate(&project, &reference, &[load_pcbs, solder], Some("Inspector 1".to_string()), now + Duration::hours(2)).unwrap();
+
+        // then
+        assert_eq!(certificate.operators, vec!["Operator 1".to_string()]);
+        assert_eq!(certificate.started_at, Some(now));
+        assert_eq!(certificate.completed_at, Some(now + Duration::hours(1)));
+        assert_eq!(certificate.units, vec!["panel=1::unit=2".to_string(), "panel=1::unit=10".to_string()]);
+        assert_eq!((certificate.placed, certificate.total), (2, 2));
+        assert_eq!(certificate.parts, vec![CertificatePart { manufacturer: "CONN_MFR1".to_string(), mpn: "CONN1".to_string(), quantity: 2 }]);
+        assert!(certificate.outstanding_issues.is_empty());
+
+        // and
+        let markdown = build_certificate_markdown(&certificate);
+        assert!(markdown.contains("| Placements | 2/2 |"));
+        assert!(markdown.contains("| Issued by | Inspector 1 |"));
+        assert!(markdown.contains("| CONN_MFR1 | CONN1 | 2 |"));
+    }
+
+    #[test]
+    pub fn unplaced_placements_are_outstanding_issues() {
+        // given
+        let project = build_project(false);
+        let reference = Reference::from_str("bottom_1").unwrap();
+
+        // when
+        let certificate = build_phase_certificate(&project, &reference, &[], None, OffsetDateTime::UNIX_EPOCH).unwrap();
+
+        // then
+        assert_eq!(certificate.outstanding_issues[0], CertificateIssue { object_path: "panel=1::unit=2::ref_des=J1".to_string(), message: "Not placed".to_string() });
+        assert_eq!(certificate.outstanding_issues.len(), 2);
+    }
+
+    #[test]
+    pub fn incomplete_phase() {
+        // given
+        let mut project = build_project(true);
+        let reference = Reference::from_str("bottom_1").unwrap();
+        for operation_state in project.phase_states.get_mut(&reference).unwrap().operation_state.values_mut() {
+            operation_state.status = ProcessOperationStatus::Pending;
+        }
+
+        // when
+        let result = build_phase_certificate(&project, &reference, &[], None, OffsetDateTime::UNIX_EPOCH);
+
+        // then
+        assert!(matches!(result, Err(CertificateError::PhaseIncomplete(_))));
+    }
+}
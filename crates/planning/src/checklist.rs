@@ -0,0 +1,192 @@
+//! Checklists of the tools and consumables required by process operations, e.g. 'stencil=STN-001', 'paste=SAC305'.
+//!
+//! Each item of the checklist must be confirmed when an operation of a phase is started, the confirmations are
+//! recorded in the operation history of the phase, for audits.
+
+use std::path::Path;
+use thiserror::Error;
+use time::OffsetDateTime;
+use tracing::info;
+use crate::operation_history;
+use crate::operation_history::{OperationHistoryItem, OperationHistoryKind};
+use crate::phase::PhaseError;
+use crate::process::{ProcessError, ProcessName, ProcessOperationKind};
+use crate::project::Project;
+use crate::reference::Reference;
+use crate::mutation::MUTATION_TARGET;
+
+#[derive(Error, Debug)]
+pub enum ChecklistError {
+    #[error("Invalid operation for process. process: '{process}', operation: {operation:?}")]
+    InvalidOperationForProcess { process: ProcessName, operation: ProcessOperationKind },
+
+    #[error("Checklist not confirmed. phase: '{phase}', operation: {operation:?}, unconfirmed: {items:?}")]
+    NotConfirmed { phase: Reference, operation: ProcessOperationKind, items: Vec<String> },
+
+    #[error("Unknown checklist item. operation: {operation:?}, item: '{item}', checklist: {checklist:?}")]
+    UnknownItem { operation: ProcessOperationKind, item: String, checklist: Vec<String> },
+}
+
+/// Replaces the checklist of an operation of a process, an empty list of items removes the checklist.
+pub fn set_operation_checklist(project: &mut Project, process_name: &ProcessName, operation: ProcessOperationKind, items: Vec<String>) -> anyhow::Result<bool> {
+    let processes = project.processes.clone();
+    let process = project.processes.iter_mut()
+        .find(|process| process.name.eq(process_name))
+        .ok_or(ProcessError::UnusedProcessError { processes, process: process_name.to_string() })?;
+
+    if !process.has_operation(&operation) {
+        return Err(ChecklistError::InvalidOperationForProcess { process: process_name.clone(), operation }.into())
+    }
+
+    let mut items = items;
+    items.dedup();
+
+    let existing_items = process.checklists.get(&operation).cloned().unwrap_or_default();
+    if existing_items.eq(&items) {
+        info!("Operation checklist unchanged. process: '{}', operation: {:?}", process_name, operation);
+        return Ok(false)
+    }
+
+    info!(target: MUTATION_TARGET, process = %process_name, operation = ?operation, old = ?existing_items, new = ?items, message = "Operation checklist updated. process: '{process}', operation: {operation}, old: {old}, new: {new}");
+
+    match items.is_empty() {
+        true => process.checklists.remove(&operation),
+        false => process.checklists.insert(operation, items),
+    };
+
+    Ok(true)
+}
+
+/// Returns the checklist of the operation of the phase, empty if the operation has no checklist.
+pub fn find_operation_checklist(project: &Project, phase_reference: &Reference, operation: &ProcessOperationKind) -> Result<Vec<String>, PhaseError> {
+    let phase = project.phases.get(phase_reference)
+        .ok_or(PhaseError::UnknownPhase(phase_reference.clone()))?;
+
+    let process = project.processes.iter()
+        .find(|process| process.name.eq(&phase.process))
+        .filter(|process| process.has_operation(operation))
+        .ok_or(PhaseError::InvalidOperationForPhase(phase_reference.clone(), operation.clone()))?;
+
+    Ok(process.checklists.get(operation).cloned().unwrap_or_default())
+}
+
+/// Records the start of an operation of a phase, all the items of the checklist of the operation must be confirmed.
+pub fn record_operation_started(project: &Project, path: &Path, phase_reference: &Reference, operation: ProcessOperationKind, confirmed: Vec<String>, operator: Option<String>, now: OffsetDateTime) -> anyhow::Result<()> {
+    let checklist = find_operation_checklist(project, phase_reference, &operation)?;
+
+    if let Some(item) = confirmed.iter().find(|item| !checklist.contains(item)) {
+        return Err(ChecklistError::UnknownItem { operation, item: item.clone(), checklist }.into())
+    }
+
+    let unconfirmed: Vec<String> = checklist.iter()
+        .filter(|item| !confirmed.contains(item))
+        .cloned()
+        .collect();
+
+    if !unconfirmed.is_empty() {
+        return Err(ChecklistError::NotConfirmed { phase: phase_reference.clone(), operation, items: unconfirmed }.into())
+    }
+
+    info!(target: MUTATION_TARGET, phase = %phase_reference, operation = ?operation, confirmed = ?checklist, message = "Recorded operation started. phase: '{phase}', operation: {operation}, confirmed: {confirmed}");
+
+    let phase_log_path = path.join(format!("{}_log.json", phase_reference));
+
+    let mut operation_history: Vec<OperationHistoryItem> = operation_history::read_or_default(&phase_log_path)?;
+
+    // the items are recorded in the order of the checklist
+    operation_history::append(&mut operation_history, vec![
+        OperationHistoryItem::new(now, phase_reference.clone(), OperationHistoryKind::OperationStarted { operation, confirmed: checklist, operator }),
+    ]);
+
+    operation_history::write(phase_log_path, &operation_history)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use tempfile::tempdir;
+    use time::OffsetDateTime;
+    use pnp::pcb::PcbSide;
+    use crate::checklist::{find_operation_checklist, record_operation_started, set_operation_checklist, ChecklistError};
+    use crate::operation_history;
+    use crate::operation_history::OperationHistoryKind;
+    use crate::process::{ProcessName, ProcessOperationKind};
+    use crate::project::Project;
+    use crate::reference::Reference;
+    use crate::test::project_builder::ProjectBuilder;
+
+    fn build_project() -> Project {
+        let mut project = ProjectBuilder::new()
+            .with_phase("top_1", "pnp", "load_out_1.csv", PcbSide::Top)
+            .build();
+
+        set_operation_checklist(&mut project, &ProcessName::from_str("pnp").unwrap(), ProcessOperationKind::ReflowComponents, vec![
+            "stencil=STN-001".to_string(),
+            "paste=SAC305".to_string(),
+        ]).unwrap();
+
+        project
+    }
+
+    #[test]
+    pub fn set_checklist() {
+        // given
+        let mut project = build_project();
+        let reference = Reference::from_str("top_1").unwrap();
+
+        // expect
+        assert_eq!(find_operation_checklist(&project, &reference, &ProcessOperationKind::ReflowComponents).unwrap(), vec!["stencil=STN-001", "paste=SAC305"]);
+        assert!(find_operation_checklist(&project, &reference, &ProcessOperationKind::LoadPcbs).unwrap().is_empty());
+
+        // when
+        let modified = set_operation_checklist(&mut project, &ProcessName::from_str("pnp").unwrap(), ProcessOperationKind::ReflowComponents, vec![]).unwrap();
+
+        // then
+        assert!(modified);
+        assert!(project.processes[0].checklists.is_empty());
+
+        // and
+        let result = set_operation_checklist(&mut project, &ProcessName::from_str("pnp").unwrap(), ProcessOperationKind::ManuallySolderComponents, vec!["iron".to_string()]);
+        assert!(matches!(result.unwrap_err().downcast::<ChecklistError>().unwrap(), ChecklistError::InvalidOperationForProcess { .. }));
+    }
+
+    #[test]
+    pub fn start_requires_all_items_to_be_confirmed() -> anyhow::Result<()> {
+        // given
+        let temp_dir = tempdir()?;
+        let project = build_project();
+        let reference = Reference::from_str("top_1").unwrap();
+
+        // when
+        let result = record_operation_started(&project, temp_dir.path(), &reference, ProcessOperationKind::ReflowComponents, vec!["paste=SAC305".to_string()], None, OffsetDateTime::UNIX_EPOCH);
+
+        // then
+        match result.unwrap_err().downcast::<ChecklistError>()? {
+            ChecklistError::NotConfirmed { items, .. } => assert_eq!(items, vec!["stencil=STN-001"]),
+            error => panic!("unexpected error: {:?}", error),
+        }
+        assert!(!temp_dir.path().join("top_1_log.json").exists());
+
+        // when
+        let result = record_operation_started(&project, temp_dir.path(), &reference, ProcessOperationKind::ReflowComponents, vec!["stencil=STN-002".to_string()], None, OffsetDateTime::UNIX_EPOCH);
+
+        // then
+        assert!(matches!(result.unwrap_err().downcast::<ChecklistError>()?, ChecklistError::UnknownItem { .. }));
+
+        // when
+        record_operation_started(&project, temp_dir.path(), &reference, ProcessOperationKind::ReflowComponents, vec!["paste=SAC305".to_string(), "stencil=STN-001".to_string()], Some("Operator 1".to_string()), OffsetDateTime::UNIX_EPOCH)?;
+
+        // then
+        let operation_history = operation_history::read_or_default(&temp_dir.path().join("top_1_log.json"))?;
+        assert_eq!(operation_history.len(), 1);
+        assert_eq!(operation_history[0].operation, OperationHistoryKind::OperationStarted {
+            operation: ProcessOperationKind::ReflowComponents,
+            confirmed: vec!["stencil=STN-001".to_string(), "paste=SAC305".to_string()],
+            operator: Some("Operator 1".to_string()),
+        });
+
+        Ok(())
+    }
+}
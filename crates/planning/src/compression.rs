@@ -0,0 +1,88 @@
+//! Optional zstd compression of project files.
+//!
+//! Project files of very large panels can grow to tens of MB, a compressed project file uses the
+//! `.mpnp.json.zst` extension instead of `.mpnp.json`.  Plain JSON is the default.
+//!
+//! Compressed content is detected using the zstd magic number, not the extension, when loading.
+//!
+//! Requires the 'zstd' feature.
+
+use std::path::Path;
+use anyhow::Error;
+
+pub const COMPRESSED_EXTENSION: &str = "zst";
+
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
+
+#[cfg(feature = "zstd")]
+const COMPRESSION_LEVEL: i32 = 3;
+
+/// Returns true if the project file should be compressed when saving, based on the extension.
+pub fn is_compressed_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| extension.eq_ignore_ascii_case(COMPRESSED_EXTENSION))
+}
+
+pub fn is_compressed(content: &[u8]) -> bool {
+    content.starts_with(&ZSTD_MAGIC)
+}
+
+/// Returns the content unchanged, unless it is compressed.
+pub fn decompress(content: Vec<u8>) -> Result<Vec<u8>, Error> {
+    if !is_compressed(&content) {
+        return Ok(content)
+    }
+
+    decompress_zstd(&content)
+}
+
+#[cfg(feature = "zstd")]
+pub fn compress(content: &[u8]) -> Result<Vec<u8>, Error> {
+    Ok(zstd::encode_all(content, COMPRESSION_LEVEL)?)
+}
+
+#[cfg(not(feature = "zstd"))]
+pub fn compress(_content: &[u8]) -> Result<Vec<u8>, Error> {
+    anyhow::bail!("Writing compressed project files requires the 'zstd' feature")
+}
+
+#[cfg(feature = "zstd")]
+fn decompress_zstd(content: &[u8]) -> Result<Vec<u8>, Error> {
+    Ok(zstd::decode_all(content)?)
+}
+
+#[cfg(not(feature = "zstd"))]
+fn decompress_zstd(_content: &[u8]) -> Result<Vec<u8>, Error> {
+    anyhow::bail!("Reading compressed project files requires the 'zstd' feature, decompress the file or rebuild with the feature enabled")
+}
+
+#[cfg(all(test, feature = "zstd"))]
+mod tests {
+    use std::path::PathBuf;
+    use crate::compression::{compress, decompress, is_compressed, is_compressed_path};
+
+    #[test]
+    pub fn round_trip() -> anyhow::Result<()> {
+        // given
+        let content = b"{\n    \"name\": \"job1\"\n}\n".to_vec();
+
+        // when
+        let compressed = compress(&content)?;
+
+        // then
+        assert!(is_compressed(&compressed));
+        assert_eq!(decompress(compressed)?, content);
+
+        // and plain content is unchanged
+        assert_eq!(decompress(content.clone())?, content);
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn compressed_path() {
+        assert!(is_compressed_path(&PathBuf::from("project-job1.mpnp.json.zst")));
+        assert!(!is_compressed_path(&PathBuf::from("project-job1.mpnp.json")));
+    }
+}
@@ -1,5 +1,6 @@
 use std::fmt::{Display, Formatter};
 use std::str::FromStr;
+use sha2::{Digest, Sha256};
 use thiserror::Error;
 use crate::variant::VariantName;
 
@@ -35,3 +36,14 @@ impl Display for DesignName {
 #[derive(Debug, Error)]
 #[error("Design name error")]
 pub struct DesignNameError;
+
+#[derive(Debug, Error)]
+pub enum DesignVariantError {
+    #[error("Unregistered variant. design_variant: {design_variant}, registered variants: {registered_variants:?}")]
+    UnregisteredVariant { design_variant: DesignVariant, registered_variants: Vec<String> },
+}
+
+/// The revision of a design variant, the hex encoded SHA-256 hash of its placements file.
+pub fn build_design_revision(content: &[u8]) -> String {
+    hex::encode(Sha256::digest(content))
+}
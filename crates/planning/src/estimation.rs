@@ -0,0 +1,270 @@
+//! Duration estimates of each phase, using the estimation parameters of the project.
+//!
+//! The estimate of a phase is the time to set up its feeders, one feeder for each part, plus the time to place its
+//! placements at the placement rate of its process, plus the reflow time, if its process reflows the components.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use serde_with::serde_as;
+use thiserror::Error;
+use pnp::part::Part;
+use crate::phase::Phase;
+use crate::process::{ProcessName, ProcessOperationKind, ProcessOperationStatus};
+use crate::project::{find_phase_placement_states, Project};
+use crate::reference::Reference;
+
+#[serde_as]
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct EstimationParameters {
+    /// Placements per hour, by process, the placement time of phases whose process has no rate is not estimated.
+    #[serde_as(as = "Vec<(_, _)>")]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    #[serde(default)]
+    pub placement_rates: BTreeMap<ProcessName, u32>,
+    /// Seconds, for each feeder of a phase.
+    #[serde(default)]
+    pub feeder_setup_time: u32,
+    /// Seconds, for each phase whose process reflows the components.
+    #[serde(default)]
+    pub reflow_time: u32,
+}
+
+/// A placement rate, e.g. 'pnp=1200' for 1200 placements per hour.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlacementRate {
+    pub process: ProcessName,
+    pub placements_per_hour: u32,
+}
+
+#[derive(Error, Debug)]
+#[error("Invalid placement rate, expected 'process=placements per hour'. value: '{0:}'")]
+pub struct PlacementRateError(String);
+
+impl FromStr for PlacementRate {
+    type Err = PlacementRateError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let error = || PlacementRateError(value.to_string());
+
+        let (process, rate) = value.split_once('=').ok_or_else(error)?;
+        let process = ProcessName::from_str(process.trim()).map_err(|_| error())?;
+        let placements_per_hour: u32 = rate.trim().parse().map_err(|_| error())?;
+        if process.0.is_empty() || placements_per_hour == 0 {
+            return Err(error())
+        }
+
+        Ok(PlacementRate { process, placements_per_hour })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize)]
+pub struct ProjectEstimate {
+    /// In the order of the phases.
+    pub phases: Vec<PhaseEstimate>,
+    /// Seconds, of all the phases.
+    pub total_time: u32,
+    /// Seconds, of all the phases.
+    pub remaining_time: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub struct PhaseEstimate {
+    pub phase: Reference,
+    pub feeders: usize,
+    /// Placements that are to be placed.
+    pub placements: usize,
+    pub placed: usize,
+    /// Seconds.
+    pub setup_time: u32,
+    /// Seconds, `None` if the process of the phase has no placement rate.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub placement_time: Option<u32>,
+    /// Seconds.
+    pub reflow_time: u32,
+    /// Seconds.
+    pub total_time: u32,
+    /// Seconds, the setup time is only included until the first placement is placed and the reflow time until the
+    /// reflow operation is complete.
+    pub remaining_time: u32,
+}
+
+/// `None` if the project has no estimation parameters.
+pub fn build_project_estimate(project: &Project) -> Option<ProjectEstimate> {
+    let parameters = project.estimation_parameters.as_ref()?;
+
+    let phases: Vec<PhaseEstimate> = project.phase_orderings.iter()
+        .filter_map(|reference| project.phases.get(reference))
+        .map(|phase| build_phase_estimate(project, parameters, phase))
+        .collect();
+
+    Some(ProjectEstimate {
+        total_time: phases.iter().map(|phase| phase.total_time).sum(),
+        remaining_time: phases.iter().map(|phase| phase.remaining_time).sum(),
+        phases,
+    })
+}
+
+/// `None` if the project has no estimation parameters.
+pub fn build_phase_estimate_for_phase(project: &Project, phase: &Phase) -> Option<PhaseEstimate> {
+    let parameters = project.estimation_parameters.as_ref()?;
+
+    Some(build_phase_estimate(project, parameters, phase))
+}
+
+fn build_phase_estimate(project: &Project, parameters: &EstimationParameters, phase: &Phase) -> PhaseEstimate {
+    let placement_states: Vec<_> = find_phase_placement_states(project, phase).into_iter()
+        .filter(|(_object_path, placement_state)| placement_state.place())
+        .collect();
+
+    let parts: BTreeSet<&Part> = placement_states.iter()
+        .map(|(_object_path, placement_state)| &placement_state.placement.part)
+        .collect();
+
+    let placements = placement_states.len();
+    let placed = placement_states.iter().filter(|(_object_path, placement_state)| placement_state.placed).count();
+
+    let setup_time = parameters.feeder_setup_time * parts.len() as u32;
+
+    let placement_time = |count: usize| parameters.placement_rates.get(&phase.process)
+        .map(|placements_per_hour| (count as u64 * 3600).div_ceil(*placements_per_hour as u64) as u32);
+
+    let reflow_status = project.phase_states.get(&phase.reference)
+        .and_then(|phase_state| phase_state.operation_state.get(&ProcessOperationKind::ReflowComponents))
+        .map(|operation_state| &operation_state.status);
+    let reflow_time = match reflow_status {
+        Some(_) => parameters.reflow_time,
+        None => 0,
+    };
+
+    let total_time = setup_time + placement_time(placements).unwrap_or_default() + reflow_time;
+
+    let remaining_setup_time = match placed {
+        0 => setup_time,
+        _ => 0,
+    };
+    let remaining_reflow_time = match reflow_status {
+        Some(ProcessOperationStatus::Complete) => 0,
+        _ => reflow_time,
+    };
+    let remaining_time = remaining_setup_time + placement_time(placements - placed).unwrap_or_default() + remaining_reflow_time;
+
+    PhaseEstimate {
+        phase: phase.reference.clone(),
+        feeders: parts.len(),
+        placements,
+        placed,
+        setup_time,
+        placement_time: placement_time(placements),
+        reflow_time,
+        total_time,
+        remaining_time,
+    }
+}
+
+/// e.g. '1h 2m 3s', '2m 0s' or '45s'.
+pub struct FormattedDuration(pub u32);
+
+impl Display for FormattedDuration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (hours, minutes, seconds) = (self.0 / 3600, self.0 % 3600 / 60, self.0 % 60);
+
+        match (hours, minutes) {
+            (0, 0) => write!(f, "{}s", seconds),
+            (0, _) => write!(f, "{}m {}s", minutes, seconds),
+            _ => write!(f, "{}h {}m {}s", hours, minutes, seconds),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::str::FromStr;
+    use pnp::part::Part;
+    use pnp::pcb::PcbSide;
+    use crate::estimation::{build_project_estimate, EstimationParameters, FormattedDuration, PhaseEstimate, PlacementRate};
+    use crate::process::{ProcessName, ProcessOperationKind, ProcessOperationStatus};
+    use crate::project::Project;
+    use crate::reference::Reference;
+    use crate::test::project_builder::{PlacementStateBuilder, ProjectBuilder};
+
+    fn build_project() -> Project {
+        let res1 = Part::new("RES_MFR1".to_string(), "RES1".to_string());
+        let res2 = Part::new("RES_MFR1".to_string(), "RES2".to_string());
+
+        ProjectBuilder::new()
+            .with_phase("top_1", "pnp", "load_out_top_1.csv", PcbSide::Top)
+            .with_phase("bottom_1", "manual", "load_out_bottom_1.csv", PcbSide::Top)
+            .with_placements([
+                ("R1", &res1, true, true, "top_1"),
+                ("R2", &res1, true, false, "top_1"),
+                ("R3", &res2, true, false, "top_1"),
+                ("R4", &res2, false, false, "top_1"),
+                ("J1", &res1, true, false, "bottom_1"),
+            ].map(|(ref_des, part, place, placed, phase)| PlacementStateBuilder::new(1, ref_des, part).with_place(place).with_placed(placed).with_phase(phase)))
+            .build()
+    }
+
+    #[test]
+    pub fn estimate_phases() {
+        // given
+        let mut project = build_project();
+
+        // expect
+        assert_eq!(build_project_estimate(&project), None);
+
+        // given
+        project.estimation_parameters = Some(EstimationParameters {
+            placement_rates: BTreeMap::from([(ProcessName::from_str("pnp").unwrap(), 1200)]),
+            feeder_setup_time: 60,
+            reflow_time: 600,
+        });
+
+        // when
+        let estimate = build_project_estimate(&project).unwrap();
+
+        // then 3 placements at 3 seconds each, 2 parts and a reflow, the placement that is not placed is ignored
+        assert_eq!(estimate.phases[0], PhaseEstimate {
+            phase: Reference::from_str("top_1").unwrap(),
+            feeders: 2,
+            placements: 3,
+            placed: 1,
+            setup_time: 120,
+            placement_time: Some(9),
+            reflow_time: 600,
+            total_time: 729,
+            remaining_time: 606,
+        });
+
+        // and the manual process has no placement rate, or reflow
+        assert_eq!(estimate.phases[1].placement_time, None);
+        assert_eq!((estimate.phases[1].reflow_time, estimate.phases[1].total_time), (0, 60));
+
+        // and
+        assert_eq!((estimate.total_time, estimate.remaining_time), (789, 666));
+
+        // when the reflow is complete
+        project.phase_states.get_mut(&Reference::from_str("top_1").unwrap()).unwrap()
+            .operation_state.get_mut(&ProcessOperationKind::ReflowComponents).unwrap()
+            .status = ProcessOperationStatus::Complete;
+
+        // then
+        assert_eq!(build_project_estimate(&project).unwrap().phases[0].remaining_time, 6);
+    }
+
+    #[test]
+    pub fn parse_placement_rate() {
+        assert_eq!(PlacementRate::from_str("pnp=1200").unwrap(), PlacementRate { process: ProcessName::from_str("pnp").unwrap(), placements_per_hour: 1200 });
+        assert!(PlacementRate::from_str("pnp").is_err());
+        assert!(PlacementRate::from_str("pnp=0").is_err());
+        assert!(PlacementRate::from_str("=1200").is_err());
+    }
+
+    #[test]
+    pub fn format_duration() {
+        assert_eq!(FormattedDuration(45).to_string(), "45s");
+        assert_eq!(FormattedDuration(120).to_string(), "2m 0s");
+        assert_eq!(FormattedDuration(3723).to_string(), "1h 2m 3s");
+    }
+}
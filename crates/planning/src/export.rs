@@ -0,0 +1,348 @@
+//! Machine-native exports of the placements of a phase, so that the generated files can be loaded by a machine
+//! directly, without a converter.
+//!
+//! Each format has an exporter, see `PlacementsExporter`, the format of a phase is set when the phase is created, and
+//! the export is generated alongside the other phase artifacts.  Only the placements that are to be placed are
+//! exported, in the placement ordering of the phase, with the coordinates in panel space.
+//!
+//! Before the exports are generated the parts of the exported placements are checked against the load-out of the
+//! phase, see `check_feeders`, so that parts without a feeder are found before the export is loaded by the machine.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter};
+use anyhow::Error;
+use csv::QuoteStyle;
+use rust_decimal::Decimal;
+use thiserror::Error;
+use tracing::{info, warn};
+use pnp::load_out::LoadOutItem;
+use pnp::object_path::ObjectPath;
+use pnp::part::Part;
+use pnp::pcb::PcbSide;
+use crate::phase::{Phase, PhaseError};
+use crate::placement::PlacementState;
+use crate::project::{find_phase_placement_states, Project};
+use crate::reference::Reference;
+use crate::mutation::MUTATION_TARGET;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ExportFormat {
+    /// A generic centroid (pick-and-place) file, accepted by most machines and their import tools.
+    #[default]
+    Centroid,
+}
+
+impl ExportFormat {
+    pub fn exporter(&self) -> &'static dyn PlacementsExporter {
+        match self {
+            ExportFormat::Centroid => &CentroidExporter,
+        }
+    }
+}
+
+impl Display for ExportFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExportFormat::Centroid => write!(f, "Centroid"),
+        }
+    }
+}
+
+pub trait PlacementsExporter {
+    /// Appended to the phase reference to build the file name, e.g. 'centroid.csv'.
+    fn file_suffix(&self) -> &'static str;
+
+    /// The placement states must be in the placement ordering of the phase, and in panel space.
+    fn export(&self, project: &Project, phase: &Phase, placement_states: &[(&ObjectPath, &PlacementState)]) -> Result<Vec<u8>, Error>;
+}
+
+/// Phases without an export format use the name of the default format.
+pub fn build_export_file_name(phase: &Phase) -> String {
+    let export_format = phase.export_format.clone().unwrap_or_default();
+
+    format!("{}_{}", phase.reference, export_format.exporter().file_suffix())
+}
+
+/// Sets, or removes, the export format of a phase, returns true if modified.
+pub fn update_export_format(project: &mut Project, reference: &Reference, export_format: Option<ExportFormat>) -> Result<bool, PhaseError> {
+    let phase = project.phases.get_mut(reference)
+        .ok_or(PhaseError::UnknownPhase(reference.clone()))?;
+
+    if phase.export_format.eq(&export_format) {
+        return Ok(false)
+    }
+
+    info!(target: MUTATION_TARGET, phase = %reference, old = ?phase.export_format, new = ?export_format, message = "Phase export format set. phase: '{phase}', old: {old}, new: {new}");
+    phase.export_format = export_format;
+
+    Ok(true)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MissingFeeder {
+    pub phase: Reference,
+    pub part: Part,
+    /// Count of the exported placements of the part.
+    pub placements: usize,
+}
+
+impl Display for MissingFeeder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (phase: '{}', placements: {})", self.part, self.phase, self.placements)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum FeederCheckError {
+    #[error("Parts of the machine exports have not been assigned to a feeder, the artifacts were not generated. missing: [{}]", missing.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))]
+    MissingFeeders { missing: Vec<MissingFeeder> },
+}
+
+/// The parts, of the placements to be exported, that are not in the load-out of the phase, or that are in the load-out
+/// without a feeder reference, for each phase with an export format, in phase and part order.
+pub fn find_missing_feeders(project: &Project, phase_load_out_items_map: &BTreeMap<Reference, Vec<LoadOutItem>>) -> Vec<MissingFeeder> {
+    let mut missing = vec![];
+
+    for (reference, phase) in project.phases.iter().filter(|(_reference, phase)| phase.export_format.is_some()) {
+        let load_out_items = phase_load_out_items_map.get(reference).map(Vec::as_slice).unwrap_or_default();
+
+        let mut part_placements: BTreeMap<&Part, usize> = BTreeMap::new();
+        for (_object_path, placement_state) in find_phase_placement_states(project, phase) {
+            if !placement_state.place() {
+                continue
+            }
+            *part_placements.entry(&placement_state.placement.part).or_default() += 1;
+        }
+
+        for (part, placements) in part_placements {
+            let has_feeder = pnp::load_out::find_load_out_item_by_part(load_out_items, part)
+                .is_some_and(|load_out_item| !load_out_item.reference.is_empty());
+
+            if !has_feeder {
+                missing.push(MissingFeeder { phase: reference.clone(), part: part.clone(), placements });
+            }
+        }
+    }
+
+    missing
+}
+
+/// Checks that the parts of the machine exports have been assigned to a feeder, all the missing feeders are reported
+/// in a single error, unless `allow_missing` is true, in which case they are logged as warnings instead.
+pub fn check_feeders(project: &Project, phase_load_out_items_map: &BTreeMap<Reference, Vec<LoadOutItem>>, allow_missing: bool) -> Result<Vec<MissingFeeder>, FeederCheckError> {
+    let missing = find_missing_feeders(project, phase_load_out_items_map);
+
+    if missing.is_empty() {
+        return Ok(missing)
+    }
+
+    if !allow_missing {
+        return Err(FeederCheckError::MissingFeeders { missing })
+    }
+
+    for missing_feeder in missing.iter() {
+        warn!("Part of a machine export has not been assigned to a feeder. phase: '{}', part: {:?}, placements: {}", missing_feeder.phase, missing_feeder.part, missing_feeder.placements);
+    }
+
+    Ok(missing)
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all(serialize = "PascalCase"))]
+struct CentroidRecord {
+    designator: String,
+    layer: String,
+    #[serde(rename(serialize = "MidX"))]
+    mid_x: Decimal,
+    #[serde(rename(serialize = "MidY"))]
+    mid_y: Decimal,
+    rotation: Decimal,
+    manufacturer: String,
+    mpn: String,
+    package: String,
+}
+
+/// A generic centroid file, in millimeters, with the columns `Designator`, `Layer` ('Top' or 'Bottom'), `MidX`,
+/// `MidY`, `Rotation`, `Manufacturer`, `Mpn` and `Package`.
+///
+/// Machines require unique designators, so when the phase places more than one PCB unit the designators are suffixed
+/// with the position of the unit, in object path order, e.g. 'R1_2' for 'R1' of the second unit.
+pub struct CentroidExporter;
+
+impl PlacementsExporter for CentroidExporter {
+    fn file_suffix(&self) -> &'static str {
+        "centroid.csv"
+    }
+
+    fn export(&self, project: &Project, _phase: &Phase, placement_states: &[(&ObjectPath, &PlacementState)]) -> Result<Vec<u8>, Error> {
+        let placement_states: Vec<&(&ObjectPath, &PlacementState)> = placement_states.iter()
+            .filter(|(_object_path, placement_state)| placement_state.place())
+            .collect();
+
+        let pcb_units: Vec<ObjectPath> = placement_states.iter()
+            .map(|(object_path, _placement_state)| object_path.pcb_unit())
+            .collect::<BTreeSet<ObjectPath>>()
+            .into_iter()
+            .collect();
+
+        let mut writer = csv::WriterBuilder::new()
+            .quote_style(QuoteStyle::Always)
+            .from_writer(vec![]);
+
+        for (object_path, placement_state) in placement_states {
+            let placement = &placement_state.placement;
+
+            let designator = match pcb_units.len() {
+                1 => placement.ref_des.clone(),
+                _ => {
+                    let position = pcb_units.iter().position(|pcb_unit| pcb_unit.eq(&object_path.pcb_unit())).unwrap() + 1;
+                    format!("{}_{}", placement.ref_des, position)
+                },
+            };
+
+            let layer = match placement.pcb_side {
+                PcbSide::Top => "Top",
+                PcbSide::Bottom => "Bottom",
+            };
+
+            let package = project.part_states.get(&placement.part)
+                .and_then(|part_state| part_state.details.package.clone())
+                .unwrap_or_default();
+
+            writer.serialize(CentroidRecord {
+                designator,
+                layer: layer.to_string(),
+                mid_x: placement.x,
+                mid_y: placement.y,
+                rotation: placement.rotation,
+                manufacturer: placement.part.manufacturer.clone(),
+                mpn: placement.part.mpn.clone(),
+                package,
+            })?;
+        }
+
+        Ok(writer.into_inner()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use std::collections::BTreeMap;
+    use indoc::indoc;
+    use rust_decimal_macros::dec;
+    use pnp::load_out::LoadOutItem;
+    use pnp::part::{Part, PartDetails};
+    use pnp::pcb::PcbSide;
+    use crate::export::{build_export_file_name, check_feeders, update_export_format, ExportFormat, FeederCheckError, MissingFeeder};
+    use crate::part::PartState;
+    use crate::project::{find_phase_placement_states, Project};
+    use crate::reference::Reference;
+    use crate::test::project_builder::{PlacementStateBuilder, ProjectBuilder};
+
+    fn build_project(units: &[usize]) -> Project {
+        let res1 = Part::new("RES_MFR1".to_string(), "RES1".to_string());
+        let conn1 = Part::new("CONN_MFR1".to_string(), "CONN1".to_string());
+
+        let mut project = ProjectBuilder::new()
+            .with_phase("top_1", "pnp", "load_out_1.csv", PcbSide::Top)
+            .with_placements(units.iter().flat_map(|unit| [("R1", &res1, true), ("J1", &conn1, false)]
+                .map(|(ref_des, part, place)| PlacementStateBuilder::new(*unit, ref_des, part)
+                    .with_place(place)
+                    .with_position(dec!(10.5), dec!(20), dec!(90))
+                    .with_phase("top_1")
+                )
+            ))
+            .build();
+
+        project.part_states.insert(res1.clone(), PartState {
+            details: PartDetails { package: Some("0402".to_string()), ..PartDetails::default() },
+            ..PartState::default()
+        });
+
+        project
+    }
+
+    #[test]
+    pub fn centroid() -> anyhow::Result<()> {
+        // given
+        let mut project = build_project(&[1]);
+        let reference = Reference::from_str("top_1")?;
+        update_export_format(&mut project, &reference, Some(ExportFormat::Centroid))?;
+        let phase = project.phases.get(&reference).unwrap();
+        let placement_states = find_phase_placement_states(&project, phase);
+
+        // when
+        let content = ExportFormat::Centroid.exporter().export(&project, phase, &placement_states)?;
+
+        // then placements that are not to be placed are not exported
+        assert_eq!(String::from_utf8(content)?, indoc! {r#"
+            "Designator","Layer","MidX","MidY","Rotation","Manufacturer","Mpn","Package"
+            "R1","Top","10.5","20","90","RES_MFR1","RES1","0402"
+        "#});
+
+        // and
+        assert_eq!(build_export_file_name(phase), "top_1_centroid.csv");
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn centroid_with_multiple_units() -> anyhow::Result<()> {
+        // given
+        let project = build_project(&[1, 2]);
+        let phase = project.phases.get(&Reference::from_str("top_1")?).unwrap();
+        let placement_states = find_phase_placement_states(&project, phase);
+
+        // when
+        let content = ExportFormat::Centroid.exporter().export(&project, phase, &placement_states)?;
+
+        // then
+        assert_eq!(String::from_utf8(content)?, indoc! {r#"
+            "Designator","Layer","MidX","MidY","Rotation","Manufacturer","Mpn","Package"
+            "R1_1","Top","10.5","20","90","RES_MFR1","RES1","0402"
+            "R1_2","Top","10.5","20","90","RES_MFR1","RES1","0402"
+        "#});
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn missing_feeders() -> anyhow::Result<()> {
+        // given
+        let mut project = build_project(&[1, 2]);
+        let reference = Reference::from_str("top_1")?;
+
+        // and a load-out with the part, but without a feeder
+        let phase_load_out_items_map = BTreeMap::from([
+            (reference.clone(), vec![LoadOutItem::new("".to_string(), "RES_MFR1".to_string(), "RES1".to_string())]),
+        ]);
+
+        // expect phases without an export format to be ignored
+        assert!(check_feeders(&project, &phase_load_out_items_map, false)?.is_empty());
+
+        // when
+        update_export_format(&mut project, &reference, Some(ExportFormat::Centroid))?;
+        let result = check_feeders(&project, &phase_load_out_items_map, false);
+
+        // then placements that are not to be placed are not checked
+        let expected_missing = vec![
+            MissingFeeder { phase: reference.clone(), part: Part::new("RES_MFR1".to_string(), "RES1".to_string()), placements: 2 },
+        ];
+        let FeederCheckError::MissingFeeders { missing } = result.unwrap_err();
+        assert_eq!(missing, expected_missing);
+
+        // and
+        assert_eq!(check_feeders(&project, &phase_load_out_items_map, true)?, expected_missing);
+
+        // when the part is assigned to a feeder
+        let phase_load_out_items_map = BTreeMap::from([
+            (reference.clone(), vec![LoadOutItem::new("FEEDER_1".to_string(), "RES_MFR1".to_string(), "RES1".to_string())]),
+        ]);
+
+        // then
+        assert!(check_feeders(&project, &phase_load_out_items_map, false)?.is_empty());
+
+        Ok(())
+    }
+}
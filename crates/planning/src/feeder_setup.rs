@@ -0,0 +1,231 @@
+//! Feeder setup sheets, a printable list of the feeders of a phase so that operators can load the machine from a
+//! single document.
+//!
+//! One line for each part of the phase, in feeder reference order, parts without a feeder are listed last.  The value,
+//! description, package and tape width are from the part details, see `project::update_part_details`.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use anyhow::Error;
+use csv::QuoteStyle;
+use pnp::load_out::LoadOutItem;
+use pnp::object_path::ObjectPath;
+use pnp::part::Part;
+use crate::phase::Phase;
+use crate::placement::PlacementState;
+use crate::project::Project;
+
+const STYLE: &str = "body { font-family: sans-serif; margin: 2em; } \
+    table { border-collapse: collapse; } \
+    th, td { border: 1px solid #999; padding: 0.25em 0.5em; text-align: left; } \
+    th { background: #eee; } \
+    td.loaded { width: 4em; }";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeederSetupItem {
+    /// Empty if the part has not been assigned to a feeder.
+    pub feeder_reference: String,
+    pub part: Part,
+    pub value: Option<String>,
+    pub description: Option<String>,
+    pub package: Option<String>,
+    /// The placements of the part that are to be placed.
+    pub quantity: usize,
+    /// Millimeters.
+    pub tape_width: Option<u32>,
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all(serialize = "PascalCase"))]
+pub struct FeederSetupRecord {
+    pub feeder_reference: String,
+    pub manufacturer: String,
+    pub mpn: String,
+    pub value: String,
+    pub description: String,
+    pub package: String,
+    pub quantity: usize,
+    pub tape_width: Option<u32>,
+}
+
+pub fn build_feeder_setup_file_name(phase: &Phase) -> String {
+    format!("{}_feeder_setup.csv", phase.reference)
+}
+
+pub fn build_html_feeder_setup_file_name(phase: &Phase) -> String {
+    format!("{}_feeder_setup.html", phase.reference)
+}
+
+/// One item for each part of the placements that are to be placed, in feeder reference order.
+pub fn build_feeder_setup_items(project: &Project, placement_states: &[(&ObjectPath, &PlacementState)], load_out_items: &[LoadOutItem]) -> Vec<FeederSetupItem> {
+    let mut part_quantities: BTreeMap<&Part, usize> = BTreeMap::new();
+    for (_object_path, placement_state) in placement_states.iter().filter(|(_object_path, placement_state)| placement_state.place()) {
+        *part_quantities.entry(&placement_state.placement.part).or_default() += 1;
+    }
+
+    let mut items: Vec<FeederSetupItem> = part_quantities.into_iter()
+        .map(|(part, quantity)| {
+            let feeder_reference = match pnp::load_out::find_load_out_item_by_part(load_out_items, part) {
+                Some(load_out_item) => load_out_item.reference.clone(),
+                _ => "".to_string(),
+            };
+
+            let details = project.find_part_details(part);
+
+            FeederSetupItem {
+                feeder_reference,
+                part: part.clone(),
+                value: details.and_then(|details| details.value.clone()),
+                description: details.and_then(|details| details.description.clone()),
+                package: details.and_then(|details| details.package.clone()),
+                quantity,
+                tape_width: details.and_then(|details| details.tape_width),
+            }
+        })
+        .collect();
+
+    items.sort_by(|item_a, item_b| {
+        pnp::load_out::feeder_reference_cmp(&item_a.feeder_reference, &item_b.feeder_reference)
+            .then_with(|| item_a.part.cmp(&item_b.part))
+    });
+
+    items
+}
+
+pub fn build_feeder_setup_csv(items: &[FeederSetupItem]) -> Result<Vec<u8>, Error> {
+    let mut writer = csv::WriterBuilder::new()
+        .quote_style(QuoteStyle::Always)
+        .from_writer(vec![]);
+
+    for item in items.iter() {
+        writer.serialize(FeederSetupRecord {
+            feeder_reference: item.feeder_reference.clone(),
+            manufacturer: item.part.manufacturer.clone(),
+            mpn: item.part.mpn.clone(),
+            value: item.value.clone().unwrap_or_default(),
+            description: item.description.clone().unwrap_or_default(),
+            package: item.package.clone().unwrap_or_default(),
+            quantity: item.quantity,
+            tape_width: item.tape_width,
+        })?;
+    }
+
+    Ok(writer.into_inner()?)
+}
+
+/// A single, self-contained, document with a column for the operator to tick off each loaded feeder.
+pub fn build_feeder_setup_html(project: &Project, phase: &Phase, items: &[FeederSetupItem]) -> String {
+    let mut html = String::new();
+
+    writeln!(html, "<!DOCTYPE html>").unwrap();
+    writeln!(html, "<html>").unwrap();
+    writeln!(html, "<head>").unwrap();
+    writeln!(html, "<meta charset=\"utf-8\">").unwrap();
+    writeln!(html, "<title>Feeder setup - {} - {}</title>", escape_html(&project.name), phase.reference).unwrap();
+    writeln!(html, "<style>{}</style>", STYLE).unwrap();
+    writeln!(html, "</head>").unwrap();
+    writeln!(html, "<body>").unwrap();
+    writeln!(html, "<h1>Feeder setup - {}</h1>", phase.reference).unwrap();
+    writeln!(html, "<p>Project: {}, process: {}, PCB side: {:?}</p>", escape_html(&project.name), phase.process, phase.pcb_side).unwrap();
+    writeln!(html, "<table>").unwrap();
+    writeln!(html, "<tr><th>Feeder</th><th>Manufacturer</th><th>Mpn</th><th>Value</th><th>Description</th><th>Package</th><th>Quantity</th><th>Tape width</th><th>Loaded</th></tr>").unwrap();
+
+    for item in items.iter() {
+        writeln!(html, "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td class=\"loaded\"></td></tr>",
+            escape_html(&item.feeder_reference),
+            escape_html(&item.part.manufacturer),
+            escape_html(&item.part.mpn),
+            escape_html(item.value.as_deref().unwrap_or_default()),
+            escape_html(item.description.as_deref().unwrap_or_default()),
+            escape_html(item.package.as_deref().unwrap_or_default()),
+            item.quantity,
+            item.tape_width.map(|tape_width| format!("{}mm", tape_width)).unwrap_or_default(),
+        ).unwrap();
+    }
+
+    writeln!(html, "</table>").unwrap();
+    writeln!(html, "</body>").unwrap();
+    writeln!(html, "</html>").unwrap();
+
+    html
+}
+
+fn escape_html(value: &str) -> String {
+    value.replace('&', "&amp;").replace('"', "&quot;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use pnp::load_out::LoadOutItem;
+    use pnp::object_path::ObjectPath;
+    use pnp::part::{Part, PartDetails};
+    use pnp::pcb::PcbSide;
+    use crate::feeder_setup::{build_feeder_setup_csv, build_feeder_setup_html, build_feeder_setup_items, FeederSetupItem};
+    use crate::part::PartState;
+    use crate::placement::PlacementState;
+    use crate::reference::Reference;
+    use crate::test::project_builder::{PlacementStateBuilder, ProjectBuilder};
+
+    #[test]
+    pub fn feeder_setup_sheet() {
+        // given
+        let mut project = ProjectBuilder::new()
+            .with_phase("top_1", "pnp", "load_out_1.csv", PcbSide::Top)
+            .build();
+        let phase = project.phases.get(&Reference::from_str("top_1").unwrap()).unwrap().clone();
+
+        // and
+        let res1 = Part::new("RES_MFR1".to_string(), "RES1".to_string());
+        let cap1 = Part::new("CAP_MFR1".to_string(), "CAP1".to_string());
+        let conn1 = Part::new("CONN_MFR1".to_string(), "CONN1".to_string());
+        project.part_states.insert(res1.clone(), PartState {
+            details: PartDetails { value: Some("10K".to_string()), description: Some("10K <1%>".to_string()), package: Some("0402".to_string()), tape_width: Some(8), ..PartDetails::default() },
+            ..PartState::default()
+        });
+
+        // and the placements in object path order
+        let placement_states = [
+            ("C1", &cap1, true),
+            ("J1", &conn1, true),
+            ("R1", &res1, true),
+            ("R2", &res1, true),
+            ("R3", &res1, false),
+        ].map(|(ref_des, part, place)| PlacementStateBuilder::new(1, ref_des, part).with_place(place).with_phase("top_1").build());
+        let placement_states: Vec<(&ObjectPath, &PlacementState)> = placement_states.iter()
+            .map(|(object_path, placement_state)| (object_path, placement_state))
+            .collect();
+
+        // and 'FEEDER_2' is before 'FEEDER_10', the connector is not assigned to a feeder
+        let load_out_items = vec![
+            LoadOutItem::new("FEEDER_10".to_string(), "CAP_MFR1".to_string(), "CAP1".to_string()),
+            LoadOutItem::new("FEEDER_2".to_string(), "RES_MFR1".to_string(), "RES1".to_string()),
+        ];
+
+        // when
+        let items = build_feeder_setup_items(&project, &placement_states, &load_out_items);
+
+        // then
+        assert_eq!(items, vec![
+            FeederSetupItem { feeder_reference: "FEEDER_2".to_string(), part: res1, value: Some("10K".to_string()), description: Some("10K <1%>".to_string()), package: Some("0402".to_string()), quantity: 2, tape_width: Some(8) },
+            FeederSetupItem { feeder_reference: "FEEDER_10".to_string(), part: cap1, value: None, description: None, package: None, quantity: 1, tape_width: None },
+            FeederSetupItem { feeder_reference: "".to_string(), part: conn1, value: None, description: None, package: None, quantity: 1, tape_width: None },
+        ]);
+
+        // when
+        let content = String::from_utf8(build_feeder_setup_csv(&items).unwrap()).unwrap();
+
+        // then
+        assert_eq!(content, "\"FeederReference\",\"Manufacturer\",\"Mpn\",\"Value\",\"Description\",\"Package\",\"Quantity\",\"TapeWidth\"\n\
+            \"FEEDER_2\",\"RES_MFR1\",\"RES1\",\"10K\",\"10K <1%>\",\"0402\",\"2\",\"8\"\n\
+            \"FEEDER_10\",\"CAP_MFR1\",\"CAP1\",\"\",\"\",\"\",\"1\",\"\"\n\
+            \"\",\"CONN_MFR1\",\"CONN1\",\"\",\"\",\"\",\"1\",\"\"\n");
+
+        // when
+        let html = build_feeder_setup_html(&project, &phase, &items);
+
+        // then
+        assert!(html.contains("<h1>Feeder setup - top_1</h1>"));
+        assert!(html.contains("<tr><td>FEEDER_2</td><td>RES_MFR1</td><td>RES1</td><td>10K</td><td>10K &lt;1%&gt;</td><td>0402</td><td>2</td><td>8mm</td><td class=\"loaded\"></td></tr>"));
+    }
+}
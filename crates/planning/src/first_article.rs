@@ -0,0 +1,245 @@
+//! First-article inspection (FAI), the first PCB unit of a phase is inspected and signed off before the remaining units
+//! are placed.
+//!
+//! A checklist is generated for each phase that requires the inspection, with one line for each part, the sign-off is
+//! recorded in the phase state and in the operation history of the phase.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use anyhow::Error;
+use csv::QuoteStyle;
+use serde_with::serde_as;
+use serde_with::DisplayFromStr;
+use thiserror::Error;
+use time::OffsetDateTime;
+use time::serde::rfc3339;
+use tracing::info;
+use pnp::object_path::ObjectPath;
+use pnp::part::Part;
+use crate::operation_history;
+use crate::operation_history::{OperationHistoryItem, OperationHistoryKind};
+use crate::phase::{Phase, PhaseError};
+use crate::placement::PlacementState;
+use crate::project::{find_phase_placement_states, Project};
+use crate::reference::Reference;
+use crate::mutation::MUTATION_TARGET;
+
+#[derive(Error, Debug)]
+pub enum FirstArticleError {
+    #[error("The first article of the phase has not been signed off, only the first article can be placed. phase: '{phase}', first_article: '{first_article}', object_path: '{object_path}'")]
+    NotSignedOff { phase: Reference, first_article: ObjectPath, object_path: ObjectPath },
+
+    #[error("The phase does not require a first-article inspection. phase: '{phase}'")]
+    NotRequired { phase: Reference },
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq)]
+pub struct FirstArticleInspection {
+    #[serde(with = "rfc3339")]
+    pub signed_off_at: OffsetDateTime,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub operator: Option<String>,
+}
+
+/// A line of the first-article checklist, the sign-off columns are left empty, for the inspector.
+#[serde_as]
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all(serialize = "PascalCase"))]
+pub struct FirstArticleRecord {
+    pub manufacturer: String,
+    pub mpn: String,
+    pub package: String,
+    pub quantity: usize,
+    /// A placement of the part, on the first article, to verify.
+    pub sample_ref_des: String,
+    #[serde_as(as = "DisplayFromStr")]
+    pub sample_object_path: ObjectPath,
+    pub verified: String,
+    pub inspector: String,
+    pub date: String,
+}
+
+pub fn build_first_article_file_name(phase: &Phase) -> String {
+    format!("{}_first_article.csv", phase.reference)
+}
+
+/// The first PCB unit, by object path, with placements of the phase, `None` if the phase has no placements to place.
+pub fn find_first_article(project: &Project, phase: &Phase) -> Option<ObjectPath> {
+    find_phase_placement_states(project, phase).into_iter()
+        .filter(|(_object_path, placement_state)| placement_state.place())
+        .map(|(object_path, _placement_state)| object_path.pcb_unit())
+        .min()
+}
+
+/// One line for each part of the first article, in part order.
+pub fn build_first_article_checklist_csv(project: &Project, phase: &Phase, placement_states: &[(&ObjectPath, &PlacementState)]) -> Result<Vec<u8>, Error> {
+    let first_article = find_first_article(project, phase);
+
+    let mut part_placements: BTreeMap<&Part, Vec<(&ObjectPath, &PlacementState)>> = BTreeMap::new();
+    for (object_path, placement_state) in placement_states.iter() {
+        if !placement_state.place() || first_article.as_ref().is_none_or(|first_article| object_path.pcb_unit().ne(first_article)) {
+            continue
+        }
+        part_placements.entry(&placement_state.placement.part).or_default().push((object_path, placement_state));
+    }
+
+    let mut writer = csv::WriterBuilder::new()
+        .quote_style(QuoteStyle::Always)
+        .from_writer(vec![]);
+
+    for (part, mut part_placement_states) in part_placements.into_iter() {
+        part_placement_states.sort_by_key(|(object_path, _placement_state)| *object_path);
+        let (sample_object_path, sample_placement_state) = part_placement_states[0];
+
+        let package = project.part_states.get(part)
+            .and_then(|part_state| part_state.details.package.clone())
+            .unwrap_or_default();
+
+        writer.serialize(FirstArticleRecord {
+            manufacturer: part.manufacturer.clone(),
+            mpn: part.mpn.clone(),
+            package,
+            quantity: part_placement_states.len(),
+            sample_ref_des: sample_placement_state.placement.ref_des.clone(),
+            sample_object_path: sample_object_path.clone(),
+            verified: "".to_string(),
+            inspector: "".to_string(),
+            date: "".to_string(),
+        })?;
+    }
+
+    Ok(writer.into_inner()?)
+}
+
+/// Enables or disables the first-article inspection of a phase, returns true if modified.
+pub fn set_first_article_inspection_required(project: &mut Project, reference: &Reference, required: bool) -> Result<bool, PhaseError> {
+    let phase = project.phases.get_mut(reference)
+        .ok_or(PhaseError::UnknownPhase(reference.clone()))?;
+
+    if phase.first_article_inspection_required == required {
+        return Ok(false)
+    }
+
+    info!(target: MUTATION_TARGET, phase = %reference, required, message = "Phase first-article inspection set. phase: '{phase}', required: {required}");
+    phase.first_article_inspection_required = required;
+
+    Ok(true)
+}
+
+/// Records the sign-off of the first article of the phase, replacing any previous sign-off.
+pub fn record_first_article_inspection(project: &mut Project, path: &Path, reference: &Reference, operator: Option<String>, now: OffsetDateTime) -> anyhow::Result<()> {
+    let phase = project.phases.get(reference)
+        .ok_or(PhaseError::UnknownPhase(reference.clone()))?;
+
+    if !phase.first_article_inspection_required {
+        return Err(FirstArticleError::NotRequired { phase: reference.clone() }.into())
+    }
+
+    info!(target: MUTATION_TARGET, phase = %reference, operator = ?operator, message = "Recorded first-article inspection. phase: '{phase}', operator: {operator}");
+
+    let phase_log_path = path.join(format!("{}_log.json", reference));
+
+    let mut operation_history: Vec<OperationHistoryItem> = operation_history::read_or_default(&phase_log_path)?;
+
+    operation_history::append(&mut operation_history, vec![
+        OperationHistoryItem::new(now, reference.clone(), OperationHistoryKind::FirstArticleInspected { operator: operator.clone() }),
+    ]);
+
+    operation_history::write(phase_log_path, &operation_history)?;
+
+    let phase_state = project.phase_states.get_mut(reference).unwrap();
+    phase_state.first_article_inspection = Some(FirstArticleInspection { signed_off_at: now, operator });
+
+    Ok(())
+}
+
+/// The first article of each phase that requires a first-article inspection that has not been signed off, the other
+/// PCB units of these phases must not be placed.
+pub fn find_unsigned_first_articles(project: &Project) -> BTreeMap<Reference, ObjectPath> {
+    project.phases.values()
+        .filter(|phase| phase.first_article_inspection_required)
+        .filter(|phase| project.phase_states.get(&phase.reference)
+            .is_some_and(|phase_state| phase_state.first_article_inspection.is_none()))
+        .filter_map(|phase| find_first_article(project, phase).map(|first_article| (phase.reference.clone(), first_article)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use indoc::indoc;
+    use regex::Regex;
+    use tempfile::tempdir;
+    use time::OffsetDateTime;
+    use pnp::object_path::ObjectPath;
+    use pnp::part::Part;
+    use pnp::pcb::PcbSide;
+    use crate::first_article::{build_first_article_checklist_csv, record_first_article_inspection, set_first_article_inspection_required, FirstArticleError};
+    use crate::placement::PlacementOperation;
+    use crate::project::{find_phase_placement_states, update_placements_operation, Project};
+    use crate::reference::Reference;
+    use crate::test::project_builder::{PlacementStateBuilder, ProjectBuilder};
+
+    fn build_project() -> Project {
+        let res1 = Part::new("RES_MFR1".to_string(), "RES1".to_string());
+        let cap1 = Part::new("CAP_MFR1".to_string(), "CAP1".to_string());
+
+        ProjectBuilder::new()
+            .with_phase("top_1", "pnp", "load_out_1.csv", PcbSide::Top)
+            .with_placements([(1, "R1", &res1), (1, "R2", &res1), (1, "C1", &cap1), (2, "R1", &res1), (2, "R2", &res1), (2, "C1", &cap1)]
+                .map(|(unit, ref_des, part)| PlacementStateBuilder::new(unit, ref_des, part).with_phase("top_1")))
+            .build()
+    }
+
+    #[test]
+    pub fn checklist() -> anyhow::Result<()> {
+        // given
+        let project = build_project();
+        let phase = project.phases.get(&Reference::from_str("top_1")?).unwrap();
+        let placement_states = find_phase_placement_states(&project, phase);
+
+        // when
+        let content = build_first_article_checklist_csv(&project, phase, &placement_states)?;
+
+        // then
+        assert_eq!(String::from_utf8(content)?, indoc! {r#"
+            "Manufacturer","Mpn","Package","Quantity","SampleRefDes","SampleObjectPath","Verified","Inspector","Date"
+            "CAP_MFR1","CAP1","","1","C1","panel=1::unit=1::ref_des=C1","","",""
+            "RES_MFR1","RES1","","2","R1","panel=1::unit=1::ref_des=R1","","",""
+        "#});
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn only_the_first_article_is_placed_before_sign_off() -> anyhow::Result<()> {
+        // given
+        let temp_dir = tempdir()?;
+        let mut project = build_project();
+        let reference = Reference::from_str("top_1")?;
+        set_first_article_inspection_required(&mut project, &reference, true)?;
+
+        // when the first article is placed
+        let modified = update_placements_operation(&mut project, temp_dir.path(), vec![Regex::new("unit=1::")?.into()], PlacementOperation::Placed)?;
+
+        // then
+        assert!(modified);
+
+        // when another unit is placed
+        let result = update_placements_operation(&mut project, temp_dir.path(), vec![Regex::new("unit=2::ref_des=R1")?.into()], PlacementOperation::Placed);
+
+        // then
+        assert!(matches!(result.unwrap_err().downcast::<FirstArticleError>()?, FirstArticleError::NotSignedOff { .. }));
+        assert!(!project.placements.get(&ObjectPath::from_str("panel=1::unit=2::ref_des=R1")?).unwrap().placed);
+
+        // when
+        record_first_article_inspection(&mut project, temp_dir.path(), &reference, Some("Operator 1".to_string()), OffsetDateTime::UNIX_EPOCH)?;
+
+        // then
+        let modified = update_placements_operation(&mut project, temp_dir.path(), vec![Regex::new("unit=2::ref_des=R1")?.into()], PlacementOperation::Placed)?;
+        assert!(modified);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,177 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter};
+use time::OffsetDateTime;
+use pnp::load_out::LoadOutItem;
+use pnp::part::Part;
+use crate::placement::PlacementStatus;
+use crate::project::{add_unassigned_part_feeder_issues, find_phase_placement_states, Project};
+use crate::reference::Reference;
+use crate::{issue, moisture, report};
+use crate::report::{IssueSeverity, ProjectReportIssue};
+
+/// A summary of the remaining setup work of a project.
+#[derive(Debug, Default, PartialEq)]
+pub struct HealthSummary {
+    /// Count of severe issues, see `ProjectReport::issues`, waived issues are not counted.
+    pub errors: usize,
+    pub warnings: usize,
+    /// Count of waived issues, of any severity.
+    pub waived: usize,
+    /// Count of placements that are to be placed.
+    pub placements: usize,
+    /// Count of placements, that are to be placed, that have been assigned to a phase.
+    pub assigned_placements: usize,
+    /// Count of unique parts, per phase.
+    pub phase_parts: usize,
+    /// Count of unique parts, per phase, that have been assigned to a feeder.
+    pub assigned_phase_parts: usize,
+}
+
+/// Builds the health summary, using the same issues as the project report.
+pub fn build_health_summary(project: &Project, phase_load_out_items_map: &BTreeMap<Reference, Vec<LoadOutItem>>) -> HealthSummary {
+    let mut issues: BTreeSet<ProjectReportIssue> = BTreeSet::new();
+    let mut summary = HealthSummary::default();
+
+    for reference in project.phase_orderings.iter() {
+        let phase = project.phases.get(reference).unwrap();
+        let load_out_items = phase_load_out_items_map.get(reference).map(Vec::as_slice).unwrap_or_default();
+
+        let placement_states = find_phase_placement_states(project, phase);
+
+        add_unassigned_part_feeder_issues(&placement_states, load_out_items, &mut issues);
+
+        let parts: BTreeSet<&Part> = placement_states.iter()
+            .filter(|(_object_path, placement_state)| placement_state.place())
+            .map(|(_object_path, placement_state)| &placement_state.placement.part)
+            .collect();
+
+        summary.phase_parts += parts.len();
+        summary.assigned_phase_parts += parts.iter()
+            .filter(|part| pnp::load_out::find_load_out_item_by_part(load_out_items, part)
+                .is_some_and(|load_out_item| !load_out_item.reference.is_empty()))
+            .count();
+    }
+
+    moisture::add_floor_life_issues(project, OffsetDateTime::now_utc(), &mut issues);
+
+    let _report = report::project_build_report(project, phase_load_out_items_map, None, None, &mut issues);
+
+    let (waived_issues, issues): (Vec<&ProjectReportIssue>, Vec<&ProjectReportIssue>) = issues.iter()
+        .partition(|issue| issue::is_waived(project, issue));
+
+    summary.errors = issues.iter().filter(|issue| matches!(issue.severity, IssueSeverity::Severe)).count();
+    summary.warnings = issues.iter().filter(|issue| matches!(issue.severity, IssueSeverity::Warning)).count();
+    summary.waived = waived_issues.len();
+
+    for placement_state in project.placements.values() {
+        if !placement_state.place() || !matches!(placement_state.status, PlacementStatus::Known) {
+            continue
+        }
+        summary.placements += 1;
+        if placement_state.phase.is_some() {
+            summary.assigned_placements += 1;
+        }
+    }
+
+    summary
+}
+
+impl Display for HealthSummary {
+    /// e.g. 'Health: 0 errors, 2 warnings, placements assigned: 12/12 (100%), feeders assigned: 6/8 (75%)'
+    ///
+    /// Waived issues are only shown when there are some, e.g. 'Health: 0 errors, 2 warnings, 1 waived, ...'
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Health: {} {}, {} {}, ",
+            self.errors, if self.errors == 1 { "error" } else { "errors" },
+            self.warnings, if self.warnings == 1 { "warning" } else { "warnings" },
+        )?;
+        if self.waived > 0 {
+            write!(f, "{} waived, ", self.waived)?;
+        }
+        write!(f, "placements assigned: {}, feeders assigned: {}",
+            format_ratio(self.assigned_placements, self.placements),
+            format_ratio(self.assigned_phase_parts, self.phase_parts),
+        )
+    }
+}
+
+/// Percentages are rounded down, so that 100% is only shown when everything is assigned.
+fn format_ratio(count: usize, total: usize) -> String {
+    match total {
+        0 => "0/0".to_string(),
+        _ => format!("{}/{} ({}%)", count, total, count * 100 / total),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::str::FromStr;
+    use pnp::load_out::LoadOutItem;
+    use pnp::part::Part;
+    use pnp::pcb::{PcbKind, PcbSide};
+    use time::OffsetDateTime;
+    use crate::health::{build_health_summary, HealthSummary};
+    use crate::issue::{build_issue_id, IssueResolution, IssueResolutionStatus};
+    use crate::report::IssueKind;
+    use crate::reference::Reference;
+    use crate::test::project_builder::{PlacementStateBuilder, ProjectBuilder};
+
+    #[test]
+    pub fn summary() {
+        // given
+        let res1 = Part::new("RES_MFR1".to_string(), "RES1".to_string());
+        let res2 = Part::new("RES_MFR1".to_string(), "RES2".to_string());
+
+        // and
+        let mut project = ProjectBuilder::new()
+            .with_pcb(PcbKind::Panel, "panel_a")
+            .with_phase("top_1", "pnp", "load_out_1.csv", PcbSide::Top)
+            .with_placements([
+                PlacementStateBuilder::new(1, "R1", &res1).with_phase("top_1"),
+                PlacementStateBuilder::new(1, "R2", &res2).with_phase("top_1"),
+                PlacementStateBuilder::new(1, "R3", &res1),
+                PlacementStateBuilder::new(1, "R4", &res1).with_place(false),
+            ])
+            .build();
+        let reference = Reference::from_str("top_1").unwrap();
+
+        // and
+        let phase_load_out_items_map = BTreeMap::from([(reference.clone(), vec![
+            LoadOutItem::new("FEEDER_1".to_string(), "RES_MFR1".to_string(), "RES1".to_string()),
+            LoadOutItem::new("".to_string(), "RES_MFR1".to_string(), "RES2".to_string()),
+        ])]);
+
+        // when
+        let summary = build_health_summary(&project, &phase_load_out_items_map);
+
+        // then
+        assert_eq!(summary, HealthSummary {
+            errors: 0,
+            // unassigned placements, including those not to be placed, and unassigned part feeder
+            warnings: 3,
+            waived: 0,
+            placements: 3,
+            assigned_placements: 2,
+            phase_parts: 2,
+            assigned_phase_parts: 1,
+        });
+
+        // and
+        assert_eq!(summary.to_string(), "Health: 0 errors, 3 warnings, placements assigned: 2/3 (66%), feeders assigned: 1/2 (50%)");
+
+        // when the unassigned part feeder issue is waived
+        let issue_kind = IssueKind::UnassignedPartFeeder { part: Part::new("RES_MFR1".to_string(), "RES2".to_string()) };
+        project.issue_resolutions.insert(build_issue_id(&issue_kind), IssueResolution {
+            status: IssueResolutionStatus::Waived,
+            reason: "Placed by hand".to_string(),
+            operator: None,
+            resolved_at: OffsetDateTime::UNIX_EPOCH,
+        });
+        let summary = build_health_summary(&project, &phase_load_out_items_map);
+
+        // then
+        assert_eq!((summary.warnings, summary.waived), (2, 1));
+        assert_eq!(summary.to_string(), "Health: 0 errors, 2 warnings, 1 waived, placements assigned: 2/3 (66%), feeders assigned: 1/2 (50%)");
+    }
+}
@@ -0,0 +1,97 @@
+//! On-hand quantities of the parts, see `stores::inventory`.
+//!
+//! The inventory is shared by all the phases of a project, the quantities are decremented as the placements are
+//! placed, so a phase only requires the parts for its unplaced placements.
+
+use pnp::part::Part;
+use crate::project::{count_phase_part_placements, Project};
+use crate::reference::Reference;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Inventory {
+    /// In inventory order, a part that is stored in more than one place has more than one item.
+    pub items: Vec<InventoryItem>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InventoryItem {
+    pub part: Part,
+    pub quantity: u32,
+}
+
+impl Inventory {
+    /// The total of the items of the part, parts that are not in the inventory have no parts on hand.
+    pub fn on_hand(&self, part: &Part) -> u32 {
+        self.items.iter()
+            .filter(|item| item.part.eq(part))
+            .fold(0, |on_hand, item| on_hand.saturating_add(item.quantity))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InventoryShortage {
+    pub phase: Reference,
+    pub part: Part,
+    pub required: u32,
+    pub on_hand: u32,
+}
+
+/// The parts that a phase requires more of than are on hand, in phase order then part order.
+pub fn find_inventory_shortages(project: &Project, inventory: &Inventory) -> Vec<InventoryShortage> {
+    let phase_part_counts = count_phase_part_placements(project);
+
+    project.phase_orderings.iter()
+        .filter_map(|reference| phase_part_counts.get(reference).map(|part_counts| (reference, part_counts)))
+        .flat_map(|(reference, part_counts)| {
+            part_counts.iter().filter_map(|(part, counts)| {
+                let on_hand = inventory.on_hand(part);
+
+                match counts.unplaced > on_hand {
+                    true => Some(InventoryShortage { phase: reference.clone(), part: part.clone(), required: counts.unplaced, on_hand }),
+                    false => None,
+                }
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use pnp::part::Part;
+    use pnp::pcb::PcbSide;
+    use crate::inventory::{find_inventory_shortages, Inventory, InventoryItem, InventoryShortage};
+    use crate::reference::Reference;
+    use crate::test::project_builder::{PlacementStateBuilder, ProjectBuilder};
+
+    #[test]
+    pub fn shortages() {
+        // given
+        let res1 = Part::new("RES_MFR1".to_string(), "RES1".to_string());
+        let res2 = Part::new("RES_MFR1".to_string(), "RES2".to_string());
+        let res3 = Part::new("RES_MFR1".to_string(), "RES3".to_string());
+
+        let project = ProjectBuilder::new()
+            .with_phase("top_1", "pnp", "load_out_1.csv", PcbSide::Top)
+            .with_placements([(1, "R1", &res1, false), (2, "R1", &res1, false), (1, "R2", &res2, true), (1, "R3", &res3, false)]
+                .map(|(unit, ref_des, part, placed)| PlacementStateBuilder::new(unit, ref_des, part).with_placed(placed).with_phase("top_1"))
+            )
+            .build();
+        let top_1 = Reference::from_str("top_1").unwrap();
+
+        // and 'RES2' has been placed, so it is not required, and 'RES3' is not in the inventory
+        let inventory = Inventory { items: vec![
+            InventoryItem { part: res1.clone(), quantity: 1 },
+            InventoryItem { part: res2.clone(), quantity: 0 },
+        ] };
+
+        // when
+        let shortages = find_inventory_shortages(&project, &inventory);
+
+        // then
+        assert_eq!(shortages, vec![
+            InventoryShortage { phase: top_1.clone(), part: res1, required: 2, on_hand: 1 },
+            InventoryShortage { phase: top_1, part: res3, required: 1, on_hand: 0 },
+        ]);
+    }
+}
@@ -0,0 +1,148 @@
+//! Acknowledging and waiving of project report issues.
+//!
+//! Issues are regenerated each time the report is built, each issue is identified by an id that is derived from the
+//! kind of the issue, so the id of an issue does not change between runs.  The resolution of an issue is stored in
+//! the project using the id.
+//!
+//! Waived issues are not counted as errors or warnings, e.g. they do not prevent a release, but they are still listed
+//! in the report, with their resolution.  Acknowledged issues are still counted.
+
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use time::serde::rfc3339;
+use time::OffsetDateTime;
+use tracing::info;
+use crate::project::Project;
+use crate::report::{IssueKind, ProjectReportIssue};
+use crate::mutation::MUTATION_TARGET;
+
+const ISSUE_ID_LENGTH: usize = 8;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq)]
+pub struct IssueResolution {
+    pub status: IssueResolutionStatus,
+    pub reason: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub operator: Option<String>,
+
+    #[serde(with = "rfc3339")]
+    pub resolved_at: OffsetDateTime,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueResolutionStatus {
+    Acknowledged,
+    Waived,
+}
+
+#[derive(Error, Debug)]
+pub enum IssueResolutionError {
+    #[error("Unknown issue, the ids of the issues are listed in the report. id: '{id}'")]
+    UnknownIssue { id: String },
+
+    #[error("A reason is required")]
+    MissingReason,
+}
+
+/// e.g. '3f2a9c1b', the first characters of the SHA-256 hash of the JSON representation of the kind.
+pub fn build_issue_id(kind: &IssueKind) -> String {
+    let content = serde_json::to_vec(kind).expect("issue kinds are always serializable");
+
+    let mut id = hex::encode(Sha256::digest(content));
+    id.truncate(ISSUE_ID_LENGTH);
+    id
+}
+
+pub fn find_resolution<'a>(project: &'a Project, issue: &ProjectReportIssue) -> Option<&'a IssueResolution> {
+    project.issue_resolutions.get(&build_issue_id(&issue.kind))
+}
+
+pub fn is_waived(project: &Project, issue: &ProjectReportIssue) -> bool {
+    find_resolution(project, issue)
+        .is_some_and(|resolution| resolution.status == IssueResolutionStatus::Waived)
+}
+
+/// Records the resolution of one of the current issues of the project, replacing any previous resolution.
+pub fn resolve_issue(project: &mut Project, issues: &[ProjectReportIssue], id: &str, status: IssueResolutionStatus, reason: String, operator: Option<String>, now: OffsetDateTime) -> Result<(), IssueResolutionError> {
+    if reason.trim().is_empty() {
+        return Err(IssueResolutionError::MissingReason)
+    }
+
+    let issue = issues.iter()
+        .find(|issue| build_issue_id(&issue.kind).eq(id))
+        .ok_or_else(|| IssueResolutionError::UnknownIssue { id: id.to_string() })?;
+
+    info!(target: MUTATION_TARGET, id = %id, status = ?status, issue_message = %issue.message, reason = %reason, message = "Issue resolved. id: '{id}', status: {status}, message: '{issue_message}', reason: '{reason}'");
+
+    project.issue_resolutions.insert(id.to_string(), IssueResolution {
+        status,
+        reason,
+        operator,
+        resolved_at: now,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use time::OffsetDateTime;
+    use pnp::object_path::ObjectPath;
+    use crate::issue::{build_issue_id, is_waived, resolve_issue, IssueResolutionError, IssueResolutionStatus};
+    use crate::project::Project;
+    use crate::report::{IssueKind, IssueSeverity, ProjectReportIssue};
+
+    fn build_issue(object_path: &str) -> ProjectReportIssue {
+        ProjectReportIssue {
+            message: "A placement has not been assigned to a phase".to_string(),
+            severity: IssueSeverity::Warning,
+            kind: IssueKind::UnassignedPlacement { object_path: ObjectPath::from_str(object_path).unwrap() },
+        }
+    }
+
+    #[test]
+    pub fn ids_are_stable_and_unique() {
+        // given
+        let issue_1 = build_issue("panel=1::unit=1::ref_des=R1");
+        let issue_2 = build_issue("panel=1::unit=1::ref_des=R2");
+
+        // expect
+        assert_eq!(build_issue_id(&issue_1.kind), build_issue_id(&issue_1.clone().kind));
+        assert_ne!(build_issue_id(&issue_1.kind), build_issue_id(&issue_2.kind));
+        assert_eq!(build_issue_id(&issue_1.kind).len(), 8);
+    }
+
+    #[test]
+    pub fn waive_issue() {
+        // given
+        let mut project = Project::default();
+        let issues = vec![build_issue("panel=1::unit=1::ref_des=R1"), build_issue("panel=1::unit=1::ref_des=R2")];
+        let id = build_issue_id(&issues[1].kind);
+
+        // when
+        resolve_issue(&mut project, &issues, &id, IssueResolutionStatus::Waived, "Not fitted".to_string(), Some("Operator 1".to_string()), OffsetDateTime::UNIX_EPOCH).unwrap();
+
+        // then
+        assert!(!is_waived(&project, &issues[0]));
+        assert!(is_waived(&project, &issues[1]));
+        assert_eq!(project.issue_resolutions[&id].operator, Some("Operator 1".to_string()));
+    }
+
+    #[test]
+    pub fn unknown_issue() {
+        // given
+        let mut project = Project::default();
+        let issues = vec![build_issue("panel=1::unit=1::ref_des=R1")];
+
+        // when
+        let result = resolve_issue(&mut project, &issues, "00000000", IssueResolutionStatus::Acknowledged, "Seen".to_string(), None, OffsetDateTime::UNIX_EPOCH);
+
+        // then
+        assert!(matches!(result, Err(IssueResolutionError::UnknownIssue { .. })));
+        assert!(project.issue_resolutions.is_empty());
+    }
+}
@@ -7,4 +7,37 @@ pub mod process;
 pub mod part;
 pub mod reference;
 pub mod report;
-pub mod operation_history;
\ No newline at end of file
+pub mod operation_history;
+pub mod work_instructions;
+pub mod health;
+pub mod moisture;
+pub mod search;
+pub mod release;
+pub mod phase_export;
+pub mod bom;
+pub mod certificate;
+pub mod issue;
+pub mod compression;
+pub mod status;
+pub mod checklist;
+pub mod locking;
+pub mod load_out_reuse;
+pub mod load_out_sharing;
+pub mod pricing;
+pub mod inventory;
+pub mod first_article;
+pub mod nozzle;
+pub mod quantity_check;
+pub mod export;
+pub mod analytics;
+pub mod audit;
+pub mod scan;
+pub mod estimation;
+pub mod progress;
+pub mod feeder_setup;
+pub mod production_run;
+pub mod signing;
+pub mod mutation;
+
+#[cfg(test)]
+mod test;
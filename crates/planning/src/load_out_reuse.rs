@@ -0,0 +1,227 @@
+//! Analysis of the parts that several projects have in common, so that similar jobs can be batched using a shared
+//! machine setup, i.e. the shared feeders are loaded once and only the additional feeders of each project are changed.
+//!
+//! Only the phases of processes with the `AutomatedPnp` operation are considered, other phases do not use the machine.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter};
+use pnp::load_out::{feeder_reference_cmp, find_load_out_item_by_part, LoadOutItem};
+use pnp::part::Part;
+use crate::process::ProcessOperationKind;
+use crate::project::{count_phase_part_placements, Project};
+use crate::reference::Reference;
+
+#[derive(Debug, PartialEq)]
+pub struct LoadOutReuse {
+    /// In the order given.
+    pub projects: Vec<String>,
+    /// Parts used by more than one project, the parts used by the most projects first.
+    pub shared_parts: Vec<SharedPart>,
+    /// For each project, in the order given, the parts that are not in the shared setup.
+    pub additional_parts: Vec<(String, Vec<Part>)>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SharedPart {
+    pub part: Part,
+    pub projects: Vec<String>,
+    /// The feeder the part is most often loaded in by the load-outs of the projects, `None` if the part has not been
+    /// assigned to a feeder, or the feeder is suggested for a part that is used by more projects.
+    pub feeder: Option<String>,
+}
+
+/// Each project is given with the load-out items of each of its phases.
+pub fn analyze_load_out_reuse(projects: &[(&Project, &BTreeMap<Reference, Vec<LoadOutItem>>)]) -> LoadOutReuse {
+    let project_parts: Vec<(String, BTreeMap<Part, Vec<String>>)> = projects.iter()
+        .map(|(project, phase_load_out_items)| (project.name.clone(), find_machine_parts(project, phase_load_out_items)))
+        .collect();
+
+    let mut part_projects: BTreeMap<&Part, Vec<String>> = BTreeMap::new();
+    let mut part_feeders: BTreeMap<&Part, BTreeMap<&String, usize>> = BTreeMap::new();
+    for (name, parts) in project_parts.iter() {
+        for (part, feeders) in parts.iter() {
+            part_projects.entry(part).or_default().push(name.clone());
+            for feeder in feeders.iter() {
+                *part_feeders.entry(part).or_default().entry(feeder).or_default() += 1;
+            }
+        }
+    }
+
+    let mut shared: Vec<(&Part, Vec<String>)> = part_projects.into_iter()
+        .filter(|(_part, projects)| projects.len() > 1)
+        .collect();
+    shared.sort_by(|(part_a, projects_a), (part_b, projects_b)| projects_b.len().cmp(&projects_a.len()).then(part_a.cmp(part_b)));
+
+    let mut used_feeders: BTreeSet<&String> = BTreeSet::new();
+    let shared_parts: Vec<SharedPart> = shared.into_iter()
+        .map(|(part, projects)| {
+            let mut feeders: Vec<(&String, usize)> = part_feeders.get(part)
+                .map(|feeders| feeders.iter().map(|(feeder, count)| (*feeder, *count)).collect())
+                .unwrap_or_default();
+            feeders.sort_by(|(feeder_a, count_a), (feeder_b, count_b)| count_b.cmp(count_a).then(feeder_reference_cmp(feeder_a, feeder_b)));
+
+            let feeder = feeders.into_iter()
+                .map(|(feeder, _count)| feeder)
+                .find(|feeder| !used_feeders.contains(feeder));
+
+            if let Some(feeder) = feeder {
+                used_feeders.insert(feeder);
+            }
+
+            SharedPart { part: part.clone(), projects, feeder: feeder.cloned() }
+        })
+        .collect();
+
+    let additional_parts = project_parts.iter()
+        .map(|(name, parts)| {
+            let additional = parts.keys()
+                .filter(|part| !shared_parts.iter().any(|shared_part| shared_part.part.eq(part)))
+                .cloned()
+                .collect();
+            (name.clone(), additional)
+        })
+        .collect();
+
+    LoadOutReuse {
+        projects: projects.iter().map(|(project, _)| project.name.clone()).collect(),
+        shared_parts,
+        additional_parts,
+    }
+}
+
+/// The parts placed by the machine, with the feeders they are loaded in by the load-outs of the phases.
+fn find_machine_parts(project: &Project, phase_load_out_items: &BTreeMap<Reference, Vec<LoadOutItem>>) -> BTreeMap<Part, Vec<String>> {
+    let machine_phases: Vec<&Reference> = project.phases.values()
+        .filter(|phase| project.processes.iter()
+            .any(|process| process.name.eq(&phase.process) && process.has_operation(&ProcessOperationKind::AutomatedPnp)))
+        .map(|phase| &phase.reference)
+        .collect();
+
+    count_phase_part_placements(project).into_iter()
+        .filter(|(reference, _part_counts)| machine_phases.contains(&reference))
+        .fold(BTreeMap::new(), |mut parts, (reference, part_counts)| {
+            for part in part_counts.into_keys() {
+                let feeder = phase_load_out_items.get(&reference)
+                    .and_then(|load_out_items| find_load_out_item_by_part(load_out_items, &part))
+                    .map(|load_out_item| load_out_item.reference.clone())
+                    .filter(|feeder| !feeder.is_empty());
+
+                let feeders: &mut Vec<String> = parts.entry(part).or_default();
+                if let Some(feeder) = feeder {
+                    feeders.push(feeder);
+                }
+            }
+            parts
+        })
+}
+
+fn format_parts(parts: &[Part]) -> String {
+    parts.iter()
+        .map(|part| part.to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+impl Display for LoadOutReuse {
+    /// e.g.
+    /// ```text
+    /// Projects: job1, job2
+    /// Shared setup: 2 feeders
+    ///   FEEDER_1: RES_MFR1:RES1, projects: [job1, job2]
+    ///   -: RES_MFR1:RES2, projects: [job1, job2]
+    /// Additional feeders:
+    ///   job1: 1 [CAP_MFR1:CAP1]
+    ///   job2: 0 []
+    /// ```
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Projects: {}", self.projects.join(", "))?;
+
+        writeln!(f, "Shared setup: {} feeders", self.shared_parts.len())?;
+        for shared_part in self.shared_parts.iter() {
+            writeln!(f, "  {}: {}, projects: [{}]", shared_part.feeder.as_deref().unwrap_or("-"), shared_part.part, shared_part.projects.join(", "))?;
+        }
+
+        writeln!(f, "Additional feeders:")?;
+        for (name, parts) in self.additional_parts.iter() {
+            writeln!(f, "  {}: {} [{}]", name, parts.len(), format_parts(parts))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::str::FromStr;
+    use indoc::indoc;
+    use pnp::load_out::LoadOutItem;
+    use pnp::part::Part;
+    use pnp::pcb::PcbSide;
+    use crate::load_out_reuse::{analyze_load_out_reuse, SharedPart};
+    use crate::project::Project;
+    use crate::reference::Reference;
+    use crate::test::project_builder::{PlacementStateBuilder, ProjectBuilder};
+
+    fn build_project(name: &str, process: &str, parts: &[&Part]) -> Project {
+        ProjectBuilder::new()
+            .with_name(name)
+            .with_phase("top_1", process, "load_out_1.csv", PcbSide::Top)
+            .with_placements(parts.iter().enumerate()
+                .map(|(index, part)| PlacementStateBuilder::new(1, &format!("R{}", index + 1), part).with_phase("top_1"))
+            )
+            .build()
+    }
+
+    fn build_load_out(items: &[(&str, &Part)]) -> BTreeMap<Reference, Vec<LoadOutItem>> {
+        let load_out_items = items.iter()
+            .map(|(feeder, part)| LoadOutItem::new(feeder.to_string(), part.manufacturer.clone(), part.mpn.clone()))
+            .collect();
+
+        BTreeMap::from([(Reference::from_str("top_1").unwrap(), load_out_items)])
+    }
+
+    #[test]
+    pub fn shared_setup() {
+        // given
+        let res1 = Part::new("RES_MFR1".to_string(), "RES1".to_string());
+        let res2 = Part::new("RES_MFR1".to_string(), "RES2".to_string());
+        let res3 = Part::new("RES_MFR1".to_string(), "RES3".to_string());
+        let cap1 = Part::new("CAP_MFR1".to_string(), "CAP1".to_string());
+
+        // and
+        let job1 = build_project("job1", "pnp", &[&res1, &res2, &cap1]);
+        let job1_load_out = build_load_out(&[("FEEDER_1", &res1), ("FEEDER_2", &res2), ("FEEDER_3", &cap1)]);
+        let job2 = build_project("job2", "pnp", &[&res1, &res2, &res3]);
+        let job2_load_out = build_load_out(&[("FEEDER_1", &res1), ("FEEDER_1", &res2)]);
+        let job3 = build_project("job3", "pnp", &[&res1]);
+        let job3_load_out = build_load_out(&[("", &res1)]);
+
+        // and a manual phase, which does not use the machine
+        let job4 = build_project("job4", "manual", &[&res3]);
+        let job4_load_out = build_load_out(&[]);
+
+        // when
+        let reuse = analyze_load_out_reuse(&[(&job1, &job1_load_out), (&job2, &job2_load_out), (&job3, &job3_load_out), (&job4, &job4_load_out)]);
+
+        // then
+        assert_eq!(reuse.shared_parts, vec![
+            SharedPart { part: res1.clone(), projects: vec!["job1".to_string(), "job2".to_string(), "job3".to_string()], feeder: Some("FEEDER_1".to_string()) },
+            // 'FEEDER_1' is suggested for 'RES1', which is used by more projects
+            SharedPart { part: res2.clone(), projects: vec!["job1".to_string(), "job2".to_string()], feeder: Some("FEEDER_2".to_string()) },
+        ]);
+
+        // and
+        assert_eq!(reuse.to_string(), indoc! {"
+            Projects: job1, job2, job3, job4
+            Shared setup: 2 feeders
+              FEEDER_1: RES_MFR1:RES1, projects: [job1, job2, job3]
+              FEEDER_2: RES_MFR1:RES2, projects: [job1, job2]
+            Additional feeders:
+              job1: 1 [CAP_MFR1:CAP1]
+              job2: 1 [RES_MFR1:RES3]
+              job3: 0 []
+              job4: 0 []
+        "});
+    }
+}
@@ -0,0 +1,194 @@
+//! Load-outs that are shared by more than one phase, e.g. when the phases use the same feeder bank of a machine.
+//!
+//! Phases share a load-out when they use the same load-out source, the quantities of the items of a shared load-out
+//! are aggregated over the phases for the report.  A feeder of a shared load-out can only hold one part, so when the
+//! phases require different parts in the same feeder the feeder is reported as a conflict.
+
+use std::collections::{BTreeMap, BTreeSet};
+use pnp::load_out::LoadOutItem;
+use pnp::part::Part;
+use crate::project::{count_phase_part_placements, Project};
+use crate::reference::Reference;
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub struct SharedLoadOut {
+    pub load_out_source: String,
+    /// In phase order.
+    pub phases: Vec<Reference>,
+    /// In load-out item order.
+    pub items: Vec<SharedLoadOutItem>,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub struct SharedLoadOutItem {
+    pub feeder_reference: String,
+    pub manufacturer: String,
+    pub mpn: String,
+    /// The total quantity required by the phases.
+    pub quantity: u32,
+    /// The quantity required by each phase, phases that do not require the part are omitted.
+    pub phase_quantities: BTreeMap<Reference, u32>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeederConflict {
+    pub load_out_source: String,
+    pub feeder_reference: String,
+    /// The parts required in the feeder, with the phases that require them, in part order.
+    pub parts: Vec<(Part, Vec<Reference>)>,
+}
+
+/// The phases that use each load-out source, i.e. the reference count of each load-out, the phases are in phase order.
+pub fn find_load_out_phases(project: &Project) -> BTreeMap<&str, Vec<&Reference>> {
+    project.phase_orderings.iter()
+        .filter_map(|reference| project.phases.get(reference))
+        .fold(BTreeMap::new(), |mut load_out_phases, phase| {
+            load_out_phases.entry(phase.load_out_source.as_str()).or_insert_with(Vec::new).push(&phase.reference);
+            load_out_phases
+        })
+}
+
+/// The load-outs used by more than one phase, in load-out source order.
+pub fn build_shared_load_outs(project: &Project, phase_load_out_items_map: &BTreeMap<Reference, Vec<LoadOutItem>>) -> Vec<SharedLoadOut> {
+    let phase_part_counts = count_phase_part_placements(project);
+
+    find_load_out_phases(project).into_iter()
+        .filter(|(_load_out_source, phases)| phases.len() > 1)
+        .map(|(load_out_source, phases)| {
+            // the phases use the same load-out source, so the items of any of the phases can be used
+            let mut load_out_items: Vec<&LoadOutItem> = phase_load_out_items_map.get(phases[0])
+                .map(|load_out_items| load_out_items.iter().collect())
+                .unwrap_or_default();
+            load_out_items.sort_by(|a, b| pnp::load_out::load_out_item_cmp(a, b));
+
+            let items = load_out_items.into_iter()
+                .map(|load_out_item| {
+                    let part = Part::new(load_out_item.manufacturer.clone(), load_out_item.mpn.clone());
+
+                    let phase_quantities: BTreeMap<Reference, u32> = phases.iter()
+                        .filter_map(|reference| {
+                            let counts = phase_part_counts.get(*reference)?.get(&part)?;
+                            Some(((*reference).clone(), counts.placed + counts.unplaced))
+                        })
+                        .collect();
+
+                    SharedLoadOutItem {
+                        feeder_reference: load_out_item.reference.clone(),
+                        manufacturer: load_out_item.manufacturer.clone(),
+                        mpn: load_out_item.mpn.clone(),
+                        quantity: phase_quantities.values().sum(),
+                        phase_quantities,
+                    }
+                })
+                .collect();
+
+            SharedLoadOut {
+                load_out_source: load_out_source.to_string(),
+                phases: phases.into_iter().cloned().collect(),
+                items,
+            }
+        })
+        .collect()
+}
+
+/// The feeders of the shared load-outs in which the phases require more than one part, items that have not been
+/// assigned to a feeder, and parts that are not required by any of the phases, are ignored.
+pub fn find_feeder_conflicts(shared_load_outs: &[SharedLoadOut]) -> Vec<FeederConflict> {
+    let mut conflicts = vec![];
+
+    for shared_load_out in shared_load_outs.iter() {
+        let mut feeder_parts: BTreeMap<&str, BTreeMap<Part, BTreeSet<Reference>>> = BTreeMap::new();
+
+        for item in shared_load_out.items.iter().filter(|item| !item.feeder_reference.is_empty() && item.quantity > 0) {
+            feeder_parts.entry(item.feeder_reference.as_str()).or_default()
+                .entry(Part::new(item.manufacturer.clone(), item.mpn.clone())).or_default()
+                .extend(item.phase_quantities.keys().cloned());
+        }
+
+        let mut load_out_conflicts: Vec<FeederConflict> = feeder_parts.into_iter()
+            .filter(|(_feeder_reference, parts)| parts.len() > 1)
+            .map(|(feeder_reference, parts)| FeederConflict {
+                load_out_source: shared_load_out.load_out_source.clone(),
+                feeder_reference: feeder_reference.to_string(),
+                parts: parts.into_iter().map(|(part, phases)| (part, phases.into_iter().collect())).collect(),
+            })
+            .collect();
+        load_out_conflicts.sort_by(|a, b| pnp::load_out::feeder_reference_cmp(&a.feeder_reference, &b.feeder_reference));
+
+        conflicts.extend(load_out_conflicts);
+    }
+
+    conflicts
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::str::FromStr;
+    use pnp::load_out::LoadOutItem;
+    use pnp::part::Part;
+    use pnp::pcb::PcbSide;
+    use crate::load_out_sharing::{build_shared_load_outs, find_feeder_conflicts, find_load_out_phases, FeederConflict, SharedLoadOutItem};
+    use crate::project::Project;
+    use crate::reference::Reference;
+    use crate::test::project_builder::{PlacementStateBuilder, ProjectBuilder};
+
+    fn build_project() -> Project {
+        let res1 = Part::new("RES_MFR1".to_string(), "RES1".to_string());
+        let res2 = Part::new("RES_MFR1".to_string(), "RES2".to_string());
+
+        ProjectBuilder::new()
+            .with_phase("top_1", "pnp", "load_out_1.csv", PcbSide::Top)
+            .with_phase("top_2", "pnp", "load_out_1.csv", PcbSide::Top)
+            .with_phase("top_3", "pnp", "load_out_3.csv", PcbSide::Top)
+            .with_placements([(1, "R1", &res1, "top_1"), (2, "R1", &res1, "top_2"), (2, "R2", &res2, "top_2")]
+                .map(|(unit, ref_des, part, phase)| PlacementStateBuilder::new(unit, ref_des, part).with_phase(phase)))
+            .build()
+    }
+
+    #[test]
+    pub fn shared_load_outs() {
+        // given
+        let project = build_project();
+        let top_1 = Reference::from_str("top_1").unwrap();
+        let top_2 = Reference::from_str("top_2").unwrap();
+
+        // and the same feeder is used for both parts
+        let load_out_items = || vec![
+            LoadOutItem::new("FEEDER_1".to_string(), "RES_MFR1".to_string(), "RES1".to_string()),
+            LoadOutItem::new("FEEDER_1".to_string(), "RES_MFR1".to_string(), "RES2".to_string()),
+        ];
+        let phase_load_out_items_map = BTreeMap::from([
+            (top_1.clone(), load_out_items()),
+            (top_2.clone(), load_out_items()),
+            (Reference::from_str("top_3").unwrap(), vec![]),
+        ]);
+
+        // expect
+        assert_eq!(find_load_out_phases(&project).get("load_out_1.csv"), Some(&vec![&top_1, &top_2]));
+
+        // when
+        let shared_load_outs = build_shared_load_outs(&project, &phase_load_out_items_map);
+
+        // then the load-out that is only used by 'top_3' is not shared
+        assert_eq!(shared_load_outs.len(), 1);
+        assert_eq!(shared_load_outs[0].phases, vec![top_1.clone(), top_2.clone()]);
+        assert_eq!(shared_load_outs[0].items, vec![
+            SharedLoadOutItem { feeder_reference: "FEEDER_1".to_string(), manufacturer: "RES_MFR1".to_string(), mpn: "RES1".to_string(), quantity: 2, phase_quantities: BTreeMap::from([(top_1.clone(), 1), (top_2.clone(), 1)]) },
+            SharedLoadOutItem { feeder_reference: "FEEDER_1".to_string(), manufacturer: "RES_MFR1".to_string(), mpn: "RES2".to_string(), quantity: 1, phase_quantities: BTreeMap::from([(top_2.clone(), 1)]) },
+        ]);
+
+        // when
+        let conflicts = find_feeder_conflicts(&shared_load_outs);
+
+        // then
+        assert_eq!(conflicts, vec![FeederConflict {
+            load_out_source: "load_out_1.csv".to_string(),
+            feeder_reference: "FEEDER_1".to_string(),
+            parts: vec![
+                (Part::new("RES_MFR1".to_string(), "RES1".to_string()), vec![top_1, top_2.clone()]),
+                (Part::new("RES_MFR1".to_string(), "RES2".to_string()), vec![top_2]),
+            ],
+        }]);
+    }
+}
@@ -0,0 +1,139 @@
+//! Advisory locking of project files, so that the planner and other tools, e.g. a GUI, can use the same project.
+//!
+//! A sibling lock file is locked instead of the project file, since saving replaces the project file.  The lock file
+//! is not removed, removing it would allow two processes to lock different files.  The plain and the compressed project
+//! files share the lock file, so that converting one to the other is done while holding a single lock.
+//!
+//! A process that changes the project holds the exclusive lock from loading the project until saving it, so that the
+//! changes of other processes are not lost.
+
+use std::fs::{File, OpenOptions, TryLockError};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tracing::trace;
+use crate::compression;
+
+const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Error, Debug)]
+#[error("Project is locked by another process, try again later. path: {path:?}")]
+pub struct ProjectLockedError {
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LockMode {
+    /// For reading, multiple processes can read at the same time.
+    Shared,
+    /// For writing.
+    Exclusive,
+}
+
+/// A held lock, the lock is released when dropped.
+#[derive(Debug)]
+pub struct ProjectLock {
+    _file: File,
+}
+
+/// e.g. 'project-job1.mpnp.json' or 'project-job1.mpnp.json.zst' -> 'project-job1.lock'
+pub fn build_lock_file_path(project_file_path: &Path) -> PathBuf {
+    let file_name = project_file_path.file_name().unwrap().to_string_lossy();
+    let file_name = file_name.strip_suffix(&format!(".{}", compression::COMPRESSED_EXTENSION)).unwrap_or(&file_name);
+    let name = file_name.strip_suffix(".mpnp.json").unwrap_or(file_name);
+
+    project_file_path.with_file_name(format!("{}.lock", name))
+}
+
+/// Waits up to a timeout for the lock, other processes hold the lock while reading, or while changing, the project.
+pub fn lock(project_file_path: &Path, mode: LockMode) -> anyhow::Result<ProjectLock> {
+    lock_with_timeout(project_file_path, mode, LOCK_TIMEOUT)
+}
+
+pub fn lock_with_timeout(project_file_path: &Path, mode: LockMode, timeout: Duration) -> anyhow::Result<ProjectLock> {
+    let lock_file_path = build_lock_file_path(project_file_path);
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_file_path)?;
+
+    let started_at = Instant::now();
+    loop {
+        let result = match mode {
+            LockMode::Shared => file.try_lock_shared(),
+            LockMode::Exclusive => file.try_lock(),
+        };
+
+        match result {
+            Ok(()) => {
+                trace!("Locked project. path: {:?}, mode: {:?}", lock_file_path, mode);
+                return Ok(ProjectLock { _file: file })
+            },
+            Err(TryLockError::WouldBlock) if started_at.elapsed() < timeout => thread::sleep(LOCK_RETRY_INTERVAL),
+            Err(TryLockError::WouldBlock) => return Err(ProjectLockedError { path: project_file_path.to_path_buf() }.into()),
+            Err(TryLockError::Error(error)) => return Err(error.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use tempfile::tempdir;
+    use crate::locking::{build_lock_file_path, lock, lock_with_timeout, LockMode, ProjectLockedError};
+    use crate::project::{build_compressed_project_file_path, build_plain_project_file_path};
+
+    const TIMEOUT: Duration = Duration::from_millis(100);
+
+    #[test]
+    pub fn exclusive_lock_excludes_other_locks() -> anyhow::Result<()> {
+        // given
+        let temp_dir = tempdir()?;
+        let project_file_path = temp_dir.path().join("project-job1.mpnp.json");
+
+        // and
+        let shared_lock = lock(&project_file_path, LockMode::Shared)?;
+        let other_shared_lock = lock(&project_file_path, LockMode::Shared)?;
+
+        // when
+        let result = lock_with_timeout(&project_file_path, LockMode::Exclusive, TIMEOUT);
+
+        // then
+        assert!(result.unwrap_err().downcast::<ProjectLockedError>().is_ok());
+
+        // when the locks are released
+        drop(shared_lock);
+        drop(other_shared_lock);
+
+        // then
+        let _exclusive_lock = lock(&project_file_path, LockMode::Exclusive)?;
+        assert!(lock_with_timeout(&project_file_path, LockMode::Shared, TIMEOUT).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn plain_and_compressed_project_files_share_a_lock() -> anyhow::Result<()> {
+        // given
+        let temp_dir = tempdir()?;
+        let plain_project_file_path = build_plain_project_file_path("job1", temp_dir.path());
+        let compressed_project_file_path = build_compressed_project_file_path(&plain_project_file_path);
+
+        // and
+        let _exclusive_lock = lock(&plain_project_file_path, LockMode::Exclusive)?;
+
+        // when
+        let result = lock_with_timeout(&compressed_project_file_path, LockMode::Shared, TIMEOUT);
+
+        // then
+        assert!(result.unwrap_err().downcast::<ProjectLockedError>().is_ok());
+
+        // and
+        assert_eq!(build_lock_file_path(&compressed_project_file_path), temp_dir.path().join("project-job1.lock"));
+
+        Ok(())
+    }
+}
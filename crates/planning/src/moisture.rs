@@ -0,0 +1,234 @@
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use thiserror::Error;
+use time::{Duration, OffsetDateTime};
+use tracing::trace;
+use pnp::part::Part;
+use crate::phase::FeederExposure;
+use crate::process::ProcessOperationStatus;
+use crate::project::Project;
+use crate::reference::Reference;
+use crate::report::{IssueKind, IssueSeverity, ProjectReportIssue};
+
+/// A warning is given when the floor life expires within this period, as the assembly of a phase is usually
+/// completed within a shift.
+pub const FLOOR_LIFE_WARNING_PERIOD: Duration = Duration::hours(8);
+
+/// Moisture sensitivity level, see IPC/JEDEC J-STD-033.
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MslLevel {
+    Level1,
+    Level2,
+    Level2a,
+    Level3,
+    Level4,
+    Level5,
+    Level5a,
+    /// Must be baked before use, the floor life is specified on the label.
+    Level6,
+}
+
+impl MslLevel {
+    /// The floor life at <= 30°C/60% RH, `None` if unlimited, or, for level 6, specified on the label.
+    pub fn default_floor_life(&self) -> Option<Duration> {
+        match self {
+            MslLevel::Level1 => None,
+            MslLevel::Level2 => Some(Duration::days(365)),
+            MslLevel::Level2a => Some(Duration::weeks(4)),
+            MslLevel::Level3 => Some(Duration::hours(168)),
+            MslLevel::Level4 => Some(Duration::hours(72)),
+            MslLevel::Level5 => Some(Duration::hours(48)),
+            MslLevel::Level5a => Some(Duration::hours(24)),
+            MslLevel::Level6 => None,
+        }
+    }
+}
+
+impl Display for MslLevel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MslLevel::Level1 => write!(f, "1"),
+            MslLevel::Level2 => write!(f, "2"),
+            MslLevel::Level2a => write!(f, "2a"),
+            MslLevel::Level3 => write!(f, "3"),
+            MslLevel::Level4 => write!(f, "4"),
+            MslLevel::Level5 => write!(f, "5"),
+            MslLevel::Level5a => write!(f, "5a"),
+            MslLevel::Level6 => write!(f, "6"),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("Invalid moisture sensitivity level, expected '1', '2', '2a', '3', '4', '5', '5a' or '6'. value: '{0:}'")]
+pub struct MslLevelError(String);
+
+/// e.g. '3', or 'MSL3', ignoring case.
+impl FromStr for MslLevel {
+    type Err = MslLevelError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let trimmed = value.trim().to_ascii_lowercase();
+        let level = trimmed.strip_prefix("msl").unwrap_or(&trimmed).trim();
+
+        match level {
+            "1" => Ok(MslLevel::Level1),
+            "2" => Ok(MslLevel::Level2),
+            "2a" => Ok(MslLevel::Level2a),
+            "3" => Ok(MslLevel::Level3),
+            "4" => Ok(MslLevel::Level4),
+            "5" => Ok(MslLevel::Level5),
+            "5a" => Ok(MslLevel::Level5a),
+            "6" => Ok(MslLevel::Level6),
+            _ => Err(MslLevelError(value.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq)]
+pub struct MoistureSensitivity {
+    pub level: MslLevel,
+
+    /// Overrides the default floor life of the level, required for level 6 parts.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub floor_life_hours: Option<u32>,
+}
+
+impl MoistureSensitivity {
+    pub fn floor_life(&self) -> Option<Duration> {
+        match self.floor_life_hours {
+            Some(hours) => Some(Duration::hours(hours as i64)),
+            None => self.level.default_floor_life(),
+        }
+    }
+}
+
+/// Adds issues for the feeders of incomplete phases where the floor life of the loaded part has been exceeded, or will
+/// be exceeded within `FLOOR_LIFE_WARNING_PERIOD`.
+pub fn add_floor_life_issues(project: &Project, now: OffsetDateTime, issues: &mut BTreeSet<ProjectReportIssue>) {
+    for (reference, phase_state) in project.phase_states.iter() {
+        let phase_complete = phase_state.operation_state.values()
+            .all(|operation_state| operation_state.status.eq(&ProcessOperationStatus::Complete));
+        if phase_complete {
+            continue
+        }
+
+        for (feeder_reference, exposure) in phase_state.feeder_exposures.iter() {
+            let Some(floor_life) = project.part_states.get(&exposure.part)
+                .and_then(|part_state| part_state.moisture_sensitivity.as_ref())
+                .and_then(MoistureSensitivity::floor_life) else {
+                continue
+            };
+
+            let remaining = floor_life - (now - exposure.loaded_at);
+            trace!("Floor life. phase: '{}', feeder: '{}', part: {:?}, remaining: {}", reference, feeder_reference, exposure.part, remaining);
+
+            if let Some(issue) = build_floor_life_issue(reference, feeder_reference, exposure, remaining) {
+                issues.insert(issue);
+            }
+        }
+    }
+}
+
+fn build_floor_life_issue(phase: &Reference, feeder_reference: &str, exposure: &FeederExposure, remaining: Duration) -> Option<ProjectReportIssue> {
+    let part: Part = exposure.part.clone();
+
+    if remaining.is_negative() || remaining.is_zero() {
+        Some(ProjectReportIssue {
+            message: "The floor life of a moisture sensitive part has been exceeded, bake before use".to_string(),
+            severity: IssueSeverity::Severe,
+            kind: IssueKind::FloorLifeExceeded { phase: phase.clone(), feeder_reference: feeder_reference.to_string(), part },
+        })
+    } else if remaining < FLOOR_LIFE_WARNING_PERIOD {
+        Some(ProjectReportIssue {
+            message: format!("The floor life of a moisture sensitive part expires in {} minutes", remaining.whole_minutes()),
+            severity: IssueSeverity::Warning,
+            kind: IssueKind::FloorLifeExpiring { phase: phase.clone(), feeder_reference: feeder_reference.to_string(), part },
+        })
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use std::str::FromStr;
+    use time::{Duration, OffsetDateTime};
+    use pnp::part::Part;
+    use crate::moisture::{add_floor_life_issues, MoistureSensitivity, MslLevel};
+    use crate::part::PartState;
+    use crate::phase::{FeederExposure, PhaseState};
+    use crate::project::{ProcessFactory, Project};
+    use crate::reference::Reference;
+    use crate::report::{IssueKind, IssueSeverity, ProjectReportIssue};
+
+    #[test]
+    pub fn parse_msl_level() {
+        assert_eq!(MslLevel::from_str("3").unwrap(), MslLevel::Level3);
+        assert_eq!(MslLevel::from_str(" MSL2a ").unwrap(), MslLevel::Level2a);
+        assert!(MslLevel::from_str("7").is_err());
+        assert!(MslLevel::from_str("").is_err());
+    }
+
+    fn build_project(msl_part: &Part, loaded_at: OffsetDateTime) -> Project {
+        let mut project = Project::default();
+        project.part_states.insert(msl_part.clone(), PartState {
+            moisture_sensitivity: Some(MoistureSensitivity { level: MslLevel::Level3, floor_life_hours: None }),
+            ..PartState::default()
+        });
+
+        let mut phase_state = PhaseState::from_process(&ProcessFactory::by_name("pnp").unwrap());
+        phase_state.feeder_exposures.insert("FEEDER_1".to_string(), FeederExposure { part: msl_part.clone(), loaded_at });
+        project.phase_states.insert(Reference::from_str("top_1").unwrap(), phase_state);
+
+        project
+    }
+
+    #[test]
+    pub fn floor_life_exceeded() {
+        // given
+        let part = Part::new("MCU_MFR1".to_string(), "MCU1".to_string());
+        let loaded_at = OffsetDateTime::UNIX_EPOCH;
+        let project = build_project(&part, loaded_at);
+
+        // and
+        let now = loaded_at + Duration::hours(169);
+
+        // when
+        let mut issues = BTreeSet::new();
+        add_floor_life_issues(&project, now, &mut issues);
+
+        // then
+        assert_eq!(issues.into_iter().collect::<Vec<_>>(), vec![ProjectReportIssue {
+            message: "The floor life of a moisture sensitive part has been exceeded, bake before use".to_string(),
+            severity: IssueSeverity::Severe,
+            kind: IssueKind::FloorLifeExceeded { phase: Reference::from_str("top_1").unwrap(), feeder_reference: "FEEDER_1".to_string(), part },
+        }]);
+    }
+
+    #[test]
+    pub fn floor_life_expiring() {
+        // given
+        let part = Part::new("MCU_MFR1".to_string(), "MCU1".to_string());
+        let loaded_at = OffsetDateTime::UNIX_EPOCH;
+        let project = build_project(&part, loaded_at);
+
+        // when
+        let mut early_issues = BTreeSet::new();
+        add_floor_life_issues(&project, loaded_at + Duration::hours(24), &mut early_issues);
+
+        let mut late_issues = BTreeSet::new();
+        add_floor_life_issues(&project, loaded_at + Duration::hours(166), &mut late_issues);
+
+        // then
+        assert!(early_issues.is_empty());
+
+        // and
+        let issue = late_issues.first().unwrap();
+        assert_eq!(issue.severity, IssueSeverity::Warning);
+        assert_eq!(issue.message, "The floor life of a moisture sensitive part expires in 120 minutes");
+    }
+}
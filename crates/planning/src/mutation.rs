@@ -0,0 +1,10 @@
+//! Mutations, each change to a project is logged as a single info event with the `MUTATION_TARGET` target, the changed
+//! values as fields and a message in which each `{field}` is replaced with the value of the field, both in the trace
+//! log and in the structured JSON log of the planner, which also records the fields as they are, the rendered messages
+//! are the changes recorded in the audit log, see `crate::audit`.
+//!
+//! The message is given as the `message` field, rather than as a format string, so that the values are not formatted
+//! into the message before the fields are recorded.
+
+/// e.g. `info!(target: MUTATION_TARGET, phase = %reference, message = "Removed phase. phase: '{phase}'")`.
+pub const MUTATION_TARGET: &str = "mutation";
@@ -0,0 +1,193 @@
+//! Nozzle assignment for the placements of a phase, using the nozzle configuration of the machine used by the phase.
+//!
+//! Each placement is assigned the first nozzle, in configuration order, that can pick the package of the part, the
+//! `Nozzle` placement ordering then groups the placements by nozzle, so that the nozzles are changed as few times as
+//! possible.  The heads of the machine each hold one nozzle, so up to `heads` nozzles can be used without a change.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use thiserror::Error;
+use pnp::object_path::ObjectPath;
+use pnp::part::Part;
+use crate::placement::PlacementState;
+use crate::project::Project;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct NozzleConfiguration {
+    /// Count of heads, each head holds one nozzle.
+    pub heads: u32,
+    /// In order of preference.
+    pub nozzles: Vec<Nozzle>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Nozzle {
+    /// e.g. 'N1'
+    pub reference: String,
+    /// The packages the nozzle can pick, e.g. '0402', compared case-insensitively.
+    pub packages: Vec<String>,
+}
+
+#[derive(Error, Debug)]
+#[error("Invalid nozzle, expected '<REFERENCE>=<PACKAGE>[,<PACKAGE>...]', e.g. 'N1=0402,0603'. value: '{0:}'")]
+pub struct NozzleError(String);
+
+impl FromStr for Nozzle {
+    type Err = NozzleError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let Some((reference, packages)) = value.split_once('=') else {
+            return Err(NozzleError(value.to_string()))
+        };
+
+        let packages: Vec<String> = packages.split(',')
+            .map(str::trim)
+            .filter(|package| !package.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        if reference.trim().is_empty() || packages.is_empty() {
+            return Err(NozzleError(value.to_string()))
+        }
+
+        Ok(Nozzle { reference: reference.trim().to_string(), packages })
+    }
+}
+
+impl Display for Nozzle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}={}", self.reference, self.packages.join(","))
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct NozzleAssignments {
+    /// The index of the nozzle in the configuration, and the nozzle reference, for each placement.
+    pub nozzles: BTreeMap<ObjectPath, (usize, String)>,
+    /// Parts without a package, or whose package cannot be picked by any of the nozzles.
+    pub unassigned_parts: BTreeSet<Part>,
+}
+
+impl NozzleAssignments {
+    pub fn find_nozzle(&self, object_path: &ObjectPath) -> Option<&String> {
+        self.nozzles.get(object_path).map(|(_index, reference)| reference)
+    }
+}
+
+/// Only the placements that are to be placed are assigned a nozzle.
+pub fn assign_nozzles(project: &Project, configuration: &NozzleConfiguration, placement_states: &[(&ObjectPath, &PlacementState)]) -> NozzleAssignments {
+    let mut assignments = NozzleAssignments::default();
+
+    for (object_path, placement_state) in placement_states.iter().filter(|(_object_path, placement_state)| placement_state.place()) {
+        let part = &placement_state.placement.part;
+
+        let package = project.part_states.get(part)
+            .and_then(|part_state| part_state.details.package.as_ref());
+
+        let nozzle = package.and_then(|package| configuration.nozzles.iter()
+            .enumerate()
+            .find(|(_index, nozzle)| nozzle.packages.iter().any(|nozzle_package| nozzle_package.eq_ignore_ascii_case(package))));
+
+        match nozzle {
+            Some((index, nozzle)) => {
+                assignments.nozzles.insert((*object_path).clone(), (index, nozzle.reference.clone()));
+            },
+            None => {
+                assignments.unassigned_parts.insert(part.clone());
+            },
+        }
+    }
+
+    assignments
+}
+
+/// Counts the nozzle changes needed to place the placements in the order given, the least recently used nozzle is
+/// replaced when all the heads hold a nozzle.  Loading the first nozzles onto the empty heads is not counted.
+pub fn count_nozzle_changes<'a>(nozzles: impl Iterator<Item = &'a String>, heads: u32) -> usize {
+    let mut loaded: Vec<&String> = vec![];
+    let mut changes = 0;
+
+    for nozzle in nozzles {
+        if let Some(index) = loaded.iter().position(|loaded_nozzle| (*loaded_nozzle).eq(nozzle)) {
+            let nozzle = loaded.remove(index);
+            loaded.push(nozzle);
+            continue
+        }
+
+        if loaded.len() >= heads.max(1) as usize {
+            loaded.remove(0);
+            changes += 1;
+        }
+        loaded.push(nozzle);
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use pnp::object_path::ObjectPath;
+    use pnp::part::{Part, PartDetails};
+    use crate::nozzle::{assign_nozzles, count_nozzle_changes, Nozzle, NozzleConfiguration};
+    use crate::part::PartState;
+    use crate::placement::PlacementState;
+    use crate::test::project_builder::{PlacementStateBuilder, ProjectBuilder};
+
+    #[test]
+    pub fn parse_nozzle() {
+        // expect
+        assert_eq!(Nozzle::from_str("N1=0402, 0603").unwrap(), Nozzle { reference: "N1".to_string(), packages: vec!["0402".to_string(), "0603".to_string()] });
+        assert!(Nozzle::from_str("N1").is_err());
+        assert!(Nozzle::from_str("N1=").is_err());
+    }
+
+    #[test]
+    pub fn assign() {
+        // given
+        let res1 = Part::new("RES_MFR1".to_string(), "RES1".to_string());
+        let cap1 = Part::new("CAP_MFR1".to_string(), "CAP1".to_string());
+        let conn1 = Part::new("CONN_MFR1".to_string(), "CONN1".to_string());
+
+        let mut project = ProjectBuilder::new().build();
+        for (part, package) in [(&res1, Some("0402")), (&cap1, Some("0805")), (&conn1, None)] {
+            project.part_states.insert(part.clone(), PartState {
+                details: PartDetails { image: None, datasheet: None, package: package.map(str::to_string), ..PartDetails::default() },
+                ..PartState::default()
+            });
+        }
+
+        // and
+        let placement_states = [("R1", &res1), ("C1", &cap1), ("J1", &conn1)]
+            .map(|(ref_des, part)| PlacementStateBuilder::new(1, ref_des, part).build());
+        let placement_states: Vec<(&ObjectPath, &PlacementState)> = placement_states.iter()
+            .map(|(object_path, placement_state)| (object_path, placement_state))
+            .collect();
+
+        // and
+        let configuration = NozzleConfiguration {
+            heads: 1,
+            nozzles: vec![Nozzle::from_str("N1=0402,0603").unwrap(), Nozzle::from_str("N2=0603,0805").unwrap()],
+        };
+
+        // when
+        let assignments = assign_nozzles(&project, &configuration, &placement_states);
+
+        // then
+        assert_eq!(assignments.find_nozzle(&ObjectPath::from_str("panel=1::unit=1::ref_des=R1").unwrap()), Some(&"N1".to_string()));
+        assert_eq!(assignments.find_nozzle(&ObjectPath::from_str("panel=1::unit=1::ref_des=C1").unwrap()), Some(&"N2".to_string()));
+        assert_eq!(assignments.unassigned_parts.into_iter().collect::<Vec<Part>>(), vec![conn1]);
+    }
+
+    #[test]
+    pub fn nozzle_changes() {
+        // given
+        let nozzles: Vec<String> = ["N1", "N2", "N1", "N3", "N1"].iter().map(|nozzle| nozzle.to_string()).collect();
+
+        // expect
+        assert_eq!(count_nozzle_changes(nozzles.iter(), 1), 4);
+        assert_eq!(count_nozzle_changes(nozzles.iter(), 2), 1);
+        assert_eq!(count_nozzle_changes(nozzles.iter(), 3), 0);
+    }
+}
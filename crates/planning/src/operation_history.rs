@@ -1,18 +1,22 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::fs::File;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use anyhow::Error;
+use rust_decimal::Decimal;
 use serde::Serialize;
 use serde_json::Value;
 use serde_with::serde_as;
 use serde_with::DisplayFromStr;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
 use time::serde::rfc3339;
 use time::OffsetDateTime;
 use tracing::info;
 use crate::placement::PlacementOperation;
-use crate::process::ProcessOperationStatus;
+use crate::process::{ProcessOperationKind, ProcessOperationStatus};
 use crate::reference::Reference;
 use pnp::object_path::ObjectPath;
+use pnp::part::Part;
 
 #[serde_as]
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
@@ -22,11 +26,48 @@ pub enum OperationHistoryKind {
     AutomatedPnp { status: ProcessOperationStatus },
     ReflowComponents { status: ProcessOperationStatus },
     ManuallySolderComponents { status: ProcessOperationStatus },
+    /// An operation of a process definition, see `ProcessOperationKind::Custom`.
+    CustomOperation { operation: String, status: ProcessOperationStatus },
     PlacementOperation {
         #[serde_as(as = "DisplayFromStr")]
         object_path: ObjectPath,
         operation: PlacementOperation
     },
+    PartRenamed { from: Part, to: Part },
+    FeederLoaded { feeder_reference: String, part: Part },
+    /// The items of the checklist of the operation that were confirmed when the operation was started.
+    OperationStarted {
+        operation: ProcessOperationKind,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        #[serde(default)]
+        confirmed: Vec<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(default)]
+        operator: Option<String>,
+    },
+    /// The first article of the phase was signed off, see `first_article`.
+    FirstArticleInspected {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(default)]
+        operator: Option<String>,
+    },
+    PhaseRenamed { from: Reference, to: Reference },
+}
+
+impl OperationHistoryKind {
+    /// The operation that was completed by the history item, if any.
+    pub fn completed_operation(&self) -> Option<ProcessOperationKind> {
+        let (operation, status) = match self {
+            OperationHistoryKind::LoadPcbs { status } => (ProcessOperationKind::LoadPcbs, status),
+            OperationHistoryKind::AutomatedPnp { status } => (ProcessOperationKind::AutomatedPnp, status),
+            OperationHistoryKind::ReflowComponents { status } => (ProcessOperationKind::ReflowComponents, status),
+            OperationHistoryKind::ManuallySolderComponents { status } => (ProcessOperationKind::ManuallySolderComponents, status),
+            OperationHistoryKind::CustomOperation { operation, status } => (ProcessOperationKind::Custom(operation.clone()), status),
+            _ => return None,
+        };
+
+        matches!(status, ProcessOperationStatus::Complete).then_some(operation)
+    }
 }
 
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
@@ -36,8 +77,285 @@ pub struct OperationHistoryItem {
     pub phase: Reference,
     pub operation: OperationHistoryKind,
 
+    /// Hash of the previous item, `None` for the first item.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub previous_hash: Option<String>,
+
+    /// Hash of this item, including the `previous_hash`, so that each item is chained to the previous items.
+    ///
+    /// `None` for items written before the history was chained.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub hash: Option<String>,
+
     #[serde(flatten)]
-    pub extra: HashMap<String, Value>
+    pub extra: BTreeMap<String, Value>
+}
+
+impl OperationHistoryItem {
+    pub fn new(date_time: OffsetDateTime, phase: Reference, operation: OperationHistoryKind) -> Self {
+        Self {
+            date_time,
+            phase,
+            operation,
+            previous_hash: None,
+            hash: None,
+            extra: Default::default(),
+        }
+    }
+
+    /// Hex encoded SHA-256 of the item, excluding the `hash` field.
+    pub fn build_hash(&self) -> String {
+        let item = OperationHistoryItem { hash: None, ..self.clone() };
+
+        // serializing the item cannot fail, all map keys are strings
+        let content = serde_json::to_vec(&item).unwrap();
+
+        hex::encode(Sha256::digest(&content))
+    }
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum OperationHistoryError {
+    #[error("Unchained item, all items of a chained history must be chained, the hash has been removed. index: {index}")]
+    UnchainedItem { index: usize },
+
+    #[error("Item modified, the hash of the item does not match. index: {index}")]
+    ItemModified { index: usize },
+
+    #[error("Chain broken, items have been removed, inserted or re-ordered. index: {index}")]
+    ChainBroken { index: usize },
+
+    #[error("Head not found, items have been removed from the end. head: '{head}'")]
+    HeadNotFound { head: String },
+
+    #[error("History not chained, the hashes have been removed or the history was written before it was chained. head: '{head}'")]
+    NotChained { head: String },
+}
+
+#[derive(Debug, PartialEq)]
+pub struct OperationHistoryVerification {
+    /// Count of items, including unchained items.
+    pub items: usize,
+    /// Count of items of a history that was written before it was chained, these cannot be verified, either all the
+    /// items or none of them.
+    pub unchained_items: usize,
+    /// Hash of the last item, record it to be able to detect the removal of items from the end of the history later.
+    pub head: Option<String>,
+}
+
+/// Chains the items to the existing history, and appends them.
+///
+/// A history that was written before it was chained is chained first, so that a history is either entirely unchained
+/// or entirely chained.
+pub fn append(operation_history: &mut Vec<OperationHistoryItem>, items: Vec<OperationHistoryItem>) {
+    if operation_history.iter().all(|item| item.hash.is_none()) {
+        chain(operation_history, 0);
+    }
+
+    for mut item in items {
+        item.previous_hash = operation_history.last().and_then(|previous| previous.hash.clone());
+        item.hash = Some(item.build_hash());
+
+        operation_history.push(item);
+    }
+}
+
+/// Verifies the hash of each item, and that each item is chained to the previous item.
+///
+/// The items of a history that was written before it was chained are counted but cannot be verified, an item without
+/// a hash in a chained history is an item whose hash has been removed.
+///
+/// If a previously recorded head is given, it must be the hash of one of the items, so an unchained history fails.
+pub fn verify(operation_history: &[OperationHistoryItem], head: Option<&str>) -> Result<OperationHistoryVerification, OperationHistoryError> {
+    if operation_history.iter().all(|item| item.hash.is_none()) {
+        if let Some(head) = head {
+            return Err(OperationHistoryError::NotChained { head: head.to_string() })
+        }
+
+        return Ok(OperationHistoryVerification {
+            items: operation_history.len(),
+            unchained_items: operation_history.len(),
+            head: None,
+        })
+    }
+
+    for (index, item) in operation_history.iter().enumerate() {
+        let Some(hash) = &item.hash else {
+            return Err(OperationHistoryError::UnchainedItem { index })
+        };
+
+        if item.build_hash().ne(hash) {
+            return Err(OperationHistoryError::ItemModified { index })
+        }
+
+        let expected_previous_hash = match index {
+            0 => None,
+            _ => operation_history[index - 1].hash.clone(),
+        };
+
+        if item.previous_hash.ne(&expected_previous_hash) {
+            return Err(OperationHistoryError::ChainBroken { index })
+        }
+    }
+
+    if let Some(head) = head {
+        if !operation_history.iter().any(|item| item.hash.as_deref().eq(&Some(head))) {
+            return Err(OperationHistoryError::HeadNotFound { head: head.to_string() })
+        }
+    }
+
+    Ok(OperationHistoryVerification {
+        items: operation_history.len(),
+        unchained_items: 0,
+        head: operation_history.last().and_then(|item| item.hash.clone()),
+    })
+}
+
+/// Chains the items, starting with the item at the index, to the previous items.
+fn chain(operation_history: &mut [OperationHistoryItem], start_index: usize) {
+    for index in start_index..operation_history.len() {
+        let previous_hash = match index {
+            0 => None,
+            _ => operation_history[index - 1].hash.clone(),
+        };
+
+        let item = &mut operation_history[index];
+        item.previous_hash = previous_hash;
+        item.hash = Some(item.build_hash());
+    }
+}
+
+/// Renames the phase of the items, the history is verified first and the chained items are then re-chained.
+///
+/// A previously recorded head is no longer found after renaming, record the new head.
+pub fn rename_phase(operation_history: &mut [OperationHistoryItem], from: &Reference, to: &Reference) -> Result<(), OperationHistoryError> {
+    let verification = verify(operation_history, None)?;
+
+    for item in operation_history.iter_mut().filter(|item| item.phase.eq(from)) {
+        item.phase = to.clone();
+    }
+
+    chain(operation_history, verification.unchained_items);
+
+    Ok(())
+}
+
+/// Selects the items of an operation history by date and time, the bounds are optional, `until` is exclusive.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OperationHistoryFilter {
+    pub since: Option<OffsetDateTime>,
+    pub until: Option<OffsetDateTime>,
+}
+
+impl OperationHistoryFilter {
+    pub fn matches(&self, item: &OperationHistoryItem) -> bool {
+        self.since.is_none_or(|since| item.date_time >= since)
+            && self.until.is_none_or(|until| item.date_time < until)
+    }
+
+    pub fn apply<'a>(&self, operation_history: &'a [OperationHistoryItem]) -> Vec<&'a OperationHistoryItem> {
+        operation_history.iter()
+            .filter(|item| self.matches(item))
+            .collect()
+    }
+}
+
+/// The times of an operation, in seconds, from when the operation was started until it was completed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OperationMetrics {
+    pub operation: ProcessOperationKind,
+    pub cycles: usize,
+    pub total_time: i64,
+    pub mean_time: i64,
+}
+
+/// Throughput statistics of an operation history.
+///
+/// The placements per hour are from the time of the first placed placement until the time of the last, `None` if
+/// there are fewer than two placed placements at different times.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OperationHistoryMetrics {
+    pub items: usize,
+    pub placed: usize,
+    pub unplaced: usize,
+    pub inspection_failed: usize,
+    pub first_placed_at: Option<OffsetDateTime>,
+    pub last_placed_at: Option<OffsetDateTime>,
+    pub placements_per_hour: Option<Decimal>,
+    /// In the order the operations were first completed.
+    pub operations: Vec<OperationMetrics>,
+}
+
+/// Operations that are completed without having been started, see `OperationStarted`, are not timed.
+pub fn build_metrics(operation_history: &[&OperationHistoryItem]) -> OperationHistoryMetrics {
+    let mut placed = 0;
+    let mut unplaced = 0;
+    let mut inspection_failed = 0;
+    let mut first_placed_at: Option<OffsetDateTime> = None;
+    let mut last_placed_at: Option<OffsetDateTime> = None;
+
+    let mut started: BTreeMap<ProcessOperationKind, OffsetDateTime> = BTreeMap::new();
+    let mut operations: Vec<OperationMetrics> = vec![];
+
+    for item in operation_history.iter() {
+        match &item.operation {
+            OperationHistoryKind::OperationStarted { operation, .. } => {
+                started.insert(operation.clone(), item.date_time);
+            },
+            OperationHistoryKind::PlacementOperation { operation, .. } => match operation {
+                PlacementOperation::Placed => {
+                    placed += 1;
+                    first_placed_at = first_placed_at.or(Some(item.date_time));
+                    last_placed_at = Some(item.date_time);
+                },
+                PlacementOperation::Unplaced => unplaced += 1,
+                PlacementOperation::InspectionFailed => inspection_failed += 1,
+            },
+            kind => {
+                let Some(operation) = kind.completed_operation() else {
+                    continue
+                };
+                let Some(started_at) = started.remove(&operation) else {
+                    continue
+                };
+                let time = (item.date_time - started_at).whole_seconds();
+
+                match operations.iter_mut().find(|metrics| metrics.operation.eq(&operation)) {
+                    Some(metrics) => {
+                        metrics.cycles += 1;
+                        metrics.total_time += time;
+                        metrics.mean_time = metrics.total_time / metrics.cycles as i64;
+                    },
+                    None => operations.push(OperationMetrics { operation, cycles: 1, total_time: time, mean_time: time }),
+                }
+            },
+        }
+    }
+
+    let placements_per_hour = match (first_placed_at, last_placed_at) {
+        (Some(first_placed_at), Some(last_placed_at)) if last_placed_at > first_placed_at => {
+            let seconds = Decimal::from((last_placed_at - first_placed_at).whole_seconds().max(1));
+            Some((Decimal::from(placed) * Decimal::from(3600) / seconds).round_dp(1))
+        },
+        _ => None,
+    };
+
+    OperationHistoryMetrics {
+        items: operation_history.len(),
+        placed,
+        unplaced,
+        inspection_failed,
+        first_placed_at,
+        last_placed_at,
+        placements_per_hour,
+        operations,
+    }
+}
+
+pub fn build_phase_log_path(path: &Path, reference: &Reference) -> PathBuf {
+    path.join(format!("{}_log.json", reference))
 }
 
 pub fn write(phase_log_path: PathBuf, operation_history: &Vec<OperationHistoryItem>) -> Result<(), Error> {
@@ -58,16 +376,245 @@ pub fn write(phase_log_path: PathBuf, operation_history: &Vec<OperationHistoryIt
     Ok(())
 }
 
-pub fn read_or_default(phase_log_path: &PathBuf) -> Result<Vec<OperationHistoryItem>, Error> {
+pub fn read_or_default(phase_log_path: &Path) -> Result<Vec<OperationHistoryItem>, Error> {
     let is_new = !phase_log_path.exists();
     if is_new {
         return Ok(Default::default());
     }
 
     // TODO use a context for better error messages
-    let file = File::open(phase_log_path.clone())?;
+    let file = File::open(phase_log_path)?;
 
     let operation_history = serde_json::from_reader(file)?;
 
     Ok(operation_history)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use time::OffsetDateTime;
+    use rust_decimal_macros::dec;
+    use pnp::object_path::ObjectPath;
+    use crate::operation_history::{append, build_metrics, rename_phase, verify, OperationHistoryError, OperationHistoryFilter, OperationHistoryItem, OperationHistoryKind, OperationHistoryVerification, OperationMetrics};
+    use crate::placement::PlacementOperation;
+    use crate::process::{ProcessOperationKind, ProcessOperationStatus};
+    use crate::reference::Reference;
+
+    fn build_items(count: usize) -> Vec<OperationHistoryItem> {
+        (0..count).map(|index| OperationHistoryItem::new(
+            OffsetDateTime::UNIX_EPOCH + time::Duration::minutes(index as i64),
+            Reference::from_str("top_1").unwrap(),
+            OperationHistoryKind::LoadPcbs { status: ProcessOperationStatus::Complete },
+        )).collect()
+    }
+
+    #[test]
+    pub fn append_and_verify() {
+        // given
+        let mut operation_history = vec![];
+
+        // when
+        append(&mut operation_history, build_items(2));
+        append(&mut operation_history, build_items(1));
+
+        // then
+        assert_eq!(operation_history[0].previous_hash, None);
+        assert_eq!(operation_history[1].previous_hash, operation_history[0].hash);
+        assert_eq!(operation_history[2].previous_hash, operation_history[1].hash);
+
+        // and
+        assert_eq!(verify(&operation_history, None), Ok(OperationHistoryVerification {
+            items: 3,
+            unchained_items: 0,
+            head: operation_history[2].hash.clone(),
+        }));
+    }
+
+    #[test]
+    pub fn verify_modified_item() {
+        // given
+        let mut operation_history = vec![];
+        append(&mut operation_history, build_items(3));
+
+        // when
+        operation_history[1].operation = OperationHistoryKind::LoadPcbs { status: ProcessOperationStatus::Incomplete };
+
+        // then
+        assert_eq!(verify(&operation_history, None), Err(OperationHistoryError::ItemModified { index: 1 }));
+    }
+
+    #[test]
+    pub fn verify_removed_items() {
+        // given
+        let mut operation_history = vec![];
+        append(&mut operation_history, build_items(3));
+
+        // when
+        let mut without_second_item = operation_history.clone();
+        without_second_item.remove(1);
+
+        // then
+        assert_eq!(verify(&without_second_item, None), Err(OperationHistoryError::ChainBroken { index: 1 }));
+
+        // when
+        let mut without_first_item = operation_history.clone();
+        without_first_item.remove(0);
+
+        // then
+        assert_eq!(verify(&without_first_item, None), Err(OperationHistoryError::ChainBroken { index: 0 }));
+
+        // when
+        let head = operation_history[2].hash.clone().unwrap();
+        let mut without_last_item = operation_history.clone();
+        without_last_item.remove(2);
+
+        // then
+        assert_eq!(verify(&without_last_item, Some(&head)), Err(OperationHistoryError::HeadNotFound { head: head.clone() }));
+
+        // and
+        assert!(verify(&operation_history, Some(&head)).is_ok());
+    }
+
+    #[test]
+    pub fn verify_history_written_before_chaining() {
+        // given
+        let mut operation_history = build_items(2);
+
+        // expect
+        assert_eq!(verify(&operation_history, None), Ok(OperationHistoryVerification { items: 2, unchained_items: 2, head: None }));
+
+        // and a head cannot be verified
+        assert_eq!(verify(&operation_history, Some("head")), Err(OperationHistoryError::NotChained { head: "head".to_string() }));
+
+        // when
+        append(&mut operation_history, build_items(1));
+
+        // then the history is chained
+        let verification = verify(&operation_history, None).unwrap();
+        assert_eq!(verification.unchained_items, 0);
+        assert_eq!(verification.head, operation_history[2].hash);
+
+        // when
+        operation_history.push(build_items(1).remove(0));
+
+        // then
+        assert_eq!(verify(&operation_history, None), Err(OperationHistoryError::UnchainedItem { index: 3 }));
+    }
+
+    #[test]
+    pub fn verify_removed_hashes() {
+        // given
+        let mut operation_history = vec![];
+        append(&mut operation_history, build_items(3));
+        let head = operation_history[2].hash.clone().unwrap();
+
+        // when the hashes of the first items are removed
+        let mut without_first_hashes = operation_history.clone();
+        without_first_hashes[0].hash = None;
+        without_first_hashes[1].hash = None;
+
+        // then
+        assert_eq!(verify(&without_first_hashes, None), Err(OperationHistoryError::UnchainedItem { index: 0 }));
+
+        // when all the hashes are removed
+        let mut without_hashes = operation_history.clone();
+        for item in without_hashes.iter_mut() {
+            item.hash = None;
+            item.previous_hash = None;
+        }
+
+        // then the history can only be verified against a previously recorded head
+        assert_eq!(verify(&without_hashes, Some(&head)), Err(OperationHistoryError::NotChained { head }));
+    }
+
+    #[test]
+    pub fn rename_phase_and_rechain() {
+        // given
+        let mut operation_history = build_items(1);
+        append(&mut operation_history, build_items(2));
+        let head = operation_history[2].hash.clone().unwrap();
+
+        // and
+        let from = Reference::from_str("top_1").unwrap();
+        let to = Reference::from_str("top_a").unwrap();
+
+        // when
+        rename_phase(&mut operation_history, &from, &to).unwrap();
+
+        // then
+        assert!(operation_history.iter().all(|item| item.phase.eq(&to)));
+
+        // and the history is re-chained
+        assert_eq!(verify(&operation_history, None).unwrap().unchained_items, 0);
+
+        // and the previous head is no longer found
+        assert!(matches!(verify(&operation_history, Some(&head)), Err(OperationHistoryError::HeadNotFound { .. })));
+
+        // when the history was written before it was chained
+        let mut unchained_operation_history = build_items(2);
+        rename_phase(&mut unchained_operation_history, &from, &to).unwrap();
+
+        // then it is still unchained
+        assert_eq!(verify(&unchained_operation_history, None).unwrap().unchained_items, 2);
+    }
+
+    #[test]
+    pub fn rename_phase_with_modified_item() {
+        // given
+        let mut operation_history = vec![];
+        append(&mut operation_history, build_items(2));
+        operation_history[0].operation = OperationHistoryKind::LoadPcbs { status: ProcessOperationStatus::Incomplete };
+
+        // when
+        let result = rename_phase(&mut operation_history, &Reference::from_str("top_1").unwrap(), &Reference::from_str("top_a").unwrap());
+
+        // then
+        assert_eq!(result, Err(OperationHistoryError::ItemModified { index: 0 }));
+    }
+
+    #[test]
+    pub fn metrics() {
+        // given
+        let at = |minutes: i64| OffsetDateTime::UNIX_EPOCH + time::Duration::minutes(minutes);
+        let placed = |ref_des: &str| OperationHistoryKind::PlacementOperation {
+            object_path: ObjectPath::from_str(&format!("panel=1::unit=1::ref_des={}", ref_des)).unwrap(),
+            operation: PlacementOperation::Placed,
+        };
+        let reference = Reference::from_str("top_1").unwrap();
+        let operation_history: Vec<OperationHistoryItem> = [
+            (0, OperationHistoryKind::OperationStarted { operation: ProcessOperationKind::AutomatedPnp, confirmed: vec![], operator: None }),
+            (10, placed("R1")),
+            (20, placed("R2")),
+            (40, placed("R3")),
+            (50, OperationHistoryKind::AutomatedPnp { status: ProcessOperationStatus::Complete }),
+            (60, OperationHistoryKind::LoadPcbs { status: ProcessOperationStatus::Complete }),
+            (120, OperationHistoryKind::OperationStarted { operation: ProcessOperationKind::AutomatedPnp, confirmed: vec![], operator: None }),
+            (190, OperationHistoryKind::AutomatedPnp { status: ProcessOperationStatus::Complete }),
+        ].into_iter().map(|(minutes, kind)| OperationHistoryItem::new(at(minutes), reference.clone(), kind)).collect();
+
+        // when
+        let metrics = build_metrics(&OperationHistoryFilter::default().apply(&operation_history));
+
+        // then
+        assert_eq!(metrics.items, 8);
+        assert_eq!(metrics.placed, 3);
+        assert_eq!((metrics.first_placed_at, metrics.last_placed_at), (Some(at(10)), Some(at(40))));
+        assert_eq!(metrics.placements_per_hour, Some(dec!(6.0)));
+
+        // and the load pcbs operation was not started, so it is not timed
+        assert_eq!(metrics.operations, vec![
+            OperationMetrics { operation: ProcessOperationKind::AutomatedPnp, cycles: 2, total_time: 7200, mean_time: 3600 },
+        ]);
+
+        // when
+        let filter = OperationHistoryFilter { since: Some(at(20)), until: Some(at(120)) };
+        let metrics = build_metrics(&filter.apply(&operation_history));
+
+        // then
+        assert_eq!(metrics.items, 4);
+        assert_eq!(metrics.placed, 2);
+        assert_eq!(metrics.placements_per_hour, Some(dec!(6.0)));
+        assert!(metrics.operations.is_empty());
+    }
+}
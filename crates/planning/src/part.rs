@@ -1,4 +1,6 @@
 use std::collections::BTreeSet;
+use pnp::part::PartDetails;
+use crate::moisture::MoistureSensitivity;
 use crate::process::ProcessName;
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, Clone, Default)]
@@ -7,4 +9,12 @@ pub struct PartState {
     #[serde(skip_serializing_if = "BTreeSet::is_empty")]
     #[serde(default)]
     pub applicable_processes: BTreeSet<ProcessName>,
-}
\ No newline at end of file
+
+    #[serde(skip_serializing_if = "PartDetails::is_empty")]
+    #[serde(default)]
+    pub details: PartDetails,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub moisture_sensitivity: Option<MoistureSensitivity>,
+}
@@ -1,11 +1,21 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 use indexmap::IndexSet;
 use thiserror::Error;
 use crate::reference::Reference;
 use pnp::pcb::PcbSide;
-use crate::placement::PlacementSortingItem;
-use crate::process::{Process, ProcessName, ProcessOperationKind, ProcessOperationState};
+use pnp::part::Part;
+use time::OffsetDateTime;
+use time::serde::rfc3339;
+use crate::first_article::FirstArticleInspection;
+use crate::nozzle::NozzleConfiguration;
+use crate::placement::{PlacementSortingItem, RotationNormalization};
+use crate::quantity_check::QuantityCheckMode;
+use crate::export::ExportFormat;
+use crate::process::{Process, ProcessName, ProcessOperationKind, ProcessOperationState, ProcessOperationStatus};
+use crate::project::Project;
+use crate::certificate;
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Phase {
@@ -19,16 +29,120 @@ pub struct Phase {
 
     #[serde(skip_serializing_if = "Vec::is_empty")]
     #[serde(default)]
-    pub placement_orderings: Vec<PlacementSortingItem>
+    pub placement_orderings: Vec<PlacementSortingItem>,
+
+    #[serde(skip_serializing_if = "WorkInstructionsStyle::is_default")]
+    #[serde(default)]
+    pub work_instructions_style: WorkInstructionsStyle,
+
+    /// Key/value tags, for shop terminology, e.g. 'line=A'.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
+
+    /// The first article must be signed off before the other PCB units are placed, see `first_article`.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    #[serde(default)]
+    pub first_article_inspection_required: bool,
+
+    /// The nozzles of the machine, used to group the placements by nozzle, see `nozzle`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub nozzle_configuration: Option<NozzleConfiguration>,
+
+    /// Checks the remaining load-out item quantities before a placement operation is started, see `quantity_check`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub quantity_check: Option<QuantityCheckMode>,
+
+    /// The machine-native format the placements are exported in, see `export`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub export_format: Option<ExportFormat>,
+
+    /// The range and per-part offsets of the rotations of the artifacts, see `placement::RotationNormalization`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub rotation_normalization: Option<RotationNormalization>,
+
+    /// Phases that must be complete before the phase is started, e.g. the reflow of the top side before the manual
+    /// assembly of the bottom side, see `build_execution_plan`.
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    #[serde(default)]
+    pub dependencies: BTreeSet<Reference>,
+}
+
+impl Phase {
+    /// True if the phase has all the tags.
+    pub fn has_tags(&self, tags: &[PhaseTag]) -> bool {
+        tags.iter().all(|tag| self.tags.get(&tag.key).is_some_and(|value| value.eq(&tag.value)))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhaseTag {
+    pub key: String,
+    pub value: String,
+}
+
+#[derive(Error, Debug)]
+#[error("Invalid phase tag, expected 'key=value'. value: '{0:}'")]
+pub struct PhaseTagError(String);
+
+impl FromStr for PhaseTag {
+    type Err = PhaseTagError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.split_once('=') {
+            Some((key, tag_value)) if !key.trim().is_empty() => Ok(PhaseTag { key: key.trim().to_string(), value: tag_value.trim().to_string() }),
+            _ => Err(PhaseTagError(value.to_string())),
+        }
+    }
+}
+
+impl Display for PhaseTag {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}={}", self.key, self.value)
+    }
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum WorkInstructionsStyle {
+    /// A table of the parts, with their feeders and quantities.
+    #[default]
+    Parts,
+    /// A pick list, grouping the placements by feeder and part, with the locations of each placement, for manual assembly.
+    PickList,
+}
+
+impl WorkInstructionsStyle {
+    pub fn is_default(&self) -> bool {
+        matches!(self, WorkInstructionsStyle::Parts)
+    }
+}
+
+impl Display for WorkInstructionsStyle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parts => write!(f, "Parts"),
+            Self::PickList => write!(f, "PickList"),
+        }
+    }
 }
 
 #[derive(Error, Debug)]
 pub enum PhaseError {
     #[error("Unknown phase. phase: '{0:}'")]
     UnknownPhase(Reference),
+
+    #[error("Phase already exists. phase: '{0:}'")]
+    PhaseAlreadyExists(Reference),
     
     #[error("Invalid operation for phase. phase: '{0:}', operation: {1:?}")]
     InvalidOperationForPhase(Reference, ProcessOperationKind),
+
+    #[error("Invalid phase ordering, each phase must be given exactly once. phases: {0}")]
+    InvalidPhaseOrdering(String),
 }
 
 pub struct PhaseOrderings<'a>(pub &'a IndexSet<Reference>);
@@ -39,9 +153,31 @@ impl<'a> Display for PhaseOrderings<'a> {
     }
 }
 
+/// e.g. "['top_1', 'bottom_1']"
+pub fn format_references<T: Display>(references: impl IntoIterator<Item = T>) -> String {
+    format!("[{}]", references.into_iter().map(|reference| format!("'{}'", reference)).collect::<Vec<String>>().join(", "))
+}
+
 #[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
 pub struct PhaseState {
-    pub operation_state: BTreeMap<ProcessOperationKind, ProcessOperationState>
+    pub operation_state: BTreeMap<ProcessOperationKind, ProcessOperationState>,
+
+    /// The part loaded in each feeder, by feeder reference, and when it was loaded, so that the floor life of moisture
+    /// sensitive parts can be tracked.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    #[serde(default)]
+    pub feeder_exposures: BTreeMap<String, FeederExposure>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub first_article_inspection: Option<FirstArticleInspection>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq)]
+pub struct FeederExposure {
+    pub part: Part,
+    #[serde(with = "rfc3339")]
+    pub loaded_at: OffsetDateTime,
 }
 
 impl PhaseState {
@@ -55,6 +191,146 @@ impl PhaseState {
         
         Self {
             operation_state,
+            feeder_exposures: Default::default(),
+            first_article_inspection: None,
+        }
+    }
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum PhaseDependencyError {
+    #[error("A phase cannot depend on itself. phase: '{0:}'")]
+    SelfDependency(Reference),
+
+    #[error("Phase dependencies contain a cycle. phases: {phases:?}")]
+    Cycle { phases: Vec<String> },
+}
+
+/// The phases in the order they can be executed, i.e. each phase after the phases it depends on, phases that do not
+/// depend on each other remain in phase order.
+///
+/// Dependencies on phases that do not exist are ignored.
+pub fn build_execution_plan(project: &Project) -> Result<Vec<Reference>, PhaseDependencyError> {
+    let mut remaining: Vec<&Reference> = project.phase_orderings.iter()
+        .filter(|reference| project.phases.contains_key(*reference))
+        .collect();
+    let dependencies = |reference: &Reference| -> Vec<&Reference> {
+        project.phases[reference].dependencies.iter()
+            .filter(|dependency| project.phases.contains_key(*dependency))
+            .collect()
+    };
+
+    let mut plan: Vec<Reference> = vec![];
+
+    // the first remaining phase, in phase order, whose dependencies are all planned is planned next
+    while let Some(index) = remaining.iter().position(|reference| dependencies(reference).iter().all(|dependency| plan.contains(dependency))) {
+        plan.push(remaining.remove(index).clone());
+    }
+
+    if remaining.is_empty() {
+        return Ok(plan)
+    }
+
+    // the remaining phases are either in a cycle, or depend on a phase in a cycle, the latter are excluded
+    loop {
+        let depended_on: BTreeSet<&Reference> = remaining.iter().flat_map(|reference| dependencies(reference)).collect();
+        let count = remaining.len();
+        remaining.retain(|reference| depended_on.contains(reference));
+        if remaining.len() == count {
+            break
+        }
+    }
+
+    Err(PhaseDependencyError::Cycle { phases: remaining.iter().map(ToString::to_string).collect() })
+}
+
+/// A phase is started when any of its operations is no-longer pending.
+pub fn is_phase_started(project: &Project, reference: &Reference) -> bool {
+    project.phase_states.get(reference)
+        .is_some_and(|phase_state| phase_state.operation_state.values()
+            .any(|operation_state| !operation_state.status.eq(&ProcessOperationStatus::Pending)))
+}
+
+/// The dependencies of the phase that are not complete, in dependency order.
+pub fn find_incomplete_dependencies<'a>(project: &'a Project, reference: &Reference) -> Vec<&'a Reference> {
+    project.phases.get(reference)
+        .map(|phase| phase.dependencies.iter()
+            .filter(|dependency| project.phases.contains_key(*dependency) && !certificate::is_phase_complete(project, dependency))
+            .collect())
+        .unwrap_or_default()
+}
+
+/// The phases that have been started before the phases they depend on were complete, with the incomplete dependencies,
+/// in phase order.
+pub fn find_dependency_violations(project: &Project) -> Vec<(&Reference, Vec<&Reference>)> {
+    project.phase_orderings.iter()
+        .filter(|reference| is_phase_started(project, reference))
+        .map(|reference| (reference, find_incomplete_dependencies(project, reference)))
+        .filter(|(_reference, incomplete_dependencies)| !incomplete_dependencies.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use pnp::pcb::PcbSide;
+    use crate::phase::{build_execution_plan, find_dependency_violations, PhaseDependencyError};
+    use crate::process::{ProcessOperationKind, ProcessOperationStatus};
+    use crate::project::Project;
+    use crate::reference::Reference;
+    use crate::test::project_builder::ProjectBuilder;
+
+    fn build_project(dependencies: &[(&str, &str)]) -> Project {
+        let mut project = ["manual_bottom", "reflow_top", "inspect"].iter()
+            .fold(ProjectBuilder::new(), |builder, reference| builder.with_phase(reference, "manual", "load_out_1.csv", PcbSide::Top))
+            .build();
+        for (reference, dependency) in dependencies {
+            project.phases.get_mut(&Reference::from_str(reference).unwrap()).unwrap().dependencies.insert(Reference::from_str(dependency).unwrap());
         }
+        project
+    }
+
+    fn to_strings(references: &[Reference]) -> Vec<String> {
+        references.iter().map(Reference::to_string).collect()
+    }
+
+    #[test]
+    pub fn execution_plan() {
+        // given
+        let project = build_project(&[("manual_bottom", "reflow_top")]);
+
+        // when
+        let plan = build_execution_plan(&project).unwrap();
+
+        // then 'inspect' does not depend on the other phases, so it remains in phase order
+        assert_eq!(to_strings(&plan), vec!["reflow_top", "manual_bottom", "inspect"]);
+    }
+
+    #[test]
+    pub fn execution_plan_with_cycle() {
+        // given
+        let project = build_project(&[("manual_bottom", "reflow_top"), ("reflow_top", "manual_bottom"), ("inspect", "reflow_top")]);
+
+        // when
+        let result = build_execution_plan(&project);
+
+        // then 'inspect' depends on a phase in the cycle, but is not in the cycle
+        assert_eq!(result, Err(PhaseDependencyError::Cycle { phases: vec!["manual_bottom".to_string(), "reflow_top".to_string()] }));
+    }
+
+    #[test]
+    pub fn dependency_violations() {
+        // given
+        let mut project = build_project(&[("manual_bottom", "reflow_top")]);
+        let manual_bottom = Reference::from_str("manual_bottom").unwrap();
+
+        // expect
+        assert!(find_dependency_violations(&project).is_empty());
+
+        // when an operation of 'manual_bottom' is recorded before 'reflow_top' is complete
+        project.phase_states.get_mut(&manual_bottom).unwrap().operation_state.get_mut(&ProcessOperationKind::LoadPcbs).unwrap().status = ProcessOperationStatus::Complete;
+
+        // then
+        assert_eq!(find_dependency_violations(&project), vec![(&manual_bottom, vec![&Reference::from_str("reflow_top").unwrap()])]);
     }
 }
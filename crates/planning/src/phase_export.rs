@@ -0,0 +1,231 @@
+//! A machine-neutral export of a phase, for external converters and exporters for specific machines.
+//!
+//! The export is a JSON document, generated for each phase alongside the phase placements:
+//!
+//! * `format_version` - incremented when a field is removed or its meaning changes, fields may be added without
+//!   changing the version.
+//! * `project` - the name of the project.
+//! * `phase` - the phase `reference`, `process`, `pcb_side` ('top' or 'bottom') and `tags`.
+//! * `placements` - in the placement ordering of the phase, with the `object_path`, `ref_des`, `manufacturer`,
+//!   `mpn`, `feeder_reference` (`null` if no feeder is assigned) and the final `x`, `y` and `rotation` of the
+//!   placement, and the `nozzle`, only present if the phase has a nozzle configuration and the placement was assigned a
+//!   nozzle.  The `value`, `description` and `package` of the part are only present if the part details have them.
+//! * `load_out` - in feeder order, with the `feeder_reference`, `manufacturer`, `mpn`, the `loaded_part` (which is
+//!   an alternate, when one is loaded) and the `quantity` (`null` if the quantity is not tracked).
+//!
+//! Decimals are written as strings, so that no precision is lost.
+
+use std::collections::BTreeMap;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use pnp::load_out::LoadOutItem;
+use pnp::object_path::ObjectPath;
+use pnp::part::Part;
+use pnp::pcb::PcbSide;
+use crate::nozzle::NozzleAssignments;
+use crate::phase::Phase;
+use crate::placement::PlacementState;
+use crate::project::Project;
+
+pub const PHASE_EXPORT_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PhaseExport {
+    pub format_version: u32,
+    pub project: String,
+    pub phase: PhaseExportPhase,
+    pub placements: Vec<PhaseExportPlacement>,
+    pub load_out: Vec<PhaseExportLoadOutItem>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PhaseExportPhase {
+    pub reference: String,
+    pub process: String,
+    pub pcb_side: PcbSide,
+    pub tags: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PhaseExportPlacement {
+    pub object_path: String,
+    pub ref_des: String,
+    pub manufacturer: String,
+    pub mpn: String,
+    pub feeder_reference: Option<String>,
+    pub x: Decimal,
+    pub y: Decimal,
+    pub rotation: Decimal,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub nozzle: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub package: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PhaseExportLoadOutItem {
+    pub feeder_reference: String,
+    pub manufacturer: String,
+    pub mpn: String,
+    pub loaded_part: Part,
+    pub quantity: Option<u32>,
+}
+
+pub fn build_phase_export_file_name(phase: &Phase) -> String {
+    format!("{}_export.json", phase.reference)
+}
+
+/// Builds the export, the placement states must be in the placement ordering of the phase.
+pub fn build_phase_export(project: &Project, phase: &Phase, placement_states: &[(&ObjectPath, &PlacementState)], load_out_items: &[LoadOutItem], nozzle_assignments: &NozzleAssignments) -> PhaseExport {
+    let placements = placement_states.iter().map(|(object_path, placement_state)| {
+        let placement = &placement_state.placement;
+        let part_details = project.find_part_details(&placement.part);
+
+        PhaseExportPlacement {
+            object_path: object_path.to_string(),
+            ref_des: placement.ref_des.clone(),
+            manufacturer: placement.part.manufacturer.clone(),
+            mpn: placement.part.mpn.clone(),
+            feeder_reference: pnp::load_out::find_load_out_item_by_part(load_out_items, &placement.part)
+                .map(|load_out_item| load_out_item.reference.clone())
+                .filter(|feeder_reference| !feeder_reference.is_empty()),
+            x: placement.x,
+            y: placement.y,
+            rotation: placement.rotation,
+            nozzle: nozzle_assignments.find_nozzle(object_path).cloned(),
+            value: part_details.and_then(|details| details.value.clone()),
+            description: part_details.and_then(|details| details.description.clone()),
+            package: part_details.and_then(|details| details.package.clone()),
+        }
+    }).collect();
+
+    let mut sorted_load_out_items: Vec<&LoadOutItem> = load_out_items.iter().collect();
+    sorted_load_out_items.sort_by(|a, b| pnp::load_out::load_out_item_cmp(a, b));
+
+    let load_out = sorted_load_out_items.into_iter().map(|load_out_item| {
+        PhaseExportLoadOutItem {
+            feeder_reference: load_out_item.reference.clone(),
+            manufacturer: load_out_item.manufacturer.clone(),
+            mpn: load_out_item.mpn.clone(),
+            loaded_part: load_out_item.loaded_part(),
+            quantity: load_out_item.quantity,
+        }
+    }).collect();
+
+    PhaseExport {
+        format_version: PHASE_EXPORT_FORMAT_VERSION,
+        project: project.name.clone(),
+        phase: PhaseExportPhase {
+            reference: phase.reference.to_string(),
+            process: phase.process.to_string(),
+            pcb_side: phase.pcb_side.clone(),
+            tags: phase.tags.clone(),
+        },
+        placements,
+        load_out,
+    }
+}
+
+pub fn serialize_phase_export(phase_export: &PhaseExport) -> Result<Vec<u8>, serde_json::Error> {
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
+    let mut ser = serde_json::Serializer::with_formatter(vec![], formatter);
+    phase_export.serialize(&mut ser)?;
+
+    let mut content = ser.into_inner();
+    content.push(b'\n');
+
+    Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use rust_decimal_macros::dec;
+    use serde_json::json;
+    use pnp::load_out::LoadOutItem;
+    use pnp::part::{Part, PartDetails};
+    use pnp::pcb::PcbSide;
+    use crate::nozzle::NozzleAssignments;
+    use crate::part::PartState;
+    use crate::phase_export::{build_phase_export, serialize_phase_export};
+    use crate::reference::Reference;
+    use crate::test::project_builder::{PlacementStateBuilder, ProjectBuilder};
+
+    #[test]
+    pub fn export() {
+        // given
+        let mut project = ProjectBuilder::new()
+            .with_phase("top_1", "pnp", "load_out_1.csv", PcbSide::Top)
+            .build();
+        let phase = project.phases.get(&Reference::from_str("top_1").unwrap()).unwrap().clone();
+
+        // and the part details, from a parts master
+        let part = Part::new("RES_MFR1".to_string(), "RES1".to_string());
+        project.part_states.insert(part.clone(), PartState {
+            details: PartDetails { value: Some("10K".to_string()), package: Some("0402".to_string()), ..PartDetails::default() },
+            ..PartState::default()
+        });
+
+        // and
+        let (object_path, placement_state) = PlacementStateBuilder::new(1, "R1", &part)
+            .with_position(dec!(10.5), dec!(20), dec!(-90))
+            .with_phase("top_1")
+            .build();
+
+        // and
+        let load_out_items = vec![
+            LoadOutItem {
+                quantity: Some(100),
+                alternates: vec![Part::new("RES_MFR2".to_string(), "RES1A".to_string())],
+                loaded_alternate: Some(Part::new("RES_MFR2".to_string(), "RES1A".to_string())),
+                ..LoadOutItem::new("FEEDER_1".to_string(), "RES_MFR1".to_string(), "RES1".to_string())
+            },
+        ];
+
+        // and
+        let expected_content = json!({
+            "format_version": 1,
+            "project": "job1",
+            "phase": { "reference": "top_1", "process": "pnp", "pcb_side": "top", "tags": {} },
+            "placements": [
+                {
+                    "object_path": "panel=1::unit=1::ref_des=R1",
+                    "ref_des": "R1",
+                    "manufacturer": "RES_MFR1",
+                    "mpn": "RES1",
+                    "feeder_reference": "FEEDER_1",
+                    "x": "10.5",
+                    "y": "20",
+                    "rotation": "-90",
+                    "value": "10K",
+                    "package": "0402",
+                }
+            ],
+            "load_out": [
+                {
+                    "feeder_reference": "FEEDER_1",
+                    "manufacturer": "RES_MFR1",
+                    "mpn": "RES1",
+                    "loaded_part": { "manufacturer": "RES_MFR2", "mpn": "RES1A" },
+                    "quantity": 100,
+                }
+            ],
+        });
+
+        // when
+        let phase_export = build_phase_export(&project, &phase, &[(&object_path, &placement_state)], &load_out_items, &NozzleAssignments::default());
+        let content = serialize_phase_export(&phase_export).unwrap();
+
+        // then
+        let actual_content: serde_json::Value = serde_json::from_slice(&content).unwrap();
+        assert_eq!(actual_content, expected_content);
+    }
+}
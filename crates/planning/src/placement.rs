@@ -1,10 +1,15 @@
 use thiserror::Error;
 use std::fmt::{Display, Formatter};
 use std::collections::BTreeMap;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
 use serde_with::serde_as;
 use serde_with::DisplayFromStr;
+use time::serde::rfc3339;
+use time::OffsetDateTime;
 use util::sorting::SortOrder;
-use pnp::object_path::ObjectPath;
+use regex::Regex;
+use pnp::object_path::{ObjectPath, ObjectPathPattern};
 use pnp::part::Part;
 use pnp::placement::Placement;
 use crate::design::DesignVariant;
@@ -22,7 +27,92 @@ pub struct PlacementState {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
-    pub phase: Option<Reference>
+    pub phase: Option<Reference>,
+
+    /// Overrides `place` of the placement from the design, for this project only, see `PlacementState::place`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub place_override: Option<PlacementOverride>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub defects: Vec<PlacementDefect>,
+}
+
+impl PlacementState {
+    /// Whether the placement is to be placed, the override takes precedence over the design.
+    pub fn place(&self) -> bool {
+        self.place_override.map_or(self.placement.place, |place_override| place_override.place())
+    }
+
+    pub fn has_open_defect(&self) -> bool {
+        self.defects.iter().any(|defect| defect.status == PlacementDefectStatus::Open)
+    }
+}
+
+/// A job-level override of whether a placement is placed, e.g. to skip a placement for a batch, without changing the
+/// EDA exports.
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum PlacementOverride {
+    /// Do not place the placement, even if the design places it.
+    Skip,
+    /// Place the placement, even if the design does not place it (e.g. 'DNP').
+    Place,
+}
+
+impl PlacementOverride {
+    pub fn place(&self) -> bool {
+        matches!(self, PlacementOverride::Place)
+    }
+}
+
+/// Placements of a design variant with the same ref_des, see `project::refresh_from_design_variants`.
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq)]
+pub struct PlacementConflict {
+    pub design_variant: DesignVariant,
+    pub ref_des: String,
+    pub kind: PlacementConflictKind,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq)]
+pub enum PlacementConflictKind {
+    /// The placements have the same part, but are different, e.g. in position or rotation.
+    RefDesCollision,
+    /// The placements have different parts.
+    PartConflict { parts: Vec<Part> },
+}
+
+impl Display for PlacementConflict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            PlacementConflictKind::RefDesCollision => write!(f, "design_variant: {}, ref_des: {}, kind: RefDesCollision", self.design_variant, self.ref_des),
+            PlacementConflictKind::PartConflict { parts } => write!(f, "design_variant: {}, ref_des: {}, kind: PartConflict, parts: [{}]",
+                self.design_variant, self.ref_des, parts.iter().map(Part::to_string).collect::<Vec<_>>().join(", "),
+            ),
+        }
+    }
+}
+
+/// A defect found during inspection of a placement.
+///
+/// The phase is the phase in which the defect was found, the rework phase is set when a rework phase
+/// is created for the defect so that the defect can be closed when the placement is re-placed.
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq)]
+pub struct PlacementDefect {
+    #[serde(with = "rfc3339")]
+    pub date_time: OffsetDateTime,
+    pub phase: Reference,
+    pub status: PlacementDefectStatus,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub rework_phase: Option<Reference>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq)]
+pub enum PlacementDefectStatus {
+    Open,
+    Closed,
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq)]
@@ -33,10 +123,33 @@ pub enum PlacementStatus {
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub enum PlacementSortingMode {
+    /// Natural order of the feeder reference (e.g. 'FEEDER_2' before 'FEEDER_10'), unassigned placements last.
     FeederReference,
     PcbUnit,
+    /// Groups the placements of identical parts, reducing nozzle changes and feeder travel, combine with `PcbUnit`
+    /// to keep the unit order within each group.
+    Part,
+    /// Groups the placements by the nozzle assigned to them, in the order of the nozzle configuration of the phase,
+    /// unassigned placements last.
+    Nozzle,
+    /// Natural order of the ref des (e.g. 'R2' before 'R10').
+    RefDes,
+    /// Area of the part body, from the part details, placements of parts without a length and width last.
+    Area,
+    /// Height of the part body, from the part details, placements of parts without a height last.
+    Height,
+    /// Rotation, in panel space.
+    Angle,
+    /// X coordinate, in design (unit) space.
+    DesignX,
+    /// Y coordinate, in design (unit) space.
+    DesignY,
+    /// X coordinate, in panel space, see `PcbGeometry`.
+    PanelX,
+    /// Y coordinate, in panel space, see `PcbGeometry`.
+    PanelY,
 
-    // FUTURE add other modes, such as COST, PART, AREA, HEIGHT, REFDES, ANGLE, DESIGN_X, DESIGN_Y, PANEL_X, PANEL_Y, DESCRIPTION
+    // FUTURE add other modes, such as COST, DESCRIPTION
 }
 
 impl Display for PlacementSortingMode {
@@ -44,6 +157,16 @@ impl Display for PlacementSortingMode {
         match self {
             Self::FeederReference => write!(f, "FeederReference"),
             Self::PcbUnit => write!(f, "PcbUnit"),
+            Self::Part => write!(f, "Part"),
+            Self::Nozzle => write!(f, "Nozzle"),
+            Self::RefDes => write!(f, "RefDes"),
+            Self::Area => write!(f, "Area"),
+            Self::Height => write!(f, "Height"),
+            Self::Angle => write!(f, "Angle"),
+            Self::DesignX => write!(f, "DesignX"),
+            Self::DesignY => write!(f, "DesignY"),
+            Self::PanelX => write!(f, "PanelX"),
+            Self::PanelY => write!(f, "PanelY"),
         }
     }
 }
@@ -77,5 +200,131 @@ pub fn build_unique_parts(design_variant_placement_map: &BTreeMap<DesignVariant,
 
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize, PartialEq)]
 pub enum PlacementOperation {
-    Placed
-}
\ No newline at end of file
+    Placed,
+    Unplaced,
+    InspectionFailed,
+}
+
+/// Selects placements by object path, either with a regular expression, which can match any part of the path, or with
+/// an object path pattern, see [`ObjectPathPattern`], which is validated when it is parsed.
+#[derive(Debug, Clone)]
+pub enum ObjectPathMatcher {
+    Regex(Regex),
+    Pattern(ObjectPathPattern),
+}
+
+impl ObjectPathMatcher {
+    pub fn is_match(&self, object_path: &ObjectPath) -> bool {
+        match self {
+            ObjectPathMatcher::Regex(regex) => regex.is_match(&object_path.to_string()),
+            ObjectPathMatcher::Pattern(pattern) => pattern.is_match(object_path),
+        }
+    }
+}
+
+impl From<Regex> for ObjectPathMatcher {
+    fn from(regex: Regex) -> Self {
+        ObjectPathMatcher::Regex(regex)
+    }
+}
+
+impl From<ObjectPathPattern> for ObjectPathMatcher {
+    fn from(pattern: ObjectPathPattern) -> Self {
+        ObjectPathMatcher::Pattern(pattern)
+    }
+}
+
+impl Display for ObjectPathMatcher {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ObjectPathMatcher::Regex(regex) => write!(f, "{}", regex),
+            ObjectPathMatcher::Pattern(pattern) => write!(f, "{}", pattern),
+        }
+    }
+}
+
+/// The range of rotations a machine expects.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RotationRange {
+    /// >-180 to +180
+    Signed,
+    /// 0 to <360
+    Unsigned,
+}
+
+impl Display for RotationRange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RotationRange::Signed => write!(f, "Signed"),
+            RotationRange::Unsigned => write!(f, "Unsigned"),
+        }
+    }
+}
+
+/// Normalizes an anti-clockwise rotation to the range.
+pub fn normalize_rotation(rotation: Decimal, range: &RotationRange) -> Decimal {
+    let mut rotation = rotation % dec!(360);
+    if rotation < Decimal::ZERO {
+        rotation += dec!(360);
+    }
+    if matches!(range, RotationRange::Signed) && rotation > dec!(180) {
+        rotation -= dec!(360);
+    }
+    rotation.normalize()
+}
+
+/// The rotations of the placements of a phase, as expected by the machine of the phase.
+///
+/// The offsets correct the difference between the zero rotation of a part in the EDA tool and on the machine, e.g.
+/// when the footprint of a part is drawn in a different orientation to the part in its tape, see
+/// `stores::rotation_offsets`.
+#[serde_as]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RotationNormalization {
+    pub range: RotationRange,
+
+    /// Anti-clockwise offsets, by part, parts without an offset are not offset.
+    #[serde_as(as = "Vec<(_, _)>")]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    #[serde(default)]
+    pub offsets: BTreeMap<Part, Decimal>,
+}
+
+impl RotationNormalization {
+    pub fn apply(&self, part: &Part, rotation: Decimal) -> Decimal {
+        let offset = self.offsets.get(part).copied().unwrap_or_default();
+
+        normalize_rotation(rotation + offset, &self.range)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use rust_decimal_macros::dec;
+    use pnp::part::Part;
+    use crate::placement::{normalize_rotation, RotationNormalization, RotationRange};
+
+    #[test]
+    pub fn normalize() {
+        // expect
+        assert_eq!(normalize_rotation(dec!(-90), &RotationRange::Unsigned), dec!(270));
+        assert_eq!(normalize_rotation(dec!(360), &RotationRange::Unsigned), dec!(0));
+        assert_eq!(normalize_rotation(dec!(-180), &RotationRange::Unsigned), dec!(180));
+        assert_eq!(normalize_rotation(dec!(270.5), &RotationRange::Signed), dec!(-89.5));
+        assert_eq!(normalize_rotation(dec!(-180), &RotationRange::Signed), dec!(180));
+        assert_eq!(normalize_rotation(dec!(-540), &RotationRange::Signed), dec!(180));
+    }
+
+    #[test]
+    pub fn apply_offsets() {
+        // given
+        let res1 = Part::new("RES_MFR1".to_string(), "RES1".to_string());
+        let res2 = Part::new("RES_MFR1".to_string(), "RES2".to_string());
+        let rotation_normalization = RotationNormalization { range: RotationRange::Unsigned, offsets: BTreeMap::from([(res1.clone(), dec!(90))]) };
+
+        // expect
+        assert_eq!(rotation_normalization.apply(&res1, dec!(-180)), dec!(270));
+        assert_eq!(rotation_normalization.apply(&res2, dec!(-180)), dec!(180));
+    }
+}
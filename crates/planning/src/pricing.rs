@@ -0,0 +1,187 @@
+//! Cost estimates of the parts placed by each phase, using a price list, see `stores::pricing`.
+//!
+//! The parts for all the phases of a project are usually bought together, so the price break of a part is determined
+//! by the quantity of the part across all the phases of the project.
+
+use std::collections::BTreeMap;
+use rust_decimal::Decimal;
+use pnp::part::Part;
+use crate::project::{count_phase_part_placements, Project};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PriceList {
+    pub prices: BTreeMap<Part, PartPrice>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartPrice {
+    /// e.g. 'EUR'
+    pub currency: String,
+    /// Ordered by quantity.
+    pub price_breaks: Vec<PriceBreak>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceBreak {
+    /// The minimum quantity for the unit price.
+    pub quantity: u32,
+    pub unit_price: Decimal,
+}
+
+impl PartPrice {
+    /// The unit price of the largest price break reached by the quantity, quantities below the smallest price break use
+    /// the smallest price break.
+    pub fn unit_price(&self, quantity: u32) -> Decimal {
+        self.price_breaks.iter()
+            .rev()
+            .find(|price_break| price_break.quantity <= quantity)
+            .or(self.price_breaks.first())
+            .map_or(Decimal::ZERO, |price_break| price_break.unit_price)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize)]
+pub struct CostEstimate {
+    /// In the order of the phases.
+    pub phases: Vec<PhaseCostEstimate>,
+    /// The cost of all the phases, by currency.
+    pub total: BTreeMap<String, Decimal>,
+    /// Parts that are not in the price list, their cost is not included.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unpriced_parts: Vec<Part>,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub struct PhaseCostEstimate {
+    pub phase_name: String,
+    /// By currency.
+    pub cost: BTreeMap<String, Decimal>,
+}
+
+/// Only the placements that are to be placed, and have been assigned to a phase, are included.
+pub fn build_cost_estimate(project: &Project, price_list: &PriceList) -> CostEstimate {
+    let phase_part_counts = count_phase_part_placements(project);
+
+    let part_quantities: BTreeMap<&Part, u32> = phase_part_counts.values()
+        .flat_map(|part_counts| part_counts.iter())
+        .fold(BTreeMap::new(), |mut part_quantities, (part, counts)| {
+            *part_quantities.entry(part).or_default() += counts.placed + counts.unplaced;
+            part_quantities
+        });
+
+    let mut estimate = CostEstimate::default();
+
+    for reference in project.phase_orderings.iter() {
+        let mut cost: BTreeMap<String, Decimal> = BTreeMap::new();
+
+        for (part, counts) in phase_part_counts.get(reference).into_iter().flatten() {
+            let Some(part_price) = price_list.prices.get(part) else {
+                if !estimate.unpriced_parts.contains(part) {
+                    estimate.unpriced_parts.push(part.clone());
+                }
+                continue
+            };
+
+            let unit_price = part_price.unit_price(part_quantities[part]);
+            let quantity = Decimal::from(counts.placed + counts.unplaced);
+
+            *cost.entry(part_price.currency.clone()).or_default() += unit_price * quantity;
+        }
+
+        for (currency, amount) in cost.iter() {
+            *estimate.total.entry(currency.clone()).or_default() += amount;
+        }
+
+        estimate.phases.push(PhaseCostEstimate { phase_name: reference.to_string(), cost });
+    }
+
+    estimate.unpriced_parts.sort();
+
+    estimate
+}
+
+/// e.g. 'EUR 1.20, USD 0.50', or '-' if there are no costs.
+pub fn format_cost(cost: &BTreeMap<String, Decimal>) -> String {
+    if cost.is_empty() {
+        return "-".to_string()
+    }
+
+    cost.iter()
+        .map(|(currency, amount)| format!("{} {}", currency, amount))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use rust_decimal_macros::dec;
+    use pnp::part::Part;
+    use pnp::pcb::PcbSide;
+    use crate::pricing::{build_cost_estimate, format_cost, PartPrice, PriceBreak, PriceList};
+    use crate::project::Project;
+    use crate::test::project_builder::{PlacementStateBuilder, ProjectBuilder};
+
+    fn build_project(placements: &[(&str, &Part, &str)]) -> Project {
+        ProjectBuilder::new()
+            .with_phase("top_1", "pnp", "top_1_load_out.csv", PcbSide::Top)
+            .with_phase("bottom_1", "pnp", "bottom_1_load_out.csv", PcbSide::Top)
+            .with_placements(placements.iter().map(|(ref_des, part, reference)| PlacementStateBuilder::new(1, ref_des, part).with_phase(reference)))
+            .build()
+    }
+
+    #[test]
+    pub fn unit_price_uses_price_breaks() {
+        // given
+        let part_price = PartPrice {
+            currency: "EUR".to_string(),
+            price_breaks: vec![
+                PriceBreak { quantity: 10, unit_price: dec!(0.10) },
+                PriceBreak { quantity: 100, unit_price: dec!(0.05) },
+            ],
+        };
+
+        // expect
+        assert_eq!(part_price.unit_price(1), dec!(0.10));
+        assert_eq!(part_price.unit_price(99), dec!(0.10));
+        assert_eq!(part_price.unit_price(100), dec!(0.05));
+    }
+
+    #[test]
+    pub fn cost_estimate() {
+        // given
+        let res1 = Part::new("RES_MFR1".to_string(), "RES1".to_string());
+        let cap1 = Part::new("CAP_MFR1".to_string(), "CAP1".to_string());
+        let conn1 = Part::new("CONN_MFR1".to_string(), "CONN1".to_string());
+        let project = build_project(&[
+            ("R1", &res1, "top_1"),
+            ("R2", &res1, "bottom_1"),
+            ("C1", &cap1, "top_1"),
+            ("J1", &conn1, "bottom_1"),
+        ]);
+
+        // and the quantity of 'RES1' across both phases reaches the second price break
+        let price_list = PriceList {
+            prices: BTreeMap::from([
+                (res1.clone(), PartPrice { currency: "EUR".to_string(), price_breaks: vec![
+                    PriceBreak { quantity: 1, unit_price: dec!(0.10) },
+                    PriceBreak { quantity: 2, unit_price: dec!(0.05) },
+                ]}),
+                (cap1.clone(), PartPrice { currency: "USD".to_string(), price_breaks: vec![
+                    PriceBreak { quantity: 1, unit_price: dec!(0.25) },
+                ]}),
+            ]),
+        };
+
+        // when
+        let estimate = build_cost_estimate(&project, &price_list);
+
+        // then
+        assert_eq!(format_cost(&estimate.phases[0].cost), "EUR 0.05, USD 0.25");
+        assert_eq!(format_cost(&estimate.phases[1].cost), "EUR 0.05");
+        assert_eq!(format_cost(&estimate.total), "EUR 0.10, USD 0.25");
+
+        // and
+        assert_eq!(estimate.unpriced_parts, vec![conn1]);
+    }
+}
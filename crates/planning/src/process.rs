@@ -1,5 +1,7 @@
+use std::collections::{BTreeMap, BTreeSet};
 use std::str::FromStr;
 use std::fmt::{Display, Formatter};
+use serde_with::{DeserializeFromStr, SerializeDisplay};
 use thiserror::Error;
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
@@ -27,20 +29,251 @@ impl Display for ProcessName {
 pub struct Process {
     pub name: ProcessName,
     pub operations: Vec<ProcessOperationKind>,
+
+    /// Artifacts that must be generated for each phase that uses the process.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub required_artifacts: Vec<ArtifactType>,
+
+    /// Tools and consumables that must be confirmed when starting an operation, see `checklist`.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    #[serde(default)]
+    pub checklists: BTreeMap<ProcessOperationKind, Vec<String>>,
+
+    /// Custom operations whose status follows the progress of the placements, like the built-in placement operations.
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    #[serde(default)]
+    pub placement_operations: BTreeSet<ProcessOperationKind>,
 }
 
+/// The types of artifacts that are generated for each phase.
 #[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ArtifactType {
+    PhasePlacements,
+    WorkInstructions,
+    ReworkInstructions,
+    PhaseExport,
+    FirstArticleChecklist,
+    MachineExport,
+    FeederSetupSheet,
+}
+
+impl Display for ArtifactType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PhasePlacements => write!(f, "PhasePlacements"),
+            Self::WorkInstructions => write!(f, "WorkInstructions"),
+            Self::ReworkInstructions => write!(f, "ReworkInstructions"),
+            Self::PhaseExport => write!(f, "PhaseExport"),
+            Self::FirstArticleChecklist => write!(f, "FirstArticleChecklist"),
+            Self::MachineExport => write!(f, "MachineExport"),
+            Self::FeederSetupSheet => write!(f, "FeederSetupSheet"),
+        }
+    }
+}
+
+/// Serialized by name, e.g. 'AutomatedPnp', so that operations can be used as keys of JSON objects.
+#[derive(Debug, SerializeDisplay, DeserializeFromStr, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum ProcessOperationKind {
     LoadPcbs,
     AutomatedPnp,
     ReflowComponents,
     ManuallySolderComponents,
+    /// An operation of a process definition, e.g. 'SelectiveSolder', see `ProcessDefinition`.
+    Custom(String),
+}
+
+impl ProcessOperationKind {
+    const BUILT_IN: [ProcessOperationKind; 4] = [
+        ProcessOperationKind::LoadPcbs,
+        ProcessOperationKind::AutomatedPnp,
+        ProcessOperationKind::ReflowComponents,
+        ProcessOperationKind::ManuallySolderComponents,
+    ];
+
+    /// Built-in operations that place components, and consume parts from the load-out.
+    pub fn is_placement_operation(&self) -> bool {
+        matches!(self, ProcessOperationKind::AutomatedPnp | ProcessOperationKind::ManuallySolderComponents)
+    }
+
+    pub fn is_custom(&self) -> bool {
+        matches!(self, ProcessOperationKind::Custom(_))
+    }
+}
+
+impl Display for ProcessOperationKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::LoadPcbs => write!(f, "LoadPcbs"),
+            Self::AutomatedPnp => write!(f, "AutomatedPnp"),
+            Self::ReflowComponents => write!(f, "ReflowComponents"),
+            Self::ManuallySolderComponents => write!(f, "ManuallySolderComponents"),
+            Self::Custom(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("Invalid operation name. name: '{0}'")]
+pub struct ProcessOperationKindError(String);
+
+impl FromStr for ProcessOperationKind {
+    type Err = ProcessOperationKindError;
+
+    /// Names that are not the name of a built-in operation are custom operations.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().is_empty() || s.trim().len() != s.len() {
+            return Err(ProcessOperationKindError(s.to_string()))
+        }
+
+        let built_in = Self::BUILT_IN.into_iter().find(|operation| operation.to_string().eq(s));
+
+        Ok(built_in.unwrap_or(ProcessOperationKind::Custom(s.to_string())))
+    }
 }
 
 impl Process {
     pub fn has_operation(&self, operation: &ProcessOperationKind) -> bool {
         self.operations.contains(operation)
     }
+
+    /// Placement operations, and custom operations defined with placement tracking, follow the progress of the
+    /// placements of the phase and consume parts from the load-out.
+    pub fn tracks_placements(&self, operation: &ProcessOperationKind) -> bool {
+        operation.is_placement_operation() || self.placement_operations.contains(operation)
+    }
+}
+
+/// A process, as defined in a process definitions file, so that processes other than the built-in processes can be used.
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq)]
+pub struct ProcessDefinition {
+    pub name: ProcessName,
+    /// In the order they are performed.
+    pub operations: Vec<ProcessOperationDefinition>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub required_artifacts: Vec<ArtifactType>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq)]
+pub struct ProcessOperationDefinition {
+    pub kind: ProcessOperationKind,
+    /// Only applicable to custom operations, the built-in operations always, or never, track the placements.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub tracks_placements: Option<bool>,
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ProcessDefinitionError {
+    #[error("Process name required")]
+    MissingName,
+
+    #[error("Duplicate process. process: '{process}'")]
+    DuplicateProcess { process: ProcessName },
+
+    #[error("Process without operations. process: '{process}'")]
+    NoOperations { process: ProcessName },
+
+    #[error("Duplicate operation. process: '{process}', operation: '{operation}'")]
+    DuplicateOperation { process: ProcessName, operation: ProcessOperationKind },
+
+    #[error("Custom operation names must differ from the built-in operations. process: '{process}', operation: '{operation}'")]
+    ReservedOperationName { process: ProcessName, operation: ProcessOperationKind },
+
+    #[error("Placement tracking of built-in operations can not be changed. process: '{process}', operation: '{operation}'")]
+    BuiltInPlacementTracking { process: ProcessName, operation: ProcessOperationKind },
+}
+
+impl ProcessDefinition {
+    pub fn from_process(process: &Process) -> Self {
+        Self {
+            name: process.name.clone(),
+            operations: process.operations.iter().map(|operation| ProcessOperationDefinition {
+                kind: operation.clone(),
+                tracks_placements: process.placement_operations.contains(operation).then_some(true),
+            }).collect(),
+            required_artifacts: process.required_artifacts.clone(),
+        }
+    }
+
+    pub fn build_process(&self) -> Result<Process, ProcessDefinitionError> {
+        if self.name.0.trim().is_empty() {
+            return Err(ProcessDefinitionError::MissingName)
+        }
+
+        if self.operations.is_empty() {
+            return Err(ProcessDefinitionError::NoOperations { process: self.name.clone() })
+        }
+
+        let mut operations: Vec<ProcessOperationKind> = vec![];
+        let mut placement_operations = BTreeSet::new();
+
+        for operation in self.operations.iter() {
+            let kind = &operation.kind;
+
+            if operations.contains(kind) {
+                return Err(ProcessDefinitionError::DuplicateOperation { process: self.name.clone(), operation: kind.clone() })
+            }
+
+            // e.g. 'automatedpnp', which would be confused with the built-in operation by the CLI
+            if kind.is_custom() && ProcessOperationKind::BUILT_IN.iter().any(|built_in| built_in.to_string().eq_ignore_ascii_case(&kind.to_string())) {
+                return Err(ProcessDefinitionError::ReservedOperationName { process: self.name.clone(), operation: kind.clone() })
+            }
+
+            match operation.tracks_placements {
+                Some(tracks_placements) if !kind.is_custom() && tracks_placements != kind.is_placement_operation() => {
+                    return Err(ProcessDefinitionError::BuiltInPlacementTracking { process: self.name.clone(), operation: kind.clone() })
+                },
+                Some(true) if kind.is_custom() => {
+                    placement_operations.insert(kind.clone());
+                },
+                _ => (),
+            }
+
+            operations.push(kind.clone());
+        }
+
+        Ok(Process {
+            name: self.name.clone(),
+            operations,
+            required_artifacts: self.required_artifacts.clone(),
+            checklists: Default::default(),
+            placement_operations,
+        })
+    }
+}
+
+/// Builds the processes of the definitions, the names of the processes must be unique.
+pub fn build_processes(definitions: &[ProcessDefinition]) -> Result<Vec<Process>, ProcessDefinitionError> {
+    let mut processes: Vec<Process> = vec![];
+
+    for definition in definitions.iter() {
+        if processes.iter().any(|process| process.name.eq(&definition.name)) {
+            return Err(ProcessDefinitionError::DuplicateProcess { process: definition.name.clone() })
+        }
+
+        processes.push(definition.build_process()?);
+    }
+
+    Ok(processes)
+}
+
+/// How the status of placement operations (e.g. `AutomatedPnp`) is updated.
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, Default, PartialEq)]
+pub enum OperationTransitions {
+    /// The status is updated based on the progress of the placements, e.g. complete when all placements are placed.
+    #[default]
+    Automatic,
+    /// The status is only updated when recording phase operations.
+    Manual,
+}
+
+impl OperationTransitions {
+    pub fn is_automatic(&self) -> bool {
+        matches!(self, OperationTransitions::Automatic)
+    }
 }
 
 #[derive(Error, Debug)]
@@ -57,19 +290,14 @@ pub struct ProcessOperationState {
     pub extra: Option<ProcessOperationExtraState>,
 }
 
-#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq)]
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Default)]
 pub enum ProcessOperationStatus {
+    #[default]
     Pending,
     Incomplete,
     Complete
 }
 
-impl Default for ProcessOperationStatus {
-    fn default() -> Self {
-        Self::Pending
-    }
-}
-
 #[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq)]
 pub enum ProcessOperationExtraState {
     PlacementOperation { placements_state: PlacementsState },
@@ -89,4 +317,78 @@ impl PlacementsState {
 
 pub enum ProcessOperationSetItem {
     Completed
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod process_definition_tests {
+    use std::collections::BTreeMap;
+    use std::str::FromStr;
+    use crate::process::{build_processes, ProcessDefinition, ProcessDefinitionError, ProcessName, ProcessOperationDefinition, ProcessOperationKind};
+
+    fn build_definition(operations: Vec<(&str, Option<bool>)>) -> ProcessDefinition {
+        ProcessDefinition {
+            name: ProcessName::from_str("selective").unwrap(),
+            operations: operations.into_iter().map(|(kind, tracks_placements)| ProcessOperationDefinition {
+                kind: ProcessOperationKind::from_str(kind).unwrap(),
+                tracks_placements,
+            }).collect(),
+            required_artifacts: vec![],
+        }
+    }
+
+    #[test]
+    pub fn round_trip() -> anyhow::Result<()> {
+        // given
+        let definition = build_definition(vec![("LoadPcbs", None), ("SelectiveSolder", Some(true)), ("Wash", None)]);
+
+        // when
+        let process = definition.build_process()?;
+
+        // then
+        assert_eq!(process.operations, vec![
+            ProcessOperationKind::LoadPcbs,
+            ProcessOperationKind::Custom("SelectiveSolder".to_string()),
+            ProcessOperationKind::Custom("Wash".to_string()),
+        ]);
+        assert!(process.tracks_placements(&ProcessOperationKind::Custom("SelectiveSolder".to_string())));
+        assert!(!process.tracks_placements(&ProcessOperationKind::Custom("Wash".to_string())));
+
+        // and
+        assert_eq!(ProcessDefinition::from_process(&process), definition);
+
+        // and operations are serialized by name, so they can be used as keys
+        let json = serde_json::to_string(&definition)?;
+        assert!(json.contains(r#"{"kind":"SelectiveSolder","tracks_placements":true}"#), "json: {}", json);
+        assert_eq!(serde_json::from_str::<ProcessDefinition>(&json)?, definition);
+        assert_eq!(serde_json::to_string(&BTreeMap::from([(ProcessOperationKind::AutomatedPnp, 1)]))?, r#"{"AutomatedPnp":1}"#);
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn invalid_definitions() {
+        // expect
+        assert_eq!(
+            build_definition(vec![]).build_process(),
+            Err(ProcessDefinitionError::NoOperations { process: ProcessName::from_str("selective").unwrap() }),
+        );
+        assert!(matches!(
+            build_definition(vec![("Wash", None), ("Wash", None)]).build_process(),
+            Err(ProcessDefinitionError::DuplicateOperation { .. }),
+        ));
+        assert!(matches!(
+            build_definition(vec![("automatedpnp", None)]).build_process(),
+            Err(ProcessDefinitionError::ReservedOperationName { .. }),
+        ));
+        assert!(matches!(
+            build_definition(vec![("ReflowComponents", Some(true))]).build_process(),
+            Err(ProcessDefinitionError::BuiltInPlacementTracking { .. }),
+        ));
+
+        // and
+        let definition = build_definition(vec![("Wash", None)]);
+        assert!(matches!(build_processes(&[definition.clone(), definition]), Err(ProcessDefinitionError::DuplicateProcess { .. })));
+
+        // and
+        assert!(ProcessOperationKind::from_str("").is_err());
+    }
+}
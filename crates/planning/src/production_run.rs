@@ -0,0 +1,317 @@
+//! Production runs, for building a quantity of the same job, each run is one physical panel, or board, identified by
+//! its serial number.
+//!
+//! The placement and phase operation states of the project are those of the run in progress, when a run is completed
+//! the placed placements are recorded in the run, and they are reset when the next run is started.
+
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter};
+use serde_with::serde_as;
+use serde_with::DisplayFromStr;
+use thiserror::Error;
+use time::serde::rfc3339;
+use time::OffsetDateTime;
+use tracing::info;
+use pnp::object_path::ObjectPath;
+use crate::placement::PlacementStatus;
+use crate::project::{self, Project};
+use crate::mutation::MUTATION_TARGET;
+
+#[serde_as]
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq)]
+pub struct ProductionRun {
+    /// The serial number of the panel, or board, e.g. from a barcode label.
+    pub serial: String,
+
+    #[serde(with = "rfc3339")]
+    pub started_at: OffsetDateTime,
+
+    #[serde(with = "rfc3339::option")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub completed_at: Option<OffsetDateTime>,
+
+    /// The placements that were placed, recorded when the run is completed.
+    #[serde_as(as = "BTreeSet<DisplayFromStr>")]
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    #[serde(default)]
+    pub placed: BTreeSet<ObjectPath>,
+
+    /// The placements that were to be placed, recorded when the run is completed.
+    #[serde(default)]
+    pub placements: usize,
+}
+
+impl ProductionRun {
+    pub fn is_completed(&self) -> bool {
+        self.completed_at.is_some()
+    }
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ProductionRunError {
+    #[error("Invalid serial number, it cannot be empty")]
+    InvalidSerial,
+
+    #[error("Duplicate serial number. serial: '{0}'")]
+    DuplicateSerial(String),
+
+    #[error("A production run is in progress, complete it first. serial: '{0}'")]
+    InProgress(String),
+
+    #[error("No production run is in progress")]
+    NotInProgress,
+
+    #[error("Production run is incomplete, use '--force' to complete it anyway. serial: '{serial}', placed: {placed}/{placements}")]
+    Incomplete { serial: String, placed: usize, placements: usize },
+
+    #[error("Production quantity reached, increase the quantity to start another run. quantity: {0}")]
+    QuantityReached(u32),
+
+    #[error("Unable to reset the operations for the run. cause: {0}")]
+    ResetOperations(String),
+}
+
+/// The run in progress, if a run has been started and not completed.
+pub fn find_current_run(project: &Project) -> Option<&ProductionRun> {
+    project.production_runs.last()
+        .filter(|production_run| !production_run.is_completed())
+}
+
+/// Sets the quantity of panels, or boards, to build, `None` if the quantity is open-ended.
+pub fn update_quantity(project: &mut Project, quantity: Option<u32>) -> bool {
+    if project.production_quantity == quantity {
+        return false
+    }
+
+    project.production_quantity = quantity;
+    info!(target: MUTATION_TARGET, quantity = ?quantity, message = "Production quantity set. quantity: {quantity}");
+
+    true
+}
+
+/// Starts a run, the operations are reset if a previous run was completed, so that the progress made before the first
+/// run is started counts towards the first run.
+///
+/// Resetting the operations, see `project::reset_operations`, clears the placed flag of every placement and sets every
+/// phase operation back to pending, discarding the first-article inspections, the operation history is kept.
+///
+/// Once the production quantity has been reached no more runs can be started.
+pub fn start(project: &mut Project, serial: String, now: OffsetDateTime) -> Result<ProductionRun, ProductionRunError> {
+    if serial.trim().is_empty() {
+        return Err(ProductionRunError::InvalidSerial)
+    }
+    if let Some(production_run) = find_current_run(project) {
+        return Err(ProductionRunError::InProgress(production_run.serial.clone()))
+    }
+    if project.production_runs.iter().any(|production_run| production_run.serial.eq(&serial)) {
+        return Err(ProductionRunError::DuplicateSerial(serial))
+    }
+    if let Some(quantity) = project.production_quantity.filter(|quantity| project.production_runs.len() >= *quantity as usize) {
+        return Err(ProductionRunError::QuantityReached(quantity))
+    }
+
+    if !project.production_runs.is_empty() {
+        project::reset_operations(project)
+            .map_err(|error| ProductionRunError::ResetOperations(error.to_string()))?;
+    }
+
+    let production_run = ProductionRun {
+        serial,
+        started_at: now,
+        completed_at: None,
+        placed: Default::default(),
+        placements: 0,
+    };
+    project.production_runs.push(production_run.clone());
+
+    info!(target: MUTATION_TARGET, serial = %production_run.serial, run = project.production_runs.len(), message = "Started production run. serial: '{serial}', run: {run}");
+
+    Ok(production_run)
+}
+
+/// Completes the run in progress, recording the placed placements, a run with unplaced placements is only completed
+/// when forced.
+pub fn complete(project: &mut Project, now: OffsetDateTime, force: bool) -> Result<ProductionRun, ProductionRunError> {
+    let (placed, placements) = project.placements.iter()
+        .filter(|(_object_path, placement_state)| placement_state.place() && matches!(placement_state.status, PlacementStatus::Known))
+        .fold((BTreeSet::new(), 0), |(mut placed, placements), (object_path, placement_state)| {
+            if placement_state.placed {
+                placed.insert(object_path.clone());
+            }
+            (placed, placements + 1)
+        });
+
+    let production_run = project.production_runs.last_mut()
+        .filter(|production_run| !production_run.is_completed())
+        .ok_or(ProductionRunError::NotInProgress)?;
+
+    if placed.len() < placements && !force {
+        return Err(ProductionRunError::Incomplete { serial: production_run.serial.clone(), placed: placed.len(), placements })
+    }
+
+    production_run.completed_at = Some(now);
+    production_run.placed = placed;
+    production_run.placements = placements;
+
+    info!(target: MUTATION_TARGET, serial = %production_run.serial, placed = production_run.placed.len(), placements = production_run.placements, message = "Completed production run. serial: '{serial}', placed: {placed}/{placements}");
+
+    Ok(production_run.clone())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProductionRunReport {
+    pub quantity: Option<u32>,
+    pub completed: usize,
+    /// The serial number of the run in progress.
+    pub in_progress: Option<String>,
+    /// The runs that have not been started, `None` if the quantity is open-ended.
+    pub pending: Option<usize>,
+    pub runs: Vec<ProductionRun>,
+}
+
+pub fn build_report(project: &Project) -> ProductionRunReport {
+    let completed = project.production_runs.iter()
+        .filter(|production_run| production_run.is_completed())
+        .count();
+
+    ProductionRunReport {
+        quantity: project.production_quantity,
+        completed,
+        in_progress: find_current_run(project).map(|production_run| production_run.serial.clone()),
+        // the quantity can be lowered after runs have been started
+        pending: project.production_quantity.map(|quantity| (quantity as usize).saturating_sub(project.production_runs.len())),
+        runs: project.production_runs.clone(),
+    }
+}
+
+impl Display for ProductionRunReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let format_optional = |value: Option<String>| value.unwrap_or("-".to_string());
+
+        writeln!(f, "Production runs: quantity: {}, completed: {}, in progress: {}, pending: {}",
+            format_optional(self.quantity.map(|quantity| quantity.to_string())),
+            self.completed,
+            format_optional(self.in_progress.clone()),
+            format_optional(self.pending.map(|pending| pending.to_string())),
+        )?;
+
+        for production_run in self.runs.iter() {
+            match production_run.completed_at {
+                Some(completed_at) => writeln!(f, "Run: {}, status: completed, placed: {}/{}, started: {}, completed: {}",
+                    production_run.serial, production_run.placed.len(), production_run.placements, production_run.started_at, completed_at,
+                )?,
+                None => writeln!(f, "Run: {}, status: in progress, started: {}",
+                    production_run.serial, production_run.started_at,
+                )?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::OffsetDateTime;
+    use pnp::part::Part;
+    use pnp::pcb::PcbSide;
+    use crate::process::{ProcessOperationKind, ProcessOperationStatus};
+    use crate::production_run::{build_report, complete, find_current_run, start, update_quantity, ProductionRunError};
+    use crate::project::Project;
+    use crate::test::project_builder::{PlacementStateBuilder, ProjectBuilder};
+
+    fn build_project() -> Project {
+        let res1 = Part::new("RES_MFR1".to_string(), "RES1".to_string());
+
+        ProjectBuilder::new()
+            .with_phase("top_1", "pnp", "load_out_1.csv", PcbSide::Top)
+            .with_placements(["R1", "R2"].map(|ref_des| PlacementStateBuilder::new(1, ref_des, &res1)))
+            .build()
+    }
+
+    fn place_all(project: &mut Project) {
+        for placement_state in project.placements.values_mut() {
+            placement_state.placed = true;
+        }
+    }
+
+    #[test]
+    pub fn production_runs() {
+        // given
+        let mut project = build_project();
+        let now = OffsetDateTime::UNIX_EPOCH;
+        update_quantity(&mut project, Some(3));
+
+        // and the first placement is placed before the first run is started
+        project.placements.values_mut().next().unwrap().placed = true;
+
+        // when
+        start(&mut project, "SN001".to_string(), now).unwrap();
+
+        // then the progress is retained
+        assert_eq!(project.placements.values().filter(|placement_state| placement_state.placed).count(), 1);
+
+        // and another run cannot be started
+        let result = start(&mut project, "SN002".to_string(), now);
+        assert_eq!(result, Err(ProductionRunError::InProgress("SN001".to_string())));
+
+        // and an incomplete run is not completed unless forced
+        assert_eq!(complete(&mut project, now, false), Err(ProductionRunError::Incomplete { serial: "SN001".to_string(), placed: 1, placements: 2 }));
+
+        // when
+        place_all(&mut project);
+        let production_run = complete(&mut project, now, false).unwrap();
+
+        // then
+        assert_eq!(production_run.placed.len(), 2);
+        assert_eq!(production_run.placements, 2);
+        assert!(find_current_run(&project).is_none());
+
+        // and the phase operations are completed
+        let phase_state = project.phase_states.values_mut().next().unwrap();
+        phase_state.operation_state.get_mut(&ProcessOperationKind::LoadPcbs).unwrap().status = ProcessOperationStatus::Complete;
+
+        // when the next run is started
+        start(&mut project, "SN002".to_string(), now).unwrap();
+
+        // then the placements are reset for the run
+        assert!(project.placements.values().all(|placement_state| !placement_state.placed));
+
+        // and the phase operations are pending again
+        assert!(project.phase_states.values()
+            .flat_map(|phase_state| phase_state.operation_state.values())
+            .all(|state| state.status.eq(&ProcessOperationStatus::Pending)));
+
+        // and the serial numbers are unique
+        complete(&mut project, now, true).unwrap();
+        let result = start(&mut project, "SN001".to_string(), now);
+        assert_eq!(result, Err(ProductionRunError::DuplicateSerial("SN001".to_string())));
+
+        // and the third run is the last
+        start(&mut project, "SN003".to_string(), now).unwrap();
+        complete(&mut project, now, true).unwrap();
+        assert_eq!(start(&mut project, "SN004".to_string(), now), Err(ProductionRunError::QuantityReached(3)));
+
+        // when
+        let report = build_report(&project);
+
+        // then
+        assert_eq!(report.completed, 3);
+        assert_eq!(report.in_progress, None);
+        assert_eq!(report.pending, Some(0));
+        assert_eq!(report.runs[1].placed.len(), 0);
+        assert!(report.to_string().starts_with("Production runs: quantity: 3, completed: 3, in progress: -, pending: 0\n\
+            Run: SN001, status: completed, placed: 2/2, "));
+    }
+
+    #[test]
+    pub fn complete_without_run() {
+        // given
+        let mut project = build_project();
+
+        // expect
+        assert_eq!(complete(&mut project, OffsetDateTime::UNIX_EPOCH, true), Err(ProductionRunError::NotInProgress));
+        assert_eq!(start(&mut project, " ".to_string(), OffsetDateTime::UNIX_EPOCH), Err(ProductionRunError::InvalidSerial));
+    }
+}
@@ -0,0 +1,76 @@
+//! Progress of long-running operations, e.g. refreshing the placements of large projects and generating artifacts.
+//!
+//! The operations report their progress to a `ProgressReporter`, each report has the stage of the operation and the
+//! percentage of the whole operation that is complete, so a single progress bar can be used for the operation.
+
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressStage {
+    CheckingPlacementConflicts,
+    RefreshingParts,
+    RefreshingPlacements,
+    BuildingArtifacts,
+    WritingArtifacts,
+    Complete,
+}
+
+impl Display for ProgressStage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProgressStage::CheckingPlacementConflicts => write!(f, "Checking placement conflicts"),
+            ProgressStage::RefreshingParts => write!(f, "Refreshing parts"),
+            ProgressStage::RefreshingPlacements => write!(f, "Refreshing placements"),
+            ProgressStage::BuildingArtifacts => write!(f, "Building artifacts"),
+            ProgressStage::WritingArtifacts => write!(f, "Writing artifacts"),
+            ProgressStage::Complete => write!(f, "Complete"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    pub stage: ProgressStage,
+    /// 0-100, of the whole operation, not just the stage.
+    pub percent: u8,
+}
+
+impl Progress {
+    /// The progress of `completed` of `count` items of a stage that spans `from` to `to` percent of the operation.
+    pub fn of_items(stage: ProgressStage, from: u8, to: u8, completed: usize, count: usize) -> Self {
+        let percent = match count {
+            0 => to,
+            _ => from + ((to - from) as usize * completed.min(count) / count) as u8,
+        };
+
+        Self { stage, percent }
+    }
+}
+
+pub trait ProgressReporter {
+    fn report(&self, progress: Progress);
+}
+
+/// For callers that do not show the progress.
+pub struct NoProgress;
+
+impl ProgressReporter for NoProgress {
+    fn report(&self, _progress: Progress) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::progress::{Progress, ProgressStage};
+
+    #[test]
+    pub fn progress_of_items() {
+        // expect
+        assert_eq!(Progress::of_items(ProgressStage::BuildingArtifacts, 0, 50, 0, 4).percent, 0);
+        assert_eq!(Progress::of_items(ProgressStage::BuildingArtifacts, 0, 50, 1, 4).percent, 12);
+        assert_eq!(Progress::of_items(ProgressStage::BuildingArtifacts, 0, 50, 4, 4).percent, 50);
+        assert_eq!(Progress::of_items(ProgressStage::WritingArtifacts, 50, 100, 3, 2).percent, 100);
+
+        // and a stage with no items is complete
+        assert_eq!(Progress::of_items(ProgressStage::WritingArtifacts, 50, 100, 0, 0).percent, 100);
+    }
+}
@@ -2,9 +2,11 @@ use std::collections::btree_map::Entry;
 use tracing::{debug, info, trace, warn};
 use serde_with::serde_as;
 use serde_with::DisplayFromStr;
-use std::collections::{BTreeMap, BTreeSet, HashMap};
-use std::path::PathBuf;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
 use thiserror::Error;
 use anyhow::Error;
 use indexmap::IndexSet;
@@ -19,24 +21,38 @@ use heck::ToShoutySnakeCase;
 use time::OffsetDateTime;
 use pnp;
 use pnp::load_out::LoadOutItem;
-use pnp::object_path::ObjectPath;
-use pnp::part::Part;
-use pnp::placement::Placement;
-use pnp::pcb::{Pcb, PcbKind, PcbSide};
+use pnp::object_path::{ObjectPath, ObjectPathError, RefDes};
+use pnp::part::{Part, PartDetails};
+use pnp::placement::{Placement, PlacementKind};
+use pnp::pcb::{PanelGeometry, Pcb, PcbDimensions, PcbKind, PcbSide};
 use util::sorting::SortOrder;
 
-use crate::design::DesignVariant;
+use crate::design::{DesignVariant, DesignVariantError};
+use crate::variant::VariantName;
 use crate::reference::Reference;
+use crate::release::Release;
+use crate::production_run::ProductionRun;
+use crate::estimation::EstimationParameters;
+use crate::progress::{NoProgress, Progress, ProgressReporter, ProgressStage};
+use crate::locking::{LockMode, ProjectLock};
 use crate::part::PartState;
-use crate::phase::{Phase, PhaseError, PhaseOrderings, PhaseState};
-use crate::placement::{PlacementOperation, PlacementSortingItem, PlacementSortingMode, PlacementState, PlacementStatus};
-use crate::process::{PlacementsState, Process, ProcessError, ProcessName, ProcessNameError, ProcessOperationExtraState, ProcessOperationKind, ProcessOperationSetItem, ProcessOperationState, ProcessOperationStatus};
-use crate::{operation_history, placement, report};
-use crate::operation_history::{OperationHistoryItem, OperationHistoryKind};
+use crate::moisture::{MoistureSensitivity, MslLevel};
+use crate::phase::{FeederExposure, Phase, PhaseDependencyError, PhaseError, PhaseOrderings, PhaseState, PhaseTag, WorkInstructionsStyle};
+use crate::placement::{PlacementConflict, PlacementConflictKind, PlacementDefect, PlacementDefectStatus, ObjectPathMatcher, PlacementOperation, PlacementOverride, PlacementSortingItem, PlacementSortingMode, PlacementState, PlacementStatus, RotationNormalization};
+use crate::process::{ArtifactType, OperationTransitions, PlacementsState, Process, ProcessError, ProcessName, ProcessNameError, ProcessOperationExtraState, ProcessOperationKind, ProcessOperationSetItem, ProcessOperationState, ProcessOperationStatus};
+use crate::{compression, export, feeder_setup, first_article, locking, moisture, nozzle, operation_history, phase, phase_export, placement, report, work_instructions};
+use crate::operation_history::{OperationHistoryError, OperationHistoryItem, OperationHistoryKind, OperationHistoryVerification};
 use crate::report::{IssueKind, IssueSeverity, ProjectReportIssue};
+use crate::report::render;
+use crate::report::render::ReportFormat;
+use crate::issue::IssueResolution;
+use crate::pricing::PriceList;
+use crate::inventory::Inventory;
+use crate::nozzle::{NozzleAssignments, NozzleConfiguration};
+use crate::mutation::MUTATION_TARGET;
 
 #[serde_as]
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub struct Project {
     pub name: String,
@@ -47,11 +63,23 @@ pub struct Project {
     #[serde(default)]
     pub pcbs: Vec<Pcb>,
 
+    /// Design variants registered from the placements files found in the project directory, a unit can only be
+    /// assigned a registered variant of a design that has registered variants.
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    #[serde(default)]
+    pub design_variants: BTreeSet<DesignVariant>,
+
     #[serde_as(as = "Vec<(DisplayFromStr, _)>")]
     #[serde(skip_serializing_if = "BTreeMap::is_empty")]
     #[serde(default)]
     pub unit_assignments: BTreeMap<ObjectPath, DesignVariant>,
 
+    /// The revisions of the design variants, recorded when the placements are refreshed, see `check_design_revisions`.
+    #[serde_as(as = "Vec<(_, _)>")]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    #[serde(default)]
+    pub design_revisions: BTreeMap<DesignVariant, String>,
+
     #[serde_as(as = "Vec<(_, _)>")]
     #[serde(skip_serializing_if = "BTreeMap::is_empty")]
     #[serde(default)]
@@ -75,6 +103,51 @@ pub struct Project {
     #[serde(skip_serializing_if = "BTreeMap::is_empty")]
     #[serde(default)]
     pub placements: BTreeMap<ObjectPath, PlacementState>,
+
+    #[serde(skip_serializing_if = "OperationTransitions::is_automatic")]
+    #[serde(default)]
+    pub operation_transitions: OperationTransitions,
+
+    /// Releases to production, the last release is the current release unless it has been reopened.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub releases: Vec<Release>,
+
+    /// The quantity of panels, or boards, to build, see `production_run`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub production_quantity: Option<u32>,
+
+    /// Production runs, in the order they were started, see `production_run`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub production_runs: Vec<ProductionRun>,
+
+    /// Acknowledged and waived report issues, by issue id, see `issue::build_issue_id`.
+    #[serde_as(as = "Vec<(_, _)>")]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    #[serde(default)]
+    pub issue_resolutions: BTreeMap<String, IssueResolution>,
+
+    /// Price list file, relative to the project directory, used for the cost estimates of the report, see `pricing`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub price_list_source: Option<String>,
+
+    /// Inventory file, relative to the project directory, used for the inventory shortages of the report, see `inventory`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub inventory_source: Option<String>,
+
+    /// Parameters of the phase duration estimates of the report and status, see `estimation`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub estimation_parameters: Option<EstimationParameters>,
+
+    /// The placement conflicts of the design variants that were accepted using `--force`, see `refresh_from_design_variants`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub placement_conflicts: Vec<PlacementConflict>,
 }
 
 impl Project {
@@ -86,25 +159,38 @@ impl Project {
     }
 
     pub fn ensure_process(&mut self, process: &Process) -> anyhow::Result<()> {
-        if !self.processes.contains(process) {
-            info!("Adding process to project.  process: '{}'", process.name);
+        // processes are compared by name, so that the required artifacts of an existing process are retained
+        if !self.processes.iter().any(|existing| existing.name.eq(&process.name)) {
+            info!(target: MUTATION_TARGET, process = %process.name, message = "Adding process to project.  process: '{process}'");
             self.processes.push(process.clone())
         }
         Ok(())
     }
 
     pub fn update_assignment(&mut self, object_path: ObjectPath, design_variant: DesignVariant) -> anyhow::Result<()> {
+        let registered_variants: Vec<&VariantName> = self.design_variants.iter()
+            .filter(|registered| registered.design_name.eq(&design_variant.design_name))
+            .map(|registered| &registered.variant_name)
+            .collect();
+
+        if !registered_variants.is_empty() && !registered_variants.contains(&&design_variant.variant_name) {
+            return Err(DesignVariantError::UnregisteredVariant {
+                design_variant,
+                registered_variants: registered_variants.iter().map(|variant_name| variant_name.to_string()).collect(),
+            }.into())
+        }
+
         match self.unit_assignments.entry(object_path.clone()) {
             Entry::Vacant(entry) => {
                 entry.insert(design_variant.clone());
-                info!("Unit assignment added. unit: '{}', design_variant: {}", object_path, design_variant )
+                info!(target: MUTATION_TARGET, unit = %object_path, design_variant = %design_variant, message = "Unit assignment added. unit: '{unit}', design_variant: {design_variant}");
             }
             Entry::Occupied(mut entry) => {
                 if entry.get().eq(&design_variant) {
                     info!("Unit assignment unchanged.")
                 } else {
                     let old_value = entry.insert(design_variant.clone());
-                    info!("Unit assignment updated. unit: '{}', old: {}, new: {}", object_path, old_value, design_variant )
+                    info!(target: MUTATION_TARGET, unit = %object_path, old = %old_value, new = %design_variant, message = "Unit assignment updated. unit: '{unit}', old: {old}, new: {new}");
                 }
             }
         }
@@ -116,9 +202,9 @@ impl Project {
         
         match self.phases.entry(reference.clone()) {
             Entry::Vacant(entry) => {
-                let phase = Phase { reference: reference.clone(), process: process_name.clone(), load_out_source: load_out_source.clone(), pcb_side: pcb_side.clone(), placement_orderings: vec![] };
+                let phase = Phase { reference: reference.clone(), process: process_name.clone(), load_out_source: load_out_source.clone(), pcb_side: pcb_side.clone(), placement_orderings: vec![], work_instructions_style: Default::default(), tags: Default::default(), first_article_inspection_required: false, nozzle_configuration: None, quantity_check: None, export_format: None, rotation_normalization: None, dependencies: Default::default() };
                 entry.insert(phase);
-                info!("Created phase. reference: '{}', process: {}, load_out: {:?}", reference, process_name, load_out_source);
+                info!(target: MUTATION_TARGET, reference = %reference, process = %process_name, load_out = %load_out_source, message = "Created phase. reference: '{reference}', process: {process}, load_out: {load_out}");
                 self.phase_orderings.insert(reference.clone());
                 info!("Phase ordering: {}", PhaseOrderings(&self.phase_orderings));
 
@@ -133,7 +219,7 @@ impl Project {
                 existing_phase.process = process_name;
                 existing_phase.load_out_source = load_out_source;
 
-                info!("Updated phase. old: {:?}, new: {:?}", old_phase, existing_phase);
+                info!(target: MUTATION_TARGET, old = ?old_phase, new = ?existing_phase, message = "Updated phase. old: {old}, new: {new}");
             }
         }
 
@@ -142,12 +228,44 @@ impl Project {
 
     pub fn find_process(&self, process_name: &ProcessName) -> Result<&Process, ProcessError> {
         self.processes.iter().find(|&process| {
-            process.name.eq(&process_name)
+            process.name.eq(process_name)
         }).ok_or(
             ProcessError::UnusedProcessError { processes: self.processes.clone(), process: process_name.to_string() }
         )
     }
 
+    /// The details of the part, e.g. from a parts master, `None` if the part is unknown or has no details.
+    pub fn find_part_details(&self, part: &Part) -> Option<&PartDetails> {
+        self.part_states.get(part)
+            .map(|part_state| &part_state.details)
+            .filter(|details| !details.is_empty())
+    }
+
+    /// The PCB of a unit, or placement, path.
+    pub fn find_pcb(&self, object_path: &ObjectPath) -> Option<&Pcb> {
+        let (kind, index) = object_path.pcb_kind_and_index()?;
+
+        pnp::pcb::find_pcb(&self.pcbs, &kind, index.get())
+    }
+
+    pub fn find_pcb_by_name(&self, name: &str) -> Result<&Pcb, PcbOperationError> {
+        self.pcbs.iter()
+            .find(|pcb| pcb.name.eq(name))
+            .ok_or_else(|| PcbOperationError::UnknownPcb { name: name.to_string() })
+    }
+
+    /// The PCBs with placements that are assigned to the phase, in the order the PCBs were added.
+    pub fn find_phase_pcbs(&self, reference: &Reference) -> Vec<&Pcb> {
+        let phase_pcbs: BTreeSet<&Pcb> = self.placements.values()
+            .filter(|placement_state| placement_state.phase.as_ref().is_some_and(|phase| phase.eq(reference)))
+            .filter_map(|placement_state| self.find_pcb(&placement_state.unit_path))
+            .collect();
+
+        self.pcbs.iter()
+            .filter(|pcb| phase_pcbs.contains(pcb))
+            .collect()
+    }
+
     pub fn unique_design_variants(&self) -> Vec<DesignVariant> {
         let unique_design_variants: Vec<DesignVariant> = self.unit_assignments.iter().fold(vec![], |mut acc, (_path, design_variant)| {
             if !acc.contains(design_variant) {
@@ -182,15 +300,29 @@ impl ProcessFactory {
         match name {
             "pnp" => Ok(Process { 
                 name: process_name, 
-                operations: vec![ProcessOperationKind::LoadPcbs, ProcessOperationKind::AutomatedPnp, ProcessOperationKind::ReflowComponents] 
+                operations: vec![ProcessOperationKind::LoadPcbs, ProcessOperationKind::AutomatedPnp, ProcessOperationKind::ReflowComponents],
+                required_artifacts: vec![],
+                checklists: Default::default(),
+                placement_operations: Default::default(),
             }),
             "manual" => Ok(Process { 
                 name: process_name,
-                operations: vec![ProcessOperationKind::LoadPcbs, ProcessOperationKind::ManuallySolderComponents] 
+                operations: vec![ProcessOperationKind::LoadPcbs, ProcessOperationKind::ManuallySolderComponents],
+                required_artifacts: vec![],
+                checklists: Default::default(),
+                placement_operations: Default::default(),
             }),
             _ => Err(ProcessFactoryError::UnknownProcessName { process: process_name.to_string() })
         }
     }
+
+    /// The process of the definitions, or the built-in process, so that a definition can replace a built-in process.
+    pub fn by_name_with_definitions(name: &str, definitions: &[Process]) -> Result<Process, ProcessFactoryError> {
+        match definitions.iter().find(|definition| definition.name.0.eq(name)) {
+            Some(definition) => Ok(definition.clone()),
+            None => Self::by_name(name),
+        }
+    }
 }
 
 impl Default for Project {
@@ -202,751 +334,4094 @@ impl Default for Project {
                 ProcessFactory::by_name("manual").unwrap(),
             ],
             pcbs: vec![],
+            design_variants: Default::default(),
             unit_assignments: Default::default(),
+            design_revisions: Default::default(),
             part_states: Default::default(),
             phases: Default::default(),
             placements: Default::default(),
             phase_orderings: Default::default(),
             phase_states: Default::default(),
+            operation_transitions: Default::default(),
+            releases: Default::default(),
+            production_quantity: None,
+            production_runs: Default::default(),
+            issue_resolutions: Default::default(),
+            price_list_source: None,
+            inventory_source: None,
+            estimation_parameters: None,
+            placement_conflicts: Default::default(),
+        }
+    }
+}
+
+/// Registers the design variants, returns true if any were not already registered.
+pub fn register_design_variants(project: &mut Project, design_variants: &[DesignVariant]) -> bool {
+    let mut modified = false;
+
+    for design_variant in design_variants.iter() {
+        if project.design_variants.insert(design_variant.clone()) {
+            info!(target: MUTATION_TARGET, design_variant = %design_variant, message = "Registered design variant. design_variant: {design_variant}");
+            modified = true;
+        } else {
+            debug!("Design variant already registered. design_variant: {}", design_variant);
         }
     }
+
+    modified
 }
 
 #[derive(Error, Debug)]
 pub enum PcbOperationError {
+    #[error("A PCB with the name already exists. name: '{name}'")]
+    DuplicateName { name: String },
+
+    #[error("Unknown PCB. name: '{name}'")]
+    UnknownPcb { name: String },
+
+    #[error("The PCB is not a panel. name: '{name}'")]
+    NotAPanel { name: String },
 }
 
 pub fn add_pcb(project: &mut Project, kind: PcbKind, name: String) -> Result<(), PcbOperationError> {
-    project.pcbs.push(Pcb { kind: kind.clone(), name: name.clone() });
-    
-    match kind {
-        PcbKind::Single => info!("Added single PCB. name: '{}'", name),
-        PcbKind::Panel => info!("Added panel PCB. name: '{}'", name),
+    // names identify the PCBs when filtering, e.g. when a product has a main board and a display board
+    if project.pcbs.iter().any(|pcb| pcb.name.eq(&name)) {
+        return Err(PcbOperationError::DuplicateName { name })
     }
+
+    project.pcbs.push(Pcb::new(kind.clone(), name.clone()));
+    
+    let kind = match kind {
+        PcbKind::Single => "single",
+        PcbKind::Panel => "panel",
+    };
+    info!(target: MUTATION_TARGET, kind, name = %name, message = "Added {kind} PCB. name: '{name}'");
     Ok(())
 }
 
-#[derive(Error, Debug)]
-pub enum ArtifactGenerationError {
-    #[error("Unable to generate phase placements. cause: {0:}")]
-    PhasePlacementsGenerationError(Error),
+/// Replaces the geometry of the panel, the placements of each unit are transformed into panel-space when generating
+/// artifacts.
+pub fn set_panel_geometry(project: &mut Project, name: &str, geometry: PanelGeometry) -> Result<bool, PcbOperationError> {
+    let pcb = project.pcbs.iter_mut()
+        .find(|pcb| pcb.name.eq(name))
+        .ok_or_else(|| PcbOperationError::UnknownPcb { name: name.to_string() })?;
 
-    #[error("Unable to load items. source: {load_out_source}, error: {reason}")]
-    UnableToLoadItems { load_out_source: String, reason: anyhow::Error },
+    if pcb.kind != PcbKind::Panel {
+        return Err(PcbOperationError::NotAPanel { name: name.to_string() })
+    }
 
-    #[error("Unable to generate report. error: {reason}")]
-    ReportGenerationError { reason: anyhow::Error },
-}
+    if pcb.geometry.as_ref().eq(&Some(&geometry)) {
+        info!("Panel geometry unchanged. name: '{}'", name);
+        return Ok(false)
+    }
 
-pub fn generate_artifacts(project: &Project, path: &PathBuf, name: &String, phase_load_out_items_map: BTreeMap<Reference, Vec<LoadOutItem>>) -> Result<(), ArtifactGenerationError> {
-    
-    let mut issues: BTreeSet<ProjectReportIssue> = BTreeSet::new();
-    
+    info!(target: MUTATION_TARGET, name = %name, units = geometry.units.len(), fiducials = geometry.fiducials.len(), message = "Panel geometry updated. name: '{name}', units: {units}, fiducials: {fiducials}");
+    pcb.geometry = Some(geometry);
 
-    for reference in project.phase_orderings.iter() {
-        let phase = project.phases.get(reference).unwrap();
+    Ok(true)
+}
 
-        let load_out_items = phase_load_out_items_map.get(reference).unwrap();
-        
-        generate_phase_artifacts(project, phase, load_out_items.as_slice(), path, &mut issues)?;
+/// Replaces the dimensions of the board or panel, the coordinates of the placements are validated against the dimensions
+/// when generating artifacts.
+pub fn set_pcb_dimensions(project: &mut Project, name: &str, dimensions: PcbDimensions) -> Result<bool, PcbOperationError> {
+    let pcb = project.pcbs.iter_mut()
+        .find(|pcb| pcb.name.eq(name))
+        .ok_or_else(|| PcbOperationError::UnknownPcb { name: name.to_string() })?;
+
+    if pcb.dimensions.as_ref().eq(&Some(&dimensions)) {
+        info!("PCB dimensions unchanged. name: '{}'", name);
+        return Ok(false)
     }
-        
-    report::project_generate_report(project, path, name, &phase_load_out_items_map, &mut issues).map_err(|err|{
-        ArtifactGenerationError::ReportGenerationError { reason: err.into() }
-    })?;
-    
-    info!("Generated artifacts.");
-    
-    Ok(())
+
+    info!(target: MUTATION_TARGET, name = %name, width = %dimensions.width, height = %dimensions.height, message = "PCB dimensions updated. name: '{name}', width: {width}, height: {height}");
+    pcb.dimensions = Some(dimensions);
+
+    Ok(true)
 }
 
-fn generate_phase_artifacts(project: &Project, phase: &Phase, load_out_items: &[LoadOutItem], path: &PathBuf, issues: &mut BTreeSet<ProjectReportIssue>) -> Result<(), ArtifactGenerationError> {
-    let mut placement_states: Vec<(&ObjectPath, &PlacementState)> = project.placements.iter().filter_map(|(object_path, state)|{
-        match &state.phase {
-            Some(placement_phase) if placement_phase.eq(&phase.reference) => Some((object_path, state)),
-            _ => None
-        }
-    }).collect();
-    
-    placement_states.sort_by(|(object_path_a, placement_state_a), (object_path_b, placement_state_b)|{
-        phase.placement_orderings.iter().fold(Ordering::Equal, |mut acc, sort_ordering | {
-            if !matches!(acc, Ordering::Equal) {
-                return acc
-            }
-            acc = match sort_ordering.mode {
-                PlacementSortingMode::FeederReference => {
-                    let feeder_reference_a = match pnp::load_out::find_load_out_item_by_part(&load_out_items, &placement_state_a.placement.part) {
-                        Some(load_out_item) => load_out_item.reference.clone(),
-                        _ => "".to_string(),
-                    };
-                    let feeder_reference_b = match pnp::load_out::find_load_out_item_by_part(&load_out_items, &placement_state_b.placement.part) {
-                        Some(load_out_item) => load_out_item.reference.clone(),
-                        _ => "".to_string(),
-                    };
-
-                    trace!("Comparing feeder references. feeder_reference_a: '{}' feeder_reference_a: '{}'", feeder_reference_a, feeder_reference_b);
-                    feeder_reference_a.cmp(&feeder_reference_b)
-                },
-                PlacementSortingMode::PcbUnit => {
-                   
-                    let pcb_unit_a = object_path_a.pcb_unit();
-                    let pcb_unit_b = object_path_b.pcb_unit();
-                    
-                    trace!("Comparing pcb units, pcb_unit_a: '{}', pcb_unit_b: '{}'", pcb_unit_a, pcb_unit_b);
-                    pcb_unit_a.cmp(&pcb_unit_b)
+/// Transforms the design-space coordinates of the placements into panel-space, for each panel with a geometry.
+///
+/// Placements of units that are not in the geometry of their panel are not transformed, and an issue is added.
+fn transform_to_panel_space<'a>(project: &Project, placement_states: &[(&'a ObjectPath, &PlacementState)], issues: &mut BTreeSet<ProjectReportIssue>) -> Vec<(&'a ObjectPath, PlacementState)> {
+    placement_states.iter().map(|(object_path, placement_state)| {
+        let mut placement_state = (*placement_state).clone();
+
+        let geometry = project.find_pcb(object_path)
+            .and_then(|pcb| pcb.geometry.as_ref());
+
+        if let Some(geometry) = geometry {
+            match object_path.unit_index().and_then(|index| geometry.find_unit(index.get())) {
+                Some(panel_unit) => {
+                    let placement = &mut placement_state.placement;
+                    (placement.x, placement.y, placement.rotation) = panel_unit.transform(placement.x, placement.y, placement.rotation);
                 },
-            };
-            
-            match sort_ordering.sort_order {
-                SortOrder::Asc => acc,
-                SortOrder::Desc => {
-                    acc.reverse()
+                None => {
+                    issues.insert(ProjectReportIssue {
+                        message: "A unit is not in the panel geometry, the placements were not transformed".to_string(),
+                        severity: IssueSeverity::Severe,
+                        kind: IssueKind::MissingPanelUnitGeometry { object_path: object_path.pcb_unit() },
+                    });
                 },
             }
-        })
-    });
+        }
 
-    for (_object_path, placement_state) in placement_states.iter() {
-        let feeder_reference = match pnp::load_out::find_load_out_item_by_part(&load_out_items, &placement_state.placement.part) {
-            Some(load_out_item) => load_out_item.reference.clone(),
-            _ => "".to_string(),
+        (*object_path, placement_state)
+    }).collect()
+}
+
+/// Adds an issue for each placement, to be placed, that is outside the dimensions of its PCB, e.g. due to a unit mismatch
+/// or an incorrect panel geometry.
+///
+/// The coordinates must already have been transformed, see `transform_to_panel_space`.
+fn add_placement_outside_pcb_issues(project: &Project, placement_states: &[(&ObjectPath, &PlacementState)], issues: &mut BTreeSet<ProjectReportIssue>) {
+    for (object_path, placement_state) in placement_states.iter() {
+        let placement = &placement_state.placement;
+        if !placement_state.place() {
+            continue
+        }
+
+        let Some(pcb) = project.find_pcb(object_path) else {
+            continue
         };
-        
-        if feeder_reference.is_empty() {
-            let issue = ProjectReportIssue {
-                message: "A part has not been assigned to a feeder".to_string(),
-                severity: IssueSeverity::Warning,
-                kind: IssueKind::UnassignedPartFeeder { part: placement_state.placement.part.clone() },
-            };
-            issues.insert(issue);
+        let Some(dimensions) = pcb.dimensions.as_ref() else {
+            continue
         };
+
+        if !dimensions.contains(placement.x, placement.y) {
+            warn!("Placement outside PCB. object_path: {}, x: {}, y: {}, pcb: '{}', width: {}, height: {}", object_path, placement.x, placement.y, pcb.name, dimensions.width, dimensions.height);
+
+            issues.insert(ProjectReportIssue {
+                message: "A placement is outside the dimensions of the PCB, check the units and the panel geometry".to_string(),
+                severity: IssueSeverity::Severe,
+                kind: IssueKind::PlacementOutsidePcb { object_path: (*object_path).clone(), pcb: pcb.name.clone() },
+            });
+        }
     }
+}
 
-    let mut phase_placements_path = PathBuf::from(path);
-    phase_placements_path.push(format!("{}_placements.csv", phase.reference));
+#[derive(Error, Debug)]
+pub enum ArtifactGenerationError {
+    #[error("Unable to generate phase placements. cause: {0:}")]
+    PhasePlacementsGenerationError(Error),
 
-    store_phase_placements_as_csv(&phase_placements_path, &placement_states, load_out_items).map_err(|e|{
-        ArtifactGenerationError::PhasePlacementsGenerationError(e)
-    })?;
+    #[error("Unable to generate rework instructions. cause: {0:}")]
+    ReworkInstructionsGenerationError(Error),
 
-    info!("Generated phase placements. phase: '{}', path: {:?}", phase.reference, phase_placements_path);
+    #[error("Unable to generate first-article checklist. cause: {0:}")]
+    FirstArticleChecklistGenerationError(Error),
 
-    Ok(())
-}
+    #[error("Unable to generate phase export. cause: {0:}")]
+    PhaseExportGenerationError(Error),
 
-#[serde_as]
-#[derive(Debug, serde::Serialize)]
-#[serde(rename_all(serialize = "PascalCase"))]
-pub struct PhasePlacementRecord {
+    #[error("Unable to generate machine export. cause: {0:}")]
+    MachineExportGenerationError(Error),
 
-    #[serde_as(as = "DisplayFromStr")]
-    pub object_path: ObjectPath,
-    
-    pub feeder_reference: String,
-    pub manufacturer: String,
-    pub mpn: String,
-    pub x: Decimal,
-    pub y: Decimal,
-    pub rotation: Decimal,
-}
+    #[error("Unable to generate feeder setup sheet. cause: {0:}")]
+    FeederSetupSheetGenerationError(Error),
 
-pub fn store_phase_placements_as_csv(output_path: &PathBuf, placement_states: &[(&ObjectPath, &PlacementState)], load_out_items: &[LoadOutItem]) -> Result<(), Error> {
-    
-    trace!("Writing phase placements. output_path: {:?}", output_path);
+    #[error("Unable to generate fiducials. cause: {0:}")]
+    FiducialsGenerationError(Error),
 
-    let mut writer = csv::WriterBuilder::new()
-        .quote_style(QuoteStyle::Always)
-        .from_path(output_path)?;
+    #[error("Unable to load items. source: {load_out_source}, error: {reason}")]
+    UnableToLoadItems { load_out_source: String, reason: anyhow::Error },
 
-    for (object_path, placement_state) in placement_states.iter() {
-        
-        let feeder_reference = match pnp::load_out::find_load_out_item_by_part(&load_out_items, &placement_state.placement.part) {
-            Some(load_out_item) => load_out_item.reference.clone(),
-            _ => "".to_string(),
-        };
-        
-        writer.serialize(
-            PhasePlacementRecord {
-                object_path: (*object_path).clone(),
-                feeder_reference,
-                manufacturer: placement_state.placement.part.manufacturer.to_string(),
-                mpn: placement_state.placement.part.mpn.to_string(),
-                x: placement_state.placement.x,
-                y: placement_state.placement.y,
-                rotation: placement_state.placement.rotation,
-            }
-        )?;
+    #[error("Unable to generate report. error: {reason}")]
+    ReportGenerationError { reason: anyhow::Error },
+
+    #[error("Unable to write artifact. path: {path:?}, error: {reason}")]
+    UnableToWriteArtifact { path: PathBuf, reason: std::io::Error },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArtifactKind {
+    PhasePlacements { phase: Reference },
+    ReworkInstructions { phase: Reference },
+    WorkInstructions { phase: Reference },
+    PhaseExport { phase: Reference },
+    FirstArticleChecklist { phase: Reference },
+    MachineExport { phase: Reference },
+    FeederSetupSheet { phase: Reference },
+    /// The feeder setup sheet, rendered for printing.
+    HtmlFeederSetupSheet { phase: Reference },
+    /// The fiducials of a PCB, for machine setup.
+    Fiducials { pcb: String },
+    Report,
+    /// The report, rendered for people.
+    HtmlReport,
+}
+
+impl ArtifactKind {
+    /// Returns the phase and the type of the artifact, `None` for project artifacts.
+    pub fn phase_artifact_type(&self) -> Option<(&Reference, ArtifactType)> {
+        match self {
+            ArtifactKind::PhasePlacements { phase } => Some((phase, ArtifactType::PhasePlacements)),
+            ArtifactKind::ReworkInstructions { phase } => Some((phase, ArtifactType::ReworkInstructions)),
+            ArtifactKind::WorkInstructions { phase } => Some((phase, ArtifactType::WorkInstructions)),
+            ArtifactKind::PhaseExport { phase } => Some((phase, ArtifactType::PhaseExport)),
+            ArtifactKind::FirstArticleChecklist { phase } => Some((phase, ArtifactType::FirstArticleChecklist)),
+            ArtifactKind::MachineExport { phase } => Some((phase, ArtifactType::MachineExport)),
+            ArtifactKind::FeederSetupSheet { phase } | ArtifactKind::HtmlFeederSetupSheet { phase } => Some((phase, ArtifactType::FeederSetupSheet)),
+            ArtifactKind::Fiducials { .. } | ArtifactKind::Report | ArtifactKind::HtmlReport => None,
+        }
     }
+}
 
-    writer.flush()?;
-    
-    Ok(())
+/// An artifact that has been generated in-memory, but not written to disk.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Artifact {
+    pub kind: ArtifactKind,
+    pub file_name: String,
+    pub content: Vec<u8>,
 }
 
-pub fn assign_placements_to_phase(project: &mut Project, phase: &Phase, placements_pattern: Regex) -> BTreeSet<Part> {
-    let mut required_load_out_parts = BTreeSet::new();
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArtifactPreview {
+    pub kind: ArtifactKind,
+    pub file_name: String,
+    /// The content, limited to the requested amount of lines.
+    pub content: String,
+    /// The size of the complete content, in bytes.
+    pub size: usize,
+    pub truncated: bool,
+}
 
-    for (placement_path, state) in project.placements.iter_mut().filter(|(path, state)| {
-        let path_str = format!("{}", path);
+impl ArtifactPreview {
+    pub fn from_artifact(artifact: &Artifact, max_lines: usize) -> Self {
+        let content = String::from_utf8_lossy(&artifact.content);
+        let line_count = content.lines().count();
 
-        placements_pattern.is_match(&path_str) &&
-            state.placement.pcb_side.eq(&phase.pcb_side)
-    }) {
-        let should_assign = match &state.phase {
-            Some(other) if !other.eq(&phase.reference) => true,
-            None => true,
-            _ => false,
-        };
+        let preview_content = content.lines()
+            .take(max_lines)
+            .fold(String::new(), |mut preview_content, line| {
+                preview_content.push_str(line);
+                preview_content.push('\n');
+                preview_content
+            });
 
-        if should_assign {
-            info!("Assigning placement to phase. phase: {}, placement_path: {}", phase.reference, placement_path);
-            state.phase = Some(phase.reference.clone());
+        Self {
+            kind: artifact.kind.clone(),
+            file_name: artifact.file_name.clone(),
+            content: preview_content,
+            size: artifact.content.len(),
+            truncated: line_count > max_lines,
         }
-        let _inserted = required_load_out_parts.insert(state.placement.part.clone());
     }
+}
 
-    required_load_out_parts
+/// Generates the artifacts in-memory, in the same order they are written by `generate_artifacts`.
+///
+/// The JSON report is always generated, other formats are generated in addition to it.
+pub fn build_artifacts(project: &Project, name: &str, phase_load_out_items_map: &BTreeMap<Reference, Vec<LoadOutItem>>, price_list: Option<&PriceList>, inventory: Option<&Inventory>, report_format: ReportFormat) -> Result<Vec<Artifact>, ArtifactGenerationError> {
+    build_artifacts_with_progress(project, name, phase_load_out_items_map, price_list, inventory, report_format, &NoProgress)
 }
 
-pub fn refresh_from_design_variants(project: &mut Project, design_variant_placement_map: BTreeMap<DesignVariant, Vec<Placement>>) -> Vec<Part> {
+fn build_artifacts_with_progress(project: &Project, name: &str, phase_load_out_items_map: &BTreeMap<Reference, Vec<LoadOutItem>>, price_list: Option<&PriceList>, inventory: Option<&Inventory>, report_format: ReportFormat, progress: &dyn ProgressReporter) -> Result<Vec<Artifact>, ArtifactGenerationError> {
+    let (mut artifacts, report) = build_phase_artifacts_and_report(project, phase_load_out_items_map, price_list, inventory, progress)?;
 
-    let unique_parts = placement::build_unique_parts(&design_variant_placement_map);
+    let report_content = report::project_report_serialize(&report).map_err(|err|{
+        ArtifactGenerationError::ReportGenerationError { reason: err.into() }
+    })?;
 
-    refresh_parts(project, unique_parts.as_slice());
+    artifacts.push(Artifact {
+        kind: ArtifactKind::Report,
+        file_name: report::build_report_file_name(name),
+        content: report_content,
+    });
+
+    if report_format == ReportFormat::Html {
+        artifacts.push(Artifact {
+            kind: ArtifactKind::HtmlReport,
+            file_name: render::build_html_report_file_name(name),
+            content: render::render_html(&report).into_bytes(),
+        });
+    }
+
+    Ok(artifacts)
+}
 
-    refresh_placements(project, &design_variant_placement_map);
+/// The issues of the project, the same issues that are in the report that is generated with the artifacts.
+pub fn build_project_issues(project: &Project, phase_load_out_items_map: &BTreeMap<Reference, Vec<LoadOutItem>>, price_list: Option<&PriceList>, inventory: Option<&Inventory>) -> Result<Vec<ProjectReportIssue>, ArtifactGenerationError> {
+    let (_artifacts, report) = build_phase_artifacts_and_report(project, phase_load_out_items_map, price_list, inventory, &NoProgress)?;
 
-    unique_parts
+    Ok(report.issues.into_iter().map(|report_issue| report_issue.issue).collect())
 }
 
-fn refresh_placements(project: &mut Project, design_variant_placement_map: &BTreeMap<DesignVariant, Vec<Placement>>) {
-    let changes: Vec<(Change, ObjectPath, Placement)> = find_placement_changes(project, design_variant_placement_map);
+fn build_phase_artifacts_and_report(project: &Project, phase_load_out_items_map: &BTreeMap<Reference, Vec<LoadOutItem>>, price_list: Option<&PriceList>, inventory: Option<&Inventory>, progress: &dyn ProgressReporter) -> Result<(Vec<Artifact>, report::ProjectReport), ArtifactGenerationError> {
 
-    for (change, unit_path, placement) in changes.iter() {
-        let mut path: ObjectPath = unit_path.clone();
-        path.set_ref_des(placement.ref_des.clone());
+    let mut issues: BTreeSet<ProjectReportIssue> = BTreeSet::new();
+    let mut artifacts: Vec<Artifact> = vec![];
 
-        let placement_state_entry = project.placements.entry(path);
+    for (index, reference) in project.phase_orderings.iter().enumerate() {
+        progress.report(Progress::of_items(ProgressStage::BuildingArtifacts, 0, 50, index, project.phase_orderings.len()));
 
-        match (change, placement) {
-            (Change::New, placement) => {
-                info!("New placement. placement: {:?}", placement);
+        let phase = project.phases.get(reference).unwrap();
 
-                let placement_state = PlacementState {
-                    unit_path: unit_path.clone(),
-                    placement: placement.clone(),
-                    placed: false,
-                    status: PlacementStatus::Known,
-                    phase: None,
-                };
+        let load_out_items = phase_load_out_items_map.get(reference).unwrap();
 
-                placement_state_entry.or_insert(placement_state);
-            }
-            (Change::Existing, _) => {
-                placement_state_entry.and_modify(|ps| {
-                    if !ps.placement.eq(placement) {
-                        info!("Updating placement. old: {:?}, new: {:?}", ps.placement, placement);
-                        ps.placement = placement.clone();
-                    }
-                });
-            }
-            (Change::Unused, placement) => {
-                info!("Marking placement as unused. placement: {:?}", placement);
+        let phase_artifacts = build_phase_artifacts(project, phase, load_out_items.as_slice(), &mut issues)?;
+        artifacts.extend(phase_artifacts);
+    }
 
-                placement_state_entry.and_modify(|ps|{
-                    ps.status = PlacementStatus::Unknown;
-                });
-            }
-        }
+    artifacts.extend(build_fiducials_artifacts(project, &mut issues)?);
+
+    moisture::add_floor_life_issues(project, OffsetDateTime::now_utc(), &mut issues);
+
+    for (phase, artifact_type) in find_missing_required_artifacts(project, &artifacts) {
+        let issue = ProjectReportIssue {
+            message: "A required artifact was not generated".to_string(),
+            severity: IssueSeverity::Severe,
+            kind: IssueKind::MissingRequiredArtifact { phase, artifact: artifact_type },
+        };
+        issues.insert(issue);
     }
+
+    let report = report::project_build_report(project, phase_load_out_items_map, price_list, inventory, &mut issues);
+
+    Ok((artifacts, report))
 }
 
-fn find_placement_changes(project: &mut Project, design_variant_placement_map: &BTreeMap<DesignVariant, Vec<Placement>>) -> Vec<(Change, ObjectPath, Placement)> {
-    let mut changes: Vec<(Change, ObjectPath, Placement)> = vec![];
+/// Generates the artifacts without writing them, returns a preview of each artifact.
+pub fn preview_artifacts(project: &Project, name: &str, phase_load_out_items_map: &BTreeMap<Reference, Vec<LoadOutItem>>, price_list: Option<&PriceList>, inventory: Option<&Inventory>, max_lines: usize) -> Result<Vec<ArtifactPreview>, ArtifactGenerationError> {
+    let artifacts = build_artifacts(project, name, phase_load_out_items_map, price_list, inventory, ReportFormat::Json)?;
 
-    // find new or existing placements that are in the updated design_variant_placement_map
+    Ok(artifacts.iter().map(|artifact| ArtifactPreview::from_artifact(artifact, max_lines)).collect())
+}
 
-    for (design_variant, placements) in design_variant_placement_map.iter() {
+/// Returns the paths of the generated artifacts, including the report.
+///
+/// The report includes cost estimates when a price list is given.
+pub fn generate_artifacts(project: &Project, path: &Path, name: &str, phase_load_out_items_map: BTreeMap<Reference, Vec<LoadOutItem>>, price_list: Option<&PriceList>, inventory: Option<&Inventory>, report_format: ReportFormat) -> Result<Vec<PathBuf>, ArtifactGenerationError> {
+    generate_artifacts_with_progress(project, path, name, phase_load_out_items_map, price_list, inventory, report_format, &NoProgress)
+}
 
-        for (unit_path, assignment_design_variant) in project.unit_assignments.iter() {
-            if !design_variant.eq(assignment_design_variant) {
-                continue
-            }
+/// See `generate_artifacts`, reports the progress of building and writing the artifacts.
+#[allow(clippy::too_many_arguments)]
+pub fn generate_artifacts_with_progress(project: &Project, path: &Path, name: &str, phase_load_out_items_map: BTreeMap<Reference, Vec<LoadOutItem>>, price_list: Option<&PriceList>, inventory: Option<&Inventory>, report_format: ReportFormat, progress: &dyn ProgressReporter) -> Result<Vec<PathBuf>, ArtifactGenerationError> {
 
-            for placement in placements {
-                let mut path: ObjectPath = unit_path.clone();
-                path.set_ref_des(placement.ref_des.clone());
+    let artifacts = build_artifacts_with_progress(project, name, &phase_load_out_items_map, price_list, inventory, report_format, progress)?;
 
-                // look for a placement state for the placement for this object path
+    for (phase, artifact_type) in find_missing_required_artifacts(project, &artifacts) {
+        warn!("Required artifact not generated. phase: '{}', artifact: {}", phase, artifact_type);
+    }
 
-                match project.placements.contains_key(&path) {
-                    true => changes.push((Change::Existing, unit_path.clone(), placement.clone())),
-                    false => changes.push((Change::New, unit_path.clone(), placement.clone())),
-                }
-            }
+    let mut artifact_paths: Vec<PathBuf> = vec![];
+
+    for (index, artifact) in artifacts.iter().enumerate() {
+        progress.report(Progress::of_items(ProgressStage::WritingArtifacts, 50, 100, index, artifacts.len()));
+
+        let artifact_path = path.join(&artifact.file_name);
+
+        trace!("Writing artifact. path: {:?}", artifact_path);
+        std::fs::write(&artifact_path, &artifact.content).map_err(|reason|{
+            ArtifactGenerationError::UnableToWriteArtifact { path: artifact_path.clone(), reason }
+        })?;
+
+        match &artifact.kind {
+            ArtifactKind::PhasePlacements { phase } => info!("Generated phase placements. phase: '{}', path: {:?}", phase, artifact_path),
+            ArtifactKind::ReworkInstructions { phase } => info!("Generated rework instructions. phase: '{}', path: {:?}", phase, artifact_path),
+            ArtifactKind::WorkInstructions { phase } => info!("Generated work instructions. phase: '{}', path: {:?}", phase, artifact_path),
+            ArtifactKind::PhaseExport { phase } => info!("Generated phase export. phase: '{}', path: {:?}", phase, artifact_path),
+            ArtifactKind::FirstArticleChecklist { phase } => info!("Generated first-article checklist. phase: '{}', path: {:?}", phase, artifact_path),
+            ArtifactKind::MachineExport { phase } => info!("Generated machine export. phase: '{}', path: {:?}", phase, artifact_path),
+            ArtifactKind::FeederSetupSheet { phase } => info!("Generated feeder setup sheet. phase: '{}', path: {:?}", phase, artifact_path),
+            ArtifactKind::HtmlFeederSetupSheet { phase } => info!("Generated HTML feeder setup sheet. phase: '{}', path: {:?}", phase, artifact_path),
+            ArtifactKind::Fiducials { pcb } => info!("Generated fiducials. pcb: '{}', path: {:?}", pcb, artifact_path),
+            ArtifactKind::Report => info!("Generated report. path: {:?}", artifact_path),
+            ArtifactKind::HtmlReport => info!("Generated HTML report. path: {:?}", artifact_path),
         }
+
+        artifact_paths.push(artifact_path);
     }
 
-    // find the placements that we knew about previously, but that are no-longer in the design_variant_placement_map
+    progress.report(Progress { stage: ProgressStage::Complete, percent: 100 });
 
-    for (path, state) in project.placements.iter_mut() {
+    info!("Generated artifacts.");
 
-        for (unit_path, design_variant) in project.unit_assignments.iter() {
+    Ok(artifact_paths)
+}
 
-            let path_str = path.to_string();
-            let unit_path_str = unit_path.to_string();
-            let is_matched_unit = path_str.starts_with(&unit_path_str);
-            trace!("path_str: {}, unit_path_str: {}, is_matched_unit: {}", path_str, unit_path_str, is_matched_unit);
+/// Returns the phases, and the types of the artifacts, required by the phase's process but not in the artifacts.
+pub fn find_missing_required_artifacts(project: &Project, artifacts: &[Artifact]) -> Vec<(Reference, ArtifactType)> {
+    let mut missing_artifacts = vec![];
 
-            if is_matched_unit {
-                if let Some(placements) = design_variant_placement_map.get(design_variant) {
-                    match placements.iter().find(|placement| placement.ref_des.eq(&state.placement.ref_des)) {
-                        Some(_) => {
-                            trace!("known placement");
-                        }
-                        None => {
-                            trace!("unknown placement");
-                            match state.status {
-                                PlacementStatus::Unknown => (),
-                                PlacementStatus::Known => changes.push((Change::Unused, unit_path.clone(), state.placement.clone())),
-                            }
-                        }
-                    }
-                }
+    for reference in project.phase_orderings.iter() {
+        let phase = project.phases.get(reference).unwrap();
+
+        let Ok(process) = project.find_process(&phase.process) else {
+            continue
+        };
+
+        for required_artifact in process.required_artifacts.iter() {
+            let generated = artifacts.iter().any(|artifact| {
+                artifact.kind.phase_artifact_type().eq(&Some((reference, required_artifact.clone())))
+            });
+
+            if !generated {
+                missing_artifacts.push((reference.clone(), required_artifact.clone()));
             }
         }
     }
 
-    debug!("placement changes:\n{:?}", changes);
+    missing_artifacts
+}
 
-    changes
+pub fn build_phase_artifact_file_name(artifact_type: &ArtifactType, phase: &Phase) -> String {
+    match artifact_type {
+        ArtifactType::PhasePlacements => format!("{}_placements.csv", phase.reference),
+        ArtifactType::WorkInstructions => work_instructions::build_work_instructions_file_name(phase),
+        ArtifactType::ReworkInstructions => format!("{}_rework.csv", phase.reference),
+        ArtifactType::PhaseExport => phase_export::build_phase_export_file_name(phase),
+        ArtifactType::FirstArticleChecklist => first_article::build_first_article_file_name(phase),
+        ArtifactType::MachineExport => export::build_export_file_name(phase),
+        ArtifactType::FeederSetupSheet => feeder_setup::build_feeder_setup_file_name(phase),
+    }
 }
 
-#[derive(Debug)]
-enum Change {
-    New,
-    Existing,
-    Unused,
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArtifactValidationIssue {
+    Missing { phase: Reference, artifact: ArtifactType, path: PathBuf },
+    OutOfDate { phase: Reference, artifact: ArtifactType, path: PathBuf },
 }
 
-fn refresh_parts(project: &mut Project, all_parts: &[Part]) {
-    let changes = find_part_changes(project, all_parts);
+/// Checks the required artifacts of each phase exist in the artifact directory and that they were generated after
+/// the project file was last modified.
+pub fn validate_artifacts(project: &Project, project_file_path: &Path, artifact_path: &Path) -> anyhow::Result<Vec<ArtifactValidationIssue>> {
+    let project_modified = project_file_path.metadata()?.modified()?;
 
-    for change_item in changes.iter() {
-        match change_item {
-            (Change::New, part) => {
-                info!("New part. part: {:?}", part);
-                let _ = project.part_states.entry(part.clone()).or_default();
-            }
-            (Change::Existing, _) => {}
-            (Change::Unused, part) => {
-                info!("Removing previously part. part: {:?}", part);
-                let _ = project.part_states.remove(&part);
-            }
+    let mut validation_issues = vec![];
+
+    for reference in project.phase_orderings.iter() {
+        let phase = project.phases.get(reference).unwrap();
+        let process = project.find_process(&phase.process)?;
+
+        for required_artifact in process.required_artifacts.iter() {
+            let path = artifact_path.join(build_phase_artifact_file_name(required_artifact, phase));
+
+            let issue = match path.metadata().and_then(|metadata| metadata.modified()) {
+                Err(_) => {
+                    warn!("Required artifact missing. phase: '{}', artifact: {}, path: {:?}", reference, required_artifact, path);
+                    Some(ArtifactValidationIssue::Missing { phase: reference.clone(), artifact: required_artifact.clone(), path })
+                },
+                Ok(modified) if modified < project_modified => {
+                    warn!("Required artifact out of date. phase: '{}', artifact: {}, path: {:?}", reference, required_artifact, path);
+                    Some(ArtifactValidationIssue::OutOfDate { phase: reference.clone(), artifact: required_artifact.clone(), path })
+                },
+                Ok(_) => {
+                    trace!("Required artifact up to date. phase: '{}', artifact: {}, path: {:?}", reference, required_artifact, path);
+                    None
+                },
+            };
+
+            validation_issues.extend(issue);
         }
     }
+
+    Ok(validation_issues)
 }
 
-fn find_part_changes(project: &mut Project, all_parts: &[Part]) -> Vec<(Change, Part)> {
-    let mut changes: Vec<(Change, Part)> = vec![];
+pub fn update_required_artifacts(project: &mut Project, process_name: &ProcessName, required_artifacts: Vec<ArtifactType>) -> Result<bool, ProcessError> {
+    let processes = project.processes.clone();
+    let process = project.processes.iter_mut()
+        .find(|process| process.name.eq(process_name))
+        .ok_or(ProcessError::UnusedProcessError { processes, process: process_name.to_string() })?;
 
-    for part in all_parts.iter() {
-        match project.part_states.contains_key(part) {
-            true => changes.push((Change::Existing, part.clone())),
-            false => changes.push((Change::New, part.clone())),
+    let mut required_artifacts = required_artifacts;
+    required_artifacts.sort();
+    required_artifacts.dedup();
+
+    if process.required_artifacts.eq(&required_artifacts) {
+        info!("Required artifacts unchanged. process: '{}'", process_name);
+        return Ok(false)
+    }
+
+    let old_required_artifacts = std::mem::replace(&mut process.required_artifacts, required_artifacts);
+    info!(target: MUTATION_TARGET, process = %process_name, old = ?old_required_artifacts, new = ?process.required_artifacts, message = "Required artifacts updated. process: '{process}', old: {old}, new: {new}");
+
+    Ok(true)
+}
+
+/// Returns the placements assigned to the phase, in no particular order.
+pub(crate) fn find_phase_placement_states<'a>(project: &'a Project, phase: &Phase) -> Vec<(&'a ObjectPath, &'a PlacementState)> {
+    project.placements.iter().filter_map(|(object_path, state)|{
+        match &state.phase {
+            Some(placement_phase) if placement_phase.eq(&phase.reference) => Some((object_path, state)),
+            _ => None
         }
+    }).collect()
+}
+
+pub(crate) fn add_unassigned_part_feeder_issues(placement_states: &[(&ObjectPath, &PlacementState)], load_out_items: &[LoadOutItem], issues: &mut BTreeSet<ProjectReportIssue>) {
+    for (_object_path, placement_state) in placement_states.iter() {
+        let feeder_reference = match pnp::load_out::find_load_out_item_by_part(load_out_items, &placement_state.placement.part) {
+            Some(load_out_item) => load_out_item.reference.clone(),
+            _ => "".to_string(),
+        };
+
+        if feeder_reference.is_empty() {
+            let issue = ProjectReportIssue {
+                message: "A part has not been assigned to a feeder".to_string(),
+                severity: IssueSeverity::Warning,
+                kind: IssueKind::UnassignedPartFeeder { part: placement_state.placement.part.clone() },
+            };
+            issues.insert(issue);
+        };
     }
+}
 
-    for (part, _process) in project.part_states.iter() {
-        if !all_parts.contains(part) {
-            changes.push((Change::Unused, part.clone()))
+/// Compares two placements, given as the object path, the placement state in design space and the placement state in
+/// panel space, using a single sorting item.
+///
+/// Placements without a value for the mode, e.g. without a nozzle, are last for both sort orders.
+fn compare_placements(
+    project: &Project,
+    sort_ordering: &PlacementSortingItem,
+    load_out_items: &[LoadOutItem],
+    nozzle_assignments: &NozzleAssignments,
+    (object_path_a, design_placement_state_a, panel_placement_state_a): &(&ObjectPath, &PlacementState, &PlacementState),
+    (object_path_b, design_placement_state_b, panel_placement_state_b): &(&ObjectPath, &PlacementState, &PlacementState),
+) -> Ordering {
+    let find_part_details = |placement_state: &PlacementState| project.part_states.get(&placement_state.placement.part)
+        .map(|part_state| &part_state.details);
+
+    let apply_sort_order = |ordering: Ordering| match sort_ordering.sort_order {
+        SortOrder::Asc => ordering,
+        SortOrder::Desc => ordering.reverse(),
+    };
+
+    match sort_ordering.mode {
+        PlacementSortingMode::FeederReference => {
+            let feeder_reference_a = match pnp::load_out::find_load_out_item_by_part(load_out_items, &design_placement_state_a.placement.part) {
+                Some(load_out_item) => load_out_item.reference.clone(),
+                _ => "".to_string(),
+            };
+            let feeder_reference_b = match pnp::load_out::find_load_out_item_by_part(load_out_items, &design_placement_state_b.placement.part) {
+                Some(load_out_item) => load_out_item.reference.clone(),
+                _ => "".to_string(),
+            };
+
+            trace!("Comparing feeder references. feeder_reference_a: '{}' feeder_reference_a: '{}'", feeder_reference_a, feeder_reference_b);
+            apply_sort_order(pnp::load_out::feeder_reference_cmp(&feeder_reference_a, &feeder_reference_b))
+        },
+        PlacementSortingMode::PcbUnit => {
+            let pcb_unit_a = object_path_a.pcb_unit();
+            let pcb_unit_b = object_path_b.pcb_unit();
+
+            trace!("Comparing pcb units, pcb_unit_a: '{}', pcb_unit_b: '{}'", pcb_unit_a, pcb_unit_b);
+            apply_sort_order(pcb_unit_a.cmp(&pcb_unit_b))
+        },
+        PlacementSortingMode::Part => {
+            let part_a = &design_placement_state_a.placement.part;
+            let part_b = &design_placement_state_b.placement.part;
+
+            trace!("Comparing parts, part_a: {:?}, part_b: {:?}", part_a, part_b);
+            apply_sort_order(part_a.cmp(part_b))
+        },
+        PlacementSortingMode::Nozzle => {
+            let nozzle_index_a = nozzle_assignments.nozzles.get(*object_path_a).map(|(index, _reference)| *index);
+            let nozzle_index_b = nozzle_assignments.nozzles.get(*object_path_b).map(|(index, _reference)| *index);
+
+            trace!("Comparing nozzles, nozzle_index_a: {:?}, nozzle_index_b: {:?}", nozzle_index_a, nozzle_index_b);
+            cmp_missing_last(nozzle_index_a, nozzle_index_b, &sort_ordering.sort_order)
+        },
+        PlacementSortingMode::RefDes => {
+            let ref_des_a = &design_placement_state_a.placement.ref_des;
+            let ref_des_b = &design_placement_state_b.placement.ref_des;
+
+            trace!("Comparing ref des, ref_des_a: '{}', ref_des_b: '{}'", ref_des_a, ref_des_b);
+            apply_sort_order(util::sorting::natural_cmp(ref_des_a, ref_des_b))
+        },
+        PlacementSortingMode::Area => {
+            let area_a = find_part_details(design_placement_state_a).and_then(PartDetails::area);
+            let area_b = find_part_details(design_placement_state_b).and_then(PartDetails::area);
+
+            trace!("Comparing areas, area_a: {:?}, area_b: {:?}", area_a, area_b);
+            cmp_missing_last(area_a, area_b, &sort_ordering.sort_order)
+        },
+        PlacementSortingMode::Height => {
+            let height_a = find_part_details(design_placement_state_a).and_then(|part_details| part_details.height);
+            let height_b = find_part_details(design_placement_state_b).and_then(|part_details| part_details.height);
+
+            trace!("Comparing heights, height_a: {:?}, height_b: {:?}", height_a, height_b);
+            cmp_missing_last(height_a, height_b, &sort_ordering.sort_order)
+        },
+        PlacementSortingMode::Angle => {
+            apply_sort_order(panel_placement_state_a.placement.rotation.cmp(&panel_placement_state_b.placement.rotation))
+        },
+        PlacementSortingMode::DesignX => {
+            apply_sort_order(design_placement_state_a.placement.x.cmp(&design_placement_state_b.placement.x))
+        },
+        PlacementSortingMode::DesignY => {
+            apply_sort_order(design_placement_state_a.placement.y.cmp(&design_placement_state_b.placement.y))
+        },
+        PlacementSortingMode::PanelX => {
+            apply_sort_order(panel_placement_state_a.placement.x.cmp(&panel_placement_state_b.placement.x))
+        },
+        PlacementSortingMode::PanelY => {
+            apply_sort_order(panel_placement_state_a.placement.y.cmp(&panel_placement_state_b.placement.y))
+        },
+    }
+}
+
+/// Orders missing values after all the other values, regardless of the sort order.
+fn cmp_missing_last<T: Ord>(a: Option<T>, b: Option<T>, sort_order: &SortOrder) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => match sort_order {
+            SortOrder::Asc => a.cmp(&b),
+            SortOrder::Desc => b.cmp(&a),
+        },
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+fn build_phase_artifacts(project: &Project, phase: &Phase, load_out_items: &[LoadOutItem], issues: &mut BTreeSet<ProjectReportIssue>) -> Result<Vec<Artifact>, ArtifactGenerationError> {
+    let placement_states = find_phase_placement_states(project, phase);
+
+    let nozzle_assignments = phase.nozzle_configuration.as_ref()
+        .map(|nozzle_configuration| nozzle::assign_nozzles(project, nozzle_configuration, &placement_states))
+        .unwrap_or_default();
+    
+    let mut panel_placement_states = transform_to_panel_space(project, &placement_states, issues);
+
+    if let Some(rotation_normalization) = &phase.rotation_normalization {
+        for (_object_path, panel_placement_state) in panel_placement_states.iter_mut() {
+            let placement = &mut panel_placement_state.placement;
+            placement.rotation = rotation_normalization.apply(&placement.part, placement.rotation);
         }
     }
 
-    debug!("part changes:\n{:?}", changes);
+    let mut placement_states: Vec<(&ObjectPath, &PlacementState, &PlacementState)> = placement_states.iter()
+        .zip(panel_placement_states.iter())
+        .map(|((object_path, design_placement_state), (_object_path, panel_placement_state))| (*object_path, *design_placement_state, panel_placement_state))
+        .collect();
 
-    changes
+    // the sort is stable, placements that are equal for all the orderings remain in object path order
+    placement_states.sort_by(|placement_a, placement_b| {
+        phase.placement_orderings.iter().fold(Ordering::Equal, |acc, sort_ordering| {
+            if !matches!(acc, Ordering::Equal) {
+                return acc
+            }
+            compare_placements(project, sort_ordering, load_out_items, &nozzle_assignments, placement_a, placement_b)
+        })
+    });
+
+    let placement_states: Vec<(&ObjectPath, &PlacementState)> = placement_states.into_iter()
+        .map(|(object_path, _design_placement_state, panel_placement_state)| (object_path, panel_placement_state))
+        .collect();
+
+    add_placement_outside_pcb_issues(project, &placement_states, issues);
+
+    add_unassigned_part_feeder_issues(&placement_states, load_out_items, issues);
+
+    if let Some(nozzle_configuration) = &phase.nozzle_configuration {
+        for part in nozzle_assignments.unassigned_parts.iter() {
+            issues.insert(ProjectReportIssue {
+                message: "No nozzle can pick the package of the part".to_string(),
+                severity: IssueSeverity::Warning,
+                kind: IssueKind::NoNozzleForPart { phase: phase.reference.clone(), part: part.clone() },
+            });
+        }
+
+        let nozzle_changes = nozzle::count_nozzle_changes(placement_states.iter()
+            .filter_map(|(object_path, _placement_state)| nozzle_assignments.find_nozzle(object_path)), nozzle_configuration.heads);
+
+        info!(target: MUTATION_TARGET, phase = %phase.reference, placements = nozzle_assignments.nozzles.len(), nozzle_changes, message = "Assigned nozzles. phase: '{phase}', placements: {placements}, nozzle_changes: {nozzle_changes}");
+    }
+
+    let phase_placements_content = build_phase_placements_csv(&placement_states, load_out_items).map_err(|e|{
+        ArtifactGenerationError::PhasePlacementsGenerationError(e)
+    })?;
+
+    let mut artifacts = vec![Artifact {
+        kind: ArtifactKind::PhasePlacements { phase: phase.reference.clone() },
+        file_name: build_phase_artifact_file_name(&ArtifactType::PhasePlacements, phase),
+        content: phase_placements_content,
+    }];
+
+    artifacts.push(Artifact {
+        kind: ArtifactKind::WorkInstructions { phase: phase.reference.clone() },
+        file_name: build_phase_artifact_file_name(&ArtifactType::WorkInstructions, phase),
+        content: work_instructions::build_work_instructions_markdown(project, phase, &placement_states, load_out_items).into_bytes(),
+    });
+
+    let phase_export = phase_export::build_phase_export(project, phase, &placement_states, load_out_items, &nozzle_assignments);
+    let phase_export_content = phase_export::serialize_phase_export(&phase_export).map_err(|e|{
+        ArtifactGenerationError::PhaseExportGenerationError(e.into())
+    })?;
+
+    artifacts.push(Artifact {
+        kind: ArtifactKind::PhaseExport { phase: phase.reference.clone() },
+        file_name: build_phase_artifact_file_name(&ArtifactType::PhaseExport, phase),
+        content: phase_export_content,
+    });
+
+    if phase.first_article_inspection_required {
+        let first_article_content = first_article::build_first_article_checklist_csv(project, phase, &placement_states).map_err(|e|{
+            ArtifactGenerationError::FirstArticleChecklistGenerationError(e)
+        })?;
+
+        artifacts.push(Artifact {
+            kind: ArtifactKind::FirstArticleChecklist { phase: phase.reference.clone() },
+            file_name: build_phase_artifact_file_name(&ArtifactType::FirstArticleChecklist, phase),
+            content: first_article_content,
+        });
+    }
+
+    if let Some(export_format) = &phase.export_format {
+        let machine_export_content = export_format.exporter().export(project, phase, &placement_states).map_err(|e|{
+            ArtifactGenerationError::MachineExportGenerationError(e)
+        })?;
+
+        artifacts.push(Artifact {
+            kind: ArtifactKind::MachineExport { phase: phase.reference.clone() },
+            file_name: build_phase_artifact_file_name(&ArtifactType::MachineExport, phase),
+            content: machine_export_content,
+        });
+    }
+
+    // only for processes that use feeders, i.e. machine placement
+    let uses_feeders = project.find_process(&phase.process)
+        .is_ok_and(|process| process.has_operation(&ProcessOperationKind::AutomatedPnp));
+
+    if uses_feeders {
+        let feeder_setup_items = feeder_setup::build_feeder_setup_items(project, &placement_states, load_out_items);
+        let feeder_setup_content = feeder_setup::build_feeder_setup_csv(&feeder_setup_items).map_err(|e|{
+            ArtifactGenerationError::FeederSetupSheetGenerationError(e)
+        })?;
+
+        artifacts.push(Artifact {
+            kind: ArtifactKind::FeederSetupSheet { phase: phase.reference.clone() },
+            file_name: build_phase_artifact_file_name(&ArtifactType::FeederSetupSheet, phase),
+            content: feeder_setup_content,
+        });
+
+        artifacts.push(Artifact {
+            kind: ArtifactKind::HtmlFeederSetupSheet { phase: phase.reference.clone() },
+            file_name: feeder_setup::build_html_feeder_setup_file_name(phase),
+            content: feeder_setup::build_feeder_setup_html(project, phase, &feeder_setup_items).into_bytes(),
+        });
+    }
+
+    let rework_placement_states: Vec<(&ObjectPath, &PlacementState)> = placement_states.iter()
+        .filter(|(_object_path, placement_state)| {
+            placement_state.defects.iter().any(|defect| defect.rework_phase.as_ref().eq(&Some(&phase.reference)))
+        })
+        .cloned()
+        .collect();
+
+    if !rework_placement_states.is_empty() {
+        let rework_instructions_content = build_rework_instructions_csv(&phase.reference, &rework_placement_states).map_err(|e|{
+            ArtifactGenerationError::ReworkInstructionsGenerationError(e)
+        })?;
+
+        artifacts.push(Artifact {
+            kind: ArtifactKind::ReworkInstructions { phase: phase.reference.clone() },
+            file_name: build_phase_artifact_file_name(&ArtifactType::ReworkInstructions, phase),
+            content: rework_instructions_content,
+        });
+    }
+
+    Ok(artifacts)
 }
 
-// TODO currently only supports adding a process, add support for removing a process too.
-pub fn update_applicable_processes(project: &mut Project, all_parts: &[Part], process: Process, manufacturer_pattern: Regex, mpn_pattern: Regex) {
+#[serde_as]
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all(serialize = "PascalCase"))]
+pub struct ReworkInstructionRecord {
 
-    let changes = find_part_changes(project, all_parts);
+    #[serde_as(as = "DisplayFromStr")]
+    pub object_path: ObjectPath,
 
-    for change in changes.iter() {
-        match change {
-            (Change::Existing, part) => {
-                if manufacturer_pattern.is_match(part.manufacturer.as_str()) && mpn_pattern.is_match(part.mpn.as_str()) {
-                    project.part_states.entry(part.clone())
-                        .and_modify(|part_state| {
-                            add_process_to_part(part_state, part, process.name.clone());
-                        });
+    #[serde_as(as = "DisplayFromStr")]
+    pub defect_phase: Reference,
+    pub manufacturer: String,
+    pub mpn: String,
+    pub x: Decimal,
+    pub y: Decimal,
+    pub rotation: Decimal,
+}
+
+pub fn build_rework_instructions_csv(rework_phase: &Reference, placement_states: &[(&ObjectPath, &PlacementState)]) -> Result<Vec<u8>, Error> {
+
+    trace!("Building rework instructions. rework_phase: '{}'", rework_phase);
+
+    let mut writer = csv::WriterBuilder::new()
+        .quote_style(QuoteStyle::Always)
+        .from_writer(vec![]);
+
+    for (object_path, placement_state) in placement_states.iter() {
+        for defect in placement_state.defects.iter().filter(|defect| defect.rework_phase.as_ref().eq(&Some(rework_phase))) {
+            writer.serialize(
+                ReworkInstructionRecord {
+                    object_path: (*object_path).clone(),
+                    defect_phase: defect.phase.clone(),
+                    manufacturer: placement_state.placement.part.manufacturer.to_string(),
+                    mpn: placement_state.placement.part.mpn.to_string(),
+                    x: placement_state.placement.x,
+                    y: placement_state.placement.y,
+                    rotation: placement_state.placement.rotation,
                 }
-            },
-            _ => {
-                panic!("unexpected change. change: {:?}", change);
-            }
+            )?;
         }
     }
+
+    let content = writer.into_inner()?;
+
+    Ok(content)
 }
 
-pub fn add_process_to_part(part_state: &mut PartState, part: &Part, process: ProcessName) {
-    let inserted = part_state.applicable_processes.insert(process);
+#[serde_as]
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all(serialize = "PascalCase"))]
+pub struct PhasePlacementRecord {
 
-    if inserted {
-        info!("Added process. part: {:?}, applicable_processes: {:?}", part, part_state.applicable_processes.iter().map(|it|it.to_string()).collect::<Vec<String>>());
+    #[serde_as(as = "DisplayFromStr")]
+    pub object_path: ObjectPath,
+    
+    pub feeder_reference: String,
+    pub manufacturer: String,
+    pub mpn: String,
+    pub x: Decimal,
+    pub y: Decimal,
+    pub rotation: Decimal,
+}
+
+/// Placements that are not to be placed, see `PlacementState::place`, are omitted.
+pub fn build_phase_placements_csv(placement_states: &[(&ObjectPath, &PlacementState)], load_out_items: &[LoadOutItem]) -> Result<Vec<u8>, Error> {
+    
+    trace!("Building phase placements.");
+
+    let mut writer = csv::WriterBuilder::new()
+        .quote_style(QuoteStyle::Always)
+        .from_writer(vec![]);
+
+    for (object_path, placement_state) in placement_states.iter().filter(|(_object_path, placement_state)| placement_state.place()) {
+        
+        let feeder_reference = match pnp::load_out::find_load_out_item_by_part(load_out_items, &placement_state.placement.part) {
+            Some(load_out_item) => load_out_item.reference.clone(),
+            _ => "".to_string(),
+        };
+        
+        writer.serialize(
+            PhasePlacementRecord {
+                object_path: (*object_path).clone(),
+                feeder_reference,
+                manufacturer: placement_state.placement.part.manufacturer.to_string(),
+                mpn: placement_state.placement.part.mpn.to_string(),
+                x: placement_state.placement.x,
+                y: placement_state.placement.y,
+                rotation: placement_state.placement.rotation,
+            }
+        )?;
     }
+
+    let content = writer.into_inner()?;
+    
+    Ok(content)
 }
 
-pub fn build_project_file_path(name: &str, path: &PathBuf) -> PathBuf {
-    let mut project_file_path: PathBuf = path.clone();
-    project_file_path.push(format!("project-{}.mpnp.json", name));
-    project_file_path
+#[serde_as]
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all(serialize = "PascalCase"))]
+pub struct FiducialRecord {
+    #[serde_as(as = "DisplayFromStr")]
+    pub object_path: ObjectPath,
+
+    pub pcb_side: PcbSide,
+    pub x: Decimal,
+    pub y: Decimal,
 }
 
-pub fn load(project_file_path: &PathBuf) -> anyhow::Result<Project> {
-    let project_file = File::open(project_file_path.clone())?;
-    let mut de = serde_json::Deserializer::from_reader(project_file);
-    let project = Project::deserialize(&mut de)?;
-    Ok(project)
+/// e.g. 'panel_a_fiducials.csv'
+pub fn build_fiducials_file_name(pcb: &Pcb) -> String {
+    format!("{}_fiducials.csv", pcb.name)
 }
 
-pub fn save(project: &Project, project_file_path: &PathBuf) -> anyhow::Result<()> {
-    let project_file = File::create(project_file_path)?;
-    let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
-    let mut ser = serde_json::Serializer::with_formatter(project_file, formatter);
-    project.serialize(&mut ser)?;
+/// A fiducials artifact for each PCB that has fiducials, the coordinates are in panel space.
+fn build_fiducials_artifacts(project: &Project, issues: &mut BTreeSet<ProjectReportIssue>) -> Result<Vec<Artifact>, ArtifactGenerationError> {
+    let fiducial_states: Vec<(&ObjectPath, &PlacementState)> = project.placements.iter()
+        .filter(|(_object_path, placement_state)| matches!(placement_state.placement.kind, PlacementKind::Fiducial))
+        .collect();
 
-    let mut project_file = ser.into_inner();
-    project_file.write(b"\n")?;
+    let panel_fiducial_states = transform_to_panel_space(project, &fiducial_states, issues);
 
-    Ok(())
+    let mut artifacts = vec![];
+
+    for pcb in project.pcbs.iter() {
+        let pcb_fiducial_states: Vec<&(&ObjectPath, PlacementState)> = panel_fiducial_states.iter()
+            .filter(|(object_path, _placement_state)| project.find_pcb(object_path).is_some_and(|candidate| candidate.eq(pcb)))
+            .collect();
+
+        if pcb_fiducial_states.is_empty() {
+            continue
+        }
+
+        let content = build_fiducials_csv(&pcb_fiducial_states)
+            .map_err(ArtifactGenerationError::FiducialsGenerationError)?;
+
+        artifacts.push(Artifact {
+            kind: ArtifactKind::Fiducials { pcb: pcb.name.clone() },
+            file_name: build_fiducials_file_name(pcb),
+            content,
+        });
+    }
+
+    Ok(artifacts)
 }
 
-pub fn update_placements_operation(project: &mut Project, path: &PathBuf, object_path_patterns: Vec<Regex>, operation: PlacementOperation) -> anyhow::Result<bool> {
-    let mut modified = false;
-    let mut history_item_map: HashMap<Reference, Vec<OperationHistoryItem>> = HashMap::new();
-    
-    for object_path_pattern in object_path_patterns.iter() {
-        let placements: Vec<_> = project.placements.iter_mut().filter(|(object_path, _placement_state)|{
-            object_path_pattern.is_match(&object_path.to_string())
-        }).collect();
-        
-        if placements.is_empty() {
-            warn!("Unmatched object path pattern. object_path_pattern: {}", object_path_pattern);
+fn build_fiducials_csv(fiducial_states: &[&(&ObjectPath, PlacementState)]) -> Result<Vec<u8>, Error> {
+    let mut writer = csv::WriterBuilder::new()
+        .quote_style(QuoteStyle::Always)
+        .from_writer(vec![]);
+
+    for (object_path, placement_state) in fiducial_states.iter() {
+        writer.serialize(FiducialRecord {
+            object_path: (*object_path).clone(),
+            pcb_side: placement_state.placement.pcb_side.clone(),
+            x: placement_state.placement.x,
+            y: placement_state.placement.y,
+        })?;
+    }
+
+    let content = writer.into_inner()?;
+
+    Ok(content)
+}
+
+#[derive(Error, Debug)]
+pub enum PhaseAssignmentError {
+    #[error("Placements are already assigned to other phases. phase: '{phase}', count: {count}")]
+    PlacementsAssignedToOtherPhases { phase: Reference, count: usize },
+
+    #[error("Placements are on the other side of the PCB to the phase, use '--allow-side-mismatch' to assign them, e.g. for glue-dot workflows. phase: '{phase}', pcb_side: {pcb_side:?}, count: {count}")]
+    PcbSideMismatch { phase: Reference, pcb_side: PcbSide, count: usize },
+}
+
+/// Assigns the placements to the phase, returns the parts that are required in the load-out for the phase.
+///
+/// Placements that are already assigned to another phase are only reassigned when `allow_reassign` is set.
+///
+/// Placements on the other side of the PCB to the phase are only assigned when `allow_side_mismatch` is set, otherwise
+/// they are skipped, with a warning, and it is an error if all the placements are on the other side.
+pub fn assign_placements_to_phase(project: &mut Project, phase: &Phase, placements_pattern: Regex, allow_reassign: bool, allow_side_mismatch: bool) -> Result<BTreeSet<Part>, PhaseAssignmentError> {
+    let mut required_load_out_parts = BTreeSet::new();
+
+    // fiducials and test points are not placed
+    let is_match = |path: &ObjectPath, state: &PlacementState| {
+        let path_str = format!("{}", path);
+
+        placements_pattern.is_match(&path_str) &&
+            state.placement.kind.is_component()
+    };
+
+    let side_mismatches: Vec<&ObjectPath> = project.placements.iter()
+        .filter(|(path, state)| is_match(path, state) && !state.placement.pcb_side.eq(&phase.pcb_side))
+        .map(|(path, _state)| path)
+        .collect();
+
+    if !side_mismatches.is_empty() {
+        for placement_path in side_mismatches.iter() {
+            debug!("Placement is on the other side of the PCB to the phase. phase: {}, placement_path: {}", phase.reference, placement_path);
         }
-        
-        for (object_path, placement_state) in placements {
-            match operation {
-                PlacementOperation::Placed => {
-                    if placement_state.placed {
-                        warn!("Placed flag already set. object_path: {}", object_path);
-                    } else {
-                        info!("Setting placed flag. object_path: {}", object_path);
-                        placement_state.placed = true;
 
-                        let now = OffsetDateTime::now_utc();
+        let matches = project.placements.iter().filter(|(path, state)| is_match(path, state)).count();
+
+        match allow_side_mismatch {
+            true => warn!("Assigning placements on the other side of the PCB to the phase. phase: {}, pcb_side: {:?}, count: {}", phase.reference, phase.pcb_side, side_mismatches.len()),
+            false if side_mismatches.len() == matches => {
+                return Err(PhaseAssignmentError::PcbSideMismatch { phase: phase.reference.clone(), pcb_side: phase.pcb_side.clone(), count: side_mismatches.len() })
+            },
+            false => warn!("Skipped placements on the other side of the PCB to the phase, use '--allow-side-mismatch' to assign them. phase: {}, pcb_side: {:?}, count: {}", phase.reference, phase.pcb_side, side_mismatches.len()),
+        }
+    }
+
+    let is_candidate = |path: &ObjectPath, state: &PlacementState| {
+        is_match(path, state) &&
+            (allow_side_mismatch || state.placement.pcb_side.eq(&phase.pcb_side))
+    };
+
+    let reassignments: Vec<(&ObjectPath, &Reference)> = project.placements.iter()
+        .filter(|(path, state)| is_candidate(path, state))
+        .filter_map(|(path, state)| match &state.phase {
+            Some(other) if !other.eq(&phase.reference) => Some((path, other)),
+            _ => None,
+        })
+        .collect();
+
+    if !reassignments.is_empty() && !allow_reassign {
+        for (placement_path, old_phase) in reassignments.iter() {
+            warn!("Placement already assigned to another phase. phase: {}, placement_path: {}", old_phase, placement_path);
+        }
+        return Err(PhaseAssignmentError::PlacementsAssignedToOtherPhases { phase: phase.reference.clone(), count: reassignments.len() })
+    }
+
+    let mut reassignment_counts: BTreeMap<Reference, usize> = BTreeMap::new();
+
+    for (placement_path, state) in project.placements.iter_mut().filter(|(path, state)| is_candidate(path, state)) {
+        match &state.phase {
+            Some(other) if !other.eq(&phase.reference) => {
+                info!(target: MUTATION_TARGET, old_phase = %other, new_phase = %phase.reference, placement_path = %placement_path, message = "Reassigning placement to phase. old_phase: {old_phase}, new_phase: {new_phase}, placement_path: {placement_path}");
+                *reassignment_counts.entry(other.clone()).or_default() += 1;
+                state.phase = Some(phase.reference.clone());
+            },
+            None => {
+                info!(target: MUTATION_TARGET, phase = %phase.reference, placement_path = %placement_path, message = "Assigning placement to phase. phase: {phase}, placement_path: {placement_path}");
+                state.phase = Some(phase.reference.clone());
+            },
+            _ => {},
+        }
+        let _inserted = required_load_out_parts.insert(state.placement.part.clone());
+    }
+
+    for (old_phase, count) in reassignment_counts.iter() {
+        info!("Reassigned placements. old_phase: {}, new_phase: {}, count: {}", old_phase, phase.reference, count);
+    }
+
+    Ok(required_load_out_parts)
+}
+
+#[derive(Error, Debug)]
+pub enum PhaseUnassignmentError {
+    #[error("Placements have been placed, record them as unplaced before unassigning them. phase: '{phase}', count: {count}")]
+    PlacedPlacements { phase: Reference, count: usize },
+}
+
+/// Unassigns the placements from the phase, returns the parts that are no longer required in the load-out for the phase.
+///
+/// Open inspection defects that were being reworked in the phase can be reworked in another phase again.
+pub fn unassign_placements_from_phase(project: &mut Project, reference: &Reference, placements_pattern: &Regex) -> Result<BTreeSet<Part>, PhaseUnassignmentError> {
+    let is_candidate = |path: &ObjectPath, state: &PlacementState| {
+        state.phase.as_ref() == Some(reference) && placements_pattern.is_match(&path.to_string())
+    };
+
+    let placed_count = project.placements.iter()
+        .filter(|(path, state)| is_candidate(path, state) && state.placed)
+        .count();
+
+    if placed_count > 0 {
+        return Err(PhaseUnassignmentError::PlacedPlacements { phase: reference.clone(), count: placed_count })
+    }
+
+    let mut unassigned_parts = BTreeSet::new();
+
+    for (placement_path, state) in project.placements.iter_mut().filter(|(path, state)| is_candidate(path, state)) {
+        info!(target: MUTATION_TARGET, phase = %reference, placement_path = %placement_path, message = "Unassigning placement from phase. phase: {phase}, placement_path: {placement_path}");
+        state.phase = None;
+
+        for defect in state.defects.iter_mut().filter(|defect| defect.status == PlacementDefectStatus::Open && defect.rework_phase.as_ref() == Some(reference)) {
+            defect.rework_phase = None;
+        }
+
+        let _inserted = unassigned_parts.insert(state.placement.part.clone());
+    }
+
+    let required_parts: BTreeSet<&Part> = project.placements.values()
+        .filter(|state| state.phase.as_ref() == Some(reference))
+        .map(|state| &state.placement.part)
+        .collect();
+
+    unassigned_parts.retain(|part| !required_parts.contains(part));
+
+    Ok(unassigned_parts)
+}
+
+/// Removes the phase, its placements are unassigned, see `unassign_placements_from_phase`, returns the parts that are no
+/// longer required in the load-out for the phase.
+///
+/// The operation history of the phase is retained.
+pub fn remove_phase(project: &mut Project, reference: &Reference) -> anyhow::Result<BTreeSet<Part>> {
+    if !project.phases.contains_key(reference) {
+        return Err(PhaseError::UnknownPhase(reference.clone()).into())
+    }
+
+    let parts = unassign_placements_from_phase(project, reference, &Regex::new(".*")?)?;
+
+    project.phases.remove(reference);
+    project.phase_states.remove(reference);
+    project.phase_orderings.shift_remove(reference);
+
+    for phase in project.phases.values_mut() {
+        if phase.dependencies.remove(reference) {
+            info!("Removed phase dependency. phase: '{}', dependency: '{}'", phase.reference, reference);
+        }
+    }
+
+    info!(target: MUTATION_TARGET, phase = %reference, message = "Removed phase. phase: '{phase}'");
+    info!("Phase ordering: {}", PhaseOrderings(&project.phase_orderings));
+
+    Ok(parts)
+}
+
+/// Renames the phase, the placements, inspection defects, phase state, dependencies of other phases and the phase
+/// ordering are updated.
+///
+/// The operation history of the phase is renamed separately, see `record_phase_renamed`.
+pub fn rename_phase(project: &mut Project, from: &Reference, to: Reference) -> Result<(), PhaseError> {
+    if project.phases.contains_key(&to) {
+        return Err(PhaseError::PhaseAlreadyExists(to))
+    }
+
+    let mut phase = project.phases.remove(from)
+        .ok_or(PhaseError::UnknownPhase(from.clone()))?;
+    phase.reference = to.clone();
+    project.phases.insert(to.clone(), phase);
+
+    if let Some(phase_state) = project.phase_states.remove(from) {
+        project.phase_states.insert(to.clone(), phase_state);
+    }
+
+    project.phase_orderings = project.phase_orderings.iter()
+        .map(|reference| if reference.eq(from) { to.clone() } else { reference.clone() })
+        .collect();
+
+    for phase in project.phases.values_mut() {
+        if phase.dependencies.remove(from) {
+            phase.dependencies.insert(to.clone());
+        }
+    }
+
+    let mut placements = 0;
+    for placement_state in project.placements.values_mut() {
+        if placement_state.phase.as_ref().is_some_and(|phase| phase.eq(from)) {
+            placement_state.phase = Some(to.clone());
+            placements += 1;
+        }
+
+        for defect in placement_state.defects.iter_mut() {
+            if defect.phase.eq(from) {
+                defect.phase = to.clone();
+            }
+            if defect.rework_phase.as_ref().is_some_and(|rework_phase| rework_phase.eq(from)) {
+                defect.rework_phase = Some(to.clone());
+            }
+        }
+    }
+
+    info!(target: MUTATION_TARGET, from = %from, to = %to, placements, message = "Renamed phase. from: '{from}', to: '{to}', placements: {placements}");
+    info!("Phase ordering: {}", PhaseOrderings(&project.phase_orderings));
+
+    Ok(())
+}
+
+/// Sets the phase ordering, each phase must be given exactly once, returns true if modified.
+pub fn update_phase_orderings(project: &mut Project, references: &[Reference]) -> Result<bool, PhaseError> {
+    let phase_orderings: IndexSet<Reference> = references.iter().cloned().collect();
+
+    if phase_orderings.len() != references.len() || phase_orderings.len() != project.phases.len() {
+        return Err(PhaseError::InvalidPhaseOrdering(phase::format_references(references)))
+    }
+    if let Some(unknown) = phase_orderings.iter().find(|reference| !project.phases.contains_key(*reference)) {
+        return Err(PhaseError::UnknownPhase(unknown.clone()))
+    }
+
+    if project.phase_orderings.iter().eq(phase_orderings.iter()) {
+        return Ok(false)
+    }
+
+    project.phase_orderings = phase_orderings;
+    info!(target: MUTATION_TARGET, phase_ordering = %PhaseOrderings(&project.phase_orderings), message = "Phase ordering: {phase_ordering}");
+
+    Ok(true)
+}
+
+#[derive(Error, Debug)]
+pub enum DesignRevisionError {
+    #[error("Design variants have changed, review the changes and acknowledge them before continuing. design_variants: {design_variants:?}, affected phases: {phases:?}")]
+    Changed { design_variants: Vec<String>, phases: Vec<String> },
+}
+
+/// Checks the current revisions of the design variants against the recorded revisions, so that the placements are not
+/// silently refreshed from changed design data.
+///
+/// The revisions of design variants without a recorded revision are recorded, returns true if any were recorded.
+pub fn check_design_revisions(project: &mut Project, design_revisions: &BTreeMap<DesignVariant, String>) -> Result<bool, DesignRevisionError> {
+    let changed_design_variants = find_changed_design_variants(project, design_revisions);
+
+    if !changed_design_variants.is_empty() {
+        let phases = find_design_variant_phases(project, &changed_design_variants);
+
+        return Err(DesignRevisionError::Changed {
+            design_variants: changed_design_variants.iter().map(ToString::to_string).collect(),
+            phases: phases.iter().map(ToString::to_string).collect(),
+        })
+    }
+
+    let mut modified = false;
+    for (design_variant, revision) in design_revisions.iter() {
+        if let Entry::Vacant(entry) = project.design_revisions.entry(design_variant.clone()) {
+            info!(target: MUTATION_TARGET, design_variant = %design_variant, revision = %revision, message = "Recorded design revision. design_variant: {design_variant}, revision: {revision}");
+            entry.insert(revision.clone());
+            modified = true;
+        }
+    }
+
+    Ok(modified)
+}
+
+/// Records the current revisions of changed design variants, returns true if any changes were acknowledged.
+pub fn acknowledge_design_revisions(project: &mut Project, design_revisions: &BTreeMap<DesignVariant, String>) -> bool {
+    let changed_design_variants = find_changed_design_variants(project, design_revisions);
+    let phases = find_design_variant_phases(project, &changed_design_variants);
+
+    for design_variant in changed_design_variants.iter() {
+        let revision = design_revisions.get(design_variant).unwrap();
+        info!(target: MUTATION_TARGET, design_variant = %design_variant, revision = %revision, affected_phases = ?phases.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            message = "Acknowledged design revision. design_variant: {design_variant}, revision: {revision}, affected phases: {affected_phases}",
+        );
+        project.design_revisions.insert(design_variant.clone(), revision.clone());
+    }
+
+    !changed_design_variants.is_empty()
+}
+
+fn find_changed_design_variants(project: &Project, design_revisions: &BTreeMap<DesignVariant, String>) -> Vec<DesignVariant> {
+    design_revisions.iter()
+        .filter(|(design_variant, revision)| project.design_revisions.get(design_variant)
+            .is_some_and(|recorded_revision| recorded_revision.ne(*revision)))
+        .map(|(design_variant, _revision)| design_variant.clone())
+        .collect()
+}
+
+/// Finds the phases with placements on units that are assigned any of the design variants.
+fn find_design_variant_phases(project: &Project, design_variants: &[DesignVariant]) -> BTreeSet<Reference> {
+    project.placements.values()
+        .filter(|placement_state| project.unit_assignments.get(&placement_state.unit_path)
+            .is_some_and(|design_variant| design_variants.contains(design_variant)))
+        .filter_map(|placement_state| placement_state.phase.clone())
+        .collect()
+}
+
+/// Refreshes the parts and placements from the placements of the design variants.
+///
+/// Placements of a design variant that have the same ref_des are conflicts, since only one of them can be used for
+/// each unit, identical placements are not conflicts and are de-duplicated.  Conflicts that have not already been
+/// accepted are refused, unless `force` is used, in which case the last of the placements is used and the conflicts are
+/// recorded in the project, so they are reported.
+pub fn refresh_from_design_variants(project: &mut Project, design_variant_placement_map: BTreeMap<DesignVariant, Vec<Placement>>, force: bool) -> Result<Vec<Part>, RefreshError> {
+    refresh_from_design_variants_with_progress(project, design_variant_placement_map, force, &NoProgress)
+}
+
+/// See `refresh_from_design_variants`, reports the progress of the refresh.
+pub fn refresh_from_design_variants_with_progress(project: &mut Project, design_variant_placement_map: BTreeMap<DesignVariant, Vec<Placement>>, force: bool, progress: &dyn ProgressReporter) -> Result<Vec<Part>, RefreshError> {
+
+    progress.report(Progress { stage: ProgressStage::CheckingPlacementConflicts, percent: 0 });
+
+    let conflicts = find_placement_conflicts(&design_variant_placement_map);
+
+    let new_conflicts: Vec<&PlacementConflict> = conflicts.iter()
+        .filter(|conflict| !project.placement_conflicts.contains(conflict))
+        .collect();
+
+    if !new_conflicts.is_empty() {
+        if !force {
+            return Err(PlacementConflictError::Conflicts {
+                conflicts: new_conflicts.iter().map(ToString::to_string).collect(),
+            }.into())
+        }
+
+        for conflict in new_conflicts {
+            warn!("Accepted placement conflict, the last placement is used. conflict: {}", conflict);
+        }
+    }
+
+    if project.placement_conflicts.ne(&conflicts) {
+        info!("Placement conflicts updated. old: {}, new: {}", project.placement_conflicts.len(), conflicts.len());
+        project.placement_conflicts = conflicts;
+    }
+
+    let design_variant_placement_map = deduplicate_placements(design_variant_placement_map);
+
+    // the changes are found before the project is changed, so that an invalid placement does not leave it half-refreshed
+    let placement_changes = find_placement_changes(project, &design_variant_placement_map)?;
+
+    progress.report(Progress { stage: ProgressStage::RefreshingParts, percent: 20 });
+
+    let unique_parts = placement::build_unique_parts(&design_variant_placement_map);
+
+    refresh_parts(project, unique_parts.as_slice());
+
+    progress.report(Progress { stage: ProgressStage::RefreshingPlacements, percent: 40 });
+
+    refresh_placements(project, placement_changes);
+
+    progress.report(Progress { stage: ProgressStage::Complete, percent: 100 });
+
+    Ok(unique_parts)
+}
+
+#[derive(Error, Debug)]
+pub enum PlacementConflictError {
+    #[error("Design variants have conflicting placements, fix the designs or use '--force' to use the last of the placements. conflicts: {conflicts:?}")]
+    Conflicts { conflicts: Vec<String> },
+}
+
+#[derive(Error, Debug)]
+pub enum RefreshError {
+    #[error(transparent)]
+    PlacementConflicts(#[from] PlacementConflictError),
+    #[error("Invalid placement reference designator. design_variant: {design_variant}, cause: {error}")]
+    InvalidRefDes { design_variant: DesignVariant, error: ObjectPathError },
+}
+
+/// Placements of a design variant with the same ref_des, in design variant and ref_des order.
+pub fn find_placement_conflicts(design_variant_placement_map: &BTreeMap<DesignVariant, Vec<Placement>>) -> Vec<PlacementConflict> {
+    let mut conflicts = vec![];
+
+    for (design_variant, placements) in design_variant_placement_map.iter() {
+        let mut ref_des_placements: BTreeMap<&str, Vec<&Placement>> = BTreeMap::new();
+        for placement in placements.iter() {
+            ref_des_placements.entry(placement.ref_des.as_str()).or_default().push(placement);
+        }
+
+        for (ref_des, placements) in ref_des_placements {
+            let first = placements[0];
+            if placements.iter().all(|placement| placement.eq(&first)) {
+                continue
+            }
+
+            let parts: BTreeSet<&Part> = placements.iter().map(|placement| &placement.part).collect();
+
+            let kind = match parts.len() {
+                1 => PlacementConflictKind::RefDesCollision,
+                _ => PlacementConflictKind::PartConflict { parts: parts.into_iter().cloned().collect() },
+            };
+
+            conflicts.push(PlacementConflict { design_variant: design_variant.clone(), ref_des: ref_des.to_string(), kind });
+        }
+    }
+
+    conflicts
+}
+
+/// Keeps the last placement for each ref_des, in the order of the first placement for each ref_des.
+fn deduplicate_placements(design_variant_placement_map: BTreeMap<DesignVariant, Vec<Placement>>) -> BTreeMap<DesignVariant, Vec<Placement>> {
+    design_variant_placement_map.into_iter()
+        .map(|(design_variant, placements)| {
+            let mut deduplicated_placements: Vec<Placement> = Vec::with_capacity(placements.len());
+            let mut indexes: HashMap<String, usize> = HashMap::new();
+
+            for placement in placements {
+                match indexes.entry(placement.ref_des.clone()) {
+                    std::collections::hash_map::Entry::Occupied(entry) => deduplicated_placements[*entry.get()] = placement,
+                    std::collections::hash_map::Entry::Vacant(entry) => {
+                        entry.insert(deduplicated_placements.len());
+                        deduplicated_placements.push(placement);
+                    },
+                }
+            }
+
+            (design_variant, deduplicated_placements)
+        })
+        .collect()
+}
+
+/// Applies the changes found by `find_placement_changes`.
+fn refresh_placements(project: &mut Project, changes: Vec<PlacementChange>) {
+    for (change, unit_path, path, placement) in changes.into_iter() {
+        let placement_state_entry = project.placements.entry(path);
+
+        match change {
+            Change::New => {
+                info!(target: MUTATION_TARGET, placement = ?placement, message = "New placement. placement: {placement}");
+
+                let placement_state = PlacementState {
+                    unit_path,
+                    placement: placement.into_owned(),
+                    placed: false,
+                    status: PlacementStatus::Known,
+                    phase: None,
+                    place_override: None,
+                    defects: vec![],
+                };
+
+                placement_state_entry.or_insert(placement_state);
+            }
+            Change::Existing => {
+                placement_state_entry.and_modify(|ps| {
+                    if !ps.placement.eq(&placement) {
+                        info!(target: MUTATION_TARGET, old = ?ps.placement, new = ?placement, message = "Updating placement. old: {old}, new: {new}");
+                        ps.placement = placement.into_owned();
+                    }
+                });
+            }
+            Change::Unused => {
+                info!(target: MUTATION_TARGET, placement = ?placement, message = "Marking placement as unused. placement: {placement}");
+
+                placement_state_entry.and_modify(|ps|{
+                    ps.status = PlacementStatus::Unknown;
+                });
+            }
+        }
+    }
+}
+
+/// Finds the changes to the placements of each unit.
+///
+/// The changes borrow the placements of the design variants, so a placement is only cloned when the change is applied
+/// and the placement is new or has changed, each placement state still owns a copy of its placement.
+fn find_placement_changes<'a>(project: &Project, design_variant_placement_map: &'a BTreeMap<DesignVariant, Vec<Placement>>) -> Result<Vec<PlacementChange<'a>>, RefreshError> {
+    let mut changes: Vec<PlacementChange<'a>> = vec![];
+
+    // find new or existing placements that are in the updated design_variant_placement_map
+
+    for (unit_path, design_variant) in project.unit_assignments.iter() {
+        let Some(placements) = design_variant_placement_map.get(design_variant) else {
+            continue
+        };
+
+        for placement in placements {
+            let ref_des = RefDes::from_str(&placement.ref_des)
+                .map_err(|error| RefreshError::InvalidRefDes { design_variant: design_variant.clone(), error })?;
+
+            let mut path: ObjectPath = unit_path.clone();
+            path.set_ref_des(ref_des);
+
+            // look for a placement state for the placement for this object path
+
+            match project.placements.contains_key(&path) {
+                true => changes.push((Change::Existing, unit_path.clone(), path, Cow::Borrowed(placement))),
+                false => changes.push((Change::New, unit_path.clone(), path, Cow::Borrowed(placement))),
+            }
+        }
+    }
+
+    // find the placements that we knew about previously, but that are no-longer in the design_variant_placement_map
+
+    let design_variant_ref_des_map: BTreeMap<&DesignVariant, HashSet<&str>> = design_variant_placement_map.iter()
+        .map(|(design_variant, placements)| {
+            (design_variant, placements.iter().map(|placement| placement.ref_des.as_str()).collect())
+        })
+        .collect();
+
+    for (path, state) in project.placements.iter() {
+        let Some(design_variant) = project.unit_assignments.get(&state.unit_path) else {
+            continue
+        };
+        let Some(ref_des_set) = design_variant_ref_des_map.get(design_variant) else {
+            continue
+        };
+
+        match ref_des_set.contains(state.placement.ref_des.as_str()) {
+            true => trace!("known placement"),
+            false => {
+                trace!("unknown placement");
+                match state.status {
+                    PlacementStatus::Unknown => (),
+                    PlacementStatus::Known => changes.push((Change::Unused, state.unit_path.clone(), path.clone(), Cow::Owned(state.placement.clone()))),
+                }
+            }
+        }
+    }
+
+    debug!("placement changes:\n{:?}", changes);
+
+    Ok(changes)
+}
+
+/// The change, the unit path, the object path and the placement.
+type PlacementChange<'a> = (Change, ObjectPath, ObjectPath, Cow<'a, Placement>);
+
+#[derive(Debug)]
+enum Change {
+    New,
+    Existing,
+    Unused,
+}
+
+fn refresh_parts(project: &mut Project, all_parts: &[Part]) {
+    let changes = find_part_changes(project, all_parts);
+
+    for change_item in changes.iter() {
+        match change_item {
+            (Change::New, part) => {
+                info!(target: MUTATION_TARGET, part = ?part, message = "New part. part: {part}");
+                let _ = project.part_states.entry(part.clone()).or_default();
+            }
+            (Change::Existing, _) => {}
+            (Change::Unused, part) => {
+                info!(target: MUTATION_TARGET, part = ?part, message = "Removing previously part. part: {part}");
+                let _ = project.part_states.remove(part);
+            }
+        }
+    }
+}
+
+fn find_part_changes(project: &mut Project, all_parts: &[Part]) -> Vec<(Change, Part)> {
+    let mut changes: Vec<(Change, Part)> = vec![];
+
+    for part in all_parts.iter() {
+        match project.part_states.contains_key(part) {
+            true => changes.push((Change::Existing, part.clone())),
+            false => changes.push((Change::New, part.clone())),
+        }
+    }
+
+    for (part, _process) in project.part_states.iter() {
+        if !all_parts.contains(part) {
+            changes.push((Change::Unused, part.clone()))
+        }
+    }
+
+    debug!("part changes:\n{:?}", changes);
+
+    changes
+}
+
+// TODO currently only supports adding a process, add support for removing a process too.
+pub fn update_applicable_processes(project: &mut Project, all_parts: &[Part], process: Process, manufacturer_pattern: Regex, mpn_pattern: Regex) {
+
+    let changes = find_part_changes(project, all_parts);
+
+    for change in changes.iter() {
+        match change {
+            (Change::Existing, part) => {
+                if manufacturer_pattern.is_match(part.manufacturer.as_str()) && mpn_pattern.is_match(part.mpn.as_str()) {
+                    project.part_states.entry(part.clone())
+                        .and_modify(|part_state| {
+                            add_process_to_part(part_state, part, process.name.clone());
+                        });
+                }
+            },
+            _ => {
+                panic!("unexpected change. change: {:?}", change);
+            }
+        }
+    }
+}
+
+/// Sets, or with `None` removes, the moisture sensitivity of the parts that match the patterns.
+pub fn update_moisture_sensitivity(project: &mut Project, moisture_sensitivity: Option<MoistureSensitivity>, manufacturer_pattern: Regex, mpn_pattern: Regex) -> bool {
+    let mut modified = false;
+
+    for (part, part_state) in project.part_states.iter_mut() {
+        if !(manufacturer_pattern.is_match(part.manufacturer.as_str()) && mpn_pattern.is_match(part.mpn.as_str())) {
+            continue
+        }
+
+        if part_state.moisture_sensitivity.ne(&moisture_sensitivity) {
+            info!(target: MUTATION_TARGET, part = ?part, old = ?part_state.moisture_sensitivity, new = ?moisture_sensitivity, message = "Updated moisture sensitivity. part: {part}, old: {old}, new: {new}");
+            part_state.moisture_sensitivity.clone_from(&moisture_sensitivity);
+            modified = true;
+        }
+    }
+
+    modified
+}
+
+#[derive(Error, Debug)]
+pub enum FeederLoadedError {
+    #[error("Unknown feeder. phase: '{phase:}', feeder: '{feeder_reference:}'")]
+    UnknownFeeder { phase: Reference, feeder_reference: String },
+}
+
+/// Records the part loaded in the feeder, the floor life of a moisture sensitive part starts when it is loaded.
+///
+/// When an alternate is loaded, the alternate is recorded, so that the operation history shows the part that was used.
+pub fn record_feeder_loaded(project: &mut Project, path: &Path, phase_reference: &Reference, feeder_reference: &str, load_out_items: &[LoadOutItem]) -> anyhow::Result<()> {
+    let phase_state = project.phase_states.get_mut(phase_reference)
+        .ok_or(PhaseError::UnknownPhase(phase_reference.clone()))?;
+
+    let load_out_item = load_out_items.iter()
+        .find(|load_out_item| load_out_item.reference.eq(feeder_reference))
+        .ok_or(FeederLoadedError::UnknownFeeder { phase: phase_reference.clone(), feeder_reference: feeder_reference.to_string() })?;
+
+    let part = load_out_item.loaded_part();
+    let now = OffsetDateTime::now_utc();
+
+    phase_state.feeder_exposures.insert(feeder_reference.to_string(), FeederExposure { part: part.clone(), loaded_at: now });
+
+    info!(target: MUTATION_TARGET, phase = %phase_reference, feeder = %feeder_reference, part = ?part, message = "Recorded feeder loaded. phase: '{phase}', feeder: '{feeder}', part: {part}");
+
+    let phase_log_path = path.join(format!("{}_log.json", phase_reference));
+
+    let mut operation_history: Vec<OperationHistoryItem> = operation_history::read_or_default(&phase_log_path)?;
+
+    operation_history::append(&mut operation_history, vec![
+        OperationHistoryItem::new(now, phase_reference.clone(), OperationHistoryKind::FeederLoaded { feeder_reference: feeder_reference.to_string(), part }),
+    ]);
+
+    operation_history::write(phase_log_path, &operation_history)?;
+
+    Ok(())
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PartPlacementCounts {
+    pub placed: u32,
+    pub unplaced: u32,
+}
+
+/// Counts the placements of each part, for each phase, only placements that are to be placed are counted.
+pub fn count_phase_part_placements(project: &Project) -> BTreeMap<Reference, BTreeMap<Part, PartPlacementCounts>> {
+    project.placements.values()
+        .filter(|placement_state| placement_state.place())
+        .fold(BTreeMap::new(), |mut phase_counts, placement_state| {
+            if let Some(phase) = &placement_state.phase {
+                let counts: &mut PartPlacementCounts = phase_counts.entry(phase.clone())
+                    .or_insert_with(BTreeMap::new)
+                    .entry(placement_state.placement.part.clone())
+                    .or_default();
+
+                match placement_state.placed {
+                    true => counts.placed += 1,
+                    false => counts.unplaced += 1,
+                }
+            }
+            phase_counts
+        })
+}
+
+/// Updates the details of the parts in the project, parts that are not in the project are ignored.
+pub fn update_part_details(project: &mut Project, part_details: &BTreeMap<Part, PartDetails>) -> bool {
+    let mut modified = false;
+
+    for (part, part_state) in project.part_states.iter_mut() {
+        let Some(details) = part_details.get(part) else {
+            continue
+        };
+
+        if part_state.details.ne(details) {
+            info!(target: MUTATION_TARGET, part = ?part, image = ?details.image, datasheet = ?details.datasheet, value = ?details.value, description = ?details.description, message = "Updated part details. part: {part}, image: {image}, datasheet: {datasheet}, value: {value}, description: {description}");
+            part_state.details = details.clone();
+            modified = true;
+        }
+    }
+
+    modified
+}
+
+/// Sets the moisture sensitivity level of the parts, e.g. from a parts master, parts that already have the level are
+/// unchanged, so their floor life is kept.  Level 6 parts are skipped, as they require a floor life.
+pub fn update_msl_levels(project: &mut Project, msl_levels: &BTreeMap<Part, MslLevel>) -> bool {
+    let mut modified = false;
+
+    for (part, part_state) in project.part_states.iter_mut() {
+        let Some(level) = msl_levels.get(part) else {
+            continue
+        };
+
+        if part_state.moisture_sensitivity.as_ref().is_some_and(|moisture_sensitivity| moisture_sensitivity.level.eq(level)) {
+            continue
+        }
+
+        if matches!(level, MslLevel::Level6) {
+            warn!("Skipped level 6 part, a floor life is required, use 'set-moisture-sensitivity'. part: {:?}", part);
+            continue
+        }
+
+        let moisture_sensitivity = Some(MoistureSensitivity { level: *level, floor_life_hours: None });
+        info!(target: MUTATION_TARGET, part = ?part, old = ?part_state.moisture_sensitivity, new = ?moisture_sensitivity, message = "Updated moisture sensitivity. part: {part}, old: {old}, new: {new}");
+        part_state.moisture_sensitivity = moisture_sensitivity;
+        modified = true;
+    }
+
+    modified
+}
+
+#[derive(Error, Debug)]
+pub enum PartRenameError {
+    #[error("Unknown part. manufacturer: {}, mpn: {}", part.manufacturer, part.mpn)]
+    UnknownPart { part: Part },
+
+    #[error("Part already exists. manufacturer: {}, mpn: {}", part.manufacturer, part.mpn)]
+    PartAlreadyExists { part: Part },
+}
+
+/// Renames the part, keeping its part state, for all the placements that use the part.
+///
+/// Returns the object paths of the renamed placements.
+pub fn rename_part(project: &mut Project, from: &Part, to: &Part) -> Result<Vec<ObjectPath>, PartRenameError> {
+    if project.part_states.contains_key(to) {
+        return Err(PartRenameError::PartAlreadyExists { part: to.clone() })
+    }
+
+    let part_state = project.part_states.remove(from)
+        .ok_or(PartRenameError::UnknownPart { part: from.clone() })?;
+    project.part_states.insert(to.clone(), part_state);
+
+    let object_paths: Vec<ObjectPath> = project.placements.iter_mut()
+        .filter(|(_object_path, placement_state)| placement_state.placement.part.eq(from))
+        .map(|(object_path, placement_state)| {
+            trace!("Renaming placement part. object_path: {}", object_path);
+            placement_state.placement.part = to.clone();
+            object_path.clone()
+        })
+        .collect();
+
+    info!(target: MUTATION_TARGET, from = ?from, to = ?to, placements = object_paths.len(), message = "Renamed part. from: {from}, to: {to}, placements: {placements}");
+
+    Ok(object_paths)
+}
+
+/// Adds a part renamed history item to the log of each phase.
+pub fn record_part_renamed(path: &Path, phases: &BTreeSet<Reference>, from: &Part, to: &Part) -> anyhow::Result<()> {
+    let now = OffsetDateTime::now_utc();
+
+    for phase_reference in phases.iter() {
+        let phase_log_path = operation_history::build_phase_log_path(path, phase_reference);
+
+        let mut operation_history: Vec<OperationHistoryItem> = operation_history::read_or_default(&phase_log_path)?;
+
+        operation_history::append(&mut operation_history, vec![
+            OperationHistoryItem::new(now, phase_reference.clone(), OperationHistoryKind::PartRenamed { from: from.clone(), to: to.clone() }),
+        ]);
+
+        operation_history::write(phase_log_path, &operation_history)?;
+    }
+
+    Ok(())
+}
+
+/// Renames the operation history file of the phase, the phase of the items is renamed, see
+/// `operation_history::rename_phase`, and a phase renamed history item is added.
+///
+/// Fails if there is already an operation history for the new phase reference, e.g. of a removed phase.
+pub fn record_phase_renamed(path: &Path, from: &Reference, to: &Reference) -> anyhow::Result<()> {
+    let phase_log_path = operation_history::build_phase_log_path(path, from);
+    let new_phase_log_path = operation_history::build_phase_log_path(path, to);
+
+    if new_phase_log_path.exists() {
+        anyhow::bail!("Operation history already exists. path: {:?}", new_phase_log_path);
+    }
+
+    let mut operation_history: Vec<OperationHistoryItem> = operation_history::read_or_default(&phase_log_path)?;
+    operation_history::rename_phase(&mut operation_history, from, to)?;
+
+    operation_history::append(&mut operation_history, vec![
+        OperationHistoryItem::new(OffsetDateTime::now_utc(), to.clone(), OperationHistoryKind::PhaseRenamed { from: from.clone(), to: to.clone() }),
+    ]);
+
+    operation_history::write(new_phase_log_path, &operation_history)?;
+
+    if phase_log_path.exists() {
+        std::fs::remove_file(&phase_log_path)?;
+        info!("Removed operation history file. path: {:?}", phase_log_path);
+    }
+
+    Ok(())
+}
+
+/// Verifies the operation history of the phase, or of all the phases, see `operation_history::verify`.
+pub fn verify_operation_history(project: &Project, path: &Path, phase: Option<&Reference>, head: Option<&str>) -> anyhow::Result<Vec<(Reference, Result<OperationHistoryVerification, OperationHistoryError>)>> {
+    let references: Vec<&Reference> = match phase {
+        Some(reference) => {
+            let phase = project.phases.get(reference)
+                .ok_or(PhaseError::UnknownPhase(reference.clone()))?;
+            vec![&phase.reference]
+        },
+        None => project.phase_orderings.iter().collect(),
+    };
+
+    let mut results = vec![];
+
+    for reference in references {
+        let mut phase_log_path = path.to_path_buf();
+        phase_log_path.push(format!("{}_log.json", reference));
+
+        let operation_history: Vec<OperationHistoryItem> = operation_history::read_or_default(&phase_log_path)?;
+
+        results.push((reference.clone(), operation_history::verify(&operation_history, head)));
+    }
+
+    Ok(results)
+}
+
+pub fn add_process_to_part(part_state: &mut PartState, part: &Part, process: ProcessName) {
+    let inserted = part_state.applicable_processes.insert(process);
+
+    if inserted {
+        info!(target: MUTATION_TARGET, part = ?part, applicable_processes = ?part_state.applicable_processes.iter().map(|it|it.to_string()).collect::<Vec<String>>(), message = "Added process. part: {part}, applicable_processes: {applicable_processes}");
+    }
+}
+
+/// Returns the path of the compressed project file, if it exists, otherwise the path of the plain project file.
+pub fn build_project_file_path(name: &str, path: &Path) -> PathBuf {
+    let project_file_path = build_plain_project_file_path(name, path);
+
+    let compressed_project_file_path = build_compressed_project_file_path(&project_file_path);
+    if compressed_project_file_path.exists() {
+        return compressed_project_file_path
+    }
+
+    project_file_path
+}
+
+pub fn build_plain_project_file_path(name: &str, path: &Path) -> PathBuf {
+    path.join(format!("project-{}.mpnp.json", name))
+}
+
+/// e.g. 'project-job1.mpnp.json' -> 'project-job1.mpnp.json.zst'
+pub fn build_compressed_project_file_path(project_file_path: &Path) -> PathBuf {
+    let mut file_name = project_file_path.file_name().unwrap().to_os_string();
+    file_name.push(format!(".{}", compression::COMPRESSED_EXTENSION));
+    project_file_path.with_file_name(file_name)
+}
+
+/// Loads the project, holding a shared lock while reading, see `locking`.
+///
+/// To change the project use `load_locked` and `save_locked` instead, holding an exclusive lock from loading until
+/// saving, otherwise the changes of another process made in between are lost.
+pub fn load(project_file_path: &PathBuf) -> anyhow::Result<Project> {
+    let content = {
+        // a lock file is not created when there is no project file
+        let _lock = match project_file_path.exists() {
+            true => Some(locking::lock(project_file_path, LockMode::Shared)?),
+            false => None,
+        };
+        std::fs::read(project_file_path)?
+    };
+
+    deserialize(content)
+}
+
+/// Loads the project, the caller holds the exclusive lock of the project file.
+pub fn load_locked(project_file_path: &Path, _lock: &ProjectLock) -> anyhow::Result<Project> {
+    deserialize(std::fs::read(project_file_path)?)
+}
+
+fn deserialize(content: Vec<u8>) -> anyhow::Result<Project> {
+    let content = compression::decompress(content)?;
+    let mut de = serde_json::Deserializer::from_slice(&content);
+    let project = Project::deserialize(&mut de)?;
+    Ok(project)
+}
+
+/// Saves the project, compressed if the extension of the path is '.zst'.
+///
+/// The content is written to a temporary file which then replaces the project file, holding an exclusive lock, so that
+/// readers never see a partially written project file.
+pub fn save(project: &Project, project_file_path: &Path) -> anyhow::Result<()> {
+    let lock = locking::lock(project_file_path, LockMode::Exclusive)?;
+
+    save_locked(project, project_file_path, &lock)
+}
+
+/// See `save`, the caller holds the exclusive lock of the project file, e.g. since loading it.
+pub fn save_locked(project: &Project, project_file_path: &Path, _lock: &ProjectLock) -> anyhow::Result<()> {
+    let mut content = serialize(project)?;
+    if compression::is_compressed_path(project_file_path) {
+        content = compression::compress(&content)?;
+    }
+
+    let mut temporary_file_name = project_file_path.file_name().unwrap().to_os_string();
+    temporary_file_name.push(".tmp");
+    let temporary_file_path = project_file_path.with_file_name(temporary_file_name);
+
+    let mut temporary_file = File::create(&temporary_file_path)?;
+    temporary_file.write_all(&content)?;
+    temporary_file.sync_all()?;
+
+    std::fs::rename(&temporary_file_path, project_file_path)?;
+
+    Ok(())
+}
+
+/// Checked before any placement is updated, so that the placements are not partially updated.
+pub fn ensure_first_articles_signed_off(project: &Project, object_path_patterns: &[ObjectPathMatcher]) -> Result<(), first_article::FirstArticleError> {
+    let unsigned_first_articles = first_article::find_unsigned_first_articles(project);
+    if unsigned_first_articles.is_empty() {
+        return Ok(())
+    }
+
+    for (object_path, placement_state) in project.placements.iter() {
+        let Some(first_article) = placement_state.phase.as_ref().and_then(|phase| unsigned_first_articles.get(phase)) else {
+            continue
+        };
+
+        if placement_state.placed || object_path.pcb_unit().eq(first_article) {
+            continue
+        }
+
+        if object_path_patterns.iter().any(|object_path_pattern| object_path_pattern.is_match(object_path)) {
+            return Err(first_article::FirstArticleError::NotSignedOff {
+                phase: placement_state.phase.clone().unwrap(),
+                first_article: first_article.clone(),
+                object_path: object_path.clone(),
+            })
+        }
+    }
+
+    Ok(())
+}
+
+/// Serializes the project in the same format that `save` uses.
+pub fn serialize(project: &Project) -> anyhow::Result<Vec<u8>> {
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
+    let mut ser = serde_json::Serializer::with_formatter(vec![], formatter);
+    project.serialize(&mut ser)?;
+
+    let mut content = ser.into_inner();
+    content.push(b'\n');
+
+    Ok(content)
+}
+
+pub fn update_placements_operation(project: &mut Project, path: &Path, object_path_patterns: Vec<ObjectPathMatcher>, operation: PlacementOperation) -> anyhow::Result<bool> {
+    if operation == PlacementOperation::Placed {
+        ensure_first_articles_signed_off(project, &object_path_patterns)?;
+    }
+
+    let mut modified = false;
+    let mut history_item_map: HashMap<Reference, Vec<OperationHistoryItem>> = HashMap::new();
+    
+    for object_path_pattern in object_path_patterns.iter() {
+        let placements: Vec<_> = project.placements.iter_mut().filter(|(object_path, _placement_state)|{
+            object_path_pattern.is_match(object_path)
+        }).collect();
+        
+        if placements.is_empty() {
+            warn!("Unmatched object path pattern. object_path_pattern: {}", object_path_pattern);
+        }
+        
+        for (object_path, placement_state) in placements {
+            let recorded = match operation {
+                PlacementOperation::Placed => {
+                    if placement_state.placed {
+                        warn!("Placed flag already set. object_path: {}", object_path);
+                        false
+                    } else {
+                        info!(target: MUTATION_TARGET, object_path = %object_path, message = "Setting placed flag. object_path: {object_path}");
+                        placement_state.placed = true;
+
+                        close_reworked_defects(object_path, placement_state);
+                        true
+                    }
+                }
+                PlacementOperation::Unplaced => {
+                    if !placement_state.placed {
+                        warn!("Placed flag not set. object_path: {}", object_path);
+                        false
+                    } else {
+                        info!(target: MUTATION_TARGET, object_path = %object_path, message = "Clearing placed flag. object_path: {object_path}");
+                        placement_state.placed = false;
+                        true
+                    }
+                }
+                PlacementOperation::InspectionFailed => {
+                    if placement_state.phase.is_none() {
+                        warn!("Placement not assigned to a phase. object_path: {}", object_path);
+                        false
+                    } else if !placement_state.placed {
+                        warn!("Placed flag not set. object_path: {}", object_path);
+                        false
+                    } else if placement_state.has_open_defect() {
+                        warn!("Open inspection defect already recorded. object_path: {}", object_path);
+                        false
+                    } else {
+                        info!(target: MUTATION_TARGET, object_path = %object_path, message = "Recording inspection defect. object_path: {object_path}");
+                        placement_state.defects.push(PlacementDefect {
+                            date_time: OffsetDateTime::now_utc(),
+                            phase: placement_state.phase.clone().unwrap(),
+                            status: PlacementDefectStatus::Open,
+                            rework_phase: None,
+                        });
+                        true
+                    }
+                }
+            };
+
+            if recorded {
+                let now = OffsetDateTime::now_utc();
+
+                let phase = placement_state.phase.as_ref().unwrap();
+
+                let history_item = OperationHistoryItem::new(
+                    now,
+                    phase.clone(),
+                    OperationHistoryKind::PlacementOperation { object_path: object_path.clone(), operation: operation.clone() },
+                );
+
+                let history_items = history_item_map.entry(phase.clone())
+                    .or_default();
+
+                history_items.push(history_item);
+
+                modified = true;
+            }
+        }
+    }
+
+    if modified {
+        let original_phase_states = project.phase_states.clone();
+
+        update_phase_operation_states(project);
+
+        record_operation_transitions(project, &original_phase_states, &mut history_item_map);
+
+        for phase_reference in history_item_map.keys() {
+            warn_of_incomplete_dependencies(project, phase_reference);
+        }
+
+        for (phase_reference, history_items) in history_item_map {
+            let phase_log_path = operation_history::build_phase_log_path(path, &phase_reference);
+
+            let mut operation_history: Vec<OperationHistoryItem> = operation_history::read_or_default(&phase_log_path)?;
+            
+            operation_history::append(&mut operation_history, history_items);
+            
+            operation_history::write(phase_log_path, &operation_history)?;
+        }
+    }
+    
+    Ok(modified)
+}
+
+/// Sets, or clears, the placement override of the placements matching the patterns, the design is not changed.
+///
+/// The phase operation states are updated, since overridden placements change the placements that are to be placed.
+pub fn set_placement_override(project: &mut Project, object_path_patterns: &[Regex], place_override: Option<PlacementOverride>) -> bool {
+    let mut modified = false;
+
+    for object_path_pattern in object_path_patterns.iter() {
+        let placements: Vec<_> = project.placements.iter_mut().filter(|(object_path, _placement_state)|{
+            object_path_pattern.is_match(&object_path.to_string())
+        }).collect();
+
+        if placements.is_empty() {
+            warn!("Unmatched object path pattern. object_path_pattern: {}", object_path_pattern);
+        }
+
+        for (object_path, placement_state) in placements {
+            if placement_state.place_override.eq(&place_override) {
+                continue
+            }
+
+            info!(target: MUTATION_TARGET, object_path = %object_path, old = ?placement_state.place_override, new = ?place_override, message = "Setting placement override. object_path: {object_path}, old: {old}, new: {new}");
+            placement_state.place_override = place_override;
+            modified = true;
+        }
+    }
+
+    if modified {
+        update_phase_operation_states(project);
+    }
+
+    modified
+}
+
+/// Operations can be recorded out of order, e.g. when a dependency was completed but not recorded, so they are only warned of.
+fn warn_of_incomplete_dependencies(project: &Project, reference: &Reference) {
+    let incomplete_dependencies = phase::find_incomplete_dependencies(project, reference);
+    if !incomplete_dependencies.is_empty() {
+        warn!("Phase operation recorded before the phases it depends on were complete. phase: '{}', incomplete dependencies: {}", reference, phase::format_references(&incomplete_dependencies));
+    }
+}
+
+/// Closes the open defects of a placement that were being reworked in the placement's current phase.
+fn close_reworked_defects(object_path: &ObjectPath, placement_state: &mut PlacementState) {
+    for defect in placement_state.defects.iter_mut() {
+        if defect.status == PlacementDefectStatus::Open && defect.rework_phase.is_some() && defect.rework_phase.eq(&placement_state.phase) {
+            defect.status = PlacementDefectStatus::Closed;
+            info!(target: MUTATION_TARGET, object_path = %object_path, phase = %defect.phase, message = "Closed inspection defect. object_path: {object_path}, phase: {phase}");
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ReworkError {
+    #[error("Phase already exists. phase: '{0:}'")]
+    PhaseAlreadyExists(Reference),
+
+    #[error("No open inspection defects found. pcb_side: {0:?}")]
+    NoOpenDefects(PcbSide),
+}
+
+/// Creates a rework phase and assigns the placements with open, un-reworked, inspection defects to it.
+///
+/// The placements are moved to the rework phase and their placed flag is reset, the defects retain the phase
+/// in which they were found.
+///
+/// Returns the parts required for the rework phase's load-out.
+pub fn create_rework_phase(project: &mut Project, reference: &Reference, process: &Process, load_out_source: String, pcb_side: PcbSide) -> anyhow::Result<BTreeSet<Part>> {
+    if project.phases.contains_key(reference) {
+        return Err(ReworkError::PhaseAlreadyExists(reference.clone()).into())
+    }
+
+    let defective_placement_count = project.placements.values()
+        .filter(|placement_state| is_rework_candidate(placement_state, &pcb_side))
+        .count();
+
+    if defective_placement_count == 0 {
+        return Err(ReworkError::NoOpenDefects(pcb_side).into())
+    }
+
+    project.update_phase(reference.clone(), process.name.clone(), load_out_source, pcb_side.clone())?;
+
+    let mut required_load_out_parts = BTreeSet::new();
+
+    for (object_path, placement_state) in project.placements.iter_mut()
+        .filter(|(_object_path, placement_state)| is_rework_candidate(placement_state, &pcb_side)) {
+
+        for defect in placement_state.defects.iter_mut().filter(|defect| defect.status == PlacementDefectStatus::Open) {
+            defect.rework_phase = Some(reference.clone());
+        }
+
+        info!(target: MUTATION_TARGET, phase = %reference, placement_path = %object_path, message = "Assigning placement to rework phase. phase: {phase}, placement_path: {placement_path}");
+        placement_state.phase = Some(reference.clone());
+        placement_state.placed = false;
+
+        let _inserted = required_load_out_parts.insert(placement_state.placement.part.clone());
+    }
+
+    update_phase_operation_states(project);
+
+    Ok(required_load_out_parts)
+}
+
+fn is_rework_candidate(placement_state: &PlacementState, pcb_side: &PcbSide) -> bool {
+    placement_state.placement.pcb_side.eq(pcb_side)
+        && placement_state.defects.iter().any(|defect| {
+            defect.status == PlacementDefectStatus::Open && defect.rework_phase.is_none()
+        })
+}
+
+pub fn update_phase_operation_states(project: &mut Project) -> bool {
+    let mut modified = false;
+
+    for (reference, phase_state) in project.phase_states.iter_mut() {
+        trace!("reference: {:?}, phase_state: {:?}", reference, phase_state);
+
+        for (operation, operation_state) in phase_state.operation_state.iter_mut() {
+            trace!("operation: {:?}, operation_state: {:?}", operation, operation_state);
+
+            let tracks_placements = project.phases.get(reference)
+                .and_then(|phase| project.processes.iter().find(|process| process.name.eq(&phase.process)))
+                .map_or(operation.is_placement_operation(), |process| process.tracks_placements(operation));
+
+            let maybe_state = if tracks_placements {
+                let placements_state = project.placements.iter()
+                    .fold(PlacementsState::default(), |mut state, (_object_path, placement_status)| {
+                        if let Some(placement_phase) = &placement_status.phase {
+                            if placement_phase.eq(reference) && placement_status.place() {
+                                if placement_status.placed {
+                                    state.placed += 1;
+                                }
+                                state.total += 1;
+                            }
+                        }
+
+                        state
+                    });
+
+                let status = if placements_state.total == 0 || placements_state.placed == 0 {
+                    ProcessOperationStatus::Pending
+                } else if placements_state.are_all_placements_placed() {
+                    ProcessOperationStatus::Complete
+                } else {
+                    ProcessOperationStatus::Incomplete
+                };
+
+                Some((placements_state, status))
+            } else {
+                None
+            };
+            trace!("maybe_state: {:?}", maybe_state);
+
+
+            let original_operation_state = operation_state.clone();
+
+            if let Some((placements_state, status)) = &maybe_state {
+                if project.operation_transitions.is_automatic() {
+                    operation_state.status = status.clone();
+                }
+                operation_state.extra = Some(ProcessOperationExtraState::PlacementOperation { placements_state: placements_state.clone() });
+            }
+
+            let phase_operation_modified = !original_operation_state.eq(operation_state);
+
+            if phase_operation_modified {
+                info!("Updating phase status. phase: {}", reference);
+
+                if let Some((_maybe_state, status)) = maybe_state.filter(|_state| project.operation_transitions.is_automatic()) {
+                    match status {
+                        ProcessOperationStatus::Complete => info!("Phase operation complete. phase: {}, operation: {:?}", reference, operation),
+                        ProcessOperationStatus::Incomplete => info!("Phase operation incomplete. phase: {}, operation: {:?}", reference, operation),
+                        ProcessOperationStatus::Pending => info!("Phase operation pending. phase: {}, operation: {:?}", reference, operation),
+                    }
+                }
+            }
+
+            modified |= phase_operation_modified;
+        }
+    }
+
+    modified
+}
+
+#[derive(Error, Debug)]
+pub enum PartStateError {
+    #[error("No part state found. manufacturer: {}, mpn: {}", part.manufacturer, part.mpn)]
+    NoPartStateFound { part: Part }
+}
+
+pub fn update_phase_operation(project: &mut Project, path: &Path, phase_reference: &Reference, operation: ProcessOperationKind, set_item: ProcessOperationSetItem) -> anyhow::Result<bool> {
+
+    let phase_state = project.phase_states.get_mut(phase_reference)
+        .ok_or(PhaseError::UnknownPhase(phase_reference.clone()))?;
+
+    let mut modified = false;
+
+    let state = phase_state.operation_state.get_mut(&operation)
+        .ok_or(PhaseError::InvalidOperationForPhase(phase_reference.clone(), operation.clone()))?;
+
+    match set_item {
+        ProcessOperationSetItem::Completed => {
+            if state.status.ne(&ProcessOperationStatus::Complete) {
+
+                state.status = ProcessOperationStatus::Complete;
+                modified = true;
+            }
+        }
+    }
+
+    if modified {
+        info!(target: MUTATION_TARGET, phase = %phase_reference, operation = ?operation, message = "Phase operation completed. phase: {phase}, operation: {operation}");
+
+        let history_operation = build_history_operation_kind(&operation, state);
+        warn_of_incomplete_dependencies(project, phase_reference);
+
+        let now = OffsetDateTime::now_utc();
+
+        let history_item = OperationHistoryItem::new(now, phase_reference.clone(), history_operation);
+
+        let phase_log_path = operation_history::build_phase_log_path(path, phase_reference);
+
+        let mut operation_history: Vec<OperationHistoryItem> = operation_history::read_or_default(&phase_log_path)?;
+
+        operation_history::append(&mut operation_history, vec![history_item]);
+
+        operation_history::write(phase_log_path, &operation_history)?;
+    }
+
+    Ok(modified)
+}
+
+/// Adds history items for placement operations that have been completed, or reopened, by updating the phase operation states.
+fn record_operation_transitions(project: &Project, original_phase_states: &BTreeMap<Reference, PhaseState>, history_item_map: &mut HashMap<Reference, Vec<OperationHistoryItem>>) {
+    for (reference, phase_state) in project.phase_states.iter() {
+        let Some(original_phase_state) = original_phase_states.get(reference) else {
+            continue
+        };
+
+        for (operation, operation_state) in phase_state.operation_state.iter() {
+            let Some(original_operation_state) = original_phase_state.operation_state.get(operation) else {
+                continue
+            };
+
+            let was_complete = original_operation_state.status.eq(&ProcessOperationStatus::Complete);
+            let is_complete = operation_state.status.eq(&ProcessOperationStatus::Complete);
+
+            let message = match (was_complete, is_complete) {
+                (false, true) => "Phase operation completed automatically. phase: {phase}, operation: {operation}",
+                (true, false) => "Phase operation reopened automatically. phase: {phase}, operation: {operation}",
+                _ => continue,
+            };
+            info!(target: MUTATION_TARGET, phase = %reference, operation = ?operation, message);
+
+            history_item_map.entry(reference.clone()).or_default().push(OperationHistoryItem::new(
+                OffsetDateTime::now_utc(),
+                reference.clone(),
+                build_history_operation_kind(operation, operation_state),
+            ));
+        }
+    }
+}
+
+fn build_history_operation_kind(operation: &ProcessOperationKind, state: &ProcessOperationState) -> OperationHistoryKind {
+    match operation {
+        ProcessOperationKind::LoadPcbs => OperationHistoryKind::LoadPcbs { status: state.status.clone() },
+        ProcessOperationKind::AutomatedPnp => OperationHistoryKind::AutomatedPnp { status: state.status.clone() },
+        ProcessOperationKind::ReflowComponents => OperationHistoryKind::ReflowComponents { status: state.status.clone() },
+        ProcessOperationKind::ManuallySolderComponents => OperationHistoryKind::ManuallySolderComponents { status: state.status.clone() },
+        ProcessOperationKind::Custom(name) => OperationHistoryKind::CustomOperation { operation: name.clone(), status: state.status.clone() },
+    }
+}
+
+#[cfg(test)]
+mod build_history_operation_kind {
+    use rstest::rstest;
+    use crate::operation_history::OperationHistoryKind;
+    use crate::process::{ProcessOperationKind, ProcessOperationState, ProcessOperationStatus};
+    use crate::project::build_history_operation_kind;
+
+    #[rstest]
+    #[case(ProcessOperationStatus::Pending)]
+    #[case(ProcessOperationStatus::Incomplete)]
+    #[case(ProcessOperationStatus::Complete)]
+    pub fn for_load_pcbs(#[case] status: ProcessOperationStatus) {
+        // given
+        let state = ProcessOperationState { status: status.clone(), extra: None };
+        
+        // and
+        let expected_result: OperationHistoryKind = OperationHistoryKind::LoadPcbs { status: status.clone() }; 
+        
+        // when
+        let result = build_history_operation_kind(&ProcessOperationKind::LoadPcbs, &state);
+        
+        // then
+        assert_eq!(result, expected_result)
+    }
+
+    #[rstest]
+    #[case(ProcessOperationStatus::Pending)]
+    #[case(ProcessOperationStatus::Incomplete)]
+    #[case(ProcessOperationStatus::Complete)]
+    pub fn for_automated_pnp(#[case] status: ProcessOperationStatus) {
+        // given
+        let state = ProcessOperationState { status: status.clone(), extra: None };
+
+        // and
+        let expected_result: OperationHistoryKind = OperationHistoryKind::AutomatedPnp { status: status.clone() };
+
+        // when
+        let result = build_history_operation_kind(&ProcessOperationKind::AutomatedPnp, &state);
+
+        // then
+        assert_eq!(result, expected_result)
+    }
+
+    #[rstest]
+    #[case(ProcessOperationStatus::Pending)]
+    #[case(ProcessOperationStatus::Incomplete)]
+    #[case(ProcessOperationStatus::Complete)]
+    pub fn for_manually_solder_components(#[case] status: ProcessOperationStatus) {
+        // given
+        let state = ProcessOperationState { status: status.clone(), extra: None };
+
+        // and
+        let expected_result: OperationHistoryKind = OperationHistoryKind::ManuallySolderComponents { status: status.clone() };
+
+        // when
+        let result = build_history_operation_kind(&ProcessOperationKind::ManuallySolderComponents, &state);
+
+        // then
+        assert_eq!(result, expected_result)
+    }
+
+    #[rstest]
+    #[case(ProcessOperationStatus::Pending)]
+    #[case(ProcessOperationStatus::Incomplete)]
+    #[case(ProcessOperationStatus::Complete)]
+    pub fn for_reflow_components(#[case] status: ProcessOperationStatus) {
+        // given
+        let state = ProcessOperationState { status: status.clone(), extra: None };
+
+        // and
+        let expected_result: OperationHistoryKind = OperationHistoryKind::ReflowComponents { status: status.clone() };
+
+        // when
+        let result = build_history_operation_kind(&ProcessOperationKind::ReflowComponents, &state);
+
+        // then
+        assert_eq!(result, expected_result)
+    }
+
+}
+
+#[cfg(test)]
+mod create_rework_phase {
+    use std::str::FromStr;
+    use time::OffsetDateTime;
+    use pnp::object_path::ObjectPath;
+    use pnp::part::Part;
+    use pnp::pcb::PcbSide;
+    use crate::placement::{PlacementDefect, PlacementDefectStatus};
+    use crate::project::{create_rework_phase, ProcessFactory};
+    use crate::reference::Reference;
+    use crate::test::project_builder::{PlacementStateBuilder, ProjectBuilder};
+
+    fn build_open_defect(phase: &str) -> PlacementDefect {
+        PlacementDefect {
+            date_time: OffsetDateTime::now_utc(),
+            phase: Reference::from_str(phase).unwrap(),
+            status: PlacementDefectStatus::Open,
+            rework_phase: None,
+        }
+    }
+
+    #[test]
+    pub fn assigns_defective_placements_to_rework_phase() {
+        // given
+        let part = |ref_des: &str| Part::new("MFR1".to_string(), format!("MPN_{}", ref_des));
+        let mut project = ProjectBuilder::new()
+            .with_placements([
+                PlacementStateBuilder::new(1, "R1", &part("R1")).with_placed(true).with_phase("top_1").with_defects(vec![build_open_defect("top_1")]),
+                PlacementStateBuilder::new(1, "R2", &part("R2")).with_placed(true).with_phase("top_1"),
+                PlacementStateBuilder::new(1, "R3", &part("R3")).with_pcb_side(PcbSide::Bottom).with_placed(true).with_phase("bottom_1").with_defects(vec![build_open_defect("bottom_1")]),
+            ])
+            .build();
+
+        // and
+        let process = ProcessFactory::by_name("manual").unwrap();
+        let rework_reference = Reference::from_str("rework_1").unwrap();
+
+        // and
+        let expected_parts = vec![Part::new("MFR1".to_string(), "MPN_R1".to_string())];
+
+        // when
+        let parts = create_rework_phase(&mut project, &rework_reference, &process, "rework_1".to_string(), PcbSide::Top).unwrap();
+
+        // then
+        assert_eq!(parts.into_iter().collect::<Vec<_>>(), expected_parts);
+        assert!(project.phases.contains_key(&rework_reference));
+
+        // and
+        let r1_state = project.placements.get(&ObjectPath::from_str("panel=1::unit=1::ref_des=R1").unwrap()).unwrap();
+        assert_eq!(r1_state.phase, Some(rework_reference.clone()));
+        assert!(!r1_state.placed);
+        assert_eq!(r1_state.defects[0].phase, Reference::from_str("top_1").unwrap());
+        assert_eq!(r1_state.defects[0].rework_phase, Some(rework_reference.clone()));
+
+        // and
+        let r2_state = project.placements.get(&ObjectPath::from_str("panel=1::unit=1::ref_des=R2").unwrap()).unwrap();
+        assert_eq!(r2_state.phase, Some(Reference::from_str("top_1").unwrap()));
+
+        // and
+        let r3_state = project.placements.get(&ObjectPath::from_str("panel=1::unit=1::ref_des=R3").unwrap()).unwrap();
+        assert_eq!(r3_state.phase, Some(Reference::from_str("bottom_1").unwrap()));
+        assert_eq!(r3_state.defects[0].rework_phase, None);
+    }
+
+    #[test]
+    pub fn fails_without_open_defects() {
+        // given
+        let part = Part::new("MFR1".to_string(), "MPN_R1".to_string());
+        let mut project = ProjectBuilder::new()
+            .with_placements([PlacementStateBuilder::new(1, "R1", &part).with_placed(true).with_phase("top_1")])
+            .build();
+
+        // and
+        let process = ProcessFactory::by_name("manual").unwrap();
+        let rework_reference = Reference::from_str("rework_1").unwrap();
+
+        // when
+        let result = create_rework_phase(&mut project, &rework_reference, &process, "rework_1".to_string(), PcbSide::Top);
+
+        // then
+        assert!(result.is_err());
+        assert!(project.phases.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod record_inspection_failure {
+    use std::str::FromStr;
+    use regex::Regex;
+    use tempfile::tempdir;
+    use pnp::object_path::ObjectPath;
+    use pnp::part::Part;
+    use crate::placement::PlacementOperation;
+    use crate::project::update_placements_operation;
+    use crate::test::project_builder::{PlacementStateBuilder, ProjectBuilder};
+
+    #[test]
+    pub fn records_defect_for_placed_placements_only() -> anyhow::Result<()> {
+        // given
+        let temp_dir = tempdir()?;
+        let part = Part::new("MFR1".to_string(), "MPN1".to_string());
+        let mut project = ProjectBuilder::new()
+            .with_placements([
+                PlacementStateBuilder::new(1, "R1", &part).with_placed(true).with_phase("top_1"),
+                PlacementStateBuilder::new(1, "R2", &part).with_phase("top_1"),
+            ])
+            .build();
+
+        // when
+        let modified = update_placements_operation(&mut project, temp_dir.path(), vec![Regex::new("ref_des=R2")?.into()], PlacementOperation::InspectionFailed)?;
+
+        // then
+        assert!(!modified);
+        assert!(project.placements[&ObjectPath::from_str("panel=1::unit=1::ref_des=R2")?].defects.is_empty());
+
+        // when
+        let modified = update_placements_operation(&mut project, temp_dir.path(), vec![Regex::new("ref_des=R1")?.into()], PlacementOperation::InspectionFailed)?;
+
+        // then
+        assert!(modified);
+        assert_eq!(project.placements[&ObjectPath::from_str("panel=1::unit=1::ref_des=R1")?].defects.len(), 1);
+
+        Ok(())
+    }
+}
+
+pub fn update_placement_orderings(project: &mut Project, reference: &Reference, placement_orderings: &Vec<PlacementSortingItem>) -> anyhow::Result<bool> {
+    let phase = project.phases.get_mut(reference)
+        .ok_or(PhaseError::UnknownPhase(reference.clone()))?;
+
+    let modified = if phase.placement_orderings.eq(placement_orderings) {
+        false
+    } else {
+        phase.placement_orderings.clone_from(placement_orderings);
+
+        let orderings = placement_orderings
+            .iter().map(|item|{
+                format!("{}:{}",
+                    item.mode.to_string().to_shouty_snake_case(),
+                    item.sort_order.to_string().to_shouty_snake_case()
+                )
+            }).collect::<Vec<_>>().join(", ");
+        info!(target: MUTATION_TARGET, phase = %reference, orderings = %orderings, message = "Phase placement orderings set. phase: '{phase}', orderings: [{orderings}]");
+        true
+    };
+
+    Ok(modified)
+}
+
+pub fn update_work_instructions_style(project: &mut Project, reference: &Reference, style: WorkInstructionsStyle) -> Result<bool, PhaseError> {
+    let phase = project.phases.get_mut(reference)
+        .ok_or(PhaseError::UnknownPhase(reference.clone()))?;
+
+    let modified = if phase.work_instructions_style.eq(&style) {
+        false
+    } else {
+        info!(target: MUTATION_TARGET, phase = %reference, old = %phase.work_instructions_style, new = %style, message = "Phase work instructions style set. phase: '{phase}', old: {old}, new: {new}");
+        phase.work_instructions_style = style;
+        true
+    };
+
+    Ok(modified)
+}
+
+/// Sets the nozzle configuration of the phase, `None` removes the configuration, returns true if modified.
+pub fn update_nozzle_configuration(project: &mut Project, reference: &Reference, nozzle_configuration: Option<NozzleConfiguration>) -> Result<bool, PhaseError> {
+    let phase = project.phases.get_mut(reference)
+        .ok_or(PhaseError::UnknownPhase(reference.clone()))?;
+
+    if phase.nozzle_configuration.eq(&nozzle_configuration) {
+        return Ok(false)
+    }
+
+    info!(target: MUTATION_TARGET, phase = %reference, old = ?phase.nozzle_configuration, new = ?nozzle_configuration, message = "Phase nozzle configuration set. phase: '{phase}', old: {old}, new: {new}");
+    phase.nozzle_configuration = nozzle_configuration;
+
+    Ok(true)
+}
+
+/// Sets the price list used for the cost estimates of the report, `None` removes the price list, returns true if modified.
+pub fn update_price_list_source(project: &mut Project, price_list_source: Option<String>) -> bool {
+    if project.price_list_source.eq(&price_list_source) {
+        return false
+    }
+
+    info!(target: MUTATION_TARGET, old = ?project.price_list_source, new = ?price_list_source, message = "Price list set. old: {old}, new: {new}");
+    project.price_list_source = price_list_source;
+
+    true
+}
+
+/// Sets the parameters of the phase duration estimates, `None` removes the parameters, returns true if modified.
+pub fn update_estimation_parameters(project: &mut Project, estimation_parameters: Option<EstimationParameters>) -> bool {
+    if project.estimation_parameters.eq(&estimation_parameters) {
+        return false
+    }
+
+    info!(target: MUTATION_TARGET, old = ?project.estimation_parameters, new = ?estimation_parameters, message = "Estimation parameters set. old: {old}, new: {new}");
+    project.estimation_parameters = estimation_parameters;
+
+    true
+}
+
+/// Sets the inventory used for the inventory shortages of the report, `None` removes the inventory, returns true if modified.
+pub fn update_inventory_source(project: &mut Project, inventory_source: Option<String>) -> bool {
+    if project.inventory_source.eq(&inventory_source) {
+        return false
+    }
+
+    info!(target: MUTATION_TARGET, old = ?project.inventory_source, new = ?inventory_source, message = "Inventory set. old: {old}, new: {new}");
+    project.inventory_source = inventory_source;
+
+    true
+}
+
+/// Sets the phases that the phase depends on, replacing any previous dependencies, returns true if modified.
+///
+/// The dependencies are rejected if they would make a cycle, see `phase::build_execution_plan`.
+pub fn update_phase_dependencies(project: &mut Project, reference: &Reference, dependencies: BTreeSet<Reference>) -> anyhow::Result<bool> {
+    if dependencies.contains(reference) {
+        return Err(PhaseDependencyError::SelfDependency(reference.clone()).into())
+    }
+    if let Some(unknown) = dependencies.iter().find(|dependency| !project.phases.contains_key(*dependency)) {
+        return Err(PhaseError::UnknownPhase(unknown.clone()).into())
+    }
+
+    let phase = project.phases.get_mut(reference)
+        .ok_or(PhaseError::UnknownPhase(reference.clone()))?;
+
+    if phase.dependencies.eq(&dependencies) {
+        return Ok(false)
+    }
+
+    let original_dependencies = std::mem::replace(&mut phase.dependencies, dependencies);
+
+    match phase::build_execution_plan(project) {
+        Ok(plan) => {
+            info!(target: MUTATION_TARGET, phase = %reference, dependencies = %phase::format_references(&project.phases[reference].dependencies), message = "Phase dependencies set. phase: '{phase}', dependencies: {dependencies}");
+            info!("Execution plan: {}", phase::format_references(&plan));
+            Ok(true)
+        },
+        Err(error) => {
+            project.phases.get_mut(reference).unwrap().dependencies = original_dependencies;
+            Err(error.into())
+        },
+    }
+}
+
+/// Sets the rotation normalization of the artifacts of the phase, `None` removes it, returns true if modified.
+pub fn update_rotation_normalization(project: &mut Project, reference: &Reference, rotation_normalization: Option<RotationNormalization>) -> Result<bool, PhaseError> {
+    let phase = project.phases.get_mut(reference)
+        .ok_or(PhaseError::UnknownPhase(reference.clone()))?;
+
+    if phase.rotation_normalization.eq(&rotation_normalization) {
+        return Ok(false)
+    }
+
+    match &rotation_normalization {
+        Some(rotation_normalization) => info!(target: MUTATION_TARGET, phase = %reference, range = %rotation_normalization.range, offsets = rotation_normalization.offsets.len(),
+            message = "Phase rotation normalization set. phase: '{phase}', range: {range}, offsets: {offsets}",
+        ),
+        None => info!(target: MUTATION_TARGET, phase = %reference, message = "Phase rotation normalization removed. phase: '{phase}'"),
+    }
+    phase.rotation_normalization = rotation_normalization;
+
+    Ok(true)
+}
+
+/// Sets and removes tags of the phase, returns true if the tags were modified.
+pub fn update_phase_tags(project: &mut Project, reference: &Reference, tags: &[PhaseTag], remove: &[String]) -> Result<bool, PhaseError> {
+    let phase = project.phases.get_mut(reference)
+        .ok_or(PhaseError::UnknownPhase(reference.clone()))?;
+
+    let mut modified = false;
+
+    for key in remove.iter() {
+        if phase.tags.remove(key).is_some() {
+            info!(target: MUTATION_TARGET, phase = %reference, key = %key, message = "Phase tag removed. phase: '{phase}', key: '{key}'");
+            modified = true;
+        }
+    }
+
+    for tag in tags.iter() {
+        if phase.tags.get(&tag.key).ne(&Some(&tag.value)) {
+            info!(target: MUTATION_TARGET, phase = %reference, tag = %tag, message = "Phase tag set. phase: '{phase}', tag: '{tag}'");
+            phase.tags.insert(tag.key.clone(), tag.value.clone());
+            modified = true;
+        }
+    }
+
+    Ok(modified)
+}
+
+pub fn reset_operations(project: &mut Project) -> anyhow::Result<()> {
+    
+    reset_placement_operations(project);
+    reset_phase_operations(project);
+    
+    update_phase_operation_states(project);
+
+    info!(target: MUTATION_TARGET, placements = project.placements.len(), phases = project.phase_states.len(), message = "Operations reset. placements: {placements}, phases: {phases}");
+    
+    Ok(())
+}
+
+/// Clones the project for a repeat job.
+///
+/// The PCBs, unit assignments, parts, phases and load-outs are kept, operations, inspection defects, feeder exposures,
+/// issue resolutions, releases and production runs are not.
+pub fn clone_project(project: &Project, name: String) -> Project {
+    let mut cloned_project = project.clone();
+    cloned_project.name = name;
+    cloned_project.releases.clear();
+    cloned_project.production_runs.clear();
+    cloned_project.issue_resolutions.clear();
+
+    for placement_state in cloned_project.placements.values_mut() {
+        placement_state.defects.clear();
+    }
+
+    for phase_state in cloned_project.phase_states.values_mut() {
+        phase_state.feeder_exposures.clear();
+    }
+
+    reset_placement_operations(&mut cloned_project);
+    reset_phase_operations(&mut cloned_project);
+
+    update_phase_operation_states(&mut cloned_project);
+
+    info!(target: MUTATION_TARGET, name = %project.name, new_name = %cloned_project.name, message = "Cloned project. name: '{name}', new_name: '{new_name}'");
+
+    cloned_project
+}
+
+/// Clones a phase, the new phase is added after the other phases and has no placements assigned.
+pub fn clone_phase(project: &mut Project, source_reference: &Reference, reference: Reference, load_out_source: String) -> Result<(), PhaseError> {
+    let source_phase = project.phases.get(source_reference)
+        .ok_or(PhaseError::UnknownPhase(source_reference.clone()))?;
+
+    if project.phases.contains_key(&reference) {
+        return Err(PhaseError::PhaseAlreadyExists(reference))
+    }
+
+    let phase = Phase {
+        reference: reference.clone(),
+        load_out_source,
+        ..source_phase.clone()
+    };
+
+    let phase_state = PhaseState {
+        operation_state: project.phase_states.get(source_reference).unwrap().operation_state.keys()
+            .map(|operation| (operation.clone(), ProcessOperationState::default()))
+            .collect(),
+        feeder_exposures: Default::default(),
+        first_article_inspection: None,
+    };
+
+    info!(target: MUTATION_TARGET, source = %source_reference, reference = %reference, load_out = %phase.load_out_source, message = "Cloned phase. source: '{source}', reference: '{reference}', load_out: {load_out}");
+
+    project.phases.insert(reference.clone(), phase);
+    project.phase_states.insert(reference.clone(), phase_state);
+    project.phase_orderings.insert(reference);
+    info!("Phase ordering: {}", PhaseOrderings(&project.phase_orderings));
+
+    update_phase_operation_states(project);
+
+    Ok(())
+}
+
+fn reset_placement_operations(project: &mut Project) {
+    for (_object_path, placement_state) in project.placements.iter_mut() {
+        placement_state.placed = false;
+    }
+
+    info!("Placement operations reset.");
+}
+
+fn reset_phase_operations(project: &mut Project) {
+    for (reference, phase_state) in project.phase_states.iter_mut() {
+        for (_kind, state) in phase_state.operation_state.iter_mut() {
+            state.status = ProcessOperationStatus::Pending;
+        }
+        phase_state.first_article_inspection = None;
+        info!("Phase operations reset. phase: {}", reference);
+    }
+}
+
+#[cfg(test)]
+mod preview_artifacts {
+    use std::collections::BTreeMap;
+    use std::str::FromStr;
+    use rust_decimal_macros::dec;
+    use pnp::load_out::LoadOutItem;
+    use pnp::part::Part;
+    use pnp::pcb::{PcbKind, PcbSide};
+    use crate::project::{preview_artifacts, ArtifactKind, Project};
+    use crate::reference::Reference;
+    use crate::test::project_builder::{PlacementStateBuilder, ProjectBuilder};
+
+    fn build_project() -> Project {
+        let part = Part::new("MFR1".to_string(), "PART1".to_string());
+
+        ProjectBuilder::new()
+            .with_pcb(PcbKind::Panel, "panel_a")
+            .with_phase("top_1", "pnp", "load_out_1.csv", PcbSide::Top)
+            .with_placements(["R1", "R2"].map(|ref_des| PlacementStateBuilder::new(1, ref_des, &part)
+                .with_position(dec!(10), dec!(20), dec!(90))
+                .with_phase("top_1")
+            ))
+            .build()
+    }
+
+    #[test]
+    pub fn previews_artifacts_with_truncated_content() {
+        // given
+        let project = build_project();
+        let phase_load_out_items_map = BTreeMap::from([
+            (Reference::from_str("top_1").unwrap(), vec![LoadOutItem::new("FEEDER_1".to_string(), "MFR1".to_string(), "PART1".to_string())]),
+        ]);
+
+        // and
+        let expected_placements_content = "\"ObjectPath\",\"FeederReference\",\"Manufacturer\",\"Mpn\",\"X\",\"Y\",\"Rotation\"\n\
+            \"panel=1::unit=1::ref_des=R1\",\"FEEDER_1\",\"MFR1\",\"PART1\",\"10\",\"20\",\"90\"\n";
+
+        // when
+        let result = preview_artifacts(&project, "job1", &phase_load_out_items_map, None, None, 2);
+
+        // then
+        let previews = result.unwrap();
+        assert_eq!(previews.len(), 6);
+
+        // and
+        let placements_preview = &previews[0];
+        assert_eq!(placements_preview.kind, ArtifactKind::PhasePlacements { phase: Reference::from_str("top_1").unwrap() });
+        assert_eq!(placements_preview.file_name, "top_1_placements.csv");
+        assert_eq!(placements_preview.content, expected_placements_content);
+        assert!(placements_preview.truncated);
+
+        // and
+        let work_instructions_preview = &previews[1];
+        assert_eq!(work_instructions_preview.kind, ArtifactKind::WorkInstructions { phase: Reference::from_str("top_1").unwrap() });
+        assert_eq!(work_instructions_preview.file_name, "top_1_work_instructions.md");
+        assert_eq!(work_instructions_preview.content, "# Work instructions - top_1\n\n");
+
+        // and
+        let phase_export_preview = &previews[2];
+        assert_eq!(phase_export_preview.kind, ArtifactKind::PhaseExport { phase: Reference::from_str("top_1").unwrap() });
+        assert_eq!(phase_export_preview.file_name, "top_1_export.json");
+
+        // and
+        let feeder_setup_preview = &previews[3];
+        assert_eq!(feeder_setup_preview.kind, ArtifactKind::FeederSetupSheet { phase: Reference::from_str("top_1").unwrap() });
+        assert_eq!(feeder_setup_preview.file_name, "top_1_feeder_setup.csv");
+        assert_eq!(previews[4].file_name, "top_1_feeder_setup.html");
+
+        // and
+        let report_preview = &previews[5];
+        assert_eq!(report_preview.kind, ArtifactKind::Report);
+        assert_eq!(report_preview.file_name, "job1_report.json");
+        assert_eq!(report_preview.content, "{\n    \"name\": \"job1\",\n");
+        assert!(report_preview.truncated);
+    }
+}
+
+#[cfg(test)]
+mod build_artifacts {
+    use std::collections::BTreeMap;
+    use std::str::FromStr;
+    use indoc::indoc;
+    use rust_decimal_macros::dec;
+    use pnp::load_out::LoadOutItem;
+    use pnp::part::{Part, PartDetails};
+    use pnp::pcb::{PcbKind, PcbSide};
+    use util::sorting::SortOrder;
+    use crate::part::PartState;
+    use crate::placement::{PlacementSortingItem, PlacementSortingMode};
+    use crate::project::{build_artifacts, update_placement_orderings, ArtifactKind};
+    use crate::reference::Reference;
+    use crate::report::render::ReportFormat;
+    use crate::test::project_builder::{PlacementStateBuilder, ProjectBuilder};
+
+    #[test]
+    pub fn orders_feeder_references_deterministically() {
+        // given
+        let mut project = ProjectBuilder::new()
+            .with_pcb(PcbKind::Single, "pcb_a")
+            .with_phase("top_1", "pnp", "load_out_1.csv", PcbSide::Top)
+            .build();
+        let reference = Reference::from_str("top_1").unwrap();
+        update_placement_orderings(&mut project, &reference, &vec![
+            PlacementSortingItem { mode: PlacementSortingMode::FeederReference, sort_order: SortOrder::Asc },
+        ]).unwrap();
+
+        // and
+        project.placements.extend([("R1", "PART1"), ("R2", "PART2"), ("R3", "PART3"), ("R4", "PART4")].map(|(ref_des, mpn)| {
+            PlacementStateBuilder::new(1, ref_des, &Part::new("MFR1".to_string(), mpn.to_string()))
+                .with_unit_path("single=1::unit=1")
+                .with_position(dec!(10), dec!(20), dec!(0))
+                .with_phase("top_1")
+                .build()
+        }));
+
+        // and load-out items in a non-sorted order, as they could be after manual editing
+        let phase_load_out_items_map = BTreeMap::from([
+            (reference.clone(), vec![
+                LoadOutItem::new("FEEDER_10".to_string(), "MFR1".to_string(), "PART1".to_string()),
+                LoadOutItem::new("".to_string(), "MFR1".to_string(), "PART4".to_string()),
+                LoadOutItem::new("FEEDER_2".to_string(), "MFR1".to_string(), "PART3".to_string()),
+                LoadOutItem::new("FEEDER_1".to_string(), "MFR1".to_string(), "PART2".to_string()),
+            ]),
+        ]);
+
+        // and
+        let expected_placements_content = indoc! {r#"
+            "ObjectPath","FeederReference","Manufacturer","Mpn","X","Y","Rotation"
+            "single=1::unit=1::ref_des=R2","FEEDER_1","MFR1","PART2","10","20","0"
+            "single=1::unit=1::ref_des=R3","FEEDER_2","MFR1","PART3","10","20","0"
+            "single=1::unit=1::ref_des=R1","FEEDER_10","MFR1","PART1","10","20","0"
+            "single=1::unit=1::ref_des=R4","","MFR1","PART4","10","20","0"
+        "#};
+
+        // and
+        let expected_load_out_assignments = vec![
+            ("FEEDER_1", "PART2"),
+            ("FEEDER_2", "PART3"),
+            ("FEEDER_10", "PART1"),
+            ("", "PART4"),
+        ];
+
+        // when
+        let artifacts = build_artifacts(&project, "job1", &phase_load_out_items_map, None, None, ReportFormat::Json).unwrap();
+
+        // then
+        assert_eq!(String::from_utf8(artifacts[0].content.clone()).unwrap(), expected_placements_content);
+
+        // and
+        let report_artifact = artifacts.iter().find(|artifact| artifact.kind.eq(&ArtifactKind::Report)).unwrap();
+        let report: serde_json::Value = serde_json::from_slice(&report_artifact.content).unwrap();
+        let load_out_assignments: Vec<(&str, &str)> = report["phase_specifications"][0]["load_out_assignments"].as_array().unwrap().iter()
+            .map(|item| (item["feeder_reference"].as_str().unwrap(), item["mpn"].as_str().unwrap()))
+            .collect();
+        assert_eq!(load_out_assignments, expected_load_out_assignments);
+    }
+
+    #[test]
+    pub fn groups_placements_by_part() {
+        // given
+        let mut project = ProjectBuilder::new()
+            .with_pcb(PcbKind::Panel, "panel_a")
+            .with_phase("top_1", "pnp", "load_out_1.csv", PcbSide::Top)
+            .build();
+        let reference = Reference::from_str("top_1").unwrap();
+        update_placement_orderings(&mut project, &reference, &vec![
+            PlacementSortingItem { mode: PlacementSortingMode::Part, sort_order: SortOrder::Asc },
+            PlacementSortingItem { mode: PlacementSortingMode::PcbUnit, sort_order: SortOrder::Asc },
+        ]).unwrap();
+
+        // and
+        for unit in [1, 2] {
+            project.placements.extend([("R1", "PART2"), ("R2", "PART1")].map(|(ref_des, mpn)| {
+                PlacementStateBuilder::new(unit, ref_des, &Part::new("MFR1".to_string(), mpn.to_string()))
+                    .with_position(dec!(10), dec!(20), dec!(0))
+                    .with_phase("top_1")
+                    .build()
+            }));
+        }
+
+        // and
+        let phase_load_out_items_map = BTreeMap::from([
+            (reference.clone(), vec![
+                LoadOutItem::new("FEEDER_1".to_string(), "MFR1".to_string(), "PART1".to_string()),
+                LoadOutItem::new("FEEDER_2".to_string(), "MFR1".to_string(), "PART2".to_string()),
+            ]),
+        ]);
+
+        // and
+        let expected_placements_content = indoc! {r#"
+            "ObjectPath","FeederReference","Manufacturer","Mpn","X","Y","Rotation"
+            "panel=1::unit=1::ref_des=R2","FEEDER_1","MFR1","PART1","10","20","0"
+            "panel=1::unit=2::ref_des=R2","FEEDER_1","MFR1","PART1","10","20","0"
+            "panel=1::unit=1::ref_des=R1","FEEDER_2","MFR1","PART2","10","20","0"
+            "panel=1::unit=2::ref_des=R1","FEEDER_2","MFR1","PART2","10","20","0"
+        "#};
+
+        // when
+        let artifacts = build_artifacts(&project, "job1", &phase_load_out_items_map, None, None, ReportFormat::Json).unwrap();
+
+        // then
+        assert_eq!(String::from_utf8(artifacts[0].content.clone()).unwrap(), expected_placements_content);
+    }
+
+    #[test]
+    pub fn sorts_placements_using_mixed_orderings() {
+        // given
+        let mut project = ProjectBuilder::new()
+            .with_pcb(PcbKind::Single, "pcb_a")
+            .with_phase("top_1", "pnp", "load_out_1.csv", PcbSide::Top)
+            .build();
+        let reference = Reference::from_str("top_1").unwrap();
+
+        // and 'PART3' has no height
+        for (mpn, height) in [("PART1", Some(dec!(1.0))), ("PART2", Some(dec!(0.5))), ("PART3", None)] {
+            project.part_states.insert(Part::new("MFR1".to_string(), mpn.to_string()), PartState {
+                details: PartDetails { height, ..PartDetails::default() },
+                ..PartState::default()
+            });
+        }
+
+        // and
+        project.placements.extend([("C1", "PART3", dec!(50)), ("C2", "PART2", dec!(40)), ("R1", "PART2", dec!(30)), ("R10", "PART1", dec!(20)), ("R2", "PART1", dec!(10))].map(|(ref_des, mpn, x)| {
+            PlacementStateBuilder::new(1, ref_des, &Part::new("MFR1".to_string(), mpn.to_string()))
+                .with_unit_path("single=1::unit=1")
+                .with_position(x, dec!(20), dec!(0))
+                .with_phase("top_1")
+                .build()
+        }));
+
+        // and
+        let phase_load_out_items_map = BTreeMap::from([
+            (reference.clone(), vec![]),
+        ]);
+
+        // and
+        update_placement_orderings(&mut project, &reference, &vec![
+            PlacementSortingItem { mode: PlacementSortingMode::Height, sort_order: SortOrder::Desc },
+            PlacementSortingItem { mode: PlacementSortingMode::RefDes, sort_order: SortOrder::Asc },
+        ]).unwrap();
+
+        // when
+        let artifacts = build_artifacts(&project, "job1", &phase_load_out_items_map, None, None, ReportFormat::Json).unwrap();
+
+        // then the tallest parts are first, in ref des order, and the part without a height is last
+        assert_eq!(String::from_utf8(artifacts[0].content.clone()).unwrap(), indoc! {r#"
+            "ObjectPath","FeederReference","Manufacturer","Mpn","X","Y","Rotation"
+            "single=1::unit=1::ref_des=R2","","MFR1","PART1","10","20","0"
+            "single=1::unit=1::ref_des=R10","","MFR1","PART1","20","20","0"
+            "single=1::unit=1::ref_des=C2","","MFR1","PART2","40","20","0"
+            "single=1::unit=1::ref_des=R1","","MFR1","PART2","30","20","0"
+            "single=1::unit=1::ref_des=C1","","MFR1","PART3","50","20","0"
+        "#});
+
+        // when
+        update_placement_orderings(&mut project, &reference, &vec![
+            PlacementSortingItem { mode: PlacementSortingMode::DesignY, sort_order: SortOrder::Asc },
+            PlacementSortingItem { mode: PlacementSortingMode::DesignX, sort_order: SortOrder::Desc },
+        ]).unwrap();
+        let artifacts = build_artifacts(&project, "job1", &phase_load_out_items_map, None, None, ReportFormat::Json).unwrap();
+
+        // then the equal Y coordinates fall through to the X coordinates
+        assert_eq!(String::from_utf8(artifacts[0].content.clone()).unwrap(), indoc! {r#"
+            "ObjectPath","FeederReference","Manufacturer","Mpn","X","Y","Rotation"
+            "single=1::unit=1::ref_des=C1","","MFR1","PART3","50","20","0"
+            "single=1::unit=1::ref_des=C2","","MFR1","PART2","40","20","0"
+            "single=1::unit=1::ref_des=R1","","MFR1","PART2","30","20","0"
+            "single=1::unit=1::ref_des=R10","","MFR1","PART1","20","20","0"
+            "single=1::unit=1::ref_des=R2","","MFR1","PART1","10","20","0"
+        "#});
+    }
+}
+
+#[cfg(test)]
+mod refresh_from_design_variants {
+    use std::collections::BTreeMap;
+    use std::str::FromStr;
+    use rust_decimal_macros::dec;
+    use pnp::object_path::{ObjectPath, ObjectPathError};
+    use pnp::part::Part;
+    use pnp::pcb::{PcbKind, PcbSide};
+    use pnp::placement::{Placement, PlacementKind};
+    use crate::design::{DesignName, DesignVariant};
+    use crate::placement::{PlacementConflictKind, PlacementStatus};
+    use crate::progress::{Progress, ProgressReporter, ProgressStage};
+    use crate::project::{refresh_from_design_variants, refresh_from_design_variants_with_progress, PlacementConflictError, Project, RefreshError};
+    use crate::test::project_builder::ProjectBuilder;
+    use crate::variant::VariantName;
+
+    #[derive(Default)]
+    struct RecordingProgressReporter {
+        reports: std::cell::RefCell<Vec<Progress>>,
+    }
+
+    impl ProgressReporter for RecordingProgressReporter {
+        fn report(&self, progress: Progress) {
+            self.reports.borrow_mut().push(progress);
+        }
+    }
+
+    fn build_placement(ref_des: &str) -> Placement {
+        Placement {
+            ref_des: ref_des.to_string(),
+            part: Part::new("RES_MFR1".to_string(), "RES1".to_string()),
+            place: true,
+            pcb_side: PcbSide::Top,
+            x: dec!(10),
+            y: dec!(20),
+            rotation: dec!(0),
+            kind: PlacementKind::Component,
+        }
+    }
+
+    fn build_design_variant(variant_name: &str) -> DesignVariant {
+        DesignVariant { design_name: DesignName::from_str("design_a").unwrap(), variant_name: VariantName::from_str(variant_name).unwrap() }
+    }
+
+    fn status(project: &Project, path: &str) -> (PlacementStatus, bool) {
+        let placement_state = &project.placements[&ObjectPath::from_str(path).unwrap()];
+        (placement_state.status.clone(), placement_state.placed)
+    }
+
+    #[test]
+    pub fn units_sharing_a_design_variant_have_independent_placement_states() {
+        // given a 100-up panel, where 'unit=1' is a prefix of other units, e.g. 'unit=10'
+        let mut project = ProjectBuilder::new()
+            .with_pcb(PcbKind::Panel, "panel_a")
+            .build();
+        for index in 1..=100 {
+            let variant_name = if index == 1 { "variant_a" } else { "variant_b" };
+            project.update_assignment(ObjectPath::from_str(&format!("panel=1::unit={}", index)).unwrap(), build_design_variant(variant_name)).unwrap();
+        }
+
+        // and
+        let design_variant_placement_map = BTreeMap::from([
+            (build_design_variant("variant_a"), vec![build_placement("R1")]),
+            (build_design_variant("variant_b"), vec![build_placement("R1"), build_placement("R2"), build_placement("R3")]),
+        ]);
+        refresh_from_design_variants(&mut project, design_variant_placement_map, false).unwrap();
+
+        // and a placement of one unit is placed
+        project.placements.get_mut(&ObjectPath::from_str("panel=1::unit=10::ref_des=R1").unwrap()).unwrap().placed = true;
+
+        // then
+        assert_eq!(project.placements.len(), 1 + (99 * 3));
+        assert_eq!(status(&project, "panel=1::unit=10::ref_des=R2"), (PlacementStatus::Known, false));
+
+        // when the design variant is changed
+        let mut changed_placement = build_placement("R1");
+        changed_placement.x = dec!(11);
+        let design_variant_placement_map = BTreeMap::from([
+            (build_design_variant("variant_a"), vec![build_placement("R1")]),
+            (build_design_variant("variant_b"), vec![changed_placement, build_placement("R2")]),
+        ]);
+        refresh_from_design_variants(&mut project, design_variant_placement_map, false).unwrap();
+
+        // then the placements of each unit are updated
+        assert_eq!(status(&project, "panel=1::unit=10::ref_des=R1"), (PlacementStatus::Known, true));
+        assert_eq!(status(&project, "panel=1::unit=11::ref_des=R1"), (PlacementStatus::Known, false));
+        assert_eq!(project.placements[&ObjectPath::from_str("panel=1::unit=11::ref_des=R1").unwrap()].placement.x, dec!(11));
+
+        // and the unit with the other design variant is unchanged
+        assert_eq!(status(&project, "panel=1::unit=1::ref_des=R1"), (PlacementStatus::Known, false));
+        assert_eq!(project.placements[&ObjectPath::from_str("panel=1::unit=1::ref_des=R1").unwrap()].placement.x, dec!(10));
+
+        // and the placements of a unit are only compared with the design variant of the unit
+        assert_eq!(status(&project, "panel=1::unit=10::ref_des=R2"), (PlacementStatus::Known, false));
+
+        // and the removed placements are unknown
+        assert_eq!(status(&project, "panel=1::unit=10::ref_des=R3"), (PlacementStatus::Unknown, false));
+        assert_eq!(status(&project, "panel=1::unit=100::ref_des=R3"), (PlacementStatus::Unknown, false));
+    }
+
+    #[test]
+    pub fn progress_is_reported() {
+        // given
+        let mut project = ProjectBuilder::new()
+            .with_pcb(PcbKind::Single, "pcb_a")
+            .build();
+        project.update_assignment(ObjectPath::from_str("panel=1::unit=1").unwrap(), build_design_variant("variant_a")).unwrap();
+
+        // and
+        let design_variant_placement_map = BTreeMap::from([
+            (build_design_variant("variant_a"), vec![build_placement("R1")]),
+        ]);
+        let progress = RecordingProgressReporter::default();
+
+        // when
+        refresh_from_design_variants_with_progress(&mut project, design_variant_placement_map, false, &progress).unwrap();
+
+        // then
+        let stages: Vec<(ProgressStage, u8)> = progress.reports.borrow().iter().map(|progress| (progress.stage, progress.percent)).collect();
+        assert_eq!(stages, vec![
+            (ProgressStage::CheckingPlacementConflicts, 0),
+            (ProgressStage::RefreshingParts, 20),
+            (ProgressStage::RefreshingPlacements, 40),
+            (ProgressStage::Complete, 100),
+        ]);
+    }
+
+    #[test]
+    pub fn conflicting_placements_are_refused_unless_forced() {
+        // given
+        let mut project = ProjectBuilder::new()
+            .with_pcb(PcbKind::Single, "pcb_a")
+            .build();
+        project.update_assignment(ObjectPath::from_str("panel=1::unit=1").unwrap(), build_design_variant("variant_a")).unwrap();
+
+        // and a duplicate, a ref_des collision and a part conflict
+        let mut moved_placement = build_placement("R2");
+        moved_placement.x = dec!(11);
+        let mut other_part_placement = build_placement("R3");
+        other_part_placement.part = Part::new("RES_MFR1".to_string(), "RES2".to_string());
+
+        let design_variant_placement_map = BTreeMap::from([
+            (build_design_variant("variant_a"), vec![
+                build_placement("R1"), build_placement("R1"),
+                build_placement("R2"), moved_placement,
+                build_placement("R3"), other_part_placement,
+            ]),
+        ]);
+
+        // when
+        let result = refresh_from_design_variants(&mut project, design_variant_placement_map.clone(), false);
+
+        // then
+        let Err(RefreshError::PlacementConflicts(PlacementConflictError::Conflicts { conflicts })) = result else {
+            panic!("expected conflicts");
+        };
+        assert_eq!(conflicts, vec![
+            "design_variant: design_a-variant_a, ref_des: R2, kind: RefDesCollision",
+            "design_variant: design_a-variant_a, ref_des: R3, kind: PartConflict, parts: [RES_MFR1:RES1, RES_MFR1:RES2]",
+        ]);
+        assert!(project.placements.is_empty());
+
+        // when
+        refresh_from_design_variants(&mut project, design_variant_placement_map.clone(), true).unwrap();
+
+        // then the last placements are used
+        assert_eq!(project.placements.len(), 3);
+        assert_eq!(project.placements[&ObjectPath::from_str("panel=1::unit=1::ref_des=R2").unwrap()].placement.x, dec!(11));
+        assert_eq!(project.placements[&ObjectPath::from_str("panel=1::unit=1::ref_des=R3").unwrap()].placement.part.mpn, "RES2");
+
+        // and the conflicts are recorded
+        assert_eq!(project.placement_conflicts.len(), 2);
+        assert_eq!(project.placement_conflicts[0].kind, PlacementConflictKind::RefDesCollision);
+
+        // and accepted conflicts are not refused
+        assert!(refresh_from_design_variants(&mut project, design_variant_placement_map, false).is_ok());
+
+        // when the conflicts are fixed
+        let design_variant_placement_map = BTreeMap::from([
+            (build_design_variant("variant_a"), vec![build_placement("R1"), build_placement("R2"), build_placement("R3")]),
+        ]);
+        refresh_from_design_variants(&mut project, design_variant_placement_map, false).unwrap();
+
+        // then
+        assert!(project.placement_conflicts.is_empty());
+    }
+
+    #[test]
+    pub fn invalid_ref_des_is_refused() {
+        // given
+        let mut project = ProjectBuilder::new()
+            .with_pcb(PcbKind::Single, "pcb_a")
+            .build();
+        project.update_assignment(ObjectPath::from_str("panel=1::unit=1").unwrap(), build_design_variant("variant_a")).unwrap();
+
+        // and a ref_des containing a path separator
+        let design_variant_placement_map = BTreeMap::from([
+            (build_design_variant("variant_a"), vec![build_placement("R1"), build_placement("U1:A")]),
+        ]);
+
+        // when
+        let result = refresh_from_design_variants(&mut project, design_variant_placement_map, false);
+
+        // then
+        assert!(matches!(result, Err(RefreshError::InvalidRefDes { error: ObjectPathError::InvalidRefDes(ref_des), .. }) if ref_des == "U1:A"));
+
+        // and the project is unchanged
+        assert!(project.placements.is_empty());
+        assert!(project.part_states.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod assign_placements_to_phase {
+    use std::collections::BTreeSet;
+    use std::str::FromStr;
+    use indoc::indoc;
+    use regex::Regex;
+    use rust_decimal_macros::dec;
+    use pnp::object_path::ObjectPath;
+    use pnp::part::Part;
+    use pnp::pcb::{PcbKind, PcbSide};
+    use pnp::placement::PlacementKind;
+    use crate::project::{add_pcb, assign_placements_to_phase, build_fiducials_artifacts, remove_phase, unassign_placements_from_phase, ArtifactKind, PhaseAssignmentError, PhaseUnassignmentError, Project};
+    use crate::reference::Reference;
+    use crate::test::project_builder::{PlacementStateBuilder, ProjectBuilder};
+
+    fn build_project() -> Project {
+        let part = Part::new("MFR1".to_string(), "PART1".to_string());
+
+        ProjectBuilder::new()
+            .with_phase("top_1", "pnp", "load_out_1.csv", PcbSide::Top)
+            .with_phase("top_2", "pnp", "load_out_1.csv", PcbSide::Top)
+            .with_placements([
+                PlacementStateBuilder::new(1, "R1", &part).with_position(dec!(10), dec!(20), dec!(90)).with_phase("top_1"),
+                PlacementStateBuilder::new(1, "R2", &part).with_position(dec!(10), dec!(20), dec!(90)),
+            ])
+            .build()
+    }
+
+    fn placement_phases(project: &Project) -> Vec<Option<String>> {
+        project.placements.values().map(|state| state.phase.as_ref().map(|phase| phase.to_string())).collect()
+    }
+
+    #[test]
+    pub fn reassignment_requires_allow_reassign() {
+        // given
+        let mut project = build_project();
+        let phase = project.phases.get(&Reference::from_str("top_2").unwrap()).unwrap().clone();
+
+        // when
+        let result = assign_placements_to_phase(&mut project, &phase, Regex::new(".*").unwrap(), false, false);
+
+        // then
+        assert!(matches!(result, Err(PhaseAssignmentError::PlacementsAssignedToOtherPhases { count: 1, .. })));
+
+        // and placements are unchanged
+        assert_eq!(placement_phases(&project), vec![Some("top_1".to_string()), None]);
+    }
+
+    #[test]
+    pub fn reassign_with_allow_reassign() {
+        // given
+        let mut project = build_project();
+        let phase = project.phases.get(&Reference::from_str("top_2").unwrap()).unwrap().clone();
+
+        // when
+        let result = assign_placements_to_phase(&mut project, &phase, Regex::new(".*").unwrap(), true, false);
+
+        // then
+        assert_eq!(result.unwrap().len(), 1);
+
+        // and
+        assert_eq!(placement_phases(&project), vec![Some("top_2".to_string()), Some("top_2".to_string())]);
+    }
+
+    #[test]
+    pub fn side_mismatch_requires_allow_side_mismatch() {
+        // given a placement on the bottom of the PCB
+        let mut project = build_project();
+        let phase = project.phases.get(&Reference::from_str("top_2").unwrap()).unwrap().clone();
+        project.placements.get_mut(&ObjectPath::from_str("panel=1::unit=1::ref_des=R2").unwrap()).unwrap().placement.pcb_side = PcbSide::Bottom;
+
+        // when only placements on the other side are matched
+        let result = assign_placements_to_phase(&mut project, &phase, Regex::new(".*R2").unwrap(), false, false);
+
+        // then
+        assert!(matches!(result, Err(PhaseAssignmentError::PcbSideMismatch { count: 1, .. })));
+        assert_eq!(placement_phases(&project), vec![Some("top_1".to_string()), None]);
+
+        // when other placements are matched too
+        assign_placements_to_phase(&mut project, &phase, Regex::new(".*").unwrap(), true, false).unwrap();
+
+        // then the placement on the other side is skipped
+        assert_eq!(placement_phases(&project), vec![Some("top_2".to_string()), None]);
+
+        // when
+        assign_placements_to_phase(&mut project, &phase, Regex::new(".*R2").unwrap(), false, true).unwrap();
+
+        // then
+        assert_eq!(placement_phases(&project), vec![Some("top_2".to_string()), Some("top_2".to_string())]);
+    }
+
+    #[test]
+    pub fn fiducials_are_not_assigned() {
+        // given
+        let mut project = build_project();
+        add_pcb(&mut project, PcbKind::Panel, "panel_a".to_string()).unwrap();
+        let phase = project.phases.get(&Reference::from_str("top_2").unwrap()).unwrap().clone();
+
+        // and
+        project.placements.extend([
+            PlacementStateBuilder::new(1, "FID1", &Part::new("".to_string(), "".to_string()))
+                .with_place(false)
+                .with_position(dec!(10), dec!(20), dec!(90))
+                .with_kind(PlacementKind::Fiducial)
+                .build(),
+        ]);
+
+        // when
+        assign_placements_to_phase(&mut project, &phase, Regex::new(".*").unwrap(), true, false).unwrap();
+
+        // then
+        assert_eq!(project.placements[&ObjectPath::from_str("panel=1::unit=1::ref_des=FID1").unwrap()].phase, None);
+
+        // when
+        let mut issues = BTreeSet::new();
+        let artifacts = build_fiducials_artifacts(&project, &mut issues).unwrap();
+
+        // then
+        assert_eq!(artifacts.len(), 1);
+        assert_eq!(artifacts[0].kind, ArtifactKind::Fiducials { pcb: "panel_a".to_string() });
+        assert_eq!(artifacts[0].file_name, "panel_a_fiducials.csv");
+        assert_eq!(String::from_utf8(artifacts[0].content.clone()).unwrap(), indoc! {r#"
+            "ObjectPath","PcbSide","X","Y"
+            "panel=1::unit=1::ref_des=FID1","top","10","20"
+        "#});
+    }
+
+    #[test]
+    pub fn unassign_placed_placements() {
+        // given
+        let mut project = build_project();
+        project.placements.values_mut().next().unwrap().placed = true;
+
+        // when
+        let result = unassign_placements_from_phase(&mut project, &Reference::from_str("top_1").unwrap(), &Regex::new(".*").unwrap());
+
+        // then
+        assert!(matches!(result, Err(PhaseUnassignmentError::PlacedPlacements { count: 1, .. })));
+
+        // and placements are unchanged
+        assert_eq!(placement_phases(&project), vec![Some("top_1".to_string()), None]);
+    }
+
+    #[test]
+    pub fn remove() {
+        // given
+        let mut project = build_project();
+        let reference = Reference::from_str("top_1").unwrap();
+
+        // when
+        let parts = remove_phase(&mut project, &reference).unwrap();
+
+        // then
+        assert_eq!(parts.into_iter().collect::<Vec<_>>(), vec![Part::new("MFR1".to_string(), "PART1".to_string())]);
+
+        // and
+        assert_eq!(placement_phases(&project), vec![None, None]);
+        assert_eq!(project.phases.keys().map(Reference::to_string).collect::<Vec<_>>(), vec!["top_2"]);
+        assert_eq!(project.phase_orderings.iter().map(Reference::to_string).collect::<Vec<_>>(), vec!["top_2"]);
+        assert!(!project.phase_states.contains_key(&reference));
+
+        // and the phase cannot be removed again
+        assert!(remove_phase(&mut project, &reference).is_err());
+    }
+}
+
+#[cfg(test)]
+mod operation_transitions {
+    use std::str::FromStr;
+    use regex::Regex;
+    use rust_decimal_macros::dec;
+    use pnp::object_path::ObjectPath;
+    use pnp::part::Part;
+    use pnp::pcb::PcbSide;
+    use crate::operation_history;
+    use crate::operation_history::OperationHistoryKind;
+    use crate::placement::PlacementOperation;
+    use crate::process::{OperationTransitions, ProcessOperationKind, ProcessOperationStatus};
+    use crate::project::{update_phase_operation_states, update_placements_operation, Project};
+    use crate::reference::Reference;
+    use crate::test::project_builder::{PlacementStateBuilder, ProjectBuilder};
+
+    fn build_project(operation_transitions: OperationTransitions) -> Project {
+        let mut project = ProjectBuilder::new()
+            .with_phase("top_1", "pnp", "load_out_1.csv", PcbSide::Top)
+            .with_placements([
+                PlacementStateBuilder::new(1, "R1", &Part::new("MFR1".to_string(), "PART1".to_string()))
+                    .with_position(dec!(10), dec!(20), dec!(90))
+                    .with_phase("top_1"),
+            ])
+            .build();
+        project.operation_transitions = operation_transitions;
+
+        update_phase_operation_states(&mut project);
+
+        project
+    }
+
+    fn automated_pnp_status(project: &Project) -> ProcessOperationStatus {
+        let phase_state = project.phase_states.get(&Reference::from_str("top_1").unwrap()).unwrap();
+        phase_state.operation_state.get(&ProcessOperationKind::AutomatedPnp).unwrap().status.clone()
+    }
+
+    #[test]
+    pub fn complete_and_reopen_automatically() {
+        // given
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().to_path_buf();
+        let mut project = build_project(OperationTransitions::Automatic);
+
+        // when
+        update_placements_operation(&mut project, &path, vec![Regex::new(".*").unwrap().into()], PlacementOperation::Placed).unwrap();
+
+        // then
+        assert_eq!(automated_pnp_status(&project), ProcessOperationStatus::Complete);
+
+        // when
+        update_placements_operation(&mut project, &path, vec![Regex::new(".*").unwrap().into()], PlacementOperation::Unplaced).unwrap();
+
+        // then
+        assert_eq!(automated_pnp_status(&project), ProcessOperationStatus::Pending);
+
+        // and
+        let operation_history = operation_history::read_or_default(&path.join("top_1_log.json")).unwrap();
+        let operations: Vec<OperationHistoryKind> = operation_history.into_iter().map(|item| item.operation).collect();
+        assert_eq!(operations, vec![
+            OperationHistoryKind::PlacementOperation { object_path: ObjectPath::from_str("panel=1::unit=1::ref_des=R1").unwrap(), operation: PlacementOperation::Placed },
+            OperationHistoryKind::AutomatedPnp { status: ProcessOperationStatus::Complete },
+            OperationHistoryKind::PlacementOperation { object_path: ObjectPath::from_str("panel=1::unit=1::ref_des=R1").unwrap(), operation: PlacementOperation::Unplaced },
+            OperationHistoryKind::AutomatedPnp { status: ProcessOperationStatus::Pending },
+        ]);
+    }
+
+    #[test]
+    pub fn manual_transitions_do_not_change_status() {
+        // given
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().to_path_buf();
+        let mut project = build_project(OperationTransitions::Manual);
+
+        // when
+        update_placements_operation(&mut project, &path, vec![Regex::new(".*").unwrap().into()], PlacementOperation::Placed).unwrap();
+
+        // then
+        assert_eq!(automated_pnp_status(&project), ProcessOperationStatus::Pending);
+    }
+}
+
+#[cfg(test)]
+mod rename_part {
+    use std::str::FromStr;
+    use rust_decimal_macros::dec;
+    use pnp::object_path::ObjectPath;
+    use pnp::part::Part;
+    use crate::part::PartState;
+    use crate::project::{rename_part, PartRenameError, Project};
+    use crate::test::project_builder::{PlacementStateBuilder, ProjectBuilder};
+
+    fn build_project() -> Project {
+        let placements = [("R1", "RES1"), ("R2", "RES2"), ("R3", "RES1")]
+            .map(|(ref_des, mpn)| (ref_des, Part::new("RES_MFR1".to_string(), mpn.to_string())));
+
+        let mut project = ProjectBuilder::new()
+            .with_placements(placements.iter().map(|(ref_des, part)| PlacementStateBuilder::new(1, ref_des, part).with_position(dec!(10), dec!(20), dec!(90))))
+            .build();
+        for (_ref_des, part) in placements {
+            project.part_states.insert(part, PartState::default());
+        }
+
+        project
+    }
+
+    #[test]
+    pub fn rename() {
+        // given
+        let mut project = build_project();
+        let from = Part::new("RES_MFR1".to_string(), "RES1".to_string());
+        let to = Part::new("RES_MFR2".to_string(), "RES1A".to_string());
+
+        // when
+        let result = rename_part(&mut project, &from, &to);
+
+        // then
+        assert_eq!(result.unwrap(), vec![
+            ObjectPath::from_str("panel=1::unit=1::ref_des=R1").unwrap(),
+            ObjectPath::from_str("panel=1::unit=1::ref_des=R3").unwrap(),
+        ]);
+
+        // and
+        assert!(!project.part_states.contains_key(&from));
+        assert!(project.part_states.contains_key(&to));
+
+        // and
+        let mpns: Vec<&str> = project.placements.values().map(|state| state.placement.part.mpn.as_str()).collect();
+        assert_eq!(mpns, vec!["RES1A", "RES2", "RES1A"]);
+    }
+
+    #[test]
+    pub fn rename_to_existing_part() {
+        // given
+        let mut project = build_project();
+        let from = Part::new("RES_MFR1".to_string(), "RES1".to_string());
+        let to = Part::new("RES_MFR1".to_string(), "RES2".to_string());
+
+        // when
+        let result = rename_part(&mut project, &from, &to);
+
+        // then
+        assert!(matches!(result, Err(PartRenameError::PartAlreadyExists { .. })));
+
+        // and
+        assert_eq!(project.part_states.len(), 2);
+    }
+
+    #[test]
+    pub fn rename_unknown_part() {
+        // given
+        let mut project = build_project();
+        let from = Part::new("RES_MFR1".to_string(), "RES9".to_string());
+        let to = Part::new("RES_MFR2".to_string(), "RES9A".to_string());
+
+        // when
+        let result = rename_part(&mut project, &from, &to);
+
+        // then
+        assert!(matches!(result, Err(PartRenameError::UnknownPart { .. })));
+    }
+}
+
+#[cfg(test)]
+mod clone {
+    use std::str::FromStr;
+    use rust_decimal_macros::dec;
+    use time::OffsetDateTime;
+    use pnp::part::Part;
+    use pnp::pcb::PcbSide;
+    use crate::issue::{IssueResolution, IssueResolutionStatus};
+    use crate::phase::{FeederExposure, PhaseError};
+    use crate::placement::{PlacementDefect, PlacementDefectStatus};
+    use crate::process::{ProcessName, ProcessOperationKind, ProcessOperationStatus};
+    use crate::project::{clone_phase, clone_project, rename_phase, update_phase_operation_states, update_phase_orderings, Project};
+    use crate::reference::Reference;
+    use crate::test::project_builder::{PlacementStateBuilder, ProjectBuilder};
+
+    fn build_project() -> Project {
+        let mut project = ProjectBuilder::new()
+            .with_phase("top_1", "pnp", "load_out_1.csv", PcbSide::Top)
+            .with_placements([
+                PlacementStateBuilder::new(1, "R1", &Part::new("MFR1".to_string(), "PART1".to_string()))
+                    .with_position(dec!(10), dec!(20), dec!(90))
+                    .with_placed(true)
+                    .with_phase("top_1")
+                    .with_defects(vec![PlacementDefect {
+                        date_time: OffsetDateTime::now_utc(),
+                        phase: Reference::from_str("top_1").unwrap(),
+                        status: PlacementDefectStatus::Open,
+                        rework_phase: None,
+                    }]),
+            ])
+            .build();
+
+        let _modified = update_phase_operation_states(&mut project);
+
+        project
+    }
+
+    #[test]
+    pub fn clone_project_without_operations() {
+        // given
+        let mut project = build_project();
+        let reference = Reference::from_str("top_1").unwrap();
+
+        // and
+        project.phase_states.get_mut(&reference).unwrap().feeder_exposures.insert("FEEDER_1".to_string(), FeederExposure {
+            part: Part::new("RES_MFR1".to_string(), "RES1".to_string()),
+            loaded_at: OffsetDateTime::now_utc(),
+        });
+        project.issue_resolutions.insert("issue1".to_string(), IssueResolution {
+            status: IssueResolutionStatus::Waived,
+            reason: "Reason 1".to_string(),
+            operator: None,
+            resolved_at: OffsetDateTime::now_utc(),
+        });
+
+        // when
+        let cloned_project = clone_project(&project, "job2".to_string());
+
+        // then
+        assert_eq!(cloned_project.name, "job2");
+        assert_eq!(cloned_project.phases, project.phases);
+
+        // and
+        let placement_state = cloned_project.placements.values().next().unwrap();
+        assert!(!placement_state.placed);
+        assert!(placement_state.defects.is_empty());
+
+        // and
+        let phase_state = cloned_project.phase_states.get(&reference).unwrap();
+        assert_eq!(phase_state.operation_state.get(&ProcessOperationKind::AutomatedPnp).unwrap().status, ProcessOperationStatus::Pending);
+        assert!(phase_state.feeder_exposures.is_empty());
+
+        // and
+        assert!(cloned_project.issue_resolutions.is_empty());
+
+        // and the original is unchanged
+        assert!(project.placements.values().next().unwrap().placed);
+        assert_eq!(project.phase_states.get(&reference).unwrap().feeder_exposures.len(), 1);
+        assert_eq!(project.issue_resolutions.len(), 1);
+    }
+
+    #[test]
+    pub fn clone_phase_with_new_reference() {
+        // given
+        let mut project = build_project();
+
+        // when
+        let result = clone_phase(&mut project, &Reference::from_str("top_1").unwrap(), Reference::from_str("top_2").unwrap(), "load_out_2.csv".to_string());
+
+        // then
+        assert!(result.is_ok());
+
+        // and
+        let phase = project.phases.get(&Reference::from_str("top_2").unwrap()).unwrap();
+        assert_eq!(phase.process, ProcessName::from_str("pnp").unwrap());
+        assert_eq!(phase.load_out_source, "load_out_2.csv");
+        assert_eq!(phase.pcb_side, PcbSide::Top);
+
+        // and
+        assert_eq!(project.phase_orderings.iter().map(Reference::to_string).collect::<Vec<_>>(), vec!["top_1", "top_2"]);
+        let operations = |reference: &str| project.phase_states.get(&Reference::from_str(reference).unwrap()).unwrap().operation_state.keys().cloned().collect::<Vec<_>>();
+        assert_eq!(operations("top_2"), operations("top_1"));
+    }
+
+    #[test]
+    pub fn clone_phase_to_existing_reference() {
+        // given
+        let mut project = build_project();
+
+        // when
+        let result = clone_phase(&mut project, &Reference::from_str("top_1").unwrap(), Reference::from_str("top_1").unwrap(), "load_out_2.csv".to_string());
+
+        // then
+        assert!(matches!(result, Err(PhaseError::PhaseAlreadyExists(_))));
+    }
+
+    #[test]
+    pub fn rename_phase_with_placements_and_dependencies() {
+        // given
+        let mut project = build_project();
+        let top_1 = Reference::from_str("top_1").unwrap();
+        let top_a = Reference::from_str("top_a").unwrap();
+
+        // and
+        clone_phase(&mut project, &top_1, Reference::from_str("top_2").unwrap(), "load_out_2.csv".to_string()).unwrap();
+        project.phases.get_mut(&Reference::from_str("top_2").unwrap()).unwrap().dependencies.insert(top_1.clone());
+
+        // when
+        rename_phase(&mut project, &top_1, top_a.clone()).unwrap();
 
-                        let phase = placement_state.phase.as_ref().unwrap();
+        // then
+        assert_eq!(project.phases.get(&top_a).unwrap().reference, top_a);
+        assert!(!project.phases.contains_key(&top_1));
+        assert!(project.phase_states.contains_key(&top_a));
+        assert_eq!(project.phase_orderings.iter().map(Reference::to_string).collect::<Vec<_>>(), vec!["top_a", "top_2"]);
 
-                        let history_item = OperationHistoryItem {
-                            date_time: now,
-                            phase: phase.clone(),
-                            operation: OperationHistoryKind::PlacementOperation { object_path: object_path.clone(), operation: operation.clone() },
-                            extra: Default::default(),
-                        };
+        // and
+        assert!(project.phases.get(&Reference::from_str("top_2").unwrap()).unwrap().dependencies.contains(&top_a));
 
-                        let history_items = history_item_map.entry(phase.clone())
-                            .or_default();
+        // and
+        let placement_state = project.placements.values().next().unwrap();
+        assert_eq!(placement_state.phase, Some(top_a.clone()));
+        assert_eq!(placement_state.defects[0].phase, top_a);
+    }
 
-                        history_items.push(history_item);
+    #[test]
+    pub fn rename_phase_to_existing_reference() {
+        // given
+        let mut project = build_project();
+        let top_1 = Reference::from_str("top_1").unwrap();
+        clone_phase(&mut project, &top_1, Reference::from_str("top_2").unwrap(), "load_out_2.csv".to_string()).unwrap();
 
-                        modified = true;
-                    }
-                }
-            }
-        }
+        // when
+        let result = rename_phase(&mut project, &top_1, Reference::from_str("top_2").unwrap());
+
+        // then
+        assert!(matches!(result, Err(PhaseError::PhaseAlreadyExists(_))));
     }
 
-    if modified {
-        update_phase_operation_states(project);
+    #[test]
+    pub fn reorder_phases() {
+        // given
+        let mut project = build_project();
+        let top_1 = Reference::from_str("top_1").unwrap();
+        let top_2 = Reference::from_str("top_2").unwrap();
+        clone_phase(&mut project, &top_1, top_2.clone(), "load_out_2.csv".to_string()).unwrap();
 
-        for (phase_reference, history_items) in history_item_map {
-            let mut phase_log_path = path.clone();
-            phase_log_path.push(format!("{}_log.json", phase_reference));
+        // when
+        let result = update_phase_orderings(&mut project, &[top_2.clone(), top_1.clone()]);
 
-            let mut operation_history: Vec<OperationHistoryItem> = operation_history::read_or_default(&phase_log_path)?;
-            
-            operation_history.extend(history_items);
-            
-            operation_history::write(phase_log_path, &operation_history)?;
-        }
-    }
-    
-    Ok(modified)
-}
+        // then
+        assert!(matches!(result, Ok(true)));
+        assert_eq!(project.phase_orderings.iter().map(Reference::to_string).collect::<Vec<_>>(), vec!["top_2", "top_1"]);
 
-pub fn update_phase_operation_states(project: &mut Project) -> bool {
-    let mut modified = false;
+        // expect the same ordering to be unmodified
+        assert!(matches!(update_phase_orderings(&mut project, &[top_2.clone(), top_1.clone()]), Ok(false)));
 
-    for (reference, phase_state) in project.phase_states.iter_mut() {
-        trace!("reference: {:?}, phase_state: {:?}", reference, phase_state);
+        // expect missing and duplicated phases to be rejected
+        assert!(matches!(update_phase_orderings(&mut project, std::slice::from_ref(&top_2)), Err(PhaseError::InvalidPhaseOrdering(_))));
+        assert!(matches!(update_phase_orderings(&mut project, &[top_2.clone(), top_2.clone()]), Err(PhaseError::InvalidPhaseOrdering(_))));
 
-        for (operation, operation_state) in phase_state.operation_state.iter_mut() {
-            trace!("operation: {:?}, operation_state: {:?}", operation, operation_state);
+        // and unknown phases
+        assert!(matches!(update_phase_orderings(&mut project, &[top_2.clone(), Reference::from_str("top_3").unwrap()]), Err(PhaseError::UnknownPhase(_))));
+    }
+}
 
-            let maybe_state = if operation.eq(&ProcessOperationKind::AutomatedPnp) || operation.eq(&ProcessOperationKind::ManuallySolderComponents) {
-                let placements_state = project.placements.iter()
-                    .fold(PlacementsState::default(), |mut state, (_object_path, placement_status)| {
-                        if let Some(placement_phase) = &placement_status.phase {
-                            if placement_phase.eq(reference) {
-                                if placement_status.placed {
-                                    state.placed += 1;
-                                }
-                                state.total += 1;
-                            }
-                        }
+#[cfg(test)]
+mod load_and_save {
+    use std::str::FromStr;
+    use tempfile::tempdir;
+    use pnp::object_path::ObjectPath;
+    use crate::design::{DesignName, DesignVariant};
+    use crate::variant::VariantName;
+    use crate::locking::{lock, LockMode, ProjectLockedError};
+    use crate::project::{build_plain_project_file_path, load, save, Project};
+
+    #[test]
+    pub fn save_replaces_the_project_file() -> anyhow::Result<()> {
+        // given
+        let temp_dir = tempdir()?;
+        let project_file_path = build_plain_project_file_path("job1", temp_dir.path());
+        save(&Project::new("job1".to_string()), &project_file_path)?;
 
-                        state
-                    });
+        // when
+        save(&Project::new("job2".to_string()), &project_file_path)?;
 
-                let status = if placements_state.total == 0 || placements_state.placed == 0 {
-                    ProcessOperationStatus::Pending
-                } else if placements_state.are_all_placements_placed() {
-                    ProcessOperationStatus::Complete
-                } else {
-                    ProcessOperationStatus::Incomplete
-                };
+        // then
+        assert_eq!(load(&project_file_path)?.name, "job2");
 
-                Some((placements_state, status))
-            } else {
-                None
-            };
-            trace!("maybe_state: {:?}", maybe_state);
+        // and the temporary file was renamed
+        let mut file_names: Vec<String> = std::fs::read_dir(temp_dir.path())?
+            .map(|entry| entry.unwrap().file_name().to_str().unwrap().to_string())
+            .collect();
+        file_names.sort();
+        assert_eq!(file_names, vec!["project-job1.lock", "project-job1.mpnp.json"]);
 
+        Ok(())
+    }
 
-            let original_operation_state = operation_state.clone();
+    #[test]
+    pub fn save_fails_while_the_project_is_locked() -> anyhow::Result<()> {
+        // given
+        let temp_dir = tempdir()?;
+        let project_file_path = build_plain_project_file_path("job1", temp_dir.path());
+        save(&Project::new("job1".to_string()), &project_file_path)?;
 
-            match (&maybe_state, operation) {
-                (Some((placements_state, status)), ProcessOperationKind::AutomatedPnp) => {
-                    operation_state.status = status.clone();
-                    operation_state.extra = Some(ProcessOperationExtraState::PlacementOperation { placements_state: placements_state.clone() });
-                }
-                (Some((placements_state, status)), ProcessOperationKind::ManuallySolderComponents) => {
-                    operation_state.status = status.clone();
-                    operation_state.extra = Some(ProcessOperationExtraState::PlacementOperation { placements_state: placements_state.clone() });
-                },
-                (_, _) => {}
-            };
+        // and another process is reading the project
+        let _lock = lock(&project_file_path, LockMode::Shared)?;
 
-            let phase_operation_modified = !original_operation_state.eq(operation_state);
+        // when
+        let result = save(&Project::new("job2".to_string()), &project_file_path);
 
-            if phase_operation_modified {
-                info!("Updating phase status. phase: {}", reference);
+        // then
+        assert!(result.unwrap_err().downcast::<ProjectLockedError>().is_ok());
 
-                if let Some((_maybe_state, status)) = maybe_state {
-                    match status {
-                        ProcessOperationStatus::Complete => info!("Phase operation complete. phase: {}, operation: {:?}", reference, operation),
-                        ProcessOperationStatus::Incomplete => info!("Phase operation incomplete. phase: {}, operation: {:?}", reference, operation),
-                        ProcessOperationStatus::Pending => info!("Phase operation pending. phase: {}, operation: {:?}", reference, operation),
-                    }
-                }
-            }
+        // and the project can still be read
+        assert_eq!(load(&project_file_path)?.name, "job1");
 
-            modified |= phase_operation_modified;
-        }
+        Ok(())
     }
 
-    modified
-}
+    /// Earlier versions accepted object paths with a '0' index, which are now invalid, such project files have to be
+    /// corrected by hand.
+    #[test]
+    pub fn load_fails_for_invalid_object_paths() -> anyhow::Result<()> {
+        // given
+        let temp_dir = tempdir()?;
+        let project_file_path = build_plain_project_file_path("job1", temp_dir.path());
 
-#[derive(Error, Debug)]
-pub enum PartStateError {
-    #[error("No part state found. manufacturer: {}, mpn: {}", part.manufacturer, part.mpn)]
-    NoPartStateFound { part: Part }
-}
+        // and
+        let mut project = Project::new("job1".to_string());
+        project.unit_assignments.insert(ObjectPath::from_str("panel=1::unit=1")?, DesignVariant {
+            design_name: DesignName::from_str("design_a")?,
+            variant_name: VariantName::from_str("variant_a")?,
+        });
+        save(&project, &project_file_path)?;
 
-pub fn update_phase_operation(project: &mut Project, path: &PathBuf, phase_reference: &Reference, operation: ProcessOperationKind, set_item: ProcessOperationSetItem) -> anyhow::Result<bool> {
+        // and
+        let content = std::fs::read_to_string(&project_file_path)?;
+        std::fs::write(&project_file_path, content.replace("panel=1::unit=1", "panel=0::unit=1"))?;
 
-    let phase_state = project.phase_states.get_mut(phase_reference)
-        .ok_or(PhaseError::UnknownPhase(phase_reference.clone()))?;
+        // when
+        let result = load(&project_file_path);
 
-    let mut modified = false;
+        // then
+        let error = result.unwrap_err().to_string();
+        assert!(error.contains("Invalid index in path. index: '0'"), "error: {}", error);
 
-    let state = phase_state.operation_state.get_mut(&operation)
-        .ok_or(PhaseError::InvalidOperationForPhase(phase_reference.clone(), operation.clone()))?;
+        Ok(())
+    }
+}
 
-    match set_item {
-        ProcessOperationSetItem::Completed => {
-            if state.status.ne(&ProcessOperationStatus::Complete) {
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+pub enum ProjectDiffKind {
+    Added,
+    Removed,
+    Changed,
+}
 
-                state.status = ProcessOperationStatus::Complete;
-                modified = true;
-            }
+impl Display for ProjectDiffKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProjectDiffKind::Added => write!(f, "added"),
+            ProjectDiffKind::Removed => write!(f, "removed"),
+            ProjectDiffKind::Changed => write!(f, "changed"),
         }
     }
+}
 
-    if modified {
-        let history_operation = build_history_operation_kind(&operation, state);
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub struct ProjectDiffItem {
+    pub key: String,
+    pub kind: ProjectDiffKind,
+    /// The changed fields, nested fields are separated by a '.' (e.g. 'placement.rotation'), empty unless changed.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<String>,
+}
 
-        let now = OffsetDateTime::now_utc();
+/// The structural differences between two projects, in key order.
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize)]
+pub struct ProjectDiff {
+    pub placements: Vec<ProjectDiffItem>,
+    pub part_states: Vec<ProjectDiffItem>,
+    pub phases: Vec<ProjectDiffItem>,
+}
 
-        let history_item = OperationHistoryItem {
-            date_time: now,
-            phase: phase_reference.clone(),
-            operation: history_operation,
-            extra: Default::default(),
-        };
+impl ProjectDiff {
+    pub fn is_empty(&self) -> bool {
+        self.placements.is_empty() && self.part_states.is_empty() && self.phases.is_empty()
+    }
+}
 
-        let mut phase_log_path = path.clone();
-        phase_log_path.push(format!("{}_log.json", phase_reference));
+impl Display for ProjectDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (name, items) in [("placement", &self.placements), ("part", &self.part_states), ("phase", &self.phases)] {
+            for item in items.iter() {
+                write!(f, "{} '{}' {}", name, item.key, item.kind)?;
+                if !item.fields.is_empty() {
+                    write!(f, ", fields: {}", item.fields.join(", "))?;
+                }
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
 
-        let mut operation_history: Vec<OperationHistoryItem> = operation_history::read_or_default(&phase_log_path)?;
+/// Diffs the placements, part states and phases of a previous snapshot of a project against the project.
+pub fn build_project_diff(before: &Project, after: &Project) -> anyhow::Result<ProjectDiff> {
+    Ok(ProjectDiff {
+        placements: diff_map(&before.placements, &after.placements)?,
+        part_states: diff_map(&before.part_states, &after.part_states)?,
+        phases: diff_map(&before.phases, &after.phases)?,
+    })
+}
 
-        operation_history.push(history_item);
-        
-        operation_history::write(phase_log_path, &operation_history)?;
+fn diff_map<K: Ord + Display, V: Serialize>(before: &BTreeMap<K, V>, after: &BTreeMap<K, V>) -> anyhow::Result<Vec<ProjectDiffItem>> {
+    let keys: BTreeSet<&K> = before.keys().chain(after.keys()).collect();
+
+    let mut items = vec![];
+    for key in keys {
+        let (kind, fields) = match (before.get(key), after.get(key)) {
+            (None, Some(_)) => (ProjectDiffKind::Added, vec![]),
+            (Some(_), None) => (ProjectDiffKind::Removed, vec![]),
+            (Some(before_value), Some(after_value)) => {
+                let fields = find_changed_fields(&serde_json::to_value(before_value)?, &serde_json::to_value(after_value)?, "");
+                if fields.is_empty() {
+                    continue
+                }
+                (ProjectDiffKind::Changed, fields)
+            },
+            (None, None) => continue,
+        };
+        items.push(ProjectDiffItem { key: key.to_string(), kind, fields });
     }
 
-    Ok(modified)
+    Ok(items)
 }
 
-fn build_history_operation_kind(operation: &ProcessOperationKind, state: &ProcessOperationState) -> OperationHistoryKind {
-    match operation {
-        ProcessOperationKind::LoadPcbs => OperationHistoryKind::LoadPcbs { status: state.status.clone() },
-        ProcessOperationKind::AutomatedPnp => OperationHistoryKind::AutomatedPnp { status: state.status.clone() },
-        ProcessOperationKind::ReflowComponents => OperationHistoryKind::ReflowComponents { status: state.status.clone() },
-        ProcessOperationKind::ManuallySolderComponents => OperationHistoryKind::ManuallySolderComponents { status: state.status.clone() },
+fn find_changed_fields(before: &serde_json::Value, after: &serde_json::Value, prefix: &str) -> Vec<String> {
+    use serde_json::Value;
+
+    match (before, after) {
+        (Value::Object(before_fields), Value::Object(after_fields)) => {
+            let keys: BTreeSet<&String> = before_fields.keys().chain(after_fields.keys()).collect();
+
+            keys.into_iter().flat_map(|key| {
+                let field = match prefix.is_empty() {
+                    true => key.clone(),
+                    false => format!("{}.{}", prefix, key),
+                };
+                match (before_fields.get(key), after_fields.get(key)) {
+                    (Some(before_value), Some(after_value)) => find_changed_fields(before_value, after_value, &field),
+                    _ => vec![field],
+                }
+            }).collect()
+        },
+        _ if before.ne(after) => vec![prefix.to_string()],
+        _ => vec![],
     }
 }
 
 #[cfg(test)]
-mod build_history_operation_kind {
-    use rstest::rstest;
-    use crate::operation_history::OperationHistoryKind;
-    use crate::process::{ProcessOperationKind, ProcessOperationState, ProcessOperationStatus};
-    use crate::project::build_history_operation_kind;
-
-    #[rstest]
-    #[case(ProcessOperationStatus::Pending)]
-    #[case(ProcessOperationStatus::Incomplete)]
-    #[case(ProcessOperationStatus::Complete)]
-    pub fn for_load_pcbs(#[case] status: ProcessOperationStatus) {
-        // given
-        let state = ProcessOperationState { status: status.clone(), extra: None };
-        
-        // and
-        let expected_result: OperationHistoryKind = OperationHistoryKind::LoadPcbs { status: status.clone() }; 
-        
-        // when
-        let result = build_history_operation_kind(&ProcessOperationKind::LoadPcbs, &state);
-        
-        // then
-        assert_eq!(result, expected_result)
+mod project_diff {
+    use std::str::FromStr;
+    use indoc::indoc;
+    use rust_decimal_macros::dec;
+    use pnp::object_path::ObjectPath;
+    use pnp::part::Part;
+    use pnp::pcb::PcbSide;
+    use crate::part::PartState;
+    use crate::process::ProcessName;
+    use crate::project::{build_project_diff, Project, ProjectDiffItem, ProjectDiffKind};
+    use crate::reference::Reference;
+    use crate::test::project_builder::{PlacementStateBuilder, ProjectBuilder};
+
+    fn build_project() -> Project {
+        let part = Part::new("RES_MFR1".to_string(), "RES1".to_string());
+
+        let mut project = ProjectBuilder::new()
+            .with_phase("top_1", "pnp", "load_out_1.csv", PcbSide::Top)
+            .with_placements(["R1", "R2"].map(|ref_des| PlacementStateBuilder::new(1, ref_des, &part).with_position(dec!(10), dec!(20), dec!(0))))
+            .build();
+        project.part_states.insert(part, PartState::default());
+
+        project
     }
 
-    #[rstest]
-    #[case(ProcessOperationStatus::Pending)]
-    #[case(ProcessOperationStatus::Incomplete)]
-    #[case(ProcessOperationStatus::Complete)]
-    pub fn for_automated_pnp(#[case] status: ProcessOperationStatus) {
+    #[test]
+    pub fn diff() -> anyhow::Result<()> {
         // given
-        let state = ProcessOperationState { status: status.clone(), extra: None };
+        let before = build_project();
+        let mut after = before.clone();
 
         // and
-        let expected_result: OperationHistoryKind = OperationHistoryKind::AutomatedPnp { status: status.clone() };
+        let placement_state = after.placements.get_mut(&ObjectPath::from_str("panel=1::unit=1::ref_des=R1")?).unwrap();
+        placement_state.placed = true;
+        placement_state.placement.rotation = dec!(90);
+        after.placements.remove(&ObjectPath::from_str("panel=1::unit=1::ref_des=R2")?);
+        after.part_states.insert(Part::new("CAP_MFR1".to_string(), "CAP1".to_string()), PartState::default());
+        after.update_phase(Reference::from_str("bottom_1")?, ProcessName::from_str("manual")?, "load_out_2.csv".to_string(), PcbSide::Bottom)?;
 
         // when
-        let result = build_history_operation_kind(&ProcessOperationKind::AutomatedPnp, &state);
+        let diff = build_project_diff(&before, &after)?;
 
         // then
-        assert_eq!(result, expected_result)
-    }
-
-    #[rstest]
-    #[case(ProcessOperationStatus::Pending)]
-    #[case(ProcessOperationStatus::Incomplete)]
-    #[case(ProcessOperationStatus::Complete)]
-    pub fn for_manually_solder_components(#[case] status: ProcessOperationStatus) {
-        // given
-        let state = ProcessOperationState { status: status.clone(), extra: None };
+        assert_eq!(diff.placements, vec![
+            ProjectDiffItem { key: "panel=1::unit=1::ref_des=R1".to_string(), kind: ProjectDiffKind::Changed, fields: vec!["placed".to_string(), "placement.rotation".to_string()] },
+            ProjectDiffItem { key: "panel=1::unit=1::ref_des=R2".to_string(), kind: ProjectDiffKind::Removed, fields: vec![] },
+        ]);
 
         // and
-        let expected_result: OperationHistoryKind = OperationHistoryKind::ManuallySolderComponents { status: status.clone() };
+        assert_eq!(diff.to_string(), indoc! {"
+            placement 'panel=1::unit=1::ref_des=R1' changed, fields: placed, placement.rotation
+            placement 'panel=1::unit=1::ref_des=R2' removed
+            part 'CAP_MFR1:CAP1' added
+            phase 'bottom_1' added
+        "});
 
-        // when
-        let result = build_history_operation_kind(&ProcessOperationKind::ManuallySolderComponents, &state);
+        // and
+        assert!(build_project_diff(&after, &after)?.is_empty());
 
-        // then
-        assert_eq!(result, expected_result)
+        Ok(())
     }
+}
 
-    #[rstest]
-    #[case(ProcessOperationStatus::Pending)]
-    #[case(ProcessOperationStatus::Incomplete)]
-    #[case(ProcessOperationStatus::Complete)]
-    pub fn for_reflow_components(#[case] status: ProcessOperationStatus) {
+#[cfg(test)]
+mod set_placement_override {
+    use std::str::FromStr;
+    use regex::Regex;
+    use pnp::object_path::ObjectPath;
+    use pnp::part::Part;
+    use pnp::pcb::PcbSide;
+    use crate::placement::PlacementOverride;
+    use crate::process::{PlacementsState, ProcessOperationExtraState, ProcessOperationKind};
+    use crate::project::{set_placement_override, update_phase_operation_states, Project};
+    use crate::reference::Reference;
+    use crate::test::project_builder::{PlacementStateBuilder, ProjectBuilder};
+
+    #[test]
+    pub fn skip_placement() {
         // given
-        let state = ProcessOperationState { status: status.clone(), extra: None };
+        let top_1 = Reference::from_str("top_1").unwrap();
+        let res1 = Part::new("RES_MFR1".to_string(), "RES1".to_string());
+        let mut project = ProjectBuilder::new()
+            .with_phase("top_1", "pnp", "load_out_1.csv", PcbSide::Top)
+            .with_placements([("R1", true, true), ("R2", true, false), ("R3", false, false)]
+                .map(|(ref_des, place, placed)| PlacementStateBuilder::new(1, ref_des, &res1).with_place(place).with_placed(placed).with_phase("top_1")))
+            .build();
+        update_phase_operation_states(&mut project);
+
+        let placements_state = |project: &Project| match &project.phase_states[&top_1].operation_state[&ProcessOperationKind::AutomatedPnp].extra {
+            Some(ProcessOperationExtraState::PlacementOperation { placements_state }) => placements_state.clone(),
+            _ => panic!("expected placements state"),
+        };
 
-        // and
-        let expected_result: OperationHistoryKind = OperationHistoryKind::ReflowComponents { status: status.clone() };
+        // expect the placement that the design does not place to be excluded
+        assert_eq!(placements_state(&project), PlacementsState { placed: 1, total: 2 });
 
         // when
-        let result = build_history_operation_kind(&ProcessOperationKind::ReflowComponents, &state);
+        let modified = set_placement_override(&mut project, &[Regex::new("ref_des=R2$").unwrap()], Some(PlacementOverride::Skip));
 
         // then
-        assert_eq!(result, expected_result)
-    }
-
-}
-
-pub fn update_placement_orderings(project: &mut Project, reference: &Reference, placement_orderings: &Vec<PlacementSortingItem>) -> anyhow::Result<bool> {
-    let phase = project.phases.get_mut(reference)
-        .ok_or(PhaseError::UnknownPhase(reference.clone()))?;
-
-    let modified = if phase.placement_orderings.eq(placement_orderings) {
-        false
-    } else {
-        phase.placement_orderings.clone_from(placement_orderings);
-
-        info!("Phase placement orderings set. phase: '{}', orderings: [{}]", reference, placement_orderings
-            .iter().map(|item|{
-                format!("{}:{}",
-                    item.mode.to_string().to_shouty_snake_case(),
-                    item.sort_order.to_string().to_shouty_snake_case()
-                )
-            }).collect::<Vec<_>>().join(", ")
-        );
-        true
-    };
+        assert!(modified);
+        let r2 = &project.placements[&ObjectPath::from_str("panel=1::unit=1::ref_des=R2").unwrap()];
+        assert!(!r2.place());
+        assert!(r2.placement.place);
 
-    Ok(modified)
-}
+        // and all the remaining placements are placed
+        assert_eq!(placements_state(&project), PlacementsState { placed: 1, total: 1 });
 
-pub fn reset_operations(project: &mut Project) -> anyhow::Result<()> {
-    
-    reset_placement_operations(project);
-    reset_phase_operations(project);
-    
-    update_phase_operation_states(project);
-    
-    Ok(())
-}
+        // when
+        let modified = set_placement_override(&mut project, &[Regex::new("ref_des=R2$").unwrap()], Some(PlacementOverride::Skip));
 
-fn reset_placement_operations(project: &mut Project) {
-    for (_object_path, placement_state) in project.placements.iter_mut() {
-        placement_state.placed = false;
-    }
+        // then
+        assert!(!modified);
 
-    info!("Placement operations reset.");
-}
+        // when the override is removed, and a placement the design does not place is placed
+        set_placement_override(&mut project, &[Regex::new("ref_des=R2$").unwrap()], None);
+        set_placement_override(&mut project, &[Regex::new("ref_des=R3$").unwrap()], Some(PlacementOverride::Place));
 
-fn reset_phase_operations(project: &mut Project) {
-    for (reference, phase_state) in project.phase_states.iter_mut() {
-        for (_kind, state) in phase_state.operation_state.iter_mut() {
-            state.status = ProcessOperationStatus::Pending;
-        }
-        info!("Phase operations reset. phase: {}", reference);
+        // then
+        assert_eq!(placements_state(&project), PlacementsState { placed: 1, total: 3 });
     }
 }
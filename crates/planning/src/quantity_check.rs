@@ -0,0 +1,186 @@
+//! Checks the remaining quantities of the load-out items of a phase before a placement operation is started, so that
+//! parts do not run out part way through a run.
+//!
+//! Only load-out items with a tracked quantity are checked, the required quantity of a part is the count of its
+//! placements, in the phase, that have not been placed yet.
+
+use std::fmt::{Display, Formatter};
+use thiserror::Error;
+use tracing::{info, warn};
+use pnp::load_out::LoadOutItem;
+use pnp::part::Part;
+use crate::phase::PhaseError;
+use crate::project::{count_phase_part_placements, Project};
+use crate::reference::Reference;
+use crate::mutation::MUTATION_TARGET;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum QuantityCheckMode {
+    /// Insufficient quantities are logged, the operation can be started.
+    Warn,
+    /// The operation can only be started if insufficient quantities are overridden.
+    Block,
+}
+
+impl Display for QuantityCheckMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Warn => write!(f, "Warn"),
+            Self::Block => write!(f, "Block"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuantityShortfall {
+    pub feeder_reference: String,
+    pub part: Part,
+    pub remaining: u32,
+    pub required: u32,
+}
+
+impl Display for QuantityShortfall {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (feeder: '{}', remaining: {}, required: {})", self.part, self.feeder_reference, self.remaining, self.required)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum QuantityCheckError {
+    #[error("Insufficient load-out item quantities, the operation was not started. phase: '{phase}', shortfalls: [{}]", shortfalls.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))]
+    Insufficient { phase: Reference, shortfalls: Vec<QuantityShortfall> },
+}
+
+/// Sets, or removes, the quantity check of a phase, returns true if modified.
+pub fn set_quantity_check(project: &mut Project, reference: &Reference, mode: Option<QuantityCheckMode>) -> Result<bool, PhaseError> {
+    let phase = project.phases.get_mut(reference)
+        .ok_or(PhaseError::UnknownPhase(reference.clone()))?;
+
+    if phase.quantity_check.eq(&mode) {
+        return Ok(false)
+    }
+
+    info!(target: MUTATION_TARGET, phase = %reference, old = ?phase.quantity_check, new = ?mode, message = "Phase quantity check set. phase: '{phase}', old: {old}, new: {new}");
+    phase.quantity_check = mode;
+
+    Ok(true)
+}
+
+/// The load-out items, with a tracked quantity, that have fewer parts remaining than required to complete the phase,
+/// in load-out order.
+pub fn find_quantity_shortfalls(project: &Project, reference: &Reference, load_out_items: &[LoadOutItem]) -> Vec<QuantityShortfall> {
+    let phase_part_counts = count_phase_part_placements(project);
+    let Some(part_counts) = phase_part_counts.get(reference) else {
+        return vec![]
+    };
+
+    load_out_items.iter()
+        .filter_map(|item| {
+            let remaining = item.quantity?;
+            let part = Part::new(item.manufacturer.clone(), item.mpn.clone());
+            let required = part_counts.get(&part).map_or(0, |counts| counts.unplaced);
+
+            (remaining < required).then(|| QuantityShortfall { feeder_reference: item.reference.clone(), part, remaining, required })
+        })
+        .collect()
+}
+
+/// Checks the quantities of the load-out items of the phase, using the quantity check of the phase.
+///
+/// `overridden` is true if the operator has chosen to start the operation regardless, e.g. after being prompted.
+pub fn check_quantities(project: &Project, reference: &Reference, load_out_items: &[LoadOutItem], overridden: bool) -> anyhow::Result<Vec<QuantityShortfall>> {
+    let phase = project.phases.get(reference)
+        .ok_or(PhaseError::UnknownPhase(reference.clone()))?;
+
+    let Some(mode) = &phase.quantity_check else {
+        return Ok(vec![])
+    };
+
+    let shortfalls = find_quantity_shortfalls(project, reference, load_out_items);
+
+    for shortfall in shortfalls.iter() {
+        warn!("Insufficient load-out item quantity. phase: '{}', feeder: '{}', part: {:?}, remaining: {}, required: {}", reference, shortfall.feeder_reference, shortfall.part, shortfall.remaining, shortfall.required);
+    }
+
+    if !shortfalls.is_empty() && matches!(mode, QuantityCheckMode::Block) {
+        if !overridden {
+            return Err(QuantityCheckError::Insufficient { phase: reference.clone(), shortfalls }.into())
+        }
+        warn!("Insufficient load-out item quantities overridden. phase: '{}', shortfalls: {}", reference, shortfalls.len());
+    }
+
+    Ok(shortfalls)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use pnp::load_out::LoadOutItem;
+    use pnp::part::Part;
+    use pnp::pcb::PcbSide;
+    use crate::project::Project;
+    use crate::quantity_check::{check_quantities, find_quantity_shortfalls, set_quantity_check, QuantityCheckError, QuantityCheckMode, QuantityShortfall};
+    use crate::reference::Reference;
+    use crate::test::project_builder::{PlacementStateBuilder, ProjectBuilder};
+
+    fn build_project() -> Project {
+        let res1 = Part::new("RES_MFR1".to_string(), "RES1".to_string());
+        let cap1 = Part::new("CAP_MFR1".to_string(), "CAP1".to_string());
+
+        ProjectBuilder::new()
+            .with_phase("top_1", "pnp", "load_out_1.csv", PcbSide::Top)
+            .with_placements([(1, "R1", &res1, true), (1, "C1", &cap1, false), (2, "R1", &res1, false), (2, "C1", &cap1, false), (3, "R1", &res1, false)]
+                .map(|(unit, ref_des, part, placed)| PlacementStateBuilder::new(unit, ref_des, part).with_placed(placed).with_phase("top_1")))
+            .build()
+    }
+
+    fn build_load_out_items() -> Vec<LoadOutItem> {
+        vec![
+            LoadOutItem { quantity: Some(1), ..LoadOutItem::new("FEEDER_1".to_string(), "RES_MFR1".to_string(), "RES1".to_string()) },
+            LoadOutItem::new("FEEDER_2".to_string(), "CAP_MFR1".to_string(), "CAP1".to_string()),
+        ]
+    }
+
+    #[test]
+    pub fn shortfalls_of_tracked_items() {
+        // given
+        let project = build_project();
+
+        // when
+        let shortfalls = find_quantity_shortfalls(&project, &Reference::from_str("top_1").unwrap(), &build_load_out_items());
+
+        // then only the unplaced placements are required, and the untracked 'CAP1' is not checked
+        assert_eq!(shortfalls, vec![
+            QuantityShortfall { feeder_reference: "FEEDER_1".to_string(), part: Part::new("RES_MFR1".to_string(), "RES1".to_string()), remaining: 1, required: 2 },
+        ]);
+    }
+
+    #[test]
+    pub fn block_unless_overridden() -> anyhow::Result<()> {
+        // given
+        let mut project = build_project();
+        let reference = Reference::from_str("top_1")?;
+        let load_out_items = build_load_out_items();
+
+        // and without a quantity check
+        assert!(check_quantities(&project, &reference, &load_out_items, false)?.is_empty());
+
+        // when
+        set_quantity_check(&mut project, &reference, Some(QuantityCheckMode::Block))?;
+        let result = check_quantities(&project, &reference, &load_out_items, false);
+
+        // then
+        assert!(matches!(result.unwrap_err().downcast::<QuantityCheckError>()?, QuantityCheckError::Insufficient { .. }));
+
+        // and
+        assert_eq!(check_quantities(&project, &reference, &load_out_items, true)?.len(), 1);
+
+        // when
+        set_quantity_check(&mut project, &reference, Some(QuantityCheckMode::Warn))?;
+
+        // then
+        assert_eq!(check_quantities(&project, &reference, &load_out_items, false)?.len(), 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,115 @@
+use thiserror::Error;
+use time::serde::rfc3339;
+use time::OffsetDateTime;
+use tracing::info;
+use crate::project::Project;
+use crate::mutation::MUTATION_TARGET;
+
+/// A release of the project to production, the planning data is frozen until the project is reopened.
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq)]
+pub struct Release {
+    pub version: u32,
+
+    #[serde(with = "rfc3339")]
+    pub released_at: OffsetDateTime,
+
+    /// Name of the snapshot directory, see `build_snapshot_name`.
+    pub snapshot: String,
+
+    #[serde(with = "rfc3339::option")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub reopened_at: Option<OffsetDateTime>,
+}
+
+#[derive(Error, Debug)]
+pub enum ReleaseError {
+    #[error("Project is released, planning changes are not allowed, reopen the project first. version: {version}")]
+    Released { version: u32 },
+
+    #[error("Project is not released")]
+    NotReleased,
+}
+
+/// The current release, if the project has been released and not reopened.
+pub fn find_current_release(project: &Project) -> Option<&Release> {
+    project.releases.last()
+        .filter(|release| release.reopened_at.is_none())
+}
+
+pub fn ensure_planning_modifiable(project: &Project) -> Result<(), ReleaseError> {
+    match find_current_release(project) {
+        Some(release) => Err(ReleaseError::Released { version: release.version }),
+        None => Ok(()),
+    }
+}
+
+pub fn build_snapshot_name(project_name: &str, version: u32) -> String {
+    format!("{}_v{}", project_name, version)
+}
+
+/// Records a new release, the version is one more than the previous release, returns the release.
+pub fn release(project: &mut Project, now: OffsetDateTime) -> Result<Release, ReleaseError> {
+    ensure_planning_modifiable(project)?;
+
+    let version = project.releases.last().map(|release| release.version + 1).unwrap_or(1);
+
+    let release = Release {
+        version,
+        released_at: now,
+        snapshot: build_snapshot_name(&project.name, version),
+        reopened_at: None,
+    };
+    project.releases.push(release.clone());
+
+    info!(target: MUTATION_TARGET, version = release.version, snapshot = %release.snapshot, message = "Released project. version: {version}, snapshot: '{snapshot}'");
+
+    Ok(release)
+}
+
+/// Reopens the current release, so that planning changes can be made for the next release.
+pub fn reopen(project: &mut Project, now: OffsetDateTime) -> Result<u32, ReleaseError> {
+    let release = project.releases.last_mut()
+        .filter(|release| release.reopened_at.is_none())
+        .ok_or(ReleaseError::NotReleased)?;
+
+    release.reopened_at = Some(now);
+
+    info!(target: MUTATION_TARGET, version = release.version, message = "Reopened project. version: {version}");
+
+    Ok(release.version)
+}
+
+#[cfg(test)]
+mod tests {
+    use time::OffsetDateTime;
+    use crate::project::Project;
+    use crate::release::{ensure_planning_modifiable, release, reopen, ReleaseError};
+
+    #[test]
+    pub fn release_and_reopen() {
+        // given
+        let mut project = Project::new("job1".to_string());
+        let now = OffsetDateTime::UNIX_EPOCH;
+
+        // when
+        let first_release = release(&mut project, now).unwrap();
+
+        // then
+        assert_eq!(first_release.version, 1);
+        assert_eq!(first_release.snapshot, "job1_v1");
+        assert!(matches!(ensure_planning_modifiable(&project), Err(ReleaseError::Released { version: 1 })));
+        assert!(matches!(release(&mut project, now), Err(ReleaseError::Released { version: 1 })));
+
+        // when
+        let reopened_version = reopen(&mut project, now).unwrap();
+
+        // then
+        assert_eq!(reopened_version, 1);
+        assert!(ensure_planning_modifiable(&project).is_ok());
+        assert!(matches!(reopen(&mut project, now), Err(ReleaseError::NotReleased)));
+
+        // and
+        assert_eq!(release(&mut project, now).unwrap().version, 2);
+    }
+}
@@ -1,36 +1,47 @@
+pub mod render;
+
 use serde_with::serde_as;
 use serde_with::DisplayFromStr;
-use std::path::PathBuf;
 use std::collections::{BTreeMap, BTreeSet, HashMap};
 use tracing::{info, trace};
 use std::cmp::Ordering;
-use std::fs::File;
 use thiserror::Error;
 use anyhow::Error;
 use serde::Serialize;
-use std::io::Write;
 use pnp::pcb::{Pcb, PcbKind};
 use pnp::load_out::LoadOutItem;
-use pnp::object_path::ObjectPath;
+use pnp::object_path::{ObjectPath, PanelIndex};
 use pnp::part::Part;
 use util::sorting::SortOrder;
 use crate::design::{DesignName, DesignVariant};
-use crate::placement::{PlacementState, PlacementStatus};
-use crate::process::{ProcessOperationExtraState, ProcessOperationKind, ProcessOperationStatus};
+use crate::placement::{PlacementConflictKind, PlacementOverride, PlacementState, PlacementStatus};
+use crate::process::{ArtifactType, ProcessOperationExtraState, ProcessOperationKind, ProcessOperationStatus};
 use crate::project::Project;
 use crate::reference::Reference;
 use crate::variant::VariantName;
+use crate::issue;
+use crate::issue::IssueResolution;
+use crate::pricing;
+use crate::pricing::{CostEstimate, PriceList};
+use crate::estimation::{self, FormattedDuration, ProjectEstimate};
+use crate::inventory;
+use crate::inventory::Inventory;
+use crate::load_out_sharing;
+use crate::phase;
+use crate::phase::PhaseDependencyError;
+use crate::load_out_sharing::SharedLoadOut;
 
 #[derive(Debug, Error)]
 pub enum ReportGenerationError {
-    #[error("Unable to save report. cause: {reason:}")]
-    UnableToSaveReport { reason: Error },
+    #[error("Unable to serialize report. cause: {reason:}")]
+    UnableToSerializeReport { reason: Error },
 }
 
 // FUTURE add a test to ensure that duplicate issues are not added to the report.
 //        currently a BTreeSet is used to prevent duplicate issues.
 
-pub fn project_generate_report(project: &Project, path: &PathBuf, name: &String, phase_load_out_items_map: &BTreeMap<Reference, Vec<LoadOutItem>>, issue_set: &mut BTreeSet<ProjectReportIssue>) -> Result<(), ReportGenerationError> {
+/// Cost estimates are only included when a price list is given.
+pub fn project_build_report(project: &Project, phase_load_out_items_map: &BTreeMap<Reference, Vec<LoadOutItem>>, price_list: Option<&PriceList>, inventory: Option<&Inventory>, issue_set: &mut BTreeSet<ProjectReportIssue>) -> ProjectReport {
 
     let mut report = ProjectReport::default();
 
@@ -75,6 +86,15 @@ pub fn project_generate_report(project: &Project, path: &PathBuf, name: &String,
 
                         Some(PhaseOperationOverview { operation: PhaseOperationKind::ManuallySolderComponents, message: placements_message.clone(), status: operation_state.status.clone() })
                     },
+                    (ProcessOperationKind::Custom(name), Some(ProcessOperationExtraState::PlacementOperation { placements_state })) => {
+                        if phase_status == PhaseStatus::Complete && operation_state.status != ProcessOperationStatus::Complete {
+                            phase_status = PhaseStatus::Incomplete;
+                        }
+
+                        let placements_message = format!("{}/{} placements placed", placements_state.placed, placements_state.total);
+
+                        Some(PhaseOperationOverview { operation: PhaseOperationKind::Custom(name.clone()), message: placements_message, status: operation_state.status.clone() })
+                    },
                     (_, _) => None,
                 };
                 
@@ -94,6 +114,8 @@ pub fn project_generate_report(project: &Project, path: &PathBuf, name: &String,
                 status: phase_status,
                 process: phase.process.to_string(),
                 operations_overview,
+                tags: phase.tags.clone(),
+                pcbs: project.find_phase_pcbs(reference).into_iter().map(|pcb| pcb.name.clone()).collect(),
             }
         }));
     } else {
@@ -118,24 +140,127 @@ pub fn project_generate_report(project: &Project, path: &PathBuf, name: &String,
 
     report.phase_specifications.extend(phase_specifications);
 
+    report.shared_load_outs = load_out_sharing::build_shared_load_outs(project, phase_load_out_items_map);
+
+    report.placement_overrides = project.placements.iter()
+        .filter_map(|(object_path, placement_state)| placement_state.place_override.map(|place_override| ReportPlacementOverride {
+            object_path: object_path.clone(),
+            place_override,
+        }))
+        .collect();
+
+    for conflict in project.placement_conflicts.iter() {
+        let (message, kind) = match &conflict.kind {
+            PlacementConflictKind::RefDesCollision => (
+                "Design variant has different placements with the same ref_des, the last placement is used.",
+                IssueKind::RefDesCollision { design_variant: conflict.design_variant.clone(), ref_des: conflict.ref_des.clone() },
+            ),
+            PlacementConflictKind::PartConflict { parts } => (
+                "Design variant has placements with the same ref_des and different parts, the last placement is used.",
+                IssueKind::PlacementPartConflict { design_variant: conflict.design_variant.clone(), ref_des: conflict.ref_des.clone(), parts: parts.clone() },
+            ),
+        };
+
+        issue_set.insert(ProjectReportIssue {
+            message: message.to_string(),
+            severity: IssueSeverity::Severe,
+            kind,
+        });
+    }
+
+    for conflict in load_out_sharing::find_feeder_conflicts(&report.shared_load_outs) {
+        issue_set.insert(ProjectReportIssue {
+            message: "Phases sharing a load-out require different parts in the same feeder".to_string(),
+            severity: IssueSeverity::Severe,
+            kind: IssueKind::SharedFeederConflict {
+                load_out_source: conflict.load_out_source,
+                feeder_reference: conflict.feeder_reference,
+                parts: conflict.parts.into_iter().map(|(part, _phases)| part).collect(),
+            },
+        });
+    }
+
+    if project.phases.values().any(|phase| !phase.dependencies.is_empty()) {
+        match phase::build_execution_plan(project) {
+            Ok(plan) => {
+                info!("Execution plan: {}", phase::format_references(&plan));
+                report.execution_plan = plan;
+            },
+            Err(error) => {
+                // a phase that depends on itself is a cycle of one phase
+                let phases = match error {
+                    PhaseDependencyError::Cycle { phases } => phases,
+                    PhaseDependencyError::SelfDependency(reference) => vec![reference.to_string()],
+                };
+
+                issue_set.insert(ProjectReportIssue {
+                    message: "Phase dependencies contain a cycle, the phases cannot be executed.".to_string(),
+                    severity: IssueSeverity::Severe,
+                    kind: IssueKind::PhaseDependencyCycle { phases },
+                });
+            },
+        }
+    }
+
+    for (reference, incomplete_dependencies) in phase::find_dependency_violations(project) {
+        for dependency in incomplete_dependencies {
+            issue_set.insert(ProjectReportIssue {
+                message: "Phase started before a phase it depends on was complete.".to_string(),
+                severity: IssueSeverity::Warning,
+                kind: IssueKind::PhaseDependencyViolation { phase: reference.clone(), dependency: dependency.clone() },
+            });
+        }
+    }
+
+    if let Some(price_list) = price_list {
+        let cost_estimate = pricing::build_cost_estimate(project, price_list);
+        info!("Estimated cost. total: {}, unpriced parts: {}", pricing::format_cost(&cost_estimate.total), cost_estimate.unpriced_parts.len());
+
+        for part in cost_estimate.unpriced_parts.iter() {
+            issue_set.insert(ProjectReportIssue {
+                message: "No price for part, the part is not included in the cost estimate.".to_string(),
+                severity: IssueSeverity::Warning,
+                kind: IssueKind::MissingPartPrice { part: part.clone() },
+            });
+        }
+
+        report.cost_estimate = Some(cost_estimate);
+    }
+
+    if let Some(time_estimate) = estimation::build_project_estimate(project) {
+        info!("Estimated time. total: {}, remaining: {}", FormattedDuration(time_estimate.total_time), FormattedDuration(time_estimate.remaining_time));
+
+        report.time_estimate = Some(time_estimate);
+    }
+
+    if let Some(inventory) = inventory {
+        for shortage in inventory::find_inventory_shortages(project, inventory) {
+            issue_set.insert(ProjectReportIssue {
+                message: "The phase requires more parts than are in the inventory.".to_string(),
+                severity: IssueSeverity::Warning,
+                kind: IssueKind::InventoryShortage { phase: shortage.phase, part: shortage.part, required: shortage.required, on_hand: shortage.on_hand },
+            });
+        }
+    }
+
     project_report_add_placement_issues(project, issue_set);
     let mut issues: Vec<ProjectReportIssue> = issue_set.iter().cloned().collect();
 
     project_report_sort_issues(&mut issues);
     
-    for issue in issues.iter() {
-        info!("Issue detected. severity: {:?}, message: '{}', kind: {:?}", issue.severity, issue.message, issue.kind );
-    }
-    
-    report.issues = issues;
+    report.issues = issues.into_iter().map(|issue| {
+        let id = issue::build_issue_id(&issue.kind);
+        let resolution = issue::find_resolution(project, &issue).cloned();
 
-    let report_file_path = build_report_file_path(name, path);
+        match &resolution {
+            Some(resolution) => info!("Issue detected. id: '{}', severity: {:?}, message: '{}', kind: {:?}, resolution: {:?}", id, issue.severity, issue.message, issue.kind, resolution.status),
+            None => info!("Issue detected. id: '{}', severity: {:?}, message: '{}', kind: {:?}", id, issue.severity, issue.message, issue.kind),
+        }
 
-    project_report_save(&report, &report_file_path).map_err(|err|{
-        ReportGenerationError::UnableToSaveReport { reason: err }
-    })?;
+        ReportIssue { id, issue, resolution }
+    }).collect();
 
-    Ok(())
+    report
 }
 
 fn generate_issues_for_invalid_unit_assignments(project: &Project) -> BTreeSet<ProjectReportIssue> {
@@ -147,7 +272,7 @@ fn generate_issues_for_invalid_unit_assignments(project: &Project) -> BTreeSet<P
         if let Some((pcb_kind, index)) = object_path.pcb_kind_and_index() {
             let issue = match pcb_kind_counts.get(&pcb_kind) {
                 Some(count) => {
-                    if index > *count {
+                    if index.get() > *count {
                         Some(ProjectReportIssue {
                             message: "Invalid unit assignment, index out of range.".to_string(),
                             severity: IssueSeverity::Severe,
@@ -189,11 +314,14 @@ fn build_phase_specification(project: &Project, phase_load_out_items_map: &BTree
 
     let load_out_items = phase_load_out_items_map.get(reference).unwrap();
 
-    let load_out_assignments = load_out_items.iter().map(|load_out_item| {
+    let mut sorted_load_out_items: Vec<&LoadOutItem> = load_out_items.iter().collect();
+    sorted_load_out_items.sort_by(|a, b| pnp::load_out::load_out_item_cmp(a, b));
+
+    let load_out_assignments = sorted_load_out_items.into_iter().map(|load_out_item| {
         let quantity = project.placements.iter()
             .filter(|(_object_path, placement_state)| {
                 matches!(&placement_state.phase, Some(other_phase_reference) if phase.reference.eq(other_phase_reference))
-                    && placement_state.placement.place
+                    && placement_state.place()
                     && load_out_item.manufacturer.eq(&placement_state.placement.part.manufacturer)
                     && load_out_item.mpn.eq(&placement_state.placement.part.mpn)
             })
@@ -201,20 +329,28 @@ fn build_phase_specification(project: &Project, phase_load_out_items_map: &BTree
                 quantity + 1
             });
 
+        let part_details = project.find_part_details(&Part::new(load_out_item.manufacturer.clone(), load_out_item.mpn.clone()));
+
         PhaseLoadOutAssignmentItem {
             feeder_reference: load_out_item.reference.clone(),
             manufacturer: load_out_item.manufacturer.clone(),
             mpn: load_out_item.mpn.clone(),
+            value: part_details.and_then(|details| details.value.clone()),
+            description: part_details.and_then(|details| details.description.clone()),
+            package: part_details.and_then(|details| details.package.clone()),
             quantity,
+            alternates: load_out_item.alternates.clone(),
+            loaded_alternate: load_out_item.loaded_alternate.clone(),
         }
     }).collect();
 
     let operations = phase_state.operation_state.keys().map(|operation| {
         match operation {
-            ProcessOperationKind::LoadPcbs => build_operation_load_pcbs(project),
+            ProcessOperationKind::LoadPcbs => build_operation_load_pcbs(project, reference),
             ProcessOperationKind::AutomatedPnp => PhaseOperation::PlaceComponents {},
             ProcessOperationKind::ReflowComponents => PhaseOperation::ReflowComponents {},
             ProcessOperationKind::ManuallySolderComponents => PhaseOperation::ManuallySolderComponents {},
+            ProcessOperationKind::Custom(name) => PhaseOperation::CustomOperation { operation: name.clone() },
         }
     }).collect();
 
@@ -225,51 +361,51 @@ fn build_phase_specification(project: &Project, phase_load_out_items_map: &BTree
     }
 }
 
-fn build_operation_load_pcbs(project: &Project) -> PhaseOperation {
-    let unit_paths_with_placements = build_unit_paths_with_placements(&project.placements);
-
-    let pcbs: Vec<PcbReportItem> = unit_paths_with_placements.iter().find_map(|unit_path| {
-        if let Some((kind, mut index)) = unit_path.pcb_kind_and_index() {
-
-            // TODO consider if unit paths should use zero-based index
-            index -= 1;
-
-            // Note: the user may not have made any unit assignments yet.
-            let mut unit_assignments = find_unit_assignments(project, unit_path);
+/// The PCBs to load for the phase, each PCB with the assignments of its units that have placements in the phase.
+fn build_operation_load_pcbs(project: &Project, reference: &Reference) -> PhaseOperation {
+    let phase_placements = project.placements.iter()
+        .filter(|(_object_path, placement_state)| placement_state.phase.as_ref().is_some_and(|phase| phase.eq(reference)));
 
-            match kind {
-                PcbKind::Panel => {
-                    let pcb = project.pcbs.get(index).unwrap();
-
-
-                    Some(PcbReportItem::Panel {
-                        name: pcb.name.clone(),
-                        unit_assignments,
-                    })
-                },
-                PcbKind::Single => {
-                    let pcb = project.pcbs.get(index).unwrap();
+    let unit_paths_with_placements = build_unit_paths_with_placements(phase_placements);
 
-                    assert!(unit_assignments.len() <= 1);
+    let mut pcb_unit_paths: BTreeMap<(PcbKind, PanelIndex), Vec<&ObjectPath>> = BTreeMap::new();
+    for unit_path in unit_paths_with_placements.iter() {
+        if let Some(kind_and_index) = unit_path.pcb_kind_and_index() {
+            pcb_unit_paths.entry(kind_and_index).or_default().push(unit_path);
+        }
+    }
 
-                    Some(PcbReportItem::Single {
-                        name: pcb.name.clone(),
-                        unit_assignment: unit_assignments.pop()
-                    })
-                },
-            }
-        } else {
-            None
+    let pcbs: Vec<PcbReportItem> = pcb_unit_paths.into_iter().filter_map(|((kind, index), unit_paths)| {
+        // unit assignments are reported as issues when there is no matching PCB
+        let pcb = pnp::pcb::find_pcb(&project.pcbs, &kind, index.get())?;
+
+        // Note: the user may not have made any unit assignments yet.
+        let mut unit_assignments: Vec<PcbUnitAssignmentItem> = unit_paths.into_iter()
+            .flat_map(|unit_path| find_unit_assignments(project, unit_path))
+            .collect();
+
+        match kind {
+            PcbKind::Panel => Some(PcbReportItem::Panel {
+                name: pcb.name.clone(),
+                unit_assignments,
+            }),
+            PcbKind::Single => {
+                assert!(unit_assignments.len() <= 1);
+
+                Some(PcbReportItem::Single {
+                    name: pcb.name.clone(),
+                    unit_assignment: unit_assignments.pop()
+                })
+            },
         }
-    }).into_iter().collect();
+    }).collect();
 
-    let operation = PhaseOperation::PreparePcbs { pcbs };
-    operation
+    PhaseOperation::PreparePcbs { pcbs }
 }
 
-fn build_unit_paths_with_placements(placement_states: &BTreeMap<ObjectPath, PlacementState>) -> BTreeSet<ObjectPath> {
-    placement_states.iter().fold(BTreeSet::<ObjectPath>::new(), |mut acc, (object_path, placement_state)| {
-        if placement_state.placement.place {
+fn build_unit_paths_with_placements<'a>(placement_states: impl Iterator<Item = (&'a ObjectPath, &'a PlacementState)>) -> BTreeSet<ObjectPath> {
+    placement_states.fold(BTreeSet::<ObjectPath>::new(), |mut acc, (object_path, placement_state)| {
+        if placement_state.place() {
             let pcb_unit = object_path.pcb_unit();
             if acc.insert(pcb_unit) {
                 trace!("Phase pcb unit found.  object_path: {}", object_path);
@@ -281,7 +417,7 @@ fn build_unit_paths_with_placements(placement_states: &BTreeMap<ObjectPath, Plac
 
 fn project_report_add_placement_issues(project: &Project, issues: &mut BTreeSet<ProjectReportIssue>) {
     for (object_path, _placement_state) in project.placements.iter().filter(|(_object_path, placement_state)| {
-        placement_state.phase.is_none() && placement_state.status == PlacementStatus::Known
+        placement_state.phase.is_none() && placement_state.status == PlacementStatus::Known && placement_state.placement.kind.is_component()
     }) {
         issues.insert(ProjectReportIssue {
             message: "A placement has not been assigned to a phase".to_string(),
@@ -289,6 +425,16 @@ fn project_report_add_placement_issues(project: &Project, issues: &mut BTreeSet<
             kind: IssueKind::UnassignedPlacement { object_path: object_path.clone() },
         });
     }
+
+    for (object_path, _placement_state) in project.placements.iter().filter(|(_object_path, placement_state)| {
+        placement_state.has_open_defect()
+    }) {
+        issues.insert(ProjectReportIssue {
+            message: "A placement has an open inspection defect".to_string(),
+            severity: IssueSeverity::Warning,
+            kind: IssueKind::OpenInspectionDefect { object_path: object_path.clone() },
+        });
+    }
 }
 
 fn project_report_sort_issues(issues: &mut [ProjectReportIssue]) {
@@ -296,7 +442,7 @@ fn project_report_sort_issues(issues: &mut [ProjectReportIssue]) {
 
         let sort_orderings = &[("severity", SortOrder::Desc), ("kind", SortOrder::Asc), ("message", SortOrder::Asc)];
         
-        sort_orderings.iter().fold( Ordering::Equal, | mut acc, (&ref mode, sort_order) | {
+        sort_orderings.iter().fold( Ordering::Equal, | mut acc, (mode, sort_order) | {
             if !matches!(acc, Ordering::Equal) {
                 return acc
             }
@@ -308,6 +454,20 @@ fn project_report_sort_issues(issues: &mut [ProjectReportIssue]) {
                     IssueKind::InvalidUnitAssignment { .. } => 2,
                     IssueKind::UnassignedPlacement { .. } => 3,
                     IssueKind::UnassignedPartFeeder { .. } => 4,
+                    IssueKind::OpenInspectionDefect { .. } => 5,
+                    IssueKind::MissingRequiredArtifact { .. } => 6,
+                    IssueKind::FloorLifeExceeded { .. } => 7,
+                    IssueKind::FloorLifeExpiring { .. } => 8,
+                    IssueKind::MissingPanelUnitGeometry { .. } => 9,
+                    IssueKind::PlacementOutsidePcb { .. } => 10,
+                    IssueKind::MissingPartPrice { .. } => 11,
+                    IssueKind::NoNozzleForPart { .. } => 12,
+                    IssueKind::SharedFeederConflict { .. } => 13,
+                    IssueKind::InventoryShortage { .. } => 14,
+                    IssueKind::PhaseDependencyCycle { .. } => 15,
+                    IssueKind::PhaseDependencyViolation { .. } => 16,
+                    IssueKind::RefDesCollision { .. } => 17,
+                    IssueKind::PlacementPartConflict { .. } => 18,
                 }   
             }
             fn severity_ordinal(severity: &IssueSeverity) -> usize {
@@ -317,7 +477,7 @@ fn project_report_sort_issues(issues: &mut [ProjectReportIssue]) {
                 }   
             }
             
-            acc = match mode {
+            acc = match *mode {
                 "kind" => {
                     let a_ordinal = kind_ordinal(&a.kind); 
                     let b_ordinal = kind_ordinal(&b.kind);
@@ -333,6 +493,32 @@ fn project_report_sort_issues(issues: &mut [ProjectReportIssue]) {
                                     object_path_a.cmp(object_path_b),
                                 (IssueKind::UnassignedPartFeeder { part: part_a }, IssueKind::UnassignedPartFeeder { part: part_b}) =>
                                     part_a.cmp(part_b),
+                                (IssueKind::OpenInspectionDefect { object_path: object_path_a }, IssueKind::OpenInspectionDefect { object_path: object_path_b }) =>
+                                    object_path_a.cmp(object_path_b),
+                                (IssueKind::MissingRequiredArtifact { phase: phase_a, artifact: artifact_a }, IssueKind::MissingRequiredArtifact { phase: phase_b, artifact: artifact_b }) =>
+                                    phase_a.cmp(phase_b).then(artifact_a.cmp(artifact_b)),
+                                (IssueKind::FloorLifeExceeded { phase: phase_a, feeder_reference: feeder_reference_a, .. }, IssueKind::FloorLifeExceeded { phase: phase_b, feeder_reference: feeder_reference_b, .. }) =>
+                                    phase_a.cmp(phase_b).then(pnp::load_out::feeder_reference_cmp(feeder_reference_a, feeder_reference_b)),
+                                (IssueKind::FloorLifeExpiring { phase: phase_a, feeder_reference: feeder_reference_a, .. }, IssueKind::FloorLifeExpiring { phase: phase_b, feeder_reference: feeder_reference_b, .. }) =>
+                                    phase_a.cmp(phase_b).then(pnp::load_out::feeder_reference_cmp(feeder_reference_a, feeder_reference_b)),
+                                (IssueKind::MissingPanelUnitGeometry { object_path: object_path_a }, IssueKind::MissingPanelUnitGeometry { object_path: object_path_b }) =>
+                                    object_path_a.cmp(object_path_b),
+                                (IssueKind::PlacementOutsidePcb { object_path: object_path_a, .. }, IssueKind::PlacementOutsidePcb { object_path: object_path_b, .. }) =>
+                                    object_path_a.cmp(object_path_b),
+                                (IssueKind::MissingPartPrice { part: part_a }, IssueKind::MissingPartPrice { part: part_b }) =>
+                                    part_a.cmp(part_b),
+                                (IssueKind::NoNozzleForPart { phase: phase_a, part: part_a }, IssueKind::NoNozzleForPart { phase: phase_b, part: part_b }) =>
+                                    phase_a.cmp(phase_b).then(part_a.cmp(part_b)),
+                                (IssueKind::SharedFeederConflict { load_out_source: load_out_source_a, feeder_reference: feeder_reference_a, .. }, IssueKind::SharedFeederConflict { load_out_source: load_out_source_b, feeder_reference: feeder_reference_b, .. }) =>
+                                    load_out_source_a.cmp(load_out_source_b).then(pnp::load_out::feeder_reference_cmp(feeder_reference_a, feeder_reference_b)),
+                                (IssueKind::InventoryShortage { phase: phase_a, part: part_a, .. }, IssueKind::InventoryShortage { phase: phase_b, part: part_b, .. }) =>
+                                    phase_a.cmp(phase_b).then(part_a.cmp(part_b)),
+                                (IssueKind::PhaseDependencyViolation { phase: phase_a, dependency: dependency_a }, IssueKind::PhaseDependencyViolation { phase: phase_b, dependency: dependency_b }) =>
+                                    phase_a.cmp(phase_b).then(dependency_a.cmp(dependency_b)),
+                                (IssueKind::RefDesCollision { design_variant: design_variant_a, ref_des: ref_des_a }, IssueKind::RefDesCollision { design_variant: design_variant_b, ref_des: ref_des_b }) =>
+                                    design_variant_a.cmp(design_variant_b).then(ref_des_a.cmp(ref_des_b)),
+                                (IssueKind::PlacementPartConflict { design_variant: design_variant_a, ref_des: ref_des_a, .. }, IssueKind::PlacementPartConflict { design_variant: design_variant_b, ref_des: ref_des_b, .. }) =>
+                                    design_variant_a.cmp(design_variant_b).then(ref_des_a.cmp(ref_des_b)),
                                 _ => ordinal_ordering,
                             }
                         }
@@ -343,8 +529,7 @@ fn project_report_sort_issues(issues: &mut [ProjectReportIssue]) {
                 "severity" => {
                     let a_ordinal = severity_ordinal(&a.severity);
                     let b_ordinal = severity_ordinal(&b.severity);
-                    let ordinal_ordering = a_ordinal.cmp(&b_ordinal);
-                    ordinal_ordering
+                    a_ordinal.cmp(&b_ordinal)
                 },
                 _ => unreachable!()
             };
@@ -564,23 +749,51 @@ pub struct ProjectReport {
     pub status: ProjectStatus,
     pub phase_overviews: Vec<PhaseOverview>,
     pub phase_specifications: Vec<PhaseSpecification>,
+    /// The load-outs used by more than one phase, with the quantities required by the phases.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub shared_load_outs: Vec<SharedLoadOut>,
+    /// The placements whose design `place` is overridden for the project, in object path order.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub placement_overrides: Vec<ReportPlacementOverride>,
+    /// The phases in the order they can be executed, only present if phases have dependencies.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub execution_plan: Vec<Reference>,
     /// A list of unique issues.
     /// Note: Using a Vec doesn't prevent duplicates, duplicates must be filtered before adding them.
-    pub issues: Vec<ProjectReportIssue>,
+    pub issues: Vec<ReportIssue>,
+    /// Only present if the project has a price list.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost_estimate: Option<CostEstimate>,
+    /// Only present if the project has estimation parameters.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_estimate: Option<ProjectEstimate>,
+}
+
+#[serde_as]
+#[derive(Clone, serde::Serialize, Debug, PartialEq)]
+pub struct ReportPlacementOverride {
+    #[serde_as(as = "DisplayFromStr")]
+    pub object_path: ObjectPath,
+    pub place_override: PlacementOverride,
 }
 
+/// An issue, with its id and resolution, if it has been acknowledged or waived.
 #[derive(Clone, serde::Serialize)]
+pub struct ReportIssue {
+    pub id: String,
+    #[serde(flatten)]
+    pub issue: ProjectReportIssue,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolution: Option<IssueResolution>,
+}
+
+#[derive(Clone, serde::Serialize, Default)]
 pub enum ProjectStatus {
+    #[default]
     Incomplete,
     Complete,
 }
 
-impl Default for ProjectStatus {
-    fn default() -> Self {
-        Self::Incomplete
-    }
-}
-
 #[derive(Clone, serde::Serialize, PartialEq)]
 pub enum PhaseStatus {
     Incomplete, 
@@ -593,6 +806,11 @@ pub struct PhaseOverview {
     pub status: PhaseStatus,
     pub process: String,
     pub operations_overview: Vec<PhaseOperationOverview>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, String>,
+    /// Names of the PCBs with placements in the phase, so that the phases of each PCB can be found.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub pcbs: Vec<String>,
 }
 
 #[derive(Clone, serde::Serialize)]
@@ -632,6 +850,8 @@ pub enum PhaseOperation {
     PlaceComponents {},
     ReflowComponents {},
     ManuallySolderComponents {},
+    /// An operation of a process definition.
+    CustomOperation { operation: String },
 }
 
 #[derive(Clone, serde::Serialize)]
@@ -639,6 +859,7 @@ pub enum PhaseOperationKind {
     PreparePcbs,
     PlaceComponents,
     ManuallySolderComponents,
+    Custom(String),
 }
 
 
@@ -647,7 +868,20 @@ pub struct PhaseLoadOutAssignmentItem {
     pub feeder_reference: String,
     pub manufacturer: String,
     pub mpn: String,
+    /// From the part details, e.g. from a parts master.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub package: Option<String>,
     pub quantity: u32,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub alternates: Vec<Part>,
+    /// The alternate that is loaded instead of the part, for traceability.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub loaded_alternate: Option<Part>,
 }
 
 // FUTURE implement `Display` and improve info logging
@@ -678,24 +912,87 @@ pub enum IssueKind {
         object_path: ObjectPath
     },
     UnassignedPartFeeder { part: Part },
+    OpenInspectionDefect {
+        #[serde_as(as = "DisplayFromStr")]
+        object_path: ObjectPath
+    },
+    MissingRequiredArtifact {
+        #[serde_as(as = "DisplayFromStr")]
+        phase: Reference,
+        artifact: ArtifactType,
+    },
+    FloorLifeExceeded {
+        #[serde_as(as = "DisplayFromStr")]
+        phase: Reference,
+        feeder_reference: String,
+        part: Part,
+    },
+    FloorLifeExpiring {
+        #[serde_as(as = "DisplayFromStr")]
+        phase: Reference,
+        feeder_reference: String,
+        part: Part,
+    },
+    MissingPanelUnitGeometry {
+        #[serde_as(as = "DisplayFromStr")]
+        object_path: ObjectPath
+    },
+    PlacementOutsidePcb {
+        #[serde_as(as = "DisplayFromStr")]
+        object_path: ObjectPath,
+        pcb: String,
+    },
+    MissingPartPrice { part: Part },
+    NoNozzleForPart {
+        #[serde_as(as = "DisplayFromStr")]
+        phase: Reference,
+        part: Part,
+    },
+    SharedFeederConflict {
+        load_out_source: String,
+        feeder_reference: String,
+        parts: Vec<Part>,
+    },
+    InventoryShortage {
+        #[serde_as(as = "DisplayFromStr")]
+        phase: Reference,
+        part: Part,
+        required: u32,
+        on_hand: u32,
+    },
+    PhaseDependencyCycle { phases: Vec<String> },
+    PhaseDependencyViolation {
+        #[serde_as(as = "DisplayFromStr")]
+        phase: Reference,
+        #[serde_as(as = "DisplayFromStr")]
+        dependency: Reference,
+    },
+    RefDesCollision {
+        #[serde_as(as = "DisplayFromStr")]
+        design_variant: DesignVariant,
+        ref_des: String,
+    },
+    PlacementPartConflict {
+        #[serde_as(as = "DisplayFromStr")]
+        design_variant: DesignVariant,
+        ref_des: String,
+        parts: Vec<Part>,
+    },
 }
 
-fn build_report_file_path(name: &str, path: &PathBuf) -> PathBuf {
-    let mut report_file_path: PathBuf = path.clone();
-    report_file_path.push(format!("{}_report.json", name));
-    report_file_path
+pub fn build_report_file_name(name: &str) -> String {
+    format!("{}_report.json", name)
 }
 
-fn project_report_save(report: &ProjectReport, report_file_path: &PathBuf) -> anyhow::Result<()> {
-    let report_file = File::create(report_file_path)?;
+pub fn project_report_serialize(report: &ProjectReport) -> Result<Vec<u8>, ReportGenerationError> {
     let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
-    let mut ser = serde_json::Serializer::with_formatter(report_file, formatter);
-    report.serialize(&mut ser)?;
+    let mut ser = serde_json::Serializer::with_formatter(vec![], formatter);
+    report.serialize(&mut ser).map_err(|err|{
+        ReportGenerationError::UnableToSerializeReport { reason: err.into() }
+    })?;
 
-    let mut report_file = ser.into_inner();
-    report_file.write(b"\n")?;
+    let mut content = ser.into_inner();
+    content.push(b'\n');
 
-    info!("Generated report. path: {:?}", report_file_path);
-    
-    Ok(())
+    Ok(content)
 }
\ No newline at end of file
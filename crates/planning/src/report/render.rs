@@ -0,0 +1,401 @@
+//! Renders the project report for people, the JSON report remains the source for other tools.
+//!
+//! The HTML report is a single, self-contained, file so that it can be attached to a job or printed.  PDF output is
+//! not supported, a PDF can be created by printing the HTML report from a browser.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use serde_json::Value;
+use crate::estimation::FormattedDuration;
+use crate::report::{PcbReportItem, PcbUnitAssignmentItem, PhaseOperation, ProjectReport, ReportIssue};
+
+const STYLE: &str = "body { font-family: sans-serif; margin: 2em; } \
+    table { border-collapse: collapse; margin-bottom: 1em; } \
+    th, td { border: 1px solid #999; padding: 0.25em 0.5em; text-align: left; } \
+    th { background: #eee; } \
+    .Severe { color: #b00; } \
+    .Warning { color: #a60; }";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReportFormat {
+    /// Only the JSON report.
+    #[default]
+    Json,
+    /// The HTML report, in addition to the JSON report.
+    Html,
+}
+
+/// e.g. 'job1_report.html'
+pub fn build_html_report_file_name(name: &str) -> String {
+    format!("{}_report.html", name)
+}
+
+/// Renders the report as an HTML document with the phases, their load-outs, the shared load-outs, the issues and the
+/// cost estimate.
+pub fn render_html(report: &ProjectReport) -> String {
+    let mut html = String::new();
+
+    writeln!(html, "<!DOCTYPE html>").unwrap();
+    writeln!(html, "<html>").unwrap();
+    writeln!(html, "<head>").unwrap();
+    writeln!(html, "<meta charset=\"utf-8\">").unwrap();
+    writeln!(html, "<title>Report - {}</title>", escape_html(&report.name)).unwrap();
+    writeln!(html, "<style>{}</style>", STYLE).unwrap();
+    writeln!(html, "</head>").unwrap();
+    writeln!(html, "<body>").unwrap();
+    writeln!(html, "<h1>Report - {}</h1>", escape_html(&report.name)).unwrap();
+    writeln!(html, "<p>Status: {}</p>", variant_name(&report.status)).unwrap();
+
+    write_phase_overviews(&mut html, report);
+    write_execution_plan(&mut html, report);
+    write_phase_specifications(&mut html, report);
+    write_shared_load_outs(&mut html, report);
+    write_placement_overrides(&mut html, report);
+    write_issues(&mut html, &report.issues);
+    write_cost_estimate(&mut html, report);
+    write_time_estimate(&mut html, report);
+
+    writeln!(html, "</body>").unwrap();
+    writeln!(html, "</html>").unwrap();
+
+    html
+}
+
+fn write_phase_overviews(html: &mut String, report: &ProjectReport) {
+    writeln!(html, "<h2>Phases</h2>").unwrap();
+    if report.phase_overviews.is_empty() {
+        writeln!(html, "<p>No phases.</p>").unwrap();
+        return
+    }
+
+    write_table_header(html, &["Phase", "Process", "Status", "PCBs", "Operations", "Tags"]);
+    for phase_overview in report.phase_overviews.iter() {
+        let operations = phase_overview.operations_overview.iter()
+            .map(|operation_overview| format!("{} ({})", escape_html(&operation_overview.message), variant_name(&operation_overview.status)))
+            .collect::<Vec<_>>()
+            .join("<br>");
+        let tags = phase_overview.tags.iter()
+            .map(|(name, value)| format!("{}={}", escape_html(name), escape_html(value)))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        write_table_row(html, &[
+            escape_html(&phase_overview.phase_name),
+            escape_html(&phase_overview.process),
+            variant_name(&phase_overview.status),
+            escape_html(&phase_overview.pcbs.join(", ")),
+            operations,
+            tags,
+        ]);
+    }
+    writeln!(html, "</table>").unwrap();
+}
+
+fn write_execution_plan(html: &mut String, report: &ProjectReport) {
+    if report.execution_plan.is_empty() {
+        return
+    }
+
+    writeln!(html, "<h2>Execution plan</h2>").unwrap();
+    writeln!(html, "<ol>").unwrap();
+    for reference in report.execution_plan.iter() {
+        writeln!(html, "<li>{}</li>", escape_html(&reference.to_string())).unwrap();
+    }
+    writeln!(html, "</ol>").unwrap();
+}
+
+fn write_phase_specifications(html: &mut String, report: &ProjectReport) {
+    for phase_specification in report.phase_specifications.iter() {
+        writeln!(html, "<h2>Phase - {}</h2>", escape_html(&phase_specification.phase_name)).unwrap();
+
+        writeln!(html, "<h3>Operations</h3>").unwrap();
+        writeln!(html, "<ol>").unwrap();
+        for operation in phase_specification.operations.iter() {
+            match operation {
+                PhaseOperation::PreparePcbs { pcbs } => {
+                    let pcbs = pcbs.iter().map(build_pcb_description).collect::<Vec<_>>().join("; ");
+                    writeln!(html, "<li>Prepare PCBs: {}</li>", pcbs).unwrap();
+                },
+                PhaseOperation::PlaceComponents {} => writeln!(html, "<li>Place components</li>").unwrap(),
+                PhaseOperation::ReflowComponents {} => writeln!(html, "<li>Reflow components</li>").unwrap(),
+                PhaseOperation::ManuallySolderComponents {} => writeln!(html, "<li>Manually solder components</li>").unwrap(),
+                PhaseOperation::CustomOperation { operation } => writeln!(html, "<li>{}</li>", escape_html(operation)).unwrap(),
+            }
+        }
+        writeln!(html, "</ol>").unwrap();
+
+        writeln!(html, "<h3>Load-out</h3>").unwrap();
+        if phase_specification.load_out_assignments.is_empty() {
+            writeln!(html, "<p>No parts.</p>").unwrap();
+            continue
+        }
+
+        write_table_header(html, &["Feeder", "Manufacturer", "Mpn", "Value", "Description", "Quantity", "Alternates", "Loaded alternate"]);
+        for item in phase_specification.load_out_assignments.iter() {
+            let alternates = item.alternates.iter().map(|part| escape_html(&part.to_string())).collect::<Vec<_>>().join(", ");
+            let loaded_alternate = item.loaded_alternate.as_ref().map(|part| escape_html(&part.to_string())).unwrap_or_default();
+
+            write_table_row(html, &[
+                escape_html(&item.feeder_reference),
+                escape_html(&item.manufacturer),
+                escape_html(&item.mpn),
+                escape_html(item.value.as_deref().unwrap_or_default()),
+                escape_html(item.description.as_deref().unwrap_or_default()),
+                item.quantity.to_string(),
+                alternates,
+                loaded_alternate,
+            ]);
+        }
+        writeln!(html, "</table>").unwrap();
+    }
+}
+
+/// e.g. 'panel_a (panel=1::unit=1: design_a/variant_a, panel=1::unit=2: design_a/variant_b)'
+fn build_pcb_description(pcb: &PcbReportItem) -> String {
+    let describe_assignment = |unit_assignment: &PcbUnitAssignmentItem| format!("{}: {}/{}",
+        unit_assignment.unit_path, unit_assignment.design_name, unit_assignment.variant_name,
+    );
+
+    let (name, assignments) = match pcb {
+        PcbReportItem::Panel { name, unit_assignments } => (name, unit_assignments.iter().map(describe_assignment).collect::<Vec<_>>()),
+        PcbReportItem::Single { name, unit_assignment } => (name, unit_assignment.iter().map(describe_assignment).collect::<Vec<_>>()),
+    };
+
+    match assignments.is_empty() {
+        true => format!("{} (unassigned)", escape_html(name)),
+        false => format!("{} ({})", escape_html(name), escape_html(&assignments.join(", "))),
+    }
+}
+
+fn write_shared_load_outs(html: &mut String, report: &ProjectReport) {
+    for shared_load_out in report.shared_load_outs.iter() {
+        writeln!(html, "<h2>Shared load-out - {}</h2>", escape_html(&shared_load_out.load_out_source)).unwrap();
+        let phases = shared_load_out.phases.iter().map(|reference| reference.to_string()).collect::<Vec<_>>();
+        writeln!(html, "<p>Phases: {}</p>", escape_html(&phases.join(", "))).unwrap();
+
+        write_table_header(html, &["Feeder", "Manufacturer", "Mpn", "Quantity", "Phase quantities"]);
+        for item in shared_load_out.items.iter() {
+            let phase_quantities = item.phase_quantities.iter()
+                .map(|(reference, quantity)| format!("{}: {}", reference, quantity))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            write_table_row(html, &[
+                escape_html(&item.feeder_reference),
+                escape_html(&item.manufacturer),
+                escape_html(&item.mpn),
+                item.quantity.to_string(),
+                escape_html(&phase_quantities),
+            ]);
+        }
+        writeln!(html, "</table>").unwrap();
+    }
+}
+
+fn write_placement_overrides(html: &mut String, report: &ProjectReport) {
+    if report.placement_overrides.is_empty() {
+        return
+    }
+
+    writeln!(html, "<h2>Placement overrides</h2>").unwrap();
+    write_table_header(html, &["Object path", "Override"]);
+    for placement_override in report.placement_overrides.iter() {
+        write_table_row(html, &[
+            escape_html(&placement_override.object_path.to_string()),
+            variant_name(&placement_override.place_override),
+        ]);
+    }
+    writeln!(html, "</table>").unwrap();
+}
+
+fn write_issues(html: &mut String, issues: &[ReportIssue]) {
+    writeln!(html, "<h2>Issues</h2>").unwrap();
+    if issues.is_empty() {
+        writeln!(html, "<p>No issues.</p>").unwrap();
+        return
+    }
+
+    write_table_header(html, &["Id", "Severity", "Message", "Kind", "Details", "Resolution"]);
+    for report_issue in issues.iter() {
+        let severity = variant_name(&report_issue.issue.severity);
+        let resolution = report_issue.resolution.as_ref()
+            .map(|resolution| format!("{}: {}", variant_name(&resolution.status), escape_html(&resolution.reason)))
+            .unwrap_or_default();
+
+        writeln!(html, "<tr class=\"{}\">{}</tr>", severity, [
+            escape_html(&report_issue.id),
+            severity.clone(),
+            escape_html(&report_issue.issue.message),
+            variant_name(&report_issue.issue.kind),
+            escape_html(&build_variant_details(&report_issue.issue.kind)),
+            resolution,
+        ].iter().map(|cell| format!("<td>{}</td>", cell)).collect::<String>()).unwrap();
+    }
+    writeln!(html, "</table>").unwrap();
+}
+
+fn write_cost_estimate(html: &mut String, report: &ProjectReport) {
+    let Some(cost_estimate) = &report.cost_estimate else {
+        return
+    };
+
+    let format_cost = |cost: &BTreeMap<String, Decimal>| cost.iter()
+        .map(|(currency, amount)| format!("{} {}", amount, currency))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    writeln!(html, "<h2>Cost estimate</h2>").unwrap();
+    write_table_header(html, &["Phase", "Cost"]);
+    for phase_cost_estimate in cost_estimate.phases.iter() {
+        write_table_row(html, &[escape_html(&phase_cost_estimate.phase_name), escape_html(&format_cost(&phase_cost_estimate.cost))]);
+    }
+    write_table_row(html, &["Total".to_string(), escape_html(&format_cost(&cost_estimate.total))]);
+    writeln!(html, "</table>").unwrap();
+
+    if !cost_estimate.unpriced_parts.is_empty() {
+        let unpriced_parts = cost_estimate.unpriced_parts.iter().map(|part| part.to_string()).collect::<Vec<_>>();
+        writeln!(html, "<p>Unpriced parts: {}</p>", escape_html(&unpriced_parts.join(", "))).unwrap();
+    }
+}
+
+fn write_time_estimate(html: &mut String, report: &ProjectReport) {
+    let Some(time_estimate) = &report.time_estimate else {
+        return
+    };
+
+    writeln!(html, "<h2>Time estimate</h2>").unwrap();
+    write_table_header(html, &["Phase", "Feeders", "Placements", "Setup", "Placement", "Reflow", "Total", "Remaining"]);
+    for phase_estimate in time_estimate.phases.iter() {
+        let placement_time = phase_estimate.placement_time
+            .map(|placement_time| FormattedDuration(placement_time).to_string())
+            .unwrap_or("unknown".to_string());
+
+        write_table_row(html, &[
+            escape_html(&phase_estimate.phase.to_string()),
+            phase_estimate.feeders.to_string(),
+            format!("{}/{}", phase_estimate.placed, phase_estimate.placements),
+            FormattedDuration(phase_estimate.setup_time).to_string(),
+            placement_time,
+            FormattedDuration(phase_estimate.reflow_time).to_string(),
+            FormattedDuration(phase_estimate.total_time).to_string(),
+            FormattedDuration(phase_estimate.remaining_time).to_string(),
+        ]);
+    }
+    write_table_row(html, &[
+        "Total".to_string(), String::new(), String::new(), String::new(), String::new(), String::new(),
+        FormattedDuration(time_estimate.total_time).to_string(),
+        FormattedDuration(time_estimate.remaining_time).to_string(),
+    ]);
+    writeln!(html, "</table>").unwrap();
+}
+
+fn write_table_header(html: &mut String, headers: &[&str]) {
+    writeln!(html, "<table>").unwrap();
+    writeln!(html, "<tr>{}</tr>", headers.iter().map(|header| format!("<th>{}</th>", header)).collect::<String>()).unwrap();
+}
+
+/// The cells must already be escaped.
+fn write_table_row(html: &mut String, cells: &[String]) {
+    writeln!(html, "<tr>{}</tr>", cells.iter().map(|cell| format!("<td>{}</td>", cell)).collect::<String>()).unwrap();
+}
+
+/// The name of an enum variant, as it appears in the JSON report, e.g. 'Complete' or 'UnassignedPlacement'.
+fn variant_name<T: Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(Value::String(name)) => name,
+        Ok(Value::Object(map)) => map.keys().next().cloned().unwrap_or_default(),
+        _ => String::new(),
+    }
+}
+
+/// The fields of an enum variant, as they appear in the JSON report, e.g. `object_path: 'panel=1::unit=1::ref_des=R1'`.
+fn build_variant_details<T: Serialize>(value: &T) -> String {
+    let Ok(Value::Object(map)) = serde_json::to_value(value) else {
+        return String::new()
+    };
+
+    map.values()
+        .filter_map(Value::as_object)
+        .flat_map(|fields| fields.iter())
+        .map(|(name, value)| match value {
+            Value::String(value) => format!("{}: '{}'", name, value),
+            value => format!("{}: {}", name, value),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn escape_html(value: &str) -> String {
+    value.replace('&', "&amp;").replace('"', "&quot;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::str::FromStr;
+    use pnp::object_path::ObjectPath;
+    use crate::report::render::render_html;
+    use crate::report::{IssueKind, IssueSeverity, PhaseLoadOutAssignmentItem, PhaseOperation, PhaseOverview, PhaseSpecification, PhaseStatus, ProjectReport, ProjectReportIssue, ReportIssue};
+
+    #[test]
+    pub fn render_report_as_html() {
+        // given
+        let report = ProjectReport {
+            name: "job<1>".to_string(),
+            phase_overviews: vec![PhaseOverview {
+                phase_name: "top_1".to_string(),
+                status: PhaseStatus::Incomplete,
+                process: "pnp".to_string(),
+                operations_overview: vec![],
+                tags: BTreeMap::new(),
+                pcbs: vec!["panel_a".to_string()],
+            }],
+            phase_specifications: vec![PhaseSpecification {
+                phase_name: "top_1".to_string(),
+                operations: vec![PhaseOperation::PlaceComponents {}],
+                load_out_assignments: vec![PhaseLoadOutAssignmentItem {
+                    feeder_reference: "FEEDER_1".to_string(),
+                    manufacturer: "RES_MFR1".to_string(),
+                    mpn: "RES1".to_string(),
+                    value: Some("10K".to_string()),
+                    description: None,
+                    package: None,
+                    quantity: 2,
+                    alternates: vec![],
+                    loaded_alternate: None,
+                }],
+            }],
+            issues: vec![ReportIssue {
+                id: "3f2a9c1b".to_string(),
+                issue: ProjectReportIssue {
+                    message: "A placement has not been assigned to a phase".to_string(),
+                    severity: IssueSeverity::Warning,
+                    kind: IssueKind::UnassignedPlacement { object_path: ObjectPath::from_str("panel=1::unit=1::ref_des=R1").unwrap() },
+                },
+                resolution: None,
+            }],
+            ..ProjectReport::default()
+        };
+
+        // when
+        let html = render_html(&report);
+
+        // then
+        assert!(html.contains("<title>Report - job&lt;1&gt;</title>"));
+        assert!(html.contains("<p>Status: Incomplete</p>"));
+        assert!(html.contains("<tr><td>top_1</td><td>pnp</td><td>Incomplete</td><td>panel_a</td><td></td><td></td></tr>"));
+        assert!(html.contains("<li>Place components</li>"));
+        assert!(html.contains("<tr><td>FEEDER_1</td><td>RES_MFR1</td><td>RES1</td><td>10K</td><td></td><td>2</td><td></td><td></td></tr>"));
+
+        // and
+        assert!(html.contains(concat!(
+            "<tr class=\"Warning\"><td>3f2a9c1b</td><td>Warning</td><td>A placement has not been assigned to a phase</td>",
+            "<td>UnassignedPlacement</td><td>object_path: 'panel=1::unit=1::ref_des=R1'</td><td></td></tr>",
+        )));
+
+        // and no cost estimate without a price list
+        assert!(!html.contains("Cost estimate"));
+    }
+}
@@ -0,0 +1,247 @@
+//! Barcode driven recording of production activities, e.g. using a handheld scanner at the machine.
+//!
+//! A scan session is for a single phase, the operator scans the barcode of a PCB unit to select it, then the barcode of
+//! a feeder to record the placements of the feeder's part on the unit as placed.  Scanning the barcode of an operation
+//! records the operation as complete.
+//!
+//! Barcodes are resolved using the barcode mappings, barcodes that are not mapped are used as-is, i.e. a barcode of
+//! 'panel=1::unit=1' selects that unit, a barcode of 'FEEDER_1' selects that feeder and a barcode of 'ReflowComponents'
+//! selects that operation.
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use thiserror::Error;
+use pnp::load_out::LoadOutItem;
+use pnp::object_path::ObjectPath;
+use pnp::part::Part;
+use crate::process::ProcessOperationKind;
+use crate::project::Project;
+use crate::reference::Reference;
+
+/// What a barcode identifies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanTarget {
+    /// A PCB unit, e.g. 'panel=1::unit=1'.
+    Unit(ObjectPath),
+    /// A feeder reference, e.g. 'FEEDER_1'.
+    Feeder(String),
+    Operation(ProcessOperationKind),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScanAction {
+    UnitSelected { unit_path: ObjectPath },
+    /// The placements are the unplaced placements of the feeder's part, on the selected unit.
+    PlaceFeeder { feeder_reference: String, part: Part, placements: Vec<ObjectPath> },
+    CompleteOperation { operation: ProcessOperationKind },
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ScanError {
+    #[error("Unknown barcode. barcode: '{0}'")]
+    UnknownBarcode(String),
+
+    #[error("No unit selected, scan a unit first. feeder: '{0}'")]
+    NoUnitSelected(String),
+
+    #[error("Unknown feeder. phase: '{phase}', feeder: '{feeder_reference}'")]
+    UnknownFeeder { phase: Reference, feeder_reference: String },
+
+    #[error("Unit has no placements in the phase. phase: '{phase}', unit: '{unit_path}'")]
+    UnitNotInPhase { phase: Reference, unit_path: ObjectPath },
+
+    #[error("No unplaced placements. phase: '{phase}', unit: '{unit_path}', feeder: '{feeder_reference}', part: {part:?}")]
+    NoUnplacedPlacements { phase: Reference, unit_path: Box<ObjectPath>, feeder_reference: String, part: Part },
+
+    #[error("Unknown operation for phase. phase: '{phase}', operation: '{operation}'")]
+    UnknownOperation { phase: Reference, operation: ProcessOperationKind },
+}
+
+/// Mapped barcodes take precedence, then unit paths, then the feeder references of the load-out, then the names of the
+/// built-in operations.  Custom operations must be mapped.
+pub fn resolve_barcode(barcode: &str, mappings: &BTreeMap<String, ScanTarget>, load_out_items: &[LoadOutItem]) -> Result<ScanTarget, ScanError> {
+    let barcode = barcode.trim();
+
+    if let Some(target) = mappings.get(barcode) {
+        return Ok(target.clone())
+    }
+
+    if let Ok(object_path) = ObjectPath::from_str(barcode) {
+        if object_path.unit_index().is_some() {
+            return Ok(ScanTarget::Unit(object_path.pcb_unit()))
+        }
+    }
+
+    if load_out_items.iter().any(|item| item.reference.eq(barcode)) {
+        return Ok(ScanTarget::Feeder(barcode.to_string()))
+    }
+
+    match ProcessOperationKind::from_str(barcode) {
+        Ok(operation) if !operation.is_custom() => Ok(ScanTarget::Operation(operation)),
+        _ => Err(ScanError::UnknownBarcode(barcode.to_string())),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ScanSession {
+    pub phase: Reference,
+    /// The most recently scanned unit.
+    pub unit_path: Option<ObjectPath>,
+}
+
+impl ScanSession {
+    pub fn new(phase: Reference) -> Self {
+        Self { phase, unit_path: None }
+    }
+
+    /// Applies a scan to the session, the returned action is recorded by the caller.
+    ///
+    /// The selected unit is kept when a scan fails, so the operator can just scan the next barcode.
+    pub fn scan(&mut self, project: &Project, load_out_items: &[LoadOutItem], target: ScanTarget) -> Result<ScanAction, ScanError> {
+        match target {
+            ScanTarget::Unit(unit_path) => {
+                let in_phase = project.placements.values()
+                    .any(|placement_state| placement_state.unit_path.eq(&unit_path) && placement_state.phase.as_ref() == Some(&self.phase));
+                if !in_phase {
+                    return Err(ScanError::UnitNotInPhase { phase: self.phase.clone(), unit_path })
+                }
+
+                self.unit_path = Some(unit_path.clone());
+
+                Ok(ScanAction::UnitSelected { unit_path })
+            },
+            ScanTarget::Feeder(feeder_reference) => {
+                let unit_path = self.unit_path.as_ref()
+                    .ok_or(ScanError::NoUnitSelected(feeder_reference.clone()))?;
+
+                let load_out_item = load_out_items.iter()
+                    .find(|item| item.reference.eq(&feeder_reference))
+                    .ok_or(ScanError::UnknownFeeder { phase: self.phase.clone(), feeder_reference: feeder_reference.clone() })?;
+                let part = Part::new(load_out_item.manufacturer.clone(), load_out_item.mpn.clone());
+
+                let placements: Vec<ObjectPath> = project.placements.iter()
+                    .filter(|(_object_path, placement_state)| {
+                        placement_state.unit_path.eq(unit_path)
+                            && placement_state.phase.as_ref() == Some(&self.phase)
+                            && placement_state.placement.part.eq(&part)
+                            && placement_state.place()
+                            && !placement_state.placed
+                    })
+                    .map(|(object_path, _placement_state)| object_path.clone())
+                    .collect();
+
+                if placements.is_empty() {
+                    return Err(ScanError::NoUnplacedPlacements { phase: self.phase.clone(), unit_path: Box::new(unit_path.clone()), feeder_reference, part })
+                }
+
+                Ok(ScanAction::PlaceFeeder { feeder_reference, part, placements })
+            },
+            ScanTarget::Operation(operation) => {
+                let has_operation = project.phase_states.get(&self.phase)
+                    .is_some_and(|phase_state| phase_state.operation_state.contains_key(&operation));
+                if !has_operation {
+                    return Err(ScanError::UnknownOperation { phase: self.phase.clone(), operation })
+                }
+
+                Ok(ScanAction::CompleteOperation { operation })
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::str::FromStr;
+    use pnp::load_out::LoadOutItem;
+    use pnp::object_path::ObjectPath;
+    use pnp::part::Part;
+    use pnp::pcb::PcbSide;
+    use crate::process::ProcessOperationKind;
+    use crate::project::Project;
+    use crate::reference::Reference;
+    use crate::scan::{resolve_barcode, ScanAction, ScanError, ScanSession, ScanTarget};
+    use crate::test::project_builder::{PlacementStateBuilder, ProjectBuilder};
+
+    fn build_project() -> Project {
+        let res1 = Part::new("RES_MFR1".to_string(), "RES1".to_string());
+
+        ProjectBuilder::new()
+            .with_phase("top_1", "pnp", "load_out_1.csv", PcbSide::Top)
+            .with_placements([(1, "R1", false), (1, "R2", true), (2, "R1", false)]
+                .map(|(unit, ref_des, placed)| PlacementStateBuilder::new(unit, ref_des, &res1).with_placed(placed).with_phase("top_1")))
+            .build()
+    }
+
+    #[test]
+    pub fn resolve_barcodes() {
+        // given
+        let mappings = BTreeMap::from([
+            ("0001".to_string(), ScanTarget::Unit(ObjectPath::from_str("panel=1::unit=2").unwrap())),
+            ("OP-MANUAL".to_string(), ScanTarget::Operation(ProcessOperationKind::ManuallySolderComponents)),
+        ]);
+        let load_out_items = vec![LoadOutItem::new("FEEDER_1".to_string(), "RES_MFR1".to_string(), "RES1".to_string())];
+
+        // expect
+        assert_eq!(resolve_barcode("0001", &mappings, &load_out_items), Ok(ScanTarget::Unit(ObjectPath::from_str("panel=1::unit=2").unwrap())));
+        assert_eq!(resolve_barcode("OP-MANUAL", &mappings, &load_out_items), Ok(ScanTarget::Operation(ProcessOperationKind::ManuallySolderComponents)));
+        assert_eq!(resolve_barcode("panel=1::unit=1\r", &mappings, &load_out_items), Ok(ScanTarget::Unit(ObjectPath::from_str("panel=1::unit=1").unwrap())));
+        assert_eq!(resolve_barcode("FEEDER_1", &mappings, &load_out_items), Ok(ScanTarget::Feeder("FEEDER_1".to_string())));
+        assert_eq!(resolve_barcode("ReflowComponents", &mappings, &load_out_items), Ok(ScanTarget::Operation(ProcessOperationKind::ReflowComponents)));
+        assert_eq!(resolve_barcode("FEEDER_2", &mappings, &load_out_items), Err(ScanError::UnknownBarcode("FEEDER_2".to_string())));
+    }
+
+    #[test]
+    pub fn scan_unit_then_feeder() {
+        // given
+        let project = build_project();
+        let load_out_items = vec![LoadOutItem::new("FEEDER_1".to_string(), "RES_MFR1".to_string(), "RES1".to_string())];
+        let mut session = ScanSession::new(Reference::from_str("top_1").unwrap());
+
+        // expect a unit to be required
+        assert_eq!(
+            session.scan(&project, &load_out_items, ScanTarget::Feeder("FEEDER_1".to_string())),
+            Err(ScanError::NoUnitSelected("FEEDER_1".to_string()))
+        );
+
+        // when
+        let action = session.scan(&project, &load_out_items, ScanTarget::Unit(ObjectPath::from_str("panel=1::unit=1").unwrap()));
+
+        // then
+        assert_eq!(action, Ok(ScanAction::UnitSelected { unit_path: ObjectPath::from_str("panel=1::unit=1").unwrap() }));
+
+        // when
+        let action = session.scan(&project, &load_out_items, ScanTarget::Feeder("FEEDER_1".to_string()));
+
+        // then only the unplaced placements of the selected unit are placed
+        assert_eq!(action, Ok(ScanAction::PlaceFeeder {
+            feeder_reference: "FEEDER_1".to_string(),
+            part: Part::new("RES_MFR1".to_string(), "RES1".to_string()),
+            placements: vec![ObjectPath::from_str("panel=1::unit=1::ref_des=R1").unwrap()],
+        }));
+
+        // and
+        assert!(matches!(
+            session.scan(&project, &load_out_items, ScanTarget::Unit(ObjectPath::from_str("panel=1::unit=3").unwrap())),
+            Err(ScanError::UnitNotInPhase { .. })
+        ));
+        assert_eq!(session.unit_path, Some(ObjectPath::from_str("panel=1::unit=1").unwrap()));
+    }
+
+    #[test]
+    pub fn scan_operation() {
+        // given
+        let project = build_project();
+        let mut session = ScanSession::new(Reference::from_str("top_1").unwrap());
+
+        // expect
+        assert_eq!(
+            session.scan(&project, &[], ScanTarget::Operation(ProcessOperationKind::AutomatedPnp)),
+            Ok(ScanAction::CompleteOperation { operation: ProcessOperationKind::AutomatedPnp })
+        );
+        assert!(matches!(
+            session.scan(&project, &[], ScanTarget::Operation(ProcessOperationKind::ManuallySolderComponents)),
+            Err(ScanError::UnknownOperation { .. })
+        ));
+    }
+}
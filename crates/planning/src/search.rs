@@ -0,0 +1,168 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+use pnp::load_out::LoadOutItem;
+use crate::project::Project;
+use crate::reference::Reference;
+
+/// The groups of search results, in the order they are presented.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SearchGroup {
+    Placements,
+    Phases,
+    Parts,
+    LoadOutItems,
+}
+
+impl Display for SearchGroup {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SearchGroup::Placements => write!(f, "Placements"),
+            SearchGroup::Phases => write!(f, "Phases"),
+            SearchGroup::Parts => write!(f, "Parts"),
+            SearchGroup::LoadOutItems => write!(f, "Load-out items"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchMatch {
+    /// Identifies the matched item, e.g. the object path of a placement, or the phase and feeder of a load-out item.
+    pub key: String,
+    /// The name of the matched field, e.g. 'ref_des'.
+    pub field: &'static str,
+    pub text: String,
+    /// Byte ranges of `text` that match the query.
+    pub spans: Vec<Range<usize>>,
+}
+
+impl SearchMatch {
+    /// The text with each matching span surrounded by the markers, e.g. 'R[1]0' for 'R10' and the query '1'.
+    pub fn highlighted(&self, start_marker: &str, end_marker: &str) -> String {
+        let mut highlighted = String::new();
+        let mut position = 0;
+
+        for span in self.spans.iter() {
+            highlighted.push_str(&self.text[position..span.start]);
+            highlighted.push_str(start_marker);
+            highlighted.push_str(&self.text[span.clone()]);
+            highlighted.push_str(end_marker);
+            position = span.end;
+        }
+        highlighted.push_str(&self.text[position..]);
+
+        highlighted
+    }
+}
+
+/// Searches the placements, phases, parts and load-out items of a project, matching is case-insensitive.
+///
+/// Groups without matches are omitted.
+pub fn search_project(project: &Project, phase_load_out_items_map: &BTreeMap<Reference, Vec<LoadOutItem>>, query: &str) -> BTreeMap<SearchGroup, Vec<SearchMatch>> {
+    let mut results: BTreeMap<SearchGroup, Vec<SearchMatch>> = BTreeMap::new();
+
+    if query.is_empty() {
+        return results
+    }
+
+    let mut add_match = |group: SearchGroup, key: String, field: &'static str, text: String| {
+        let spans = find_spans(&text, query);
+        if !spans.is_empty() {
+            results.entry(group).or_default().push(SearchMatch { key, field, text, spans });
+        }
+    };
+
+    for (object_path, placement_state) in project.placements.iter() {
+        let part = &placement_state.placement.part;
+        add_match(SearchGroup::Placements, object_path.to_string(), "ref_des", placement_state.placement.ref_des.clone());
+        add_match(SearchGroup::Placements, object_path.to_string(), "manufacturer", part.manufacturer.clone());
+        add_match(SearchGroup::Placements, object_path.to_string(), "mpn", part.mpn.clone());
+    }
+
+    for reference in project.phase_orderings.iter() {
+        add_match(SearchGroup::Phases, reference.to_string(), "reference", reference.to_string());
+    }
+
+    for part in project.part_states.keys() {
+        let key = format!("{} {}", part.manufacturer, part.mpn);
+        add_match(SearchGroup::Parts, key.clone(), "manufacturer", part.manufacturer.clone());
+        add_match(SearchGroup::Parts, key, "mpn", part.mpn.clone());
+    }
+
+    for (reference, load_out_items) in phase_load_out_items_map.iter() {
+        for load_out_item in load_out_items.iter() {
+            let key = format!("{} {}", reference, load_out_item.reference);
+            add_match(SearchGroup::LoadOutItems, key.clone(), "feeder", load_out_item.reference.clone());
+            add_match(SearchGroup::LoadOutItems, key.clone(), "manufacturer", load_out_item.manufacturer.clone());
+            add_match(SearchGroup::LoadOutItems, key, "mpn", load_out_item.mpn.clone());
+        }
+    }
+
+    results
+}
+
+/// Finds the non-overlapping, case-insensitive, matches of the query.
+///
+/// Only ASCII characters are folded, so that the byte ranges of the lowercase text are also valid for the original text.
+fn find_spans(text: &str, query: &str) -> Vec<Range<usize>> {
+    let text = text.to_ascii_lowercase();
+    let query = query.to_ascii_lowercase();
+
+    text.match_indices(&query)
+        .map(|(index, matched)| index..index + matched.len())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::ops::Range;
+    use std::str::FromStr;
+    use rust_decimal_macros::dec;
+    use pnp::load_out::LoadOutItem;
+    use pnp::part::Part;
+    use crate::part::PartState;
+    use crate::reference::Reference;
+    use crate::search::{find_spans, search_project, SearchGroup, SearchMatch};
+    use crate::test::project_builder::{PlacementStateBuilder, ProjectBuilder};
+
+    #[test]
+    pub fn search() {
+        // given
+        let part = Part::new("RES_MFR1".to_string(), "RES1".to_string());
+        let mut project = ProjectBuilder::new()
+            .with_placements([PlacementStateBuilder::new(1, "R1", &part).with_position(dec!(10), dec!(20), dec!(0))])
+            .build();
+        project.part_states.insert(part, PartState::default());
+
+        // and
+        let phase_load_out_items_map = BTreeMap::from([
+            (Reference::from_str("top_1").unwrap(), vec![LoadOutItem::new("FEEDER_1".to_string(), "RES_MFR1".to_string(), "RES1".to_string())]),
+        ]);
+
+        // when
+        let results = search_project(&project, &phase_load_out_items_map, "res1");
+
+        // then
+        assert_eq!(results.keys().copied().collect::<Vec<_>>(), vec![SearchGroup::Placements, SearchGroup::Parts, SearchGroup::LoadOutItems]);
+
+        // and
+        assert_eq!(results[&SearchGroup::Placements], vec![SearchMatch {
+            key: "panel=1::unit=1::ref_des=R1".to_string(),
+            field: "mpn",
+            text: "RES1".to_string(),
+            spans: vec![Range { start: 0, end: 4 }],
+        }]);
+
+        // and
+        let load_out_match = &results[&SearchGroup::LoadOutItems][0];
+        assert_eq!(load_out_match.key, "top_1 FEEDER_1");
+        assert_eq!(load_out_match.highlighted("[", "]"), "[RES1]");
+    }
+
+    #[test]
+    pub fn spans() {
+        assert_eq!(find_spans("CAP_MFR1 cap2", "cap"), vec![0..3, 9..12]);
+        assert!(find_spans("R10", "x").is_empty());
+    }
+}
@@ -0,0 +1,217 @@
+//! Detached ed25519 signatures for generated artifacts.
+//!
+//! Each signed file gets a sibling `<file>.sig` containing the hex encoded signature of the file's contents.
+//! A manifest, listing each artifact and its SHA-256 hash, is written and signed too so that missing or modified
+//! artifacts can be detected, files that are not listed in the manifest are not verified.
+//!
+//! Key files contain the hex encoded 32 byte ed25519 secret key (signing) or public key (verifying).
+
+use std::fs;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use tracing::{info, trace};
+
+#[derive(Error, Debug)]
+pub enum SigningError {
+    #[error("Unable to read key. path: {path:?}, cause: {reason:}")]
+    UnableToReadKey { path: PathBuf, reason: std::io::Error },
+
+    #[error("Invalid key. path: {path:?}")]
+    InvalidKey { path: PathBuf },
+
+    #[error("IO error. path: {path:?}, cause: {reason:}")]
+    IoError { path: PathBuf, reason: std::io::Error },
+
+    #[error("Invalid manifest. path: {path:?}, cause: {reason:}")]
+    InvalidManifest { path: PathBuf, reason: serde_json::Error },
+
+    #[error("Missing signature. path: {path:?}")]
+    MissingSignature { path: PathBuf },
+
+    #[error("Invalid signature. path: {path:?}")]
+    InvalidSignature { path: PathBuf },
+
+    #[error("Artifact hash mismatch. path: {path:?}")]
+    HashMismatch { path: PathBuf },
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct ArtifactManifest {
+    pub artifacts: Vec<ArtifactManifestEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ArtifactManifestEntry {
+    /// File name, relative to the manifest
+    pub file: String,
+    pub sha256: String,
+}
+
+pub fn load_signing_key(path: &Path) -> Result<SigningKey, SigningError> {
+    let bytes = load_key_bytes(path)?;
+    Ok(SigningKey::from_bytes(&bytes))
+}
+
+pub fn load_verifying_key(path: &Path) -> Result<VerifyingKey, SigningError> {
+    let bytes = load_key_bytes(path)?;
+    VerifyingKey::from_bytes(&bytes).map_err(|_err| SigningError::InvalidKey { path: path.to_path_buf() })
+}
+
+fn load_key_bytes(path: &Path) -> Result<[u8; 32], SigningError> {
+    let content = fs::read_to_string(path).map_err(|reason| SigningError::UnableToReadKey { path: path.to_path_buf(), reason })?;
+
+    let bytes = hex::decode(content.trim()).map_err(|_err| SigningError::InvalidKey { path: path.to_path_buf() })?;
+
+    bytes.try_into().map_err(|_err| SigningError::InvalidKey { path: path.to_path_buf() })
+}
+
+pub fn build_signature_path(path: &Path) -> PathBuf {
+    let mut signature_path = path.as_os_str().to_owned();
+    signature_path.push(".sig");
+    PathBuf::from(signature_path)
+}
+
+pub fn build_manifest_path(name: &str, path: &Path) -> PathBuf {
+    path.join(format!("{}_manifest.json", name))
+}
+
+/// Writes a detached signature for the file, returns the path of the signature.
+pub fn sign_file(signing_key: &SigningKey, path: &Path) -> Result<PathBuf, SigningError> {
+    let content = fs::read(path).map_err(|reason| SigningError::IoError { path: path.to_path_buf(), reason })?;
+
+    let signature = signing_key.sign(&content);
+
+    let signature_path = build_signature_path(path);
+    let mut signature_file = File::create(&signature_path).map_err(|reason| SigningError::IoError { path: signature_path.clone(), reason })?;
+    signature_file.write_all(hex::encode(signature.to_bytes()).as_bytes())
+        .map_err(|reason| SigningError::IoError { path: signature_path.clone(), reason })?;
+
+    trace!("Signed file. path: {:?}, signature_path: {:?}", path, signature_path);
+
+    Ok(signature_path)
+}
+
+pub fn verify_file(verifying_key: &VerifyingKey, path: &Path) -> Result<(), SigningError> {
+    let content = fs::read(path).map_err(|reason| SigningError::IoError { path: path.to_path_buf(), reason })?;
+
+    let signature_path = build_signature_path(path);
+    let signature_content = fs::read_to_string(&signature_path)
+        .map_err(|_err| SigningError::MissingSignature { path: path.to_path_buf() })?;
+
+    let signature_bytes: [u8; 64] = hex::decode(signature_content.trim()).ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| SigningError::InvalidSignature { path: path.to_path_buf() })?;
+
+    let signature = Signature::from_bytes(&signature_bytes);
+
+    verifying_key.verify(&content, &signature).map_err(|_err| SigningError::InvalidSignature { path: path.to_path_buf() })
+}
+
+/// Signs each artifact and writes a signed manifest of the artifacts, returns the path of the manifest.
+pub fn sign_artifacts(signing_key: &SigningKey, path: &Path, name: &str, artifact_paths: &[PathBuf]) -> Result<PathBuf, SigningError> {
+    let mut manifest = ArtifactManifest::default();
+
+    for artifact_path in artifact_paths.iter() {
+        let content = fs::read(artifact_path).map_err(|reason| SigningError::IoError { path: artifact_path.clone(), reason })?;
+
+        let file = artifact_path.strip_prefix(path).unwrap_or(artifact_path).to_string_lossy().to_string();
+
+        manifest.artifacts.push(ArtifactManifestEntry { file, sha256: hex::encode(Sha256::digest(&content)) });
+
+        sign_file(signing_key, artifact_path)?;
+    }
+
+    let manifest_path = build_manifest_path(name, path);
+    let manifest_content = serde_json::to_string_pretty(&manifest).unwrap();
+    fs::write(&manifest_path, manifest_content + "\n").map_err(|reason| SigningError::IoError { path: manifest_path.clone(), reason })?;
+
+    sign_file(signing_key, &manifest_path)?;
+
+    info!("Signed artifacts. manifest: {:?}, count: {}", manifest_path, manifest.artifacts.len());
+
+    Ok(manifest_path)
+}
+
+/// Verifies the manifest and each artifact listed in the manifest, returns the paths of the verified artifacts.
+pub fn verify_artifacts(verifying_key: &VerifyingKey, path: &Path, name: &str) -> Result<Vec<PathBuf>, SigningError> {
+    let manifest_path = build_manifest_path(name, path);
+
+    verify_file(verifying_key, &manifest_path)?;
+
+    let manifest_content = fs::read(&manifest_path).map_err(|reason| SigningError::IoError { path: manifest_path.clone(), reason })?;
+    let manifest: ArtifactManifest = serde_json::from_slice(&manifest_content)
+        .map_err(|reason| SigningError::InvalidManifest { path: manifest_path.clone(), reason })?;
+
+    let mut verified_paths = vec![];
+
+    for entry in manifest.artifacts.iter() {
+        let artifact_path = path.join(&entry.file);
+
+        let content = fs::read(&artifact_path).map_err(|reason| SigningError::IoError { path: artifact_path.clone(), reason })?;
+        if hex::encode(Sha256::digest(&content)) != entry.sha256 {
+            return Err(SigningError::HashMismatch { path: artifact_path })
+        }
+
+        verify_file(verifying_key, &artifact_path)?;
+
+        trace!("Verified artifact. path: {:?}", artifact_path);
+        verified_paths.push(artifact_path);
+    }
+
+    info!("Verified artifacts. manifest: {:?}, count: {}", manifest_path, verified_paths.len());
+
+    Ok(verified_paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use ed25519_dalek::SigningKey;
+    use crate::signing::{sign_artifacts, verify_artifacts, SigningError};
+
+    #[test]
+    pub fn sign_and_verify_artifacts() {
+        // given
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().to_path_buf();
+        let artifact_path = path.join("top_1_placements.csv");
+        fs::write(&artifact_path, "\"ObjectPath\"\n").unwrap();
+
+        // and
+        let signing_key = SigningKey::from_bytes(&[1; 32]);
+
+        // when
+        sign_artifacts(&signing_key, &path, "job1", std::slice::from_ref(&artifact_path)).unwrap();
+        let result = verify_artifacts(&signing_key.verifying_key(), &path, "job1");
+
+        // then
+        assert_eq!(result.unwrap(), vec![artifact_path]);
+    }
+
+    #[test]
+    pub fn verify_detects_modified_artifact() {
+        // given
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().to_path_buf();
+        let artifact_path = path.join("top_1_placements.csv");
+        fs::write(&artifact_path, "\"ObjectPath\"\n").unwrap();
+
+        // and
+        let signing_key = SigningKey::from_bytes(&[1; 32]);
+        sign_artifacts(&signing_key, &path, "job1", std::slice::from_ref(&artifact_path)).unwrap();
+
+        // and
+        fs::write(&artifact_path, "\"ObjectPath\"\n\"modified\"\n").unwrap();
+
+        // when
+        let result = verify_artifacts(&signing_key.verifying_key(), &path, "job1");
+
+        // then
+        assert!(matches!(result, Err(SigningError::HashMismatch { .. })));
+    }
+}
@@ -0,0 +1,320 @@
+//! Read-only summaries of where a project stands, for the `status` and `inspect-phase` commands.
+
+use std::fmt::{Display, Formatter};
+use pnp::object_path::ObjectPath;
+use pnp::part::Part;
+use pnp::pcb::PcbSide;
+use crate::estimation::{self, FormattedDuration, PhaseEstimate, ProjectEstimate};
+use crate::phase::{Phase, PhaseError};
+use crate::placement::{PlacementState, PlacementStatus};
+use crate::process::{ProcessName, ProcessOperationKind, ProcessOperationStatus};
+use crate::project::{find_phase_placement_states, Project};
+use crate::reference::Reference;
+
+#[derive(Debug, PartialEq)]
+pub struct ProjectStatus {
+    pub name: String,
+    /// In the order of the phases.
+    pub phases: Vec<PhaseStatus>,
+    /// Count of placements that are to be placed.
+    pub placements: usize,
+    pub placed: usize,
+    /// Placements, that are to be placed, that have not been assigned to a phase.
+    pub unassigned_placements: Vec<ObjectPath>,
+    /// `None` if the project has no estimation parameters.
+    pub estimate: Option<ProjectEstimate>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct PhaseStatus {
+    pub reference: Reference,
+    pub process: ProcessName,
+    pub pcb_side: PcbSide,
+    pub load_out_source: String,
+    pub tags: Vec<String>,
+    pub operations: Vec<(ProcessOperationKind, ProcessOperationStatus)>,
+    /// All the placements of the phase, including those that are not to be placed.
+    pub placements: Vec<PhasePlacementStatus>,
+    /// `None` if the project has no estimation parameters.
+    pub estimate: Option<PhaseEstimate>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct PhasePlacementStatus {
+    pub object_path: ObjectPath,
+    pub part: Part,
+    pub place: bool,
+    pub placed: bool,
+}
+
+impl PhaseStatus {
+    /// Count of placements that are to be placed.
+    pub fn placements_to_place(&self) -> usize {
+        self.placements.iter().filter(|placement| placement.place).count()
+    }
+
+    pub fn placed(&self) -> usize {
+        self.placements.iter().filter(|placement| placement.place && placement.placed).count()
+    }
+}
+
+/// The operation states should be updated before building the status, see `project::update_phase_operation_states`.
+pub fn build_project_status(project: &Project) -> ProjectStatus {
+    let phases = project.phase_orderings.iter()
+        .map(|reference| build_phase_status_for_phase(project, project.phases.get(reference).unwrap()))
+        .collect();
+
+    let placements_to_place: Vec<(&ObjectPath, &PlacementState)> = project.placements.iter()
+        .filter(|(_object_path, placement_state)| placement_state.place() && matches!(placement_state.status, PlacementStatus::Known))
+        .collect();
+
+    ProjectStatus {
+        name: project.name.clone(),
+        phases,
+        placements: placements_to_place.len(),
+        placed: placements_to_place.iter().filter(|(_object_path, placement_state)| placement_state.placed).count(),
+        unassigned_placements: placements_to_place.iter()
+            .filter(|(_object_path, placement_state)| placement_state.phase.is_none())
+            .map(|(object_path, _placement_state)| (*object_path).clone())
+            .collect(),
+        estimate: estimation::build_project_estimate(project),
+    }
+}
+
+pub fn build_phase_status(project: &Project, phase_reference: &Reference) -> Result<PhaseStatus, PhaseError> {
+    let phase = project.phases.get(phase_reference)
+        .ok_or(PhaseError::UnknownPhase(phase_reference.clone()))?;
+
+    Ok(build_phase_status_for_phase(project, phase))
+}
+
+fn build_phase_status_for_phase(project: &Project, phase: &Phase) -> PhaseStatus {
+    let operations = project.phase_states.get(&phase.reference)
+        .map(|phase_state| phase_state.operation_state.iter()
+            .map(|(kind, state)| (kind.clone(), state.status.clone()))
+            .collect())
+        .unwrap_or_default();
+
+    let placements = find_phase_placement_states(project, phase).into_iter()
+        .map(|(object_path, placement_state)| PhasePlacementStatus {
+            object_path: object_path.clone(),
+            part: placement_state.placement.part.clone(),
+            place: placement_state.place(),
+            placed: placement_state.placed,
+        })
+        .collect();
+
+    PhaseStatus {
+        reference: phase.reference.clone(),
+        process: phase.process.clone(),
+        pcb_side: phase.pcb_side.clone(),
+        load_out_source: phase.load_out_source.clone(),
+        tags: phase.tags.iter().map(|(key, value)| format!("{}={}", key, value)).collect(),
+        operations,
+        placements,
+        estimate: estimation::build_phase_estimate_for_phase(project, phase),
+    }
+}
+
+fn format_operations(operations: &[(ProcessOperationKind, ProcessOperationStatus)]) -> String {
+    operations.iter()
+        .map(|(kind, status)| format!("{:?}: {:?}", kind, status))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+impl Display for ProjectStatus {
+    /// e.g.
+    /// ```text
+    /// Project: job1
+    /// Placements: placed: 1/3, unassigned: 1
+    /// Estimate: total: 12m 9s, remaining: 10m 6s
+    /// Phases:
+    ///   top_1 process: pnp, pcb_side: Top, placed: 1/2, operations: [LoadPcbs: Complete, AutomatedPnp: Incomplete], estimate: 12m 9s, remaining: 10m 6s
+    /// Unassigned placements:
+    ///   panel=1::unit=1::ref_des=R3
+    /// ```
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Project: {}", self.name)?;
+        writeln!(f, "Placements: placed: {}/{}, unassigned: {}", self.placed, self.placements, self.unassigned_placements.len())?;
+        if let Some(estimate) = &self.estimate {
+            writeln!(f, "Estimate: total: {}, remaining: {}", FormattedDuration(estimate.total_time), FormattedDuration(estimate.remaining_time))?;
+        }
+
+        writeln!(f, "Phases:")?;
+        for phase in self.phases.iter() {
+            write!(f, "  {} process: {}, pcb_side: {:?}, placed: {}/{}, operations: [{}]",
+                phase.reference, phase.process, phase.pcb_side, phase.placed(), phase.placements_to_place(), format_operations(&phase.operations),
+            )?;
+            match &phase.estimate {
+                Some(estimate) => writeln!(f, ", estimate: {}, remaining: {}", FormattedDuration(estimate.total_time), FormattedDuration(estimate.remaining_time))?,
+                None => writeln!(f)?,
+            }
+        }
+
+        if !self.unassigned_placements.is_empty() {
+            writeln!(f, "Unassigned placements:")?;
+            for object_path in self.unassigned_placements.iter() {
+                writeln!(f, "  {}", object_path)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for PhaseStatus {
+    /// e.g.
+    /// ```text
+    /// Phase: top_1
+    /// Process: pnp, pcb_side: Top, load_out: load_out_1.csv, tags: [line=A]
+    /// Estimate: total: 12m 9s, remaining: 10m 6s, setup: 2m 0s, placement: 9s, reflow: 10m 0s
+    /// Operations:
+    ///   LoadPcbs: Complete
+    ///   AutomatedPnp: Incomplete
+    /// Placements: placed: 1/2
+    ///   panel=1::unit=1::ref_des=R1 part: RES_MFR1:RES1, placed: true
+    ///   panel=1::unit=1::ref_des=R2 part: RES_MFR1:RES2, placed: false
+    ///   panel=1::unit=1::ref_des=R4 part: RES_MFR1:RES1, place: false
+    /// ```
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Phase: {}", self.reference)?;
+        writeln!(f, "Process: {}, pcb_side: {:?}, load_out: {}, tags: [{}]", self.process, self.pcb_side, self.load_out_source, self.tags.join(", "))?;
+        if let Some(estimate) = &self.estimate {
+            let placement_time = estimate.placement_time
+                .map(|placement_time| FormattedDuration(placement_time).to_string())
+                .unwrap_or("unknown".to_string());
+
+            writeln!(f, "Estimate: total: {}, remaining: {}, setup: {}, placement: {}, reflow: {}",
+                FormattedDuration(estimate.total_time), FormattedDuration(estimate.remaining_time), FormattedDuration(estimate.setup_time), placement_time, FormattedDuration(estimate.reflow_time),
+            )?;
+        }
+
+        writeln!(f, "Operations:")?;
+        for (kind, status) in self.operations.iter() {
+            writeln!(f, "  {:?}: {:?}", kind, status)?;
+        }
+
+        writeln!(f, "Placements: placed: {}/{}", self.placed(), self.placements_to_place())?;
+        for placement in self.placements.iter() {
+            match placement.place {
+                true => writeln!(f, "  {} part: {}, placed: {}", placement.object_path, placement.part, placement.placed)?,
+                false => writeln!(f, "  {} part: {}, place: false", placement.object_path, placement.part)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::str::FromStr;
+    use indoc::indoc;
+    use pnp::object_path::ObjectPath;
+    use pnp::part::Part;
+    use pnp::pcb::PcbSide;
+    use crate::estimation::EstimationParameters;
+    use crate::phase::PhaseError;
+    use crate::process::ProcessName;
+    use crate::project::Project;
+    use crate::reference::Reference;
+    use crate::status::{build_phase_status, build_project_status};
+    use crate::test::project_builder::{PlacementStateBuilder, ProjectBuilder};
+
+    fn build_project() -> Project {
+        let res1 = Part::new("RES_MFR1".to_string(), "RES1".to_string());
+        let res2 = Part::new("RES_MFR1".to_string(), "RES2".to_string());
+
+        ProjectBuilder::new()
+            .with_phase("top_1", "pnp", "load_out_1.csv", PcbSide::Top)
+            .with_placements([
+                PlacementStateBuilder::new(1, "R1", &res1).with_placed(true).with_phase("top_1"),
+                PlacementStateBuilder::new(1, "R2", &res2).with_phase("top_1"),
+                PlacementStateBuilder::new(1, "R3", &res1),
+                PlacementStateBuilder::new(1, "R4", &res1).with_place(false).with_phase("top_1"),
+            ])
+            .build()
+    }
+
+    #[test]
+    pub fn project_status() {
+        // given
+        let project = build_project();
+
+        // when
+        let status = build_project_status(&project);
+
+        // then
+        assert_eq!((status.placed, status.placements), (1, 3));
+        assert_eq!(status.unassigned_placements, vec![ObjectPath::from_str("panel=1::unit=1::ref_des=R3").unwrap()]);
+
+        // and
+        assert_eq!(status.to_string(), indoc! {"
+            Project: job1
+            Placements: placed: 1/3, unassigned: 1
+            Phases:
+              top_1 process: pnp, pcb_side: Top, placed: 1/2, operations: [LoadPcbs: Pending, AutomatedPnp: Pending, ReflowComponents: Pending]
+            Unassigned placements:
+              panel=1::unit=1::ref_des=R3
+        "});
+    }
+
+    #[test]
+    pub fn project_status_with_estimate() {
+        // given
+        let mut project = build_project();
+        project.estimation_parameters = Some(EstimationParameters {
+            placement_rates: BTreeMap::from([(ProcessName::from_str("pnp").unwrap(), 1200)]),
+            feeder_setup_time: 60,
+            reflow_time: 600,
+        });
+
+        // when
+        let status = build_project_status(&project);
+
+        // then
+        assert_eq!(status.to_string(), indoc! {"
+            Project: job1
+            Placements: placed: 1/3, unassigned: 1
+            Estimate: total: 12m 6s, remaining: 10m 3s
+            Phases:
+              top_1 process: pnp, pcb_side: Top, placed: 1/2, operations: [LoadPcbs: Pending, AutomatedPnp: Pending, ReflowComponents: Pending], estimate: 12m 6s, remaining: 10m 3s
+            Unassigned placements:
+              panel=1::unit=1::ref_des=R3
+        "});
+
+        // when
+        let status = build_phase_status(&project, &Reference::from_str("top_1").unwrap()).unwrap();
+
+        // then
+        assert!(status.to_string().contains("Estimate: total: 12m 6s, remaining: 10m 3s, setup: 2m 0s, placement: 6s, reflow: 10m 0s\n"));
+    }
+
+    #[test]
+    pub fn phase_status() {
+        // given
+        let project = build_project();
+
+        // when
+        let status = build_phase_status(&project, &Reference::from_str("top_1").unwrap()).unwrap();
+
+        // then
+        assert_eq!(status.to_string(), indoc! {"
+            Phase: top_1
+            Process: pnp, pcb_side: Top, load_out: load_out_1.csv, tags: []
+            Operations:
+              LoadPcbs: Pending
+              AutomatedPnp: Pending
+              ReflowComponents: Pending
+            Placements: placed: 1/2
+              panel=1::unit=1::ref_des=R1 part: RES_MFR1:RES1, placed: true
+              panel=1::unit=1::ref_des=R2 part: RES_MFR1:RES2, placed: false
+              panel=1::unit=1::ref_des=R4 part: RES_MFR1:RES1, place: false
+        "});
+
+        // and
+        assert!(matches!(build_phase_status(&project, &Reference::from_str("bottom_1").unwrap()), Err(PhaseError::UnknownPhase(_))));
+    }
+}
@@ -0,0 +1,142 @@
+use std::str::FromStr;
+use rust_decimal::Decimal;
+use pnp::object_path::ObjectPath;
+use pnp::part::Part;
+use pnp::pcb::{PcbKind, PcbSide};
+use pnp::placement::{Placement, PlacementKind};
+use crate::placement::{PlacementDefect, PlacementState, PlacementStatus};
+use crate::process::ProcessName;
+use crate::project::{add_pcb, Project};
+use crate::reference::Reference;
+
+/// A known placement of a unit of the first panel, at the origin of the top side, that is to be placed, has not been
+/// placed and is not assigned to a phase, unless changed.
+pub struct PlacementStateBuilder {
+    object_path: ObjectPath,
+    placement_state: PlacementState,
+}
+
+impl PlacementStateBuilder {
+    /// e.g. 'panel=1::unit=2::ref_des=R1' for a unit of 2 and a ref_des of 'R1'.
+    pub fn new(unit: usize, ref_des: &str, part: &Part) -> Self {
+        let unit_path = ObjectPath::from_str(&format!("panel=1::unit={}", unit)).unwrap();
+        let object_path = ObjectPath::from_str(&format!("{}::ref_des={}", unit_path, ref_des)).unwrap();
+
+        Self {
+            object_path,
+            placement_state: PlacementState {
+                unit_path,
+                placement: Placement {
+                    ref_des: ref_des.to_string(),
+                    part: part.clone(),
+                    place: true,
+                    pcb_side: PcbSide::Top,
+                    x: Decimal::ZERO,
+                    y: Decimal::ZERO,
+                    rotation: Decimal::ZERO,
+                    kind: PlacementKind::Component,
+                },
+                placed: false,
+                status: PlacementStatus::Known,
+                phase: None,
+                place_override: None,
+                defects: vec![],
+            },
+        }
+    }
+
+    /// e.g. 'single=1::unit=1', for placements that are not of the first panel.
+    pub fn with_unit_path(mut self, unit_path: &str) -> Self {
+        let unit_path = ObjectPath::from_str(unit_path).unwrap();
+        self.object_path = ObjectPath::from_str(&format!("{}::ref_des={}", unit_path, self.placement_state.placement.ref_des)).unwrap();
+        self.placement_state.unit_path = unit_path;
+        self
+    }
+
+    pub fn with_place(mut self, place: bool) -> Self {
+        self.placement_state.placement.place = place;
+        self
+    }
+
+    pub fn with_pcb_side(mut self, pcb_side: PcbSide) -> Self {
+        self.placement_state.placement.pcb_side = pcb_side;
+        self
+    }
+
+    pub fn with_position(mut self, x: Decimal, y: Decimal, rotation: Decimal) -> Self {
+        self.placement_state.placement.x = x;
+        self.placement_state.placement.y = y;
+        self.placement_state.placement.rotation = rotation;
+        self
+    }
+
+    pub fn with_kind(mut self, kind: PlacementKind) -> Self {
+        self.placement_state.placement.kind = kind;
+        self
+    }
+
+    pub fn with_status(mut self, status: PlacementStatus) -> Self {
+        self.placement_state.status = status;
+        self
+    }
+
+    pub fn with_placed(mut self, placed: bool) -> Self {
+        self.placement_state.placed = placed;
+        self
+    }
+
+    pub fn with_phase(mut self, phase: &str) -> Self {
+        self.placement_state.phase = Some(Reference::from_str(phase).unwrap());
+        self
+    }
+
+    pub fn with_defects(mut self, defects: Vec<PlacementDefect>) -> Self {
+        self.placement_state.defects = defects;
+        self
+    }
+
+    pub fn build(self) -> (ObjectPath, PlacementState) {
+        (self.object_path, self.placement_state)
+    }
+}
+
+/// A project named 'job1', see `PlacementStateBuilder` for the placements.
+pub struct ProjectBuilder {
+    project: Project,
+}
+
+impl Default for ProjectBuilder {
+    fn default() -> Self {
+        Self { project: Project::new("job1".to_string()) }
+    }
+}
+
+impl ProjectBuilder {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.project.name = name.to_string();
+        self
+    }
+
+    pub fn with_pcb(mut self, kind: PcbKind, name: &str) -> Self {
+        add_pcb(&mut self.project, kind, name.to_string()).unwrap();
+        self
+    }
+
+    pub fn with_phase(mut self, reference: &str, process: &str, load_out_source: &str, pcb_side: PcbSide) -> Self {
+        self.project.update_phase(Reference::from_str(reference).unwrap(), ProcessName::from_str(process).unwrap(), load_out_source.to_string(), pcb_side).unwrap();
+        self
+    }
+
+    pub fn with_placements(mut self, placements: impl IntoIterator<Item = PlacementStateBuilder>) -> Self {
+        self.project.placements.extend(placements.into_iter().map(PlacementStateBuilder::build));
+        self
+    }
+
+    pub fn build(self) -> Project {
+        self.project
+    }
+}
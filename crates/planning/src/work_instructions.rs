@@ -0,0 +1,302 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use pnp::load_out::LoadOutItem;
+use pnp::object_path::ObjectPath;
+use pnp::part::{Part, PartDetails};
+use crate::phase::{Phase, WorkInstructionsStyle};
+use crate::placement::PlacementState;
+use crate::project::Project;
+
+const THUMBNAIL_HEIGHT: u32 = 48;
+
+pub fn build_work_instructions_file_name(phase: &Phase) -> String {
+    format!("{}_work_instructions.md", phase.reference)
+}
+
+/// Builds markdown work instructions for the phase, in the style of the phase.
+///
+/// Parts are listed in feeder reference order, parts without a feeder are listed last.
+pub fn build_work_instructions_markdown(project: &Project, phase: &Phase, placement_states: &[(&ObjectPath, &PlacementState)], load_out_items: &[LoadOutItem]) -> String {
+    let groups = build_part_groups(placement_states, load_out_items);
+
+    let mut markdown = String::new();
+
+    writeln!(markdown, "# Work instructions - {}", phase.reference).unwrap();
+    writeln!(markdown).unwrap();
+    writeln!(markdown, "* Process: {}", phase.process).unwrap();
+    writeln!(markdown, "* PCB side: {:?}", phase.pcb_side).unwrap();
+    writeln!(markdown, "* Placements: {}", groups.iter().map(|group| group.placement_states.len()).sum::<usize>()).unwrap();
+    writeln!(markdown).unwrap();
+
+    match phase.work_instructions_style {
+        WorkInstructionsStyle::Parts => write_parts(&mut markdown, project, &groups),
+        WorkInstructionsStyle::PickList => write_pick_list(&mut markdown, project, &groups),
+    }
+
+    markdown
+}
+
+struct PartGroup<'a> {
+    feeder_reference: String,
+    part: &'a Part,
+    /// In the order of the phase placements
+    placement_states: Vec<&'a PlacementState>,
+}
+
+fn build_part_groups<'a>(placement_states: &[(&ObjectPath, &'a PlacementState)], load_out_items: &[LoadOutItem]) -> Vec<PartGroup<'a>> {
+    let part_placement_states: BTreeMap<&Part, Vec<&PlacementState>> = placement_states.iter()
+        .filter(|(_object_path, placement_state)| placement_state.place())
+        .fold(BTreeMap::new(), |mut part_placement_states, (_object_path, placement_state)| {
+            part_placement_states.entry(&placement_state.placement.part).or_default().push(placement_state);
+            part_placement_states
+        });
+
+    let mut groups: Vec<PartGroup> = part_placement_states.into_iter().map(|(part, placement_states)| {
+        let feeder_reference = match pnp::load_out::find_load_out_item_by_part(load_out_items, part) {
+            Some(load_out_item) => load_out_item.reference.clone(),
+            _ => "".to_string(),
+        };
+        PartGroup { feeder_reference, part, placement_states }
+    }).collect();
+
+    groups.sort_by(|group_a, group_b| {
+        pnp::load_out::feeder_reference_cmp(&group_a.feeder_reference, &group_b.feeder_reference)
+            .then_with(|| group_a.part.cmp(group_b.part))
+    });
+
+    groups
+}
+
+/// A table of the parts with their feeder, quantity, image and datasheet so that operators can identify the parts.
+fn write_parts(markdown: &mut String, project: &Project, groups: &[PartGroup]) {
+    writeln!(markdown, "## Parts").unwrap();
+    writeln!(markdown).unwrap();
+    writeln!(markdown, "| Feeder | Manufacturer | Mpn | Quantity | Image | Datasheet |").unwrap();
+    writeln!(markdown, "| --- | --- | --- | --- | --- | --- |").unwrap();
+
+    for group in groups.iter() {
+        let (image, datasheet) = format_part_details(project, group.part);
+
+        writeln!(markdown, "| {} | {} | {} | {} | {} | {} |",
+            escape_cell(&group.feeder_reference), escape_cell(&group.part.manufacturer), escape_cell(&group.part.mpn), group.placement_states.len(),
+            escape_cell(&image), escape_cell(&datasheet),
+        ).unwrap();
+    }
+}
+
+/// A section for each part, listing the reference designators and the location of each placement, so that an
+/// operator can place all the placements of a part before moving on to the next part.
+fn write_pick_list(markdown: &mut String, project: &Project, groups: &[PartGroup]) {
+    writeln!(markdown, "## Pick list").unwrap();
+
+    for group in groups.iter() {
+        writeln!(markdown).unwrap();
+        if group.feeder_reference.is_empty() {
+            writeln!(markdown, "### {} {}", group.part.manufacturer, group.part.mpn).unwrap();
+        } else {
+            writeln!(markdown, "### {} - {} {}", group.feeder_reference, group.part.manufacturer, group.part.mpn).unwrap();
+        }
+        writeln!(markdown).unwrap();
+
+        let mut ref_des_list: Vec<&str> = vec![];
+        for placement_state in group.placement_states.iter() {
+            if !ref_des_list.contains(&placement_state.placement.ref_des.as_str()) {
+                ref_des_list.push(&placement_state.placement.ref_des);
+            }
+        }
+
+        writeln!(markdown, "Place {}: {}", group.placement_states.len(), ref_des_list.join(", ")).unwrap();
+
+        let (image, datasheet) = format_part_details(project, group.part);
+        let details: Vec<String> = [image, datasheet].into_iter().filter(|detail| !detail.is_empty()).collect();
+        if !details.is_empty() {
+            writeln!(markdown).unwrap();
+            writeln!(markdown, "{}", details.join(" ")).unwrap();
+        }
+
+        writeln!(markdown).unwrap();
+        writeln!(markdown, "| Unit | RefDes | X | Y | Rotation |").unwrap();
+        writeln!(markdown, "| --- | --- | --- | --- | --- |").unwrap();
+
+        for placement_state in group.placement_states.iter() {
+            let placement = &placement_state.placement;
+            writeln!(markdown, "| {} | {} | {} | {} | {} |",
+                escape_cell(&placement_state.unit_path.to_string()), escape_cell(&placement.ref_des), placement.x, placement.y, placement.rotation,
+            ).unwrap();
+        }
+    }
+}
+
+/// Returns the image and datasheet markdown of the part, empty if the part has no such details.
+fn format_part_details(project: &Project, part: &Part) -> (String, String) {
+    let details = project.part_states.get(part)
+        .map(|part_state| &part_state.details)
+        .cloned()
+        .unwrap_or_default();
+
+    let PartDetails { image, datasheet, .. } = details;
+
+    let image = image
+        .map(|image| format!("<img src=\"{}\" alt=\"{}\" height=\"{}\">", escape_html(&image), escape_html(&part.mpn), THUMBNAIL_HEIGHT))
+        .unwrap_or_default();
+    let datasheet = datasheet
+        .map(|datasheet| format!("[Datasheet]({})", datasheet.replace(' ', "%20")))
+        .unwrap_or_default();
+
+    (image, datasheet)
+}
+
+fn escape_cell(value: &str) -> String {
+    value.replace('|', "\\|")
+}
+
+fn escape_html(value: &str) -> String {
+    value.replace('&', "&amp;").replace('"', "&quot;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use indoc::indoc;
+    use rust_decimal_macros::dec;
+    use pnp::load_out::LoadOutItem;
+    use pnp::object_path::ObjectPath;
+    use pnp::part::{Part, PartDetails};
+    use pnp::pcb::PcbSide;
+    use crate::part::PartState;
+    use crate::placement::PlacementState;
+    use crate::reference::Reference;
+    use crate::phase::WorkInstructionsStyle;
+    use crate::test::project_builder::{PlacementStateBuilder, ProjectBuilder};
+    use crate::work_instructions::build_work_instructions_markdown;
+
+    #[test]
+    pub fn build_markdown() {
+        // given
+        let mut project = ProjectBuilder::new()
+            .with_phase("top_1", "pnp", "load_out_1.csv", PcbSide::Top)
+            .build();
+        let phase = project.phases.get(&Reference::from_str("top_1").unwrap()).unwrap().clone();
+
+        // and
+        let res1 = Part::new("RES_MFR1".to_string(), "RES1".to_string());
+        let res2 = Part::new("RES_MFR1".to_string(), "RES2".to_string());
+        let cap1 = Part::new("CAP_MFR1".to_string(), "CAP1".to_string());
+
+        project.part_states.insert(res1.clone(), PartState {
+            details: PartDetails { image: Some("images/res1.png".to_string()), datasheet: Some("https://example.com/res1.pdf".to_string()), package: None, ..PartDetails::default() },
+            ..PartState::default()
+        });
+
+        // and
+        let placements = [("R1", &res1), ("R2", &res1), ("R3", &res2), ("C1", &cap1)]
+            .map(|(ref_des, part)| PlacementStateBuilder::new(1, ref_des, part)
+                .with_position(dec!(10), dec!(20), dec!(90))
+                .with_phase("top_1")
+                .build()
+            );
+        let placement_states: Vec<(&ObjectPath, &PlacementState)> = placements.iter().map(|(object_path, state)| (object_path, state)).collect();
+
+        // and
+        let load_out_items = vec![
+            LoadOutItem::new("FEEDER_10".to_string(), "RES_MFR1".to_string(), "RES1".to_string()),
+            LoadOutItem::new("FEEDER_2".to_string(), "CAP_MFR1".to_string(), "CAP1".to_string()),
+        ];
+
+        // and
+        let expected_markdown = indoc! {r#"
+            # Work instructions - top_1
+
+            * Process: pnp
+            * PCB side: Top
+            * Placements: 4
+
+            ## Parts
+
+            | Feeder | Manufacturer | Mpn | Quantity | Image | Datasheet |
+            | --- | --- | --- | --- | --- | --- |
+            | FEEDER_2 | CAP_MFR1 | CAP1 | 1 |  |  |
+            | FEEDER_10 | RES_MFR1 | RES1 | 2 | <img src="images/res1.png" alt="RES1" height="48"> | [Datasheet](https://example.com/res1.pdf) |
+            |  | RES_MFR1 | RES2 | 1 |  |  |
+        "#};
+
+        // when
+        let markdown = build_work_instructions_markdown(&project, &phase, &placement_states, &load_out_items);
+
+        // then
+        assert_eq!(markdown, expected_markdown);
+    }
+
+    #[test]
+    pub fn build_pick_list_markdown() {
+        // given
+        let mut project = ProjectBuilder::new()
+            .with_phase("manual_1", "manual", "load_out_1.csv", PcbSide::Top)
+            .build();
+        let reference = Reference::from_str("manual_1").unwrap();
+        project.phases.get_mut(&reference).unwrap().work_instructions_style = WorkInstructionsStyle::PickList;
+        let phase = project.phases.get(&reference).unwrap().clone();
+
+        // and
+        let res1 = Part::new("RES_MFR1".to_string(), "RES1".to_string());
+        let cap1 = Part::new("CAP_MFR1".to_string(), "CAP1".to_string());
+
+        project.part_states.insert(cap1.clone(), PartState {
+            details: PartDetails { image: None, datasheet: Some("https://example.com/cap1.pdf".to_string()), package: None, ..PartDetails::default() },
+            ..PartState::default()
+        });
+
+        // and
+        let placements = [(1, "R1", &res1, dec!(10)), (1, "R2", &res1, dec!(15)), (1, "C1", &cap1, dec!(20)), (2, "R1", &res1, dec!(10)), (2, "R2", &res1, dec!(15)), (2, "C1", &cap1, dec!(20))]
+            .map(|(unit, ref_des, part, x)| PlacementStateBuilder::new(unit, ref_des, part)
+                .with_position(x, dec!(5.5), dec!(0))
+                .with_phase("manual_1")
+                .build()
+            );
+        let placement_states: Vec<(&ObjectPath, &PlacementState)> = placements.iter().map(|(object_path, state)| (object_path, state)).collect();
+
+        // and
+        let load_out_items = vec![
+            LoadOutItem::new("TRAY_1".to_string(), "CAP_MFR1".to_string(), "CAP1".to_string()),
+        ];
+
+        // and
+        let expected_markdown = indoc! {r#"
+            # Work instructions - manual_1
+
+            * Process: manual
+            * PCB side: Top
+            * Placements: 6
+
+            ## Pick list
+
+            ### TRAY_1 - CAP_MFR1 CAP1
+
+            Place 2: C1
+
+            [Datasheet](https://example.com/cap1.pdf)
+
+            | Unit | RefDes | X | Y | Rotation |
+            | --- | --- | --- | --- | --- |
+            | panel=1::unit=1 | C1 | 20 | 5.5 | 0 |
+            | panel=1::unit=2 | C1 | 20 | 5.5 | 0 |
+
+            ### RES_MFR1 RES1
+
+            Place 4: R1, R2
+
+            | Unit | RefDes | X | Y | Rotation |
+            | --- | --- | --- | --- | --- |
+            | panel=1::unit=1 | R1 | 10 | 5.5 | 0 |
+            | panel=1::unit=1 | R2 | 15 | 5.5 | 0 |
+            | panel=1::unit=2 | R1 | 10 | 5.5 | 0 |
+            | panel=1::unit=2 | R2 | 15 | 5.5 | 0 |
+        "#};
+
+        // when
+        let markdown = build_work_instructions_markdown(&project, &phase, &placement_states, &load_out_items);
+
+        // then
+        assert_eq!(markdown, expected_markdown);
+    }
+}
@@ -0,0 +1,104 @@
+use thiserror::Error;
+
+/// A feeder from a feeder library.
+///
+/// The constraints are optional, e.g. a feeder without any packages accepts parts of any package.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Feeder {
+    pub reference: String,
+    /// e.g. 'tape', 'tray', 'tube'
+    pub kind: String,
+    /// Width of the tape, in millimeters.
+    pub tape_width: Option<u32>,
+    /// The packages of the parts that can be loaded into the feeder, compared ignoring case.
+    pub packages: Vec<String>,
+    /// The maximum quantity of parts that can be loaded into the feeder.
+    pub capacity: Option<u32>,
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum FeederConstraintError {
+    #[error("Package not compatible with the feeder. feeder: '{feeder}', package: '{package}', packages: {packages:?}")]
+    IncompatiblePackage { feeder: String, package: String, packages: Vec<String> },
+
+    #[error("The package of the part is unknown, the feeder only accepts specific packages. feeder: '{feeder}', packages: {packages:?}")]
+    UnknownPackage { feeder: String, packages: Vec<String> },
+
+    #[error("Quantity exceeds the capacity of the feeder. feeder: '{feeder}', quantity: {quantity}, capacity: {capacity}")]
+    CapacityExceeded { feeder: String, quantity: u32, capacity: u32 },
+}
+
+impl Feeder {
+    /// Checks a part, of the package, and the quantity, can be loaded into the feeder.
+    pub fn check(&self, package: Option<&str>, quantity: Option<u32>) -> Result<(), FeederConstraintError> {
+        if !self.packages.is_empty() {
+            let package = package.ok_or_else(|| FeederConstraintError::UnknownPackage { feeder: self.reference.clone(), packages: self.packages.clone() })?;
+
+            if !self.packages.iter().any(|compatible| compatible.eq_ignore_ascii_case(package)) {
+                return Err(FeederConstraintError::IncompatiblePackage { feeder: self.reference.clone(), package: package.to_string(), packages: self.packages.clone() })
+            }
+        }
+
+        if let (Some(quantity), Some(capacity)) = (quantity, self.capacity) {
+            if quantity > capacity {
+                return Err(FeederConstraintError::CapacityExceeded { feeder: self.reference.clone(), quantity, capacity })
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns true if a part, of the package and supplied on tape of the width, can be loaded into the feeder, an unknown
+    /// tape width is assumed to be compatible.
+    pub fn is_compatible(&self, package: Option<&str>, tape_width: Option<u32>) -> bool {
+        let is_tape_width_compatible = match (self.tape_width, tape_width) {
+            (Some(feeder_tape_width), Some(tape_width)) => feeder_tape_width == tape_width,
+            _ => true,
+        };
+
+        is_tape_width_compatible && self.check(package, None).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::feeder::{Feeder, FeederConstraintError};
+
+    fn build_feeder() -> Feeder {
+        Feeder {
+            reference: "FEEDER_1".to_string(),
+            kind: "tape".to_string(),
+            tape_width: Some(8),
+            packages: vec!["0402".to_string(), "0603".to_string()],
+            capacity: Some(5000),
+        }
+    }
+
+    #[test]
+    pub fn compatible() {
+        assert_eq!(build_feeder().check(Some("0603"), Some(5000)), Ok(()));
+        assert_eq!(Feeder { packages: vec![], capacity: None, ..build_feeder() }.check(None, Some(10000)), Ok(()));
+        assert!(build_feeder().is_compatible(Some("0402"), Some(8)));
+        assert!(build_feeder().is_compatible(Some("0402"), None));
+    }
+
+    #[test]
+    pub fn incompatible() {
+        // given
+        let feeder = build_feeder();
+
+        // expect
+        assert_eq!(feeder.check(Some("SOT-23"), None), Err(FeederConstraintError::IncompatiblePackage {
+            feeder: "FEEDER_1".to_string(),
+            package: "SOT-23".to_string(),
+            packages: vec!["0402".to_string(), "0603".to_string()],
+        }));
+        assert!(matches!(feeder.check(None, None), Err(FeederConstraintError::UnknownPackage { .. })));
+        assert!(!feeder.is_compatible(Some("0402"), Some(12)));
+        assert_eq!(feeder.check(Some("0402"), Some(5001)), Err(FeederConstraintError::CapacityExceeded {
+            feeder: "FEEDER_1".to_string(),
+            quantity: 5001,
+            capacity: 5000,
+        }));
+    }
+}
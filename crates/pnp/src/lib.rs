@@ -4,4 +4,6 @@ pub mod placement;
 pub mod load_out;
 pub mod object_path;
 
-pub mod pcb;
\ No newline at end of file
+pub mod pcb;
+
+pub mod feeder;
@@ -1,26 +1,297 @@
-use crate::part::Part;
+use std::cmp::Ordering;
+use util::sorting::natural_cmp;
+use crate::feeder::Feeder;
+use crate::part::{Part, PartDetails};
 
 #[derive(Debug, PartialEq)]
 pub struct LoadOutItem {
     pub reference: String,
     pub manufacturer: String,
     pub mpn: String,
+
+    /// The quantity of parts remaining, `None` if the quantity is not tracked.
+    pub quantity: Option<u32>,
+    /// The reel (or tray/tube) identifier
+    pub reel: Option<String>,
+
+    /// Equivalent parts that can be loaded instead of the part, in order of preference.
+    pub alternates: Vec<Part>,
+    /// The alternate that is currently loaded, `None` if the part itself is loaded.
+    pub loaded_alternate: Option<Part>,
 }
 
 impl LoadOutItem {
+    pub fn matches_part(&self, part: &Part) -> bool {
+        self.manufacturer.eq(&part.manufacturer)
+            && self.mpn.eq(&part.mpn)
+    }
+
     pub fn new(reference: String, manufacturer: String, mpn: String) -> Self {
         Self {
             reference,
             manufacturer,
             mpn,
+            quantity: None,
+            reel: None,
+            alternates: vec![],
+            loaded_alternate: None,
         }
     }
+
+    /// The part that is loaded in the feeder, either the loaded alternate or the part itself.
+    pub fn loaded_part(&self) -> Part {
+        self.loaded_alternate.clone()
+            .unwrap_or_else(|| Part::new(self.manufacturer.clone(), self.mpn.clone()))
+    }
 }
 
 pub fn find_load_out_item_by_part<'load_out>(load_out_items: &'load_out [LoadOutItem], part: &Part) -> Option<&'load_out LoadOutItem> {
     let matched_item = load_out_items.iter().find(|&load_out_item| {
-        load_out_item.manufacturer.eq(&part.manufacturer)
-            && load_out_item.mpn.eq(&part.mpn)
+        load_out_item.matches_part(part)
     });
     matched_item
-}
\ No newline at end of file
+}
+
+/// The ordering of load-out items, used for load-outs, reports and artifacts so that regenerated files are stable.
+///
+/// Items are ordered by feeder reference using a natural order (e.g. 'FEEDER_2' before 'FEEDER_10'), then by part.
+/// Items that have not been assigned to a feeder are ordered last.
+pub fn load_out_item_cmp(a: &LoadOutItem, b: &LoadOutItem) -> Ordering {
+    feeder_reference_cmp(&a.reference, &b.reference)
+        .then_with(|| a.manufacturer.cmp(&b.manufacturer))
+        .then_with(|| a.mpn.cmp(&b.mpn))
+}
+
+/// Compares feeder references using a natural order, an empty (unassigned) feeder reference is ordered last.
+pub fn feeder_reference_cmp(a: &str, b: &str) -> Ordering {
+    a.is_empty().cmp(&b.is_empty())
+        .then_with(|| natural_cmp(a, b))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FeederSuggestionReason {
+    /// The part is already loaded on the feeder in another load-out.
+    AssignedInOtherLoadOut,
+    /// The feeder is not used by the load-out.
+    Free,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeederSuggestion {
+    pub reference: String,
+    pub reason: FeederSuggestionReason,
+}
+
+/// The prefix used for suggested feeder references when the load-out has no numbered feeder references.
+const DEFAULT_FEEDER_PREFIX: &str = "FEEDER_";
+
+/// Suggests feeders for a part, up to `limit` suggestions.
+///
+/// Feeders that the part is already loaded on in other load-outs are suggested first, when they are not used by the
+/// load-out, so that reels do not need to be moved between phases.  Then the lowest free feeder numbers are suggested,
+/// using the most common prefix of the numbered feeder references in the load-out (e.g. 'FEEDER_' for 'FEEDER_1').
+///
+/// When a feeder library is given only its feeders that are compatible with the package and the tape width of the part
+/// are suggested, see `Feeder::is_compatible`.  Free feeders made for specific packages are suggested before feeders
+/// that accept any package, then feeders with a tape width before feeders without one.
+pub fn suggest_feeders(part: &Part, part_details: Option<&PartDetails>, load_out_items: &[LoadOutItem], other_load_out_items: &[LoadOutItem], feeders: Option<&[Feeder]>, limit: usize) -> Vec<FeederSuggestion> {
+    let is_used = |reference: &str| load_out_items.iter().any(|item| item.reference.eq(reference));
+
+    let package = part_details.and_then(|part_details| part_details.package.as_deref());
+    let tape_width = part_details.and_then(|part_details| part_details.tape_width);
+    let is_compatible = |feeder: &Feeder| feeder.is_compatible(package, tape_width);
+    let is_compatible_reference = |reference: &str| feeders.is_none_or(|feeders| feeders.iter()
+        .any(|feeder| feeder.reference.eq(reference) && is_compatible(feeder)));
+
+    let mut suggestions: Vec<FeederSuggestion> = vec![];
+
+    let mut other_references: Vec<&str> = other_load_out_items.iter()
+        .filter(|item| item.matches_part(part) && !item.reference.is_empty())
+        .map(|item| item.reference.as_str())
+        .collect();
+    other_references.sort_by(|a, b| natural_cmp(a, b));
+    other_references.dedup();
+
+    for reference in other_references.into_iter().filter(|reference| !is_used(reference) && is_compatible_reference(reference)) {
+        suggestions.push(FeederSuggestion { reference: reference.to_string(), reason: FeederSuggestionReason::AssignedInOtherLoadOut });
+    }
+
+    if let Some(feeders) = feeders {
+        let mut free_feeders: Vec<&Feeder> = feeders.iter()
+            .filter(|feeder| !is_used(&feeder.reference) && is_compatible(feeder))
+            .filter(|feeder| !suggestions.iter().any(|suggestion| suggestion.reference.eq(&feeder.reference)))
+            .collect();
+        free_feeders.sort_by(|a, b| a.packages.is_empty().cmp(&b.packages.is_empty())
+            .then_with(|| a.tape_width.is_none().cmp(&b.tape_width.is_none()))
+            .then_with(|| natural_cmp(&a.reference, &b.reference))
+        );
+
+        suggestions.extend(free_feeders.into_iter()
+            .map(|feeder| FeederSuggestion { reference: feeder.reference.clone(), reason: FeederSuggestionReason::Free }));
+        suggestions.truncate(limit);
+
+        return suggestions
+    }
+
+    let mut prefix_counts: Vec<(&str, usize)> = vec![];
+    for (prefix, _number) in load_out_items.iter().filter_map(|item| split_feeder_reference(&item.reference)) {
+        match prefix_counts.iter_mut().find(|(other_prefix, _count)| other_prefix.eq(&prefix)) {
+            Some((_prefix, count)) => *count += 1,
+            None => prefix_counts.push((prefix, 1)),
+        }
+    }
+    let prefix = prefix_counts.iter()
+        .max_by(|(prefix_a, count_a), (prefix_b, count_b)| count_a.cmp(count_b).then_with(|| prefix_b.cmp(prefix_a)))
+        .map_or(DEFAULT_FEEDER_PREFIX, |(prefix, _count)| prefix);
+
+    let mut number: u32 = 1;
+    while suggestions.len() < limit {
+        let reference = format!("{}{}", prefix, number);
+        let is_suggested = suggestions.iter().any(|suggestion| suggestion.reference.eq(&reference));
+        if !is_used(&reference) && !is_suggested {
+            suggestions.push(FeederSuggestion { reference, reason: FeederSuggestionReason::Free });
+        }
+        number += 1;
+    }
+
+    suggestions.truncate(limit);
+
+    suggestions
+}
+
+/// Splits a feeder reference into a prefix and a number, e.g. 'FEEDER_12' into ('FEEDER_', 12).
+fn split_feeder_reference(reference: &str) -> Option<(&str, u32)> {
+    let prefix = reference.trim_end_matches(|c: char| c.is_ascii_digit());
+    reference[prefix.len()..].parse::<u32>().ok().map(|number| (prefix, number))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::feeder::Feeder;
+    use crate::load_out::{load_out_item_cmp, suggest_feeders, FeederSuggestion, FeederSuggestionReason, LoadOutItem};
+    use crate::part::{Part, PartDetails};
+
+    #[test]
+    fn sort_by_feeder_reference_then_part_with_unassigned_last() {
+        // given
+        let mut items = vec![
+            LoadOutItem::new("".to_string(), "MFR1".to_string(), "PART2".to_string()),
+            LoadOutItem::new("FEEDER_10".to_string(), "MFR1".to_string(), "PART1".to_string()),
+            LoadOutItem::new("".to_string(), "MFR1".to_string(), "PART1".to_string()),
+            LoadOutItem::new("FEEDER_2".to_string(), "MFR2".to_string(), "PART3".to_string()),
+            LoadOutItem::new("FEEDER_2".to_string(), "MFR1".to_string(), "PART4".to_string()),
+        ];
+
+        // and
+        let expected_items = vec![
+            LoadOutItem::new("FEEDER_2".to_string(), "MFR1".to_string(), "PART4".to_string()),
+            LoadOutItem::new("FEEDER_2".to_string(), "MFR2".to_string(), "PART3".to_string()),
+            LoadOutItem::new("FEEDER_10".to_string(), "MFR1".to_string(), "PART1".to_string()),
+            LoadOutItem::new("".to_string(), "MFR1".to_string(), "PART1".to_string()),
+            LoadOutItem::new("".to_string(), "MFR1".to_string(), "PART2".to_string()),
+        ];
+
+        // when
+        items.sort_by(load_out_item_cmp);
+
+        // then
+        assert_eq!(items, expected_items);
+    }
+
+    #[test]
+    fn suggest_feeders_from_other_load_outs_then_free_feeders() {
+        // given
+        let part = Part::new("MFR1".to_string(), "PART1".to_string());
+        let load_out_items = vec![
+            LoadOutItem::new("FEEDER_1".to_string(), "MFR1".to_string(), "PART2".to_string()),
+            LoadOutItem::new("FEEDER_3".to_string(), "MFR1".to_string(), "PART3".to_string()),
+            LoadOutItem::new("TRAY_1".to_string(), "MFR1".to_string(), "PART4".to_string()),
+            LoadOutItem::new("".to_string(), "MFR1".to_string(), "PART1".to_string()),
+        ];
+
+        // and
+        let other_load_out_items = vec![
+            LoadOutItem::new("FEEDER_7".to_string(), "MFR1".to_string(), "PART1".to_string()),
+            // used by another part in the load-out
+            LoadOutItem::new("FEEDER_3".to_string(), "MFR1".to_string(), "PART1".to_string()),
+        ];
+
+        // and
+        let expected_suggestions = vec![
+            FeederSuggestion { reference: "FEEDER_7".to_string(), reason: FeederSuggestionReason::AssignedInOtherLoadOut },
+            FeederSuggestion { reference: "FEEDER_2".to_string(), reason: FeederSuggestionReason::Free },
+            FeederSuggestion { reference: "FEEDER_4".to_string(), reason: FeederSuggestionReason::Free },
+        ];
+
+        // when
+        let suggestions = suggest_feeders(&part, None, &load_out_items, &other_load_out_items, None, 3);
+
+        // then
+        assert_eq!(suggestions, expected_suggestions);
+    }
+
+    #[test]
+    fn suggest_default_feeders_for_empty_load_out() {
+        // given
+        let part = Part::new("MFR1".to_string(), "PART1".to_string());
+
+        // and
+        let expected_suggestions = vec![
+            FeederSuggestion { reference: "FEEDER_1".to_string(), reason: FeederSuggestionReason::Free },
+            FeederSuggestion { reference: "FEEDER_2".to_string(), reason: FeederSuggestionReason::Free },
+        ];
+
+        // when
+        let suggestions = suggest_feeders(&part, None, &[], &[], None, 2);
+
+        // then
+        assert_eq!(suggestions, expected_suggestions);
+    }
+
+    #[test]
+    fn suggest_compatible_feeders_from_feeder_library() {
+        // given
+        let part = Part::new("MFR1".to_string(), "PART1".to_string());
+        let part_details = PartDetails { package: Some("0402".to_string()), tape_width: Some(8), ..Default::default() };
+        let load_out_items = vec![
+            LoadOutItem::new("FEEDER_1".to_string(), "MFR1".to_string(), "PART2".to_string()),
+        ];
+
+        // and
+        let build_feeder = |reference: &str, tape_width: Option<u32>, packages: &[&str]| Feeder {
+            reference: reference.to_string(),
+            kind: "tape".to_string(),
+            tape_width,
+            packages: packages.iter().map(|package| package.to_string()).collect(),
+            capacity: None,
+        };
+        let feeders = vec![
+            build_feeder("FEEDER_1", Some(8), &["0402"]),
+            // free, but of the wrong tape width
+            build_feeder("FEEDER_2", Some(12), &[]),
+            // free, but not for the package
+            build_feeder("FEEDER_3", Some(8), &["SOT-23"]),
+            build_feeder("FEEDER_4", None, &[]),
+            build_feeder("FEEDER_5", Some(8), &[]),
+            build_feeder("FEEDER_6", Some(8), &["0402", "0603"]),
+        ];
+
+        // and the part is loaded on an incompatible feeder in another load-out
+        let other_load_out_items = vec![
+            LoadOutItem::new("FEEDER_3".to_string(), "MFR1".to_string(), "PART1".to_string()),
+        ];
+
+        // and
+        let expected_suggestions = vec![
+            FeederSuggestion { reference: "FEEDER_6".to_string(), reason: FeederSuggestionReason::Free },
+            FeederSuggestion { reference: "FEEDER_5".to_string(), reason: FeederSuggestionReason::Free },
+            FeederSuggestion { reference: "FEEDER_4".to_string(), reason: FeederSuggestionReason::Free },
+        ];
+
+        // when
+        let suggestions = suggest_feeders(&part, Some(&part_details), &load_out_items, &other_load_out_items, Some(&feeders), 5);
+
+        // then
+        assert_eq!(suggestions, expected_suggestions);
+    }
+}
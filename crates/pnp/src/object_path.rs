@@ -1,45 +1,149 @@
 use std::fmt::{Display, Formatter};
+use std::num::NonZeroUsize;
 use std::str::FromStr;
 use thiserror::Error;
 use crate::pcb::PcbKind;
 
-#[derive(Debug, Clone, PartialOrd, Ord, Eq, PartialEq)]
-struct ObjectPathChunk {
-    key: String,
-    value: String,
+const PCB_KEYS: [&str; 2] = ["panel", "single"];
+const UNIT_KEY: &str = "unit";
+const REF_DES_KEY: &str = "ref_des";
+
+/// The 1-based index of a panel, or of a single PCB, e.g. 1 for 'panel=1::unit=2'.
+#[derive(Debug, Clone, Copy, PartialOrd, Ord, Eq, PartialEq, Hash)]
+pub struct PanelIndex(NonZeroUsize);
+
+impl PanelIndex {
+    pub fn new(index: usize) -> Result<Self, ObjectPathError> {
+        NonZeroUsize::new(index)
+            .map(Self)
+            .ok_or(ObjectPathError::InvalidIndex(index.to_string()))
+    }
+
+    pub fn get(&self) -> usize {
+        self.0.get()
+    }
 }
 
-impl FromStr for ObjectPathChunk {
+impl FromStr for PanelIndex {
     type Err = ObjectPathError;
 
-    fn from_str(chunk: &str) -> Result<Self, Self::Err> {
-        let parts: Vec<&str> = chunk.split('=').collect();
-        if parts.len() == 2 {
-            let (key, value) = (parts[0], parts[1]);
-            
-            match key {
-                "single" |
-                "panel" |
-                "unit" => {
-                    let index: usize = value.parse().map_err(|_err|
-                        ObjectPathError::InvalidIndex(value.to_string())
-                    )?;
-                    Ok(ObjectPathChunk { key: key.to_string(), value: index.to_string() })
-                },
-                "ref_des" => {
-                    Ok(ObjectPathChunk { key: key.to_string(), value: value.to_string() })
-                }
-                _ => Err(ObjectPathError::UnknownKey(key.to_string()))
-            }
-        } else {
-            Err(ObjectPathError::InvalidChunk(chunk.to_string()))
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        parse_index(value).map(Self)
+    }
+}
+
+impl Display for PanelIndex {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// The 1-based index of a unit of a PCB, e.g. 2 for 'panel=1::unit=2'.
+#[derive(Debug, Clone, Copy, PartialOrd, Ord, Eq, PartialEq, Hash)]
+pub struct UnitIndex(NonZeroUsize);
+
+impl UnitIndex {
+    pub fn new(index: usize) -> Result<Self, ObjectPathError> {
+        NonZeroUsize::new(index)
+            .map(Self)
+            .ok_or(ObjectPathError::InvalidIndex(index.to_string()))
+    }
+
+    pub fn get(&self) -> usize {
+        self.0.get()
+    }
+}
+
+impl FromStr for UnitIndex {
+    type Err = ObjectPathError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        parse_index(value).map(Self)
+    }
+}
+
+impl Display for UnitIndex {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A reference designator, e.g. 'R1', it cannot be empty or contain the ':' and '=' path separators.
+#[derive(Debug, Clone, PartialOrd, Ord, Eq, PartialEq, Hash)]
+pub struct RefDes(String);
+
+impl RefDes {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for RefDes {
+    type Err = ObjectPathError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if value.is_empty() || value.contains([':', '=']) {
+            return Err(ObjectPathError::InvalidRefDes(value.to_string()))
         }
+
+        Ok(Self(value.to_string()))
     }
 }
 
-impl Display for ObjectPathChunk {
+impl Display for RefDes {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}={}", self.key, self.value)
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Indices are 1-based, the canonical form has no leading zeros, e.g. '01' is '1'.
+fn parse_index(value: &str) -> Result<NonZeroUsize, ObjectPathError> {
+    value.parse::<NonZeroUsize>()
+        .map_err(|_| ObjectPathError::InvalidIndex(value.to_string()))
+}
+
+/// The position of the key in a path, chunks must be in this order and each key can only be used once.
+fn key_position(key: &str) -> Option<usize> {
+    match key {
+        _ if PCB_KEYS.contains(&key) => Some(0),
+        UNIT_KEY => Some(1),
+        REF_DES_KEY => Some(2),
+        _ => None,
+    }
+}
+
+/// Splits the path into its key and value pairs, checking the keys and their order, the values are checked by the caller.
+fn split_chunks(path: &str) -> Result<Vec<(&str, &str)>, ObjectPathError> {
+    let mut chunks = vec![];
+    for chunk in path.split("::") {
+        let (key, value) = chunk.split_once('=')
+            .filter(|(_key, value)| !value.contains('='))
+            .ok_or(ObjectPathError::InvalidChunk(chunk.to_string()))?;
+
+        let position = key_position(key)
+            .ok_or(ObjectPathError::UnknownKey(key.to_string()))?;
+        if position != chunks.len() {
+            return Err(ObjectPathError::InvalidOrder(path.to_string()))
+        }
+
+        chunks.push((key, value));
+    }
+
+    Ok(chunks)
+}
+
+/// The kind of PCB of a key, the key must be one of the `PCB_KEYS`.
+fn pcb_kind(key: &str) -> PcbKind {
+    match key {
+        "panel" => PcbKind::Panel,
+        _ => PcbKind::Single,
+    }
+}
+
+fn pcb_key(kind: &PcbKind) -> &'static str {
+    match kind {
+        PcbKind::Panel => "panel",
+        PcbKind::Single => "single",
     }
 }
 
@@ -51,63 +155,58 @@ impl Display for ObjectPathChunk {
 ///
 /// `panel=1::unit=1`
 /// `panel=1::unit=1::ref_des=R1` 
+///
+/// Paths are validated when they are parsed, including the paths in project files, earlier versions accepted a '0'
+/// index and reference designators containing ':', project files with such paths have to be corrected before they can
+/// be loaded.
 #[derive(Debug, Clone, PartialOrd, Ord, Eq, PartialEq, Default)]
 pub struct ObjectPath {
-    chunks: Vec<ObjectPathChunk>,
+    pcb: Option<(PcbKind, PanelIndex)>,
+    unit: Option<UnitIndex>,
+    ref_des: Option<RefDes>,
 }
 
 impl ObjectPath {
-    pub fn set_ref_des(&mut self, ref_des: String) {
-        self.set_chunk(ObjectPathChunk { key: "ref_des".to_string(), value: ref_des })
+    /// The path of a unit, e.g. 'panel=1::unit=2'.
+    pub fn new_unit(kind: PcbKind, pcb_index: PanelIndex, unit_index: UnitIndex) -> Self {
+        Self {
+            pcb: Some((kind, pcb_index)),
+            unit: Some(unit_index),
+            ref_des: None,
+        }
     }
 
-    pub fn pcb_unit(&self) -> ObjectPath {
-        // TODO consider replacing 'panel' and 'single' with just 'pcb', since the pcb defines the kind now.
-        const PCB_UNIT_KEYS: [&str; 3] = ["panel", "single", "unit"];
-        
-        self.chunks.iter().fold(ObjectPath::default(), | mut object_path, chunk | {
-
-            if PCB_UNIT_KEYS.contains(&chunk.key.as_str()) {
-                object_path.chunks.push(chunk.clone())
-            }
-            
-            object_path  
-        })
+    pub fn set_ref_des(&mut self, ref_des: RefDes) {
+        self.ref_des = Some(ref_des);
     }
-    
-    pub fn pcb_kind_and_index(&self) -> Option<(PcbKind, usize)> {
-        self.find_chunk_by_key("panel")
-            .or_else(|| self.find_chunk_by_key("single"))
-            .map(|chunk|(PcbKind::try_from(&chunk.key).unwrap(), chunk.value.parse().unwrap()))
+
+    /// The reference designator, e.g. 'R1' for 'panel=1::unit=2::ref_des=R1'.
+    pub fn ref_des(&self) -> Option<&RefDes> {
+        self.ref_des.as_ref()
     }
 
-    fn set_chunk(&mut self, chunk: ObjectPathChunk) {
-        let existing_chunk = self.find_chunk_by_key_mut(&chunk.key);
-        match existing_chunk {
-            Some(existing_chunk) => existing_chunk.value = chunk.value,
-            _ => self.chunks.push(chunk)
+    pub fn pcb_unit(&self) -> ObjectPath {
+        // TODO consider replacing 'panel' and 'single' with just 'pcb', since the pcb defines the kind now.
+        ObjectPath {
+            pcb: self.pcb.clone(),
+            unit: self.unit,
+            ref_des: None,
         }
     }
-
-    fn find_chunk_by_key(&self, key: &str) -> Option<&ObjectPathChunk> {
-        let existing_chunk = self.chunks.iter().find(|existing| {
-            existing.key.eq(key)
-        });
-        
-        existing_chunk
+    
+    pub fn pcb_kind_and_index(&self) -> Option<(PcbKind, PanelIndex)> {
+        self.pcb.clone()
     }
 
-    fn find_chunk_by_key_mut(&mut self, key: &str) -> Option<&mut ObjectPathChunk> {
-        let existing_chunk = self.chunks.iter_mut().find(|existing| {
-            existing.key.eq(key)
-        });
-
-        existing_chunk
+    /// The index of the unit, e.g. 2 for 'panel=1::unit=2::ref_des=R1'.
+    pub fn unit_index(&self) -> Option<UnitIndex> {
+        self.unit
     }
 }
 
 #[cfg(test)]
 mod pcb_unit_tests {
+    use proptest::prelude::*;
     use rstest::rstest;
     use super::*;
     
@@ -130,12 +229,89 @@ mod pcb_unit_tests {
     /// the invalid trailing ':' becomes part of the index.
     #[case("panel=1:", Err(ObjectPathError::InvalidIndex("1:".to_string())))]
     #[case("panel=1::::ref_des=R1", Err(ObjectPathError::InvalidChunk("".to_string())))]
+    #[case("panel=0::unit=1", Err(ObjectPathError::InvalidIndex("0".to_string())))]
+    #[case("panel=1::unit=-1", Err(ObjectPathError::InvalidIndex("-1".to_string())))]
+    #[case("panel=1::unit=1::ref_des=", Err(ObjectPathError::InvalidRefDes("".to_string())))]
+    #[case("panel=1::unit=1::ref_des=R1:", Err(ObjectPathError::InvalidRefDes("R1:".to_string())))]
+    #[case("panel=1::unit=1::ref_des=R=1", Err(ObjectPathError::InvalidChunk("ref_des=R=1".to_string())))]
+    #[case("unit=1::panel=1", Err(ObjectPathError::InvalidOrder("unit=1::panel=1".to_string())))]
+    #[case("panel=1::ref_des=R1", Err(ObjectPathError::InvalidOrder("panel=1::ref_des=R1".to_string())))]
+    #[case("panel=1::single=1", Err(ObjectPathError::InvalidOrder("panel=1::single=1".to_string())))]
+    #[case("panel=1::unit=1::unit=2", Err(ObjectPathError::InvalidOrder("panel=1::unit=1::unit=2".to_string())))]
+    #[case("panel=1::unit=*", Err(ObjectPathError::InvalidIndex("*".to_string())))]
     pub fn from_str_errors(#[case] input: &str, #[case] expected_result: Result<ObjectPath, ObjectPathError>) {
 
         // expect
         assert_eq!(ObjectPath::from_str(input), expected_result);
     }
     
+    #[rstest]
+    #[case("panel=01::unit=002::ref_des=R1", "panel=1::unit=2::ref_des=R1")]
+    #[case("single=+1::unit=1", "single=1::unit=1")]
+    pub fn canonical_form(#[case] input: &str, #[case] expected_result: &str) {
+        // expect
+        assert_eq!(ObjectPath::from_str(input).unwrap().to_string(), expected_result);
+    }
+
+    proptest! {
+        /// Every valid path round-trips through its canonical form and its components.
+        #[test]
+        fn round_trip(
+            kind in prop::sample::select(vec!["panel", "single"]),
+            panel_index in 1..=usize::MAX,
+            unit_index in 1..=usize::MAX,
+            ref_des in "[^:=]+",
+        ) {
+            // given
+            let input = format!("{}={}::unit={}::ref_des={}", kind, panel_index, unit_index, ref_des);
+
+            // when
+            let object_path = ObjectPath::from_str(&input).unwrap();
+
+            // then
+            prop_assert_eq!(object_path.to_string(), input);
+            prop_assert_eq!(ObjectPath::from_str(&object_path.to_string()).unwrap(), object_path.clone());
+
+            // and
+            prop_assert_eq!(object_path.pcb_kind_and_index(), Some((PcbKind::try_from(&kind.to_string()).unwrap(), PanelIndex::new(panel_index).unwrap())));
+            prop_assert_eq!(object_path.unit_index(), Some(UnitIndex::new(unit_index).unwrap()));
+            prop_assert_eq!(object_path.ref_des().map(RefDes::as_str), Some(ref_des.as_str()));
+        }
+    }
+
+    #[test]
+    pub fn new_unit() {
+        // given
+        let expected_result = ObjectPath::from_str("panel=1::unit=2").expect("always ok");
+
+        // when
+        let result = ObjectPath::new_unit(PcbKind::Panel, PanelIndex::new(1).unwrap(), UnitIndex::new(2).unwrap());
+
+        // then
+        assert_eq!(result, expected_result);
+    }
+
+    #[test]
+    pub fn indices_are_not_zero() {
+        // expect
+        assert_eq!(PanelIndex::new(0), Err(ObjectPathError::InvalidIndex("0".to_string())));
+        assert_eq!(UnitIndex::new(0), Err(ObjectPathError::InvalidIndex("0".to_string())));
+    }
+
+    #[test]
+    pub fn units_are_in_index_order() {
+        // given
+        let mut object_paths: Vec<ObjectPath> = ["panel=1::unit=10", "panel=1::unit=2", "panel=1::unit=1"].iter()
+            .map(|object_path| ObjectPath::from_str(object_path).unwrap())
+            .collect();
+
+        // when
+        object_paths.sort();
+
+        // then
+        assert_eq!(object_paths.iter().map(ToString::to_string).collect::<Vec<_>>(), vec!["panel=1::unit=1", "panel=1::unit=2", "panel=1::unit=10"]);
+    }
+
     #[test]
     pub fn pcb_unit() {
         // given
@@ -160,7 +336,7 @@ mod pcb_unit_tests {
         let expected_result= ObjectPath::from_str("panel=1::unit=1::ref_des=R1").expect("always ok");
         
         // when
-        object_path.set_ref_des("R1".to_string());
+        object_path.set_ref_des(RefDes::from_str("R1").unwrap());
         
         // then
         assert_eq!(object_path, expected_result);
@@ -175,7 +351,7 @@ mod pcb_unit_tests {
         let expected_result= ObjectPath::from_str("panel=1::unit=1::ref_des=R1").expect("always ok");
         
         // when
-        object_path.set_ref_des("R1".to_string());
+        object_path.set_ref_des(RefDes::from_str("R1").unwrap());
         
         // then
         assert_eq!(object_path, expected_result);
@@ -184,10 +360,17 @@ mod pcb_unit_tests {
 
 impl Display for ObjectPath {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let formatted_chunks: Vec<String> = self.chunks.iter()
-            .map(|chunk| format!("{}", chunk))
-            .collect();
-        
+        let mut formatted_chunks: Vec<String> = vec![];
+        if let Some((kind, index)) = &self.pcb {
+            formatted_chunks.push(format!("{}={}", pcb_key(kind), index));
+        }
+        if let Some(unit) = &self.unit {
+            formatted_chunks.push(format!("{}={}", UNIT_KEY, unit));
+        }
+        if let Some(ref_des) = &self.ref_des {
+            formatted_chunks.push(format!("{}={}", REF_DES_KEY, ref_des));
+        }
+
         write!(f, "{}",
            formatted_chunks.join("::")
         )
@@ -198,21 +381,156 @@ impl FromStr for ObjectPath {
     type Err = ObjectPathError;
 
     fn from_str(value: &str) -> Result<Self, Self::Err> {
-        value.split("::")
-            .try_fold(ObjectPath::default(), |mut object_path: ObjectPath, chunk_str| {
-                match ObjectPathChunk::from_str(chunk_str) {
-                    Ok(chunk) => {
-                        object_path.chunks.push(chunk);
-                        Ok(object_path)
-                    },
-                    Err(err) => Err(err),
-                }
+        let mut object_path = ObjectPath::default();
+        for (key, value) in split_chunks(value)? {
+            match key {
+                UNIT_KEY => object_path.unit = Some(UnitIndex::from_str(value)?),
+                REF_DES_KEY => object_path.ref_des = Some(RefDes::from_str(value)?),
+                _ => object_path.pcb = Some((pcb_kind(key), PanelIndex::from_str(value)?)),
+            }
+        }
+
+        Ok(object_path)
+    }
+}
+
+/// A chunk of a pattern, an index of `None` is the '*' wildcard.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ObjectPathChunkPattern {
+    Pcb { kind: PcbKind, index: Option<PanelIndex> },
+    Unit { index: Option<UnitIndex> },
+    RefDes(RefDes),
+    /// A reference designator with '*' and '?' wildcards.
+    RefDesWildcard(String),
+}
+
+impl ObjectPathChunkPattern {
+    fn is_match(&self, object_path: &ObjectPath) -> bool {
+        match self {
+            ObjectPathChunkPattern::Pcb { kind, index } => object_path.pcb.as_ref()
+                .is_some_and(|(pcb_kind, pcb_index)| pcb_kind.eq(kind) && index.is_none_or(|index| index.eq(pcb_index))),
+            ObjectPathChunkPattern::Unit { index } => object_path.unit
+                .is_some_and(|unit_index| index.is_none_or(|index| index.eq(&unit_index))),
+            ObjectPathChunkPattern::RefDes(ref_des) => object_path.ref_des.as_ref()
+                .is_some_and(|candidate| candidate.eq(ref_des)),
+            ObjectPathChunkPattern::RefDesWildcard(pattern) => object_path.ref_des.as_ref()
+                .is_some_and(|candidate| wildcard_match(pattern, candidate.as_str())),
+        }
+    }
+}
+
+impl Display for ObjectPathChunkPattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ObjectPathChunkPattern::Pcb { kind, index: Some(index) } => write!(f, "{}={}", pcb_key(kind), index),
+            ObjectPathChunkPattern::Pcb { kind, index: None } => write!(f, "{}=*", pcb_key(kind)),
+            ObjectPathChunkPattern::Unit { index: Some(index) } => write!(f, "{}={}", UNIT_KEY, index),
+            ObjectPathChunkPattern::Unit { index: None } => write!(f, "{}=*", UNIT_KEY),
+            ObjectPathChunkPattern::RefDes(ref_des) => write!(f, "{}={}", REF_DES_KEY, ref_des),
+            ObjectPathChunkPattern::RefDesWildcard(pattern) => write!(f, "{}={}", REF_DES_KEY, pattern),
+        }
+    }
+}
+
+/// A pattern that matches object paths, the keys, their order and the values are validated like an [`ObjectPath`] so
+/// that a malformed pattern is an error instead of a pattern that silently matches nothing.
+///
+/// An index can be '*', a reference designator can use the '*' (any characters) and '?' (any character) wildcards, a
+/// pattern without a reference designator matches all the placements of the matching units.
+///
+/// e.g.
+///
+/// `panel=1::unit=*::ref_des=R1`
+/// `panel=1::unit=2`
+/// `single=1::unit=1::ref_des=C?`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectPathPattern {
+    chunks: Vec<ObjectPathChunkPattern>,
+}
+
+impl ObjectPathPattern {
+    pub fn is_match(&self, object_path: &ObjectPath) -> bool {
+        self.chunks.iter().all(|pattern| pattern.is_match(object_path))
+    }
+}
+
+impl From<&ObjectPath> for ObjectPathPattern {
+    /// A pattern that matches the object path, and the object paths below it.
+    fn from(object_path: &ObjectPath) -> Self {
+        let pcb = object_path.pcb.as_ref()
+            .map(|(kind, index)| ObjectPathChunkPattern::Pcb { kind: kind.clone(), index: Some(*index) });
+        let unit = object_path.unit
+            .map(|index| ObjectPathChunkPattern::Unit { index: Some(index) });
+        let ref_des = object_path.ref_des.clone()
+            .map(ObjectPathChunkPattern::RefDes);
+
+        Self { chunks: pcb.into_iter().chain(unit).chain(ref_des).collect() }
+    }
+}
+
+impl FromStr for ObjectPathPattern {
+    type Err = ObjectPathError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let chunks = split_chunks(value)?
+            .into_iter()
+            .map(|(key, value)| match (key, value) {
+                (UNIT_KEY, "*") => Ok(ObjectPathChunkPattern::Unit { index: None }),
+                (UNIT_KEY, _) => UnitIndex::from_str(value).map(|index| ObjectPathChunkPattern::Unit { index: Some(index) }),
+                (REF_DES_KEY, _) if value.contains(['*', '?']) => RefDes::from_str(value).map(|ref_des| ObjectPathChunkPattern::RefDesWildcard(ref_des.to_string())),
+                (REF_DES_KEY, _) => RefDes::from_str(value).map(ObjectPathChunkPattern::RefDes),
+                (_, "*") => Ok(ObjectPathChunkPattern::Pcb { kind: pcb_kind(key), index: None }),
+                _ => PanelIndex::from_str(value).map(|index| ObjectPathChunkPattern::Pcb { kind: pcb_kind(key), index: Some(index) }),
             })
-        
-        // TODO validate the the order of the chunks is correct
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(ObjectPathPattern { chunks })
+    }
+}
+
+impl Display for ObjectPathPattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let formatted_chunks: Vec<String> = self.chunks.iter()
+            .map(|chunk| format!("{}", chunk))
+            .collect();
+
+        write!(f, "{}", formatted_chunks.join("::"))
     }
 }
 
+/// Matches the value against a pattern where '*' matches any characters and '?' matches any single character.
+fn wildcard_match(pattern: &str, value: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let value: Vec<char> = value.chars().collect();
+
+    let (mut pattern_index, mut value_index) = (0, 0);
+    // the position of the last '*' and the value position it was tried at, for backtracking
+    let mut star: Option<(usize, usize)> = None;
+
+    while value_index < value.len() {
+        match pattern.get(pattern_index) {
+            Some('*') => {
+                star = Some((pattern_index, value_index));
+                pattern_index += 1;
+            },
+            Some(character) if *character == '?' || *character == value[value_index] => {
+                pattern_index += 1;
+                value_index += 1;
+            },
+            _ => match star {
+                Some((star_pattern_index, star_value_index)) => {
+                    star = Some((star_pattern_index, star_value_index + 1));
+                    pattern_index = star_pattern_index + 1;
+                    value_index = star_value_index + 1;
+                },
+                None => return false,
+            },
+        }
+    }
+
+    pattern[pattern_index..].iter().all(|character| *character == '*')
+}
+
 #[derive(Error, Debug, PartialEq)]
 pub enum ObjectPathError {
     #[error("Invalid object path. value: '{0:}'")]
@@ -223,4 +541,83 @@ pub enum ObjectPathError {
     InvalidChunk(String),
     #[error("Invalid chunk key in path. key: '{0:}'")]
     UnknownKey(String),
+    #[error("Invalid chunk order in path, expected '<panel|single>=<index>[::unit=<index>[::ref_des=<ref_des>]]'. value: '{0:}'")]
+    InvalidOrder(String),
+    #[error("Invalid reference designator in path. ref_des: '{0:}'")]
+    InvalidRefDes(String),
+}
+
+#[cfg(test)]
+mod pattern_tests {
+    use rstest::rstest;
+    use super::*;
+
+    #[rstest]
+    #[case("panel=1::unit=1::ref_des=R1", "panel=1::unit=1::ref_des=R1", true)]
+    #[case("panel=1::unit=1::ref_des=R1", "panel=1::unit=1::ref_des=R10", false)]
+    #[case("panel=1::unit=1::ref_des=R1", "panel=1::unit=10::ref_des=R1", false)]
+    #[case("panel=1::unit=*::ref_des=R1", "panel=1::unit=10::ref_des=R1", true)]
+    #[case("panel=1::unit=1", "panel=1::unit=1::ref_des=J1", true)]
+    #[case("panel=1::unit=1", "panel=1::unit=11::ref_des=J1", false)]
+    #[case("panel=*::unit=*::ref_des=*", "panel=2::unit=3::ref_des=C1", true)]
+    #[case("panel=*", "single=1::unit=1::ref_des=C1", false)]
+    #[case("panel=1::unit=1::ref_des=R*", "panel=1::unit=1::ref_des=R12", true)]
+    #[case("panel=1::unit=1::ref_des=R*", "panel=1::unit=1::ref_des=C1", false)]
+    #[case("panel=1::unit=1::ref_des=R?", "panel=1::unit=1::ref_des=R1", true)]
+    #[case("panel=1::unit=1::ref_des=R?", "panel=1::unit=1::ref_des=R10", false)]
+    #[case("panel=1::unit=1::ref_des=*_A", "panel=1::unit=1::ref_des=U1_B_A", true)]
+    #[case("panel=01::unit=01::ref_des=R1", "panel=1::unit=1::ref_des=R1", true)]
+    #[case("panel=1::unit=1::ref_des=R1", "panel=1::unit=1", false)]
+    pub fn is_match(#[case] pattern: &str, #[case] object_path: &str, #[case] expected_result: bool) {
+        // given
+        let pattern = ObjectPathPattern::from_str(pattern).unwrap();
+        let object_path = ObjectPath::from_str(object_path).unwrap();
+
+        // expect
+        assert_eq!(pattern.is_match(&object_path), expected_result);
+    }
+
+    #[rstest]
+    #[case("panel=1::unit=x", ObjectPathError::InvalidIndex("x".to_string()))]
+    #[case("panel=1::unit=0", ObjectPathError::InvalidIndex("0".to_string()))]
+    #[case("panel=1::unit=R*", ObjectPathError::InvalidIndex("R*".to_string()))]
+    #[case("panel=1::ref_des=R1", ObjectPathError::InvalidOrder("panel=1::ref_des=R1".to_string()))]
+    #[case(".*J1", ObjectPathError::InvalidChunk(".*J1".to_string()))]
+    #[case("panel=1::unit=1::ref_des=", ObjectPathError::InvalidRefDes("".to_string()))]
+    pub fn from_str_errors(#[case] input: &str, #[case] expected_error: ObjectPathError) {
+        // expect
+        assert_eq!(ObjectPathPattern::from_str(input), Err(expected_error));
+    }
+
+    #[test]
+    pub fn canonical_form() {
+        // expect
+        assert_eq!(ObjectPathPattern::from_str("panel=01::unit=*::ref_des=R?").unwrap().to_string(), "panel=1::unit=*::ref_des=R?");
+    }
+
+    /// An object path is matched by its own pattern, by the pattern of its unit, and by a pattern with the unit index
+    /// replaced by a wildcard, but not by the pattern of any other object path.
+    #[test]
+    pub fn object_path_matches() {
+        // given
+        let object_paths: Vec<ObjectPath> = ["panel", "single"].iter()
+            .flat_map(|kind| [1, 2, 10, 11].map(move |unit| (kind, unit)))
+            .flat_map(|(kind, unit)| ["R1", "R10", "C1"].map(move |ref_des| format!("{}=1::unit={}::ref_des={}", kind, unit, ref_des)))
+            .map(|object_path| ObjectPath::from_str(&object_path).unwrap())
+            .collect();
+
+        for object_path in object_paths.iter() {
+            // when
+            let pattern = ObjectPathPattern::from(object_path);
+            let unit_pattern = ObjectPathPattern::from(&object_path.pcb_unit());
+            let any_unit_pattern = ObjectPathPattern::from_str(&object_path.to_string().replace(&format!("unit={}", object_path.unit_index().unwrap()), "unit=*")).unwrap();
+
+            // then
+            for other in object_paths.iter() {
+                assert_eq!(pattern.is_match(other), other.eq(object_path));
+                assert_eq!(unit_pattern.is_match(other), other.pcb_unit().eq(&object_path.pcb_unit()));
+                assert_eq!(any_unit_pattern.is_match(other), other.pcb_kind_and_index() == object_path.pcb_kind_and_index() && other.ref_des() == object_path.ref_des());
+            }
+        }
+    }
 }
@@ -1,3 +1,8 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use rust_decimal::Decimal;
+use thiserror::Error;
+
 #[derive(Debug, Clone)]
 #[derive(Hash, PartialEq, Eq, PartialOrd, Ord)]
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -15,3 +20,114 @@ impl Part {
     }
 }
 
+/// Formats the part as `<manufacturer>:<mpn>`, e.g. 'RES_MFR1:RES1'.
+impl Display for Part {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.manufacturer, self.mpn)
+    }
+}
+
+/// Parses a part from `<manufacturer>:<mpn>`, the mpn may contain ':' characters.
+impl FromStr for Part {
+    type Err = PartError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.split_once(':') {
+            Some((manufacturer, mpn)) if !manufacturer.is_empty() && !mpn.is_empty() => {
+                Ok(Part::new(manufacturer.to_string(), mpn.to_string()))
+            },
+            _ => Err(PartError::Invalid(value.to_string())),
+        }
+    }
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum PartError {
+    #[error("Invalid part, expected '<manufacturer>:<mpn>'. value: '{0:}'")]
+    Invalid(String),
+}
+
+
+/// Details from the part library that help operators identify a part.
+#[derive(Debug, Clone, Default)]
+#[derive(PartialEq, Eq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct PartDetails {
+    /// Image path or URL
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub image: Option<String>,
+
+    /// Datasheet URL
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub datasheet: Option<String>,
+
+    /// Package (e.g. '0402'), used to check the part is compatible with a feeder
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub package: Option<String>,
+
+    /// Length of the body, in millimeters, used to sort placements by area
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub length: Option<Decimal>,
+
+    /// Width of the body, in millimeters, used to sort placements by area
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub width: Option<Decimal>,
+
+    /// Height of the body, in millimeters, used to sort placements by height
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub height: Option<Decimal>,
+
+    /// Value (e.g. '10K', '100nF'), for operators and reports
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub value: Option<String>,
+
+    /// Description (e.g. '10K 1% 0402 resistor'), for feeder setup sheets
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// Width of the tape the part is supplied on, in millimeters, for feeder setup sheets
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub tape_width: Option<u32>,
+}
+
+impl PartDetails {
+    pub fn is_empty(&self) -> bool {
+        self.image.is_none() && self.datasheet.is_none() && self.package.is_none()
+            && self.length.is_none() && self.width.is_none() && self.height.is_none()
+            && self.value.is_none() && self.description.is_none() && self.tape_width.is_none()
+    }
+
+    /// The area of the body, `None` unless both the length and the width are known.
+    pub fn area(&self) -> Option<Decimal> {
+        self.length.zip(self.width).map(|(length, width)| length * width)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use crate::part::{Part, PartError};
+
+    #[test]
+    fn parse_and_format() {
+        // when
+        let part = Part::from_str("RES_MFR1:RES1:10K").unwrap();
+
+        // then
+        assert_eq!(part, Part::new("RES_MFR1".to_string(), "RES1:10K".to_string()));
+        assert_eq!(part.to_string(), "RES_MFR1:RES1:10K");
+
+        // and
+        assert_eq!(Part::from_str("RES1"), Err(PartError::Invalid("RES1".to_string())));
+        assert_eq!(Part::from_str(":RES1"), Err(PartError::Invalid(":RES1".to_string())));
+    }
+}
@@ -1,3 +1,9 @@
+use std::str::FromStr;
+use rust_decimal::Decimal;
+use rust_decimal::MathematicalOps;
+use rust_decimal_macros::dec;
+use thiserror::Error;
+
 #[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
 #[serde(rename_all = "lowercase")]
 pub enum PcbSide {
@@ -9,6 +15,34 @@ pub enum PcbSide {
 pub struct Pcb {
     pub kind: PcbKind,
     pub name: String,
+
+    /// Only used for panels, when absent the placements are not transformed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub geometry: Option<PanelGeometry>,
+
+    /// The extents of the board or panel, when absent the coordinates of the placements are not validated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dimensions: Option<PcbDimensions>,
+}
+
+impl Pcb {
+    pub fn new(kind: PcbKind, name: String) -> Self {
+        Self { kind, name, geometry: None, dimensions: None }
+    }
+}
+
+/// The width and height of a board or panel, in millimeters, the extents are from the origin to (width, height).
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PcbDimensions {
+    pub width: Decimal,
+    pub height: Decimal,
+}
+
+impl PcbDimensions {
+    /// True if the coordinates are within the extents, coordinates on the edges are within the extents.
+    pub fn contains(&self, x: Decimal, y: Decimal) -> bool {
+        (Decimal::ZERO..=self.width).contains(&x) && (Decimal::ZERO..=self.height).contains(&y)
+    }
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -28,4 +62,241 @@ impl TryFrom<&String> for PcbKind {
             _ => Err(())
         }
     }
-}
\ No newline at end of file
+}
+#[derive(Error, Debug, PartialEq)]
+pub enum PanelGeometryError {
+    #[error("Invalid panel unit, expected '<unit>,<x>,<y>,<rotation>'. value: '{0}'")]
+    InvalidUnit(String),
+
+    #[error("Invalid fiducial, expected '<name>,<x>,<y>'. value: '{0}'")]
+    InvalidFiducial(String),
+
+    #[error("Duplicate panel unit. unit: {0}")]
+    DuplicateUnit(usize),
+}
+
+/// The positions of the units and fiducials of a panel, relative to the origin of the panel, in millimeters.
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PanelGeometry {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub units: Vec<PanelUnit>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fiducials: Vec<Fiducial>,
+}
+
+impl PanelGeometry {
+    pub fn new(units: Vec<PanelUnit>, fiducials: Vec<Fiducial>) -> Result<Self, PanelGeometryError> {
+        let mut unit_indexes: Vec<usize> = units.iter().map(|unit| unit.unit).collect();
+        unit_indexes.sort();
+        if let Some(window) = unit_indexes.windows(2).find(|window| window[0] == window[1]) {
+            return Err(PanelGeometryError::DuplicateUnit(window[0]))
+        }
+
+        Ok(Self { units, fiducials })
+    }
+
+    /// Finds the unit by its 1-based index, as used in object paths, e.g. 'unit=2'.
+    pub fn find_unit(&self, index: usize) -> Option<&PanelUnit> {
+        self.units.iter().find(|unit| unit.unit == index)
+    }
+}
+
+/// The position of the origin of a unit in the panel, and the anti-clockwise rotation of the unit about its origin.
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PanelUnit {
+    pub unit: usize,
+    pub x: Decimal,
+    pub y: Decimal,
+    pub rotation: Decimal,
+}
+
+impl PanelUnit {
+    /// Transforms design-space coordinates, relative to the origin of the unit, into panel-space coordinates.
+    ///
+    /// Returns the x, y and rotation, the rotation is in the range >-180 to +180.
+    pub fn transform(&self, x: Decimal, y: Decimal, rotation: Decimal) -> (Decimal, Decimal, Decimal) {
+        let (sin, cos) = sin_cos_degrees(self.rotation);
+
+        let panel_x = (x * cos - y * sin + self.x).normalize();
+        let panel_y = (x * sin + y * cos + self.y).normalize();
+
+        (panel_x, panel_y, normalize_rotation(rotation + self.rotation))
+    }
+}
+
+impl FromStr for PanelUnit {
+    type Err = PanelGeometryError;
+
+    /// e.g. '2,55.5,0,90'
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || PanelGeometryError::InvalidUnit(value.to_string());
+
+        let values = split_values(value);
+        let [unit, x, y, rotation] = values.as_slice() else {
+            return Err(invalid())
+        };
+
+        Ok(Self {
+            unit: unit.parse().ok().filter(|unit| *unit > 0).ok_or_else(invalid)?,
+            x: Decimal::from_str(x).map_err(|_| invalid())?,
+            y: Decimal::from_str(y).map_err(|_| invalid())?,
+            rotation: Decimal::from_str(rotation).map_err(|_| invalid())?,
+        })
+    }
+}
+
+/// A fiducial, used by the machine to locate the panel.
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Fiducial {
+    pub name: String,
+    pub x: Decimal,
+    pub y: Decimal,
+}
+
+impl FromStr for Fiducial {
+    type Err = PanelGeometryError;
+
+    /// e.g. 'FID1,5,5'
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || PanelGeometryError::InvalidFiducial(value.to_string());
+
+        let values = split_values(value);
+        let [name, x, y] = values.as_slice() else {
+            return Err(invalid())
+        };
+
+        if name.is_empty() {
+            return Err(invalid())
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            x: Decimal::from_str(x).map_err(|_| invalid())?,
+            y: Decimal::from_str(y).map_err(|_| invalid())?,
+        })
+    }
+}
+
+fn split_values(value: &str) -> Vec<&str> {
+    value.split(',').map(str::trim).collect()
+}
+
+/// Multiples of 90 degrees are exact, since most panels only rotate units by 90 or 180 degrees.
+fn sin_cos_degrees(degrees: Decimal) -> (Decimal, Decimal) {
+    let degrees = normalize_rotation(degrees);
+
+    if degrees == dec!(0) {
+        (dec!(0), dec!(1))
+    } else if degrees == dec!(90) {
+        (dec!(1), dec!(0))
+    } else if degrees == dec!(180) {
+        (dec!(0), dec!(-1))
+    } else if degrees == dec!(-90) {
+        (dec!(-1), dec!(0))
+    } else {
+        let radians = degrees * Decimal::PI / dec!(180);
+        (radians.sin().round_dp(12), radians.cos().round_dp(12))
+    }
+}
+
+/// Normalizes an anti-clockwise rotation to the range >-180 to +180.
+fn normalize_rotation(mut rotation: Decimal) -> Decimal {
+    while rotation > dec!(180) {
+        rotation -= dec!(360);
+    }
+    while rotation <= dec!(-180) {
+        rotation += dec!(360);
+    }
+    rotation.normalize()
+}
+
+/// Finds the PCB for the kind and index of an object path (e.g. 'panel=2'), the index is 1-based and only counts
+/// PCBs of the same kind, in the order they were added.
+pub fn find_pcb<'a>(pcbs: &'a [Pcb], kind: &PcbKind, index: usize) -> Option<&'a Pcb> {
+    pcbs.iter()
+        .filter(|pcb| pcb.kind.eq(kind))
+        .nth(index.checked_sub(1)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use rust_decimal_macros::dec;
+    use crate::pcb::{find_pcb, Fiducial, PanelGeometry, PanelGeometryError, PanelUnit, Pcb, PcbDimensions, PcbKind};
+
+    #[test]
+    pub fn find_pcb_by_kind_and_index() {
+        // given
+        let pcbs = vec![
+            Pcb::new(PcbKind::Panel, "main_board".to_string()),
+            Pcb::new(PcbKind::Single, "display_board".to_string()),
+            Pcb::new(PcbKind::Panel, "power_board".to_string()),
+        ];
+
+        // expect
+        assert_eq!(find_pcb(&pcbs, &PcbKind::Panel, 2).map(|pcb| pcb.name.as_str()), Some("power_board"));
+        assert_eq!(find_pcb(&pcbs, &PcbKind::Single, 1).map(|pcb| pcb.name.as_str()), Some("display_board"));
+        assert_eq!(find_pcb(&pcbs, &PcbKind::Single, 2), None);
+        assert_eq!(find_pcb(&pcbs, &PcbKind::Panel, 0), None);
+    }
+
+    #[test]
+    pub fn transform_unit() {
+        // given
+        let unit = PanelUnit { unit: 2, x: dec!(100), y: dec!(50), rotation: dec!(90) };
+
+        // when
+        let (x, y, rotation) = unit.transform(dec!(10), dec!(5), dec!(135));
+
+        // then
+        assert_eq!((x, y, rotation), (dec!(95), dec!(60), dec!(-135)));
+    }
+
+    #[test]
+    pub fn transform_unit_by_arbitrary_angle() {
+        // given
+        let unit = PanelUnit { unit: 1, x: dec!(0), y: dec!(0), rotation: dec!(45) };
+
+        // when
+        let (x, y, rotation) = unit.transform(dec!(10), dec!(0), dec!(0));
+
+        // then
+        assert_eq!((x.round_dp(6), y.round_dp(6), rotation), (dec!(7.071068), dec!(7.071068), dec!(45)));
+    }
+
+    #[test]
+    pub fn parse_units_and_fiducials() {
+        assert_eq!(PanelUnit::from_str("2, 55.5, 0, 180"), Ok(PanelUnit { unit: 2, x: dec!(55.5), y: dec!(0), rotation: dec!(180) }));
+        assert_eq!(PanelUnit::from_str("0,1,2,3"), Err(PanelGeometryError::InvalidUnit("0,1,2,3".to_string())));
+        assert_eq!(PanelUnit::from_str("1,1,2"), Err(PanelGeometryError::InvalidUnit("1,1,2".to_string())));
+        assert_eq!(Fiducial::from_str("FID1,5,-5"), Ok(Fiducial { name: "FID1".to_string(), x: dec!(5), y: dec!(-5) }));
+        assert_eq!(Fiducial::from_str(",5,5"), Err(PanelGeometryError::InvalidFiducial(",5,5".to_string())));
+    }
+
+    #[test]
+    pub fn duplicate_units() {
+        // given
+        let units = vec![
+            PanelUnit { unit: 1, x: dec!(0), y: dec!(0), rotation: dec!(0) },
+            PanelUnit { unit: 1, x: dec!(50), y: dec!(0), rotation: dec!(0) },
+        ];
+
+        // expect
+        assert_eq!(PanelGeometry::new(units, vec![]), Err(PanelGeometryError::DuplicateUnit(1)));
+    }
+
+    #[test]
+    pub fn dimensions_contain_coordinates() {
+        // given
+        let dimensions = PcbDimensions { width: dec!(100), height: dec!(80) };
+
+        // expect
+        assert!(dimensions.contains(dec!(0), dec!(0)));
+        assert!(dimensions.contains(dec!(100), dec!(80)));
+        assert!(dimensions.contains(dec!(55.5), dec!(12.25)));
+        assert!(!dimensions.contains(dec!(-0.1), dec!(10)));
+        assert!(!dimensions.contains(dec!(10), dec!(80.01)));
+        // e.g. inches instead of millimeters
+        assert!(!dimensions.contains(dec!(2540), dec!(10)));
+    }
+}
@@ -1,3 +1,4 @@
+use std::fmt::{Display, Formatter};
 use rust_decimal::Decimal;
 use crate::pcb::PcbSide;
 use crate::part::Part;
@@ -18,4 +19,34 @@ pub struct Placement {
     /// Positive values indicate anti-clockwise rotation
     /// Range is >-180 to +180.
     pub rotation: Decimal,
+
+    #[serde(skip_serializing_if = "PlacementKind::is_component")]
+    #[serde(default)]
+    pub kind: PlacementKind,
+}
+
+/// Only components are placed, fiducials and test points are in the EDA exports but are only needed for machine setup
+/// and testing.
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PlacementKind {
+    #[default]
+    Component,
+    Fiducial,
+    TestPoint,
+}
+
+impl PlacementKind {
+    pub fn is_component(&self) -> bool {
+        matches!(self, PlacementKind::Component)
+    }
+}
+
+impl Display for PlacementKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlacementKind::Component => write!(f, "Component"),
+            PlacementKind::Fiducial => write!(f, "Fiducial"),
+            PlacementKind::TestPoint => write!(f, "TestPoint"),
+        }
+    }
 }
@@ -0,0 +1,402 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+use anyhow::{bail, Context};
+use time::OffsetDateTime;
+use tracing::{info, trace};
+use planning::mutation::MUTATION_TARGET;
+
+/// Directory, alongside the backed-up file, that contains the backups.
+pub const BACKUP_DIR: &str = ".backups";
+
+/// Maximum amount of backups kept for each file, the oldest backups are removed first.
+pub const MAX_BACKUPS: usize = 10;
+
+/// A file is backed up at most once per interval, so that a script of many commands does not replace all the backups.
+pub const MIN_BACKUP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How many backups of each file are kept, and for how long, when cleaning up, see [`cleanup`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetentionPolicy {
+    /// At least one backup of each file is kept.
+    pub max_count: usize,
+    /// `None` to keep backups regardless of their age, the latest backup of each file is kept regardless of its age.
+    pub max_age: Option<Duration>,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            max_count: MAX_BACKUPS,
+            max_age: None,
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct CleanupSummary {
+    /// The backups that were removed, or that would be removed for a dry run.
+    pub removed: Vec<PathBuf>,
+    pub reclaimed_bytes: u64,
+}
+
+/// Backs up the file before it is modified, unless the latest backup is more recent than `MIN_BACKUP_INTERVAL`.
+///
+/// Missing and empty files are not backed up, returns the path of the backup, if one was made.
+pub fn backup_before_modification(path: &Path) -> anyhow::Result<Option<PathBuf>> {
+    if !has_content(path) {
+        return Ok(None)
+    }
+
+    if let Some(latest) = find_backups(path)?.last() {
+        let modified = latest.metadata()?.modified()?;
+        let age = SystemTime::now().duration_since(modified).unwrap_or_default();
+        if age < MIN_BACKUP_INTERVAL {
+            trace!("Skipping backup, recent backup exists. path: {:?}, backup: {:?}", path, latest);
+            return Ok(None)
+        }
+    }
+
+    backup(path).map(Some)
+}
+
+/// Backs up the file, regardless of the age of the latest backup, removing the oldest backups that exceed `MAX_BACKUPS`.
+pub fn backup(path: &Path) -> anyhow::Result<PathBuf> {
+    let backup_dir = build_backup_dir(path);
+    fs::create_dir_all(&backup_dir)
+        .with_context(|| format!("Error creating backup directory. path: {:?}", backup_dir))?;
+
+    let mut backup_path = backup_dir.join(build_backup_file_name(path, OffsetDateTime::now_utc()));
+    while backup_path.exists() {
+        // another backup was made within the same millisecond
+        thread::sleep(Duration::from_millis(1));
+        backup_path = backup_dir.join(build_backup_file_name(path, OffsetDateTime::now_utc()));
+    }
+
+    // the content is written, instead of copied, so that the modification time of the backup is the time of the backup
+    let content = fs::read(path)
+        .with_context(|| format!("Error reading file. path: {:?}", path))?;
+    fs::write(&backup_path, content)
+        .with_context(|| format!("Error writing backup. path: {:?}", backup_path))?;
+
+    info!("Backed up file. path: {:?}, backup: {:?}", path, backup_path);
+
+    let backups = find_backups(path)?;
+    let excess = backups.len().saturating_sub(MAX_BACKUPS);
+    for old_backup in backups.iter().take(excess) {
+        fs::remove_file(old_backup)
+            .with_context(|| format!("Error removing backup. path: {:?}", old_backup))?;
+        info!("Removed old backup. backup: {:?}", old_backup);
+    }
+
+    Ok(backup_path)
+}
+
+/// Returns the backups of the file, oldest first.
+pub fn find_backups(path: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let backup_dir = build_backup_dir(path);
+    if !backup_dir.exists() {
+        return Ok(vec![])
+    }
+
+    let (stem, extension) = split_file_name(path);
+    let prefix = format!("{}.", stem);
+
+    let mut backups: Vec<PathBuf> = fs::read_dir(&backup_dir)
+        .with_context(|| format!("Error reading backup directory. path: {:?}", backup_dir))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|backup_path| {
+            let (backup_stem, backup_extension) = split_file_name(backup_path);
+            backup_extension.eq(&extension)
+                && backup_stem.strip_prefix(&prefix).is_some_and(is_timestamp)
+        })
+        .collect();
+
+    // the timestamps sort chronologically
+    backups.sort();
+
+    Ok(backups)
+}
+
+/// Restores a backup of the file, the current content of the file is backed up first so that the restore can be undone.
+pub fn restore(path: &Path, backup_path: &Path) -> anyhow::Result<()> {
+    if !find_backups(path)?.iter().any(|candidate| candidate.eq(backup_path)) {
+        bail!("Unknown backup. path: {:?}, backup: {:?}", path, backup_path)
+    }
+
+    if has_content(path) {
+        backup(path)?;
+    }
+
+    let content = fs::read(backup_path)
+        .with_context(|| format!("Error reading backup. path: {:?}", backup_path))?;
+    fs::write(path, content)
+        .with_context(|| format!("Error writing file. path: {:?}", path))?;
+
+    info!(target: MUTATION_TARGET, path = ?path, backup = ?backup_path, message = "Restored file. path: {path}, backup: {backup}");
+
+    Ok(())
+}
+
+/// Removes the backups in the backup directory that are not retained by the policy, with `dry_run` nothing is removed.
+///
+/// Only files named like backups are removed, the age of a backup is the age of the file.
+pub fn cleanup(backup_dir: &Path, policy: &RetentionPolicy, now: SystemTime, dry_run: bool) -> anyhow::Result<CleanupSummary> {
+    let mut summary = CleanupSummary::default();
+
+    if !backup_dir.exists() {
+        return Ok(summary)
+    }
+
+    let mut backup_sets: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+    for entry in fs::read_dir(backup_dir).with_context(|| format!("Error reading backup directory. path: {:?}", backup_dir))? {
+        let backup_path = entry?.path();
+        let file_name = backup_path.file_name().unwrap_or_default().to_string_lossy().to_string();
+
+        if let Some(backed_up_file_name) = find_backed_up_file_name(&file_name) {
+            backup_sets.entry(backed_up_file_name).or_default().push(backup_path);
+        }
+    }
+
+    for (backed_up_file_name, mut backups) in backup_sets {
+        // newest first, the timestamps sort chronologically
+        backups.sort();
+        backups.reverse();
+
+        for (index, backup_path) in backups.iter().enumerate() {
+            let metadata = backup_path.metadata()?;
+            let age = now.duration_since(metadata.modified()?).unwrap_or_default();
+
+            let retained = index == 0 || (index < policy.max_count && policy.max_age.is_none_or(|max_age| age <= max_age));
+            if retained {
+                continue
+            }
+
+            if !dry_run {
+                fs::remove_file(backup_path)
+                    .with_context(|| format!("Error removing backup. path: {:?}", backup_path))?;
+                info!("Removed backup. file: '{}', backup: {:?}", backed_up_file_name, backup_path);
+            }
+
+            summary.reclaimed_bytes += metadata.len();
+            summary.removed.push(backup_path.clone());
+        }
+    }
+
+    Ok(summary)
+}
+
+/// e.g. 'load_out_1.20241016T120000.123Z.csv' -> 'load_out_1.csv', `None` if the file name is not that of a backup.
+fn find_backed_up_file_name(file_name: &str) -> Option<String> {
+    file_name.match_indices('.')
+        .find_map(|(index, _separator)| {
+            let timestamp = file_name.get(index + 1..index + 21)?;
+            let remainder = &file_name[index + 21..];
+
+            match is_timestamp(timestamp) && (remainder.is_empty() || remainder.starts_with('.')) {
+                true => Some(format!("{}{}", &file_name[..index], remainder)),
+                false => None,
+            }
+        })
+}
+
+pub fn build_backup_dir(path: &Path) -> PathBuf {
+    path.parent().unwrap_or(Path::new("")).join(BACKUP_DIR)
+}
+
+/// e.g. 'load_out_1.20241016T120000.123Z.csv'
+fn build_backup_file_name(path: &Path, date_time: OffsetDateTime) -> String {
+    let (stem, extension) = split_file_name(path);
+
+    let timestamp = format!("{:04}{:02}{:02}T{:02}{:02}{:02}.{:03}Z",
+        date_time.year(), date_time.month() as u8, date_time.day(),
+        date_time.hour(), date_time.minute(), date_time.second(), date_time.millisecond(),
+    );
+
+    match extension {
+        Some(extension) => format!("{}.{}.{}", stem, timestamp, extension),
+        None => format!("{}.{}", stem, timestamp),
+    }
+}
+
+fn split_file_name(path: &Path) -> (String, Option<String>) {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+    let extension = path.extension().map(|extension| extension.to_string_lossy().to_string());
+    (stem, extension)
+}
+
+fn is_timestamp(value: &str) -> bool {
+    value.len() == 20
+        && value.ends_with('Z')
+        && value.chars().filter(|c| c.is_ascii_digit()).count() == 17
+}
+
+fn has_content(path: &Path) -> bool {
+    path.metadata().map(|metadata| metadata.len() > 0).unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use assert_fs::TempDir;
+    use time::{Date, Month, OffsetDateTime, Time};
+    use std::time::{Duration, SystemTime};
+    use crate::backup::{backup, backup_before_modification, build_backup_dir, build_backup_file_name, cleanup, find_backed_up_file_name, find_backups, restore, RetentionPolicy, MAX_BACKUPS};
+
+    #[test]
+    pub fn backup_file_name() {
+        // given
+        let date_time = OffsetDateTime::new_utc(
+            Date::from_calendar_date(2024, Month::October, 16).unwrap(),
+            Time::from_hms_milli(9, 5, 1, 42).unwrap(),
+        );
+
+        // when
+        let file_name = build_backup_file_name("project/load_out_1.csv".as_ref(), date_time);
+
+        // then
+        assert_eq!(file_name, "load_out_1.20241016T090501.042Z.csv");
+    }
+
+    #[test]
+    pub fn backup_is_rate_limited() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("load_out_1.csv");
+        fs::write(&path, "content 1")?;
+
+        // when
+        let first = backup_before_modification(&path)?;
+        let second = backup_before_modification(&path)?;
+
+        // then
+        assert!(first.is_some());
+        assert!(second.is_none());
+        assert_eq!(find_backups(&path)?, vec![first.unwrap()]);
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn empty_file_is_not_backed_up() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("load_out_1.csv");
+        fs::write(&path, "")?;
+
+        // when
+        let result = backup_before_modification(&path)?;
+
+        // then
+        assert!(result.is_none());
+        assert!(!build_backup_dir(&path).exists());
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn oldest_backups_are_removed() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("load_out_1.csv");
+        fs::write(&path, "content")?;
+
+        // and a backup of another file, in the same directory
+        let other_path = temp_dir.path().join("load_out_10.csv");
+        fs::write(&other_path, "other content")?;
+        backup(&other_path)?;
+
+        // and
+        let backup_dir = build_backup_dir(&path);
+        fs::create_dir_all(&backup_dir)?;
+        for index in 0..MAX_BACKUPS {
+            fs::write(backup_dir.join(format!("load_out_1.20240101T0000{:02}.000Z.csv", index)), "old content")?;
+        }
+
+        // when
+        let latest = backup(&path)?;
+
+        // then
+        let backups = find_backups(&path)?;
+        assert_eq!(backups.len(), MAX_BACKUPS);
+        assert_eq!(backups.first().unwrap(), &backup_dir.join("load_out_1.20240101T000001.000Z.csv"));
+        assert_eq!(backups.last().unwrap(), &latest);
+
+        // and
+        assert_eq!(find_backups(&other_path)?.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn restore_backs_up_current_content() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("load_out_1.csv");
+        fs::write(&path, "content 1")?;
+        let backup_path = backup(&path)?;
+
+        // and
+        fs::write(&path, "content 2")?;
+
+        // when
+        restore(&path, &backup_path)?;
+
+        // then
+        assert_eq!(fs::read_to_string(&path)?, "content 1");
+
+        // and
+        let backups = find_backups(&path)?;
+        assert_eq!(backups.len(), 2);
+        assert_eq!(fs::read_to_string(backups.last().unwrap())?, "content 2");
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn backed_up_file_name() {
+        assert_eq!(find_backed_up_file_name("load_out_1.20241016T120000.123Z.csv"), Some("load_out_1.csv".to_string()));
+        assert_eq!(find_backed_up_file_name("load_out.v2.20241016T120000.123Z"), Some("load_out.v2".to_string()));
+        assert_eq!(find_backed_up_file_name("load_out_1.csv"), None);
+        assert_eq!(find_backed_up_file_name("notes.txt"), None);
+    }
+
+    #[test]
+    pub fn cleanup_using_count_and_age() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let backup_dir = build_backup_dir(&temp_dir.path().join("load_out_1.csv"));
+        fs::create_dir_all(&backup_dir)?;
+        for index in 1..=4 {
+            fs::write(backup_dir.join(format!("load_out_1.20240101T00000{}.000Z.csv", index)), "content")?;
+        }
+        fs::write(backup_dir.join("load_out_2.20240101T000001.000Z.csv"), "content")?;
+        fs::write(backup_dir.join("notes.txt"), "not a backup")?;
+
+        // when
+        let summary = cleanup(&backup_dir, &RetentionPolicy { max_count: 3, max_age: None }, SystemTime::now(), false)?;
+
+        // then
+        assert_eq!(summary.removed, vec![backup_dir.join("load_out_1.20240101T000001.000Z.csv")]);
+        assert_eq!(summary.reclaimed_bytes, 7);
+        assert_eq!(find_backups(&temp_dir.path().join("load_out_1.csv"))?.len(), 3);
+
+        // when the backups are older than the maximum age
+        let later = SystemTime::now() + Duration::from_secs(60 * 60 * 24 * 2);
+        let dry_run_summary = cleanup(&backup_dir, &RetentionPolicy { max_count: 10, max_age: Some(Duration::from_secs(60 * 60 * 24)) }, later, true)?;
+
+        // then only the latest backup of each file would be kept
+        assert_eq!(dry_run_summary.removed, vec![
+            backup_dir.join("load_out_1.20240101T000003.000Z.csv"),
+            backup_dir.join("load_out_1.20240101T000002.000Z.csv"),
+        ]);
+
+        // and nothing is removed for a dry run
+        assert_eq!(find_backups(&temp_dir.path().join("load_out_1.csv"))?.len(), 3);
+        assert!(backup_dir.join("notes.txt").exists());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,121 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::str::FromStr;
+use anyhow::{bail, Context, Error};
+use tracing::{info, trace};
+use pnp::object_path::ObjectPath;
+use planning::process::ProcessOperationKind;
+use planning::scan::ScanTarget;
+
+/// The default barcode mappings file, in the project directory.
+pub const BARCODE_MAPPINGS_FILE: &str = "barcodes.csv";
+
+#[derive(Debug, Clone, Copy, serde::Deserialize)]
+pub enum BarcodeKind {
+    Unit,
+    Feeder,
+    Operation,
+}
+
+/// A barcode mapping record, e.g.
+///
+/// ```csv
+/// "Barcode","Kind","Value"
+/// "PCB-0001","Unit","panel=1::unit=1"
+/// "FDR-0001","Feeder","FEEDER_1"
+/// "OP-REFLOW","Operation","ReflowComponents"
+/// ```
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all(deserialize = "PascalCase"))]
+pub struct BarcodeMappingRecord {
+    barcode: String,
+    kind: BarcodeKind,
+    value: String,
+}
+
+impl BarcodeMappingRecord {
+    pub fn build_target(&self) -> Result<ScanTarget, Error> {
+        let value = self.value.trim();
+
+        Ok(match self.kind {
+            BarcodeKind::Unit => ScanTarget::Unit(ObjectPath::from_str(value)?.pcb_unit()),
+            BarcodeKind::Feeder => ScanTarget::Feeder(value.to_string()),
+            BarcodeKind::Operation => ScanTarget::Operation(ProcessOperationKind::from_str(value)?),
+        })
+    }
+}
+
+pub fn load_barcode_mappings(barcode_mappings_path: &Path) -> Result<BTreeMap<String, ScanTarget>, Error> {
+    let mut csv_reader = csv::ReaderBuilder::new().from_path(barcode_mappings_path)
+        .with_context(|| format!("Error reading barcode mappings. file: {:?}", barcode_mappings_path))?;
+
+    let mut mappings: BTreeMap<String, ScanTarget> = BTreeMap::new();
+
+    for result in csv_reader.deserialize() {
+        let record: BarcodeMappingRecord = result
+            .with_context(|| "Deserializing barcode mapping record".to_string())?;
+
+        trace!("{:?}", record);
+
+        let target = record.build_target()
+            .with_context(|| format!("Building barcode mapping from record. record: {:?}", record))?;
+
+        let barcode = record.barcode.trim().to_string();
+        if mappings.contains_key(&barcode) {
+            bail!("Duplicate barcode mapping. barcode: '{}', file: {:?}", barcode, barcode_mappings_path)
+        }
+
+        mappings.insert(barcode, target);
+    }
+
+    info!("Loaded barcode mappings. file: {:?}, mappings: {}", barcode_mappings_path, mappings.len());
+
+    Ok(mappings)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use assert_fs::TempDir;
+    use indoc::indoc;
+    use pnp::object_path::ObjectPath;
+    use planning::process::ProcessOperationKind;
+    use planning::scan::ScanTarget;
+    use crate::barcodes::load_barcode_mappings;
+
+    #[test]
+    pub fn load() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let barcode_mappings_path = temp_dir.path().join("barcodes.csv");
+        std::fs::write(&barcode_mappings_path, indoc! {r#"
+            "Barcode","Kind","Value"
+            "PCB-0001","Unit","panel=1::unit=1"
+            "FDR-0001","Feeder","FEEDER_1"
+            "OP-REFLOW","Operation","ReflowComponents"
+            "OP-WASH","Operation","Wash"
+        "#})?;
+
+        // when
+        let mappings = load_barcode_mappings(&barcode_mappings_path)?;
+
+        // then
+        assert_eq!(mappings.len(), 4);
+        assert_eq!(mappings["PCB-0001"], ScanTarget::Unit(ObjectPath::from_str("panel=1::unit=1")?));
+        assert_eq!(mappings["FDR-0001"], ScanTarget::Feeder("FEEDER_1".to_string()));
+        assert_eq!(mappings["OP-REFLOW"], ScanTarget::Operation(ProcessOperationKind::ReflowComponents));
+        assert_eq!(mappings["OP-WASH"], ScanTarget::Operation(ProcessOperationKind::Custom("Wash".to_string())));
+
+        // given
+        std::fs::write(&barcode_mappings_path, indoc! {r#"
+            "Barcode","Kind","Value"
+            "PCB-0001","Unit","panel=1::unit=1"
+            "PCB-0001","Unit","panel=1::unit=2"
+        "#})?;
+
+        // expect
+        assert!(load_barcode_mappings(&barcode_mappings_path).is_err());
+
+        Ok(())
+    }
+}
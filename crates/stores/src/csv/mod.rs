@@ -1,22 +1,19 @@
 use std::collections::HashMap;
+use std::str::FromStr;
 use thiserror::Error;
 use heck::ToUpperCamelCase;
 use regex::{Error, Regex};
+use rust_decimal::Decimal;
 use assembly::rules::AssemblyRule;
 use criteria::{ExactMatchCriterion, GenericCriteria, RegexMatchCriterion, FieldCriterion};
-use eda::EdaTool;
+use eda::plugin::EdaToolRegistry;
 use eda::substitution::{EdaSubstitutionRule, EdaSubstitutionRuleTransformItem};
 use part_mapper::criteria::PlacementMappingCriteria;
 use part_mapper::part_mapping::PartMapping;
-use pnp::part::Part;
+use planning::moisture::{MslLevel, MslLevelError};
+use pnp::part::{Part, PartDetails};
 use pnp::load_out::LoadOutItem;
 
-#[derive(Debug, serde::Deserialize)]
-enum CSVEdaToolValue {
-    DipTrace,
-    KiCad,
-}
-
 #[derive(Debug, serde::Deserialize)]
 #[serde(rename_all(deserialize = "PascalCase"))]
 pub struct PartMappingRecord(HashMap<String, String>);
@@ -40,7 +37,8 @@ pub enum PartMappingRecordError {
 }
 
 impl PartMappingRecord {
-    pub fn build_part_mapping<'part>(&self, parts: &'part [Part]) -> Result<PartMapping<'part>, PartMappingRecordError> {
+    /// The fields of the placements that are matched are those of the EDA tool, see `EdaToolPlugin::field_names`.
+    pub fn build_part_mapping<'part>(&self, parts: &'part [Part], registry: &EdaToolRegistry) -> Result<PartMapping<'part>, PartMappingRecordError> {
 
         // NOTE: Initially the PartMappingRecord had more properties and was using serde flatten on the fields but there was a bug;
         //       so we have to do some deserialization manually instead.
@@ -55,7 +53,7 @@ impl PartMappingRecord {
         let mpn = fields.get("Mpn")
             .ok_or(PartMappingRecordError::MissingField{ field: "Mpn".to_string() })?;
 
-        let eda = csv_eda_tool_value_to_eda_tool(eda).ok_or(PartMappingRecordError::UnknownEda { eda: eda.clone() })?;
+        let eda = registry.find(&eda.to_upper_camel_case()).ok_or(PartMappingRecordError::UnknownEda { eda: eda.clone() })?;
 
 
         let part_criteria: Part = Part { manufacturer: manufacturer.clone(), mpn: mpn.clone() };
@@ -69,7 +67,7 @@ impl PartMappingRecord {
             _ => Err(PartMappingRecordError::NoMatchingPart { criteria: part_criteria })
         }?;
 
-        let fields_names = eda_fields_names(&eda);
+        let fields_names = eda.field_names();
 
         let mut mapping_criteria: Vec<Box<dyn PlacementMappingCriteria>> = vec![];
 
@@ -82,8 +80,8 @@ impl PartMappingRecord {
         
         matched_fields.sort();
 
-        let criteria_fields: Vec<Box<dyn FieldCriterion>> = matched_fields.iter().try_fold(vec![], |mut acc, (&ref key, &ref value)| {
-            let value_kind = build_value_kind(&value)
+        let criteria_fields: Vec<Box<dyn FieldCriterion>> = matched_fields.iter().try_fold(vec![], |mut acc, (key, value)| {
+            let value_kind = build_value_kind(value)
                 .map_err(|error| PartMappingRecordError::InvalidRegex { error })?;
 
             let boxed_criterion: Box<dyn FieldCriterion> = match value_kind {
@@ -129,6 +127,27 @@ pub fn build_value_kind(value: &str) -> Result<ValueKind, Error> {
 pub struct PartRecord {
     manufacturer: String,
     mpn: String,
+    #[serde(default)]
+    image: Option<String>,
+    #[serde(default)]
+    datasheet: Option<String>,
+    #[serde(default)]
+    package: Option<String>,
+    #[serde(default)]
+    length: Option<Decimal>,
+    #[serde(default)]
+    width: Option<Decimal>,
+    #[serde(default)]
+    height: Option<Decimal>,
+    #[serde(default)]
+    value: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    tape_width: Option<u32>,
+    /// Moisture sensitivity level, e.g. '3'
+    #[serde(default)]
+    msl: Option<String>,
 }
 
 impl PartRecord {
@@ -138,6 +157,28 @@ impl PartRecord {
             mpn: self.mpn.clone(),
         })
     }
+
+    pub fn build_part_details(&self) -> PartDetails {
+        PartDetails {
+            image: self.image.clone(),
+            datasheet: self.datasheet.clone(),
+            package: self.package.clone(),
+            length: self.length,
+            width: self.width,
+            height: self.height,
+            value: self.value.clone(),
+            description: self.description.clone(),
+            tape_width: self.tape_width,
+        }
+    }
+
+    /// `None` if the record has no moisture sensitivity level.
+    pub fn build_msl_level(&self) -> Result<Option<MslLevel>, MslLevelError> {
+        self.msl.as_deref()
+            .filter(|msl| !msl.trim().is_empty())
+            .map(MslLevel::from_str)
+            .transpose()
+    }
 }
 
 #[derive(Debug, serde::Deserialize, serde::Serialize)]
@@ -146,14 +187,58 @@ pub struct LoadOutItemRecord {
     pub reference: String,
     pub manufacturer: String,
     pub mpn: String,
+    #[serde(default)]
+    pub quantity: Option<u32>,
+    #[serde(default)]
+    pub reel: Option<String>,
+    /// Alternate parts, in order of preference, e.g. 'RES_MFR2:RES1A;RES_MFR3:RES1B'
+    #[serde(default)]
+    pub alternates: Option<String>,
+    /// The loaded alternate part, e.g. 'RES_MFR2:RES1A', empty if the part itself is loaded.
+    #[serde(default)]
+    pub loaded_alternate: Option<String>,
 }
 
+const ALTERNATES_SEPARATOR: char = ';';
+
 impl LoadOutItemRecord {
+    pub fn from_load_out_item(item: &LoadOutItem) -> Self {
+        let alternates = match item.alternates.is_empty() {
+            true => None,
+            false => Some(item.alternates.iter().map(Part::to_string).collect::<Vec<_>>().join(&ALTERNATES_SEPARATOR.to_string())),
+        };
+
+        Self {
+            reference: item.reference.to_string(),
+            manufacturer: item.manufacturer.to_string(),
+            mpn: item.mpn.to_string(),
+            quantity: item.quantity,
+            reel: item.reel.clone(),
+            alternates,
+            loaded_alternate: item.loaded_alternate.as_ref().map(Part::to_string),
+        }
+    }
+
     pub fn build_load_out_item(&self) -> Result<LoadOutItem, anyhow::Error> {
+        let alternates = self.alternates.iter()
+            .flat_map(|alternates| alternates.split(ALTERNATES_SEPARATOR))
+            .filter(|alternate| !alternate.trim().is_empty())
+            .map(|alternate| Part::from_str(alternate.trim()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let loaded_alternate = self.loaded_alternate.as_deref()
+            .filter(|loaded_alternate| !loaded_alternate.trim().is_empty())
+            .map(|loaded_alternate| Part::from_str(loaded_alternate.trim()))
+            .transpose()?;
+
         Ok(LoadOutItem {
             reference: self.reference.clone(),
             manufacturer: self.manufacturer.clone(),
             mpn: self.mpn.clone(),
+            quantity: self.quantity,
+            reel: self.reel.clone(),
+            alternates,
+            loaded_alternate,
         })
     }
 }
@@ -178,7 +263,9 @@ pub enum SubstitutionRecordError {
 }
 
 impl SubstitutionRecord {
-    pub fn build_eda_substitution(&self) -> anyhow::Result<EdaSubstitutionRule, SubstitutionRecordError> {
+    /// The fields of the placements that are matched and substituted are those of the EDA tool, see
+    /// `EdaToolPlugin::field_names`.
+    pub fn build_eda_substitution(&self, registry: &EdaToolRegistry) -> anyhow::Result<EdaSubstitutionRule, SubstitutionRecordError> {
 
         // NOTE: Initially the SubstitutionRecord had more properties and was using serde flatten on the fields but there was a bug;
         //       so we have to do some deserialization manually instead.
@@ -189,9 +276,9 @@ impl SubstitutionRecord {
         let eda = fields.get("Eda")
             .ok_or(SubstitutionRecordError::MissingField{ field: "Eda".to_string() })?;
 
-        let eda = csv_eda_tool_value_to_eda_tool(eda).ok_or(SubstitutionRecordError::UnknownEda { eda: eda.clone() })?;
+        let eda = registry.find(&eda.to_upper_camel_case()).ok_or(SubstitutionRecordError::UnknownEda { eda: eda.clone() })?;
 
-        let fields_names = eda_fields_names(&eda);
+        let fields_names = eda.field_names();
 
         let mut criteria: Vec<Box<dyn FieldCriterion>> = vec![];
         let mut transforms: Vec<EdaSubstitutionRuleTransformItem> = vec![];
@@ -207,7 +294,7 @@ impl SubstitutionRecord {
             match (fields.get(&name_field), fields.get(&pattern_field)) {
                 (Some(field_name_value), Some(pattern_value)) => {
 
-                    let value_kind = build_value_kind(&pattern_value)
+                    let value_kind = build_value_kind(pattern_value)
                         .map_err(|error| SubstitutionRecordError::InvalidRegex { error })?;
 
                     let boxed_criterion: Box<dyn FieldCriterion> = match value_kind {
@@ -227,23 +314,6 @@ impl SubstitutionRecord {
     }
 }
 
-fn eda_fields_names(eda: &EdaTool) -> &'static [&'static str] {
-    match eda {
-        EdaTool::DipTrace => &["name", "value"],
-        EdaTool::KiCad => &["package", "val"],
-    }
-}
-
-fn csv_eda_tool_value_to_eda_tool(eda: &String) -> Option<EdaTool> {
-    if eda.to_upper_camel_case().eq("DipTrace") {
-        Some(EdaTool::DipTrace)
-    } else if eda.to_upper_camel_case().eq("KiCad") {
-        Some(EdaTool::KiCad)
-    } else {
-        None
-    }
-}
-
 #[derive(Error, Debug)]
 pub enum CSVSubstitutionRecordError {
     #[error("Unknown EDA: '{eda:}'")]
@@ -1,49 +1,351 @@
 use tracing::Level;
-use anyhow::{Context, Error};
-use std::path::PathBuf;
-use tracing::trace;
-use eda::diptrace::csv::DiptracePlacementRecord;
-use eda::placement::EdaPlacement;
-use eda::EdaTool;
-use eda::kicad::csv::KiCadPlacementRecord;
+use anyhow::{bail, Context, Error};
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use csv::{Reader, StringRecord, Trim};
+use thiserror::Error;
+use tracing::{info, trace};
+use eda::placement::{parse_decimal, DecimalParseError, DecimalSeparator, EdaPlacement};
+use eda::plugin::{EdaToolPlugin, EdaToolRegistry};
+use crate::xlsx;
 
+#[derive(Error, Debug)]
+pub enum EdaPlacementsError {
+    #[error("Invalid number in placements. line: {line}, column: '{column}', reason: {reason}")]
+    InvalidNumber { line: u64, column: String, reason: DecimalParseError },
+}
+
+/// The numeric columns, e.g. X, Y and rotation, are parsed using the decimal separator, see [`DecimalSeparator`].
+///
+/// Native, non-CSV, placements files of the EDA tool are also loaded, e.g. KiCad board files.
 #[tracing::instrument(level = Level::DEBUG)]
-pub fn load_eda_placements(eda_tool: EdaTool, placements_source: &String) -> Result<Vec<EdaPlacement>, Error> {
+pub fn load_eda_placements(eda_tool: &dyn EdaToolPlugin, placements_source: &String, decimal_separator: DecimalSeparator) -> Result<Vec<EdaPlacement>, Error> {
+    let placements_path_buf = PathBuf::from(placements_source);
+    let placements_path = placements_path_buf.as_path();
+
+    if let Some(content) = read_native_content(placements_path)? {
+        if eda_tool.is_native_content(&content) {
+            let placements = eda_tool.parse_native_content(&content)
+                .with_context(|| format!("Error reading {} placements file. file: {}", eda_tool.name(), placements_path.to_str().unwrap()))?;
+
+            return Ok(placements)
+        }
+    }
+
+    let mut csv_reader = build_csv_reader(placements_path)?;
+
+    let placements = deserialize_placements(&mut csv_reader, eda_tool, decimal_separator)?;
+
+    Ok(placements)
+}
+
+/// Detects the EDA tool, or fab-house dialect, of the placements file from its content or column names.
+pub fn detect_eda_tool<'registry>(registry: &'registry EdaToolRegistry, placements_source: &String) -> Result<&'registry dyn EdaToolPlugin, Error> {
     let placements_path_buf = PathBuf::from(placements_source);
     let placements_path = placements_path_buf.as_path();
-    let mut csv_reader = csv::ReaderBuilder::new().from_path(placements_path)
+
+    if let Some(content) = read_native_content(placements_path)? {
+        if let Some(eda_tool) = registry.detect_native_content(&content) {
+            info!("Detected placements format. format: {}, file: {}", eda_tool.name(), placements_path.to_str().unwrap());
+            return Ok(eda_tool)
+        }
+    }
+
+    let mut csv_reader = build_csv_reader(placements_path)?;
+
+    let headers = csv_reader.headers()
+        .with_context(|| format!("Error reading placements headers. file: {}", placements_path.to_str().unwrap()))?;
+    let headers: Vec<&str> = headers.iter().collect();
+
+    match registry.detect(&headers) {
+        Some(eda_tool) => {
+            info!("Detected placements format. format: {}, file: {}", eda_tool.name(), placements_path.to_str().unwrap());
+            Ok(eda_tool)
+        },
+        None => bail!("Unable to detect placements format, specify the EDA tool. file: {}, headers: {:?}", placements_path.to_str().unwrap(), headers),
+    }
+}
+
+/// The content of the file, `None` for '.xlsx' files, which are never native placements files.
+fn read_native_content(placements_path: &Path) -> Result<Option<String>, Error> {
+    if xlsx::is_xlsx_path(placements_path) {
+        return Ok(None)
+    }
+
+    let content = fs::read_to_string(placements_path)
         .with_context(|| format!("Error reading placements. file: {}", placements_path.to_str().unwrap()))?;
-    
+
+    Ok(Some(content))
+}
+
+fn deserialize_placements(csv_reader: &mut Reader<Cursor<String>>, eda_tool: &dyn EdaToolPlugin, decimal_separator: DecimalSeparator) -> Result<Vec<EdaPlacement>, Error> {
+    let headers = csv_reader.headers()
+        .with_context(|| "Reading placement headers".to_string())?
+        .clone();
+
+    let numeric_columns: Vec<(usize, &str)> = headers.iter()
+        .enumerate()
+        .filter(|(_index, header)| eda_tool.numeric_headers().iter().any(|numeric_header| header.eq_ignore_ascii_case(numeric_header)))
+        .collect();
 
     let mut placements: Vec<EdaPlacement> = vec![];
 
-    match eda_tool {
-        EdaTool::DipTrace => {
-            for result in csv_reader.deserialize() {
-                let record: DiptracePlacementRecord = result
-                    .with_context(|| "Deserializing placement record".to_string())?;
+    for result in csv_reader.records() {
+        let string_record = result
+            .with_context(|| "Reading placement record".to_string())?;
 
-                trace!("{:?}", record);
+        let string_record = normalize_numeric_fields(&string_record, &numeric_columns, decimal_separator)?;
 
-                let placement = record.build_eda_placement()
-                    .with_context(|| format!("Building placement from record. record: {:?}", record))?;
+        trace!("{:?}", string_record);
 
-                placements.push(placement);
-            }
-        },
-        EdaTool::KiCad => {
-            for result in csv_reader.deserialize() {
-                let record: KiCadPlacementRecord = result
-                    .with_context(|| "Deserializing placement record".to_string())?;
-
-                trace!("{:?}", record);
-
-                let placement = record.build_eda_placement()
-                    .with_context(|| format!("Building placement from record. record: {:?}", record))?;
-                
-                placements.push(placement);
-            }
-        }
+        let placement = eda_tool.build_eda_placement(&headers, &string_record)
+            .with_context(|| "Building placement from record".to_string())?;
+
+        placements.push(placement);
     }
+
     Ok(placements)
-}
\ No newline at end of file
+}
+
+/// Rewrites the numeric fields using a decimal point, so that records can be deserialized regardless of the locale of
+/// the tool that wrote the file.
+fn normalize_numeric_fields(string_record: &StringRecord, numeric_columns: &[(usize, &str)], decimal_separator: DecimalSeparator) -> Result<StringRecord, EdaPlacementsError> {
+    let line = string_record.position().map(|position| position.line()).unwrap_or_default();
+
+    let fields = string_record.iter()
+        .enumerate()
+        .map(|(index, field)| match numeric_columns.iter().find(|(column_index, _column)| *column_index == index) {
+            Some((_column_index, column)) => parse_decimal(field, decimal_separator)
+                .map(|value| value.to_string())
+                .map_err(|reason| EdaPlacementsError::InvalidNumber { line, column: column.to_string(), reason }),
+            None => Ok(field.to_string()),
+        })
+        .collect::<Result<Vec<String>, EdaPlacementsError>>()?;
+
+    Ok(StringRecord::from(fields))
+}
+
+/// Fab-house centroid files are often semicolon separated, the delimiter is detected using the header line.
+///
+/// The first sheet of '.xlsx' files is read instead, see [`xlsx::read_csv_content`].
+fn build_csv_reader(placements_path: &Path) -> Result<Reader<Cursor<String>>, Error> {
+    let content = xlsx::read_csv_content(placements_path)
+        .with_context(|| format!("Error reading placements. file: {}", placements_path.to_str().unwrap()))?;
+
+    let header_line = content.lines().next().unwrap_or_default();
+    let delimiter = detect_delimiter(header_line);
+
+    Ok(csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .trim(Trim::Headers)
+        .from_reader(Cursor::new(content)))
+}
+
+fn detect_delimiter(header_line: &str) -> u8 {
+    let semicolons = header_line.matches(';').count();
+    let commas = header_line.matches(',').count();
+
+    if semicolons > commas {
+        b';'
+    } else {
+        b','
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use assert_fs::TempDir;
+    use indoc::indoc;
+    use rust_decimal_macros::dec;
+    use rust_xlsxwriter::Workbook;
+    use eda::plugin::EdaToolRegistry;
+    use eda::placement::{DecimalParseError, DecimalSeparator};
+    use pnp::pcb::PcbSide;
+    use crate::eda_placements::{detect_delimiter, detect_eda_tool, load_eda_placements, EdaPlacementsError};
+
+    #[test]
+    pub fn load_detected_eurocircuits_placements() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("placements.csv");
+        fs::write(&path, indoc! {"
+            Designator;Value;Package;PosX;PosY;Rot;Side
+            R1;330R;0402;12,5;7,25;0;T
+            R2;1K;0402;22,5;7,25;90;T
+        "})?;
+        let source = path.to_str().unwrap().to_string();
+
+        // when
+        let registry = EdaToolRegistry::default();
+        let eda_tool = detect_eda_tool(&registry, &source)?;
+        let placements = load_eda_placements(eda_tool, &source, DecimalSeparator::Auto)?;
+
+        // then
+        assert_eq!(eda_tool.name(), "Eurocircuits");
+        assert_eq!(placements.len(), 2);
+        assert_eq!(placements[1].ref_des, "R2");
+        assert_eq!(placements[1].x, dec!(22.5));
+        assert_eq!(placements[1].pcb_side, PcbSide::Top);
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn load_detected_kicad_pos_placements() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("board-top.pos");
+        fs::write(&path, indoc! {"
+            ### Footprint positions - created on 2024-01-01T12:00:00 ###
+            ## Unit = mm, Angle = deg.
+            ## Side : top
+            # Ref     Val       Package                PosX       PosY       Rot  Side
+            R1        330R      R_0402_1005Metric    12.5000    7.2500     0.0000  top
+            ## End
+        "})?;
+        let source = path.to_str().unwrap().to_string();
+
+        // when
+        let registry = EdaToolRegistry::default();
+        let eda_tool = detect_eda_tool(&registry, &source)?;
+        let placements = load_eda_placements(eda_tool, &source, DecimalSeparator::Auto)?;
+
+        // then
+        assert_eq!(eda_tool.name(), "KiCad");
+        assert_eq!(placements.len(), 1);
+        assert_eq!(placements[0].ref_des, "R1");
+        assert_eq!(placements[0].x, dec!(12.5));
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn load_detected_kicad_pcb_placements() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("board.kicad_pcb");
+        fs::write(&path, indoc! {r#"
+            (kicad_pcb (version 20240108) (generator "pcbnew")
+              (footprint "Resistor_SMD:R_0402_1005Metric" (layer "F.Cu")
+                (at 12.5 -7.25)
+                (property "Reference" "R1")
+                (property "Value" "330R")
+                (attr smd)
+              )
+            )
+        "#})?;
+        let source = path.to_str().unwrap().to_string();
+
+        // when
+        let registry = EdaToolRegistry::default();
+        let eda_tool = detect_eda_tool(&registry, &source)?;
+        let placements = load_eda_placements(eda_tool, &source, DecimalSeparator::Auto)?;
+
+        // then
+        assert_eq!(eda_tool.name(), "KiCad");
+        assert_eq!(placements.len(), 1);
+        assert_eq!(placements[0].ref_des, "R1");
+        assert_eq!((placements[0].x, placements[0].y), (dec!(12.5), dec!(7.25)));
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn load_detected_xlsx_placements() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("placements.xlsx");
+
+        // and
+        let mut workbook = Workbook::new();
+        let sheet = workbook.add_worksheet();
+        sheet.write_row(0, 0, ["RefDes", "Name", "Value", "Side", "X", "Y", "Rotation"])?;
+        sheet.write_row(1, 0, ["R1", "RES_0402", "330R", "Top"])?;
+        sheet.write_row(1, 4, [12.5, 7.25, 90.0])?;
+        workbook.save(&path)?;
+        let source = path.to_str().unwrap().to_string();
+
+        // when
+        let registry = EdaToolRegistry::default();
+        let eda_tool = detect_eda_tool(&registry, &source)?;
+        let placements = load_eda_placements(eda_tool, &source, DecimalSeparator::Auto)?;
+
+        // then
+        assert_eq!(eda_tool.name(), "DipTrace");
+        assert_eq!(placements.len(), 1);
+        assert_eq!(placements[0].ref_des, "R1");
+        assert_eq!((placements[0].x, placements[0].y, placements[0].rotation), (dec!(12.5), dec!(7.25), dec!(90)));
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn load_kicad_placements_with_decimal_commas() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("placements.csv");
+        fs::write(&path, indoc! {r#"
+            "Ref";"Val";"Package";"PosX";"PosY";"Rot";"Side"
+            "R1";"330R";"R_0402_1005Metric";"12,5";"-7,25";"90,0";"top"
+        "#})?;
+        let source = path.to_str().unwrap().to_string();
+
+        // when
+        let placements = load_eda_placements(EdaToolRegistry::default().find("KiCad").unwrap(), &source, DecimalSeparator::Comma)?;
+
+        // then
+        assert_eq!((placements[0].x, placements[0].y, placements[0].rotation), (dec!(12.5), dec!(-7.25), dec!(90)));
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn invalid_number_reports_line_and_column() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("placements.csv");
+        fs::write(&path, indoc! {r#"
+            RefDes,Name,Value,Side,X,Y,Rotation
+            R1,RES_0402,330R,Top,12.5,7.25,0
+            R2,RES_0402,1K,Top,22.5,7.25,"90,0"
+        "#})?;
+        let source = path.to_str().unwrap().to_string();
+
+        // when
+        let result = load_eda_placements(EdaToolRegistry::default().find("DipTrace").unwrap(), &source, DecimalSeparator::Point);
+
+        // then
+        let error = result.unwrap_err().downcast::<EdaPlacementsError>()?;
+        assert_eq!(error.to_string(), "Invalid number in placements. line: 3, column: 'Rotation', reason: Unexpected decimal separator. expected: Point, value: '90,0'");
+        let EdaPlacementsError::InvalidNumber { reason, .. } = error;
+        assert_eq!(reason, DecimalParseError::UnexpectedSeparator { expected: DecimalSeparator::Point, value: "90,0".to_string() });
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn unknown_format() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("placements.csv");
+        fs::write(&path, "Part,X,Y\n")?;
+
+        // when
+        let registry = EdaToolRegistry::default();
+        let result = detect_eda_tool(&registry, &path.to_str().unwrap().to_string());
+
+        // then
+        assert!(result.is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn delimiter() {
+        assert_eq!(detect_delimiter(r#""RefDes","Name","Value""#), b',');
+        assert_eq!(detect_delimiter("Designator;Value;Package"), b';');
+        assert_eq!(detect_delimiter(""), b',');
+    }
+}
@@ -0,0 +1,167 @@
+use tracing::Level;
+use anyhow::{Context, Error};
+use std::path::PathBuf;
+use thiserror::Error;
+use tracing::{info, trace};
+use pnp::feeder::Feeder;
+
+/// A feeder library record, e.g.
+///
+/// ```csv
+/// "Reference","Type","TapeWidth","Packages","Capacity"
+/// "FEEDER_1","tape","8","0402;0603","5000"
+/// ```
+///
+/// The packages are separated by ';', an empty list of packages accepts any package.
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all(deserialize = "PascalCase"))]
+pub struct FeederRecord {
+    reference: String,
+    #[serde(rename(deserialize = "Type"))]
+    kind: String,
+    #[serde(default)]
+    tape_width: Option<u32>,
+    #[serde(default)]
+    packages: Option<String>,
+    #[serde(default)]
+    capacity: Option<u32>,
+}
+
+impl FeederRecord {
+    pub fn build_feeder(&self) -> Result<Feeder, FeederLibraryError> {
+        if self.reference.trim().is_empty() {
+            return Err(FeederLibraryError::MissingReference)
+        }
+
+        let packages = self.packages.as_deref().unwrap_or_default()
+            .split(';')
+            .map(str::trim)
+            .filter(|package| !package.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        Ok(Feeder {
+            reference: self.reference.trim().to_string(),
+            kind: self.kind.trim().to_string(),
+            tape_width: self.tape_width,
+            packages,
+            capacity: self.capacity,
+        })
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum FeederLibraryError {
+    #[error("A feeder reference is required")]
+    MissingReference,
+
+    #[error("Duplicate feeder. feeder: '{feeder_reference}'")]
+    DuplicateFeeder { feeder_reference: String },
+
+    #[error("Unknown feeder, the feeder is not in the feeder library. feeder: '{feeder_reference}'")]
+    UnknownFeeder { feeder_reference: String },
+}
+
+#[tracing::instrument(level = Level::DEBUG)]
+pub fn load_feeders(feeders_source: &String) -> Result<Vec<Feeder>, Error> {
+    let feeders_path_buf = PathBuf::from(feeders_source);
+    let feeders_path = feeders_path_buf.as_path();
+    let mut csv_reader = csv::ReaderBuilder::new()
+        .from_path(feeders_path)
+        .with_context(|| format!("Error reading feeder library. file: {}", feeders_path.to_str().unwrap()))?;
+
+    let mut feeders: Vec<Feeder> = vec![];
+
+    for result in csv_reader.deserialize() {
+        let record: FeederRecord = result
+            .with_context(|| "Deserializing feeder record".to_string())?;
+
+        trace!("{:?}", record);
+
+        let feeder = record.build_feeder()
+            .with_context(|| format!("Building feeder from record. record: {:?}", record))?;
+
+        if feeders.iter().any(|existing| existing.reference.eq(&feeder.reference)) {
+            return Err(FeederLibraryError::DuplicateFeeder { feeder_reference: feeder.reference }.into())
+        }
+
+        feeders.push(feeder);
+    }
+
+    info!("Loaded feeder library. file: {}, feeders: {}", feeders_path.to_str().unwrap(), feeders.len());
+
+    Ok(feeders)
+}
+
+pub fn find_feeder<'a>(feeders: &'a [Feeder], feeder_reference: &str) -> Result<&'a Feeder, FeederLibraryError> {
+    feeders.iter()
+        .find(|feeder| feeder.reference.eq(feeder_reference))
+        .ok_or_else(|| FeederLibraryError::UnknownFeeder { feeder_reference: feeder_reference.to_string() })
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::TempDir;
+    use indoc::indoc;
+    use pnp::feeder::Feeder;
+    use crate::feeders::{find_feeder, load_feeders, FeederLibraryError};
+
+    #[test]
+    pub fn load() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let feeders_path = temp_dir.path().join("feeders.csv");
+        std::fs::write(&feeders_path, indoc! {r#"
+            "Reference","Type","TapeWidth","Packages","Capacity"
+            "FEEDER_1","tape","8","0402; 0603","5000"
+            "TRAY_1","tray","","",""
+        "#})?;
+
+        // when
+        let feeders = load_feeders(&feeders_path.to_str().unwrap().to_string())?;
+
+        // then
+        assert_eq!(feeders, vec![
+            Feeder {
+                reference: "FEEDER_1".to_string(),
+                kind: "tape".to_string(),
+                tape_width: Some(8),
+                packages: vec!["0402".to_string(), "0603".to_string()],
+                capacity: Some(5000),
+            },
+            Feeder {
+                reference: "TRAY_1".to_string(),
+                kind: "tray".to_string(),
+                tape_width: None,
+                packages: vec![],
+                capacity: None,
+            },
+        ]);
+
+        // and
+        assert!(matches!(find_feeder(&feeders, "FEEDER_2"), Err(FeederLibraryError::UnknownFeeder { .. })));
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn duplicate_feeder() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let feeders_path = temp_dir.path().join("feeders.csv");
+        std::fs::write(&feeders_path, indoc! {r#"
+            "Reference","Type"
+            "FEEDER_1","tape"
+            "FEEDER_1","tray"
+        "#})?;
+
+        // when
+        let result = load_feeders(&feeders_path.to_str().unwrap().to_string());
+
+        // then
+        let error = result.unwrap_err().downcast::<FeederLibraryError>()?;
+        assert!(matches!(error, FeederLibraryError::DuplicateFeeder { .. }));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,222 @@
+use tracing::Level;
+use std::collections::BTreeMap;
+use std::path::Path;
+use anyhow::{Context, Error};
+use csv::QuoteStyle;
+use thiserror::Error;
+use tracing::{info, trace, warn};
+use planning::inventory::{Inventory, InventoryItem};
+use planning::mutation::MUTATION_TARGET;
+use pnp::part::Part;
+
+/// An inventory record, a part that is stored in more than one place has a record for each place, e.g.
+///
+/// ```csv
+/// "Manufacturer","Mpn","Quantity"
+/// "RES_MFR1","RES1","1000"
+/// "RES_MFR1","RES1","250"
+/// ```
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all(serialize = "PascalCase", deserialize = "PascalCase"))]
+pub struct InventoryRecord {
+    manufacturer: String,
+    mpn: String,
+    quantity: u32,
+}
+
+#[derive(Error, Debug)]
+pub enum InventoryError {
+    #[error("The total quantity of a part is too large. part: {part}")]
+    QuantityOverflow { part: Part },
+}
+
+#[tracing::instrument(level = Level::DEBUG)]
+pub fn load_inventory(inventory_path: &Path) -> Result<Inventory, Error> {
+    let mut csv_reader = csv::ReaderBuilder::new()
+        .from_path(inventory_path)
+        .with_context(|| format!("Error reading inventory. file: {:?}", inventory_path))?;
+
+    let mut inventory = Inventory::default();
+    let mut totals: BTreeMap<Part, u32> = BTreeMap::new();
+
+    for result in csv_reader.deserialize() {
+        let record: InventoryRecord = result
+            .with_context(|| "Deserializing inventory record".to_string())?;
+
+        trace!("{:?}", record);
+
+        let part = Part::new(record.manufacturer.trim().to_string(), record.mpn.trim().to_string());
+
+        // the records of a part are kept, but the total of the part must be representable
+        let total = totals.entry(part.clone()).or_default();
+        *total = total.checked_add(record.quantity)
+            .ok_or(InventoryError::QuantityOverflow { part: part.clone() })?;
+
+        inventory.items.push(InventoryItem { part, quantity: record.quantity });
+    }
+
+    info!("Loaded inventory. file: {:?}, parts: {}, items: {}", inventory_path, totals.len(), inventory.items.len());
+
+    Ok(inventory)
+}
+
+pub fn store_inventory(inventory_path: &Path, inventory: &Inventory) -> Result<(), Error> {
+    crate::backup::backup_before_modification(inventory_path)?;
+
+    let mut writer = csv::WriterBuilder::new()
+        .quote_style(QuoteStyle::Always)
+        .from_path(inventory_path)
+        .with_context(|| format!("Error writing inventory. file: {:?}", inventory_path))?;
+
+    for item in inventory.items.iter() {
+        writer.serialize(InventoryRecord { manufacturer: item.part.manufacturer.clone(), mpn: item.part.mpn.clone(), quantity: item.quantity })?;
+    }
+
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Decrements the on-hand quantities of the consumed parts, quantities do not go below zero.
+pub fn consume_inventory(inventory_path: &Path, consumed: &BTreeMap<Part, u32>) -> Result<(), Error> {
+    let mut inventory = load_inventory(inventory_path)?;
+
+    consume(&mut inventory, consumed);
+
+    store_inventory(inventory_path, &inventory)
+}
+
+/// Decrements the on-hand quantities of the consumed parts, see `consume_inventory`.
+///
+/// The items of a part are decremented in inventory order, so that the first place a part is stored in is used up
+/// before the next.
+pub fn consume(inventory: &mut Inventory, consumed: &BTreeMap<Part, u32>) {
+    for (part, consumed_quantity) in consumed.iter() {
+        let on_hand = inventory.on_hand(part);
+        if on_hand < *consumed_quantity {
+            warn!("Consumed more parts than are in the inventory. part: {:?}, on_hand: {}, consumed: {}", part, on_hand, consumed_quantity);
+        }
+
+        let mut remaining_consumed_quantity = *consumed_quantity;
+        for item in inventory.items.iter_mut().filter(|item| item.part.eq(part)) {
+            let item_consumed_quantity = item.quantity.min(remaining_consumed_quantity);
+            item.quantity -= item_consumed_quantity;
+            remaining_consumed_quantity -= item_consumed_quantity;
+        }
+
+        info!(target: MUTATION_TARGET, part = ?part, consumed = consumed_quantity, remaining = inventory.on_hand(part), message = "Consumed inventory quantity. part: {part}, consumed: {consumed}, remaining: {remaining}");
+    }
+}
+
+/// Increments the on-hand quantities of the returned parts, e.g. when placements are unplaced, parts that are not in the
+/// inventory are not tracked.
+///
+/// Since the items of a part are used up in inventory order, the parts are returned to the first item of the part that
+/// is not used up, the item they were consumed from, or to the last item if they are all used up.
+pub fn return_parts(inventory: &mut Inventory, returned: &BTreeMap<Part, u32>) {
+    for (part, returned_quantity) in returned.iter() {
+        let Some(index) = inventory.items.iter().position(|item| item.part.eq(part) && item.quantity > 0)
+            .or_else(|| inventory.items.iter().rposition(|item| item.part.eq(part)))
+        else {
+            continue
+        };
+        let item = &mut inventory.items[index];
+        item.quantity = item.quantity.saturating_add(*returned_quantity);
+
+        info!(target: MUTATION_TARGET, part = ?part, returned = returned_quantity, remaining = inventory.on_hand(part), message = "Returned inventory quantity. part: {part}, returned: {returned}, remaining: {remaining}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use assert_fs::TempDir;
+    use indoc::indoc;
+    use planning::inventory::{Inventory, InventoryItem};
+    use pnp::part::Part;
+    use crate::inventory::{consume, consume_inventory, load_inventory, return_parts, InventoryError};
+
+    #[test]
+    pub fn load_and_consume() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let inventory_path = temp_dir.path().join("inventory.csv");
+        std::fs::write(&inventory_path, indoc! {r#"
+            "Manufacturer","Mpn","Quantity"
+            "RES_MFR1","RES1","10"
+            "RES_MFR1","RES1","5"
+            "CAP_MFR1","CAP1","1"
+        "#})?;
+
+        let res1 = Part::new("RES_MFR1".to_string(), "RES1".to_string());
+        let cap1 = Part::new("CAP_MFR1".to_string(), "CAP1".to_string());
+
+        // expect
+        assert_eq!(load_inventory(&inventory_path)?.on_hand(&res1), 15);
+
+        // when
+        consume_inventory(&inventory_path, &BTreeMap::from([(res1.clone(), 2), (cap1.clone(), 3)]))?;
+
+        // then the records of a part are decremented in order
+        let content = std::fs::read_to_string(&inventory_path)?;
+        assert_eq!(content, indoc! {r#"
+            "Manufacturer","Mpn","Quantity"
+            "RES_MFR1","RES1","8"
+            "RES_MFR1","RES1","5"
+            "CAP_MFR1","CAP1","0"
+        "#});
+
+        // when more than the first record is consumed
+        consume_inventory(&inventory_path, &BTreeMap::from([(res1.clone(), 10)]))?;
+
+        // then
+        let content = std::fs::read_to_string(&inventory_path)?;
+        assert_eq!(content, indoc! {r#"
+            "Manufacturer","Mpn","Quantity"
+            "RES_MFR1","RES1","0"
+            "RES_MFR1","RES1","3"
+            "CAP_MFR1","CAP1","0"
+        "#});
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn consume_and_return() {
+        // given
+        let res1 = Part::new("RES_MFR1".to_string(), "RES1".to_string());
+        let mut inventory = Inventory { items: vec![
+            InventoryItem { part: res1.clone(), quantity: 1 },
+            InventoryItem { part: res1.clone(), quantity: 5 },
+        ] };
+
+        // and
+        consume(&mut inventory, &BTreeMap::from([(res1.clone(), 2)]));
+
+        // when
+        return_parts(&mut inventory, &BTreeMap::from([(res1, 1)]));
+
+        // then the parts are returned to the item they were last consumed from
+        assert_eq!(inventory.items.iter().map(|item| item.quantity).collect::<Vec<_>>(), vec![0, 5]);
+    }
+
+    #[test]
+    pub fn load_with_quantity_overflow() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let inventory_path = temp_dir.path().join("inventory.csv");
+        std::fs::write(&inventory_path, indoc! {r#"
+            "Manufacturer","Mpn","Quantity"
+            "RES_MFR1","RES1","4294967295"
+            "RES_MFR1","RES1","1"
+        "#})?;
+
+        // when
+        let result = load_inventory(&inventory_path);
+
+        // then
+        assert!(matches!(result.unwrap_err().downcast::<InventoryError>()?, InventoryError::QuantityOverflow { part } if part.eq(&Part::new("RES_MFR1".to_string(), "RES1".to_string()))));
+
+        Ok(())
+    }
+}
@@ -10,11 +10,23 @@
 pub mod parts;
 pub mod eda_placements;
 pub mod placements;
+pub mod placement_classification;
 pub mod part_mappings;
 
 pub mod substitutions;
 pub mod load_out;
+pub mod feeders;
+pub mod pricing;
+pub mod inventory;
+pub mod rotation_offsets;
+pub mod process_definitions;
+pub mod barcodes;
 pub mod assembly_rules;
+pub mod part_rename;
+pub mod preferences;
+pub mod backup;
+pub mod unit_assignments;
 pub mod csv;
+pub mod xlsx;
 
 pub mod test;
\ No newline at end of file
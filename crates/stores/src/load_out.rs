@@ -1,31 +1,53 @@
-use std::collections::BTreeSet;
-use tracing::{info, Level};
-use std::path::PathBuf;
+use std::collections::{BTreeMap, BTreeSet};
+use tracing::{info, warn, Level};
+use std::path::{Path, PathBuf};
 use anyhow::{Context, Error};
+#[cfg(not(feature = "http"))]
+use anyhow::bail;
 use csv::QuoteStyle;
 use tracing::trace;
 use std::fs::File;
+use std::io::{Read, Write};
 use std::str::FromStr;
 use std::fmt::{Display, Formatter};
 use pnp::load_out::LoadOutItem;
 use pnp::part::Part;
+use pnp::feeder::{Feeder, FeederConstraintError};
 use regex::Regex;
-use planning::phase::Phase;
+use planning::project::Project;
 use planning::process::{Process, ProcessName, ProcessOperationKind};
 use planning::reference::Reference;
+use planning::mutation::MUTATION_TARGET;
 use thiserror::Error;
 use crate::csv::LoadOutItemRecord;
+use util::sorting::natural_cmp;
 
 #[tracing::instrument(level = Level::DEBUG)]
 pub fn load_items(load_out_source: &LoadOutSource) -> Result<Vec<LoadOutItem>, Error>  {
     info!("Loading load-out. source: '{}'", load_out_source);
-    
-    let load_out_path_buf = PathBuf::from(load_out_source.to_string());
-    let load_out_path = load_out_path_buf.as_path();
+
+    load_out_source.repository()?.load_items()
+}
+
+pub fn store_items(load_out_source: &LoadOutSource, items: &[LoadOutItem]) -> Result<(), Error> {
+    info!("Storing load-out. source: '{}'", load_out_source);
+
+    load_out_source.repository()?.store_items(items)
+}
+
+pub fn ensure_load_out(load_out_source: &LoadOutSource) -> anyhow::Result<()> {
+    let created = load_out_source.repository()?.ensure()?;
+    if created {
+        info!(target: MUTATION_TARGET, source = %load_out_source, message = "Created load-out. source: '{source}'");
+    }
+
+    Ok(())
+}
+
+fn read_items<R: Read>(reader: R) -> Result<Vec<LoadOutItem>, Error> {
     let mut csv_reader = csv::ReaderBuilder::new()
-        .from_path(load_out_path)
-        .with_context(|| format!("Error reading load-out. file: {}", load_out_path.to_str().unwrap()))?;
-   
+        .from_reader(reader);
+
     let mut items: Vec<LoadOutItem> = vec![];
 
     for result in csv_reader.deserialize() {
@@ -42,23 +64,16 @@ pub fn load_items(load_out_source: &LoadOutSource) -> Result<Vec<LoadOutItem>, E
     Ok(items)
 }
 
-pub fn store_items(load_out_source: &LoadOutSource, items: &[LoadOutItem]) -> Result<(), Error> {
-    info!("Storing load-out. source: '{}'", load_out_source);
-
-    let output_path = PathBuf::from(load_out_source.to_string());
-
+fn write_items<W: Write>(writer: W, items: &[LoadOutItem]) -> Result<(), Error> {
     let mut writer = csv::WriterBuilder::new()
         .quote_style(QuoteStyle::Always)
-        .from_path(output_path)?;
-
-    for item in items {
-        writer.serialize(
-            LoadOutItemRecord {
-                reference: item.reference.to_string(),
-                manufacturer: item.manufacturer.to_string(),
-                mpn: item.mpn.to_string(),
-            }
-        )?;
+        .from_writer(writer);
+
+    let mut sorted_items: Vec<&LoadOutItem> = items.iter().collect();
+    sorted_items.sort_by(|a, b| pnp::load_out::load_out_item_cmp(a, b));
+
+    for item in sorted_items {
+        writer.serialize(LoadOutItemRecord::from_load_out_item(item))?;
     }
     
     writer.flush()?;
@@ -66,37 +81,184 @@ pub fn store_items(load_out_source: &LoadOutSource, items: &[LoadOutItem]) -> Re
     Ok(())
 }
 
-pub fn ensure_load_out(load_out_source: &LoadOutSource) -> anyhow::Result<()> {
-    let load_out_path_buf = PathBuf::from(load_out_source.to_string());
-    let load_out_path = load_out_path_buf.as_path();
-    if !load_out_path.exists() {
-        File::create(&load_out_path)?;    
-        info!("Created load-out. source: '{}'", load_out_source);
+/// A backend that a load-out is stored in, see `LoadOutSource::repository`.
+pub trait LoadOutRepository {
+    fn load_items(&self) -> Result<Vec<LoadOutItem>, Error>;
+
+    fn store_items(&self, items: &[LoadOutItem]) -> Result<(), Error>;
+
+    /// Creates an empty load-out if the load-out does not exist, returns true if created.
+    fn ensure(&self) -> Result<bool, Error>;
+}
+
+/// The default backend, a CSV file, which is backed up before each modification, see `backup`.
+pub struct FileLoadOutRepository {
+    path: PathBuf,
+}
+
+impl LoadOutRepository for FileLoadOutRepository {
+    fn load_items(&self) -> Result<Vec<LoadOutItem>, Error> {
+        let file = File::open(&self.path)
+            .with_context(|| format!("Error reading load-out. file: {}", self.path.to_str().unwrap()))?;
+
+        read_items(file)
+    }
+
+    fn store_items(&self, items: &[LoadOutItem]) -> Result<(), Error> {
+        crate::backup::backup_before_modification(&self.path)?;
+
+        write_items(File::create(&self.path)?, items)
+    }
+
+    fn ensure(&self) -> Result<bool, Error> {
+        if self.path.exists() {
+            return Ok(false)
+        }
+
+        File::create(&self.path)?;
+
+        Ok(true)
     }
-    
-    Ok(())
 }
 
-#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
-pub struct LoadOutSource(String);
+/// A CSV file on a web server, e.g. a load-out shared by several machines or sites.
+///
+/// The load-out is fetched using `GET` and updated using `PUT`, the server is responsible for any backups.
+#[cfg(feature = "http")]
+pub struct HttpLoadOutRepository {
+    url: String,
+}
+
+#[cfg(feature = "http")]
+impl LoadOutRepository for HttpLoadOutRepository {
+    fn load_items(&self) -> Result<Vec<LoadOutItem>, Error> {
+        let response = ureq::get(&self.url).call()
+            .with_context(|| format!("Error fetching load-out. url: {}", self.url))?;
+
+        read_items(response.into_reader())
+    }
+
+    fn store_items(&self, items: &[LoadOutItem]) -> Result<(), Error> {
+        let mut content: Vec<u8> = vec![];
+        write_items(&mut content, items)?;
+
+        ureq::put(&self.url)
+            .set("Content-Type", "text/csv")
+            .send_bytes(&content)
+            .with_context(|| format!("Error updating load-out. url: {}", self.url))?;
+
+        Ok(())
+    }
+
+    fn ensure(&self) -> Result<bool, Error> {
+        match ureq::head(&self.url).call() {
+            Ok(_) => Ok(false),
+            Err(ureq::Error::Status(404, _)) => {
+                ureq::put(&self.url)
+                    .set("Content-Type", "text/csv")
+                    .send_bytes(&[])
+                    .with_context(|| format!("Error creating load-out. url: {}", self.url))?;
+
+                Ok(true)
+            },
+            Err(error) => Err(Error::new(error).context(format!("Error fetching load-out. url: {}", self.url))),
+        }
+    }
+}
+
+/// Where a load-out is stored, e.g. 'load_out_1.csv', 'file:///load_outs/load_out_1.csv' or
+/// 'https://example.com/load_outs/load_out_1.csv'.
+///
+/// Sources without a scheme are file paths, a 'file://' source is the same as the path without the scheme.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LoadOutSource {
+    File(String),
+    /// An 'http://' or 'https://' URL.
+    Url(String),
+}
 
 impl FromStr for LoadOutSource {
     type Err = LoadOutSourceError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(LoadOutSource(s.to_string()))
+        if let Some(path) = s.strip_prefix("file://") {
+            return Ok(LoadOutSource::File(path.to_string()))
+        }
+
+        match s.split_once("://") {
+            Some(("http" | "https", _)) => Ok(LoadOutSource::Url(s.to_string())),
+            Some((scheme, _)) => Err(LoadOutSourceError::UnsupportedScheme(scheme.to_string())),
+            None => Ok(LoadOutSource::File(s.to_string())),
+        }
+    }
+}
+
+/// Token that is replaced with the project directory when resolving a load-out source, e.g. '${PROJECT_DIR}/load_out_1.csv'
+pub const PROJECT_DIR_TOKEN: &str = "${PROJECT_DIR}";
+
+impl LoadOutSource {
+    /// Resolves a project-relative source, or a source starting with the `${PROJECT_DIR}` token, using the project directory.
+    ///
+    /// Absolute sources and URLs are unchanged.
+    pub fn resolve(&self, project_dir: &Path) -> LoadOutSource {
+        let LoadOutSource::File(source) = self else {
+            return self.clone()
+        };
+
+        let path = match source.strip_prefix(PROJECT_DIR_TOKEN) {
+            Some(remainder) => project_dir.join(remainder.trim_start_matches(['/', '\\'])),
+            None => project_dir.join(source),
+        };
+
+        LoadOutSource::File(path.to_string_lossy().to_string())
+    }
+
+    /// Returns a project-relative source if the source is an absolute path within the project directory.
+    pub fn to_project_relative(&self, project_dir: &Path) -> Option<LoadOutSource> {
+        let path = self.path()?;
+        if !path.is_absolute() {
+            return None
+        }
+
+        let project_dir = std::path::absolute(project_dir).ok()?;
+
+        path.strip_prefix(&project_dir).ok()
+            .map(|relative_path| LoadOutSource::File(relative_path.to_string_lossy().to_string()))
+    }
+
+    /// `None` if the load-out is not a file, e.g. for the backups of a load-out.
+    pub fn path(&self) -> Option<PathBuf> {
+        match self {
+            LoadOutSource::File(path) => Some(PathBuf::from(path)),
+            LoadOutSource::Url(_) => None,
+        }
+    }
+
+    pub fn repository(&self) -> Result<Box<dyn LoadOutRepository>, Error> {
+        match self {
+            LoadOutSource::File(path) => Ok(Box::new(FileLoadOutRepository { path: PathBuf::from(path) })),
+            #[cfg(feature = "http")]
+            LoadOutSource::Url(url) => Ok(Box::new(HttpLoadOutRepository { url: url.clone() })),
+            #[cfg(not(feature = "http"))]
+            LoadOutSource::Url(url) => bail!("Load-out URLs require the 'http' feature. source: '{}'", url),
+        }
     }
 }
 
 impl Display for LoadOutSource {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_str(self.0.as_str())
+        match self {
+            LoadOutSource::File(path) => f.write_str(path),
+            LoadOutSource::Url(url) => f.write_str(url),
+        }
     }
 }
 
 #[derive(Debug, Error)]
-#[error("Design name error")]
-pub struct LoadOutSourceError;
+pub enum LoadOutSourceError {
+    #[error("Unsupported load-out source scheme, use a file path, 'file://', 'http://' or 'https://'. scheme: '{0}'")]
+    UnsupportedScheme(String),
+}
 
 #[derive(Error, Debug)]
 pub enum LoadOutOperationError<E> {
@@ -142,13 +304,9 @@ pub fn add_parts_to_load_out(load_out_source: &LoadOutSource, parts: BTreeSet<Pa
                 continue
             }
 
-            let load_out_item = LoadOutItem {
-                reference: "".to_string(),
-                manufacturer: part.manufacturer.clone(),
-                mpn: part.mpn.clone(),
-            };
+            let load_out_item = LoadOutItem::new("".to_string(), part.manufacturer.clone(), part.mpn.clone());
 
-            info!("Adding part to load_out. part: {:?}", part);
+            info!(target: MUTATION_TARGET, part = ?part, message = "Adding part to load_out. part: {part}");
             load_out_items.push(load_out_item)
         }
 
@@ -157,6 +315,34 @@ pub fn add_parts_to_load_out(load_out_source: &LoadOutSource, parts: BTreeSet<Pa
 }
 
 
+/// Removes the items of the parts that have not been assigned to a feeder, returns the parts that were removed.
+///
+/// Items that have been assigned to a feeder are kept, since the part may still be loaded in the feeder.
+pub fn remove_parts_from_load_out(load_out_source: &LoadOutSource, parts: &BTreeSet<Part>) -> Result<Vec<Part>, LoadOutOperationError<anyhow::Error>> {
+
+    perform_load_out_operation(load_out_source, | load_out_items| {
+        let mut removed = vec![];
+
+        load_out_items.retain(|load_out_item| {
+            let part = Part::new(load_out_item.manufacturer.clone(), load_out_item.mpn.clone());
+            if !parts.contains(&part) {
+                return true
+            }
+
+            if !load_out_item.reference.is_empty() {
+                info!("Keeping part in load_out, it is assigned to a feeder. part: {:?}, feeder: '{}'", part, load_out_item.reference);
+                return true
+            }
+
+            info!(target: MUTATION_TARGET, part = ?part, message = "Removing part from load_out. part: {part}");
+            removed.push(part);
+            false
+        });
+
+        Ok(removed)
+    })
+}
+
 #[derive(Error, Debug)]
 pub enum FeederAssignmentError {
     #[error("No matching part; patterns must match exactly one part. manufacturer: {manufacturer}, mpn: {mpn}")]
@@ -164,13 +350,19 @@ pub enum FeederAssignmentError {
 
     #[error("Multiple matching parts; patterns must match exactly one part for the process. process: {process}, manufacturer: {manufacturer}, mpn: {mpn}")]
     MultipleMatchingParts { process: ProcessName, manufacturer: Regex, mpn: Regex },
+
+    #[error("Incompatible feeder. part: {part}, reason: {reason}")]
+    IncompatibleFeeder { part: Part, reason: FeederConstraintError },
 }
 
-pub fn assign_feeder_to_load_out_item(phase: &Phase, process: &Process, feeder_reference: &Reference, manufacturer: Regex, mpn: Regex) -> anyhow::Result<Vec<Part>> {
+/// When a feeder, from the feeder library, is given, the package of each part, and the quantity, are checked against
+/// the constraints of the feeder before any items are changed.
+#[allow(clippy::too_many_arguments)]
+pub fn assign_feeder_to_load_out_item(load_out_source: &LoadOutSource, process: &Process, feeder_reference: &Reference, manufacturer: Regex, mpn: Regex, quantity: Option<u32>, reel: Option<String>, feeder: Option<&Feeder>, part_packages: &BTreeMap<Part, String>) -> anyhow::Result<Vec<Part>> {
 
     let mut parts: Vec<Part> = vec![];
 
-    perform_load_out_operation(&LoadOutSource(phase.load_out_source.clone()), |load_out_items| {
+    perform_load_out_operation(load_out_source, |load_out_items| {
         let mut items: Vec<_> = load_out_items.iter_mut().filter(|item| {
             manufacturer.is_match(&item.manufacturer)
                 && mpn.is_match(&item.mpn)
@@ -181,13 +373,29 @@ pub fn assign_feeder_to_load_out_item(phase: &Phase, process: &Process, feeder_r
         }
 
         if process.has_operation(&ProcessOperationKind::AutomatedPnp) && items.len() > 1 {
-            return Err(FeederAssignmentError::MultipleMatchingParts { process: phase.process.clone(), manufacturer: manufacturer.clone(), mpn: mpn.clone() })
+            return Err(FeederAssignmentError::MultipleMatchingParts { process: process.name.clone(), manufacturer: manufacturer.clone(), mpn: mpn.clone() })
+        }
+
+        if let Some(feeder) = feeder {
+            for item in items.iter() {
+                let part = Part::new(item.manufacturer.clone(), item.mpn.clone());
+                let package = part_packages.get(&part).map(String::as_str);
+
+                feeder.check(package, quantity.or(item.quantity))
+                    .map_err(|reason| FeederAssignmentError::IncompatibleFeeder { part, reason })?;
+            }
         }
 
         for item in items.iter_mut() {
             let part = Part { manufacturer: item.manufacturer.clone(), mpn: item.mpn.clone() };
 
             item.reference = feeder_reference.to_string();
+            if quantity.is_some() {
+                item.quantity = quantity;
+            }
+            if reel.is_some() {
+                item.reel.clone_from(&reel);
+            }
 
             parts.push(part);
         }
@@ -196,8 +404,897 @@ pub fn assign_feeder_to_load_out_item(phase: &Phase, process: &Process, feeder_r
     })?;
 
     for part in parts.iter() {
-        info!("Assigned feeder to load-out item. feeder: {}, part: {:?}", feeder_reference, part);
+        info!(target: MUTATION_TARGET, feeder = %feeder_reference, part = ?part, message = "Assigned feeder to load-out item. feeder: {feeder}, part: {part}");
     }
 
     Ok(parts)
 }
+
+#[derive(Error, Debug)]
+pub enum LoadOutAlternateError {
+    #[error("Unknown feeder. feeder: '{feeder_reference}'")]
+    UnknownFeeder { feeder_reference: String },
+
+    #[error("Alternate not in the alternates of the load-out item. feeder: '{feeder_reference}', alternate: '{alternate}'")]
+    UnknownAlternate { feeder_reference: String, alternate: Part },
+}
+
+/// Sets the alternates, in order of preference, of the load-out item for the feeder, returns the part of the item.
+///
+/// The loaded alternate is cleared if it is no-longer an alternate.
+pub fn set_load_out_item_alternates(load_out_source: &LoadOutSource, feeder_reference: &str, alternates: Vec<Part>) -> Result<Part, LoadOutOperationError<LoadOutAlternateError>> {
+    perform_load_out_operation(load_out_source, |load_out_items| {
+        let item = find_load_out_item_by_feeder(load_out_items, feeder_reference)?;
+
+        let part = Part::new(item.manufacturer.clone(), item.mpn.clone());
+
+        if let Some(loaded_alternate) = item.loaded_alternate.take_if(|loaded_alternate| !alternates.contains(loaded_alternate)) {
+            info!(target: MUTATION_TARGET, feeder = %feeder_reference, part = ?part, alternate = ?loaded_alternate, message = "Cleared loaded alternate. feeder: '{feeder}', part: {part}, alternate: {alternate}");
+        }
+
+        info!(target: MUTATION_TARGET, feeder = %feeder_reference, part = ?part, alternates = ?alternates, message = "Set load-out item alternates. feeder: '{feeder}', part: {part}, alternates: {alternates}");
+        item.alternates.clone_from(&alternates);
+
+        Ok(part)
+    })
+}
+
+/// Records which part is loaded in the feeder, an alternate or, when `alternate` is `None`, the part itself.
+///
+/// Returns the loaded part.
+pub fn set_loaded_alternate(load_out_source: &LoadOutSource, feeder_reference: &str, alternate: Option<Part>) -> Result<Part, LoadOutOperationError<LoadOutAlternateError>> {
+    perform_load_out_operation(load_out_source, |load_out_items| {
+        let item = find_load_out_item_by_feeder(load_out_items, feeder_reference)?;
+
+        if let Some(alternate) = &alternate {
+            if !item.alternates.contains(alternate) {
+                return Err(LoadOutAlternateError::UnknownAlternate { feeder_reference: feeder_reference.to_string(), alternate: alternate.clone() })
+            }
+        }
+
+        item.loaded_alternate.clone_from(&alternate);
+
+        Ok(item.loaded_part())
+    })
+}
+
+fn find_load_out_item_by_feeder<'items>(load_out_items: &'items mut [LoadOutItem], feeder_reference: &str) -> Result<&'items mut LoadOutItem, LoadOutAlternateError> {
+    load_out_items.iter_mut()
+        .find(|item| item.reference.eq(feeder_reference))
+        .ok_or(LoadOutAlternateError::UnknownFeeder { feeder_reference: feeder_reference.to_string() })
+}
+
+#[derive(Error, Debug)]
+pub enum LoadOutItemEditError {
+    #[error("Unknown feeder. feeder: '{feeder_reference}'")]
+    UnknownFeeder { feeder_reference: String },
+
+    #[error("Feeder already in use. feeder: '{feeder_reference}', part: '{part}'")]
+    FeederInUse { feeder_reference: String, part: Part },
+}
+
+/// Removes the load-out item for the feeder, returns the removed item.
+///
+/// Unlike `remove_parts_from_load_out`, the item is removed even though it is assigned to a feeder.
+pub fn remove_load_out_item(load_out_source: &LoadOutSource, feeder_reference: &str) -> Result<LoadOutItem, LoadOutOperationError<LoadOutItemEditError>> {
+    perform_load_out_operation(load_out_source, |load_out_items| {
+        let index = find_load_out_item_index_by_feeder(load_out_items, feeder_reference)?;
+        let item = load_out_items.remove(index);
+
+        info!(target: MUTATION_TARGET, feeder = %feeder_reference, part = ?Part::new(item.manufacturer.clone(), item.mpn.clone()), message = "Removed load-out item. feeder: '{feeder}', part: {part}");
+
+        Ok(item)
+    })
+}
+
+/// Sets the quantity of parts loaded in the feeder, `None` stops tracking the quantity, returns the part of the item.
+pub fn set_load_out_item_quantity(load_out_source: &LoadOutSource, feeder_reference: &str, quantity: Option<u32>) -> Result<Part, LoadOutOperationError<LoadOutItemEditError>> {
+    perform_load_out_operation(load_out_source, |load_out_items| {
+        let index = find_load_out_item_index_by_feeder(load_out_items, feeder_reference)?;
+        let item = &mut load_out_items[index];
+
+        let part = Part::new(item.manufacturer.clone(), item.mpn.clone());
+
+        info!(target: MUTATION_TARGET, feeder = %feeder_reference, part = ?part, old = ?item.quantity, new = ?quantity, message = "Set load-out item quantity. feeder: '{feeder}', part: {part}, old: {old}, new: {new}");
+        item.quantity = quantity;
+
+        Ok(part)
+    })
+}
+
+/// Moves the load-out item to another feeder, e.g. when a feeder is replaced, returns the part of the item.
+///
+/// The new feeder must not already be in use by another item of the load-out.
+pub fn rename_feeder(load_out_source: &LoadOutSource, feeder_reference: &str, new_feeder_reference: &str) -> Result<Part, LoadOutOperationError<LoadOutItemEditError>> {
+    perform_load_out_operation(load_out_source, |load_out_items| {
+        if let Some(other_item) = load_out_items.iter().find(|item| item.reference.eq(new_feeder_reference)) {
+            return Err(LoadOutItemEditError::FeederInUse {
+                feeder_reference: new_feeder_reference.to_string(),
+                part: Part::new(other_item.manufacturer.clone(), other_item.mpn.clone()),
+            })
+        }
+
+        let index = find_load_out_item_index_by_feeder(load_out_items, feeder_reference)?;
+        let item = &mut load_out_items[index];
+
+        let part = Part::new(item.manufacturer.clone(), item.mpn.clone());
+
+        info!(target: MUTATION_TARGET, feeder = %feeder_reference, new_feeder = %new_feeder_reference, part = ?part, message = "Renamed feeder. feeder: '{feeder}', new_feeder: '{new_feeder}', part: {part}");
+        item.reference = new_feeder_reference.to_string();
+
+        Ok(part)
+    })
+}
+
+fn find_load_out_item_index_by_feeder(load_out_items: &[LoadOutItem], feeder_reference: &str) -> Result<usize, LoadOutItemEditError> {
+    load_out_items.iter()
+        .position(|item| !feeder_reference.is_empty() && item.reference.eq(feeder_reference))
+        .ok_or(LoadOutItemEditError::UnknownFeeder { feeder_reference: feeder_reference.to_string() })
+}
+
+/// Consumes parts from the load-out items, items without a quantity are not tracked.
+///
+/// `consumed` is the quantity of each part that has been used, `required` is the quantity of each part that is still
+/// required to complete the phase.
+///
+/// Returns the shortfall, see `consume_items`.
+pub fn consume_load_out_items(load_out_source: &LoadOutSource, consumed: &BTreeMap<Part, u32>, required: &BTreeMap<Part, u32>) -> Result<BTreeMap<Part, u32>, LoadOutOperationError<anyhow::Error>> {
+
+    perform_load_out_operation(load_out_source, | load_out_items| {
+        Ok(consume_items(load_out_items, consumed, required))
+    })
+}
+
+/// Consumes parts from the load-out items, see `consume_load_out_items`.
+///
+/// A part that is loaded into more than one feeder is consumed from the feeders in feeder order, each feeder is
+/// emptied before the next one is used.
+///
+/// Returns the shortfall, the quantity of each tracked part that was consumed but exceeded the loaded quantity, e.g.
+/// when a reel was replaced without updating the quantity, the feeders are emptied and a warning is logged.
+pub fn consume_items(load_out_items: &mut [LoadOutItem], consumed: &BTreeMap<Part, u32>, required: &BTreeMap<Part, u32>) -> BTreeMap<Part, u32> {
+    let mut feeder_indexes: Vec<usize> = (0..load_out_items.len()).collect();
+    feeder_indexes.sort_by(|index, other_index| natural_cmp(&load_out_items[*index].reference, &load_out_items[*other_index].reference));
+
+    let mut unconsumed = consumed.clone();
+    let mut remaining = BTreeMap::<Part, u32>::new();
+
+    for index in feeder_indexes {
+        let item = &mut load_out_items[index];
+        let part = Part { manufacturer: item.manufacturer.clone(), mpn: item.mpn.clone() };
+
+        let Some(quantity) = item.quantity else {
+            continue
+        };
+
+        let mut remaining_quantity = quantity;
+        if let Some(unconsumed_quantity) = unconsumed.get_mut(&part).filter(|unconsumed_quantity| **unconsumed_quantity > 0) {
+            let consumed_quantity = quantity.min(*unconsumed_quantity);
+            *unconsumed_quantity -= consumed_quantity;
+            remaining_quantity -= consumed_quantity;
+            item.quantity = Some(remaining_quantity);
+            info!(target: MUTATION_TARGET, feeder = %item.reference, part = ?part, consumed = consumed_quantity, remaining = remaining_quantity, message = "Consumed load-out item quantity. feeder: '{feeder}', part: {part}, consumed: {consumed}, remaining: {remaining}");
+        }
+
+        *remaining.entry(part).or_default() += remaining_quantity;
+    }
+
+    for (part, remaining_quantity) in remaining.iter() {
+        let required_quantity = required.get(part).copied().unwrap_or(0);
+
+        if *remaining_quantity < required_quantity {
+            warn!("Insufficient load-out item quantity. part: {:?}, remaining: {}, required: {}", part, remaining_quantity, required_quantity);
+        }
+    }
+
+    // parts without a tracked quantity are not consumed, so they are not short
+    let shortfall: BTreeMap<Part, u32> = unconsumed.into_iter()
+        .filter(|(part, unconsumed_quantity)| *unconsumed_quantity > 0 && remaining.contains_key(part))
+        .collect();
+
+    for (part, shortfall_quantity) in shortfall.iter() {
+        warn!("Consumed more than the loaded load-out item quantity. part: {:?}, shortfall: {}", part, shortfall_quantity);
+    }
+
+    shortfall
+}
+
+/// Returns parts to the load-out items, e.g. when placements are unplaced, so that placing them again does not consume
+/// them twice, items without a quantity are not tracked.
+///
+/// Since feeders are emptied in feeder order, the parts are returned to the first feeder of the part that is not empty,
+/// the feeder they were consumed from, or to the last feeder if they are all empty.
+pub fn return_items(load_out_items: &mut [LoadOutItem], returned: &BTreeMap<Part, u32>) {
+    let mut feeder_indexes: Vec<usize> = (0..load_out_items.len()).collect();
+    feeder_indexes.sort_by(|index, other_index| natural_cmp(&load_out_items[*index].reference, &load_out_items[*other_index].reference));
+
+    for (part, returned_quantity) in returned.iter() {
+        let part_feeder_indexes: Vec<usize> = feeder_indexes.iter().copied()
+            .filter(|index| {
+                let item = &load_out_items[*index];
+                item.quantity.is_some() && item.manufacturer.eq(&part.manufacturer) && item.mpn.eq(&part.mpn)
+            })
+            .collect();
+
+        let Some(index) = part_feeder_indexes.iter()
+            .find(|index| load_out_items[**index].quantity.is_some_and(|quantity| quantity > 0))
+            .or(part_feeder_indexes.last())
+        else {
+            continue
+        };
+
+        let item = &mut load_out_items[*index];
+        let remaining_quantity = item.quantity.unwrap().saturating_add(*returned_quantity);
+        item.quantity = Some(remaining_quantity);
+        info!(target: MUTATION_TARGET, feeder = %item.reference, part = ?part, returned = returned_quantity, remaining = remaining_quantity, message = "Returned load-out item quantity. feeder: '{feeder}', part: {part}, returned: {returned}, remaining: {remaining}");
+    }
+}
+
+/// Converts the absolute load-out sources of the phases that are within the project directory to project-relative
+/// sources, so that the project can be moved to another directory or machine.
+pub fn migrate_load_out_sources(project: &mut Project, project_dir: &Path) -> bool {
+    let mut modified = false;
+
+    for phase in project.phases.values_mut() {
+        let Ok(load_out_source) = LoadOutSource::from_str(&phase.load_out_source) else {
+            continue
+        };
+
+        if let Some(relative_load_out_source) = load_out_source.to_project_relative(project_dir) {
+            info!(target: MUTATION_TARGET, phase = %phase.reference, old = %load_out_source, new = %relative_load_out_source, message = "Migrated load-out source. phase: '{phase}', old: '{old}', new: '{new}'");
+            phase.load_out_source = relative_load_out_source.to_string();
+            modified = true;
+        }
+    }
+
+    modified
+}
+
+/// The phases that use the load-out, in phase order, i.e. the phases that share it.
+///
+/// The sources are compared after being resolved, so that a project-relative source, the equivalent `${PROJECT_DIR}`
+/// source and the equivalent absolute source all refer to the same load-out.
+pub fn find_load_out_phases(project: &Project, load_out_source: &LoadOutSource, project_dir: &Path) -> Vec<Reference> {
+    let resolved_load_out_source = load_out_source.resolve(project_dir);
+
+    project.phase_orderings.iter()
+        .filter_map(|reference| project.phases.get(reference))
+        .filter(|phase| LoadOutSource::from_str(&phase.load_out_source).is_ok_and(|source| source.resolve(project_dir).eq(&resolved_load_out_source)))
+        .map(|phase| phase.reference.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod consume_load_out_items_tests {
+    use std::collections::BTreeMap;
+    use std::str::FromStr;
+    use assert_fs::TempDir;
+    use pnp::load_out::LoadOutItem;
+    use pnp::part::Part;
+    use crate::load_out::{consume_items, consume_load_out_items, load_items, return_items, store_items, LoadOutSource};
+
+    #[test]
+    pub fn consume_tracked_quantities() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let mut test_load_out_path = temp_dir.path().to_path_buf();
+        test_load_out_path.push("load_out.csv");
+        let load_out_source = LoadOutSource::from_str(test_load_out_path.to_str().unwrap()).unwrap();
+
+        // and
+        let tracked_item = LoadOutItem { quantity: Some(10), reel: Some("REEL_1".to_string()), ..LoadOutItem::new("FEEDER_1".to_string(), "MFR1".to_string(), "PART1".to_string()) };
+        let untracked_item = LoadOutItem::new("FEEDER_2".to_string(), "MFR2".to_string(), "PART2".to_string());
+        store_items(&load_out_source, &[tracked_item, untracked_item])?;
+
+        // and
+        let consumed = BTreeMap::from([
+            (Part::new("MFR1".to_string(), "PART1".to_string()), 4),
+            (Part::new("MFR2".to_string(), "PART2".to_string()), 4),
+        ]);
+        let required = BTreeMap::from([
+            (Part::new("MFR1".to_string(), "PART1".to_string()), 8),
+        ]);
+
+        // and
+        let expected_items = vec![
+            LoadOutItem { quantity: Some(6), reel: Some("REEL_1".to_string()), ..LoadOutItem::new("FEEDER_1".to_string(), "MFR1".to_string(), "PART1".to_string()) },
+            LoadOutItem::new("FEEDER_2".to_string(), "MFR2".to_string(), "PART2".to_string()),
+        ];
+
+        // when
+        consume_load_out_items(&load_out_source, &consumed, &required)?;
+
+        // then
+        let items = load_items(&load_out_source)?;
+        assert_eq!(items, expected_items);
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn consume_part_in_multiple_feeders() {
+        // given
+        let mut items = vec![
+            LoadOutItem { quantity: Some(5), ..LoadOutItem::new("FEEDER_10".to_string(), "MFR1".to_string(), "PART1".to_string()) },
+            LoadOutItem { quantity: Some(3), ..LoadOutItem::new("FEEDER_2".to_string(), "MFR1".to_string(), "PART1".to_string()) },
+        ];
+
+        // and
+        let part1 = Part::new("MFR1".to_string(), "PART1".to_string());
+
+        // when
+        let shortfall = consume_items(&mut items, &BTreeMap::from([(part1.clone(), 4)]), &BTreeMap::from([(part1, 4)]));
+
+        // then the first feeder is emptied before the next feeder is used
+        assert_eq!(items[1].quantity, Some(0));
+        assert_eq!(items[0].quantity, Some(4));
+
+        // and
+        assert!(shortfall.is_empty());
+    }
+
+    #[test]
+    pub fn consume_more_than_loaded() {
+        // given
+        let mut items = vec![
+            LoadOutItem { quantity: Some(3), ..LoadOutItem::new("FEEDER_1".to_string(), "MFR1".to_string(), "PART1".to_string()) },
+            LoadOutItem::new("FEEDER_2".to_string(), "MFR1".to_string(), "PART2".to_string()),
+        ];
+
+        // and
+        let part1 = Part::new("MFR1".to_string(), "PART1".to_string());
+        let part2 = Part::new("MFR1".to_string(), "PART2".to_string());
+
+        // when
+        let shortfall = consume_items(&mut items, &BTreeMap::from([(part1.clone(), 5), (part2, 5)]), &BTreeMap::new());
+
+        // then the feeder is emptied
+        assert_eq!(items[0].quantity, Some(0));
+
+        // and the shortfall is returned, the part without a tracked quantity is not short
+        assert_eq!(shortfall, BTreeMap::from([(part1, 2)]));
+    }
+
+    #[test]
+    pub fn consume_and_return() {
+        // given
+        let mut items = vec![
+            LoadOutItem { quantity: Some(1), ..LoadOutItem::new("FEEDER_1".to_string(), "MFR1".to_string(), "PART1".to_string()) },
+            LoadOutItem { quantity: Some(5), ..LoadOutItem::new("FEEDER_2".to_string(), "MFR1".to_string(), "PART1".to_string()) },
+        ];
+
+        // and
+        let part1 = Part::new("MFR1".to_string(), "PART1".to_string());
+        consume_items(&mut items, &BTreeMap::from([(part1.clone(), 2)]), &BTreeMap::new());
+
+        // when
+        return_items(&mut items, &BTreeMap::from([(part1, 1)]));
+
+        // then the part is returned to the feeder it was last consumed from
+        assert_eq!(items[0].quantity, Some(0));
+        assert_eq!(items[1].quantity, Some(5));
+    }
+}
+
+#[cfg(test)]
+mod assign_feeder_tests {
+    use std::collections::BTreeMap;
+    use std::fs;
+    use std::str::FromStr;
+    use assert_fs::TempDir;
+    use indoc::indoc;
+    use regex::Regex;
+    use planning::project::ProcessFactory;
+    use planning::reference::Reference;
+    use pnp::feeder::{Feeder, FeederConstraintError};
+    use pnp::part::Part;
+    use crate::load_out::{assign_feeder_to_load_out_item, FeederAssignmentError, LoadOutOperationError, LoadOutSource};
+
+    #[test]
+    pub fn reject_incompatible_package() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let load_out_path = temp_dir.path().join("load_out.csv");
+        let load_out_content = indoc! {r#"
+            "Reference","Manufacturer","Mpn","Quantity","Reel","Alternates","LoadedAlternate"
+            "","MFR1","PART1","","","",""
+        "#};
+        fs::write(&load_out_path, load_out_content)?;
+        let load_out_source = LoadOutSource::from_str(load_out_path.to_str().unwrap()).unwrap();
+
+        // and
+        let process = ProcessFactory::by_name("pnp")?;
+        let feeder = Feeder {
+            reference: "FEEDER_1".to_string(),
+            kind: "tape".to_string(),
+            tape_width: Some(8),
+            packages: vec!["0402".to_string()],
+            capacity: None,
+        };
+        let part_packages = BTreeMap::from([(Part::new("MFR1".to_string(), "PART1".to_string()), "SOT-23".to_string())]);
+
+        // when
+        let result = assign_feeder_to_load_out_item(
+            &load_out_source, &process, &Reference::from_str("FEEDER_1")?, Regex::new("MFR1")?, Regex::new("PART1")?,
+            None, None, Some(&feeder), &part_packages,
+        );
+
+        // then
+        let error = result.unwrap_err().downcast::<LoadOutOperationError<FeederAssignmentError>>()?;
+        assert!(matches!(error, LoadOutOperationError::OperationError {
+            reason: FeederAssignmentError::IncompatibleFeeder { reason: FeederConstraintError::IncompatiblePackage { .. }, .. }, ..
+        }));
+
+        // and the load-out is unchanged
+        assert_eq!(fs::read_to_string(&load_out_path)?, load_out_content);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod store_items_tests {
+    use std::fs;
+    use std::str::FromStr;
+    use assert_fs::TempDir;
+    use indoc::indoc;
+    use pnp::load_out::LoadOutItem;
+    use crate::load_out::{store_items, LoadOutSource};
+
+    #[test]
+    pub fn store_items_in_feeder_reference_order() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let mut test_load_out_path = temp_dir.path().to_path_buf();
+        test_load_out_path.push("load_out.csv");
+        let load_out_source = LoadOutSource::from_str(test_load_out_path.to_str().unwrap()).unwrap();
+
+        // and
+        let items = vec![
+            LoadOutItem::new("".to_string(), "MFR2".to_string(), "PART4".to_string()),
+            LoadOutItem::new("FEEDER_10".to_string(), "MFR1".to_string(), "PART1".to_string()),
+            LoadOutItem::new("FEEDER_2".to_string(), "MFR1".to_string(), "PART2".to_string()),
+            LoadOutItem::new("".to_string(), "MFR1".to_string(), "PART3".to_string()),
+        ];
+
+        // and
+        let expected_content = indoc! {r#"
+            "Reference","Manufacturer","Mpn","Quantity","Reel","Alternates","LoadedAlternate"
+            "FEEDER_2","MFR1","PART2","","","",""
+            "FEEDER_10","MFR1","PART1","","","",""
+            "","MFR1","PART3","","","",""
+            "","MFR2","PART4","","","",""
+        "#};
+
+        // when
+        store_items(&load_out_source, &items)?;
+
+        // then
+        let content = fs::read_to_string(&test_load_out_path)?;
+        assert_eq!(content, expected_content);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod remove_parts_tests {
+    use std::collections::BTreeSet;
+    use std::str::FromStr;
+    use assert_fs::TempDir;
+    use pnp::load_out::LoadOutItem;
+    use pnp::part::Part;
+    use crate::load_out::{load_items, remove_parts_from_load_out, store_items, LoadOutSource};
+
+    #[test]
+    pub fn keep_parts_assigned_to_feeders() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let test_load_out_path = temp_dir.path().join("load_out.csv");
+        let load_out_source = LoadOutSource::from_str(test_load_out_path.to_str().unwrap()).unwrap();
+
+        // and
+        store_items(&load_out_source, &[
+            LoadOutItem::new("FEEDER_1".to_string(), "MFR1".to_string(), "PART1".to_string()),
+            LoadOutItem::new("".to_string(), "MFR1".to_string(), "PART2".to_string()),
+            LoadOutItem::new("".to_string(), "MFR1".to_string(), "PART3".to_string()),
+        ])?;
+
+        // when
+        let removed = remove_parts_from_load_out(&load_out_source, &BTreeSet::from([
+            Part::new("MFR1".to_string(), "PART1".to_string()),
+            Part::new("MFR1".to_string(), "PART2".to_string()),
+        ]))?;
+
+        // then
+        assert_eq!(removed, vec![Part::new("MFR1".to_string(), "PART2".to_string())]);
+
+        // and
+        let mpns: Vec<String> = load_items(&load_out_source)?.into_iter().map(|item| item.mpn).collect();
+        assert_eq!(mpns, vec!["PART1", "PART3"]);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod alternates_tests {
+    use std::str::FromStr;
+    use assert_fs::TempDir;
+    use pnp::load_out::LoadOutItem;
+    use pnp::part::Part;
+    use crate::load_out::{load_items, remove_load_out_item, rename_feeder, set_load_out_item_alternates, set_load_out_item_quantity, set_loaded_alternate, store_items, LoadOutAlternateError, LoadOutItemEditError, LoadOutOperationError, LoadOutSource};
+
+    #[test]
+    pub fn set_and_load_alternates() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let test_load_out_path = temp_dir.path().join("load_out.csv");
+        let load_out_source = LoadOutSource::from_str(test_load_out_path.to_str().unwrap()).unwrap();
+        store_items(&load_out_source, &[LoadOutItem::new("FEEDER_1".to_string(), "RES_MFR1".to_string(), "RES1".to_string())])?;
+
+        // and
+        let alternate_1 = Part::from_str("RES_MFR2:RES1A")?;
+        let alternate_2 = Part::from_str("RES_MFR3:RES1B")?;
+
+        // when
+        set_load_out_item_alternates(&load_out_source, "FEEDER_1", vec![alternate_1.clone(), alternate_2.clone()])?;
+        let loaded_part = set_loaded_alternate(&load_out_source, "FEEDER_1", Some(alternate_2.clone()))?;
+
+        // then
+        assert_eq!(loaded_part, alternate_2);
+
+        // and
+        let items = load_items(&load_out_source)?;
+        assert_eq!(items, vec![LoadOutItem {
+            alternates: vec![alternate_1.clone(), alternate_2.clone()],
+            loaded_alternate: Some(alternate_2),
+            ..LoadOutItem::new("FEEDER_1".to_string(), "RES_MFR1".to_string(), "RES1".to_string())
+        }]);
+
+        // when the loaded alternate is removed
+        set_load_out_item_alternates(&load_out_source, "FEEDER_1", vec![alternate_1.clone()])?;
+
+        // then the part itself is loaded
+        let items = load_items(&load_out_source)?;
+        assert_eq!(items[0].loaded_part(), Part::from_str("RES_MFR1:RES1")?);
+
+        // and
+        let result = set_loaded_alternate(&load_out_source, "FEEDER_1", Some(Part::from_str("RES_MFR3:RES1B")?));
+        assert!(matches!(result, Err(LoadOutOperationError::OperationError { reason: LoadOutAlternateError::UnknownAlternate { .. }, .. })));
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn edit_load_out_items() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let test_load_out_path = temp_dir.path().join("load_out.csv");
+        let load_out_source = LoadOutSource::from_str(test_load_out_path.to_str().unwrap()).unwrap();
+        store_items(&load_out_source, &[
+            LoadOutItem::new("FEEDER_1".to_string(), "RES_MFR1".to_string(), "RES1".to_string()),
+            LoadOutItem::new("FEEDER_2".to_string(), "RES_MFR1".to_string(), "RES2".to_string()),
+        ])?;
+
+        // when
+        let part = set_load_out_item_quantity(&load_out_source, "FEEDER_1", Some(100))?;
+        rename_feeder(&load_out_source, "FEEDER_1", "FEEDER_3")?;
+        let removed_item = remove_load_out_item(&load_out_source, "FEEDER_2")?;
+
+        // then
+        assert_eq!(part, Part::from_str("RES_MFR1:RES1")?);
+        assert_eq!(removed_item.mpn, "RES2");
+
+        // and
+        let items = load_items(&load_out_source)?;
+        assert_eq!(items, vec![LoadOutItem {
+            quantity: Some(100),
+            ..LoadOutItem::new("FEEDER_3".to_string(), "RES_MFR1".to_string(), "RES1".to_string())
+        }]);
+
+        // and
+        let result = rename_feeder(&load_out_source, "FEEDER_2", "FEEDER_4");
+        assert!(matches!(result, Err(LoadOutOperationError::OperationError { reason: LoadOutItemEditError::UnknownFeeder { .. }, .. })));
+
+        // and a feeder cannot hold two parts
+        store_items(&load_out_source, &[
+            LoadOutItem::new("FEEDER_1".to_string(), "RES_MFR1".to_string(), "RES1".to_string()),
+            LoadOutItem::new("FEEDER_2".to_string(), "RES_MFR1".to_string(), "RES2".to_string()),
+        ])?;
+        let result = rename_feeder(&load_out_source, "FEEDER_1", "FEEDER_2");
+        assert!(matches!(result, Err(LoadOutOperationError::OperationError { reason: LoadOutItemEditError::FeederInUse { .. }, .. })));
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod load_out_source_tests {
+    use std::path::PathBuf;
+    use std::str::FromStr;
+    use planning::phase::Phase;
+    use planning::process::ProcessName;
+    use planning::project::Project;
+    use planning::reference::Reference;
+    use pnp::pcb::PcbSide;
+    use crate::load_out::{find_load_out_phases, migrate_load_out_sources, LoadOutSource, LoadOutSourceError};
+
+    fn project_dir() -> PathBuf {
+        std::path::absolute("projects/job1").unwrap()
+    }
+
+    #[test]
+    pub fn parse_sources() {
+        // expect
+        assert_eq!(LoadOutSource::from_str("load_out_1.csv").unwrap(), LoadOutSource::File("load_out_1.csv".to_string()));
+        assert_eq!(LoadOutSource::from_str("file:///load_outs/load_out_1.csv").unwrap(), LoadOutSource::File("/load_outs/load_out_1.csv".to_string()));
+        assert_eq!(LoadOutSource::from_str("https://example.com/load_out_1.csv").unwrap(), LoadOutSource::Url("https://example.com/load_out_1.csv".to_string()));
+        assert!(matches!(LoadOutSource::from_str("db://load_outs/1"), Err(LoadOutSourceError::UnsupportedScheme(scheme)) if scheme == "db"));
+    }
+
+    #[test]
+    pub fn resolve_url_is_unchanged() {
+        // given
+        let load_out_source = LoadOutSource::from_str("http://example.com/load_out_1.csv").unwrap();
+
+        // when
+        let result = load_out_source.resolve(&project_dir());
+
+        // then
+        assert_eq!(result, load_out_source);
+
+        // and
+        assert_eq!(result.path(), None);
+        assert_eq!(result.to_project_relative(&project_dir()), None);
+    }
+
+    #[test]
+    pub fn resolve_relative_source() {
+        // given
+        let load_out_source = LoadOutSource::from_str("load_out_1.csv").unwrap();
+
+        // when
+        let result = load_out_source.resolve(&project_dir());
+
+        // then
+        assert_eq!(result.to_string(), project_dir().join("load_out_1.csv").to_string_lossy());
+    }
+
+    #[test]
+    pub fn resolve_project_dir_token() {
+        // given
+        let load_out_source = LoadOutSource::from_str("${PROJECT_DIR}/load_outs/load_out_1.csv").unwrap();
+
+        // when
+        let result = load_out_source.resolve(&project_dir());
+
+        // then
+        assert_eq!(result.to_string(), project_dir().join("load_outs/load_out_1.csv").to_string_lossy());
+    }
+
+    #[test]
+    pub fn resolve_absolute_source_is_unchanged() {
+        // given
+        let absolute_path = std::path::absolute("other/load_out_1.csv").unwrap();
+        let load_out_source = LoadOutSource::from_str(absolute_path.to_str().unwrap()).unwrap();
+
+        // when
+        let result = load_out_source.resolve(&project_dir());
+
+        // then
+        assert_eq!(result, load_out_source);
+    }
+
+    #[test]
+    pub fn round_trip_absolute_source_within_project_dir() {
+        // given
+        let absolute_path = project_dir().join("load_outs/load_out_1.csv");
+        let load_out_source = LoadOutSource::from_str(absolute_path.to_str().unwrap()).unwrap();
+
+        // when
+        let relative_load_out_source = load_out_source.to_project_relative(&project_dir()).unwrap();
+
+        // then
+        assert_eq!(relative_load_out_source, LoadOutSource::from_str("load_outs/load_out_1.csv").unwrap());
+
+        // and
+        assert_eq!(relative_load_out_source.resolve(&project_dir()), load_out_source);
+    }
+
+    #[test]
+    pub fn migrate_only_sources_within_project_dir() {
+        // given
+        let outside_path = std::path::absolute("other/load_out_2.csv").unwrap().to_string_lossy().to_string();
+
+        let mut project = Project::default();
+        for (reference, load_out_source) in [
+            ("top_1", project_dir().join("load_out_1.csv").to_string_lossy().to_string()),
+            ("bottom_1", outside_path.clone()),
+            ("top_2", "load_out_3.csv".to_string()),
+        ] {
+            let reference = Reference::from_str(reference).unwrap();
+            project.phases.insert(reference.clone(), Phase {
+                reference,
+                process: ProcessName::from_str("pnp").unwrap(),
+                load_out_source,
+                pcb_side: PcbSide::Top,
+                placement_orderings: vec![],
+                work_instructions_style: Default::default(),
+                tags: Default::default(),
+                first_article_inspection_required: false,
+                nozzle_configuration: None,
+                quantity_check: None,
+                export_format: None,
+                rotation_normalization: None,
+                dependencies: Default::default(),
+            });
+        }
+
+        // when
+        let modified = migrate_load_out_sources(&mut project, &project_dir());
+
+        // then
+        assert!(modified);
+
+        // and
+        let load_out_sources: Vec<(String, String)> = project.phases.values()
+            .map(|phase| (phase.reference.to_string(), phase.load_out_source.clone()))
+            .collect();
+        assert_eq!(load_out_sources, vec![
+            ("bottom_1".to_string(), outside_path),
+            ("top_1".to_string(), "load_out_1.csv".to_string()),
+            ("top_2".to_string(), "load_out_3.csv".to_string()),
+        ]);
+    }
+
+    #[test]
+    pub fn phases_sharing_a_load_out() {
+        // given
+        let mut project = Project::default();
+        for (reference, load_out_source) in [
+            ("top_1", "load_out_1.csv".to_string()),
+            ("top_2", "${PROJECT_DIR}/load_out_1.csv".to_string()),
+            ("bottom_1", project_dir().join("load_out_1.csv").to_string_lossy().to_string()),
+            ("bottom_2", "load_out_2.csv".to_string()),
+        ] {
+            project.update_phase(Reference::from_str(reference).unwrap(), ProcessName::from_str("pnp").unwrap(), load_out_source, PcbSide::Top).unwrap();
+        }
+
+        // when
+        let phases = find_load_out_phases(&project, &LoadOutSource::from_str("load_out_1.csv").unwrap(), &project_dir());
+
+        // then the phases are in phase order
+        let phases: Vec<String> = phases.iter().map(Reference::to_string).collect();
+        assert_eq!(phases, vec!["top_1", "top_2", "bottom_1"]);
+    }
+}
+
+#[cfg(all(test, feature = "http"))]
+mod http_load_out_tests {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::str::FromStr;
+    use std::sync::{Arc, Mutex};
+    use pnp::load_out::LoadOutItem;
+    use pnp::part::Part;
+    use crate::load_out::{ensure_load_out, load_items, store_items, LoadOutSource};
+    use crate::part_rename::{rename_part_in_load_out_items, RecordChange};
+
+    /// Serves a single resource, `HEAD` and `GET` return 404 until the resource has been `PUT`.
+    fn start_server(requests: usize) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/load_outs/load_out_1.csv", listener.local_addr().unwrap());
+        let methods = Arc::new(Mutex::new(vec![]));
+
+        let server_methods = methods.clone();
+        std::thread::spawn(move || {
+            let mut content: Option<Vec<u8>> = None;
+
+            for stream in listener.incoming().take(requests) {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let method = request_line.split_whitespace().next().unwrap().to_string();
+
+                let mut content_length = 0;
+                loop {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).unwrap();
+                    if header.trim().is_empty() {
+                        break
+                    }
+                    if let Some((name, value)) = header.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            content_length = value.trim().parse().unwrap();
+                        }
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+
+                let (status, response_body) = match (method.as_str(), &content) {
+                    ("PUT", _) => {
+                        content = Some(body);
+                        ("200 OK", vec![])
+                    },
+                    ("GET", Some(content)) => ("200 OK", content.clone()),
+                    ("HEAD", Some(_)) => ("200 OK", vec![]),
+                    _ => ("404 Not Found", vec![]),
+                };
+
+                // recorded before responding, so the client sees the request once it has the response
+                server_methods.lock().unwrap().push(method.clone());
+
+                write!(stream, "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", status, response_body.len()).unwrap();
+                if method.ne("HEAD") {
+                    stream.write_all(&response_body).unwrap();
+                }
+            }
+        });
+
+        (url, methods)
+    }
+
+    #[test]
+    pub fn store_and_load_items() -> anyhow::Result<()> {
+        // given
+        let (url, methods) = start_server(4);
+        let load_out_source = LoadOutSource::from_str(&url)?;
+
+        // and
+        let items = vec![
+            LoadOutItem::new("FEEDER_1".to_string(), "MFR1".to_string(), "PART1".to_string()),
+        ];
+
+        // when
+        ensure_load_out(&load_out_source)?;
+        store_items(&load_out_source, &items)?;
+        let loaded_items = load_items(&load_out_source)?;
+
+        // then
+        assert_eq!(loaded_items, items);
+
+        // and the missing load-out was created
+        assert_eq!(*methods.lock().unwrap(), vec!["HEAD", "PUT", "PUT", "GET"]);
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn rename_part() -> anyhow::Result<()> {
+        // given
+        let (url, methods) = start_server(4);
+        let load_out_source = LoadOutSource::from_str(&url)?;
+
+        // and
+        store_items(&load_out_source, &[
+            LoadOutItem::new("FEEDER_1".to_string(), "RES_MFR1".to_string(), "RES1".to_string()),
+            LoadOutItem::new("FEEDER_2".to_string(), "RES_MFR1".to_string(), "RES2".to_string()),
+        ])?;
+
+        // and
+        let from = Part::new("RES_MFR1".to_string(), "RES1".to_string());
+        let to = Part::new("RES_MFR2".to_string(), "RES1A".to_string());
+
+        // when
+        let (changes, renamed_items) = rename_part_in_load_out_items(load_items(&load_out_source)?, &from, &to)?.unwrap();
+        store_items(&load_out_source, &renamed_items)?;
+
+        // then
+        assert_eq!(changes, vec![
+            RecordChange { before: "FEEDER_1,RES_MFR1,RES1,,,,".to_string(), after: "FEEDER_1,RES_MFR2,RES1A,,,,".to_string() },
+        ]);
+
+        // and
+        assert_eq!(load_items(&load_out_source)?, vec![
+            LoadOutItem::new("FEEDER_1".to_string(), "RES_MFR2".to_string(), "RES1A".to_string()),
+            LoadOutItem::new("FEEDER_2".to_string(), "RES_MFR1".to_string(), "RES2".to_string()),
+        ]);
+        assert_eq!(*methods.lock().unwrap(), vec!["PUT", "GET", "PUT", "GET"]);
+
+        Ok(())
+    }
+}
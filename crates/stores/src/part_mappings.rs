@@ -2,17 +2,20 @@ use tracing::Level;
 use anyhow::{Context, Error};
 use std::path::PathBuf;
 use tracing::trace;
+use crate::xlsx;
 use crate::csv::PartMappingRecord;
+use eda::plugin::EdaToolRegistry;
 use pnp::part::Part;
 use part_mapper::part_mapping::PartMapping;
 
 #[tracing::instrument(level = Level::DEBUG)]
-pub fn load_part_mappings<'part>(parts: &'part Vec<Part>, part_mappings_source: &String) -> Result<Vec<PartMapping<'part>>, Error> {
+pub fn load_part_mappings<'part>(parts: &'part Vec<Part>, part_mappings_source: &String, registry: &EdaToolRegistry) -> Result<Vec<PartMapping<'part>>, Error> {
     let part_mappings_path_buf = PathBuf::from(part_mappings_source);
     let part_mappings_path = part_mappings_path_buf.as_path();
-    let mut csv_reader = csv::ReaderBuilder::new()
-        .from_path(part_mappings_path)
+    let content = xlsx::read_csv_content(part_mappings_path)
         .with_context(|| format!("Error reading part mappings. file: {}", part_mappings_path.to_str().unwrap()))?;
+    let mut csv_reader = csv::ReaderBuilder::new()
+        .from_reader(content.as_bytes());
 
     let mut part_mappings: Vec<PartMapping> = vec![];
 
@@ -22,7 +25,7 @@ pub fn load_part_mappings<'part>(parts: &'part Vec<Part>, part_mappings_source:
 
         trace!("{:?}", record);
 
-        let part_mapping = record.build_part_mapping(parts)
+        let part_mapping = record.build_part_mapping(parts, registry)
             .with_context(|| format!("Building part mapping from record. record: {:?}", record))?;
 
         part_mappings.push(part_mapping);
@@ -38,6 +41,7 @@ pub mod csv_loading_tests {
     use criteria::{ExactMatchCriterion, GenericCriteria, RegexMatchCriterion};
     use part_mapper::part_mapping::PartMapping;
     use pnp::part::Part;
+    use eda::plugin::EdaToolRegistry;
     use crate::part_mappings::load_part_mappings;
     use crate::part_mappings::test::TestPartMappingRecord;
 
@@ -73,7 +77,7 @@ pub mod csv_loading_tests {
         println!("{csv_content:}");
 
         // when
-        let result = load_part_mappings(&parts, &test_part_mappings_source);
+        let result = load_part_mappings(&parts, &test_part_mappings_source, &EdaToolRegistry::default());
 
         // then
         assert!(result.is_ok());
@@ -118,13 +122,13 @@ pub mod csv_loading_tests {
 
         // and
         let expected_result: Vec<PartMapping> = vec![
-            PartMapping { part: parts.get(0).unwrap(), criteria: vec![
+            PartMapping { part: parts.first().unwrap(), criteria: vec![
                 Box::new(GenericCriteria { criteria: vec![
                     Box::new(ExactMatchCriterion { field_name: "name".to_string(), field_pattern: "12345".to_string() }),
                     Box::new(ExactMatchCriterion { field_name: "value".to_string(), field_pattern: "54321".to_string() }),
                 ] })
             ] },
-            PartMapping { part: parts.get(0).unwrap(), criteria: vec![
+            PartMapping { part: parts.first().unwrap(), criteria: vec![
                 Box::new(GenericCriteria { criteria: vec![
                     Box::new(ExactMatchCriterion { field_name: "name".to_string(), field_pattern: "12345".to_string() }),
                     Box::new(RegexMatchCriterion { field_name: "value".to_string(), field_pattern: Regex::new(".*").unwrap() }),
@@ -136,7 +140,7 @@ pub mod csv_loading_tests {
         println!("{csv_content:}");
 
         // when
-        let result = load_part_mappings(&parts, &test_part_mappings_source)?;
+        let result = load_part_mappings(&parts, &test_part_mappings_source, &EdaToolRegistry::default())?;
 
         // then
         assert_eq!(result, expected_result);
@@ -0,0 +1,265 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use anyhow::{bail, Context};
+use csv::{QuoteStyle, StringRecord};
+use tracing::{info, trace};
+use pnp::load_out::LoadOutItem;
+use pnp::part::Part;
+use planning::mutation::MUTATION_TARGET;
+use crate::csv::LoadOutItemRecord;
+
+const MANUFACTURER_HEADER: &str = "Manufacturer";
+const MPN_HEADER: &str = "Mpn";
+
+#[derive(Debug, PartialEq)]
+pub struct RecordChange {
+    pub before: String,
+    pub after: String,
+}
+
+/// The new content of a file, and the changed records, after renaming a part.
+#[derive(Debug)]
+pub struct FileChange {
+    pub path: PathBuf,
+    pub changes: Vec<RecordChange>,
+    pub content: Vec<u8>,
+}
+
+/// Renames the part in a CSV file that has 'Manufacturer' and 'Mpn' columns, e.g. placements, load-outs, part mappings.
+///
+/// The file is not modified, returns `None` if the file does not contain the part.
+pub fn rename_part_in_csv(path: &Path, from: &Part, to: &Part) -> anyhow::Result<Option<FileChange>> {
+    let mut csv_reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .from_path(path)
+        .with_context(|| format!("Error reading file. file: {:?}", path))?;
+
+    let mut records = csv_reader.records();
+
+    let Some(headers) = records.next() else {
+        // empty file, e.g. a new load-out
+        return Ok(None)
+    };
+    let headers = headers.with_context(|| format!("Reading headers. file: {:?}", path))?;
+
+    let manufacturer_index = headers.iter().position(|header| header.eq(MANUFACTURER_HEADER));
+    let mpn_index = headers.iter().position(|header| header.eq(MPN_HEADER));
+    let (Some(manufacturer_index), Some(mpn_index)) = (manufacturer_index, mpn_index) else {
+        bail!("Missing columns. file: {:?}, required: ['{}', '{}']", path, MANUFACTURER_HEADER, MPN_HEADER)
+    };
+
+    let mut writer = csv::WriterBuilder::new()
+        .quote_style(QuoteStyle::Always)
+        .from_writer(vec![]);
+
+    writer.write_record(&headers)?;
+
+    let mut changes = vec![];
+
+    for result in records {
+        let record = result.with_context(|| format!("Reading record. file: {:?}", path))?;
+
+        let matched = record.get(manufacturer_index).eq(&Some(from.manufacturer.as_str()))
+            && record.get(mpn_index).eq(&Some(from.mpn.as_str()));
+
+        if !matched {
+            writer.write_record(&record)?;
+            continue
+        }
+
+        let renamed: StringRecord = record.iter().enumerate().map(|(index, field)| {
+            if index == manufacturer_index {
+                to.manufacturer.as_str()
+            } else if index == mpn_index {
+                to.mpn.as_str()
+            } else {
+                field
+            }
+        }).collect();
+
+        trace!("Renamed part in record. file: {:?}, before: {:?}, after: {:?}", path, record, renamed);
+
+        changes.push(RecordChange { before: format_record(&record), after: format_record(&renamed) });
+        writer.write_record(&renamed)?;
+    }
+
+    if changes.is_empty() {
+        return Ok(None)
+    }
+
+    let content = writer.into_inner()?;
+
+    Ok(Some(FileChange { path: path.to_path_buf(), changes, content }))
+}
+
+/// Renames the part in the items of a load-out that is not a file, e.g. a load-out on a web server, which is loaded and
+/// stored using its repository, see `load_out::LoadOutSource`.
+///
+/// The items are not stored, returns `None` if the load-out does not contain the part, otherwise the changed records,
+/// formatted as for `rename_part_in_csv`, and the renamed items.
+pub fn rename_part_in_load_out_items(mut items: Vec<LoadOutItem>, from: &Part, to: &Part) -> anyhow::Result<Option<(Vec<RecordChange>, Vec<LoadOutItem>)>> {
+    let mut changes = vec![];
+
+    for item in items.iter_mut().filter(|item| item.matches_part(from)) {
+        let before = format_load_out_item(item)?;
+        item.manufacturer.clone_from(&to.manufacturer);
+        item.mpn.clone_from(&to.mpn);
+
+        changes.push(RecordChange { before, after: format_load_out_item(item)? });
+    }
+
+    if changes.is_empty() {
+        return Ok(None)
+    }
+
+    Ok(Some((changes, items)))
+}
+
+fn format_load_out_item(item: &LoadOutItem) -> anyhow::Result<String> {
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_writer(vec![]);
+    writer.serialize(LoadOutItemRecord::from_load_out_item(item))?;
+
+    Ok(String::from_utf8(writer.into_inner()?)?.trim_end().to_string())
+}
+
+fn format_record(record: &StringRecord) -> String {
+    record.iter().collect::<Vec<_>>().join(",")
+}
+
+/// Writes all the files, or none of them.
+///
+/// The content of each file is written to a temporary file alongside it first, the temporary files are then renamed.
+pub fn write_files(files: &[(PathBuf, Vec<u8>)]) -> anyhow::Result<()> {
+    let mut temporary_paths: Vec<PathBuf> = vec![];
+
+    for (path, content) in files.iter() {
+        let temporary_path = build_temporary_path(path);
+
+        if let Err(reason) = fs::write(&temporary_path, content) {
+            for temporary_path in temporary_paths.iter().chain([&temporary_path]) {
+                let _ = fs::remove_file(temporary_path);
+            }
+            return Err(reason).with_context(|| format!("Error writing file. file: {:?}", temporary_path));
+        }
+
+        temporary_paths.push(temporary_path);
+    }
+
+    for ((path, _content), temporary_path) in files.iter().zip(temporary_paths) {
+        fs::rename(&temporary_path, path)
+            .with_context(|| format!("Error replacing file. file: {:?}", path))?;
+
+        info!(target: MUTATION_TARGET, path = ?path, message = "Updated file. path: {path}");
+    }
+
+    Ok(())
+}
+
+fn build_temporary_path(path: &Path) -> PathBuf {
+    let mut temporary_path = path.as_os_str().to_owned();
+    temporary_path.push(".tmp");
+    PathBuf::from(temporary_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use assert_fs::TempDir;
+    use indoc::indoc;
+    use pnp::part::Part;
+    use crate::part_rename::{rename_part_in_csv, write_files, RecordChange};
+
+    #[test]
+    pub fn rename_part_in_load_out() {
+        // given
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("load_out.csv");
+        fs::write(&path, indoc! {r#"
+            "Reference","Manufacturer","Mpn","Quantity","Reel"
+            "FEEDER_1","RES_MFR1","RES1","",""
+            "FEEDER_2","RES_MFR1","RES2","",""
+        "#}).unwrap();
+
+        // and
+        let from = Part::new("RES_MFR1".to_string(), "RES1".to_string());
+        let to = Part::new("RES_MFR2".to_string(), "RES1A".to_string());
+
+        // when
+        let result = rename_part_in_csv(&path, &from, &to).unwrap().unwrap();
+
+        // then
+        assert_eq!(String::from_utf8(result.content).unwrap(), indoc! {r#"
+            "Reference","Manufacturer","Mpn","Quantity","Reel"
+            "FEEDER_1","RES_MFR2","RES1A","",""
+            "FEEDER_2","RES_MFR1","RES2","",""
+        "#});
+
+        // and
+        assert_eq!(result.changes, vec![
+            RecordChange { before: "FEEDER_1,RES_MFR1,RES1,,".to_string(), after: "FEEDER_1,RES_MFR2,RES1A,,".to_string() },
+        ]);
+
+        // and the file is unchanged
+        assert!(fs::read_to_string(&path).unwrap().contains("RES1\""));
+    }
+
+    #[test]
+    pub fn rename_unused_part() {
+        // given
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("part_mappings.csv");
+        fs::write(&path, indoc! {r#"
+            "Eda","Package","Val","Manufacturer","Mpn"
+            "KiCad","R_0402_1005Metric","10K","RES_MFR1","RES2"
+        "#}).unwrap();
+
+        // and
+        let from = Part::new("RES_MFR1".to_string(), "RES1".to_string());
+        let to = Part::new("RES_MFR2".to_string(), "RES1A".to_string());
+
+        // when
+        let result = rename_part_in_csv(&path, &from, &to).unwrap();
+
+        // then
+        assert!(result.is_none());
+    }
+
+    #[test]
+    pub fn rename_part_in_file_without_part_columns() {
+        // given
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("eda_placements.csv");
+        fs::write(&path, "\"ref\",\"Package\",\"Val\"\n").unwrap();
+
+        // and
+        let from = Part::new("RES_MFR1".to_string(), "RES1".to_string());
+        let to = Part::new("RES_MFR2".to_string(), "RES1A".to_string());
+
+        // when
+        let result = rename_part_in_csv(&path, &from, &to);
+
+        // then
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn write_files_replaces_all_files() {
+        // given
+        let temp_dir = TempDir::new().unwrap();
+        let path_1 = temp_dir.path().join("file_1.csv");
+        let path_2 = temp_dir.path().join("file_2.csv");
+        fs::write(&path_1, "old").unwrap();
+
+        // when
+        write_files(&[(path_1.clone(), b"new 1".to_vec()), (path_2.clone(), b"new 2".to_vec())]).unwrap();
+
+        // then
+        assert_eq!(fs::read_to_string(&path_1).unwrap(), "new 1");
+        assert_eq!(fs::read_to_string(&path_2).unwrap(), "new 2");
+
+        // and no temporary files remain
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 2);
+    }
+}
@@ -1,19 +1,116 @@
 use tracing::Level;
 use anyhow::{Context, Error};
-use std::path::PathBuf;
-use tracing::trace;
-use pnp::part::Part;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use rust_decimal::Decimal;
+use tracing::{info, trace};
+use eda::diptrace::bom::{DiptraceBomRecord, DIPTRACE_BOM_HEADERS};
+use planning::moisture::MslLevel;
+use crate::xlsx;
+use pnp::part::{Part, PartDetails};
 use crate::csv::PartRecord;
 
+/// A part of a parts master, or part library, with the attributes of the part.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartsMasterItem {
+    pub part: Part,
+    pub details: PartDetails,
+    /// Moisture sensitivity level, `None` if not specified.
+    pub msl_level: Option<MslLevel>,
+}
+
+/// A part of a '.toml' parts master, e.g.
+///
+/// ```toml
+/// [[parts]]
+/// manufacturer = "RES_MFR1"
+/// mpn = "RES1"
+/// value = "10K"
+/// description = "10K 1% resistor"
+/// package = "0402"
+/// height = 0.35
+/// msl = "1"
+/// ```
+#[derive(Debug, serde::Deserialize)]
+struct PartsMasterRecord {
+    manufacturer: String,
+    mpn: String,
+    #[serde(default)]
+    image: Option<String>,
+    #[serde(default)]
+    datasheet: Option<String>,
+    #[serde(default)]
+    package: Option<String>,
+    #[serde(default)]
+    length: Option<Decimal>,
+    #[serde(default)]
+    width: Option<Decimal>,
+    #[serde(default)]
+    height: Option<Decimal>,
+    #[serde(default)]
+    value: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    tape_width: Option<u32>,
+    #[serde(default)]
+    msl: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct PartsMasterFile {
+    #[serde(default)]
+    parts: Vec<PartsMasterRecord>,
+}
+
+/// Loads the parts from a parts file, or from a DipTrace BOM, see [`DiptraceBomRecord`].
 #[tracing::instrument(level = Level::DEBUG)]
 pub fn load_parts(parts_source: &String) -> Result<Vec<Part>, Error> {
+    let parts = read_parts(parts_source)?.into_iter()
+        .map(|item| item.part)
+        .collect();
+
+    Ok(parts)
+}
+
+/// Loads the details of each part, parts without details are included.
+#[tracing::instrument(level = Level::DEBUG)]
+pub fn load_part_details(parts_source: &String) -> Result<BTreeMap<Part, PartDetails>, Error> {
+    let part_details = read_parts(parts_source)?.into_iter()
+        .map(|item| (item.part, item.details))
+        .collect();
+
+    Ok(part_details)
+}
+
+/// Loads a parts master, a '.toml' file, or a parts file (CSV or '.xlsx') with the optional 'Value', 'Description',
+/// 'Package', 'Height' and 'Msl' columns, or a DipTrace BOM.
+#[tracing::instrument(level = Level::DEBUG)]
+pub fn load_parts_master(parts_source: &String) -> Result<Vec<PartsMasterItem>, Error> {
+    read_parts(parts_source)
+}
+
+fn read_parts(parts_source: &String) -> Result<Vec<PartsMasterItem>, Error> {
     let parts_path_buf = PathBuf::from(parts_source);
     let parts_path = parts_path_buf.as_path();
-    let mut csv_reader = csv::ReaderBuilder::new()
-        .from_path(parts_path)
+
+    if parts_path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("toml")) {
+        return read_toml_parts(parts_path)
+    }
+
+    let content = xlsx::read_csv_content(parts_path)
         .with_context(|| format!("Error reading parts. file: {}", parts_path.to_str().unwrap()))?;
+    let mut csv_reader = csv::ReaderBuilder::new()
+        .from_reader(content.as_bytes());
+
+    let headers = csv_reader.headers()
+        .with_context(|| format!("Error reading parts headers. file: {}", parts_path.to_str().unwrap()))?;
+
+    if is_diptrace_bom(headers) {
+        return read_diptrace_bom_parts(csv_reader, parts_path)
+    }
 
-    let mut parts: Vec<Part> = vec![];
+    let mut parts: Vec<PartsMasterItem> = vec![];
 
     for result in csv_reader.deserialize() {
         let record: PartRecord = result
@@ -23,8 +120,263 @@ pub fn load_parts(parts_source: &String) -> Result<Vec<Part>, Error> {
 
         let part = record.build_part()
             .with_context(|| format!("Building part from record. record: {:?}", record))?;
+        let msl_level = record.build_msl_level()
+            .with_context(|| format!("Building moisture sensitivity level from record. record: {:?}", record))?;
 
-        parts.push(part);
+        parts.push(PartsMasterItem { part, details: record.build_part_details(), msl_level });
     }
     Ok(parts)
-}
\ No newline at end of file
+}
+
+fn read_toml_parts(parts_path: &Path) -> Result<Vec<PartsMasterItem>, Error> {
+    let content = std::fs::read_to_string(parts_path)
+        .with_context(|| format!("Error reading parts master. file: {}", parts_path.to_str().unwrap()))?;
+    let parts_master: PartsMasterFile = toml::from_str(&content)
+        .with_context(|| format!("Error parsing parts master. file: {}", parts_path.to_str().unwrap()))?;
+
+    let mut parts: Vec<PartsMasterItem> = vec![];
+
+    for record in parts_master.parts {
+        trace!("{:?}", record);
+
+        let msl_level = record.msl.as_deref()
+            .filter(|msl| !msl.trim().is_empty())
+            .map(str::parse::<MslLevel>)
+            .transpose()
+            .with_context(|| format!("Building moisture sensitivity level from record. record: {:?}", record))?;
+
+        parts.push(PartsMasterItem {
+            part: Part::new(record.manufacturer, record.mpn),
+            details: PartDetails {
+                image: record.image,
+                datasheet: record.datasheet,
+                package: record.package,
+                length: record.length,
+                width: record.width,
+                height: record.height,
+                value: record.value,
+                description: record.description,
+                tape_width: record.tape_width,
+            },
+            msl_level,
+        });
+    }
+
+    info!("Loaded parts master. file: {}, parts: {}", parts_path.to_str().unwrap(), parts.len());
+
+    Ok(parts)
+}
+
+fn is_diptrace_bom(headers: &csv::StringRecord) -> bool {
+    DIPTRACE_BOM_HEADERS.iter()
+        .all(|required_header| headers.iter().any(|header| header.trim().eq(*required_header)))
+}
+
+/// Items without a manufacturer or manufacturer part number, e.g. fiducials, are skipped.  Parts that appear more than
+/// once are only included once.
+fn read_diptrace_bom_parts(mut csv_reader: csv::Reader<&[u8]>, parts_path: &Path) -> Result<Vec<PartsMasterItem>, Error> {
+    info!("Reading parts from DipTrace BOM. file: {}", parts_path.to_str().unwrap());
+
+    let mut parts: Vec<PartsMasterItem> = vec![];
+
+    for result in csv_reader.deserialize() {
+        let record: DiptraceBomRecord = result
+            .with_context(|| "Deserializing DipTrace BOM record".to_string())?;
+
+        trace!("{:?}", record);
+
+        let Some(part) = record.build_part() else {
+            info!("Skipping BOM item without a manufacturer and manufacturer part number. ref_des: {:?}", record.ref_des_list());
+            continue
+        };
+
+        if parts.iter().any(|item| item.part.eq(&part)) {
+            continue
+        }
+
+        parts.push(PartsMasterItem { part, details: record.build_part_details(), msl_level: None });
+    }
+    Ok(parts)
+}
+
+#[cfg(test)]
+mod load_part_details_tests {
+    use std::collections::BTreeMap;
+    use assert_fs::TempDir;
+    use indoc::indoc;
+    use rust_decimal_macros::dec;
+    use pnp::part::{Part, PartDetails};
+    use planning::moisture::MslLevel;
+    use crate::parts::{load_part_details, load_parts, load_parts_master, PartsMasterItem};
+
+    #[test]
+    pub fn load_with_optional_columns() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let parts_path = temp_dir.path().join("parts.csv");
+        std::fs::write(&parts_path, indoc! {r#"
+            "Manufacturer","Mpn","Image","Datasheet","Package","Length","Width","Height","Value","Description","TapeWidth"
+            "RES_MFR1","RES1","images/res1.png","https://example.com/res1.pdf","0402","1.0","0.5","0.35","10K","10K 1% resistor","8"
+            "RES_MFR1","RES2","","","","","","","","",""
+        "#})?;
+
+        // when
+        let result = load_part_details(&parts_path.to_str().unwrap().to_string())?;
+
+        // then
+        assert_eq!(result, BTreeMap::from([
+            (Part::new("RES_MFR1".to_string(), "RES1".to_string()), PartDetails {
+                image: Some("images/res1.png".to_string()),
+                datasheet: Some("https://example.com/res1.pdf".to_string()),
+                package: Some("0402".to_string()),
+                length: Some(dec!(1.0)),
+                width: Some(dec!(0.5)),
+                height: Some(dec!(0.35)),
+                value: Some("10K".to_string()),
+                description: Some("10K 1% resistor".to_string()),
+                tape_width: Some(8),
+            }),
+            (Part::new("RES_MFR1".to_string(), "RES2".to_string()), PartDetails::default()),
+        ]));
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn load_without_optional_columns() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let parts_path = temp_dir.path().join("parts.csv");
+        std::fs::write(&parts_path, indoc! {r#"
+            "Manufacturer","Mpn"
+            "RES_MFR1","RES1"
+        "#})?;
+
+        // when
+        let result = load_part_details(&parts_path.to_str().unwrap().to_string())?;
+
+        // then
+        assert_eq!(result, BTreeMap::from([
+            (Part::new("RES_MFR1".to_string(), "RES1".to_string()), PartDetails::default()),
+        ]));
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn load_from_diptrace_bom() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let parts_path = temp_dir.path().join("bom.csv");
+        std::fs::write(&parts_path, indoc! {r#"
+            "Quantity","RefDes","Value","Name","Pattern","Manufacturer","MPN","Datasheet"
+            "2","R1, R2","330R","RES","RES_0402","RES_MFR1","RES1",""
+            "1","C1","100nF","CAP","CAP_0402","CAP_MFR1","CAP1","https://example.com/cap1.pdf"
+            "1","R3","330R","RES","RES_0402","RES_MFR1","RES1",""
+            "1","FID1","","FIDUCIAL","FID_1MM","","",""
+        "#})?;
+        let parts_source = parts_path.to_str().unwrap().to_string();
+
+        // when
+        let parts = load_parts(&parts_source)?;
+
+        // then
+        assert_eq!(parts, vec![
+            Part::new("RES_MFR1".to_string(), "RES1".to_string()),
+            Part::new("CAP_MFR1".to_string(), "CAP1".to_string()),
+        ]);
+
+        // and
+        let part_details = load_part_details(&parts_source)?;
+        assert_eq!(part_details.get(&Part::new("CAP_MFR1".to_string(), "CAP1".to_string())), Some(&PartDetails {
+            image: None,
+            datasheet: Some("https://example.com/cap1.pdf".to_string()),
+            package: Some("CAP_0402".to_string()),
+            ..PartDetails::default()
+        }));
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn load_parts_master_from_csv() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let parts_path = temp_dir.path().join("parts.csv");
+        std::fs::write(&parts_path, indoc! {r#"
+            "Manufacturer","Mpn","Value","Msl"
+            "IC_MFR1","IC1","","3"
+            "RES_MFR1","RES1","10K",""
+        "#})?;
+
+        // when
+        let result = load_parts_master(&parts_path.to_str().unwrap().to_string())?;
+
+        // then
+        assert_eq!(result.iter().map(|item| item.msl_level).collect::<Vec<_>>(), vec![Some(MslLevel::Level3), None]);
+        assert_eq!(result[1].details.value, Some("10K".to_string()));
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn load_parts_master_from_toml() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let parts_path = temp_dir.path().join("parts.toml");
+        std::fs::write(&parts_path, indoc! {r#"
+            [[parts]]
+            manufacturer = "IC_MFR1"
+            mpn = "IC1"
+            description = "Microcontroller"
+            package = "QFN-32"
+            height = 0.9
+            msl = "MSL3"
+
+            [[parts]]
+            manufacturer = "RES_MFR1"
+            mpn = "RES1"
+            value = "10K"
+        "#})?;
+
+        // when
+        let result = load_parts_master(&parts_path.to_str().unwrap().to_string())?;
+
+        // then
+        assert_eq!(result, vec![
+            PartsMasterItem {
+                part: Part::new("IC_MFR1".to_string(), "IC1".to_string()),
+                details: PartDetails {
+                    description: Some("Microcontroller".to_string()),
+                    package: Some("QFN-32".to_string()),
+                    height: Some(dec!(0.9)),
+                    ..PartDetails::default()
+                },
+                msl_level: Some(MslLevel::Level3),
+            },
+            PartsMasterItem {
+                part: Part::new("RES_MFR1".to_string(), "RES1".to_string()),
+                details: PartDetails { value: Some("10K".to_string()), ..PartDetails::default() },
+                msl_level: None,
+            },
+        ]);
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn load_parts_master_with_invalid_msl() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let parts_path = temp_dir.path().join("parts.csv");
+        std::fs::write(&parts_path, indoc! {r#"
+            "Manufacturer","Mpn","Msl"
+            "IC_MFR1","IC1","7"
+        "#})?;
+
+        // expect
+        assert!(load_parts_master(&parts_path.to_str().unwrap().to_string()).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+use tracing::Level;
+use std::path::PathBuf;
+use anyhow::{Context, Error};
+use regex::Regex;
+use tracing::trace;
+use eda::classification::PlacementClassificationRule;
+use pnp::placement::PlacementKind;
+
+/// A placement classification rule record, the patterns are regular expressions, an empty pattern matches anything, e.g.
+///
+/// ```csv
+/// "Kind","RefDesPattern","PackagePattern"
+/// "Fiducial","^FID\d+$",""
+/// "TestPoint","^TP\d+$","^TestPoint_"
+/// ```
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all(deserialize = "PascalCase"))]
+pub struct PlacementClassificationRecord {
+    kind: PlacementKind,
+    #[serde(default)]
+    ref_des_pattern: String,
+    #[serde(default)]
+    package_pattern: String,
+}
+
+impl PlacementClassificationRecord {
+    pub fn build_rule(&self) -> Result<PlacementClassificationRule, Error> {
+        let build_pattern = |pattern: &str| match pattern.trim() {
+            "" => Ok(None),
+            pattern => Regex::new(pattern).map(Some),
+        };
+
+        Ok(PlacementClassificationRule {
+            kind: self.kind,
+            ref_des_pattern: build_pattern(&self.ref_des_pattern)?,
+            package_pattern: build_pattern(&self.package_pattern)?,
+        })
+    }
+}
+
+#[tracing::instrument(level = Level::DEBUG)]
+pub fn load_classification_rules(classification_rules_source: &String) -> Result<Vec<PlacementClassificationRule>, Error> {
+    let classification_rules_path = PathBuf::from(classification_rules_source);
+    let mut csv_reader = csv::ReaderBuilder::new().from_path(&classification_rules_path)
+        .with_context(|| format!("Error reading placement classification rules. file: {:?}", classification_rules_path))?;
+
+    let mut rules: Vec<PlacementClassificationRule> = vec![];
+
+    for result in csv_reader.deserialize() {
+        let record: PlacementClassificationRecord = result
+            .with_context(|| "Deserializing placement classification record".to_string())?;
+
+        trace!("{:?}", record);
+
+        let rule = record.build_rule()
+            .with_context(|| format!("Building placement classification rule from record. record: {:?}", record))?;
+
+        rules.push(rule);
+    }
+
+    Ok(rules)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::TempDir;
+    use indoc::indoc;
+    use pnp::placement::PlacementKind;
+    use crate::placement_classification::load_classification_rules;
+
+    #[test]
+    pub fn load() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let classification_rules_path = temp_dir.path().join("classification_rules.csv");
+        std::fs::write(&classification_rules_path, indoc! {r#"
+            "Kind","RefDesPattern","PackagePattern"
+            "Fiducial","^FID\d+$",""
+            "TestPoint","^TP","^TestPoint_"
+        "#})?;
+
+        // when
+        let rules = load_classification_rules(&classification_rules_path.to_str().unwrap().to_string())?;
+
+        // then
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0].kind, PlacementKind::Fiducial);
+        assert_eq!(rules[0].ref_des_pattern.as_ref().map(|pattern| pattern.as_str()), Some("^FID\\d+$"));
+        assert!(rules[0].package_pattern.is_none());
+        assert_eq!(rules[1].kind, PlacementKind::TestPoint);
+        assert_eq!(rules[1].package_pattern.as_ref().map(|pattern| pattern.as_str()), Some("^TestPoint_"));
+
+        Ok(())
+    }
+}
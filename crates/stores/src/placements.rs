@@ -1,12 +1,15 @@
 use std::collections::BTreeMap;
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use tracing::trace;
 use rust_decimal::Decimal;
 use anyhow::Context;
-use planning::design::DesignVariant;
+use planning::design::{DesignName, DesignVariant};
+use planning::variant::VariantName;
 use pnp::pcb::PcbSide;
 use pnp::part::Part;
-use pnp::placement::Placement;
+use pnp::placement::{Placement, PlacementKind};
 
 /// See `EdaPlacement` for details of co-ordinate system
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
@@ -20,6 +23,9 @@ pub struct PlacementRecord {
     pub x: Decimal,
     pub y: Decimal,
     pub rotation: Decimal,
+    /// Files without the column only contain components.
+    #[serde(default)]
+    pub kind: PlacementKind,
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
@@ -57,6 +63,7 @@ impl PlacementRecord {
             x: self.x,
             y: self.y,
             rotation: self.rotation,
+            kind: self.kind,
         }
     }
 }
@@ -82,14 +89,97 @@ pub fn load_placements(placements_path: PathBuf) -> Result<Vec<Placement>, anyho
     Ok(records)
 }
 
-pub fn load_all_placements(unique_design_variants: &[DesignVariant], path: &PathBuf) -> anyhow::Result<BTreeMap<DesignVariant, Vec<Placement>>> {
+pub fn build_placements_path(design_variant: &DesignVariant, path: &Path) -> PathBuf {
+    let DesignVariant { design_name: design, variant_name: variant } = design_variant;
+
+    path.join(format!("{}_{}_placements.csv", design, variant))
+}
+
+const PLACEMENTS_FILE_SUFFIX: &str = "_placements.csv";
+
+/// The columns of a design variant placements file, used to ignore other files with the same suffix, e.g. phase
+/// placements.
+const PLACEMENTS_HEADERS: [&str; 8] = ["RefDes", "Manufacturer", "Mpn", "Place", "PcbSide", "X", "Y", "Rotation"];
+
+/// A design variant placements file found in the project directory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiscoveredPlacements {
+    pub file_name: String,
+    /// The design variants the file name could be for, design and variant names can both contain underscores, so
+    /// file names are ambiguous unless the design is known.
+    pub candidates: Vec<DesignVariant>,
+}
+
+/// Finds the design variant placements files in the directory, named as per `build_placements_path`.
+///
+/// If a design is specified, only the variants of that design are found, and each file has a single candidate.
+pub fn discover_design_variants(path: &Path, design: Option<&DesignName>) -> anyhow::Result<Vec<DiscoveredPlacements>> {
+    let mut discovered: Vec<DiscoveredPlacements> = vec![];
+
+    let entries = fs::read_dir(path)
+        .with_context(|| format!("Error reading directory. path: {:?}", path))?;
+
+    for entry in entries {
+        let entry_path = entry?.path();
+        let Some(file_name) = entry_path.file_name().map(|file_name| file_name.to_string_lossy().to_string()) else {
+            continue
+        };
+        let Some(stem) = file_name.strip_suffix(PLACEMENTS_FILE_SUFFIX) else {
+            continue
+        };
+
+        let candidates = build_design_variant_candidates(stem, design);
+        if candidates.is_empty() {
+            continue
+        }
+
+        if !has_placements_headers(&entry_path) {
+            trace!("Ignoring file, not a design variant placements file. file: {:?}", entry_path);
+            continue
+        }
+
+        discovered.push(DiscoveredPlacements { file_name, candidates });
+    }
+
+    discovered.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+
+    Ok(discovered)
+}
+
+fn build_design_variant_candidates(stem: &str, design: Option<&DesignName>) -> Vec<DesignVariant> {
+    let splits: Vec<(String, &str)> = match design {
+        Some(design) => stem.strip_prefix(&format!("{}_", design))
+            .map(|variant| vec![(design.to_string(), variant)])
+            .unwrap_or_default(),
+        None => stem.match_indices('_')
+            .map(|(index, _separator)| (stem[..index].to_string(), &stem[index + 1..]))
+            .collect(),
+    };
+
+    splits.into_iter()
+        .filter(|(design, variant)| !design.is_empty() && !variant.is_empty())
+        .map(|(design, variant)| DesignVariant {
+            design_name: DesignName::from_str(&design).unwrap(),
+            variant_name: VariantName::from_str(variant).unwrap(),
+        })
+        .collect()
+}
+
+fn has_placements_headers(placements_path: &Path) -> bool {
+    let Ok(mut csv_reader) = csv::ReaderBuilder::new().from_path(placements_path) else {
+        return false
+    };
+
+    csv_reader.headers()
+        .map(|headers| PLACEMENTS_HEADERS.iter().all(|required| headers.iter().any(|header| header.eq(*required))))
+        .unwrap_or(false)
+}
+
+pub fn load_all_placements(unique_design_variants: &[DesignVariant], path: &Path) -> anyhow::Result<BTreeMap<DesignVariant, Vec<Placement>>> {
     let mut all_placements: BTreeMap<DesignVariant, Vec<Placement>> = Default::default();
 
     for design_variant in unique_design_variants {
-        let DesignVariant { design_name: design, variant_name: variant } = design_variant;
-
-        let mut placements_path = PathBuf::from(path);
-        placements_path.push(format!("{}_{}_placements.csv", design, variant));
+        let placements_path = build_placements_path(design_variant, path);
 
         let placements = load_placements(placements_path)?;
         let _ = all_placements.insert(design_variant.clone(), placements);
@@ -97,3 +187,68 @@ pub fn load_all_placements(unique_design_variants: &[DesignVariant], path: &Path
 
     Ok(all_placements)
 }
+
+/// Builds the current revisions of the design variants, from the content of their placements files.
+pub fn build_design_revisions(unique_design_variants: &[DesignVariant], path: &Path) -> anyhow::Result<BTreeMap<DesignVariant, String>> {
+    let mut design_revisions: BTreeMap<DesignVariant, String> = Default::default();
+
+    for design_variant in unique_design_variants {
+        let placements_path = build_placements_path(design_variant, path);
+        let content = fs::read(&placements_path)
+            .with_context(|| format!("Error reading placements. file: {}", placements_path.to_str().unwrap()))?;
+
+        design_revisions.insert(design_variant.clone(), planning::design::build_design_revision(&content));
+    }
+
+    Ok(design_revisions)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::str::FromStr;
+    use assert_fs::TempDir;
+    use planning::design::{DesignName, DesignVariant};
+    use planning::variant::VariantName;
+    use crate::placements::{discover_design_variants, DiscoveredPlacements};
+
+    fn build_design_variant(design: &str, variant: &str) -> DesignVariant {
+        DesignVariant { design_name: DesignName::from_str(design).unwrap(), variant_name: VariantName::from_str(variant).unwrap() }
+    }
+
+    #[test]
+    pub fn discover() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let headers = "\"RefDes\",\"Manufacturer\",\"Mpn\",\"Place\",\"PcbSide\",\"X\",\"Y\",\"Rotation\"\n";
+        fs::write(temp_dir.path().join("design_a_variant_a_placements.csv"), headers)?;
+        fs::write(temp_dir.path().join("design_a_variant_b_placements.csv"), headers)?;
+        fs::write(temp_dir.path().join("other_x_placements.csv"), headers)?;
+
+        // and a phase placements file, which has other headers
+        fs::write(temp_dir.path().join("top_1_placements.csv"), "\"ObjectPath\",\"FeederReference\"\n")?;
+
+        // when
+        let all = discover_design_variants(temp_dir.path(), None)?;
+        let design_a = discover_design_variants(temp_dir.path(), Some(&DesignName::from_str("design_a").unwrap()))?;
+
+        // then
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0], DiscoveredPlacements {
+            file_name: "design_a_variant_a_placements.csv".to_string(),
+            candidates: vec![
+                build_design_variant("design", "a_variant_a"),
+                build_design_variant("design_a", "variant_a"),
+                build_design_variant("design_a_variant", "a"),
+            ],
+        });
+
+        // and
+        assert_eq!(design_a, vec![
+            DiscoveredPlacements { file_name: "design_a_variant_a_placements.csv".to_string(), candidates: vec![build_design_variant("design_a", "variant_a")] },
+            DiscoveredPlacements { file_name: "design_a_variant_b_placements.csv".to_string(), candidates: vec![build_design_variant("design_a", "variant_b")] },
+        ]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,274 @@
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::{info, trace};
+
+/// Environment variable that overrides the directory containing the preferences file.
+pub const CONFIG_DIR_ENV: &str = "MAKERPNP_CONFIG_DIR";
+
+const APPLICATION_DIR: &str = "makerpnp";
+const PREFERENCES_FILE: &str = "preferences.toml";
+
+#[derive(Error, Debug)]
+pub enum PreferencesError {
+    #[error("Unable to determine the config directory, set '{0:}'")]
+    UnknownConfigDirectory(&'static str),
+
+    #[error("Unable to read preferences. path: {path:?}, cause: {reason:}")]
+    UnableToRead { path: PathBuf, reason: std::io::Error },
+
+    #[error("Invalid preferences. path: {path:?}, cause: {reason:}")]
+    InvalidPreferences { path: PathBuf, reason: toml::de::Error },
+
+    #[error("Unable to write preferences. path: {path:?}, cause: {reason:}")]
+    UnableToWrite { path: PathBuf, reason: std::io::Error },
+
+    #[error("Invalid preference value, expected a whole number. key: '{key:}', value: '{value:}'")]
+    InvalidNumber { key: PreferenceKey, value: String },
+}
+
+/// User preferences, shared by all the tools, any preference that is not set uses the tool's default.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct Preferences {
+    /// Preferred language (e.g. 'en-US')
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+
+    /// Default output format (e.g. 'json')
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_format: Option<String>,
+
+    /// Default artifact directory, relative to the project directory unless absolute
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub artifact_directory: Option<PathBuf>,
+
+    /// Name of the operator
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operator: Option<String>,
+
+    /// Maximum amount of backups of each file to keep when cleaning up, see `backup::RetentionPolicy`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_max_count: Option<usize>,
+
+    /// Maximum age, in days, of backups to keep when cleaning up, the latest backup of each file is always kept
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_max_age_days: Option<u32>,
+
+    /// Default key file used to sign artifacts, see `planning::signing`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signing_key: Option<PathBuf>,
+
+    /// Default key file used to verify signed artifacts, see `planning::signing`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verifying_key: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreferenceKey {
+    Language,
+    OutputFormat,
+    ArtifactDirectory,
+    Operator,
+    BackupMaxCount,
+    BackupMaxAgeDays,
+    SigningKey,
+    VerifyingKey,
+}
+
+impl PreferenceKey {
+    pub const ALL: [PreferenceKey; 8] = [
+        PreferenceKey::Language,
+        PreferenceKey::OutputFormat,
+        PreferenceKey::ArtifactDirectory,
+        PreferenceKey::Operator,
+        PreferenceKey::BackupMaxCount,
+        PreferenceKey::BackupMaxAgeDays,
+        PreferenceKey::SigningKey,
+        PreferenceKey::VerifyingKey,
+    ];
+}
+
+impl Display for PreferenceKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PreferenceKey::Language => f.write_str("language"),
+            PreferenceKey::OutputFormat => f.write_str("output-format"),
+            PreferenceKey::ArtifactDirectory => f.write_str("artifact-directory"),
+            PreferenceKey::Operator => f.write_str("operator"),
+            PreferenceKey::BackupMaxCount => f.write_str("backup-max-count"),
+            PreferenceKey::BackupMaxAgeDays => f.write_str("backup-max-age-days"),
+            PreferenceKey::SigningKey => f.write_str("signing-key"),
+            PreferenceKey::VerifyingKey => f.write_str("verifying-key"),
+        }
+    }
+}
+
+impl Preferences {
+    pub fn get(&self, key: PreferenceKey) -> Option<String> {
+        match key {
+            PreferenceKey::Language => self.language.clone(),
+            PreferenceKey::OutputFormat => self.output_format.clone(),
+            PreferenceKey::ArtifactDirectory => self.artifact_directory.as_ref().map(|path| path.to_string_lossy().to_string()),
+            PreferenceKey::Operator => self.operator.clone(),
+            PreferenceKey::BackupMaxCount => self.backup_max_count.map(|count| count.to_string()),
+            PreferenceKey::BackupMaxAgeDays => self.backup_max_age_days.map(|days| days.to_string()),
+            PreferenceKey::SigningKey => self.signing_key.as_ref().map(|path| path.to_string_lossy().to_string()),
+            PreferenceKey::VerifyingKey => self.verifying_key.as_ref().map(|path| path.to_string_lossy().to_string()),
+        }
+    }
+
+    /// Sets, or with `None` removes, a preference.
+    pub fn set(&mut self, key: PreferenceKey, value: Option<String>) -> Result<(), PreferencesError> {
+        match key {
+            PreferenceKey::Language => self.language = value,
+            PreferenceKey::OutputFormat => self.output_format = value,
+            PreferenceKey::ArtifactDirectory => self.artifact_directory = value.map(PathBuf::from),
+            PreferenceKey::Operator => self.operator = value,
+            PreferenceKey::BackupMaxCount => self.backup_max_count = parse_number(key, value)?,
+            PreferenceKey::BackupMaxAgeDays => self.backup_max_age_days = parse_number(key, value)?,
+            PreferenceKey::SigningKey => self.signing_key = value.map(PathBuf::from),
+            PreferenceKey::VerifyingKey => self.verifying_key = value.map(PathBuf::from),
+        }
+
+        Ok(())
+    }
+
+    /// Resolves the artifact directory using the project directory, the project directory is used if the preference
+    /// is not set.
+    pub fn resolve_artifact_directory(&self, project_dir: &Path) -> PathBuf {
+        match &self.artifact_directory {
+            Some(artifact_directory) => project_dir.join(artifact_directory),
+            None => project_dir.to_path_buf(),
+        }
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(key: PreferenceKey, value: Option<String>) -> Result<Option<T>, PreferencesError> {
+    value.map(|value| value.trim().parse::<T>()
+        .map_err(|_error| PreferencesError::InvalidNumber { key, value: value.clone() }))
+        .transpose()
+}
+
+/// Returns the path of the preferences file, in the user's config directory unless overridden by `CONFIG_DIR_ENV`.
+pub fn build_preferences_path() -> Result<PathBuf, PreferencesError> {
+    let config_dir = match std::env::var_os(CONFIG_DIR_ENV) {
+        Some(config_dir) => PathBuf::from(config_dir),
+        None => dirs::config_dir()
+            .ok_or(PreferencesError::UnknownConfigDirectory(CONFIG_DIR_ENV))?
+            .join(APPLICATION_DIR),
+    };
+
+    Ok(config_dir.join(PREFERENCES_FILE))
+}
+
+/// Loads the preferences, the default preferences are returned if the file does not exist.
+pub fn load(path: &Path) -> Result<Preferences, PreferencesError> {
+    if !path.exists() {
+        trace!("No preferences file. path: {:?}", path);
+        return Ok(Preferences::default())
+    }
+
+    let content = fs::read_to_string(path)
+        .map_err(|reason| PreferencesError::UnableToRead { path: path.to_path_buf(), reason })?;
+
+    toml::from_str(&content)
+        .map_err(|reason| PreferencesError::InvalidPreferences { path: path.to_path_buf(), reason })
+}
+
+pub fn save(path: &Path, preferences: &Preferences) -> Result<(), PreferencesError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|reason| PreferencesError::UnableToWrite { path: path.to_path_buf(), reason })?;
+    }
+
+    // serializing a struct of optional strings and numbers cannot fail
+    let content = toml::to_string_pretty(preferences).unwrap();
+
+    fs::write(path, content)
+        .map_err(|reason| PreferencesError::UnableToWrite { path: path.to_path_buf(), reason })?;
+
+    info!("Saved preferences. path: {:?}", path);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+    use assert_fs::TempDir;
+    use indoc::indoc;
+    use crate::preferences::{load, save, PreferenceKey, Preferences, PreferencesError};
+
+    #[test]
+    pub fn save_and_load() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("makerpnp").join("preferences.toml");
+
+        // and
+        let mut preferences = Preferences::default();
+        preferences.set(PreferenceKey::Operator, Some("Operator 1".to_string()))?;
+        preferences.set(PreferenceKey::ArtifactDirectory, Some("artifacts".to_string()))?;
+        preferences.set(PreferenceKey::BackupMaxCount, Some("5".to_string()))?;
+
+        // when
+        save(&path, &preferences)?;
+
+        // then
+        assert_eq!(std::fs::read_to_string(&path)?, indoc! {r#"
+            artifact-directory = "artifacts"
+            operator = "Operator 1"
+            backup-max-count = 5
+        "#});
+
+        // and
+        assert_eq!(load(&path)?, preferences);
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn load_missing_file() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+
+        // when
+        let preferences = load(&temp_dir.path().join("preferences.toml"))?;
+
+        // then
+        assert_eq!(preferences, Preferences::default());
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn resolve_artifact_directory() {
+        // given
+        let mut preferences = Preferences::default();
+
+        // expect
+        assert_eq!(preferences.resolve_artifact_directory(Path::new("project")), PathBuf::from("project"));
+
+        // when
+        preferences.set(PreferenceKey::ArtifactDirectory, Some("artifacts".to_string())).unwrap();
+
+        // then
+        assert_eq!(preferences.resolve_artifact_directory(Path::new("project")), PathBuf::from("project/artifacts"));
+    }
+
+    #[test]
+    pub fn invalid_number() {
+        // given
+        let mut preferences = Preferences::default();
+
+        // when
+        let result = preferences.set(PreferenceKey::BackupMaxAgeDays, Some("a week".to_string()));
+
+        // then
+        assert!(matches!(result, Err(PreferencesError::InvalidNumber { key: PreferenceKey::BackupMaxAgeDays, .. })));
+        assert_eq!(preferences.get(PreferenceKey::BackupMaxAgeDays), None);
+    }
+}
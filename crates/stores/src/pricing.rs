@@ -0,0 +1,139 @@
+use tracing::Level;
+use std::collections::btree_map::Entry;
+use std::path::Path;
+use anyhow::{Context, Error};
+use rust_decimal::Decimal;
+use thiserror::Error;
+use tracing::{info, trace};
+use planning::pricing::{PartPrice, PriceBreak, PriceList};
+use pnp::part::Part;
+
+/// A price list record, one record for each price break of a part, e.g.
+///
+/// ```csv
+/// "Manufacturer","Mpn","Currency","Quantity","UnitPrice"
+/// "RES_MFR1","RES1","EUR","1","0.10"
+/// "RES_MFR1","RES1","EUR","100","0.02"
+/// ```
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all(deserialize = "PascalCase"))]
+pub struct PriceRecord {
+    manufacturer: String,
+    mpn: String,
+    currency: String,
+    quantity: u32,
+    unit_price: Decimal,
+}
+
+#[derive(Error, Debug)]
+pub enum PriceListError {
+    #[error("A part has prices in more than one currency. part: {part}, currencies: [{currency}, {other_currency}]")]
+    MixedCurrencies { part: Part, currency: String, other_currency: String },
+
+    #[error("Duplicate price break. part: {part}, quantity: {quantity}")]
+    DuplicatePriceBreak { part: Part, quantity: u32 },
+}
+
+#[tracing::instrument(level = Level::DEBUG)]
+pub fn load_price_list(price_list_path: &Path) -> Result<PriceList, Error> {
+    let mut csv_reader = csv::ReaderBuilder::new()
+        .from_path(price_list_path)
+        .with_context(|| format!("Error reading price list. file: {:?}", price_list_path))?;
+
+    let mut price_list = PriceList::default();
+
+    for result in csv_reader.deserialize() {
+        let record: PriceRecord = result
+            .with_context(|| "Deserializing price record".to_string())?;
+
+        trace!("{:?}", record);
+
+        let part = Part::new(record.manufacturer.trim().to_string(), record.mpn.trim().to_string());
+        let currency = record.currency.trim().to_uppercase();
+        let price_break = PriceBreak { quantity: record.quantity, unit_price: record.unit_price };
+
+        match price_list.prices.entry(part) {
+            Entry::Vacant(entry) => {
+                entry.insert(PartPrice { currency, price_breaks: vec![price_break] });
+            },
+            Entry::Occupied(mut entry) => {
+                let part_price = entry.get();
+                if part_price.currency.ne(&currency) {
+                    return Err(PriceListError::MixedCurrencies { part: entry.key().clone(), currency: part_price.currency.clone(), other_currency: currency }.into())
+                }
+                if part_price.price_breaks.iter().any(|existing| existing.quantity == price_break.quantity) {
+                    return Err(PriceListError::DuplicatePriceBreak { part: entry.key().clone(), quantity: price_break.quantity }.into())
+                }
+                entry.get_mut().price_breaks.push(price_break);
+            },
+        }
+    }
+
+    for part_price in price_list.prices.values_mut() {
+        part_price.price_breaks.sort_by_key(|price_break| price_break.quantity);
+    }
+
+    info!("Loaded price list. file: {:?}, parts: {}", price_list_path, price_list.prices.len());
+
+    Ok(price_list)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::TempDir;
+    use indoc::indoc;
+    use rust_decimal_macros::dec;
+    use planning::pricing::PriceBreak;
+    use pnp::part::Part;
+    use crate::pricing::{load_price_list, PriceListError};
+
+    #[test]
+    pub fn load() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let price_list_path = temp_dir.path().join("prices.csv");
+        std::fs::write(&price_list_path, indoc! {r#"
+            "Manufacturer","Mpn","Currency","Quantity","UnitPrice"
+            "RES_MFR1","RES1","eur","100","0.02"
+            "RES_MFR1","RES1","EUR","1","0.10"
+            "CAP_MFR1","CAP1","USD","1","0.25"
+        "#})?;
+
+        // when
+        let price_list = load_price_list(&price_list_path)?;
+
+        // then
+        let part_price = &price_list.prices[&Part::new("RES_MFR1".to_string(), "RES1".to_string())];
+        assert_eq!(part_price.currency, "EUR");
+        assert_eq!(part_price.price_breaks, vec![
+            PriceBreak { quantity: 1, unit_price: dec!(0.10) },
+            PriceBreak { quantity: 100, unit_price: dec!(0.02) },
+        ]);
+
+        // and
+        assert_eq!(price_list.prices.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn mixed_currencies() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let price_list_path = temp_dir.path().join("prices.csv");
+        std::fs::write(&price_list_path, indoc! {r#"
+            "Manufacturer","Mpn","Currency","Quantity","UnitPrice"
+            "RES_MFR1","RES1","EUR","1","0.10"
+            "RES_MFR1","RES1","USD","100","0.02"
+        "#})?;
+
+        // when
+        let result = load_price_list(&price_list_path);
+
+        // then
+        let error = result.unwrap_err().downcast::<PriceListError>()?;
+        assert!(matches!(error, PriceListError::MixedCurrencies { .. }));
+
+        Ok(())
+    }
+}
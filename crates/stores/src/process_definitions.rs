@@ -0,0 +1,131 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use tracing::info;
+use planning::process::{Process, ProcessDefinition, ProcessDefinitionError};
+
+pub const PROCESS_DEFINITIONS_JSON_FILE: &str = "processes.json";
+pub const PROCESS_DEFINITIONS_TOML_FILE: &str = "processes.toml";
+
+/// The process definitions file, e.g.
+///
+/// ```toml
+/// [[processes]]
+/// name = "selective"
+/// operations = [
+///     { kind = "LoadPcbs" },
+///     { kind = "SelectiveSolder", tracks_placements = true },
+/// ]
+/// ```
+///
+/// Operations that are not built-in operations (e.g. 'AutomatedPnp') are custom operations.
+#[derive(Debug, Default, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ProcessDefinitions {
+    pub processes: Vec<ProcessDefinition>,
+}
+
+#[derive(Error, Debug)]
+pub enum ProcessDefinitionsError {
+    #[error("Only one process definitions file is allowed. paths: {paths:?}")]
+    MultipleFiles { paths: Vec<PathBuf> },
+
+    #[error("Unable to read process definitions. path: {path:?}, cause: {reason:}")]
+    UnableToRead { path: PathBuf, reason: std::io::Error },
+
+    #[error("Invalid process definitions. path: {path:?}, cause: {reason:}")]
+    InvalidJson { path: PathBuf, reason: serde_json::Error },
+
+    #[error("Invalid process definitions. path: {path:?}, cause: {reason:}")]
+    InvalidToml { path: PathBuf, reason: toml::de::Error },
+
+    #[error("Invalid process definition. path: {path:?}, cause: {reason:}")]
+    InvalidDefinition { path: PathBuf, reason: ProcessDefinitionError },
+}
+
+/// The processes defined in the project directory, in 'processes.json' or 'processes.toml', empty if there is no
+/// process definitions file.
+pub fn load_process_definitions(project_dir: &Path) -> Result<Vec<Process>, ProcessDefinitionsError> {
+    let paths: Vec<PathBuf> = [PROCESS_DEFINITIONS_JSON_FILE, PROCESS_DEFINITIONS_TOML_FILE].iter()
+        .map(|file_name| project_dir.join(file_name))
+        .filter(|path| path.exists())
+        .collect();
+
+    let path = match paths.as_slice() {
+        [] => return Ok(vec![]),
+        [path] => path,
+        _ => return Err(ProcessDefinitionsError::MultipleFiles { paths }),
+    };
+
+    let content = fs::read_to_string(path)
+        .map_err(|reason| ProcessDefinitionsError::UnableToRead { path: path.clone(), reason })?;
+
+    let definitions: ProcessDefinitions = match path.extension().and_then(|extension| extension.to_str()) {
+        Some("toml") => toml::from_str(&content)
+            .map_err(|reason| ProcessDefinitionsError::InvalidToml { path: path.clone(), reason })?,
+        _ => serde_json::from_str(&content)
+            .map_err(|reason| ProcessDefinitionsError::InvalidJson { path: path.clone(), reason })?,
+    };
+
+    let processes = planning::process::build_processes(&definitions.processes)
+        .map_err(|reason| ProcessDefinitionsError::InvalidDefinition { path: path.clone(), reason })?;
+
+    info!("Loaded process definitions. path: {:?}, processes: {}", path, processes.len());
+
+    Ok(processes)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::str::FromStr;
+    use indoc::indoc;
+    use assert_fs::TempDir;
+    use planning::process::{ProcessDefinition, ProcessOperationKind};
+    use crate::process_definitions::{load_process_definitions, ProcessDefinitions, ProcessDefinitionsError, PROCESS_DEFINITIONS_JSON_FILE, PROCESS_DEFINITIONS_TOML_FILE};
+
+    #[test]
+    pub fn load_toml_and_json() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+
+        // expect
+        assert!(load_process_definitions(temp_dir.path())?.is_empty());
+
+        // given
+        fs::write(temp_dir.path().join(PROCESS_DEFINITIONS_TOML_FILE), indoc! {r#"
+            [[processes]]
+            name = "selective"
+            operations = [
+                { kind = "LoadPcbs" },
+                { kind = "SelectiveSolder", tracks_placements = true },
+            ]
+        "#})?;
+
+        // when
+        let processes = load_process_definitions(temp_dir.path())?;
+
+        // then
+        assert_eq!(processes.len(), 1);
+        assert_eq!(processes[0].operations, vec![ProcessOperationKind::LoadPcbs, ProcessOperationKind::from_str("SelectiveSolder")?]);
+        assert!(processes[0].tracks_placements(&ProcessOperationKind::from_str("SelectiveSolder")?));
+
+        // given the same definitions, as JSON
+        let definitions = ProcessDefinitions { processes: processes.iter().map(ProcessDefinition::from_process).collect() };
+        fs::remove_file(temp_dir.path().join(PROCESS_DEFINITIONS_TOML_FILE))?;
+        fs::write(temp_dir.path().join(PROCESS_DEFINITIONS_JSON_FILE), serde_json::to_string(&definitions)?)?;
+
+        // expect
+        assert_eq!(load_process_definitions(temp_dir.path())?, processes);
+
+        // and the TOML representation is equivalent
+        assert_eq!(toml::from_str::<ProcessDefinitions>(&toml::to_string(&definitions)?)?, definitions);
+
+        // given
+        fs::write(temp_dir.path().join(PROCESS_DEFINITIONS_TOML_FILE), "")?;
+
+        // expect
+        assert!(matches!(load_process_definitions(temp_dir.path()), Err(ProcessDefinitionsError::MultipleFiles { .. })));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,110 @@
+use tracing::Level;
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::path::Path;
+use anyhow::{Context, Error};
+use rust_decimal::Decimal;
+use thiserror::Error;
+use tracing::{info, trace};
+use pnp::part::Part;
+
+/// A rotation offset record, one record for each part, e.g.
+///
+/// ```csv
+/// "Manufacturer","Mpn","Offset"
+/// "DIODE_MFR1","DIODE1","180"
+/// ```
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all(deserialize = "PascalCase"))]
+pub struct RotationOffsetRecord {
+    manufacturer: String,
+    mpn: String,
+    offset: Decimal,
+}
+
+#[derive(Error, Debug)]
+pub enum RotationOffsetError {
+    #[error("Duplicate rotation offset. part: {part}")]
+    DuplicateOffset { part: Part },
+}
+
+/// Anti-clockwise rotation offsets, by part.
+#[tracing::instrument(level = Level::DEBUG)]
+pub fn load_rotation_offsets(rotation_offsets_path: &Path) -> Result<BTreeMap<Part, Decimal>, Error> {
+    let mut csv_reader = csv::ReaderBuilder::new()
+        .from_path(rotation_offsets_path)
+        .with_context(|| format!("Error reading rotation offsets. file: {:?}", rotation_offsets_path))?;
+
+    let mut offsets = BTreeMap::new();
+
+    for result in csv_reader.deserialize() {
+        let record: RotationOffsetRecord = result
+            .with_context(|| "Deserializing rotation offset record".to_string())?;
+
+        trace!("{:?}", record);
+
+        let part = Part::new(record.manufacturer.trim().to_string(), record.mpn.trim().to_string());
+
+        match offsets.entry(part) {
+            Entry::Vacant(entry) => {
+                entry.insert(record.offset);
+            },
+            Entry::Occupied(entry) => return Err(RotationOffsetError::DuplicateOffset { part: entry.key().clone() }.into()),
+        }
+    }
+
+    info!("Loaded rotation offsets. file: {:?}, parts: {}", rotation_offsets_path, offsets.len());
+
+    Ok(offsets)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::TempDir;
+    use indoc::indoc;
+    use rust_decimal_macros::dec;
+    use pnp::part::Part;
+    use crate::rotation_offsets::{load_rotation_offsets, RotationOffsetError};
+
+    #[test]
+    pub fn load() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let rotation_offsets_path = temp_dir.path().join("rotation_offsets.csv");
+        std::fs::write(&rotation_offsets_path, indoc! {r#"
+            "Manufacturer","Mpn","Offset"
+            "DIODE_MFR1","DIODE1","180"
+            "IC_MFR1","IC1","-90"
+        "#})?;
+
+        // when
+        let offsets = load_rotation_offsets(&rotation_offsets_path)?;
+
+        // then
+        assert_eq!(offsets.get(&Part::new("IC_MFR1".to_string(), "IC1".to_string())), Some(&dec!(-90)));
+        assert_eq!(offsets.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn duplicate_offset() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let rotation_offsets_path = temp_dir.path().join("rotation_offsets.csv");
+        std::fs::write(&rotation_offsets_path, indoc! {r#"
+            "Manufacturer","Mpn","Offset"
+            "DIODE_MFR1","DIODE1","180"
+            "DIODE_MFR1","DIODE1","90"
+        "#})?;
+
+        // when
+        let result = load_rotation_offsets(&rotation_offsets_path);
+
+        // then
+        let error = result.unwrap_err().downcast::<RotationOffsetError>()?;
+        assert!(matches!(error, RotationOffsetError::DuplicateOffset { .. }));
+
+        Ok(())
+    }
+}
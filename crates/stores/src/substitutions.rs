@@ -2,11 +2,12 @@ use tracing::Level;
 use std::path::PathBuf;
 use anyhow::{Context, Error};
 use tracing::trace;
+use eda::plugin::EdaToolRegistry;
 use eda::substitution::EdaSubstitutionRule;
 use crate::csv::SubstitutionRecord;
 
 #[tracing::instrument(level = Level::DEBUG)]
-pub fn load_eda_substitutions(substitutions_source: &String) -> Result<Vec<EdaSubstitutionRule>, Error> {
+pub fn load_eda_substitutions(substitutions_source: &String, registry: &EdaToolRegistry) -> Result<Vec<EdaSubstitutionRule>, Error> {
     let substitutions_path_buf = PathBuf::from(substitutions_source);
     let substitutions_path = substitutions_path_buf.as_path();
     let mut csv_reader = csv::ReaderBuilder::new().from_path(substitutions_path)
@@ -21,7 +22,7 @@ pub fn load_eda_substitutions(substitutions_source: &String) -> Result<Vec<EdaSu
 
         trace!("{:?}", record);
 
-        let eda_substitution = record.build_eda_substitution()
+        let eda_substitution = record.build_eda_substitution(registry)
             .with_context(|| format!("Building substitution from record. record: {:?}", record))?;
 
         eda_substitutions.push(eda_substitution);
@@ -36,6 +37,7 @@ pub mod csv_loading_tests {
     use csv::QuoteStyle;
     use regex::Regex;
     use criteria::{ExactMatchCriterion, RegexMatchCriterion};
+    use eda::plugin::EdaToolRegistry;
     use eda::substitution::{EdaSubstitutionRule, EdaSubstitutionRuleTransformItem};
     use crate::substitutions::load_eda_substitutions;
     use crate::substitutions::test::TestEdaSubstitutionRecord;
@@ -98,7 +100,7 @@ pub mod csv_loading_tests {
         println!("{csv_content:}");
 
         // when
-        let result = load_eda_substitutions(&test_eda_substitutions_source)?;
+        let result = load_eda_substitutions(&test_eda_substitutions_source, &EdaToolRegistry::default())?;
 
         // then
         assert_eq!(result, expected_result);
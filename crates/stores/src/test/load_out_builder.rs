@@ -1,11 +1,15 @@
 use csv::QuoteStyle;
 
-#[derive(Debug, serde::Serialize)]
+#[derive(Debug, Default, serde::Serialize)]
 #[serde(rename_all(serialize = "PascalCase"))]
 pub struct TestLoadOutRecord {
     pub reference: String,
     pub manufacturer: String,
     pub mpn: String,
+    pub quantity: Option<u32>,
+    pub reel: Option<String>,
+    pub alternates: Option<String>,
+    pub loaded_alternate: Option<String>,
 }
 
 #[derive(Default)]
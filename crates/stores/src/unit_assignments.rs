@@ -0,0 +1,175 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::str::FromStr;
+use anyhow::Context;
+use csv::QuoteStyle;
+use thiserror::Error;
+use tracing::{info, trace, warn};
+use planning::design::{DesignName, DesignVariant};
+use planning::variant::VariantName;
+use pnp::object_path::ObjectPath;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct UnitAssignmentRecord {
+    pub unit: String,
+    pub design: String,
+    pub variant: String,
+}
+
+#[derive(Error, Debug)]
+pub enum UnitAssignmentsError {
+    #[error("Invalid unit assignments, see the log for details. file: {file}, count: {count}")]
+    InvalidRecords { file: String, count: usize },
+}
+
+/// Writes the unit assignments, in unit order, so that they can be edited in a spreadsheet and imported again.
+pub fn export(path: &Path, unit_assignments: &BTreeMap<ObjectPath, DesignVariant>) -> anyhow::Result<()> {
+    let mut writer = csv::WriterBuilder::new()
+        .quote_style(QuoteStyle::Always)
+        .from_path(path)
+        .with_context(|| format!("Error writing unit assignments. file: {:?}", path))?;
+
+    for (unit_path, design_variant) in unit_assignments.iter() {
+        writer.serialize(UnitAssignmentRecord {
+            unit: unit_path.to_string(),
+            design: design_variant.design_name.to_string(),
+            variant: design_variant.variant_name.to_string(),
+        })?;
+    }
+
+    writer.flush()?;
+
+    info!("Exported unit assignments. file: {:?}, count: {}", path, unit_assignments.len());
+
+    Ok(())
+}
+
+/// Reads the unit assignments, all the records are validated before any are returned.
+///
+/// Each invalid record is logged, with its line number, so that all the problems can be fixed at once.
+pub fn import(path: &Path) -> anyhow::Result<BTreeMap<ObjectPath, DesignVariant>> {
+    let mut csv_reader = csv::ReaderBuilder::new()
+        .from_path(path)
+        .with_context(|| format!("Error reading unit assignments. file: {:?}", path))?;
+
+    let mut unit_assignments: BTreeMap<ObjectPath, DesignVariant> = BTreeMap::new();
+    let mut error_count = 0;
+
+    for (index, result) in csv_reader.deserialize().enumerate() {
+        // the header is the first line
+        let line = index + 2;
+
+        let record: UnitAssignmentRecord = match result {
+            Ok(record) => record,
+            Err(error) => {
+                warn!("Invalid unit assignment. line: {}, error: {}", line, error);
+                error_count += 1;
+                continue
+            }
+        };
+        trace!("{:?}", record);
+
+        match build_unit_assignment(&record) {
+            Ok((unit_path, design_variant)) => {
+                if unit_assignments.insert(unit_path.clone(), design_variant).is_some() {
+                    warn!("Duplicate unit assignment. line: {}, unit: '{}'", line, unit_path);
+                    error_count += 1;
+                }
+            },
+            Err(reason) => {
+                warn!("Invalid unit assignment. line: {}, error: {}", line, reason);
+                error_count += 1;
+            }
+        }
+    }
+
+    if error_count > 0 {
+        return Err(UnitAssignmentsError::InvalidRecords { file: path.to_string_lossy().to_string(), count: error_count }.into())
+    }
+
+    Ok(unit_assignments)
+}
+
+fn build_unit_assignment(record: &UnitAssignmentRecord) -> anyhow::Result<(ObjectPath, DesignVariant)> {
+    let unit_path = ObjectPath::from_str(record.unit.trim())?;
+    if unit_path.pcb_unit().ne(&unit_path) {
+        anyhow::bail!("Not a PCB unit path. unit: '{}'", unit_path)
+    }
+
+    if record.design.trim().is_empty() || record.variant.trim().is_empty() {
+        anyhow::bail!("Design and variant are required")
+    }
+
+    let design_variant = DesignVariant {
+        design_name: DesignName::from_str(record.design.trim())?,
+        variant_name: VariantName::from_str(record.variant.trim())?,
+    };
+
+    Ok((unit_path, design_variant))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::fs;
+    use std::str::FromStr;
+    use assert_fs::TempDir;
+    use indoc::indoc;
+    use planning::design::{DesignName, DesignVariant};
+    use planning::variant::VariantName;
+    use pnp::object_path::ObjectPath;
+    use crate::unit_assignments::{export, import, UnitAssignmentsError};
+
+    #[test]
+    pub fn export_and_import() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("units.csv");
+
+        // and
+        let unit_assignments = BTreeMap::from([
+            (ObjectPath::from_str("panel=1::unit=2")?, DesignVariant { design_name: DesignName::from_str("design_a")?, variant_name: VariantName::from_str("variant_b")? }),
+            (ObjectPath::from_str("panel=1::unit=1")?, DesignVariant { design_name: DesignName::from_str("design_a")?, variant_name: VariantName::from_str("variant_a")? }),
+        ]);
+
+        // when
+        export(&path, &unit_assignments)?;
+
+        // then
+        assert_eq!(fs::read_to_string(&path)?, indoc! {r#"
+            "Unit","Design","Variant"
+            "panel=1::unit=1","design_a","variant_a"
+            "panel=1::unit=2","design_a","variant_b"
+        "#});
+
+        // and
+        assert_eq!(import(&path)?, unit_assignments);
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn import_invalid_records() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("units.csv");
+        fs::write(&path, indoc! {r#"
+            "Unit","Design","Variant"
+            "panel=1::unit=1","design_a","variant_a"
+            "panel=1::unit=1","design_a","variant_b"
+            "panel=1::unit=2::ref_des=R1","design_a","variant_a"
+            "panel=1::unit=3","",""
+            "panel=x::unit=4","design_a","variant_a"
+        "#})?;
+
+        // when
+        let result = import(&path);
+
+        // then
+        let error = result.unwrap_err().downcast::<UnitAssignmentsError>()?;
+        assert!(matches!(error, UnitAssignmentsError::InvalidRecords { count: 4, .. }));
+
+        Ok(())
+    }
+}
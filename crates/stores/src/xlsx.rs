@@ -0,0 +1,95 @@
+//! Reading of '.xlsx' spreadsheets, for users that only have spreadsheet exports of their placements, parts or
+//! part mappings.
+//!
+//! The first sheet of the workbook is converted to CSV content, so that the same records, column names and EDA tool
+//! detection are used as for CSV files.  The first row must contain the column names.
+//!
+//! Requires the 'xlsx' feature.
+
+use std::fs;
+use std::path::Path;
+use anyhow::{Context, Error};
+
+/// Returns true if the file should be read as a spreadsheet, based on the extension.
+pub fn is_xlsx_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("xlsx"))
+}
+
+/// Reads the content of a CSV file, or the first sheet of a '.xlsx' file, as CSV content.
+pub fn read_csv_content(path: &Path) -> Result<String, Error> {
+    if is_xlsx_path(path) {
+        return read_first_sheet_as_csv(path)
+    }
+
+    fs::read_to_string(path)
+        .with_context(|| format!("Error reading file. file: {}", path.to_str().unwrap()))
+}
+
+#[cfg(feature = "xlsx")]
+fn read_first_sheet_as_csv(path: &Path) -> Result<String, Error> {
+    use calamine::{open_workbook, Reader, Xlsx};
+    use tracing::info;
+
+    let mut workbook: Xlsx<_> = open_workbook(path)
+        .with_context(|| format!("Error opening spreadsheet. file: {}", path.to_str().unwrap()))?;
+
+    let range = workbook.worksheet_range_at(0)
+        .with_context(|| format!("Spreadsheet has no sheets. file: {}", path.to_str().unwrap()))?
+        .with_context(|| format!("Error reading spreadsheet. file: {}", path.to_str().unwrap()))?;
+
+    let mut writer = csv::Writer::from_writer(vec![]);
+
+    for row in range.rows() {
+        // integers and floats are formatted without a trailing '.0', e.g. '1' instead of '1.0'
+        writer.write_record(row.iter().map(|cell| cell.to_string()))?;
+    }
+
+    let content = String::from_utf8(writer.into_inner()?)?;
+
+    info!("Read spreadsheet. file: {}, rows: {}", path.to_str().unwrap(), range.height());
+
+    Ok(content)
+}
+
+#[cfg(not(feature = "xlsx"))]
+fn read_first_sheet_as_csv(path: &Path) -> Result<String, Error> {
+    anyhow::bail!("Reading '.xlsx' files requires the 'xlsx' feature, convert the file to CSV or rebuild with the feature enabled. file: {}", path.to_str().unwrap())
+}
+
+#[cfg(all(test, feature = "xlsx"))]
+mod tests {
+    use assert_fs::TempDir;
+    use indoc::indoc;
+    use rust_xlsxwriter::Workbook;
+    use crate::xlsx::read_csv_content;
+
+    #[test]
+    pub fn read_first_sheet() -> anyhow::Result<()> {
+        // given
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("placements.XLSX");
+
+        // and
+        let mut workbook = Workbook::new();
+        let sheet = workbook.add_worksheet();
+        sheet.write_row(0, 0, ["RefDes", "Name", "Value", "Side", "X", "Y", "Rotation"])?;
+        sheet.write_row(1, 0, ["R1", "RES_0402", "330R, 1%", "Top"])?;
+        sheet.write_row(1, 4, [12.5, 7.0, 90.0])?;
+        // a second sheet, which is ignored
+        workbook.add_worksheet().write(0, 0, "Notes")?;
+        workbook.save(&path)?;
+
+        // when
+        let content = read_csv_content(&path)?;
+
+        // then
+        assert_eq!(content, indoc! {r#"
+            RefDes,Name,Value,Side,X,Y,Rotation
+            R1,RES_0402,"330R, 1%",Top,12.5,7,90
+        "#});
+
+        Ok(())
+    }
+}
@@ -1,3 +1,4 @@
+use std::cmp::Ordering;
 use std::fmt::{Display, Formatter};
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq, Eq, PartialOrd, Ord)]
@@ -13,4 +14,80 @@ impl Display for SortOrder {
             Self::Desc=> write!(f, "Desc"),
         }
     }
-}
\ No newline at end of file
+}
+
+/// Compares strings using a natural order, so that embedded numbers are compared by value,
+/// e.g. 'FEEDER_2' < 'FEEDER_10'.
+///
+/// Numbers that are equal in value but differ in leading zeros (e.g. '01' and '1') are ordered
+/// using the plain string order, so that the ordering is total and deterministic.
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut chunks_a = NaturalChunks(a);
+    let mut chunks_b = NaturalChunks(b);
+
+    loop {
+        let ordering = match (chunks_a.next(), chunks_b.next()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(chunk_a), Some(chunk_b)) => {
+                let is_digits_a = chunk_a.starts_with(|c: char| c.is_ascii_digit());
+                let is_digits_b = chunk_b.starts_with(|c: char| c.is_ascii_digit());
+
+                match (is_digits_a, is_digits_b) {
+                    (true, true) => {
+                        let digits_a = chunk_a.trim_start_matches('0');
+                        let digits_b = chunk_b.trim_start_matches('0');
+                        digits_a.len().cmp(&digits_b.len())
+                            .then_with(|| digits_a.cmp(digits_b))
+                    },
+                    _ => chunk_a.cmp(chunk_b),
+                }
+            },
+        };
+
+        if ordering != Ordering::Equal {
+            return ordering
+        }
+    }
+}
+
+/// Splits a string into chunks of ascii digits and non-digits.
+struct NaturalChunks<'a>(&'a str);
+
+impl<'a> Iterator for NaturalChunks<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let first = self.0.chars().next()?;
+        let is_digit = first.is_ascii_digit();
+
+        let end = self.0.find(|c: char| c.is_ascii_digit() != is_digit).unwrap_or(self.0.len());
+        let (chunk, remainder) = self.0.split_at(end);
+        self.0 = remainder;
+
+        Some(chunk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+    use rstest::rstest;
+    use crate::sorting::natural_cmp;
+
+    #[rstest]
+    #[case("FEEDER_1", "FEEDER_2", Ordering::Less)]
+    #[case("FEEDER_2", "FEEDER_10", Ordering::Less)]
+    #[case("FEEDER_10", "FEEDER_2", Ordering::Greater)]
+    #[case("FEEDER_10", "FEEDER_10", Ordering::Equal)]
+    #[case("FEEDER_1", "FEEDER_1A", Ordering::Less)]
+    #[case("A10", "B2", Ordering::Less)]
+    #[case("FEEDER_01", "FEEDER_1", Ordering::Less)]
+    #[case("", "FEEDER_1", Ordering::Less)]
+    #[case("10", "9A", Ordering::Greater)]
+    fn natural_order(#[case] a: &str, #[case] b: &str, #[case] expected_result: Ordering) {
+        // expect
+        assert_eq!(natural_cmp(a, b), expected_result);
+    }
+}
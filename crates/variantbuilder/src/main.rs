@@ -8,12 +8,12 @@ use thiserror::Error;
 use tracing::{error, info, Level, trace};
 use assembly::AssemblyVariantProcessor;
 use assembly::assembly_variant::AssemblyVariant;
-use cli;
-use cli::args::EdaToolArg;
-use eda::placement::{EdaPlacement, EdaPlacementField};
+use cli::args::{DecimalSeparatorArg, EdaToolArg};
+use eda::placement::{DecimalSeparator, EdaPlacement, EdaPlacementField};
 use eda::substitution::{EdaSubstitutionResult, EdaSubstitutionRule, EdaSubstitutor};
-use eda::EdaTool;
-use stores::{assembly_rules, eda_placements, load_out, part_mappings, parts, substitutions};
+use eda::classification;
+use eda::plugin::{EdaToolPlugin, EdaToolRegistry};
+use stores::{assembly_rules, eda_placements, load_out, part_mappings, parts, placement_classification, substitutions};
 use stores::placements::PlacementRecord;
 use stores::load_out::LoadOutSource;
 use part_mapper::{PartMapper, PartMapperError, PartMappingError, PartMappingResult, PlacementPartMappingResult};
@@ -66,18 +66,22 @@ impl AssemblyVariantArgs {
 enum Command {
     /// Build variant
     Build {
-        /// EDA tool
+        /// EDA tool, or fab-house dialect, detected from the placements columns if not specified
         #[arg(long)]
-        eda: EdaToolArg,
+        eda: Option<EdaToolArg>,
 
         /// Load-out source
         #[arg(long, value_name = "SOURCE")]
         load_out: Option<LoadOutSource>,
 
-        /// Placements source
+        /// Placements source, a placements file or a KiCad position (.pos) or board (.kicad_pcb) file
         #[arg(long, value_name = "SOURCE")]
         placements: String,
 
+        /// Decimal separator of the placements coordinates and rotations
+        #[arg(long, default_value = "auto")]
+        decimal_separator: DecimalSeparatorArg,
+
         /// Parts source
         #[arg(long, value_name = "SOURCE")]
         parts: String,
@@ -98,6 +102,10 @@ enum Command {
         #[arg(long, value_name = "SOURCE")]
         assembly_rules: Option<String>,
 
+        /// Placement classification rules source, fiducials and test points are not placed and are not mapped to parts
+        #[arg(long, value_name = "SOURCE")]
+        classification_rules: Option<String>,
+
         /// Output CSV file
         #[arg(long, value_name = "FILE")]
         output: String,
@@ -121,46 +129,56 @@ fn main() -> anyhow::Result<()>{
         Command::Build {
             eda,
             placements,
+            decimal_separator,
             assembly_variant_args,
             parts,
             part_mappings,
             substitutions,
             load_out,
             assembly_rules,
+            classification_rules,
             output,
             ref_des_disable_list,
         } => {
-            let eda_tool= eda.build();
+            let registry = EdaToolRegistry::default();
+            let eda_tool = match eda {
+                Some(eda) => registry.find(eda.name()).unwrap(),
+                None => eda_placements::detect_eda_tool(&registry, placements)?,
+            };
             let assembly_variant = assembly_variant_args.as_ref().map_or_else(|| Ok(AssemblyVariant::default()), | args | {
                 args.build_assembly_variant()
             })?;
 
-            build_assembly_variant(eda_tool, placements, assembly_variant, parts, part_mappings, substitutions, load_out, assembly_rules, output, ref_des_disable_list)?;
+            build_assembly_variant(&registry, eda_tool, placements, DecimalSeparator::from(decimal_separator.clone()), assembly_variant, parts, part_mappings, substitutions, load_out, assembly_rules, classification_rules, output, ref_des_disable_list)?;
         },
     }
 
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 #[tracing::instrument(level = Level::DEBUG)]
 fn build_assembly_variant(
-    eda_tool: EdaTool,
+    registry: &EdaToolRegistry,
+    eda_tool: &dyn EdaToolPlugin,
     placements_source: &String,
+    decimal_separator: DecimalSeparator,
     assembly_variant: AssemblyVariant,
     parts_source: &String,
     part_mappings_source: &String,
     eda_substitutions_sources: &[String],
     load_out_source: &Option<LoadOutSource>,
     assembly_rules_source: &Option<String>,
+    classification_rules_source: &Option<String>,
     output: &String,
     ref_des_disable_list: &Vec<String>
 ) -> Result<(), Error> {
 
-    let mut original_eda_placements = eda_placements::load_eda_placements(eda_tool, placements_source)?;
+    let mut original_eda_placements = eda_placements::load_eda_placements(eda_tool, placements_source, decimal_separator)?;
     info!("Loaded {} placements", original_eda_placements.len());
 
     let eda_substitution_rules = eda_substitutions_sources.iter().try_fold(vec![], |mut rules, source| {
-        let source_rules = substitutions::load_eda_substitutions(source)?;
+        let source_rules = substitutions::load_eda_substitutions(source, registry)?;
         info!("Loaded {} substitution rules from {}", source_rules.len(), source);
         rules.extend(source_rules);
 
@@ -179,10 +197,23 @@ fn build_assembly_variant(
         }
     }
 
+    let classification_rules = match classification_rules_source {
+        Some(source) => placement_classification::load_classification_rules(source),
+        None => Ok(vec![]),
+    }?;
+    info!("Loaded {} placement classification rules", classification_rules.len());
+
+    for eda_placement in eda_placements.iter_mut() {
+        eda_placement.kind = classification::classify(eda_placement, &classification_rules);
+        if !eda_placement.kind.is_component() {
+            eda_placement.place = false;
+        }
+    }
+
     let parts = parts::load_parts(parts_source)?;
     info!("Loaded {} parts", parts.len());
 
-    let part_mappings = part_mappings::load_part_mappings(&parts, part_mappings_source)?;
+    let part_mappings = part_mappings::load_part_mappings(&parts, part_mappings_source, registry)?;
     info!("Loaded {} part mappings", part_mappings.len());
     trace!("{:?}", part_mappings);
 
@@ -202,11 +233,17 @@ fn build_assembly_variant(
     info!("Ref_des list: {}", assembly_variant.ref_des_list.join(", "));
 
     let result = AssemblyVariantProcessor::process(&eda_placements, assembly_variant)?;
-    let variant_placements = result.placements;
-    let variant_placements_count = variant_placements.len();
+    let variant_placements_count = result.placements.len();
 
     info!("Matched {} placements for assembly variant", variant_placements_count);
 
+    let (variant_placements, non_component_placements): (Vec<EdaPlacement>, Vec<EdaPlacement>) = result.placements.into_iter()
+        .partition(|eda_placement| eda_placement.kind.is_component());
+
+    if !non_component_placements.is_empty() {
+        info!("Fiducials and test points are not mapped to parts. ref_des_list: {}", non_component_placements.iter().map(|eda_placement| eda_placement.ref_des.as_str()).collect::<Vec<_>>().join(", "));
+    }
+
     trace!("{:?}", part_mappings);
 
     let processing_result = PartMapper::process(&variant_placements, &part_mappings, &load_out_items, &assembly_rules);
@@ -228,12 +265,12 @@ fn build_assembly_variant(
         }
     }
 
-    write_output_csv(output, matched_mappings)?;
+    write_output_csv(output, matched_mappings, &non_component_placements)?;
 
     Ok(())
 }
 
-fn write_output_csv(output_file_name: &String, matched_mappings: &Vec<PlacementPartMappingResult>) -> anyhow::Result<()> {
+fn write_output_csv(output_file_name: &String, matched_mappings: &Vec<PlacementPartMappingResult>, non_component_placements: &[EdaPlacement]) -> anyhow::Result<()> {
 
     let output_path = PathBuf::from(output_file_name);
 
@@ -242,24 +279,37 @@ fn write_output_csv(output_file_name: &String, matched_mappings: &Vec<PlacementP
         .from_path(output_path)?;
 
     for matched_mapping in matched_mappings.iter() {
-        match matched_mapping {
-            PlacementPartMappingResult { eda_placement, part, .. } => {
-
-                let empty_value = "".to_string();
-                let record = PlacementRecord {
-                    ref_des: eda_placement.ref_des.clone(),
-                    manufacturer: part.map_or_else(||empty_value.clone(),|part| part.manufacturer.clone()),
-                    mpn: part.map_or_else(||empty_value.clone(),|part| part.mpn.clone()),
-                    place: eda_placement.place,
-                    pcb_side: (&eda_placement.pcb_side).into(),
-                    x: eda_placement.x,
-                    y: eda_placement.y,
-                    rotation: eda_placement.rotation,
-                };
-
-                writer.serialize(record)?;
-            },
-        }
+        let PlacementPartMappingResult { eda_placement, part, .. } = matched_mapping;
+        let empty_value = "".to_string();
+        let record = PlacementRecord {
+            ref_des: eda_placement.ref_des.clone(),
+            manufacturer: part.map_or_else(||empty_value.clone(),|part| part.manufacturer.clone()),
+            mpn: part.map_or_else(||empty_value.clone(),|part| part.mpn.clone()),
+            place: eda_placement.place,
+            pcb_side: (&eda_placement.pcb_side).into(),
+            x: eda_placement.x,
+            y: eda_placement.y,
+            rotation: eda_placement.rotation,
+            kind: eda_placement.kind,
+        };
+
+        writer.serialize(record)?;
+    }
+
+    for eda_placement in non_component_placements.iter() {
+        let record = PlacementRecord {
+            ref_des: eda_placement.ref_des.clone(),
+            manufacturer: "".to_string(),
+            mpn: "".to_string(),
+            place: eda_placement.place,
+            pcb_side: (&eda_placement.pcb_side).into(),
+            x: eda_placement.x,
+            y: eda_placement.y,
+            rotation: eda_placement.rotation,
+            kind: eda_placement.kind,
+        };
+
+        writer.serialize(record)?;
     }
 
     writer.flush()?;
@@ -280,7 +330,7 @@ fn build_mapping_tree(matched_mappings: &Vec<PlacementPartMappingResult>, eda_su
         if let Some(substitution_result) = eda_substitution_results.iter().find(|candidate|{
             candidate.original_placement.ref_des.eq(&eda_placement.ref_des)
         }) {
-            let placement_label = format!("{} ({})", eda_placement.ref_des, EdaPlacementTreeFormatter::format(&substitution_result.original_placement.fields.as_slice()));
+            let placement_label = format!("{} ({})", eda_placement.ref_des, EdaPlacementTreeFormatter::format(substitution_result.original_placement.fields.as_slice()));
             let mut placement_node = Tree::new(placement_label);
 
             let mut parent = &mut placement_node;
@@ -343,6 +393,6 @@ struct EdaPlacementTreeFormatter {}
 impl EdaPlacementTreeFormatter {
     fn format(fields: &[EdaPlacementField]) -> String {
         let chunks: Vec<String> = fields.iter().map(|field|format!("{}: '{}'", field.name, field.value)).collect();
-        format!("{}", chunks.join(", "))
+        chunks.join(", ")
     }
 }
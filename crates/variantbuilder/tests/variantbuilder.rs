@@ -176,6 +176,7 @@ mod tests {
             reference: "FEEDER_1".to_string(),
             manufacturer: "RES_MFR2".to_string(),
             mpn: "RES2".to_string(),
+            ..Default::default()
         })?;
 
         // and two resistors which can both be used by the same placement
@@ -183,11 +184,13 @@ mod tests {
             reference: "FEEDER_2".to_string(),
             manufacturer: "RES_MFR3".to_string(),
             mpn: "RES3".to_string(),
+            ..Default::default()
         })?;
         writer.serialize(TestLoadOutRecord {
             reference: "FEEDER_3".to_string(),
             manufacturer: "RES_MFR4".to_string(),
             mpn: "RES4".to_string(),
+            ..Default::default()
         })?;
 
         writer.flush()?;
@@ -384,15 +387,15 @@ mod tests {
 
         // and
         let expected_csv_content = indoc! {r#"
-            "RefDes","Manufacturer","Mpn","Place","PcbSide","X","Y","Rotation"
-            "R1","RES_MFR2","RES2","true","Top","10","110","0"
-            "R3","","","true","Top","30","130","180"
-            "R4","","","true","Top","40","140","-90"
-            "D1","DIO_MFR2","DIO2","true","Top","50","150","45"
-            "C1","","","true","Top","60","160","135"
-            "J1","CONN_MFR1","CONN1","true","Top","70","170","-135"
-            "TP1","","","false","Top","80","180","-45"
-            "TP2","","","false","Top","90","190","5"
+            "RefDes","Manufacturer","Mpn","Place","PcbSide","X","Y","Rotation","Kind"
+            "R1","RES_MFR2","RES2","true","Top","10","110","0","Component"
+            "R3","","","true","Top","30","130","180","Component"
+            "R4","","","true","Top","40","140","-90","Component"
+            "D1","DIO_MFR2","DIO2","true","Top","50","150","45","Component"
+            "C1","","","true","Top","60","160","135","Component"
+            "J1","CONN_MFR1","CONN1","true","Top","70","170","-135","Component"
+            "TP1","","","false","Top","80","180","-45","Component"
+            "TP2","","","false","Top","90","190","5","Component"
         "#}.to_string();
 
         let (test_csv_output_path, test_csv_output_file_name) = build_temp_csv_file(&temp_dir, "output");
@@ -568,8 +571,8 @@ mod tests {
 
         // and
         let expected_csv_content = indoc! {r#"
-            "RefDes","Manufacturer","Mpn","Place","PcbSide","X","Y","Rotation"
-            "R1","RES_MFR1","RES1","true","Top","10","110","-179.999"
+            "RefDes","Manufacturer","Mpn","Place","PcbSide","X","Y","Rotation","Kind"
+            "R1","RES_MFR1","RES1","true","Top","10","110","-179.999","Component"
         "#}.to_string();
 
         // when
@@ -615,6 +618,200 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn build_with_detected_aisler_placements() -> Result<(), std::io::Error> {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_variantbuilder"));
+
+        // and
+        let temp_dir = tempdir()?;
+
+        // and aisler placements, with coordinate units
+        let (test_placements_path, test_placements_file_name) = build_temp_csv_file(&temp_dir, "placements");
+
+        std::fs::write(test_placements_path, indoc! {r#"
+            "Designator","Comment","Footprint","Mid X","Mid Y","Rotation","Layer"
+            "R1","330R","R_0402","10mm","110mm","270","BottomLayer"
+        "#})?;
+
+        let placements_arg = format!("--placements {}", test_placements_file_name.to_str().unwrap());
+
+        // and parts
+        let (test_parts_path, test_parts_file_name) = build_temp_csv_file(&temp_dir, "parts");
+
+        let mut writer = csv::WriterBuilder::new()
+            .quote_style(QuoteStyle::Always)
+            .from_path(test_parts_path)?;
+
+        writer.serialize(TestPartRecord {
+            manufacturer: "RES_MFR1".to_string(),
+            mpn: "RES1".to_string(),
+        })?;
+
+        writer.flush()?;
+
+        let parts_arg = format!("--parts {}", test_parts_file_name.to_str().unwrap());
+
+        // and part mappings
+        let (test_part_mappings_path, test_part_mappings_file_name) = build_temp_csv_file(&temp_dir, "part_mappings");
+
+        let mut writer = csv::WriterBuilder::new()
+            .quote_style(QuoteStyle::Always)
+            .from_path(test_part_mappings_path)?;
+
+        writer.serialize(TestPartMappingRecord {
+            eda: "Aisler".to_string(),
+            package: Some("R_0402".to_string()),
+            value: Some("330R".to_string()),
+            // maps to
+            manufacturer: "RES_MFR1".to_string(),
+            mpn: "RES1".to_string(),
+            ..TestPartMappingRecord::default()
+        })?;
+
+        writer.flush()?;
+
+        let part_mappings_arg = format!("--part-mappings {}", test_part_mappings_file_name.to_str().unwrap());
+
+        let (test_csv_output_path, test_csv_output_file_name) = build_temp_csv_file(&temp_dir, "output");
+        let csv_output_arg = format!("--output {}", test_csv_output_file_name.to_str().unwrap());
+
+        // and
+        let expected_csv_content = indoc! {r#"
+            "RefDes","Manufacturer","Mpn","Place","PcbSide","X","Y","Rotation","Kind"
+            "R1","RES_MFR1","RES1","true","Bottom","10","110","-90","Component"
+        "#}.to_string();
+
+        // when
+        cmd.args(prepare_args(vec![
+            "build",
+            placements_arg.as_str(),
+            parts_arg.as_str(),
+            part_mappings_arg.as_str(),
+            csv_output_arg.as_str(),
+        ]))
+            // then
+            .assert()
+            .stderr(print("stderr"))
+            .stdout(print("stdout").and(predicate::str::contains("Detected placements format. format: Aisler")))
+            .success();
+
+        // and
+        let csv_content = read_to_string(test_csv_output_path)?;
+        println!("{}", csv_content);
+
+        assert_csv_content(csv_content, expected_csv_content);
+
+        Ok(())
+    }
+
+    #[test]
+    fn build_with_classification_rules() -> Result<(), std::io::Error> {
+        // given
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_variantbuilder"));
+
+        // and
+        let temp_dir = tempdir()?;
+
+        // and aisler placements, with a fiducial and a test point
+        let (test_placements_path, test_placements_file_name) = build_temp_csv_file(&temp_dir, "placements");
+
+        std::fs::write(test_placements_path, indoc! {r#"
+            "Designator","Comment","Footprint","Mid X","Mid Y","Rotation","Layer"
+            "R1","330R","R_0402","10mm","110mm","0","TopLayer"
+            "FID1","","Fiducial_1mm","5mm","5mm","0","TopLayer"
+            "TP1","","TestPoint_Pad_1.0x1.0mm","20mm","120mm","0","TopLayer"
+        "#})?;
+
+        let placements_arg = format!("--placements {}", test_placements_file_name.to_str().unwrap());
+
+        // and parts
+        let (test_parts_path, test_parts_file_name) = build_temp_csv_file(&temp_dir, "parts");
+
+        let mut writer = csv::WriterBuilder::new()
+            .quote_style(QuoteStyle::Always)
+            .from_path(test_parts_path)?;
+
+        writer.serialize(TestPartRecord {
+            manufacturer: "RES_MFR1".to_string(),
+            mpn: "RES1".to_string(),
+        })?;
+
+        writer.flush()?;
+
+        let parts_arg = format!("--parts {}", test_parts_file_name.to_str().unwrap());
+
+        // and part mappings
+        let (test_part_mappings_path, test_part_mappings_file_name) = build_temp_csv_file(&temp_dir, "part_mappings");
+
+        let mut writer = csv::WriterBuilder::new()
+            .quote_style(QuoteStyle::Always)
+            .from_path(test_part_mappings_path)?;
+
+        writer.serialize(TestPartMappingRecord {
+            eda: "Aisler".to_string(),
+            package: Some("R_0402".to_string()),
+            value: Some("330R".to_string()),
+            // maps to
+            manufacturer: "RES_MFR1".to_string(),
+            mpn: "RES1".to_string(),
+            ..TestPartMappingRecord::default()
+        })?;
+
+        writer.flush()?;
+
+        let part_mappings_arg = format!("--part-mappings {}", test_part_mappings_file_name.to_str().unwrap());
+
+        // and classification rules, by ref des and by package
+        let (test_classification_rules_path, test_classification_rules_file_name) = build_temp_csv_file(&temp_dir, "classification_rules");
+
+        std::fs::write(test_classification_rules_path, indoc! {r#"
+            "Kind","RefDesPattern","PackagePattern"
+            "Fiducial","^FID\d+$",""
+            "TestPoint","","^TestPoint_"
+        "#})?;
+
+        let classification_rules_arg = format!("--classification-rules {}", test_classification_rules_file_name.to_str().unwrap());
+
+        let (test_csv_output_path, test_csv_output_file_name) = build_temp_csv_file(&temp_dir, "output");
+        let csv_output_arg = format!("--output {}", test_csv_output_file_name.to_str().unwrap());
+
+        // and the fiducial and the test point are not placed and not mapped
+        let expected_csv_content = indoc! {r#"
+            "RefDes","Manufacturer","Mpn","Place","PcbSide","X","Y","Rotation","Kind"
+            "R1","RES_MFR1","RES1","true","Top","10","110","0","Component"
+            "FID1","","","false","Top","5","5","0","Fiducial"
+            "TP1","","","false","Top","20","120","0","TestPoint"
+        "#}.to_string();
+
+        // when
+        cmd.args(prepare_args(vec![
+            "build",
+            placements_arg.as_str(),
+            parts_arg.as_str(),
+            part_mappings_arg.as_str(),
+            classification_rules_arg.as_str(),
+            csv_output_arg.as_str(),
+        ]))
+            // then
+            .assert()
+            .stderr(print("stderr"))
+            .stdout(print("stdout")
+                .and(predicate::str::contains("Loaded 2 placement classification rules"))
+                .and(predicate::str::contains("Fiducials and test points are not mapped to parts. ref_des_list: FID1, TP1"))
+                .and(predicate::str::contains("Mapping failures").not())
+            )
+            .success();
+
+        // and
+        let csv_content = read_to_string(test_csv_output_path)?;
+        println!("{}", csv_content);
+
+        assert_csv_content(csv_content, expected_csv_content);
+
+        Ok(())
+    }
+
     #[test]
     fn version() {
         // given
@@ -750,21 +947,23 @@ mod help {
         let expected_output = indoc! {"
             Build variant
 
-            Usage: variantbuilder build [OPTIONS] --eda <EDA> --placements <SOURCE> --parts <SOURCE> --part-mappings <SOURCE> --output <FILE>
+            Usage: variantbuilder build [OPTIONS] --placements <SOURCE> --parts <SOURCE> --part-mappings <SOURCE> --output <FILE>
 
             Options:
                   --eda <EDA>
-                      EDA tool [possible values: diptrace, kicad]
+                      EDA tool, or fab-house dialect, detected from the placements columns if not specified [possible values: diptrace, kicad, eurocircuits, aisler]
                   --load-out <SOURCE>
                       Load-out source
                   --placements <SOURCE>
-                      Placements source
+                      Placements source, a placements file or a KiCad position (.pos) or board (.kicad_pcb) file
               -v, --verbose...
                       Increase logging verbosity
-                  --parts <SOURCE>
-                      Parts source
+                  --decimal-separator <DECIMAL_SEPARATOR>
+                      Decimal separator of the placements coordinates and rotations [default: auto] [possible values: auto, point, comma]
               -q, --quiet...
                       Decrease logging verbosity
+                  --parts <SOURCE>
+                      Parts source
                   --part-mappings <SOURCE>
                       Part-mappings source
                   --substitutions [<SOURCE>...]
@@ -773,6 +972,8 @@ mod help {
                       List of reference designators to disable (use for do-not-fit, no-place, test-points, fiducials, etc)
                   --assembly-rules <SOURCE>
                       Assembly rules source
+                  --classification-rules <SOURCE>
+                      Placement classification rules source, fiducials and test points are not placed and are not mapped to parts
                   --output <FILE>
                       Output CSV file
                   --name <NAME>